@@ -0,0 +1,221 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The `build-local-testnet` subcommand, writing the raw chain spec of a local testnet with any
+//! number of validators and genesis parachains, along with the keys the validators need.
+
+use std::path::{Path, PathBuf};
+
+use service::chain_spec::{self, GenesisParachain, LocalTestnetParams};
+use sp_core::hexdisplay::HexDisplay;
+use structopt::StructOpt;
+
+use crate::command::Error;
+
+/// The `build-local-testnet` command.
+#[derive(Debug, StructOpt)]
+pub struct BuildLocalTestnetCmd {
+	/// The runtime of the testnet: `polkadot`, `kusama`, `westend` or `rococo`.
+	#[structopt(long, default_value = "rococo")]
+	pub runtime: String,
+
+	/// The number of validators.
+	#[structopt(long, default_value = "2")]
+	pub validators: u32,
+
+	/// The prefix of the validator seeds.
+	///
+	/// The keys of the n-th validator are derived from `//<PREFIX><n>`, counting from 1.
+	#[structopt(long, default_value = "Validator")]
+	pub seed_prefix: String,
+
+	/// Register a parachain at genesis.
+	///
+	/// Takes the form `<para_id>:<genesis_head_file>:<wasm_file>`. The files may contain either
+	/// the raw bytes or their hex encoding, as written by collators exporting their genesis state
+	/// and wasm.
+	#[structopt(long = "parachain", value_name = "PARACHAIN")]
+	pub parachains: Vec<String>,
+
+	/// Override a field of the default host configuration.
+	///
+	/// Takes the form `<field>=<value>`, with the value in JSON, e.g. `max_code_size=5242880`.
+	#[structopt(long = "host-config", value_name = "FIELD=VALUE")]
+	pub host_configuration_overrides: Vec<String>,
+
+	/// The directory the chain spec and the key files are written to.
+	#[structopt(long, parse(from_os_str), default_value = ".")]
+	pub output: PathBuf,
+}
+
+impl BuildLocalTestnetCmd {
+	/// Run the command.
+	pub fn run(&self) -> Result<(), Error> {
+		if self.validators == 0 {
+			return Err(Error::Other("A local testnet needs at least one validator".into()));
+		}
+
+		let validator_seeds = (1..=self.validators)
+			.map(|n| format!("{}{}", self.seed_prefix, n))
+			.collect::<Vec<_>>();
+		let params = LocalTestnetParams {
+			validator_seeds: validator_seeds.clone(),
+			parachains: self.parachains.iter()
+				.map(|entry| parse_parachain(entry))
+				.collect::<Result<_, _>>()?,
+			host_configuration_overrides: self.host_configuration_overrides.iter()
+				.map(|entry| parse_override(entry))
+				.collect::<Result<_, _>>()?,
+		};
+
+		let spec = chain_spec::local_testnet_config(&self.runtime, params)?;
+		let raw_spec = spec.as_json(true)?;
+
+		std::fs::create_dir_all(self.output.join("keys")).map_err(io_error(&self.output))?;
+
+		let spec_path = self.output.join(format!("{}-raw.json", spec.id()));
+		std::fs::write(&spec_path, raw_spec).map_err(io_error(&spec_path))?;
+		log::info!("Wrote the raw chain spec to {}", spec_path.display());
+
+		for seed in &validator_seeds {
+			let path = self.output.join("keys").join(format!("{}.json", seed));
+			write_key_insertions(&path, &self.runtime, seed)?;
+			log::info!("Wrote the keys of validator {} to {}", seed, path.display());
+		}
+
+		Ok(())
+	}
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> Error + '_ {
+	move |e| Error::Other(format!("{}: {}", path.display(), e))
+}
+
+fn parse_parachain(entry: &str) -> Result<GenesisParachain, Error> {
+	let invalid = |reason: String| Error::Other(format!("Invalid `--parachain` {:?}: {}", entry, reason));
+
+	let parts = entry.splitn(3, ':').collect::<Vec<_>>();
+	let (id, genesis_head, wasm) = match parts.as_slice() {
+		[id, genesis_head, wasm] => (id, genesis_head, wasm),
+		_ => return Err(invalid("expected `<para_id>:<genesis_head_file>:<wasm_file>`".into())),
+	};
+
+	Ok(GenesisParachain {
+		id: id.parse::<u32>().map_err(|e| invalid(e.to_string()))?.into(),
+		genesis_head: read_bytes(Path::new(genesis_head)).map_err(invalid)?,
+		validation_code: read_bytes(Path::new(wasm)).map_err(invalid)?,
+	})
+}
+
+/// Read a file containing either raw bytes or their `0x` prefixed hex encoding.
+fn read_bytes(path: &Path) -> Result<Vec<u8>, String> {
+	let data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+	match std::str::from_utf8(&data).map(str::trim) {
+		Ok(hex) if hex.starts_with("0x") => sp_core::bytes::from_hex(hex)
+			.map_err(|e| format!("{}: {}", path.display(), e)),
+		_ => Ok(data),
+	}
+}
+
+fn parse_override(entry: &str) -> Result<(String, serde_json::Value), Error> {
+	let invalid = |reason: String| Error::Other(format!("Invalid `--host-config` {:?}: {}", entry, reason));
+
+	let (field, value) = match entry.splitn(2, '=').collect::<Vec<_>>().as_slice() {
+		[field, value] if !field.is_empty() => (field.to_string(), *value),
+		_ => return Err(invalid("expected `<field>=<value>`".into())),
+	};
+
+	Ok((field, serde_json::from_str(value).map_err(|e| invalid(e.to_string()))?))
+}
+
+/// Write the keys of a validator as a batch of `author_insertKey` RPC requests.
+fn write_key_insertions(path: &Path, runtime: &str, seed: &str) -> Result<(), Error> {
+	let requests = chain_spec::local_testnet_key_insertions(runtime, seed)
+		.into_iter()
+		.enumerate()
+		.map(|(id, key)| serde_json::json!({
+			"jsonrpc": "2.0",
+			"id": id,
+			"method": "author_insertKey",
+			"params": [
+				String::from_utf8_lossy(&key.key_type.0),
+				key.suri,
+				format!("0x{}", HexDisplay::from(&key.public)),
+			],
+		}))
+		.collect::<Vec<_>>();
+
+	let json = serde_json::to_string_pretty(&requests).map_err(|e| Error::Other(e.to_string()))?;
+	std::fs::write(path, json).map_err(io_error(path))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Write a file into the temporary directory, unique to the given test.
+	fn write_temp_file(name: &str, contents: &[u8]) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("build-local-testnet-{}-{}", std::process::id(), name));
+		std::fs::write(&path, contents).unwrap();
+		path
+	}
+
+	#[test]
+	fn parachains_are_read_from_raw_and_hex_files() {
+		let head = write_temp_file("head", b"0x0102\n");
+		let wasm = write_temp_file("wasm", &[0, 97, 115, 109]);
+
+		let parachain = parse_parachain(&format!("2000:{}:{}", head.display(), wasm.display())).unwrap();
+		assert_eq!(u32::from(parachain.id), 2000);
+		assert_eq!(parachain.genesis_head, vec![1, 2]);
+		assert_eq!(parachain.validation_code, vec![0, 97, 115, 109]);
+
+		let _ = std::fs::remove_file(head);
+		let _ = std::fs::remove_file(wasm);
+	}
+
+	#[test]
+	fn malformed_parachains_are_rejected() {
+		let head = write_temp_file("malformed-head", b"0x01");
+
+		assert!(parse_parachain("2000").is_err());
+		assert!(parse_parachain(&format!("2000:{}", head.display())).is_err());
+		assert!(parse_parachain(&format!("para:{}:{}", head.display(), head.display())).is_err());
+		assert!(parse_parachain(&format!("2000:{}:/nonexistent/wasm", head.display())).is_err());
+
+		let _ = std::fs::remove_file(head);
+	}
+
+	#[test]
+	fn overrides_are_parsed_as_json() {
+		assert_eq!(
+			parse_override("max_code_size=5242880").unwrap(),
+			("max_code_size".to_string(), serde_json::json!(5242880)),
+		);
+		assert_eq!(
+			parse_override("field=\"a=b\"").unwrap(),
+			("field".to_string(), serde_json::json!("a=b")),
+		);
+	}
+
+	#[test]
+	fn malformed_overrides_are_rejected() {
+		assert!(parse_override("max_code_size").is_err());
+		assert!(parse_override("=5242880").is_err());
+		assert!(parse_override("max_code_size=big").is_err());
+	}
+}
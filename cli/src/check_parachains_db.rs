@@ -0,0 +1,89 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The `check-parachains-db` subcommand, checking the parachains database of a stopped node
+//! for entries which are orphaned or refer to missing entries, e.g. after a crash.
+
+use sc_cli::{CliConfiguration, DatabaseParams, SharedParams};
+use service::DatabaseConfig;
+use structopt::StructOpt;
+
+use crate::command::Error;
+
+/// The `check-parachains-db` command.
+#[derive(Debug, StructOpt)]
+pub struct CheckParachainsDbCmd {
+	/// Repair the inconsistencies found, pruning orphaned entries and entries which can't be
+	/// repaired.
+	///
+	/// The node must not be running.
+	#[structopt(long)]
+	pub repair: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl CliConfiguration for CheckParachainsDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+impl CheckParachainsDbCmd {
+	/// Run the command against the parachains database next to the given database.
+	pub fn run(&self, database: DatabaseConfig) -> Result<(), Error> {
+		let root = database.path()
+			.ok_or_else(|| Error::Other("Checking the parachains database requires a database path".into()))?;
+
+		let report = service::check_parachains_db(root.into(), self.repair)
+			.map_err(|e| Error::Other(e.to_string()))?;
+
+		for (subsystem, inconsistencies) in report.by_subsystem() {
+			match inconsistencies {
+				Some(inconsistencies) => print_inconsistencies(subsystem, &inconsistencies),
+				None => println!("{}: not checked", subsystem),
+			}
+		}
+
+		if report.is_consistent() {
+			println!("The parachains database is consistent");
+		} else if self.repair {
+			println!("Repaired all inconsistencies");
+		} else {
+			return Err(Error::Other("The parachains database is inconsistent, run with `--repair` to repair it".into()));
+		}
+
+		Ok(())
+	}
+}
+
+fn print_inconsistencies(subsystem: &str, inconsistencies: &[String]) {
+	println!("{}: {} inconsistencies", subsystem, inconsistencies.len());
+
+	for inconsistency in inconsistencies {
+		println!("  {}", inconsistency);
+	}
+}
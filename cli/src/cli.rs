@@ -24,9 +24,15 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Build the raw chain specification of a local testnet, along with the keys of its validators.
+	BuildLocalTestnet(crate::build_local_testnet::BuildLocalTestnetCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
+	/// Check the parachains database for inconsistencies, e.g. after a crash, and optionally repair them.
+	CheckParachainsDb(crate::check_parachains_db::CheckParachainsDbCmd),
+
 	/// Export blocks.
 	ExportBlocks(sc_cli::ExportBlocksCmd),
 
@@ -42,6 +48,9 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Print the state of the registered paras at a block of the local database.
+	InspectParas(crate::inspect_paras::InspectParasCmd),
+
 	#[allow(missing_docs)]
 	#[structopt(name = "prepare-worker", setting = structopt::clap::AppSettings::Hidden)]
 	PvfPrepareWorker(ValidationWorkerCommand),
@@ -112,8 +121,79 @@ pub struct RunCmd {
 	///
 	/// Must be valid socket address, of format `IP:Port`
 	/// commonly `127.0.0.1:6831`.
-	#[structopt(long)]
+	#[structopt(long, group = "tracing-exporter")]
 	pub jaeger_agent: Option<std::net::SocketAddr>,
+
+	/// Export the tracing spans to an OpenTelemetry collector instead of a jaeger agent.
+	///
+	/// Takes the base URL of the collector's OTLP/HTTP receiver, commonly `http://127.0.0.1:4318`.
+	/// The spans are exported in batches.
+	#[structopt(long, value_name = "URL", conflicts_with = "jaeger-agent", group = "tracing-exporter")]
+	pub otlp_endpoint: Option<String>,
+
+	/// Record only a ratio of the traces started by spans with the given name.
+	///
+	/// Takes the form `<span_name>=<ratio>`, with the ratio between `0` and `1`,
+	/// e.g. `availability-distribution=0.1`. Applies to `--jaeger-agent` and `--otlp-endpoint` alike,
+	/// one of which is required.
+	#[structopt(long = "tracing-sample-ratio", value_name = "SPAN=RATIO", requires = "tracing-exporter")]
+	pub tracing_sample_ratios: Vec<String>,
+
+	/// Trust a collator for a parachain when acting as a validator.
+	///
+	/// Takes the form `<para_id>:<allow|prefer>:<collator_id>`, with the collator id in SS58
+	/// format. `allow` entries make validators accept only the listed collators of the para,
+	/// `prefer` entries make them fetch collations of the listed collators first.
+	#[structopt(long = "trusted-collator", value_name = "ENTRY")]
+	pub trusted_collators: Vec<String>,
+
+	/// Read trusted collators from a file, one `--trusted-collator` entry per line.
+	///
+	/// Empty lines and lines starting with `#` are ignored.
+	#[structopt(long = "trusted-collators-file", parse(from_os_str))]
+	pub trusted_collators_file: Option<std::path::PathBuf>,
+
+	/// Maximum number of collations a validator fetches concurrently per parachain.
+	#[structopt(long = "max-parallel-collation-fetches", value_name = "COUNT")]
+	pub max_parallel_collation_fetches: Option<usize>,
+
+	/// Weight a parachain's share of collation fetches, when validating for several paras.
+	///
+	/// Takes the form `<para_id>:<weight>`, e.g. `2000:3`. Paras without an entry have a
+	/// weight of `1`.
+	#[structopt(long = "collation-fetch-weight", value_name = "WEIGHT")]
+	pub collation_fetch_weights: Vec<String>,
+
+	/// Limit the rate of incoming requests on a parachain request protocol, per peer.
+	///
+	/// Takes the form `<protocol>:<requests_per_second>[:<burst>]`, e.g. `chunk_fetching:20:50`.
+	/// Requests exceeding the limit are rejected before being processed.
+	#[structopt(long = "request-rate-limit", value_name = "LIMIT")]
+	pub request_rate_limits: Vec<String>,
+
+	/// Set the budget of the runtime API cache for a request kind, in bytes.
+	///
+	/// Takes the form `<request>:<bytes>`, e.g. `validation_code:20971520`. The request is the
+	/// snake case name of a runtime API request, e.g. `session_info` or `current_babe_epoch`.
+	#[structopt(long = "runtime-api-cache-size", value_name = "SIZE")]
+	pub runtime_api_cache_sizes: Vec<String>,
+
+	/// Log a warning with the recent reputation changes of a peer, whenever a peer gets banned
+	/// because of reputation changes reported by parachain subsystems.
+	#[structopt(long)]
+	pub log_reputation_bans: bool,
+
+	/// Record the signals and messages of a subsystem, for replaying them later.
+	///
+	/// Subsystems are named as in the overseer, e.g. `bitfield_distribution`. Only
+	/// recordings of subsystems whose messages are encoded can be replayed, currently
+	/// `bitfield_distribution`. Can be given multiple times. Requires `--recording-path`.
+	#[structopt(long = "record-subsystem", value_name = "SUBSYSTEM", requires = "recording-path")]
+	pub record_subsystems: Vec<String>,
+
+	/// The file subsystem recordings are written to. Existing files are overwritten.
+	#[structopt(long = "recording-path", parse(from_os_str))]
+	pub recording_path: Option<std::path::PathBuf>,
 }
 
 #[allow(missing_docs)]
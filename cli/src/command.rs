@@ -43,6 +43,127 @@ impl std::convert::From<String> for Error {
 
 type Result<T> = std::result::Result<T, Error>;
 
+/// Collect the trusted collators given on the command line and in the trusted collators file.
+fn trusted_collators(run: &crate::cli::RunCmd) -> Result<service::TrustedCollators> {
+	let mut trusted_collators = service::TrustedCollators::default();
+
+	if let Some(path) = &run.trusted_collators_file {
+		trusted_collators.extend_from_file(path)
+			.map_err(|e| Error::Other(format!("Invalid trusted collators file {:?}: {}", path, e)))?;
+	}
+
+	for entry in &run.trusted_collators {
+		entry.parse()
+			.and_then(|entry| trusted_collators.insert(entry))
+			.map_err(|e| Error::Other(format!("Invalid `--trusted-collator`: {}", e)))?;
+	}
+
+	Ok(trusted_collators)
+}
+
+/// Collect the subsystem recording options given on the command line.
+fn recording_config(run: &crate::cli::RunCmd) -> Result<Option<service::RecordingConfig>> {
+	let config = match &run.recording_path {
+		Some(path) => service::RecordingConfig {
+			path: path.clone(),
+			subsystems: run.record_subsystems.clone(),
+		},
+		None => return Ok(None),
+	};
+
+	config.validate()
+		.map_err(|e| Error::Other(format!("Invalid `--record-subsystem`: {}", e)))?;
+
+	Ok(Some(config))
+}
+
+/// Collect the request rate limits given on the command line.
+fn request_rate_limits(run: &crate::cli::RunCmd) -> Result<service::RequestRateLimits> {
+	let mut limits = service::RequestRateLimits::default();
+
+	for entry in &run.request_rate_limits {
+		let entry = entry.parse()
+			.map_err(|e| Error::Other(format!("Invalid `--request-rate-limit`: {}", e)))?;
+		limits.insert(entry);
+	}
+
+	Ok(limits)
+}
+
+/// Collect the collation fetching configuration given on the command line.
+fn collation_fetching_config(run: &crate::cli::RunCmd) -> Result<service::CollationFetchingConfig> {
+	let mut config = service::CollationFetchingConfig::default();
+
+	if let Some(max) = run.max_parallel_collation_fetches {
+		if max == 0 {
+			return Err(Error::Other("`--max-parallel-collation-fetches` must be at least 1".into()))
+		}
+		config.max_parallel_fetches_per_para = max;
+	}
+
+	for entry in &run.collation_fetch_weights {
+		let (para_id, weight) = parse_collation_fetch_weight(entry)
+			.map_err(|e| Error::Other(format!("Invalid `--collation-fetch-weight`: {}", e)))?;
+		config.para_weights.insert(para_id, weight);
+	}
+
+	Ok(config)
+}
+
+/// Parse a `<para_id>:<weight>` entry.
+fn parse_collation_fetch_weight(entry: &str) -> std::result::Result<(service::ParaId, u32), String> {
+	let mut parts = entry.splitn(2, ':');
+	let para_id = parts.next()
+		.and_then(|p| p.parse::<u32>().ok())
+		.ok_or_else(|| format!("expected `<para_id>:<weight>`, got `{}`", entry))?;
+	let weight = parts.next()
+		.and_then(|w| w.parse::<u32>().ok())
+		.ok_or_else(|| format!("expected `<para_id>:<weight>`, got `{}`", entry))?;
+
+	if weight == 0 {
+		return Err(format!("weight of para {} must be at least 1", para_id))
+	}
+
+	Ok((para_id.into(), weight))
+}
+
+/// Collect the runtime API cache budgets given on the command line.
+fn runtime_api_cache_config(run: &crate::cli::RunCmd) -> Result<service::RuntimeApiCacheConfig> {
+	let mut config = service::RuntimeApiCacheConfig::default();
+
+	for entry in &run.runtime_api_cache_sizes {
+		let entry = entry.parse()
+			.map_err(|e| Error::Other(format!("Invalid `--runtime-api-cache-size`: {}", e)))?;
+		config.insert(entry);
+	}
+
+	Ok(config)
+}
+
+/// Collect the span export configuration given on the command line, if any.
+fn jaeger_config(run: &crate::cli::RunCmd, node_name: &str) -> Result<Option<service::jaeger::JaegerConfig>> {
+	let builder = match (run.jaeger_agent, &run.otlp_endpoint) {
+		(Some(agent), _) => service::jaeger::JaegerConfig::builder().agent(agent),
+		(None, Some(endpoint)) => service::jaeger::JaegerConfig::builder()
+			.otlp(service::jaeger::OtlpConfig::new(endpoint)),
+		(None, None) => return Ok(None),
+	};
+
+	let builder = run.tracing_sample_ratios.iter().try_fold(builder, |builder, entry| {
+		let invalid = |reason: &str| Error::Other(format!("Invalid `--tracing-sample-ratio` {:?}: {}", entry, reason));
+		let (span_name, ratio) = match entry.rsplitn(2, '=').collect::<Vec<_>>().as_slice() {
+			[ratio, span_name] if !span_name.is_empty() => (*span_name, *ratio),
+			_ => return Err(invalid("expected `<span_name>=<ratio>`")),
+		};
+		match ratio.parse::<f64>() {
+			Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(builder.sample(span_name, ratio)),
+			_ => Err(invalid("the ratio must be between 0 and 1")),
+		}
+	})?;
+
+	Ok(Some(builder.named(node_name).build()))
+}
+
 fn get_exec_name() -> Option<String> {
 	std::env::current_exe()
 		.ok()
@@ -211,7 +332,21 @@ fn run_node_inner(cli: Cli, overseer_gen: impl service::OverseerGen) -> Result<(
 		info!("----------------------------");
 	}
 
-	let jaeger_agent = cli.run.jaeger_agent;
+	let jaeger_config = jaeger_config(&cli.run, &runner.config().network.node_name)?;
+
+	let node_config = service::NodeConfig {
+		trusted_collators: trusted_collators(&cli.run)?,
+		collation_fetching: collation_fetching_config(&cli.run)?,
+		request_rate_limits: request_rate_limits(&cli.run)?,
+		reputation_audit: service::ReputationAuditConfig {
+			log_bans: cli.run.log_reputation_bans,
+			..Default::default()
+		},
+		recording: recording_config(&cli.run)?,
+		runtime_api_cache: runtime_api_cache_config(&cli.run)?,
+		// The polkadot binary never runs a collator.
+		collator_subsystems_only: false,
+	};
 
 	runner.run_node_until_exit(move |config| async move {
 		let role = config.role.clone();
@@ -226,8 +361,9 @@ fn run_node_inner(cli: Cli, overseer_gen: impl service::OverseerGen) -> Result<(
 				service::IsCollator::No,
 				grandpa_pause,
 				cli.run.no_beefy,
-				jaeger_agent,
+				jaeger_config,
 				None,
+				node_config,
 				overseer_gen,
 			).map(|full| full.task_manager).map_err(Into::into)
 		}
@@ -246,6 +382,10 @@ pub fn run() -> Result<()> {
 				cmd.run(config.chain_spec, config.network)
 			})?)
 		},
+		Some(Subcommand::BuildLocalTestnet(cmd)) => {
+			let _ = sc_cli::LoggerBuilder::new("").init();
+			cmd.run()
+		},
 		Some(Subcommand::CheckBlock(cmd)) => {
 			let runner = cli.create_runner(cmd)
 				.map_err(Error::SubstrateCli)?;
@@ -258,6 +398,10 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, import_queue).map_err(Error::SubstrateCli), task_manager))
 			})
 		},
+		Some(Subcommand::CheckParachainsDb(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| cmd.run(config.database))?)
+		},
 		Some(Subcommand::ExportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			let chain_spec = &runner.config().chain_spec;
@@ -307,6 +451,17 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, backend).map_err(Error::SubstrateCli), task_manager))
 			})?)
 		},
+		Some(Subcommand::InspectParas(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			let chain_spec = &runner.config().chain_spec;
+
+			set_default_ss58_version(chain_spec);
+
+			Ok(runner.async_run(|mut config| {
+				let (client, _, _, task_manager) = service::new_chain_ops(&mut config, None)?;
+				Ok((cmd.run(client), task_manager))
+			})?)
+		},
 		Some(Subcommand::PvfPrepareWorker(cmd)) => {
 			let mut builder = sc_cli::LoggerBuilder::new("");
 			builder.with_colors(false);
@@ -423,3 +578,50 @@ pub fn run() -> Result<()> {
 	}?;
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use structopt::StructOpt;
+
+	#[test]
+	fn parses_collation_fetch_weights() {
+		assert_eq!(parse_collation_fetch_weight("2000:3"), Ok((2000.into(), 3)));
+		assert_eq!(parse_collation_fetch_weight("1:1"), Ok((1.into(), 1)));
+	}
+
+	#[test]
+	fn rejects_zero_collation_fetch_weights() {
+		assert_eq!(
+			parse_collation_fetch_weight("2000:0"),
+			Err("weight of para 2000 must be at least 1".into()),
+		);
+	}
+
+	#[test]
+	fn rejects_malformed_collation_fetch_weights() {
+		for entry in &["", "2000", "2000:", ":3", "para:3", "2000:x", "2000:3:1", "-1:3", "2000:-3"] {
+			assert_eq!(
+				parse_collation_fetch_weight(entry),
+				Err(format!("expected `<para_id>:<weight>`, got `{}`", entry)),
+				"{:?} should be rejected",
+				entry,
+			);
+		}
+	}
+
+	#[test]
+	fn tracing_sample_ratios_require_an_exporter() {
+		let parse = |args: &[&str]| Cli::from_iter_safe(std::iter::once("polkadot").chain(args.iter().copied()));
+
+		assert!(parse(&["--tracing-sample-ratio", "availability-distribution=0.1"]).is_err());
+		assert!(parse(&[
+			"--tracing-sample-ratio", "availability-distribution=0.1",
+			"--jaeger-agent", "127.0.0.1:6831",
+		]).is_ok());
+		assert!(parse(&[
+			"--tracing-sample-ratio", "availability-distribution=0.1",
+			"--otlp-endpoint", "http://127.0.0.1:4318",
+		]).is_ok());
+	}
+}
@@ -0,0 +1,414 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The `inspect-paras` subcommand, printing the state of the registered paras as found in the
+//! node database.
+//!
+//! Everything is read from the well known storage keys, so no runtime is executed.
+
+use std::{path::{Path, PathBuf}, sync::Arc};
+
+use polkadot_primitives::v1::{
+	well_known_keys, AbridgedHrmpChannel, Block, BlockNumber, Hash, HeadData, HrmpChannelId,
+	Id as ParaId, InboundDownwardMessage, ParaLifecycle, ValidationCode,
+};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use polkadot_rpc::{lifecycle_name, ParaStorage, ParaStorageError};
+use sc_client_api::{Backend, StorageProvider};
+use serde::Serialize;
+use service::{BlockId, HeaderBackend};
+use sp_core::{hexdisplay::HexDisplay, Bytes};
+use structopt::StructOpt;
+
+use crate::command::Error;
+
+/// The `inspect-paras` command.
+#[derive(Debug, StructOpt)]
+pub struct InspectParasCmd {
+	/// The block to inspect the state of, by hash or number. Defaults to the best block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Only print the given para.
+	#[structopt(long)]
+	pub para: Option<u32>,
+
+	/// Print the paras as JSON.
+	#[structopt(long)]
+	pub json: bool,
+
+	/// Write the current validation code of the para given by `--para` to a file.
+	///
+	/// The code is written as stored on chain, i.e. possibly compressed.
+	#[structopt(long, parse(from_os_str), requires = "para")]
+	pub dump_code: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl CliConfiguration for InspectParasCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The state of the registered paras at a block.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Inspection {
+	block_hash: Hash,
+	block_number: BlockNumber,
+	paras: Vec<ParaState>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ParaState {
+	para_id: u32,
+	lifecycle: &'static str,
+	head: Option<Bytes>,
+	// `ValidationCodeHash` is encoded as the plain hash.
+	current_code_hash: Option<Hash>,
+	pending_code_hash: Option<Hash>,
+	/// The block the pending code upgrade is applied at.
+	pending_code_upgrade_at: Option<BlockNumber>,
+	upward_queue: QueueSize,
+	downward_queue: QueueSize,
+	outbound_hrmp: Vec<HrmpChannel>,
+	inbound_hrmp: Vec<HrmpChannel>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueueSize {
+	count: u32,
+	total_size: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HrmpChannel {
+	/// The other end of the channel.
+	para_id: u32,
+	msg_count: u32,
+	total_size: u32,
+	max_capacity: u32,
+	max_total_size: u32,
+}
+
+impl InspectParasCmd {
+	/// Run the command against the given client.
+	pub async fn run<C, B>(&self, client: Arc<C>) -> Result<(), Error>
+	where
+		C: HeaderBackend<Block> + StorageProvider<Block, B>,
+		B: Backend<Block>,
+	{
+		let at = match self.at {
+			Some(ref at) => at.parse::<Block>().map_err(Error::Other)?,
+			None => BlockId::Hash(client.info().best_hash),
+		};
+		let storage = ParaStorage::new(&*client, at);
+
+		let para_ids = match self.para {
+			Some(para_id) => vec![ParaId::from(para_id)],
+			None => para_ids(&storage)?,
+		};
+		let paras = para_ids.into_iter()
+			.map(|para_id| para_state(&storage, para_id))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		if let Some(ref path) = self.dump_code {
+			self.dump_code(&storage, &paras, path)?;
+		}
+
+		let inspection = Inspection {
+			block_hash: client.expect_block_hash_from_id(&at).map_err(|e| Error::Other(e.to_string()))?,
+			block_number: client.expect_block_number_from_id(&at).map_err(|e| Error::Other(e.to_string()))?,
+			paras,
+		};
+
+		if self.json {
+			let json = serde_json::to_string_pretty(&inspection)
+				.map_err(|e| Error::Other(e.to_string()))?;
+			println!("{}", json);
+		} else {
+			print_inspection(&inspection);
+		}
+
+		Ok(())
+	}
+
+	fn dump_code<C, B>(
+		&self,
+		storage: &ParaStorage<C, B>,
+		paras: &[ParaState],
+		path: &Path,
+	) -> Result<(), Error>
+	where
+		C: StorageProvider<Block, B>,
+		B: Backend<Block>,
+	{
+		let code_hash = paras.first()
+			.and_then(|para| para.current_code_hash)
+			.ok_or_else(|| Error::Other(format!("Para {:?} has no validation code", self.para)))?;
+		let code = storage.get::<ValidationCode>(well_known_keys::code_by_hash(code_hash.into()))
+			.map_err(storage_error)?
+			.ok_or_else(|| Error::Other(format!("Validation code {} is missing", code_hash)))?;
+
+		std::fs::write(path, &code.0)
+			.map_err(|e| Error::Other(format!("Failed to write the validation code to {:?}: {}", path, e)))
+	}
+}
+
+/// The ids of all registered paras, in ascending order.
+fn para_ids<C, B>(storage: &ParaStorage<C, B>) -> Result<Vec<ParaId>, Error>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	let lifecycles = storage.lifecycles().map_err(storage_error)?;
+
+	Ok(lifecycles.into_iter().map(|(para_id, _)| para_id).collect())
+}
+
+fn para_state<C, B>(storage: &ParaStorage<C, B>, para_id: ParaId) -> Result<ParaState, Error>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	let lifecycle = storage.get::<ParaLifecycle>(well_known_keys::para_lifecycle(para_id))
+		.map_err(storage_error)?
+		.ok_or_else(|| Error::Other(format!("Para {} is not registered", u32::from(para_id))))?;
+
+	let (count, total_size) = storage.get::<(u32, u32)>(well_known_keys::relay_dispatch_queue_size(para_id))
+		.map_err(storage_error)?
+		.unwrap_or_default();
+	let upward_queue = QueueSize { count, total_size };
+
+	let downward_messages = storage.get::<Vec<InboundDownwardMessage>>(
+		well_known_keys::downward_message_queue(para_id),
+	).map_err(storage_error)?.unwrap_or_default();
+	let downward_queue = QueueSize {
+		count: downward_messages.len() as u32,
+		total_size: downward_messages.iter().map(|m| m.msg.len() as u32).sum(),
+	};
+
+	let recipients = storage.get::<Vec<ParaId>>(well_known_keys::hrmp_egress_channel_index(para_id))
+		.map_err(storage_error)?
+		.unwrap_or_default();
+	let outbound_hrmp = recipients.into_iter()
+		.map(|recipient| hrmp_channel(storage, recipient, HrmpChannelId { sender: para_id, recipient }))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let senders = storage.get::<Vec<ParaId>>(well_known_keys::hrmp_ingress_channel_index(para_id))
+		.map_err(storage_error)?
+		.unwrap_or_default();
+	let inbound_hrmp = senders.into_iter()
+		.map(|sender| hrmp_channel(storage, sender, HrmpChannelId { sender, recipient: para_id }))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(ParaState {
+		para_id: para_id.into(),
+		lifecycle: lifecycle_name(&lifecycle),
+		head: storage.get::<HeadData>(well_known_keys::para_head(para_id))
+			.map_err(storage_error)?
+			.map(|head| head.0.into()),
+		current_code_hash: storage.get(well_known_keys::current_code_hash(para_id)).map_err(storage_error)?,
+		pending_code_hash: storage.get(well_known_keys::future_code_hash(para_id)).map_err(storage_error)?,
+		pending_code_upgrade_at: storage.get(well_known_keys::future_code_upgrade_at(para_id))
+			.map_err(storage_error)?,
+		upward_queue,
+		downward_queue,
+		outbound_hrmp,
+		inbound_hrmp,
+	})
+}
+
+fn hrmp_channel<C, B>(
+	storage: &ParaStorage<C, B>,
+	counterparty: ParaId,
+	id: HrmpChannelId,
+) -> Result<HrmpChannel, Error>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	let channel = storage.get::<AbridgedHrmpChannel>(well_known_keys::hrmp_channels(id.clone()))
+		.map_err(storage_error)?
+		.ok_or_else(|| Error::Other(format!("HRMP channel {:?} is indexed but missing", id)))?;
+
+	Ok(HrmpChannel {
+		para_id: counterparty.into(),
+		msg_count: channel.msg_count,
+		total_size: channel.total_size,
+		max_capacity: channel.max_capacity,
+		max_total_size: channel.max_total_size,
+	})
+}
+
+fn storage_error(e: ParaStorageError) -> Error {
+	Error::Other(e.to_string())
+}
+
+fn print_inspection(inspection: &Inspection) {
+	println!("Paras at block #{} ({:?}):", inspection.block_number, inspection.block_hash);
+
+	for para in &inspection.paras {
+		println!();
+		println!("Para {}: {}", para.para_id, para.lifecycle);
+
+		match para.head {
+			Some(ref head) => println!("  head:         0x{} ({} bytes)", HexDisplay::from(&head.0), head.0.len()),
+			None => println!("  head:         -"),
+		}
+		match para.current_code_hash {
+			Some(ref code_hash) => println!("  current code: {:?}", code_hash),
+			None => println!("  current code: -"),
+		}
+		match (para.pending_code_hash, para.pending_code_upgrade_at) {
+			(Some(code_hash), Some(at)) => println!("  pending code: {:?} at #{}", code_hash, at),
+			(Some(code_hash), None) => println!("  pending code: {:?}", code_hash),
+			(None, _) => println!("  pending code: -"),
+		}
+		println!(
+			"  upward:       {} messages, {} bytes",
+			para.upward_queue.count,
+			para.upward_queue.total_size,
+		);
+		println!(
+			"  downward:     {} messages, {} bytes",
+			para.downward_queue.count,
+			para.downward_queue.total_size,
+		);
+
+		let channels = para.outbound_hrmp.iter().map(|c| ("to", c))
+			.chain(para.inbound_hrmp.iter().map(|c| ("from", c)));
+		for (direction, channel) in channels {
+			println!(
+				"  hrmp {:>4} {}: {}/{} messages, {}/{} bytes",
+				direction,
+				channel.para_id,
+				channel.msg_count,
+				channel.max_capacity,
+				channel.total_size,
+				channel.max_total_size,
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use parity_scale_codec::Encode;
+	use polkadot_test_client::{
+		DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+	};
+
+	fn channel(msg_count: u32) -> AbridgedHrmpChannel {
+		AbridgedHrmpChannel {
+			max_capacity: 8,
+			max_total_size: 1024,
+			max_message_size: 512,
+			msg_count,
+			total_size: msg_count * 10,
+			mqc_head: None,
+		}
+	}
+
+	#[test]
+	fn para_ids_are_sorted() {
+		let client = TestClientBuilder::new()
+			.add_extra_storage(well_known_keys::para_lifecycle(3000.into()), ParaLifecycle::Parathread.encode())
+			.add_extra_storage(well_known_keys::para_lifecycle(2000.into()), ParaLifecycle::Parachain.encode())
+			.build();
+		let storage = ParaStorage::new(&client, BlockId::Number(0));
+
+		assert_eq!(para_ids(&storage).unwrap(), vec![ParaId::from(2000), ParaId::from(3000)]);
+	}
+
+	#[test]
+	fn para_state_is_read_from_the_well_known_keys() {
+		let (para_id, recipient, sender) = (ParaId::from(2000), ParaId::from(3000), ParaId::from(4000));
+		let client = TestClientBuilder::new()
+			.add_extra_storage(well_known_keys::para_lifecycle(para_id), ParaLifecycle::UpgradingParathread.encode())
+			.add_extra_storage(well_known_keys::para_head(para_id), HeadData(vec![1, 2, 3]).encode())
+			.add_extra_storage(well_known_keys::relay_dispatch_queue_size(para_id), (2u32, 30u32).encode())
+			.add_extra_storage(
+				well_known_keys::downward_message_queue(para_id),
+				vec![InboundDownwardMessage { sent_at: 0, msg: vec![1, 2] }].encode(),
+			)
+			.add_extra_storage(well_known_keys::hrmp_egress_channel_index(para_id), vec![recipient].encode())
+			.add_extra_storage(
+				well_known_keys::hrmp_channels(HrmpChannelId { sender: para_id, recipient }),
+				channel(1).encode(),
+			)
+			.add_extra_storage(well_known_keys::hrmp_ingress_channel_index(para_id), vec![sender].encode())
+			.add_extra_storage(
+				well_known_keys::hrmp_channels(HrmpChannelId { sender, recipient: para_id }),
+				channel(3).encode(),
+			)
+			.build();
+		let storage = ParaStorage::new(&client, BlockId::Number(0));
+
+		let state = para_state(&storage, para_id).unwrap();
+		assert_eq!(state.para_id, 2000);
+		assert_eq!(state.lifecycle, "upgradingParathread");
+		assert_eq!(state.head, Some(vec![1, 2, 3].into()));
+		assert_eq!(state.current_code_hash, None);
+		assert_eq!((state.upward_queue.count, state.upward_queue.total_size), (2, 30));
+		assert_eq!((state.downward_queue.count, state.downward_queue.total_size), (1, 2));
+		assert_eq!(
+			state.outbound_hrmp.iter().map(|c| (c.para_id, c.msg_count)).collect::<Vec<_>>(),
+			vec![(3000, 1)],
+		);
+		assert_eq!(
+			state.inbound_hrmp.iter().map(|c| (c.para_id, c.msg_count)).collect::<Vec<_>>(),
+			vec![(4000, 3)],
+		);
+	}
+
+	#[test]
+	fn para_state_rejects_unregistered_and_malformed_paras() {
+		let client = TestClientBuilder::new()
+			.add_extra_storage(well_known_keys::para_lifecycle(2000.into()), ParaLifecycle::Parachain.encode())
+			.add_extra_storage(well_known_keys::hrmp_egress_channel_index(2000.into()), vec![ParaId::from(3000)].encode())
+			.build();
+		let storage = ParaStorage::new(&client, BlockId::Number(0));
+
+		assert!(para_state(&storage, 1000.into()).is_err());
+		// The channel to 3000 is indexed but missing.
+		assert!(para_state(&storage, 2000.into()).is_err());
+	}
+}
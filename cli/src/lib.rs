@@ -21,9 +21,15 @@
 #[cfg(feature = "browser")]
 mod browser;
 #[cfg(feature = "cli")]
+mod build_local_testnet;
+#[cfg(feature = "cli")]
+mod check_parachains_db;
+#[cfg(feature = "cli")]
 mod cli;
 #[cfg(feature = "cli")]
 mod command;
+#[cfg(feature = "cli")]
+mod inspect_paras;
 
 pub use service::{
 	self,
@@ -40,5 +46,14 @@ pub use cli::*;
 #[cfg(feature = "cli")]
 pub use command::*;
 
+#[cfg(feature = "cli")]
+pub use build_local_testnet::BuildLocalTestnetCmd;
+
+#[cfg(feature = "cli")]
+pub use check_parachains_db::CheckParachainsDbCmd;
+
+#[cfg(feature = "cli")]
+pub use inspect_paras::InspectParasCmd;
+
 #[cfg(feature = "cli")]
 pub use sc_cli::{Error, Result};
@@ -39,7 +39,7 @@ native_executor_instance!(
 	pub PolkadotExecutor,
 	polkadot_runtime::api::dispatch,
 	polkadot_runtime::native_version,
-	frame_benchmarking::benchmarking::HostFunctions,
+	(frame_benchmarking::benchmarking::HostFunctions, polkadot_runtime_metrics::HostFunctions),
 );
 
 #[cfg(feature = "kusama")]
@@ -47,7 +47,7 @@ native_executor_instance!(
 	pub KusamaExecutor,
 	kusama_runtime::api::dispatch,
 	kusama_runtime::native_version,
-	frame_benchmarking::benchmarking::HostFunctions,
+	(frame_benchmarking::benchmarking::HostFunctions, polkadot_runtime_metrics::HostFunctions),
 );
 
 #[cfg(feature = "westend")]
@@ -55,7 +55,7 @@ native_executor_instance!(
 	pub WestendExecutor,
 	westend_runtime::api::dispatch,
 	westend_runtime::native_version,
-	frame_benchmarking::benchmarking::HostFunctions,
+	(frame_benchmarking::benchmarking::HostFunctions, polkadot_runtime_metrics::HostFunctions),
 );
 
 #[cfg(feature = "rococo")]
@@ -63,7 +63,7 @@ native_executor_instance!(
 	pub RococoExecutor,
 	rococo_runtime::api::dispatch,
 	rococo_runtime::native_version,
-	frame_benchmarking::benchmarking::HostFunctions,
+	(frame_benchmarking::benchmarking::HostFunctions, polkadot_runtime_metrics::HostFunctions),
 );
 
 /// A set of APIs that polkadot-like runtimes must implement.
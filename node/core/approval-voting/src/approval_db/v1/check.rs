@@ -0,0 +1,305 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Consistency checks of the approval voting data, to find and prune the damage left behind
+//! by a crash or a disk problem.
+//!
+//! The stored block range and the blocks-at-height entries within it are taken as the
+//! source of truth for the blocks we track. Block entries which aren't tracked are orphans,
+//! just like candidate entries which aren't referenced by any tracked block. Tracked blocks
+//! whose candidate or approval entries are missing can't be approved anymore, so they are
+//! pruned as well, and removed from the children of their parents.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use kvdb::{DBTransaction, KeyValueDB};
+use parity_scale_codec::{Decode, Encode};
+use polkadot_node_subsystem::{SubsystemResult, SubsystemError};
+use polkadot_primitives::v1::{BlockNumber, CandidateHash, Hash};
+
+use super::{
+	BlockEntry, CandidateEntry, StoredBlockRange,
+	BLOCK_ENTRY_PREFIX, BLOCKS_AT_HEIGHT_PREFIX, CANDIDATE_ENTRY_PREFIX, STORED_BLOCKS_KEY,
+	block_entry_key, blocks_at_height_key, candidate_entry_key,
+};
+
+/// An inconsistency found in the approval voting data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+	/// An entry with an unknown key or a value which can't be decoded. Pruned.
+	Undecodable {
+		/// The key of the entry.
+		key: Vec<u8>,
+	},
+	/// A blocks-at-height entry outside of the stored block range, which is never pruned.
+	/// Pruned along with the block entries it lists.
+	OrphanedBlocksAtHeight {
+		/// The height of the entry.
+		block_number: BlockNumber,
+	},
+	/// A block listed at its height without a block entry. Removed from the list.
+	MissingBlockEntry {
+		/// Number of the block.
+		block_number: BlockNumber,
+		/// Hash of the block.
+		block_hash: Hash,
+	},
+	/// A block entry which isn't listed at its height, so it is never pruned. Pruned.
+	OrphanedBlockEntry {
+		/// Number of the block.
+		block_number: BlockNumber,
+		/// Hash of the block.
+		block_hash: Hash,
+	},
+	/// A candidate of a block without an approval entry for the block, so the block can't
+	/// be approved. The block is pruned.
+	MissingApprovalEntry {
+		/// Hash of the block.
+		block_hash: Hash,
+		/// The candidate included in the block.
+		candidate_hash: CandidateHash,
+	},
+	/// An approval entry of a candidate for a block which doesn't include it or isn't
+	/// tracked. Pruned.
+	DanglingApprovalEntry {
+		/// The candidate.
+		candidate_hash: CandidateHash,
+		/// Hash of the block.
+		block_hash: Hash,
+	},
+	/// A candidate entry which isn't referenced by any tracked block, so it is never
+	/// pruned. Pruned.
+	OrphanedCandidateEntry {
+		/// The candidate.
+		candidate_hash: CandidateHash,
+	},
+	/// A child listed by a block entry which isn't tracked or was pruned. Removed from the list.
+	StaleChild {
+		/// Hash of the block.
+		block_hash: Hash,
+		/// Hash of the child.
+		child: Hash,
+	},
+}
+
+impl fmt::Display for Inconsistency {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Inconsistency::Undecodable { key } => write!(
+				f, "undecodable entry 0x{}",
+				key.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+			),
+			Inconsistency::OrphanedBlocksAtHeight { block_number } => write!(
+				f, "orphaned blocks at height #{}", block_number,
+			),
+			Inconsistency::MissingBlockEntry { block_number, block_hash } => write!(
+				f, "missing block entry of block #{} ({:?})", block_number, block_hash,
+			),
+			Inconsistency::OrphanedBlockEntry { block_number, block_hash } => write!(
+				f, "orphaned block entry of block #{} ({:?})", block_number, block_hash,
+			),
+			Inconsistency::MissingApprovalEntry { block_hash, candidate_hash } => write!(
+				f, "missing approval entry of candidate {:?} for block {:?}", candidate_hash.0, block_hash,
+			),
+			Inconsistency::DanglingApprovalEntry { candidate_hash, block_hash } => write!(
+				f, "dangling approval entry of candidate {:?} for block {:?}", candidate_hash.0, block_hash,
+			),
+			Inconsistency::OrphanedCandidateEntry { candidate_hash } => write!(
+				f, "orphaned candidate entry of candidate {:?}", candidate_hash.0,
+			),
+			Inconsistency::StaleChild { block_hash, child } => write!(
+				f, "stale child {:?} of block {:?}", child, block_hash,
+			),
+		}
+	}
+}
+
+/// Check the consistency of the approval voting data in the given column, returning all
+/// inconsistencies found.
+///
+/// If `repair` is set, all inconsistencies are pruned in a single transaction.
+pub fn check_consistency(
+	store: &dyn KeyValueDB,
+	col_data: u32,
+	repair: bool,
+) -> SubsystemResult<Vec<Inconsistency>> {
+	let mut inconsistencies = Vec::new();
+	let mut tx = DBTransaction::new();
+
+	let mut stored_blocks = None;
+	let mut blocks_at_height = BTreeMap::new();
+	let mut block_entries = HashMap::new();
+	let mut candidate_entries = HashMap::new();
+
+	for (key, value) in store.iter(col_data) {
+		let decoded = if &key[..] == STORED_BLOCKS_KEY {
+			StoredBlockRange::decode(&mut &value[..]).map(|range| stored_blocks = Some(range))
+		} else if key.starts_with(&BLOCKS_AT_HEIGHT_PREFIX) {
+			BlockNumber::decode(&mut &key[BLOCKS_AT_HEIGHT_PREFIX.len()..])
+				.and_then(|number| <Vec<Hash>>::decode(&mut &value[..]).map(|hashes| (number, hashes)))
+				.and_then(|(number, hashes)| if blocks_at_height_key(number)[..] == key[..] {
+					blocks_at_height.insert(number, hashes);
+					Ok(())
+				} else {
+					Err("malformed key".into())
+				})
+		} else if key.starts_with(&BLOCK_ENTRY_PREFIX) {
+			BlockEntry::decode(&mut &value[..])
+				.and_then(|entry| if block_entry_key(&entry.block_hash)[..] == key[..] {
+					block_entries.insert(entry.block_hash, entry);
+					Ok(())
+				} else {
+					Err("malformed key".into())
+				})
+		} else if key.starts_with(&CANDIDATE_ENTRY_PREFIX) {
+			CandidateEntry::decode(&mut &value[..])
+				.and_then(|entry| {
+					let candidate_hash = entry.candidate.hash();
+					if candidate_entry_key(&candidate_hash)[..] == key[..] {
+						candidate_entries.insert(candidate_hash, entry);
+						Ok(())
+					} else {
+						Err("malformed key".into())
+					}
+				})
+		} else {
+			Err("unknown key".into())
+		};
+
+		if decoded.is_err() {
+			inconsistencies.push(Inconsistency::Undecodable { key: key.to_vec() });
+			tx.delete(col_data, &key);
+		}
+	}
+
+	let stored_range = stored_blocks.map_or(0..0, |StoredBlockRange(start, end)| start..end);
+
+	// Blocks listed at their height within the stored range which have a block entry.
+	let mut tracked = HashSet::new();
+	for (&block_number, hashes) in &blocks_at_height {
+		if !stored_range.contains(&block_number) {
+			inconsistencies.push(Inconsistency::OrphanedBlocksAtHeight { block_number });
+			tx.delete(col_data, &blocks_at_height_key(block_number));
+			continue;
+		}
+
+		for block_hash in hashes {
+			match block_entries.get(block_hash) {
+				Some(entry) if entry.block_number == block_number => {
+					let _ = tracked.insert(*block_hash);
+				}
+				_ => inconsistencies.push(Inconsistency::MissingBlockEntry {
+					block_number,
+					block_hash: *block_hash,
+				}),
+			}
+		}
+	}
+
+	for entry in block_entries.values() {
+		if !tracked.contains(&entry.block_hash) {
+			inconsistencies.push(Inconsistency::OrphanedBlockEntry {
+				block_number: entry.block_number,
+				block_hash: entry.block_hash,
+			});
+			tx.delete(col_data, &block_entry_key(&entry.block_hash));
+		}
+	}
+
+	let mut live = tracked.clone();
+	for block_hash in &tracked {
+		let entry = &block_entries[block_hash];
+		for (_, candidate_hash) in &entry.candidates {
+			let approval_entry_exists = candidate_entries.get(candidate_hash)
+				.map_or(false, |c: &CandidateEntry| c.block_assignments.contains_key(block_hash));
+
+			if !approval_entry_exists {
+				inconsistencies.push(Inconsistency::MissingApprovalEntry {
+					block_hash: *block_hash,
+					candidate_hash: *candidate_hash,
+				});
+				if live.remove(block_hash) {
+					tx.delete(col_data, &block_entry_key(block_hash));
+				}
+			}
+		}
+	}
+
+	// Drop the children which are not live from the lists of the live blocks.
+	for block_hash in &live {
+		let entry = &block_entries[block_hash];
+		let (live_children, stale_children): (Vec<Hash>, Vec<Hash>) = entry.children.iter()
+			.cloned()
+			.partition(|child| live.contains(child));
+
+		if !stale_children.is_empty() {
+			for child in stale_children {
+				inconsistencies.push(Inconsistency::StaleChild { block_hash: *block_hash, child });
+			}
+
+			let mut entry = entry.clone();
+			entry.children = live_children;
+			tx.put_vec(col_data, &block_entry_key(block_hash), entry.encode());
+		}
+	}
+
+	// Rewrite the lists of the blocks which are not live anymore.
+	for (&block_number, hashes) in &blocks_at_height {
+		if !stored_range.contains(&block_number) {
+			continue;
+		}
+
+		let live_hashes = hashes.iter().filter(|h| live.contains(*h)).cloned().collect::<Vec<_>>();
+		if live_hashes.len() != hashes.len() {
+			if live_hashes.is_empty() {
+				tx.delete(col_data, &blocks_at_height_key(block_number));
+			} else {
+				tx.put_vec(col_data, &blocks_at_height_key(block_number), live_hashes.encode());
+			}
+		}
+	}
+
+	for (candidate_hash, entry) in &mut candidate_entries {
+		let dangling = entry.block_assignments.keys()
+			.filter(|block_hash| !block_entries.get(*block_hash)
+				.filter(|_| live.contains(*block_hash))
+				.map_or(false, |b: &BlockEntry| b.candidates.iter().any(|(_, c)| c == candidate_hash))
+			)
+			.cloned()
+			.collect::<Vec<_>>();
+
+		if dangling.len() == entry.block_assignments.len() {
+			inconsistencies.push(Inconsistency::OrphanedCandidateEntry { candidate_hash: *candidate_hash });
+			tx.delete(col_data, &candidate_entry_key(candidate_hash));
+		} else if !dangling.is_empty() {
+			for block_hash in dangling {
+				inconsistencies.push(Inconsistency::DanglingApprovalEntry {
+					candidate_hash: *candidate_hash,
+					block_hash,
+				});
+				let _ = entry.block_assignments.remove(&block_hash);
+			}
+			tx.put_vec(col_data, &candidate_entry_key(candidate_hash), entry.encode());
+		}
+	}
+
+	if repair && !inconsistencies.is_empty() {
+		store.write(tx).map_err(|e| SubsystemError::with_origin("approval-voting", e))?;
+	}
+
+	Ok(inconsistencies)
+}
@@ -34,6 +34,13 @@ use crate::backend::{Backend, BackendWriteOp};
 use crate::persisted_entries;
 
 const STORED_BLOCKS_KEY: &[u8] = b"Approvals_StoredBlocks";
+const BLOCK_ENTRY_PREFIX: [u8; 14] = *b"Approvals_blck";
+const CANDIDATE_ENTRY_PREFIX: [u8; 14] = *b"Approvals_cand";
+const BLOCKS_AT_HEIGHT_PREFIX: [u8; 12] = *b"Approvals_at";
+
+mod check;
+
+pub use check::{check_consistency, Inconsistency};
 
 #[cfg(test)]
 pub mod tests;
@@ -269,8 +276,6 @@ pub(crate) fn load_decode<D: Decode>(store: &dyn KeyValueDB, col_data: u32, key:
 
 /// The key a given block entry is stored under.
 pub(crate) fn block_entry_key(block_hash: &Hash) -> [u8; 46] {
-	const BLOCK_ENTRY_PREFIX: [u8; 14] = *b"Approvals_blck";
-
 	let mut key = [0u8; 14 + 32];
 	key[0..14].copy_from_slice(&BLOCK_ENTRY_PREFIX);
 	key[14..][..32].copy_from_slice(block_hash.as_ref());
@@ -280,8 +285,6 @@ pub(crate) fn block_entry_key(block_hash: &Hash) -> [u8; 46] {
 
 /// The key a given candidate entry is stored under.
 pub(crate) fn candidate_entry_key(candidate_hash: &CandidateHash) -> [u8; 46] {
-	const CANDIDATE_ENTRY_PREFIX: [u8; 14] = *b"Approvals_cand";
-
 	let mut key = [0u8; 14 + 32];
 	key[0..14].copy_from_slice(&CANDIDATE_ENTRY_PREFIX);
 	key[14..][..32].copy_from_slice(candidate_hash.0.as_ref());
@@ -291,8 +294,6 @@ pub(crate) fn candidate_entry_key(candidate_hash: &CandidateHash) -> [u8; 46] {
 
 /// The key a set of block hashes corresponding to a block number is stored under.
 pub(crate) fn blocks_at_height_key(block_number: BlockNumber) -> [u8; 16] {
-	const BLOCKS_AT_HEIGHT_PREFIX: [u8; 12] = *b"Approvals_at";
-
 	let mut key = [0u8; 12 + 4];
 	key[0..12].copy_from_slice(&BLOCKS_AT_HEIGHT_PREFIX);
 	block_number.using_encoded(|s| key[12..16].copy_from_slice(s));
@@ -623,3 +623,81 @@ fn load_all_blocks_works() {
 		vec![block_hash_a, block_hash_b, block_hash_c],
 	)
 }
+
+#[test]
+fn check_consistency_prunes_damaged_and_orphaned_entries() {
+	let (mut db, store) = make_db();
+
+	let parent_hash = Hash::repeat_byte(1);
+	let block_hash_a = Hash::repeat_byte(2);
+	let block_hash_b = Hash::repeat_byte(3);
+	let block_hash_c = Hash::repeat_byte(4);
+
+	let candidate_receipt_a = make_candidate(1.into(), parent_hash);
+	let candidate_receipt_b = make_candidate(2.into(), parent_hash);
+	let candidate_hash_a = candidate_receipt_a.hash();
+	let candidate_hash_b = candidate_receipt_b.hash();
+
+	let block_entry_a = make_block_entry(
+		block_hash_a,
+		parent_hash,
+		10,
+		vec![(CoreIndex(0), candidate_hash_a)],
+	);
+	let block_entry_b = make_block_entry(
+		block_hash_b,
+		parent_hash,
+		10,
+		vec![(CoreIndex(0), candidate_hash_a), (CoreIndex(1), candidate_hash_b)],
+	);
+
+	let mut new_candidate_info = HashMap::new();
+	new_candidate_info.insert(candidate_hash_a, NewCandidateInfo::new(candidate_receipt_a, GroupIndex(0), None));
+	new_candidate_info.insert(candidate_hash_b, NewCandidateInfo::new(candidate_receipt_b, GroupIndex(1), None));
+
+	let mut overlay_db = OverlayedBackend::new(&db);
+	for entry in vec![block_entry_a.clone(), block_entry_b] {
+		add_block_entry(
+			&mut overlay_db,
+			entry.into(),
+			10,
+			|h| new_candidate_info.get(h).map(|x| x.clone()),
+		).unwrap();
+	}
+	let write_ops = overlay_db.into_write_ops();
+	db.write(write_ops).unwrap();
+
+	assert!(check_consistency(store.as_ref(), DATA_COL, false).unwrap().is_empty());
+
+	// Lose the candidate entry of block B and leave behind a block entry for C which is
+	// never tracked, but listed as a child of A.
+	let mut overlay_db = OverlayedBackend::new(&db);
+	overlay_db.delete_candidate_entry(&candidate_hash_b);
+	overlay_db.write_block_entry(make_block_entry(block_hash_c, block_hash_a, 11, Vec::new()).into());
+	let mut damaged_block_entry_a = block_entry_a.clone();
+	damaged_block_entry_a.children.push(block_hash_c);
+	overlay_db.write_block_entry(damaged_block_entry_a.into());
+	let write_ops = overlay_db.into_write_ops();
+	db.write(write_ops).unwrap();
+
+	let mut inconsistencies = check_consistency(store.as_ref(), DATA_COL, true).unwrap();
+	inconsistencies.sort_by_key(|i| i.to_string());
+
+	assert_eq!(inconsistencies, vec![
+		Inconsistency::DanglingApprovalEntry { candidate_hash: candidate_hash_a, block_hash: block_hash_b },
+		Inconsistency::MissingApprovalEntry { block_hash: block_hash_b, candidate_hash: candidate_hash_b },
+		Inconsistency::OrphanedBlockEntry { block_number: 11, block_hash: block_hash_c },
+		Inconsistency::StaleChild { block_hash: block_hash_a, child: block_hash_c },
+	]);
+
+	assert!(check_consistency(store.as_ref(), DATA_COL, false).unwrap().is_empty());
+	assert_eq!(load_blocks_at_height(store.as_ref(), &TEST_CONFIG, &10).unwrap(), vec![block_hash_a]);
+	assert_eq!(load_block_entry(store.as_ref(), &TEST_CONFIG, &block_hash_a).unwrap(), Some(block_entry_a));
+	assert!(load_block_entry(store.as_ref(), &TEST_CONFIG, &block_hash_b).unwrap().is_none());
+	assert!(load_block_entry(store.as_ref(), &TEST_CONFIG, &block_hash_c).unwrap().is_none());
+
+	let candidate_entry_a = load_candidate_entry(store.as_ref(), &TEST_CONFIG, &candidate_hash_a)
+		.unwrap()
+		.unwrap();
+	assert_eq!(candidate_entry_a.block_assignments.keys().collect::<Vec<_>>(), vec![&block_hash_a]);
+}
@@ -81,6 +81,7 @@ mod time;
 mod persisted_entries;
 
 use crate::approval_db::v1::{DbBackend, Config as DatabaseConfig};
+pub use crate::approval_db::v1::{check_consistency, Inconsistency};
 use crate::backend::{Backend, OverlayedBackend};
 
 #[cfg(test)]
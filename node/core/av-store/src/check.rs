@@ -0,0 +1,357 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Consistency checks of the availability store, to find and repair the damage left behind
+//! by a crash or a disk problem.
+//!
+//! The candidate meta entries are taken as the source of truth: index entries and data
+//! which no meta entry accounts for are orphans, meta entries referring to missing index
+//! entries or data are fixed up.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use kvdb::{DBTransaction, KeyValueDB};
+use parity_scale_codec::Decode;
+
+use polkadot_primitives::v1::{BlockNumber, CandidateHash, Hash, ValidatorIndex};
+
+use crate::{
+	AVAILABLE_PREFIX, CHUNK_PREFIX, META_PREFIX, UNFINALIZED_PREFIX, PRUNE_BY_TIME_PREFIX,
+	BEBlockNumber, CandidateMeta, Config, Error, PruningConfig, State,
+	decode_pruning_key, decode_unfinalized_key,
+	delete_available_data, delete_chunk, delete_pruning_key, delete_unfinalized_inclusion,
+	write_meta, write_pruning_key, write_unfinalized_block_contains,
+};
+
+/// An inconsistency found in the availability store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+	/// An entry with an unknown key or a value which can't be decoded. Repaired by deleting it.
+	Undecodable {
+		/// The column of the entry.
+		column: u32,
+		/// The key of the entry.
+		key: Vec<u8>,
+	},
+	/// An unfinalized block index entry the candidate meta doesn't know about.
+	/// Repaired by deleting it.
+	DanglingUnfinalizedEntry {
+		/// Number of the block.
+		block_number: BlockNumber,
+		/// Hash of the block.
+		block_hash: Hash,
+		/// The candidate included in the block.
+		candidate_hash: CandidateHash,
+	},
+	/// An unfinalized block of a candidate which is missing from the index, so the
+	/// candidate isn't touched when the block is finalized. Repaired by indexing it.
+	MissingUnfinalizedEntry {
+		/// Number of the block.
+		block_number: BlockNumber,
+		/// Hash of the block.
+		block_hash: Hash,
+		/// The candidate included in the block.
+		candidate_hash: CandidateHash,
+	},
+	/// A pruning key not matching the state of its candidate, if any, which would prune the
+	/// candidate at the wrong time. Repaired by deleting it.
+	DanglingPruningKey {
+		/// The time the candidate would be pruned at, since the unix epoch.
+		prune_at: Duration,
+		/// The candidate.
+		candidate_hash: CandidateHash,
+	},
+	/// A candidate which is never pruned because its pruning key is missing.
+	/// Repaired by writing it.
+	MissingPruningKey {
+		/// The time the candidate should be pruned at, since the unix epoch.
+		prune_at: Duration,
+		/// The candidate.
+		candidate_hash: CandidateHash,
+	},
+	/// Available data of a candidate which isn't accounted for by its meta, so it is never
+	/// pruned. Repaired by deleting it.
+	OrphanedAvailableData {
+		/// The candidate.
+		candidate_hash: CandidateHash,
+	},
+	/// A chunk of a candidate which isn't accounted for by its meta, so it is never pruned.
+	/// Repaired by deleting it.
+	OrphanedChunk {
+		/// The candidate.
+		candidate_hash: CandidateHash,
+		/// The index of the chunk.
+		index: ValidatorIndex,
+	},
+	/// Available data noted in the meta of a candidate which is missing.
+	/// Repaired by noting it as missing.
+	MissingAvailableData {
+		/// The candidate.
+		candidate_hash: CandidateHash,
+	},
+	/// A chunk noted in the meta of a candidate which is missing.
+	/// Repaired by noting it as missing.
+	MissingChunk {
+		/// The candidate.
+		candidate_hash: CandidateHash,
+		/// The index of the chunk.
+		index: ValidatorIndex,
+	},
+}
+
+impl fmt::Display for Inconsistency {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Inconsistency::Undecodable { column, key } => write!(
+				f, "undecodable entry 0x{} in column {}",
+				key.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+				column,
+			),
+			Inconsistency::DanglingUnfinalizedEntry { block_number, block_hash, candidate_hash } => write!(
+				f, "dangling unfinalized entry of candidate {:?} in block #{} ({:?})",
+				candidate_hash.0, block_number, block_hash,
+			),
+			Inconsistency::MissingUnfinalizedEntry { block_number, block_hash, candidate_hash } => write!(
+				f, "missing unfinalized entry of candidate {:?} in block #{} ({:?})",
+				candidate_hash.0, block_number, block_hash,
+			),
+			Inconsistency::DanglingPruningKey { prune_at, candidate_hash } => write!(
+				f, "dangling pruning key of candidate {:?} at {}s",
+				candidate_hash.0, prune_at.as_secs(),
+			),
+			Inconsistency::MissingPruningKey { prune_at, candidate_hash } => write!(
+				f, "missing pruning key of candidate {:?} at {}s",
+				candidate_hash.0, prune_at.as_secs(),
+			),
+			Inconsistency::OrphanedAvailableData { candidate_hash } => write!(
+				f, "orphaned available data of candidate {:?}", candidate_hash.0,
+			),
+			Inconsistency::OrphanedChunk { candidate_hash, index } => write!(
+				f, "orphaned chunk {} of candidate {:?}", index.0, candidate_hash.0,
+			),
+			Inconsistency::MissingAvailableData { candidate_hash } => write!(
+				f, "missing available data of candidate {:?}", candidate_hash.0,
+			),
+			Inconsistency::MissingChunk { candidate_hash, index } => write!(
+				f, "missing chunk {} of candidate {:?}", index.0, candidate_hash.0,
+			),
+		}
+	}
+}
+
+/// Check the consistency of the availability store, returning all inconsistencies found.
+///
+/// If `repair` is set, all inconsistencies are repaired in a single transaction.
+pub fn check_consistency(
+	db: &Arc<dyn KeyValueDB>,
+	config: &Config,
+	repair: bool,
+) -> Result<Vec<Inconsistency>, Error> {
+	check_consistency_with_pruning_config(db, config, &PruningConfig::default(), repair)
+}
+
+pub(crate) fn check_consistency_with_pruning_config(
+	db: &Arc<dyn KeyValueDB>,
+	config: &Config,
+	pruning_config: &PruningConfig,
+	repair: bool,
+) -> Result<Vec<Inconsistency>, Error> {
+	let mut inconsistencies = Vec::new();
+
+	let mut metas = HashMap::new();
+	let mut unfinalized_keys = Vec::new();
+	let mut pruning_keys = Vec::new();
+
+	for (key, value) in db.iter(config.col_meta) {
+		let decoded = if key.starts_with(META_PREFIX) {
+			CandidateHash::decode(&mut &key[META_PREFIX.len()..])
+				.and_then(|candidate_hash| CandidateMeta::decode(&mut &value[..]).map(|meta| (candidate_hash, meta)))
+				.map(|(candidate_hash, meta)| { metas.insert(candidate_hash, meta); })
+		} else if key.starts_with(UNFINALIZED_PREFIX) {
+			decode_unfinalized_key(&key).map(|k| unfinalized_keys.push(k))
+		} else if key.starts_with(PRUNE_BY_TIME_PREFIX) {
+			decode_pruning_key(&key).map(|k| pruning_keys.push(k))
+		} else {
+			Err("unknown prefix".into())
+		};
+
+		if decoded.is_err() {
+			inconsistencies.push(Inconsistency::Undecodable { column: config.col_meta, key: key.into() });
+		}
+	}
+
+	let mut indexed_blocks = HashSet::new();
+	for (block_number, block_hash, candidate_hash) in unfinalized_keys {
+		let indexed = match metas.get(&candidate_hash).map(|meta| &meta.state) {
+			Some(State::Unfinalized(_, blocks)) =>
+				blocks.contains(&(BEBlockNumber(block_number), block_hash)),
+			_ => false,
+		};
+
+		if indexed {
+			indexed_blocks.insert((block_number, block_hash, candidate_hash));
+		} else {
+			inconsistencies.push(Inconsistency::DanglingUnfinalizedEntry {
+				block_number,
+				block_hash,
+				candidate_hash,
+			});
+		}
+	}
+
+	let mut pruned_candidates = HashSet::new();
+	for (prune_at, candidate_hash) in pruning_keys {
+		let expected = metas.get(&candidate_hash).and_then(|meta| expected_prune_at(meta, pruning_config));
+
+		if expected == Some(prune_at) {
+			pruned_candidates.insert(candidate_hash);
+		} else {
+			inconsistencies.push(Inconsistency::DanglingPruningKey { prune_at, candidate_hash });
+		}
+	}
+
+	let mut stored_data = HashSet::new();
+	let mut stored_chunks = HashSet::new();
+	for (key, _) in db.iter(config.col_data) {
+		let orphan = if key.starts_with(AVAILABLE_PREFIX) {
+			CandidateHash::decode(&mut &key[AVAILABLE_PREFIX.len()..]).map(|candidate_hash| {
+				if metas.get(&candidate_hash).map_or(false, |meta| meta.data_available) {
+					stored_data.insert(candidate_hash);
+					None
+				} else {
+					Some(Inconsistency::OrphanedAvailableData { candidate_hash })
+				}
+			})
+		} else if key.starts_with(CHUNK_PREFIX) {
+			<(CandidateHash, ValidatorIndex)>::decode(&mut &key[CHUNK_PREFIX.len()..]).map(|(candidate_hash, index)| {
+				let noted = metas.get(&candidate_hash)
+					.and_then(|meta| meta.chunks_stored.get(index.0 as usize).map(|b| *b))
+					.unwrap_or(false);
+
+				if noted {
+					stored_chunks.insert((candidate_hash, index));
+					None
+				} else {
+					Some(Inconsistency::OrphanedChunk { candidate_hash, index })
+				}
+			})
+		} else {
+			Err("unknown prefix".into())
+		};
+
+		match orphan {
+			Ok(orphan) => inconsistencies.extend(orphan),
+			Err(_) => inconsistencies.push(Inconsistency::Undecodable { column: config.col_data, key: key.into() }),
+		}
+	}
+
+	for (candidate_hash, meta) in &metas {
+		if let State::Unfinalized(_, ref blocks) = meta.state {
+			for (block_number, block_hash) in blocks {
+				if !indexed_blocks.contains(&(block_number.0, *block_hash, *candidate_hash)) {
+					inconsistencies.push(Inconsistency::MissingUnfinalizedEntry {
+						block_number: block_number.0,
+						block_hash: *block_hash,
+						candidate_hash: *candidate_hash,
+					});
+				}
+			}
+		}
+
+		if let Some(prune_at) = expected_prune_at(meta, pruning_config) {
+			if !pruned_candidates.contains(candidate_hash) {
+				inconsistencies.push(Inconsistency::MissingPruningKey {
+					prune_at,
+					candidate_hash: *candidate_hash,
+				});
+			}
+		}
+
+		if meta.data_available && !stored_data.contains(candidate_hash) {
+			inconsistencies.push(Inconsistency::MissingAvailableData { candidate_hash: *candidate_hash });
+		}
+
+		for (i, stored) in meta.chunks_stored.iter().enumerate() {
+			let index = ValidatorIndex(i as _);
+			if *stored && !stored_chunks.contains(&(*candidate_hash, index)) {
+				inconsistencies.push(Inconsistency::MissingChunk { candidate_hash: *candidate_hash, index });
+			}
+		}
+	}
+
+	if repair && !inconsistencies.is_empty() {
+		db.write(repair_transaction(config, metas, &inconsistencies))?;
+	}
+
+	Ok(inconsistencies)
+}
+
+/// The time a candidate is pruned at, given its meta, or `None` if it is pruned on finality.
+fn expected_prune_at(meta: &CandidateMeta, pruning_config: &PruningConfig) -> Option<Duration> {
+	match meta.state {
+		State::Unavailable(at) => Some(Into::<Duration>::into(at) + pruning_config.keep_unavailable_for),
+		State::Unfinalized(..) => None,
+		State::Finalized(at) => Some(Into::<Duration>::into(at) + pruning_config.keep_finalized_for),
+	}
+}
+
+fn repair_transaction(
+	config: &Config,
+	mut metas: HashMap<CandidateHash, CandidateMeta>,
+	inconsistencies: &[Inconsistency],
+) -> DBTransaction {
+	let mut tx = DBTransaction::new();
+	let mut changed_metas = HashSet::new();
+
+	for inconsistency in inconsistencies {
+		match *inconsistency {
+			Inconsistency::Undecodable { column, ref key } => tx.delete(column, key),
+			Inconsistency::DanglingUnfinalizedEntry { block_number, ref block_hash, ref candidate_hash } =>
+				delete_unfinalized_inclusion(&mut tx, config, block_number, block_hash, candidate_hash),
+			Inconsistency::MissingUnfinalizedEntry { block_number, ref block_hash, ref candidate_hash } =>
+				write_unfinalized_block_contains(&mut tx, config, block_number, block_hash, candidate_hash),
+			Inconsistency::DanglingPruningKey { prune_at, ref candidate_hash } =>
+				delete_pruning_key(&mut tx, config, prune_at, candidate_hash),
+			Inconsistency::MissingPruningKey { prune_at, ref candidate_hash } =>
+				write_pruning_key(&mut tx, config, prune_at, candidate_hash),
+			Inconsistency::OrphanedAvailableData { ref candidate_hash } =>
+				delete_available_data(&mut tx, config, candidate_hash),
+			Inconsistency::OrphanedChunk { ref candidate_hash, index } =>
+				delete_chunk(&mut tx, config, candidate_hash, index),
+			Inconsistency::MissingAvailableData { candidate_hash } => {
+				if let Some(meta) = metas.get_mut(&candidate_hash) {
+					meta.data_available = false;
+					changed_metas.insert(candidate_hash);
+				}
+			}
+			Inconsistency::MissingChunk { candidate_hash, index } => {
+				if let Some(meta) = metas.get_mut(&candidate_hash) {
+					meta.chunks_stored.set(index.0 as usize, false);
+					changed_metas.insert(candidate_hash);
+				}
+			}
+		}
+	}
+
+	for candidate_hash in changed_metas {
+		write_meta(&mut tx, config, &candidate_hash, &metas[&candidate_hash]);
+	}
+
+	tx
+}
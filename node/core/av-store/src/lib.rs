@@ -52,6 +52,10 @@ use polkadot_subsystem::messages::{
 };
 use bitvec::{vec::BitVec, order::Lsb0 as BitOrderLsb0};
 
+mod check;
+
+pub use check::{check_consistency, Inconsistency};
+
 #[cfg(test)]
 mod tests;
 
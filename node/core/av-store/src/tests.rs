@@ -1082,6 +1082,72 @@ fn forkfullness_works() {
 	});
 }
 
+#[test]
+fn consistency_check_repairs_orphans_and_missing_entries() {
+	let store = Arc::new(kvdb_memorydb::create(columns::NUM_COLUMNS));
+	let db: Arc<dyn KeyValueDB> = store.clone();
+	let pruning_config = TestState::default().pruning_config;
+
+	let included = CandidateHash(Hash::repeat_byte(1));
+	let orphaned = CandidateHash(Hash::repeat_byte(2));
+	let block = (5, Hash::repeat_byte(5));
+	let chunk = ErasureChunk {
+		chunk: vec![1, 2, 3],
+		index: ValidatorIndex(0),
+		proof: vec![vec![3, 4, 5]],
+	};
+
+	with_tx(&store, |tx| {
+		// The included candidate lacks its unfinalized entry and the chunk it claims to have,
+		// but still has the pruning key of an unavailable candidate.
+		write_meta(tx, &TEST_CONFIG, &included, &CandidateMeta {
+			state: State::Unfinalized(BETimestamp(10), vec![(BEBlockNumber(block.0), block.1)]),
+			data_available: false,
+			chunks_stored: bitvec::bitvec![BitOrderLsb0, u8; 1; 1],
+		});
+		write_pruning_key(tx, &TEST_CONFIG, Duration::from_secs(11), &included);
+
+		// The orphaned candidate has no meta at all.
+		write_chunk(tx, &TEST_CONFIG, &orphaned, chunk.index, &chunk);
+		write_unfinalized_block_contains(tx, &TEST_CONFIG, block.0, &block.1, &orphaned);
+	});
+
+	let mut inconsistencies = check::check_consistency_with_pruning_config(
+		&db,
+		&TEST_CONFIG,
+		&pruning_config,
+		true,
+	).unwrap();
+	inconsistencies.sort_by_key(|i| format!("{}", i));
+
+	assert_eq!(inconsistencies, vec![
+		Inconsistency::DanglingPruningKey {
+			prune_at: Duration::from_secs(11),
+			candidate_hash: included,
+		},
+		Inconsistency::DanglingUnfinalizedEntry {
+			block_number: block.0,
+			block_hash: block.1,
+			candidate_hash: orphaned,
+		},
+		Inconsistency::MissingChunk { candidate_hash: included, index: ValidatorIndex(0) },
+		Inconsistency::MissingUnfinalizedEntry {
+			block_number: block.0,
+			block_hash: block.1,
+			candidate_hash: included,
+		},
+		Inconsistency::OrphanedChunk { candidate_hash: orphaned, index: ValidatorIndex(0) },
+	]);
+
+	assert!(check::check_consistency_with_pruning_config(&db, &TEST_CONFIG, &pruning_config, false)
+		.unwrap()
+		.is_empty());
+
+	let meta = load_meta(&db, &TEST_CONFIG, &included).unwrap().unwrap();
+	assert!(!meta.chunks_stored[0]);
+	assert!(load_chunk(&db, &TEST_CONFIG, &orphaned, ValidatorIndex(0)).unwrap().is_none());
+}
+
 async fn query_available_data(
 	virtual_overseer: &mut VirtualOverseer,
 	candidate_hash: CandidateHash,
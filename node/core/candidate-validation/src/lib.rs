@@ -24,12 +24,12 @@
 #![warn(missing_docs)]
 
 use polkadot_node_subsystem::{
-	overseer,
-	SubsystemContext, SpawnedSubsystem, SubsystemResult, SubsystemError,
+	overseer::{self, SubsystemSender as _},
+	SubsystemContext, SubsystemSender, SpawnedSubsystem, SubsystemResult, SubsystemError,
 	FromOverseer, OverseerSignal,
 	messages::{
 		CandidateValidationMessage, RuntimeApiMessage,
-		ValidationFailed, RuntimeApiRequest,
+		ValidationFailed, RuntimeApiRequest, PreCheckOutcome,
 	},
 	errors::RuntimeApiError,
 };
@@ -39,10 +39,12 @@ use polkadot_node_primitives::{
 };
 use polkadot_primitives::v1::{
 	ValidationCode, CandidateDescriptor, PersistedValidationData,
-	OccupiedCoreAssumption, Hash, CandidateCommitments,
+	OccupiedCoreAssumption, Hash, CandidateCommitments, ValidationCodeHash,
 };
 use polkadot_parachain::primitives::{ValidationParams, ValidationResult as WasmValidationResult};
-use polkadot_node_core_pvf::{Pvf, ValidationHost, ValidationError, InvalidCandidate as WasmInvalidCandidate};
+use polkadot_node_core_pvf::{
+	Pvf, ValidationHost, ValidationError, PrepareError, InvalidCandidate as WasmInvalidCandidate,
+};
 
 use parity_scale_codec::Encode;
 
@@ -176,6 +178,31 @@ where
 						Err(e) => return Err(e),
 					}
 				}
+				CandidateValidationMessage::PreCheck(
+					relay_parent,
+					validation_code_hash,
+					response_sender,
+				) => {
+					let mut sender = ctx.sender().clone();
+					let mut validation_host = validation_host.clone();
+
+					// Preparing a PVF may take a long time, validation requests must not wait for it.
+					ctx.spawn("pvf-precheck", async move {
+						let outcome = precheck_pvf(
+							&mut sender,
+							&mut validation_host,
+							relay_parent,
+							validation_code_hash,
+						).await;
+
+						if let Err(_e) = response_sender.send(outcome) {
+							tracing::warn!(
+								target: LOG_TARGET,
+								"Requester of PVF pre-check dropped",
+							)
+						}
+					}.boxed())?;
+				}
 			}
 		}
 	}
@@ -201,6 +228,57 @@ where
 	receiver.await.map_err(Into::into)
 }
 
+async fn precheck_pvf(
+	sender: &mut impl SubsystemSender,
+	mut validation_backend: impl ValidationBackend,
+	relay_parent: Hash,
+	validation_code_hash: ValidationCodeHash,
+) -> PreCheckOutcome {
+	let (tx, rx) = oneshot::channel();
+	sender.send_message(RuntimeApiMessage::Request(
+		relay_parent,
+		RuntimeApiRequest::ValidationCodeByHash(validation_code_hash, tx),
+	).into()).await;
+
+	let validation_code = match rx.await {
+		Ok(Ok(Some(code))) => code,
+		Ok(Ok(None)) | Ok(Err(_)) | Err(_) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?validation_code_hash,
+				"Pre-check: validation code is not available",
+			);
+			return PreCheckOutcome::Failed;
+		}
+	};
+
+	let raw_validation_code = match sp_maybe_compressed_blob::decompress(
+		&validation_code.0,
+		VALIDATION_CODE_BOMB_LIMIT,
+	) {
+		Ok(code) => code,
+		Err(e) => {
+			tracing::debug!(target: LOG_TARGET, err=?e, "Pre-check: invalid validation code");
+
+			// The code can never be decompressed, so it will never be fit for execution.
+			return PreCheckOutcome::Invalid;
+		}
+	};
+
+	match validation_backend.precheck_pvf(raw_validation_code.to_vec()).await {
+		Ok(()) => PreCheckOutcome::Valid,
+		Err(e) if e.is_deterministic() => {
+			tracing::debug!(target: LOG_TARGET, ?validation_code_hash, err=?e, "Pre-check: PVF rejected");
+			PreCheckOutcome::Invalid
+		}
+		Err(e) => {
+			tracing::debug!(target: LOG_TARGET, ?validation_code_hash, err=?e, "Pre-check: failed");
+			PreCheckOutcome::Failed
+		}
+	}
+}
+
 #[derive(Debug)]
 enum AssumptionCheckOutcome {
 	Matches(PersistedValidationData, ValidationCode),
@@ -463,6 +541,8 @@ trait ValidationBackend {
 		raw_validation_code: Vec<u8>,
 		params: ValidationParams
 	) -> Result<WasmValidationResult, ValidationError>;
+
+	async fn precheck_pvf(&mut self, raw_validation_code: Vec<u8>) -> Result<(), PrepareError>;
 }
 
 #[async_trait]
@@ -488,6 +568,19 @@ impl ValidationBackend for &'_ mut ValidationHost {
 
 		validation_result
 	}
+
+	async fn precheck_pvf(&mut self, raw_validation_code: Vec<u8>) -> Result<(), PrepareError> {
+		let (tx, rx) = oneshot::channel();
+		if let Err(err) = ValidationHost::precheck_pvf(
+			&mut **self,
+			Pvf::from_code(raw_validation_code),
+			tx,
+		).await {
+			return Err(PrepareError::Internal(format!("cannot send pvf to the validation host: {:?}", err)));
+		}
+
+		rx.await.map_err(|_| PrepareError::Internal("pre-check was cancelled".into()))?
+	}
 }
 
 /// Does basic checks of a candidate. Provide the encoded PoV-block. Returns `Ok` if basic checks
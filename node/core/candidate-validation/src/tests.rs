@@ -317,12 +317,21 @@ fn check_does_not_match() {
 
 struct MockValidatorBackend {
 	result: Result<WasmValidationResult, ValidationError>,
+	precheck_result: Result<(), PrepareError>,
 }
 
 impl MockValidatorBackend {
 	fn with_hardcoded_result(result: Result<WasmValidationResult, ValidationError>) -> Self {
 		Self {
 			result,
+			precheck_result: Ok(()),
+		}
+	}
+
+	fn with_hardcoded_precheck_result(precheck_result: Result<(), PrepareError>) -> Self {
+		Self {
+			result: Err(ValidationError::InternalError("not expected to validate".into())),
+			precheck_result,
 		}
 	}
 }
@@ -336,6 +345,10 @@ impl ValidationBackend for MockValidatorBackend {
 	) -> Result<WasmValidationResult, ValidationError> {
 		self.result.clone()
 	}
+
+	async fn precheck_pvf(&mut self, _raw_validation_code: Vec<u8>) -> Result<(), PrepareError> {
+		self.precheck_result.clone()
+	}
 }
 
 #[test]
@@ -632,3 +645,75 @@ fn pov_decompression_failure_is_invalid() {
 		Ok(ValidationResult::Invalid(InvalidCandidate::PoVDecompressionFailure))
 	);
 }
+
+fn precheck_with_backend(
+	backend: MockValidatorBackend,
+	code: Option<ValidationCode>,
+) -> PreCheckOutcome {
+	let relay_parent = [3; 32].into();
+	let validation_code_hash = ValidationCode(vec![1, 2, 3]).hash();
+
+	let pool = TaskExecutor::new();
+	let (mut ctx, mut ctx_handle) = test_helpers::make_subsystem_context(pool.clone());
+
+	let (check_fut, check_result) = precheck_pvf(
+		ctx.sender(),
+		backend,
+		relay_parent,
+		validation_code_hash,
+	).remote_handle();
+
+	let test_fut = async move {
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				rp,
+				RuntimeApiRequest::ValidationCodeByHash(h, tx),
+			)) => {
+				assert_eq!(rp, relay_parent);
+				assert_eq!(h, validation_code_hash);
+
+				let _ = tx.send(Ok(code));
+			}
+		);
+
+		check_result.await
+	};
+
+	executor::block_on(future::join(test_fut, check_fut)).0
+}
+
+#[test]
+fn precheck_reports_preparation_outcome() {
+	let code = Some(ValidationCode(vec![1, 2, 3]));
+
+	assert_eq!(
+		precheck_with_backend(MockValidatorBackend::with_hardcoded_precheck_result(Ok(())), code.clone()),
+		PreCheckOutcome::Valid,
+	);
+	assert_eq!(
+		precheck_with_backend(
+			MockValidatorBackend::with_hardcoded_precheck_result(
+				Err(PrepareError::Preparation("compilation failed".into())),
+			),
+			code.clone(),
+		),
+		PreCheckOutcome::Invalid,
+	);
+	// Local issues must not lead to voting against the PVF.
+	assert_eq!(
+		precheck_with_backend(
+			MockValidatorBackend::with_hardcoded_precheck_result(Err(PrepareError::DidntMakeIt)),
+			code,
+		),
+		PreCheckOutcome::Failed,
+	);
+}
+
+#[test]
+fn precheck_fails_if_code_is_unavailable() {
+	assert_eq!(
+		precheck_with_backend(MockValidatorBackend::with_hardcoded_precheck_result(Ok(())), None),
+		PreCheckOutcome::Failed,
+	);
+}
@@ -0,0 +1,325 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The PVF Pre-Checker subsystem.
+//!
+//! Before a code upgrade is enacted, the validators vote on whether the new validation code can be
+//! compiled. For every activated leaf this subsystem fetches the PVFs that await the votes, asks
+//! the Candidate Validation subsystem to prepare each of them and submits a signed statement with
+//! the outcome to the relay chain.
+//!
+//! Every PVF is judged only once. The judgement is reused to vote again in the following sessions
+//! for as long as the vote is still ongoing.
+
+#![deny(unused_crate_dependencies)]
+#![warn(missing_docs)]
+
+use futures::{
+	channel::oneshot,
+	future::BoxFuture,
+	prelude::*,
+	select,
+	stream::FuturesUnordered,
+};
+use polkadot_node_subsystem::{
+	overseer,
+	ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext,
+	SubsystemError, SubsystemResult,
+	messages::{
+		CandidateValidationMessage, PreCheckOutcome, PvfCheckerMessage, RuntimeApiMessage,
+		RuntimeApiRequest,
+	},
+};
+use polkadot_node_subsystem_util as util;
+use polkadot_primitives::v1::{
+	Hash, PvfCheckStatement, SessionIndex, ValidationCodeHash, ValidatorId, ValidatorIndex,
+	ValidatorSignature,
+};
+use sp_application_crypto::AppKey;
+use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
+use std::{
+	collections::{HashMap, HashSet},
+	convert::TryInto,
+};
+
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "parachain::pvf-checker";
+
+/// The first version of the `ParachainHost` runtime API supporting the PVF pre-checking.
+const PVF_CHECKING_API_VERSION: u32 = 3;
+
+/// A pre-check requested from the Candidate Validation subsystem, resolving to its outcome.
+type PendingPreCheck = BoxFuture<'static, (ValidationCodeHash, PreCheckOutcome)>;
+
+/// The PVF Pre-Checker subsystem.
+pub struct PvfCheckerSubsystem {
+	keystore: SyncCryptoStorePtr,
+}
+
+impl PvfCheckerSubsystem {
+	/// Create a new instance of the [`PvfCheckerSubsystem`].
+	pub fn new(keystore: SyncCryptoStorePtr) -> Self {
+		Self { keystore }
+	}
+}
+
+impl<Context> overseer::Subsystem<Context, SubsystemError> for PvfCheckerSubsystem
+where
+	Context: SubsystemContext<Message = PvfCheckerMessage>,
+	Context: overseer::SubsystemContext<Message = PvfCheckerMessage>,
+	Context: overseer::SendsTo<CandidateValidationMessage> + overseer::SendsTo<RuntimeApiMessage>,
+{
+	fn start(self, ctx: Context) -> SpawnedSubsystem {
+		let future = run(ctx, self.keystore)
+			.map_err(|e| SubsystemError::with_origin("pvf-checker", e))
+			.boxed();
+
+		SpawnedSubsystem {
+			name: "pvf-checker-subsystem",
+			future,
+		}
+	}
+}
+
+/// The data needed to vote in the context of the most recent leaf.
+struct LeafContext {
+	relay_parent: Hash,
+	session_index: SessionIndex,
+	/// Our key and index in the active validator set, if we are a validator.
+	signing_key: Option<(ValidatorId, ValidatorIndex)>,
+	/// The PVFs awaiting the votes in the state of this leaf.
+	pending: Vec<ValidationCodeHash>,
+}
+
+#[derive(Default)]
+struct State {
+	/// The outcomes of the finished pre-checks.
+	judgements: HashMap<ValidationCodeHash, PreCheckOutcome>,
+	/// The PVFs being pre-checked at the moment.
+	in_flight: HashSet<ValidationCodeHash>,
+	/// The statements we have already submitted.
+	voted: HashSet<(SessionIndex, ValidationCodeHash)>,
+	latest_leaf: Option<LeafContext>,
+}
+
+async fn run<Context>(mut ctx: Context, keystore: SyncCryptoStorePtr) -> SubsystemResult<()>
+where
+	Context: SubsystemContext<Message = PvfCheckerMessage>,
+	Context: overseer::SubsystemContext<Message = PvfCheckerMessage>,
+	Context: overseer::SendsTo<CandidateValidationMessage> + overseer::SendsTo<RuntimeApiMessage>,
+{
+	let mut state = State::default();
+	let mut pre_checks = FuturesUnordered::<PendingPreCheck>::new();
+
+	loop {
+		select! {
+			msg = ctx.recv().fuse() => match msg? {
+				FromOverseer::Signal(OverseerSignal::Conclude) => return Ok(()),
+				FromOverseer::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate { activated, .. })) => {
+					for leaf in activated {
+						if let Err(e) = state.handle_leaf(&mut ctx, &keystore, &mut pre_checks, leaf.hash).await {
+							tracing::debug!(
+								target: LOG_TARGET,
+								leaf = ?leaf.hash,
+								err = ?e,
+								"Failed to process an active leaf",
+							);
+						}
+					}
+				}
+				FromOverseer::Signal(OverseerSignal::BlockFinalized(..)) => {}
+				FromOverseer::Communication { msg } => match msg {},
+			},
+			(code_hash, outcome) = pre_checks.select_next_some() => {
+				tracing::debug!(target: LOG_TARGET, ?code_hash, ?outcome, "PVF pre-check concluded");
+
+				state.in_flight.remove(&code_hash);
+				state.judgements.insert(code_hash, outcome);
+				state.vote(&mut ctx, &keystore, code_hash).await;
+			}
+		}
+	}
+}
+
+impl State {
+	async fn handle_leaf<Context>(
+		&mut self,
+		ctx: &mut Context,
+		keystore: &SyncCryptoStorePtr,
+		pre_checks: &mut FuturesUnordered<PendingPreCheck>,
+		leaf: Hash,
+	) -> Result<(), util::Error>
+	where
+		Context: SubsystemContext<Message = PvfCheckerMessage>,
+		Context: overseer::SubsystemContext<Message = PvfCheckerMessage>,
+		Context: overseer::SendsTo<CandidateValidationMessage> + overseer::SendsTo<RuntimeApiMessage>,
+	{
+		let api_version = util::request_from_runtime_declared(
+			leaf,
+			ctx,
+			RuntimeApiRequest::Version,
+		).await.await??;
+		if api_version < PVF_CHECKING_API_VERSION {
+			tracing::trace!(
+				target: LOG_TARGET,
+				?leaf,
+				%api_version,
+				"The runtime does not support the PVF pre-checking",
+			);
+
+			// Do not vote in the context of an older leaf either.
+			self.latest_leaf = None;
+			return Ok(());
+		}
+
+		let pending = util::request_from_runtime_declared(
+			leaf,
+			ctx,
+			RuntimeApiRequest::PvfsRequirePrecheck,
+		).await.await??;
+		let session_index = util::request_from_runtime_declared(
+			leaf,
+			ctx,
+			RuntimeApiRequest::SessionIndexForChild,
+		).await.await??;
+		let validators = util::request_from_runtime_declared(
+			leaf,
+			ctx,
+			RuntimeApiRequest::Validators,
+		).await.await??;
+		let signing_key = util::signing_key_and_index(&validators, keystore).await;
+
+		// Forget the PVFs that no longer await the votes and the votes of the past sessions.
+		self.judgements.retain(|code_hash, _| pending.contains(code_hash));
+		self.voted.retain(|(voted_session, _)| *voted_session >= session_index);
+
+		let is_validator = signing_key.is_some();
+		self.latest_leaf = Some(LeafContext {
+			relay_parent: leaf,
+			session_index,
+			signing_key,
+			pending: pending.clone(),
+		});
+
+		// Only the active validators are entitled to vote.
+		if !is_validator {
+			return Ok(());
+		}
+
+		for code_hash in pending {
+			if self.judgements.contains_key(&code_hash) {
+				self.vote(ctx, keystore, code_hash).await;
+			} else if self.in_flight.insert(code_hash) {
+				let (tx, rx) = oneshot::channel();
+				ctx.send_declared(CandidateValidationMessage::PreCheck(leaf, code_hash, tx)).await;
+
+				pre_checks.push(
+					rx.map(move |outcome| (code_hash, outcome.unwrap_or(PreCheckOutcome::Failed)))
+						.boxed(),
+				);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Submits our statement about the given PVF in the context of the most recent leaf, unless
+	/// it was already submitted in that session.
+	///
+	/// No statement is submitted for the PVFs which could not be judged.
+	async fn vote<Context>(
+		&mut self,
+		ctx: &mut Context,
+		keystore: &SyncCryptoStorePtr,
+		code_hash: ValidationCodeHash,
+	)
+	where
+		Context: SubsystemContext<Message = PvfCheckerMessage>,
+		Context: overseer::SubsystemContext<Message = PvfCheckerMessage>,
+		Context: overseer::SendsTo<CandidateValidationMessage> + overseer::SendsTo<RuntimeApiMessage>,
+	{
+		let leaf = match self.latest_leaf {
+			Some(ref leaf) if leaf.pending.contains(&code_hash) => leaf,
+			_ => return,
+		};
+		let (validator_key, validator_index) = match leaf.signing_key {
+			Some(ref key) => key.clone(),
+			None => return,
+		};
+		let accept = match self.judgements.get(&code_hash) {
+			Some(PreCheckOutcome::Valid) => true,
+			Some(PreCheckOutcome::Invalid) => false,
+			Some(PreCheckOutcome::Failed) | None => return,
+		};
+
+		let (relay_parent, session_index) = (leaf.relay_parent, leaf.session_index);
+		if !self.voted.insert((session_index, code_hash)) {
+			return;
+		}
+
+		let stmt = PvfCheckStatement {
+			accept,
+			subject: code_hash,
+			session_index,
+			validator_index,
+		};
+		let signature = match sign_statement(keystore, &validator_key, &stmt).await {
+			Some(signature) => signature,
+			None => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					?code_hash,
+					"Failed to sign a PVF pre-checking statement",
+				);
+				self.voted.remove(&(session_index, code_hash));
+				return;
+			}
+		};
+
+		tracing::debug!(
+			target: LOG_TARGET,
+			?code_hash,
+			%accept,
+			%session_index,
+			"Submitting a PVF pre-checking statement",
+		);
+
+		// The outcome of the submission is of no interest: the statement is either included or
+		// we vote again in the next session.
+		let _ = util::request_from_runtime_declared(
+			relay_parent,
+			ctx,
+			|tx| RuntimeApiRequest::SubmitPvfCheckStatement(stmt, signature, tx),
+		).await;
+	}
+}
+
+async fn sign_statement(
+	keystore: &SyncCryptoStorePtr,
+	validator_key: &ValidatorId,
+	stmt: &PvfCheckStatement,
+) -> Option<ValidatorSignature> {
+	let signature = CryptoStore::sign_with(
+		&**keystore,
+		ValidatorId::ID,
+		&validator_key.clone().into(),
+		&stmt.signing_payload(),
+	).await.ok()??;
+
+	signature.try_into().ok()
+}
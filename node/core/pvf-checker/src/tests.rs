@@ -0,0 +1,261 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use polkadot_node_subsystem::{
+	jaeger, ActivatedLeaf, LeafStatus,
+	messages::{AllMessages, RuntimeApiRequest},
+};
+use polkadot_node_subsystem_test_helpers as test_helpers;
+use polkadot_node_subsystem_util::TimeoutExt as _;
+use polkadot_primitives::v1::ValidationCode;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::traits::AppVerify;
+use test_helpers::mock::make_ferdie_keystore;
+
+use std::{sync::Arc, time::Duration};
+use assert_matches::assert_matches;
+use futures::{Future, executor, future};
+
+type VirtualOverseer = test_helpers::TestSubsystemContextHandle<PvfCheckerMessage>;
+
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+fn test_harness<T: Future<Output = VirtualOverseer>>(
+	test_fn: impl FnOnce(VirtualOverseer) -> T,
+) {
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
+
+	let subsystem = run(context, make_ferdie_keystore());
+	let test_fut = test_fn(virtual_overseer);
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(subsystem);
+
+	executor::block_on(future::join(async move {
+		let mut overseer = test_fut.await;
+		overseer
+			.send(FromOverseer::Signal(OverseerSignal::Conclude))
+			.timeout(TIMEOUT)
+			.await
+			.expect("Conclude send timeout");
+	}, subsystem)).1.unwrap();
+}
+
+fn validators() -> Vec<ValidatorId> {
+	vec![
+		Sr25519Keyring::Alice.public().into(),
+		Sr25519Keyring::Ferdie.public().into(),
+	]
+}
+
+fn code_hash() -> ValidationCodeHash {
+	ValidationCode(vec![1, 2, 3]).hash()
+}
+
+async fn activate_leaf(overseer: &mut VirtualOverseer, leaf: Hash) {
+	let leaf = ActivatedLeaf {
+		hash: leaf,
+		number: 1,
+		status: LeafStatus::Fresh,
+		span: Arc::new(jaeger::Span::Disabled),
+	};
+	overseer
+		.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(leaf))))
+		.timeout(TIMEOUT)
+		.await
+		.expect("signal send timeout");
+}
+
+async fn overseer_recv(overseer: &mut VirtualOverseer) -> AllMessages {
+	overseer
+		.recv()
+		.timeout(TIMEOUT)
+		.await
+		.expect("msg recv timeout")
+}
+
+async fn answer_version(overseer: &mut VirtualOverseer, leaf: Hash, version: u32) {
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::Version(tx),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			let _ = tx.send(Ok(version));
+		}
+	);
+}
+
+/// Answers the runtime requests issued for every activated leaf.
+async fn answer_leaf_requests(
+	overseer: &mut VirtualOverseer,
+	leaf: Hash,
+	pending: Vec<ValidationCodeHash>,
+	session_index: SessionIndex,
+) {
+	answer_version(overseer, leaf, PVF_CHECKING_API_VERSION).await;
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::PvfsRequirePrecheck(tx),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			let _ = tx.send(Ok(pending));
+		}
+	);
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::SessionIndexForChild(tx),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			let _ = tx.send(Ok(session_index));
+		}
+	);
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::Validators(tx),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			let _ = tx.send(Ok(validators()));
+		}
+	);
+}
+
+async fn answer_pre_check(overseer: &mut VirtualOverseer, leaf: Hash, outcome: PreCheckOutcome) {
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::CandidateValidation(CandidateValidationMessage::PreCheck(
+			relay_parent,
+			subject,
+			tx,
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			assert_eq!(subject, code_hash());
+			let _ = tx.send(outcome);
+		}
+	);
+}
+
+async fn expect_statement(
+	overseer: &mut VirtualOverseer,
+	leaf: Hash,
+	accept: bool,
+	session_index: SessionIndex,
+) {
+	assert_matches!(
+		overseer_recv(overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::SubmitPvfCheckStatement(stmt, signature, _),
+		)) => {
+			assert_eq!(relay_parent, leaf);
+			assert_eq!(stmt, PvfCheckStatement {
+				accept,
+				subject: code_hash(),
+				session_index,
+				validator_index: ValidatorIndex(1),
+			});
+			assert!(signature.verify(&stmt.signing_payload()[..], &validators()[1]));
+		}
+	);
+}
+
+async fn expect_nothing(overseer: &mut VirtualOverseer) {
+	assert!(overseer.recv().timeout(TIMEOUT).await.is_none());
+}
+
+#[test]
+fn judges_once_and_votes_once_per_session() {
+	test_harness(|mut overseer| async move {
+		let (leaf_a, leaf_b, leaf_c) = (Hash::repeat_byte(1), Hash::repeat_byte(2), Hash::repeat_byte(3));
+
+		activate_leaf(&mut overseer, leaf_a).await;
+		answer_leaf_requests(&mut overseer, leaf_a, vec![code_hash()], 1).await;
+		answer_pre_check(&mut overseer, leaf_a, PreCheckOutcome::Valid).await;
+		expect_statement(&mut overseer, leaf_a, true, 1).await;
+
+		// Same session: the statement was submitted already.
+		activate_leaf(&mut overseer, leaf_b).await;
+		answer_leaf_requests(&mut overseer, leaf_b, vec![code_hash()], 1).await;
+		expect_nothing(&mut overseer).await;
+
+		// New session: the vote was reset, so vote again without pre-checking again.
+		activate_leaf(&mut overseer, leaf_c).await;
+		answer_leaf_requests(&mut overseer, leaf_c, vec![code_hash()], 2).await;
+		expect_statement(&mut overseer, leaf_c, true, 2).await;
+
+		overseer
+	});
+}
+
+#[test]
+fn votes_against_invalid_pvf() {
+	test_harness(|mut overseer| async move {
+		let leaf = Hash::repeat_byte(1);
+
+		activate_leaf(&mut overseer, leaf).await;
+		answer_leaf_requests(&mut overseer, leaf, vec![code_hash()], 1).await;
+		answer_pre_check(&mut overseer, leaf, PreCheckOutcome::Invalid).await;
+		expect_statement(&mut overseer, leaf, false, 1).await;
+
+		overseer
+	});
+}
+
+#[test]
+fn abstains_if_pre_check_failed() {
+	test_harness(|mut overseer| async move {
+		let (leaf_a, leaf_b) = (Hash::repeat_byte(1), Hash::repeat_byte(2));
+
+		activate_leaf(&mut overseer, leaf_a).await;
+		answer_leaf_requests(&mut overseer, leaf_a, vec![code_hash()], 1).await;
+		answer_pre_check(&mut overseer, leaf_a, PreCheckOutcome::Failed).await;
+		expect_nothing(&mut overseer).await;
+
+		activate_leaf(&mut overseer, leaf_b).await;
+		answer_leaf_requests(&mut overseer, leaf_b, vec![code_hash()], 2).await;
+		expect_nothing(&mut overseer).await;
+
+		overseer
+	});
+}
+
+#[test]
+fn ignores_leaves_without_pvf_checking_support() {
+	test_harness(|mut overseer| async move {
+		let (leaf_a, leaf_b) = (Hash::repeat_byte(1), Hash::repeat_byte(2));
+
+		activate_leaf(&mut overseer, leaf_a).await;
+		answer_version(&mut overseer, leaf_a, PVF_CHECKING_API_VERSION - 1).await;
+		expect_nothing(&mut overseer).await;
+
+		// The runtime was upgraded.
+		activate_leaf(&mut overseer, leaf_b).await;
+		answer_leaf_requests(&mut overseer, leaf_b, vec![code_hash()], 1).await;
+		answer_pre_check(&mut overseer, leaf_b, PreCheckOutcome::Valid).await;
+		expect_statement(&mut overseer, leaf_b, true, 1).await;
+
+		overseer
+	});
+}
@@ -14,6 +14,28 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+/// An error raised while preparing a PVF. Reported to the requesters of [`crate::ValidationHost::precheck_pvf`].
+#[derive(Debug, Clone)]
+pub enum PrepareError {
+	/// The PVF didn't pass the prevalidation stage. The string contains the error message.
+	Prevalidation(String),
+	/// The PVF failed to compile. The string contains the error message.
+	Preparation(String),
+	/// The worker assigned to prepare the PVF didn't finish in time or died.
+	DidntMakeIt,
+	/// The preparation result could not be obtained because of a local issue, e.g. the artifact
+	/// file could not be read back.
+	Internal(String),
+}
+
+impl PrepareError {
+	/// Returns `true` if the error is attributable to the PVF itself, rather than to the local
+	/// circumstances of this node.
+	pub fn is_deterministic(&self) -> bool {
+		matches!(self, PrepareError::Prevalidation(_) | PrepareError::Preparation(_))
+	}
+}
+
 /// A error raised during validation of the candidate.
 #[derive(Debug, Clone)]
 pub enum ValidationError {
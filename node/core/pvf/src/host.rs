@@ -21,8 +21,8 @@
 //! [`ValidationHost`], that allows communication with that event-loop.
 
 use crate::{
-	Priority, Pvf, ValidationError,
-	artifacts::{Artifacts, ArtifactState, ArtifactId},
+	Priority, Pvf, ValidationError, PrepareError,
+	artifacts::{Artifact, Artifacts, ArtifactState, ArtifactId},
 	execute, prepare,
 };
 use std::{
@@ -42,6 +42,9 @@ use futures::{
 /// An alias to not spell the type for the oneshot sender for the PVF execution result.
 pub(crate) type ResultSender = oneshot::Sender<Result<ValidationResult, ValidationError>>;
 
+/// An alias to not spell the type for the oneshot sender for the PVF pre-check result.
+pub type PrecheckResultSender = oneshot::Sender<Result<(), PrepareError>>;
+
 /// A handle to the async process serving the validation host requests.
 #[derive(Clone)]
 pub struct ValidationHost {
@@ -86,6 +89,23 @@ impl ValidationHost {
 			.await
 			.map_err(|_| "the inner loop hung up".to_string())
 	}
+
+	/// Requests the validation host to prepare the given PVF and report whether the preparation
+	/// succeeded. The result will be sent to the provided result sender.
+	///
+	/// If the PVF was already prepared, the cached result is reported.
+	///
+	/// Returns an error if the request cannot be sent to the validation host, i.e. if it shut down.
+	pub async fn precheck_pvf(
+		&mut self,
+		pvf: Pvf,
+		result_tx: PrecheckResultSender,
+	) -> Result<(), String> {
+		self.to_host_tx
+			.send(ToHost::PrecheckPvf { pvf, result_tx })
+			.await
+			.map_err(|_| "the inner loop hung up".to_string())
+	}
 }
 
 enum ToHost {
@@ -98,6 +118,10 @@ enum ToHost {
 	HeadsUp {
 		active_pvfs: Vec<Pvf>,
 	},
+	PrecheckPvf {
+		pvf: Pvf,
+		result_tx: PrecheckResultSender,
+	},
 }
 
 /// Configuration for the validation host.
@@ -177,6 +201,9 @@ pub fn start(config: Config) -> (ValidationHost, impl Future<Output = ()>) {
 	let (to_sweeper_tx, to_sweeper_rx) = mpsc::channel(100);
 	let run_sweeper = sweeper_task(to_sweeper_rx);
 
+	let (to_precheck_reader_tx, to_precheck_reader_rx) = mpsc::channel(100);
+	let run_precheck_reader = precheck_reader_task(to_precheck_reader_rx);
+
 	let run = async move {
 		let artifacts = Artifacts::new(&config.cache_path).await;
 
@@ -184,7 +211,8 @@ pub fn start(config: Config) -> (ValidationHost, impl Future<Output = ()>) {
 			run_prepare_queue,
 			run_prepare_pool,
 			run_execute_queue,
-			run_sweeper
+			run_sweeper,
+			run_precheck_reader
 		);
 
 		run(
@@ -198,12 +226,15 @@ pub fn start(config: Config) -> (ValidationHost, impl Future<Output = ()>) {
 				from_prepare_queue_rx,
 				to_execute_queue_tx,
 				to_sweeper_tx,
+				to_precheck_reader_tx,
 				awaiting_prepare: AwaitingPrepare::default(),
+				awaiting_precheck: AwaitingPrecheck::default(),
 			},
 			run_prepare_pool,
 			run_prepare_queue,
 			run_execute_queue,
 			run_sweeper,
+			run_precheck_reader,
 		)
 		.await
 	};
@@ -237,6 +268,29 @@ impl AwaitingPrepare {
 	}
 }
 
+/// A mapping from an artifact ID which is in preparation state to the list of pre-check requests
+/// that should be answered once the artifact's preparation is finished.
+#[derive(Default)]
+struct AwaitingPrecheck(HashMap<ArtifactId, Vec<PrecheckResultSender>>);
+
+impl AwaitingPrecheck {
+	fn add(&mut self, artifact_id: ArtifactId, result_tx: PrecheckResultSender) {
+		self.0.entry(artifact_id).or_default().push(result_tx);
+	}
+
+	fn take(&mut self, artifact_id: &ArtifactId) -> Vec<PrecheckResultSender> {
+		self.0.remove(artifact_id).unwrap_or_default()
+	}
+}
+
+/// A request to read back the outcome of the preparation from the artifact at the given path and
+/// to report it to the given pre-check requesters.
+#[derive(Debug)]
+struct PrecheckRead {
+	artifact_path: PathBuf,
+	result_txs: Vec<PrecheckResultSender>,
+}
+
 struct Inner {
 	cache_path: PathBuf,
 	cleanup_pulse_interval: Duration,
@@ -250,8 +304,10 @@ struct Inner {
 
 	to_execute_queue_tx: mpsc::Sender<execute::ToQueue>,
 	to_sweeper_tx: mpsc::Sender<PathBuf>,
+	to_precheck_reader_tx: mpsc::Sender<PrecheckRead>,
 
 	awaiting_prepare: AwaitingPrepare,
+	awaiting_precheck: AwaitingPrecheck,
 }
 
 #[derive(Debug)]
@@ -268,12 +324,15 @@ async fn run(
 		mut to_prepare_queue_tx,
 		mut to_execute_queue_tx,
 		mut to_sweeper_tx,
+		mut to_precheck_reader_tx,
 		mut awaiting_prepare,
+		mut awaiting_precheck,
 	}: Inner,
 	prepare_pool: impl Future<Output = ()> + Unpin,
 	prepare_queue: impl Future<Output = ()> + Unpin,
 	execute_queue: impl Future<Output = ()> + Unpin,
 	sweeper: impl Future<Output = ()> + Unpin,
+	precheck_reader: impl Future<Output = ()> + Unpin,
 ) {
 	macro_rules! break_if_fatal {
 		($expr:expr) => {
@@ -295,6 +354,7 @@ async fn run(
 	let mut prepare_pool = prepare_pool.fuse();
 	let mut execute_queue = execute_queue.fuse();
 	let mut sweeper = sweeper.fuse();
+	let mut precheck_reader = precheck_reader.fuse();
 
 	loop {
 		// biased to make it behave deterministically for tests.
@@ -315,6 +375,10 @@ async fn run(
 				never!("sweeper: long-running task never concludes; qed");
 				break;
 			},
+			_ = precheck_reader => {
+				never!("precheck_reader: long-running task never concludes; qed");
+				break;
+			},
 			() = cleanup_pulse.select_next_some() => {
 				// `select_next_some` because we don't expect this to fail, but if it does, we
 				// still don't fail. The tradeoff is that the compiled cache will start growing
@@ -336,7 +400,9 @@ async fn run(
 					&mut artifacts,
 					&mut to_prepare_queue_tx,
 					&mut to_execute_queue_tx,
+					&mut to_precheck_reader_tx,
 					&mut awaiting_prepare,
+					&mut awaiting_precheck,
 					to_host,
 				)
 				.await);
@@ -358,7 +424,9 @@ async fn run(
 					&cache_path,
 					&mut artifacts,
 					&mut to_execute_queue_tx,
+					&mut to_precheck_reader_tx,
 					&mut awaiting_prepare,
+					&mut awaiting_precheck,
 					artifact_id,
 				).await);
 			},
@@ -371,7 +439,9 @@ async fn handle_to_host(
 	artifacts: &mut Artifacts,
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
 	execute_queue: &mut mpsc::Sender<execute::ToQueue>,
+	precheck_reader: &mut mpsc::Sender<PrecheckRead>,
 	awaiting_prepare: &mut AwaitingPrepare,
+	awaiting_precheck: &mut AwaitingPrecheck,
 	to_host: ToHost,
 ) -> Result<(), Fatal> {
 	match to_host {
@@ -397,6 +467,18 @@ async fn handle_to_host(
 		ToHost::HeadsUp { active_pvfs } => {
 			handle_heads_up(artifacts, prepare_queue, active_pvfs).await?;
 		}
+		ToHost::PrecheckPvf { pvf, result_tx } => {
+			handle_precheck_pvf(
+				cache_path,
+				artifacts,
+				prepare_queue,
+				precheck_reader,
+				awaiting_precheck,
+				pvf,
+				result_tx,
+			)
+			.await?;
+		}
 	}
 
 	Ok(())
@@ -496,11 +578,79 @@ async fn handle_heads_up(
 	Ok(())
 }
 
+async fn handle_precheck_pvf(
+	cache_path: &Path,
+	artifacts: &mut Artifacts,
+	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
+	precheck_reader: &mut mpsc::Sender<PrecheckRead>,
+	awaiting_precheck: &mut AwaitingPrecheck,
+	pvf: Pvf,
+	result_tx: PrecheckResultSender,
+) -> Result<(), Fatal> {
+	let artifact_id = pvf.as_artifact_id();
+
+	if let Some(state) = artifacts.artifact_state_mut(&artifact_id) {
+		match state {
+			ArtifactState::Prepared {
+				ref mut last_time_needed,
+			} => {
+				*last_time_needed = SystemTime::now();
+
+				send_precheck_read(
+					precheck_reader,
+					PrecheckRead {
+						artifact_path: artifact_id.path(cache_path),
+						result_txs: vec![result_tx],
+					},
+				)
+				.await?;
+			}
+			ArtifactState::Preparing => {
+				awaiting_precheck.add(artifact_id, result_tx);
+			}
+		}
+	} else {
+		// The artifact is unknown: register it and put a background job into the prepare queue.
+		// Pre-checking is not on the critical path so there is no reason to compete with the
+		// preparation of the PVFs needed for validation.
+		artifacts.insert_preparing(artifact_id.clone());
+		send_prepare(
+			prepare_queue,
+			prepare::ToQueue::Enqueue {
+				priority: Priority::Background,
+				pvf,
+			},
+		)
+		.await?;
+
+		awaiting_precheck.add(artifact_id, result_tx);
+	}
+
+	Ok(())
+}
+
+/// Reads back the artifact at the given path and extracts the outcome of the preparation.
+async fn read_prepare_result(artifact_path: &Path) -> Result<(), PrepareError> {
+	let artifact_bytes = async_std::fs::read(artifact_path)
+		.await
+		.map_err(|e| PrepareError::Internal(format!("artifact read: {:?}", e)))?;
+
+	match Artifact::deserialize(&artifact_bytes) {
+		Err(e) => Err(PrepareError::Internal(format!("artifact deserialization: {}", e))),
+		Ok(Artifact::PrevalidationErr(msg)) => Err(PrepareError::Prevalidation(msg)),
+		Ok(Artifact::PreparationErr(msg)) => Err(PrepareError::Preparation(msg)),
+		Ok(Artifact::DidntMakeIt) => Err(PrepareError::DidntMakeIt),
+		Ok(Artifact::Compiled { .. }) => Ok(()),
+	}
+}
+
 async fn handle_prepare_done(
 	cache_path: &Path,
 	artifacts: &mut Artifacts,
 	execute_queue: &mut mpsc::Sender<execute::ToQueue>,
+	precheck_reader: &mut mpsc::Sender<PrecheckRead>,
 	awaiting_prepare: &mut AwaitingPrepare,
+	awaiting_precheck: &mut AwaitingPrecheck,
 	artifact_id: ArtifactId,
 ) -> Result<(), Fatal> {
 	// Make some sanity checks and extract the current state.
@@ -548,6 +698,19 @@ async fn handle_prepare_done(
 		.await?;
 	}
 
+	// Report the outcome of the preparation to the pre-check requesters, if any.
+	let pending_prechecks = awaiting_precheck.take(&artifact_id);
+	if pending_prechecks.iter().any(|result_tx| !result_tx.is_canceled()) {
+		send_precheck_read(
+			precheck_reader,
+			PrecheckRead {
+				artifact_path,
+				result_txs: pending_prechecks,
+			},
+		)
+		.await?;
+	}
+
 	// Now consider the artifact prepared.
 	*state = ArtifactState::Prepared {
 		last_time_needed: SystemTime::now(),
@@ -570,6 +733,13 @@ async fn send_execute(
 	execute_queue.send(to_queue).await.map_err(|_| Fatal)
 }
 
+async fn send_precheck_read(
+	precheck_reader: &mut mpsc::Sender<PrecheckRead>,
+	precheck_read: PrecheckRead,
+) -> Result<(), Fatal> {
+	precheck_reader.send(precheck_read).await.map_err(|_| Fatal)
+}
+
 async fn handle_cleanup_pulse(
 	cache_path: &Path,
 	sweeper_tx: &mut mpsc::Sender<PathBuf>,
@@ -597,6 +767,22 @@ async fn sweeper_task(mut sweeper_rx: mpsc::Receiver<PathBuf>) {
 	}
 }
 
+/// Reads back the artifacts of the finished preparations off the host's event loop, since an
+/// artifact can be large.
+async fn precheck_reader_task(mut precheck_reader_rx: mpsc::Receiver<PrecheckRead>) {
+	loop {
+		match precheck_reader_rx.next().await {
+			None => break,
+			Some(PrecheckRead { artifact_path, result_txs }) => {
+				let result = read_prepare_result(&artifact_path).await;
+				for result_tx in result_txs {
+					let _ = result_tx.send(result.clone());
+				}
+			}
+		}
+	}
+}
+
 /// A stream that yields a pulse continuously at a given interval.
 fn pulse_every(interval: std::time::Duration) -> impl futures::Stream<Item = ()> {
 	futures::stream::unfold(interval, {
@@ -687,6 +873,7 @@ mod tests {
 			let (from_prepare_queue_tx, from_prepare_queue_rx) = mpsc::unbounded();
 			let (to_execute_queue_tx, to_execute_queue_rx) = mpsc::channel(10);
 			let (to_sweeper_tx, to_sweeper_rx) = mpsc::channel(10);
+			let (to_precheck_reader_tx, to_precheck_reader_rx) = mpsc::channel(10);
 
 			let mk_dummy_loop = || std::future::pending().boxed();
 
@@ -701,12 +888,15 @@ mod tests {
 					from_prepare_queue_rx,
 					to_execute_queue_tx,
 					to_sweeper_tx,
+					to_precheck_reader_tx,
 					awaiting_prepare: AwaitingPrepare::default(),
+					awaiting_precheck: AwaitingPrecheck::default(),
 				},
 				mk_dummy_loop(),
 				mk_dummy_loop(),
 				mk_dummy_loop(),
 				mk_dummy_loop(),
+				precheck_reader_task(to_precheck_reader_rx).boxed(),
 			)
 			.boxed();
 
@@ -1037,4 +1227,46 @@ mod tests {
 
 		test.poll_ensure_to_execute_queue_is_empty().await;
 	}
+
+	#[async_std::test]
+	async fn precheck_pvf_requests() {
+		let mut test = Builder::default().build();
+		let mut host = test.host_handle();
+
+		// Use a discriminator not shared with other tests since the artifact is written into the
+		// shared temporary directory.
+		let (result_tx, mut result_rx) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(1001), result_tx).await.unwrap();
+
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { priority: Priority::Background, .. }
+		);
+
+		async_std::fs::write(
+			artifact_path(1001),
+			Artifact::PreparationErr("boom".to_string()).serialize(),
+		)
+		.await
+		.unwrap();
+
+		test.from_prepare_queue_tx
+			.send(prepare::FromQueue::Prepared(artifact_id(1001)))
+			.await
+			.unwrap();
+
+		let result = run_until(&mut test.run, (&mut result_rx).boxed()).await;
+		assert_matches!(result, Ok(Err(PrepareError::Preparation(msg))) if msg == "boom");
+
+		// The artifact is prepared by now, so the outcome is reported straight away without
+		// enqueueing anything.
+		let (result_tx, result_rx) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(1001), result_tx).await.unwrap();
+
+		let result = run_until(&mut test.run, result_rx.boxed()).await;
+		assert_matches!(result, Ok(Err(PrepareError::Preparation(_))));
+		assert!(test.to_prepare_queue_rx.next().now_or_never().is_none());
+
+		let _ = async_std::fs::remove_file(artifact_path(1001)).await;
+	}
 }
@@ -30,6 +30,10 @@
 //! (b) Heads up. This request allows to signal that the given PVF may be needed soon and that it
 //!     should be prepared for execution.
 //!
+//! (c) Pre-check. This request prepares the given PVF, as in heads up, but also reports back
+//!     whether the preparation succeeded. This is used to vote on code upgrades before they
+//!     are enacted.
+//!
 //! The preparation results are cached for some time after they either used or was signaled in heads up.
 //! All requests that depends on preparation of the same PVF are bundled together and will be executed
 //! as soon as the artifact is prepared.
@@ -91,11 +95,11 @@ pub mod testing;
 #[doc(hidden)]
 pub use sp_tracing;
 
-pub use error::{ValidationError, InvalidCandidate};
+pub use error::{ValidationError, InvalidCandidate, PrepareError};
 pub use priority::Priority;
 pub use pvf::Pvf;
 
-pub use host::{start, Config, ValidationHost};
+pub use host::{start, Config, PrecheckResultSender, ValidationHost};
 
 pub use execute::worker_entrypoint as execute_worker_entrypoint;
 pub use prepare::worker_entrypoint as prepare_worker_entrypoint;
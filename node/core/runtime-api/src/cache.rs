@@ -14,19 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::btree_map::BTreeMap;
+//! Caches of the runtime API request results.
+//!
+//! Every request kind has its own cache, bounded by the resident size of the cached results.
+//! The budgets can be configured per request kind in the form `<request>:<bytes>`, where the
+//! request is one of [`CacheConfig::REQUESTS`], e.g. `validation_code:20971520`.
 
-use memory_lru::{MemoryLruCache, ResidentSize};
+use std::{collections::btree_map::BTreeMap, hash::Hash as StdHash, str::FromStr};
+
+use lru::LruCache;
+use memory_lru::ResidentSize;
 use parity_util_mem::{MallocSizeOf, MallocSizeOfExt};
-use sp_consensus_babe::Epoch;
+use sp_consensus_babe::{AuthorityId as BabeAuthorityId, BabeAuthorityWeight, Epoch};
+use thiserror::Error;
 
 use polkadot_primitives::v1::{
-	AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent,
-	CommittedCandidateReceipt, CoreState, GroupRotationInfo, Hash, Id as ParaId,
-	InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData,
-	SessionIndex, SessionInfo, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+	AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent, CandidateReceipt,
+	CommittedCandidateReceipt, CoreState, DisputeStatement, DisputeStatementSet,
+	GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
+	OccupiedCoreAssumption, PersistedValidationData, ScrapedOnChainVotes, SessionIndex,
+	SessionInfo, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+	ValidatorSignature, ValidityAttestation,
 };
 
+use crate::Metrics;
+
 const AUTHORITIES_CACHE_SIZE: usize = 128 * 1024;
 const VALIDATORS_CACHE_SIZE: usize = 64 * 1024;
 const VALIDATOR_GROUPS_CACHE_SIZE: usize = 64 * 1024;
@@ -41,20 +53,131 @@ const SESSION_INFO_CACHE_SIZE: usize = 64 * 1024;
 const DMQ_CONTENTS_CACHE_SIZE: usize = 64 * 1024;
 const INBOUND_HRMP_CHANNELS_CACHE_SIZE: usize = 64 * 1024;
 const CURRENT_BABE_EPOCH_CACHE_SIZE: usize = 64 * 1024;
+const ON_CHAIN_VOTES_CACHE_SIZE: usize = 64 * 1024;
+
+/// Errors when parsing a cache size.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CacheSizeError {
+	/// The size did not consist of two `:` separated fields.
+	#[error("Malformed cache size {0:?}, expected `<request>:<bytes>`")]
+	Malformed(String),
+
+	/// The request is not a cached runtime API request.
+	#[error("Unknown runtime API request {0:?}")]
+	UnknownRequest(String),
+
+	/// The size was not a non-negative integer.
+	#[error("Invalid number of bytes {0:?}")]
+	InvalidNumber(String),
+}
 
-struct ResidentSizeOf<T>(T);
+/// The cache budget for a specific request kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheSizeEntry {
+	/// The request, one of [`CacheConfig::REQUESTS`].
+	pub request: &'static str,
+	/// The maximum resident size of the cached results, in bytes.
+	pub bytes: usize,
+}
 
-impl<T: MallocSizeOf> ResidentSize for ResidentSizeOf<T> {
-	fn resident_size(&self) -> usize {
-		std::mem::size_of::<Self>() + self.0.malloc_size_of()
+impl FromStr for CacheSizeEntry {
+	type Err = CacheSizeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let fields: Vec<_> = s.trim().split(':').map(str::trim).collect();
+		let (request, bytes) = match fields[..] {
+			[request, bytes] => (request, bytes),
+			_ => return Err(CacheSizeError::Malformed(s.into())),
+		};
+
+		let request = CacheConfig::REQUESTS
+			.iter()
+			.find(|r| **r == request)
+			.ok_or_else(|| CacheSizeError::UnknownRequest(request.into()))?;
+		let bytes = usize::from_str(bytes).map_err(|_| CacheSizeError::InvalidNumber(bytes.into()))?;
+
+		Ok(CacheSizeEntry { request, bytes })
 	}
 }
 
-struct DoesNotAllocate<T>(T);
+/// Defines [`CacheConfig`] with a budget per cached request kind, named after the request.
+macro_rules! cache_config {
+	($(
+		$(#[$attr:meta])*
+		$request:ident: $default:expr,
+	)*) => {
+		/// Budgets of the runtime API result caches, in bytes of resident size per request kind.
+		#[derive(Debug, Clone, PartialEq, Eq)]
+		pub struct CacheConfig {
+			$(
+				$(#[$attr])*
+				pub $request: usize,
+			)*
+		}
+
+		impl Default for CacheConfig {
+			fn default() -> Self {
+				Self {
+					$($request: $default,)*
+				}
+			}
+		}
 
-impl<T> ResidentSize for DoesNotAllocate<T> {
+		impl CacheConfig {
+			/// The names of the cached requests, as used in cache sizes and metrics.
+			pub const REQUESTS: &'static [&'static str] = &[$(stringify!($request),)*];
+
+			/// Set the budget of a request kind, replacing the previous one.
+			pub fn insert(&mut self, entry: CacheSizeEntry) {
+				match entry.request {
+					$(stringify!($request) => self.$request = entry.bytes,)*
+					_ => {}
+				}
+			}
+		}
+	}
+}
+
+cache_config! {
+	/// Budget of the `Authorities` results.
+	authorities: AUTHORITIES_CACHE_SIZE,
+	/// Budget of the `Validators` results.
+	validators: VALIDATORS_CACHE_SIZE,
+	/// Budget of the `ValidatorGroups` results.
+	validator_groups: VALIDATOR_GROUPS_CACHE_SIZE,
+	/// Budget of the `AvailabilityCores` results.
+	availability_cores: AVAILABILITY_CORES_CACHE_SIZE,
+	/// Budget of the `PersistedValidationData` results.
+	persisted_validation_data: PERSISTED_VALIDATION_DATA_CACHE_SIZE,
+	/// Budget of the `CheckValidationOutputs` results.
+	check_validation_outputs: CHECK_VALIDATION_OUTPUTS_CACHE_SIZE,
+	/// Budget of the `SessionIndexForChild` results.
+	session_index_for_child: SESSION_INDEX_FOR_CHILD_CACHE_SIZE,
+	/// Budget of the `ValidationCode` results.
+	validation_code: VALIDATION_CODE_CACHE_SIZE,
+	/// Budget of the `ValidationCodeByHash` results.
+	validation_code_by_hash: VALIDATION_CODE_CACHE_SIZE,
+	/// Budget of the `CandidatePendingAvailability` results.
+	candidate_pending_availability: CANDIDATE_PENDING_AVAILABILITY_CACHE_SIZE,
+	/// Budget of the `CandidateEvents` results.
+	candidate_events: CANDIDATE_EVENTS_CACHE_SIZE,
+	/// Budget of the `SessionInfo` results.
+	session_info: SESSION_INFO_CACHE_SIZE,
+	/// Budget of the `DmqContents` results.
+	dmq_contents: DMQ_CONTENTS_CACHE_SIZE,
+	/// Budget of the `InboundHrmpChannelsContents` results.
+	inbound_hrmp_channels_contents: INBOUND_HRMP_CHANNELS_CACHE_SIZE,
+	/// Budget of the `CurrentBabeEpoch` results.
+	current_babe_epoch: CURRENT_BABE_EPOCH_CACHE_SIZE,
+	/// Budget of the `FetchOnChainVotes` results.
+	on_chain_votes: ON_CHAIN_VOTES_CACHE_SIZE,
+}
+
+struct ResidentSizeOf<T>(T);
+
+impl<T: MallocSizeOf> ResidentSize for ResidentSizeOf<T> {
 	fn resident_size(&self) -> usize {
-		std::mem::size_of::<Self>()
+		std::mem::size_of::<Self>() + self.0.malloc_size_of()
 	}
 }
 
@@ -68,167 +191,270 @@ impl<T> ResidentSize for VecOfDoesNotAllocate<T> {
 	}
 }
 
+// `Epoch` does not implement `MallocSizeOf` either,
+// its only allocation is the list of authorities.
+struct EpochResidentSize(Epoch);
+
+impl ResidentSize for EpochResidentSize {
+	fn resident_size(&self) -> usize {
+		std::mem::size_of::<Self>() +
+			std::mem::size_of::<(BabeAuthorityId, BabeAuthorityWeight)>() * self.0.authorities.capacity()
+	}
+}
+
+// Neither do the dispute statements of `ScrapedOnChainVotes`,
+// which however only allocate the lists of votes and statements.
+struct OnChainVotesResidentSize(Option<ScrapedOnChainVotes>);
+
+impl ResidentSize for OnChainVotesResidentSize {
+	fn resident_size(&self) -> usize {
+		let votes = match self.0 {
+			Some(ref votes) => votes,
+			None => return std::mem::size_of::<Self>(),
+		};
+
+		let backing: usize = votes.backing_validators_per_candidate.iter()
+			.map(|(receipt, attestations)| receipt.malloc_size_of() +
+				std::mem::size_of::<(ValidatorIndex, ValidityAttestation)>() * attestations.capacity()
+			)
+			.sum();
+		let disputes: usize = votes.disputes.iter()
+			.map(|set| std::mem::size_of::<(DisputeStatement, ValidatorIndex, ValidatorSignature)>() *
+				set.statements.capacity()
+			)
+			.sum();
+
+		std::mem::size_of::<Self>() +
+			std::mem::size_of::<(CandidateReceipt, Vec<(ValidatorIndex, ValidityAttestation)>)>() *
+				votes.backing_validators_per_candidate.capacity() +
+			std::mem::size_of::<DisputeStatementSet>() * votes.disputes.capacity() +
+			backing +
+			disputes
+	}
+}
+
+/// An LRU cache bounded by the resident size of its values, reporting its hits, misses,
+/// evictions and resident size.
+struct ObservedCache<K, V> {
+	request: &'static str,
+	inner: LruCache<K, V>,
+	resident_size: usize,
+	max_size: usize,
+}
+
+impl<K: Eq + StdHash, V: ResidentSize> ObservedCache<K, V> {
+	fn new(request: &'static str, max_size: usize) -> Self {
+		ObservedCache { request, inner: LruCache::unbounded(), resident_size: 0, max_size }
+	}
+
+	fn get(&mut self, key: &K, metrics: &Metrics) -> Option<&V> {
+		let value = self.inner.get(key);
+		metrics.on_cache_lookup(self.request, value.is_some());
+		value
+	}
+
+	fn insert(&mut self, key: K, value: V, metrics: &Metrics) {
+		self.resident_size += value.resident_size();
+		if let Some(replaced) = self.inner.put(key, value) {
+			self.resident_size -= replaced.resident_size();
+		}
+
+		let mut evictions = 0;
+		while self.resident_size > self.max_size {
+			match self.inner.pop_lru() {
+				Some((_, evicted)) => {
+					self.resident_size -= evicted.resident_size();
+					evictions += 1;
+				}
+				None => break,
+			}
+		}
+
+		metrics.on_cache_insert(self.request, evictions, self.resident_size);
+	}
+}
+
 pub(crate) struct RequestResultCache {
-	authorities: MemoryLruCache<Hash, VecOfDoesNotAllocate<AuthorityDiscoveryId>>,
-	validators: MemoryLruCache<Hash, ResidentSizeOf<Vec<ValidatorId>>>,
-	validator_groups: MemoryLruCache<Hash, ResidentSizeOf<(Vec<Vec<ValidatorIndex>>, GroupRotationInfo)>>,
-	availability_cores: MemoryLruCache<Hash, ResidentSizeOf<Vec<CoreState>>>,
-	persisted_validation_data: MemoryLruCache<(Hash, ParaId, OccupiedCoreAssumption), ResidentSizeOf<Option<PersistedValidationData>>>,
-	check_validation_outputs: MemoryLruCache<(Hash, ParaId, CandidateCommitments), ResidentSizeOf<bool>>,
-	session_index_for_child: MemoryLruCache<Hash, ResidentSizeOf<SessionIndex>>,
-	validation_code: MemoryLruCache<(Hash, ParaId, OccupiedCoreAssumption), ResidentSizeOf<Option<ValidationCode>>>,
-	validation_code_by_hash: MemoryLruCache<ValidationCodeHash, ResidentSizeOf<Option<ValidationCode>>>,
-	candidate_pending_availability: MemoryLruCache<(Hash, ParaId), ResidentSizeOf<Option<CommittedCandidateReceipt>>>,
-	candidate_events: MemoryLruCache<Hash, ResidentSizeOf<Vec<CandidateEvent>>>,
-	session_info: MemoryLruCache<SessionIndex, ResidentSizeOf<Option<SessionInfo>>>,
-	dmq_contents: MemoryLruCache<(Hash, ParaId), ResidentSizeOf<Vec<InboundDownwardMessage<BlockNumber>>>>,
-	inbound_hrmp_channels_contents: MemoryLruCache<(Hash, ParaId), ResidentSizeOf<BTreeMap<ParaId, Vec<InboundHrmpMessage<BlockNumber>>>>>,
-	current_babe_epoch: MemoryLruCache<Hash, DoesNotAllocate<Epoch>>,
+	metrics: Metrics,
+	authorities: ObservedCache<Hash, VecOfDoesNotAllocate<AuthorityDiscoveryId>>,
+	validators: ObservedCache<Hash, ResidentSizeOf<Vec<ValidatorId>>>,
+	validator_groups: ObservedCache<Hash, ResidentSizeOf<(Vec<Vec<ValidatorIndex>>, GroupRotationInfo)>>,
+	availability_cores: ObservedCache<Hash, ResidentSizeOf<Vec<CoreState>>>,
+	persisted_validation_data: ObservedCache<(Hash, ParaId, OccupiedCoreAssumption), ResidentSizeOf<Option<PersistedValidationData>>>,
+	check_validation_outputs: ObservedCache<(Hash, ParaId, CandidateCommitments), ResidentSizeOf<bool>>,
+	session_index_for_child: ObservedCache<Hash, ResidentSizeOf<SessionIndex>>,
+	validation_code: ObservedCache<(Hash, ParaId, OccupiedCoreAssumption), ResidentSizeOf<Option<ValidationCode>>>,
+	validation_code_by_hash: ObservedCache<ValidationCodeHash, ResidentSizeOf<Option<ValidationCode>>>,
+	candidate_pending_availability: ObservedCache<(Hash, ParaId), ResidentSizeOf<Option<CommittedCandidateReceipt>>>,
+	candidate_events: ObservedCache<Hash, ResidentSizeOf<Vec<CandidateEvent>>>,
+	session_info: ObservedCache<SessionIndex, ResidentSizeOf<Option<SessionInfo>>>,
+	dmq_contents: ObservedCache<(Hash, ParaId), ResidentSizeOf<Vec<InboundDownwardMessage<BlockNumber>>>>,
+	inbound_hrmp_channels_contents: ObservedCache<(Hash, ParaId), ResidentSizeOf<BTreeMap<ParaId, Vec<InboundHrmpMessage<BlockNumber>>>>>,
+	current_babe_epoch: ObservedCache<Hash, EpochResidentSize>,
+	on_chain_votes: ObservedCache<Hash, OnChainVotesResidentSize>,
 }
 
-impl Default for RequestResultCache {
-	fn default() -> Self {
-		Self {
-			authorities: MemoryLruCache::new(AUTHORITIES_CACHE_SIZE),
-			validators: MemoryLruCache::new(VALIDATORS_CACHE_SIZE),
-			validator_groups: MemoryLruCache::new(VALIDATOR_GROUPS_CACHE_SIZE),
-			availability_cores: MemoryLruCache::new(AVAILABILITY_CORES_CACHE_SIZE),
-			persisted_validation_data: MemoryLruCache::new(PERSISTED_VALIDATION_DATA_CACHE_SIZE),
-			check_validation_outputs: MemoryLruCache::new(CHECK_VALIDATION_OUTPUTS_CACHE_SIZE),
-			session_index_for_child: MemoryLruCache::new(SESSION_INDEX_FOR_CHILD_CACHE_SIZE),
-			validation_code: MemoryLruCache::new(VALIDATION_CODE_CACHE_SIZE),
-			validation_code_by_hash: MemoryLruCache::new(VALIDATION_CODE_CACHE_SIZE),
-			candidate_pending_availability: MemoryLruCache::new(CANDIDATE_PENDING_AVAILABILITY_CACHE_SIZE),
-			candidate_events: MemoryLruCache::new(CANDIDATE_EVENTS_CACHE_SIZE),
-			session_info: MemoryLruCache::new(SESSION_INFO_CACHE_SIZE),
-			dmq_contents: MemoryLruCache::new(DMQ_CONTENTS_CACHE_SIZE),
-			inbound_hrmp_channels_contents: MemoryLruCache::new(INBOUND_HRMP_CHANNELS_CACHE_SIZE),
-			current_babe_epoch: MemoryLruCache::new(CURRENT_BABE_EPOCH_CACHE_SIZE),
+impl RequestResultCache {
+	pub(crate) fn new(config: &CacheConfig, metrics: Metrics) -> Self {
+		macro_rules! observed_caches {
+			($($request:ident),* $(,)?) => {
+				Self {
+					metrics,
+					$($request: ObservedCache::new(stringify!($request), config.$request),)*
+				}
+			}
 		}
+
+		observed_caches!(
+			authorities,
+			validators,
+			validator_groups,
+			availability_cores,
+			persisted_validation_data,
+			check_validation_outputs,
+			session_index_for_child,
+			validation_code,
+			validation_code_by_hash,
+			candidate_pending_availability,
+			candidate_events,
+			session_info,
+			dmq_contents,
+			inbound_hrmp_channels_contents,
+			current_babe_epoch,
+			on_chain_votes,
+		)
 	}
 }
 
 impl RequestResultCache {
 	pub(crate) fn authorities(&mut self, relay_parent: &Hash) -> Option<&Vec<AuthorityDiscoveryId>> {
-		self.authorities.get(relay_parent).map(|v| &v.0)
+		self.authorities.get(relay_parent, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_authorities(&mut self, relay_parent: Hash, authorities: Vec<AuthorityDiscoveryId>) {
-		self.authorities.insert(relay_parent, VecOfDoesNotAllocate(authorities));
+		self.authorities.insert(relay_parent, VecOfDoesNotAllocate(authorities), &self.metrics);
 	}
 
 	pub(crate) fn validators(&mut self, relay_parent: &Hash) -> Option<&Vec<ValidatorId>> {
-		self.validators.get(relay_parent).map(|v| &v.0)
+		self.validators.get(relay_parent, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_validators(&mut self, relay_parent: Hash, validators: Vec<ValidatorId>) {
-		self.validators.insert(relay_parent, ResidentSizeOf(validators));
+		self.validators.insert(relay_parent, ResidentSizeOf(validators), &self.metrics);
 	}
 
 	pub(crate) fn validator_groups(&mut self, relay_parent: &Hash) -> Option<&(Vec<Vec<ValidatorIndex>>, GroupRotationInfo)> {
-		self.validator_groups.get(relay_parent).map(|v| &v.0)
+		self.validator_groups.get(relay_parent, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_validator_groups(&mut self, relay_parent: Hash, groups: (Vec<Vec<ValidatorIndex>>, GroupRotationInfo)) {
-		self.validator_groups.insert(relay_parent, ResidentSizeOf(groups));
+		self.validator_groups.insert(relay_parent, ResidentSizeOf(groups), &self.metrics);
 	}
 
 	pub(crate) fn availability_cores(&mut self, relay_parent: &Hash) -> Option<&Vec<CoreState>> {
-		self.availability_cores.get(relay_parent).map(|v| &v.0)
+		self.availability_cores.get(relay_parent, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_availability_cores(&mut self, relay_parent: Hash, cores: Vec<CoreState>) {
-		self.availability_cores.insert(relay_parent, ResidentSizeOf(cores));
+		self.availability_cores.insert(relay_parent, ResidentSizeOf(cores), &self.metrics);
 	}
 
 	pub(crate) fn persisted_validation_data(&mut self, key: (Hash, ParaId, OccupiedCoreAssumption)) -> Option<&Option<PersistedValidationData>> {
-		self.persisted_validation_data.get(&key).map(|v| &v.0)
+		self.persisted_validation_data.get(&key, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_persisted_validation_data(&mut self, key: (Hash, ParaId, OccupiedCoreAssumption), data: Option<PersistedValidationData>) {
-		self.persisted_validation_data.insert(key, ResidentSizeOf(data));
+		self.persisted_validation_data.insert(key, ResidentSizeOf(data), &self.metrics);
 	}
 
 	pub(crate) fn check_validation_outputs(&mut self, key: (Hash, ParaId, CandidateCommitments)) -> Option<&bool> {
-		self.check_validation_outputs.get(&key).map(|v| &v.0)
+		self.check_validation_outputs.get(&key, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_check_validation_outputs(&mut self, key: (Hash, ParaId, CandidateCommitments), value: bool) {
-		self.check_validation_outputs.insert(key, ResidentSizeOf(value));
+		self.check_validation_outputs.insert(key, ResidentSizeOf(value), &self.metrics);
 	}
 
 	pub(crate) fn session_index_for_child(&mut self, relay_parent: &Hash) -> Option<&SessionIndex> {
-		self.session_index_for_child.get(relay_parent).map(|v| &v.0)
+		self.session_index_for_child.get(relay_parent, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_session_index_for_child(&mut self, relay_parent: Hash, index: SessionIndex) {
-		self.session_index_for_child.insert(relay_parent, ResidentSizeOf(index));
+		self.session_index_for_child.insert(relay_parent, ResidentSizeOf(index), &self.metrics);
 	}
 
 	pub(crate) fn validation_code(&mut self, key: (Hash, ParaId, OccupiedCoreAssumption)) -> Option<&Option<ValidationCode>> {
-		self.validation_code.get(&key).map(|v| &v.0)
+		self.validation_code.get(&key, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_validation_code(&mut self, key: (Hash, ParaId, OccupiedCoreAssumption), value: Option<ValidationCode>) {
-		self.validation_code.insert(key, ResidentSizeOf(value));
+		self.validation_code.insert(key, ResidentSizeOf(value), &self.metrics);
 	}
 
 	// the actual key is `ValidationCodeHash` (`Hash` is ignored),
 	// but we keep the interface that way to keep the macro simple
 	pub(crate) fn validation_code_by_hash(&mut self, key: (Hash, ValidationCodeHash)) -> Option<&Option<ValidationCode>> {
-		self.validation_code_by_hash.get(&key.1).map(|v| &v.0)
+		self.validation_code_by_hash.get(&key.1, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_validation_code_by_hash(&mut self, key: ValidationCodeHash, value: Option<ValidationCode>) {
-		self.validation_code_by_hash.insert(key, ResidentSizeOf(value));
+		self.validation_code_by_hash.insert(key, ResidentSizeOf(value), &self.metrics);
 	}
 
 	pub(crate) fn candidate_pending_availability(&mut self, key: (Hash, ParaId)) -> Option<&Option<CommittedCandidateReceipt>> {
-		self.candidate_pending_availability.get(&key).map(|v| &v.0)
+		self.candidate_pending_availability.get(&key, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_candidate_pending_availability(&mut self, key: (Hash, ParaId), value: Option<CommittedCandidateReceipt>) {
-		self.candidate_pending_availability.insert(key, ResidentSizeOf(value));
+		self.candidate_pending_availability.insert(key, ResidentSizeOf(value), &self.metrics);
 	}
 
 	pub(crate) fn candidate_events(&mut self, relay_parent: &Hash) -> Option<&Vec<CandidateEvent>> {
-		self.candidate_events.get(relay_parent).map(|v| &v.0)
+		self.candidate_events.get(relay_parent, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_candidate_events(&mut self, relay_parent: Hash, events: Vec<CandidateEvent>) {
-		self.candidate_events.insert(relay_parent, ResidentSizeOf(events));
+		self.candidate_events.insert(relay_parent, ResidentSizeOf(events), &self.metrics);
 	}
 
 	pub(crate) fn session_info(&mut self, key: (Hash, SessionIndex)) -> Option<&Option<SessionInfo>> {
-		self.session_info.get(&key.1).map(|v| &v.0)
+		self.session_info.get(&key.1, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_session_info(&mut self, key: SessionIndex, value: Option<SessionInfo>) {
-		self.session_info.insert(key, ResidentSizeOf(value));
+		self.session_info.insert(key, ResidentSizeOf(value), &self.metrics);
 	}
 
 	pub(crate) fn dmq_contents(&mut self, key: (Hash, ParaId)) -> Option<&Vec<InboundDownwardMessage<BlockNumber>>> {
-		self.dmq_contents.get(&key).map(|v| &v.0)
+		self.dmq_contents.get(&key, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_dmq_contents(&mut self, key: (Hash, ParaId), value: Vec<InboundDownwardMessage<BlockNumber>>) {
-		self.dmq_contents.insert(key, ResidentSizeOf(value));
+		self.dmq_contents.insert(key, ResidentSizeOf(value), &self.metrics);
 	}
 
 	pub(crate) fn inbound_hrmp_channels_contents(&mut self, key: (Hash, ParaId)) -> Option<&BTreeMap<ParaId, Vec<InboundHrmpMessage<BlockNumber>>>> {
-		self.inbound_hrmp_channels_contents.get(&key).map(|v| &v.0)
+		self.inbound_hrmp_channels_contents.get(&key, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_inbound_hrmp_channel_contents(&mut self, key: (Hash, ParaId), value: BTreeMap<ParaId, Vec<InboundHrmpMessage<BlockNumber>>>) {
-		self.inbound_hrmp_channels_contents.insert(key, ResidentSizeOf(value));
+		self.inbound_hrmp_channels_contents.insert(key, ResidentSizeOf(value), &self.metrics);
 	}
 
 	pub(crate) fn current_babe_epoch(&mut self, relay_parent: &Hash) -> Option<&Epoch> {
-		self.current_babe_epoch.get(relay_parent).map(|v| &v.0)
+		self.current_babe_epoch.get(relay_parent, &self.metrics).map(|v| &v.0)
 	}
 
 	pub(crate) fn cache_current_babe_epoch(&mut self, relay_parent: Hash, epoch: Epoch) {
-		self.current_babe_epoch.insert(relay_parent, DoesNotAllocate(epoch));
+		self.current_babe_epoch.insert(relay_parent, EpochResidentSize(epoch), &self.metrics);
+	}
+
+	pub(crate) fn on_chain_votes(&mut self, relay_parent: &Hash) -> Option<&Option<ScrapedOnChainVotes>> {
+		self.on_chain_votes.get(relay_parent, &self.metrics).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_on_chain_votes(&mut self, relay_parent: Hash, scraped: Option<ScrapedOnChainVotes>) {
+		self.on_chain_votes.insert(relay_parent, OnChainVotesResidentSize(scraped), &self.metrics);
 	}
 }
 
@@ -248,4 +474,5 @@ pub(crate) enum RequestResult {
 	DmqContents(Hash, ParaId, Vec<InboundDownwardMessage<BlockNumber>>),
 	InboundHrmpChannelsContents(Hash, ParaId, BTreeMap<ParaId, Vec<InboundHrmpMessage<BlockNumber>>>),
 	CurrentBabeEpoch(Hash, Epoch),
+	FetchOnChainVotes(Hash, Option<ScrapedOnChainVotes>),
 }
@@ -35,7 +35,7 @@ use polkadot_subsystem::{
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
 use polkadot_primitives::v1::{Block, BlockId, Hash, ParachainHost};
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_authority_discovery::AuthorityDiscoveryApi;
 use sp_core::traits::SpawnNamed;
 use sp_consensus_babe::BabeApi;
@@ -44,6 +44,8 @@ use futures::{prelude::*, stream::FuturesUnordered, channel::oneshot, select};
 use std::{sync::Arc, collections::VecDeque, pin::Pin};
 use cache::{RequestResult, RequestResultCache};
 
+pub use cache::{CacheConfig, CacheSizeEntry, CacheSizeError};
+
 mod cache;
 
 #[cfg(test)]
@@ -76,13 +78,23 @@ pub struct RuntimeApiSubsystem<Client> {
 impl<Client> RuntimeApiSubsystem<Client> {
 	/// Create a new Runtime API subsystem wrapping the given client and metrics.
 	pub fn new(client: Arc<Client>, metrics: Metrics, spawn_handle: impl SpawnNamed + 'static) -> Self {
+		Self::with_config(client, metrics, spawn_handle, CacheConfig::default())
+	}
+
+	/// Create a new Runtime API subsystem with the given budgets of the request result caches.
+	pub fn with_config(
+		client: Arc<Client>,
+		metrics: Metrics,
+		spawn_handle: impl SpawnNamed + 'static,
+		cache_config: CacheConfig,
+	) -> Self {
 		RuntimeApiSubsystem {
+			requests_cache: RequestResultCache::new(&cache_config, metrics.clone()),
 			client,
 			metrics,
 			spawn_handle: Box::new(spawn_handle),
 			waiting_requests: Default::default(),
 			active_requests: Default::default(),
-			requests_cache: RequestResultCache::default(),
 		}
 	}
 }
@@ -139,6 +151,8 @@ impl<Client> RuntimeApiSubsystem<Client> where
 				self.requests_cache.cache_inbound_hrmp_channel_contents((relay_parent, para_id), contents),
 			CurrentBabeEpoch(relay_parent, epoch) =>
 				self.requests_cache.cache_current_babe_epoch(relay_parent, epoch),
+			FetchOnChainVotes(relay_parent, scraped) =>
+				self.requests_cache.cache_on_chain_votes(relay_parent, scraped),
 		}
 	}
 
@@ -207,6 +221,14 @@ impl<Client> RuntimeApiSubsystem<Client> where
 			Request::CurrentBabeEpoch(sender) =>
 				query!(current_babe_epoch(), sender)
 					.map(|sender| Request::CurrentBabeEpoch(sender)),
+			Request::FetchOnChainVotes(sender) =>
+				query!(on_chain_votes(), sender)
+					.map(|sender| Request::FetchOnChainVotes(sender)),
+			// The version is cheap to look up, the set of PVFs awaiting the votes changes with every
+			// vote, and submissions have side effects, so none of them is cached.
+			request @ Request::Version(_) |
+			request @ Request::PvfsRequirePrecheck(_) |
+			request @ Request::SubmitPvfCheckStatement(..) => Some(request),
 		}
 	}
 
@@ -307,6 +329,26 @@ where
 	let _timer = metrics.time_make_runtime_api_request();
 
 	macro_rules! query {
+		// Runtime APIs added in a later version of `ParachainHost` fail on older runtimes.
+		($req_variant:ident, $api_name:ident ($($param:expr),*), ver = $version:literal, $sender:expr) => {{
+			let sender = $sender;
+			let api = client.runtime_api();
+			let at = BlockId::Hash(relay_parent);
+			let res = match api.has_api_with::<dyn ParachainHost<Block>, _>(&at, |v| v >= $version) {
+				Ok(true) => api.$api_name(&at $(, $param.clone() )*)
+					.map_err(|e| RuntimeApiError::from(format!("{:?}", e))),
+				Ok(false) => Err(RuntimeApiError::from(format!(
+					"{} requires version {} of the ParachainHost runtime API",
+					stringify!($api_name),
+					$version,
+				))),
+				Err(e) => Err(RuntimeApiError::from(format!("{:?}", e))),
+			};
+			metrics.on_request(res.is_ok());
+			let _ = sender.send(res.clone());
+
+			res.ok().map(|res| RequestResult::$req_variant(relay_parent, $( $param, )* res))
+		}};
 		($req_variant:ident, $api_name:ident ($($param:expr),*), $sender:expr) => {{
 			let sender = $sender;
 			let api = client.runtime_api();
@@ -319,7 +361,46 @@ where
 		}}
 	}
 
+	macro_rules! query_uncached {
+		($api_name:ident ($($param:expr),*), ver = $version:literal, $sender:expr) => {{
+			let sender = $sender;
+			let api = client.runtime_api();
+			let at = BlockId::Hash(relay_parent);
+			let res = match api.has_api_with::<dyn ParachainHost<Block>, _>(&at, |v| v >= $version) {
+				Ok(true) => api.$api_name(&at $(, $param)*)
+					.map_err(|e| RuntimeApiError::from(format!("{:?}", e))),
+				Ok(false) => Err(RuntimeApiError::from(format!(
+					"{} requires version {} of the ParachainHost runtime API",
+					stringify!($api_name),
+					$version,
+				))),
+				Err(e) => Err(RuntimeApiError::from(format!("{:?}", e))),
+			};
+			metrics.on_request(res.is_ok());
+			let _ = sender.send(res);
+
+			None
+		}}
+	}
+
 	match request {
+		Request::Version(sender) => {
+			// The predicate is only called with the version of the API if the runtime provides it.
+			let version = std::cell::Cell::new(None);
+			let res = client.runtime_api()
+				.has_api_with::<dyn ParachainHost<Block>, _>(&BlockId::Hash(relay_parent), |v| {
+					version.set(Some(v));
+					true
+				})
+				.map_err(|e| RuntimeApiError::from(format!("{:?}", e)))
+				.and_then(|_| version.get().ok_or_else(|| RuntimeApiError::from(
+					"The runtime does not provide the ParachainHost runtime API".to_owned(),
+				)));
+			metrics.on_request(res.is_ok());
+			let _ = sender.send(res);
+
+			None
+		}
 		Request::Authorities(sender) => query!(Authorities, authorities(), sender),
 		Request::Validators(sender) => query!(Validators, validators(), sender),
 		Request::ValidatorGroups(sender) => query!(ValidatorGroups, validator_groups(), sender),
@@ -340,6 +421,11 @@ where
 		Request::DmqContents(id, sender) => query!(DmqContents, dmq_contents(id), sender),
 		Request::InboundHrmpChannelsContents(id, sender) => query!(InboundHrmpChannelsContents, inbound_hrmp_channels_contents(id), sender),
 		Request::CurrentBabeEpoch(sender) => query!(CurrentBabeEpoch, current_epoch(), sender),
+		Request::FetchOnChainVotes(sender) => query!(FetchOnChainVotes, on_chain_votes(), ver = 2, sender),
+		Request::PvfsRequirePrecheck(sender) =>
+			query_uncached!(pvfs_require_precheck(), ver = 3, sender),
+		Request::SubmitPvfCheckStatement(stmt, signature, sender) =>
+			query_uncached!(submit_pvf_check_statement(stmt, signature), ver = 3, sender),
 	}
 }
 
@@ -347,6 +433,9 @@ where
 struct MetricsInner {
 	chain_api_requests: prometheus::CounterVec<prometheus::U64>,
 	make_runtime_api_request: prometheus::Histogram,
+	cache_lookups: prometheus::CounterVec<prometheus::U64>,
+	cache_evictions: prometheus::CounterVec<prometheus::U64>,
+	cache_resident_bytes: prometheus::GaugeVec<prometheus::U64>,
 }
 
 /// Runtime API metrics.
@@ -369,6 +458,20 @@ impl Metrics {
 			.map(|metrics| metrics.chain_api_requests.with_label_values(&["cached"]).inc());
 	}
 
+	fn on_cache_lookup(&self, request: &str, hit: bool) {
+		if let Some(metrics) = &self.0 {
+			let result = if hit { "hit" } else { "miss" };
+			metrics.cache_lookups.with_label_values(&[request, result]).inc();
+		}
+	}
+
+	fn on_cache_insert(&self, request: &str, evictions: u64, resident_bytes: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.cache_evictions.with_label_values(&[request]).inc_by(evictions);
+			metrics.cache_resident_bytes.with_label_values(&[request]).set(resident_bytes as u64);
+		}
+	}
+
 	/// Provide a timer for `make_runtime_api_request` which observes on drop.
 	fn time_make_runtime_api_request(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.make_runtime_api_request.start_timer())
@@ -397,6 +500,36 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			cache_lookups: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"parachain_runtime_api_cache_lookups_total",
+						"Number of runtime API cache lookups per request kind, by whether the result was cached.",
+					),
+					&["request", "result"],
+				)?,
+				registry,
+			)?,
+			cache_evictions: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"parachain_runtime_api_cache_evictions_total",
+						"Number of results evicted from the runtime API cache, per request kind.",
+					),
+					&["request"],
+				)?,
+				registry,
+			)?,
+			cache_resident_bytes: prometheus::register(
+				prometheus::GaugeVec::new(
+					prometheus::Opts::new(
+						"parachain_runtime_api_cache_resident_bytes",
+						"Resident size of the results in the runtime API cache, per request kind.",
+					),
+					&["request"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
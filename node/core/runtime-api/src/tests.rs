@@ -21,6 +21,8 @@ use polkadot_primitives::v1::{
 	Id as ParaId, OccupiedCoreAssumption, SessionIndex, ValidationCode,
 	CommittedCandidateReceipt, CandidateEvent, InboundDownwardMessage,
 	InboundHrmpMessage, SessionInfo, AuthorityDiscoveryId, ValidationCodeHash,
+	ScrapedOnChainVotes, CandidateReceipt, ValidityAttestation, PvfCheckStatement,
+	ValidatorSignature,
 };
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use sp_core::testing::TaskExecutor;
@@ -48,6 +50,9 @@ struct MockRuntimeApi {
 	dmq_contents: HashMap<ParaId, Vec<InboundDownwardMessage>>,
 	hrmp_channels: HashMap<ParaId, BTreeMap<ParaId, Vec<InboundHrmpMessage>>>,
 	babe_epoch: Option<BabeEpoch>,
+	on_chain_votes: Option<ScrapedOnChainVotes>,
+	pvfs_require_precheck: Vec<ValidationCodeHash>,
+	submitted_pvf_check_statements: Arc<Mutex<Vec<(PvfCheckStatement, ValidatorSignature)>>>,
 }
 
 impl ProvideRuntimeApi<Block> for MockRuntimeApi {
@@ -148,6 +153,18 @@ sp_api::mock_impl_runtime_apis! {
 		) -> Option<ValidationCode> {
 			self.validation_code_by_hash.get(&hash).map(|c| c.clone())
 		}
+
+		fn on_chain_votes(&self) -> Option<ScrapedOnChainVotes> {
+			self.on_chain_votes.clone()
+		}
+
+		fn submit_pvf_check_statement(&self, stmt: PvfCheckStatement, signature: ValidatorSignature) {
+			self.submitted_pvf_check_statements.lock().unwrap().push((stmt, signature));
+		}
+
+		fn pvfs_require_precheck(&self) -> Vec<ValidationCodeHash> {
+			self.pvfs_require_precheck.clone()
+		}
 	}
 
 	impl BabeApi<Block> for MockRuntimeApi {
@@ -788,3 +805,153 @@ fn request_babe_epoch() {
 
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
+
+#[test]
+fn requests_on_chain_votes() {
+	let (ctx, mut ctx_handle) = test_helpers::make_subsystem_context(TaskExecutor::new());
+	let mut runtime_api = MockRuntimeApi::default();
+	let votes = ScrapedOnChainVotes {
+		session: 1,
+		backing_validators_per_candidate: vec![(
+			CandidateReceipt::default(),
+			vec![(ValidatorIndex(0), ValidityAttestation::Implicit(Default::default()))],
+		)],
+		disputes: Vec::new(),
+	};
+	runtime_api.on_chain_votes = Some(votes.clone());
+	let runtime_api = Arc::new(runtime_api);
+	let relay_parent = [1; 32].into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+
+		ctx_handle.send(FromOverseer::Communication {
+			msg: RuntimeApiMessage::Request(relay_parent, Request::FetchOnChainVotes(tx))
+		}).await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), Some(votes));
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn requests_version() {
+	let (ctx, mut ctx_handle) = test_helpers::make_subsystem_context(TaskExecutor::new());
+	let runtime_api = Arc::new(MockRuntimeApi::default());
+	let relay_parent = [1; 32].into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+
+		ctx_handle.send(FromOverseer::Communication {
+			msg: RuntimeApiMessage::Request(relay_parent, Request::Version(tx))
+		}).await;
+
+		assert_eq!(rx.await.unwrap().unwrap(), 3);
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn requests_pvfs_require_precheck_and_submits_statements() {
+	let (ctx, mut ctx_handle) = test_helpers::make_subsystem_context(TaskExecutor::new());
+	let mut runtime_api = MockRuntimeApi::default();
+	let code_hash = ValidationCode(vec![1, 2, 3]).hash();
+	runtime_api.pvfs_require_precheck = vec![code_hash];
+	let runtime_api = Arc::new(runtime_api);
+	let relay_parent = [1; 32].into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+		ctx_handle.send(FromOverseer::Communication {
+			msg: RuntimeApiMessage::Request(relay_parent, Request::PvfsRequirePrecheck(tx))
+		}).await;
+		assert_eq!(rx.await.unwrap().unwrap(), vec![code_hash]);
+
+		let stmt = PvfCheckStatement {
+			accept: true,
+			subject: code_hash,
+			session_index: 1,
+			validator_index: ValidatorIndex(0),
+		};
+		let signature = ValidatorSignature::default();
+
+		// Submissions are never served from the cache, so both of them must reach the runtime.
+		for _ in 0..2 {
+			let (tx, rx) = oneshot::channel();
+			ctx_handle.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::SubmitPvfCheckStatement(stmt.clone(), signature.clone(), tx),
+				)
+			}).await;
+			rx.await.unwrap().unwrap();
+		}
+
+		assert_eq!(
+			*runtime_api.submitted_pvf_check_statements.lock().unwrap(),
+			vec![(stmt.clone(), signature.clone()); 2],
+		);
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn cache_sizes_are_parsed() {
+	let mut config = CacheConfig::default();
+	config.insert("validation_code:1024".parse().unwrap());
+	config.insert(" session_info : 0 ".parse().unwrap());
+
+	assert_eq!(config.validation_code, 1024);
+	assert_eq!(config.session_info, 0);
+	assert_eq!(config.validation_code_by_hash, CacheConfig::default().validation_code_by_hash);
+
+	assert_eq!(
+		"validation_code".parse::<CacheSizeEntry>(),
+		Err(CacheSizeError::Malformed("validation_code".into())),
+	);
+	assert_eq!(
+		"validation_codes:1024".parse::<CacheSizeEntry>(),
+		Err(CacheSizeError::UnknownRequest("validation_codes".into())),
+	);
+	assert_eq!(
+		"validation_code:1k".parse::<CacheSizeEntry>(),
+		Err(CacheSizeError::InvalidNumber("1k".into())),
+	);
+}
+
+#[test]
+fn cache_evicts_results_exceeding_the_budget() {
+	let authorities: Vec<AuthorityDiscoveryId> = vec![Default::default(); 2];
+	let (first, second) = (Hash::repeat_byte(1), Hash::repeat_byte(2));
+
+	// Room for three authorities, i.e. for one of the two results.
+	let config = CacheConfig {
+		authorities: 3 * std::mem::size_of::<AuthorityDiscoveryId>(),
+		..Default::default()
+	};
+	let mut cache = cache::RequestResultCache::new(&config, Metrics(None));
+
+	cache.cache_authorities(first, authorities.clone());
+	assert_eq!(cache.authorities(&first), Some(&authorities));
+
+	cache.cache_authorities(second, authorities.clone());
+	assert_eq!(cache.authorities(&first), None);
+	assert_eq!(cache.authorities(&second), Some(&authorities));
+}
@@ -16,20 +16,26 @@
 
 //! Polkadot Jaeger configuration.
 
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+
 /// Configuration for the jaeger tracing.
 #[derive(Clone)]
 pub struct JaegerConfig {
 	pub(crate) node_name: String,
-	pub(crate) agent_addr: std::net::SocketAddr,
+	pub(crate) exporter: JaegerExporter,
+	pub(crate) sampling: SamplingRatios,
 }
 
 impl std::default::Default for JaegerConfig {
 	fn default() -> Self {
 		Self {
 			node_name: "unknown_".to_owned(),
-			agent_addr: "127.0.0.1:6831"
-				.parse()
-				.expect(r#"Static "127.0.0.1:6831" is a valid socket address string. qed"#),
+			exporter: JaegerExporter::Agent(
+				"127.0.0.1:6831"
+					.parse()
+					.expect(r#"Static "127.0.0.1:6831" is a valid socket address string. qed"#),
+			),
+			sampling: SamplingRatios::default(),
 		}
 	}
 }
@@ -41,6 +47,91 @@ impl JaegerConfig {
 	}
 }
 
+/// The destination the collected spans are sent to.
+#[derive(Clone, Debug)]
+pub enum JaegerExporter {
+	/// Send each span to a jaeger agent via UDP.
+	Agent(SocketAddr),
+	/// Export the spans in batches to an OpenTelemetry collector via OTLP/HTTP.
+	Otlp(OtlpConfig),
+}
+
+/// Configuration of the OTLP exporter.
+#[derive(Clone, Debug)]
+pub struct OtlpConfig {
+	pub(crate) endpoint: String,
+	pub(crate) max_batch_size: usize,
+	pub(crate) max_queue_size: usize,
+	pub(crate) batch_timeout: Duration,
+}
+
+impl OtlpConfig {
+	/// Export to the collector listening at `endpoint`, commonly `http://127.0.0.1:4318`.
+	///
+	/// The spans are posted to the `/v1/traces` path of the endpoint.
+	pub fn new<S>(endpoint: S) -> Self
+	where
+		S: AsRef<str>,
+	{
+		Self {
+			endpoint: endpoint.as_ref().trim_end_matches('/').to_owned(),
+			max_batch_size: 512,
+			max_queue_size: 4096,
+			batch_timeout: Duration::from_secs(5),
+		}
+	}
+
+	/// Set the maximum number of spans exported in a single request.
+	pub fn max_batch_size(mut self, max_batch_size: usize) -> Self {
+		self.max_batch_size = max_batch_size.max(1);
+		self
+	}
+
+	/// Set the maximum number of finished spans waiting for export.
+	///
+	/// Spans finished while the queue is full are dropped.
+	pub fn max_queue_size(mut self, max_queue_size: usize) -> Self {
+		self.max_queue_size = max_queue_size;
+		self
+	}
+
+	/// Set the maximum time a finished span waits for its batch to fill up.
+	pub fn batch_timeout(mut self, batch_timeout: Duration) -> Self {
+		self.batch_timeout = batch_timeout;
+		self
+	}
+}
+
+/// Sampling ratios per span name.
+///
+/// Only applies to spans which start a trace, i.e. the ones created from an
+/// identifier, children are recorded if and only if their parent is.
+/// The decision is derived from the trace identifier, so all nodes agree on
+/// which traces to record.
+#[derive(Clone, Debug, Default)]
+pub struct SamplingRatios(HashMap<String, f64>);
+
+impl SamplingRatios {
+	/// Whether the trace with the given identifier, started by a span with
+	/// the given name, should be recorded.
+	pub(crate) fn is_sampled(&self, span_name: &str, trace_id: u128) -> bool {
+		let ratio = match self.0.get(span_name) {
+			Some(ratio) => *ratio,
+			None => return true,
+		};
+
+		if ratio >= 1.0 {
+			true
+		} else if ratio <= 0.0 {
+			false
+		} else {
+			// The identifiers are derived from hashes, so their bits are uniformly distributed.
+			let threshold = (ratio * u64::MAX as f64) as u64;
+			((trace_id >> 64) as u64) < threshold
+		}
+	}
+}
+
 /// Jaeger configuration builder.
 #[derive(Default)]
 pub struct JaegerConfigBuilder {
@@ -62,7 +153,23 @@ impl JaegerConfigBuilder {
 	where
 		U: Into<std::net::SocketAddr>,
 	{
-		self.inner.agent_addr = addr.into();
+		self.inner.exporter = JaegerExporter::Agent(addr.into());
+		self
+	}
+
+	/// Export the collected spans to an OpenTelemetry collector instead of a jaeger agent.
+	pub fn otlp(mut self, config: OtlpConfig) -> Self {
+		self.inner.exporter = JaegerExporter::Otlp(config);
+		self
+	}
+
+	/// Record only the given ratio, between `0.0` and `1.0`, of the traces
+	/// started by spans with the given name.
+	pub fn sample<S>(mut self, span_name: S, ratio: f64) -> Self
+	where
+		S: AsRef<str>,
+	{
+		self.inner.sampling.0.insert(span_name.as_ref().to_owned(), ratio);
 		self
 	}
 
@@ -71,3 +178,33 @@ impl JaegerConfigBuilder {
 		self.inner
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sampling_is_derived_from_the_trace_id() {
+		let sampling = JaegerConfig::builder()
+			.sample("half", 0.5)
+			.sample("none", 0.0)
+			.sample("all", 1.0)
+			.build()
+			.sampling;
+
+		let low = 1u128 << 64;
+		let high = u128::MAX;
+
+		assert!(sampling.is_sampled("unconfigured", high));
+		assert!(sampling.is_sampled("all", high));
+		assert!(!sampling.is_sampled("none", low));
+		assert!(sampling.is_sampled("half", low));
+		assert!(!sampling.is_sampled("half", high));
+
+		let sampled = (0..1000u128)
+			.map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15_f39c_c060_5ced_c835))
+			.filter(|trace_id| sampling.is_sampled("half", *trace_id))
+			.count();
+		assert!((400..600).contains(&sampled), "sampled {} of 1000 traces", sampled);
+	}
+}
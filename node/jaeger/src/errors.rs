@@ -25,4 +25,7 @@ pub enum JaegerError {
 
 	#[error("Missing jaeger configuration")]
 	MissingConfiguration,
+
+	#[error("Invalid OTLP endpoint {0}: {1}")]
+	InvalidEndpoint(String, String),
 }
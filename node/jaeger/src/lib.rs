@@ -43,17 +43,26 @@
 //!  -p 9411:9411 \
 //!  docker.io/jaegertracing/all-in-one:1.21
 //! ```
+//!
+//! Alternatively the spans can be exported to any OpenTelemetry collector
+//! with an OTLP/HTTP receiver, see [`OtlpConfig`]. The spans are batched
+//! before export, which avoids sending a UDP datagram per span.
+//!
+//! In both cases [`JaegerConfigBuilder::sample`] allows recording only a
+//! fraction of the traces started by a frequently created span.
 
 #![forbid(unused_imports)]
 
 mod config;
 mod errors;
+mod otlp;
 mod spans;
 
-pub use self::config::{JaegerConfig, JaegerConfigBuilder};
+pub use self::config::{JaegerConfig, JaegerConfigBuilder, JaegerExporter, OtlpConfig};
 pub use self::errors::JaegerError;
 pub use self::spans::{PerLeafSpan, Span, Stage};
 
+use self::config::SamplingRatios;
 use self::spans::TraceIdentifier;
 
 use sp_core::traits::SpawnNamed;
@@ -65,12 +74,21 @@ lazy_static::lazy_static! {
 	static ref INSTANCE: RwLock<Jaeger> = RwLock::new(Jaeger::None);
 }
 
-/// Stateful convenience wrapper around [`mick_jaeger`].
+/// Stateful convenience wrapper around [`mick_jaeger`] and the OTLP exporter.
 pub enum Jaeger {
 	/// Launched and operational state.
 	Launched {
 		/// [`mick_jaeger`] provided API to record spans to.
 		traces_in: Arc<mick_jaeger::TracesIn>,
+		/// Sampling ratios of the traces.
+		sampling: SamplingRatios,
+	},
+	/// Launched and operational state, exporting via OTLP.
+	LaunchedOtlp {
+		/// Queue to record spans to.
+		traces_in: Arc<otlp::TracesIn>,
+		/// Sampling ratios of the traces.
+		sampling: SamplingRatios,
 	},
 	/// Preparation state with the necessary config to launch the collector.
 	Prep(JaegerConfig),
@@ -90,55 +108,78 @@ impl Jaeger {
 		Ok(())
 	}
 
-	/// Spawn the background task in order to send the tracing information out via UDP
+	/// Spawn the background task in order to send the tracing information out,
+	/// either via UDP to a jaeger agent or in batches via OTLP.
 	#[cfg(not(target_os = "unknown"))]
 	pub fn launch<S: SpawnNamed>(self, spawner: S) -> result::Result<(), JaegerError> {
 		let cfg = match self {
 			Self::Prep(cfg) => Ok(cfg),
-			Self::Launched { .. } => return Err(JaegerError::AlreadyLaunched),
+			Self::Launched { .. } | Self::LaunchedOtlp { .. } => return Err(JaegerError::AlreadyLaunched),
 			Self::None => Err(JaegerError::MissingConfiguration),
 		}?;
 
-		let jaeger_agent = cfg.agent_addr;
+		let service_name = format!("polkadot-{}", cfg.node_name);
+		let sampling = cfg.sampling;
+
+		let instance = match cfg.exporter {
+			JaegerExporter::Agent(jaeger_agent) => {
+				log::info!("🐹 Collecting jaeger spans for {:?}", &jaeger_agent);
+
+				let (traces_in, mut traces_out) = mick_jaeger::init(mick_jaeger::Config { service_name });
+
+				// Spawn a background task that pulls span information and sends them on the network.
+				spawner.spawn(
+					"jaeger-collector",
+					Box::pin(async move {
+						match async_std::net::UdpSocket::bind("0.0.0.0:0").await {
+							Ok(udp_socket) => loop {
+								let buf = traces_out.next().await;
+								// UDP sending errors happen only either if the API is misused or in case of missing privilege.
+								if let Err(e) = udp_socket.send_to(&buf, jaeger_agent).await {
+									log::debug!(target: "jaeger", "UDP send error: {}", e);
+								}
+							},
+							Err(e) => {
+								log::warn!(target: "jaeger", "UDP socket open error: {}", e);
+							}
+						}
+					}),
+				);
 
-		log::info!("🐹 Collecting jaeger spans for {:?}", &jaeger_agent);
+				Self::Launched { traces_in, sampling }
+			}
+			JaegerExporter::Otlp(otlp_config) => {
+				log::info!("🐹 Exporting spans via OTLP to {}", &otlp_config.endpoint);
 
-		let (traces_in, mut traces_out) =
-			mick_jaeger::init(mick_jaeger::Config { service_name: format!("polkadot-{}", cfg.node_name) });
+				let (traces_in, export) = otlp::init(otlp_config, service_name)?;
+				spawner.spawn("jaeger-otlp-exporter", Box::pin(export));
 
-		// Spawn a background task that pulls span information and sends them on the network.
-		spawner.spawn(
-			"jaeger-collector",
-			Box::pin(async move {
-				match async_std::net::UdpSocket::bind("0.0.0.0:0").await {
-					Ok(udp_socket) => loop {
-						let buf = traces_out.next().await;
-						// UDP sending errors happen only either if the API is misused or in case of missing privilege.
-						if let Err(e) = udp_socket.send_to(&buf, jaeger_agent).await {
-							log::debug!(target: "jaeger", "UDP send error: {}", e);
-						}
-					},
-					Err(e) => {
-						log::warn!(target: "jaeger", "UDP socket open error: {}", e);
-					}
-				}
-			}),
-		);
-
-		*INSTANCE.write() = Self::Launched { traces_in };
+				Self::LaunchedOtlp { traces_in, sampling }
+			}
+		};
+
+		*INSTANCE.write() = instance;
 		Ok(())
 	}
 
-	pub(crate) fn span<F>(&self, lazy_hash: F, span_name: &'static str) -> Option<mick_jaeger::Span>
+	pub(crate) fn span<F>(&self, lazy_hash: F, span_name: &'static str) -> Span
 	where
 		F: Fn() -> TraceIdentifier,
 	{
-		if let Self::Launched { traces_in, .. } = self {
-			let ident = lazy_hash();
-			let trace_id = std::num::NonZeroU128::new(ident)?;
-			Some(traces_in.span(trace_id, span_name))
-		} else {
-			None
+		let (ident, sampling) = match self {
+			Self::Launched { sampling, .. } | Self::LaunchedOtlp { sampling, .. } => (lazy_hash(), sampling),
+			_ => return Span::Disabled,
+		};
+
+		let trace_id = match std::num::NonZeroU128::new(ident) {
+			Some(trace_id) if sampling.is_sampled(span_name, ident) => trace_id,
+			_ => return Span::Disabled,
+		};
+
+		match self {
+			Self::Launched { traces_in, .. } => Span::Enabled(traces_in.span(trace_id, span_name)),
+			Self::LaunchedOtlp { traces_in, .. } => Span::Otlp(traces_in.span(trace_id, span_name)),
+			_ => Span::Disabled,
 		}
 	}
 }
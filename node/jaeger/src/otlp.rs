@@ -0,0 +1,367 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Batched export of spans to an OpenTelemetry collector.
+//!
+//! Finished spans are queued and posted in batches, encoded as JSON,
+//! to the `/v1/traces` path of the collector's OTLP/HTTP receiver.
+
+use futures::channel::mpsc;
+use parking_lot::Mutex;
+use serde_json::json;
+
+use std::{
+	num::{NonZeroU128, NonZeroU64},
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(not(target_os = "unknown"))]
+use {
+	crate::{config::OtlpConfig, errors::JaegerError},
+	futures::{Future, FutureExt, StreamExt},
+};
+
+const LOG_TARGET: &str = "jaeger::otlp";
+
+/// The `SPAN_KIND_INTERNAL` span kind of OTLP.
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// The value of a span attribute.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+	String(String),
+	Int(i64),
+}
+
+/// A span which finished and waits for export.
+#[derive(Debug)]
+struct FinishedSpan {
+	trace_id: NonZeroU128,
+	span_id: NonZeroU64,
+	parent_span_id: Option<NonZeroU64>,
+	name: &'static str,
+	start_unix_nanos: u64,
+	end_unix_nanos: u64,
+	attributes: Vec<(&'static str, Value)>,
+	links: Vec<(NonZeroU128, NonZeroU64)>,
+}
+
+/// Queue to record finished spans to, drained by the exporter.
+pub struct TracesIn {
+	spans: Mutex<mpsc::Sender<FinishedSpan>>,
+}
+
+impl TracesIn {
+	/// Start a new trace.
+	pub(crate) fn span(self: &Arc<Self>, trace_id: NonZeroU128, name: &'static str) -> Span {
+		Span::new(self.clone(), trace_id, None, name)
+	}
+
+	fn submit(&self, span: FinishedSpan) {
+		if let Err(e) = self.spans.lock().try_send(span) {
+			if e.is_full() {
+				log::trace!(target: LOG_TARGET, "Export queue is full, dropping span");
+			}
+		}
+	}
+}
+
+/// A span recorded for OTLP export.
+///
+/// The span is queued for export once dropped.
+pub struct Span {
+	traces_in: Arc<TracesIn>,
+	trace_id: NonZeroU128,
+	span_id: NonZeroU64,
+	parent_span_id: Option<NonZeroU64>,
+	name: &'static str,
+	start_unix_nanos: u64,
+	attributes: Vec<(&'static str, Value)>,
+	links: Vec<(NonZeroU128, NonZeroU64)>,
+}
+
+impl Span {
+	fn new(
+		traces_in: Arc<TracesIn>,
+		trace_id: NonZeroU128,
+		parent_span_id: Option<NonZeroU64>,
+		name: &'static str,
+	) -> Self {
+		Self {
+			traces_in,
+			trace_id,
+			span_id: random_span_id(),
+			parent_span_id,
+			name,
+			start_unix_nanos: unix_nanos_now(),
+			attributes: Vec::new(),
+			links: Vec::new(),
+		}
+	}
+
+	/// Derive a child span from `self`.
+	pub(crate) fn child(&self, name: &'static str) -> Self {
+		Self::new(self.traces_in.clone(), self.trace_id, Some(self.span_id), name)
+	}
+
+	pub(crate) fn add_string_tag(&mut self, key: &'static str, value: &str) {
+		self.attributes.push((key, Value::String(value.to_owned())));
+	}
+
+	pub(crate) fn add_int_tag(&mut self, key: &'static str, value: i64) {
+		self.attributes.push((key, Value::Int(value)));
+	}
+
+	/// Link to `other`, the OTLP equivalent of the `FollowsFrom` relationship.
+	pub(crate) fn add_follows_from(&mut self, other: &Self) {
+		self.links.push((other.trace_id, other.span_id));
+	}
+}
+
+impl Drop for Span {
+	fn drop(&mut self) {
+		self.traces_in.submit(FinishedSpan {
+			trace_id: self.trace_id,
+			span_id: self.span_id,
+			parent_span_id: self.parent_span_id,
+			name: self.name,
+			start_unix_nanos: self.start_unix_nanos,
+			end_unix_nanos: unix_nanos_now(),
+			attributes: std::mem::take(&mut self.attributes),
+			links: std::mem::take(&mut self.links),
+		});
+	}
+}
+
+fn random_span_id() -> NonZeroU64 {
+	loop {
+		if let Some(id) = NonZeroU64::new(rand::random()) {
+			return id
+		}
+	}
+}
+
+fn unix_nanos_now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|since_epoch| since_epoch.as_nanos() as u64)
+		.unwrap_or_default()
+}
+
+fn encode_value(value: &Value) -> serde_json::Value {
+	match value {
+		Value::String(s) => json!({ "stringValue": s }),
+		// 64 bit integers are encoded as strings in the JSON mapping of protobuf.
+		Value::Int(i) => json!({ "intValue": i.to_string() }),
+	}
+}
+
+fn encode_span(span: &FinishedSpan) -> serde_json::Value {
+	let mut encoded = json!({
+		"traceId": format!("{:032x}", span.trace_id),
+		"spanId": format!("{:016x}", span.span_id),
+		"name": span.name,
+		"kind": SPAN_KIND_INTERNAL,
+		"startTimeUnixNano": span.start_unix_nanos.to_string(),
+		"endTimeUnixNano": span.end_unix_nanos.to_string(),
+		"attributes": span.attributes.iter().map(|(key, value)| json!({
+			"key": key,
+			"value": encode_value(value),
+		})).collect::<Vec<_>>(),
+		"links": span.links.iter().map(|(trace_id, span_id)| json!({
+			"traceId": format!("{:032x}", trace_id),
+			"spanId": format!("{:016x}", span_id),
+		})).collect::<Vec<_>>(),
+	});
+
+	if let Some(parent_span_id) = span.parent_span_id {
+		encoded["parentSpanId"] = json!(format!("{:016x}", parent_span_id));
+	}
+
+	encoded
+}
+
+/// Encode a batch of spans as an OTLP `ExportTraceServiceRequest`.
+fn encode_batch(service_name: &str, batch: &[FinishedSpan]) -> serde_json::Value {
+	json!({
+		"resourceSpans": [{
+			"resource": {
+				"attributes": [{
+					"key": "service.name",
+					"value": { "stringValue": service_name },
+				}],
+			},
+			"scopeSpans": [{
+				"scope": { "name": "polkadot-node-jaeger" },
+				"spans": batch.iter().map(encode_span).collect::<Vec<_>>(),
+			}],
+		}],
+	})
+}
+
+/// Create the queue to record spans to and the future exporting them.
+#[cfg(not(target_os = "unknown"))]
+pub(crate) fn init(
+	config: OtlpConfig,
+	service_name: String,
+) -> Result<(Arc<TracesIn>, impl Future<Output = ()> + Send), JaegerError> {
+	let uri = format!("{}/v1/traces", config.endpoint);
+	let uri = uri.parse::<hyper::Uri>().map_err(|e| JaegerError::InvalidEndpoint(uri.clone(), e.to_string()))?;
+
+	let (tx, rx) = mpsc::channel(config.max_queue_size);
+	let traces_in = Arc::new(TracesIn { spans: Mutex::new(tx) });
+
+	Ok((traces_in, export(config, uri, service_name, rx)))
+}
+
+/// Collect the finished spans into batches and post them to the collector.
+///
+/// A batch is exported once it is full or the batch timeout elapsed since
+/// its first span was received.
+#[cfg(not(target_os = "unknown"))]
+async fn export(
+	config: OtlpConfig,
+	uri: hyper::Uri,
+	service_name: String,
+	mut spans: mpsc::Receiver<FinishedSpan>,
+) {
+	let client = hyper::Client::new();
+
+	while let Some(first) = spans.next().await {
+		let mut batch = vec![first];
+		let mut timeout = futures_timer::Delay::new(config.batch_timeout).fuse();
+
+		while batch.len() < config.max_batch_size {
+			futures::select! {
+				span = spans.next() => match span {
+					Some(span) => batch.push(span),
+					None => break,
+				},
+				_ = timeout => break,
+			}
+		}
+
+		let body = encode_batch(&service_name, &batch).to_string();
+		let request = hyper::Request::post(uri.clone())
+			.header(hyper::header::CONTENT_TYPE, "application/json")
+			.body(hyper::Body::from(body))
+			.expect("The URI is valid and the header is static; qed");
+
+		// Failing exports are expected while the collector is unavailable.
+		match client.request(request).await {
+			Ok(response) if response.status().is_success() => {}
+			Ok(response) => log::debug!(
+				target: LOG_TARGET,
+				"Collector rejected a batch of {} spans with status {}",
+				batch.len(),
+				response.status(),
+			),
+			Err(e) => log::debug!(target: LOG_TARGET, "Failed to export a batch of {} spans: {}", batch.len(), e),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn traces_in(capacity: usize) -> (Arc<TracesIn>, mpsc::Receiver<FinishedSpan>) {
+		let (tx, rx) = mpsc::channel(capacity);
+		(Arc::new(TracesIn { spans: Mutex::new(tx) }), rx)
+	}
+
+	#[test]
+	fn spans_are_queued_when_dropped() {
+		let (traces_in, mut rx) = traces_in(8);
+		let trace_id = NonZeroU128::new(0xabcd).unwrap();
+
+		let mut root = traces_in.span(trace_id, "root");
+		root.add_int_tag("para-id", 100);
+		let mut child = root.child("child");
+		child.add_string_tag("candidate-hash", "0x01");
+		child.add_follows_from(&root);
+		let (root_id, child_id) = (root.span_id, child.span_id);
+
+		drop(child);
+		drop(root);
+
+		let child = rx.try_next().unwrap().unwrap();
+		assert_eq!(child.name, "child");
+		assert_eq!(child.trace_id, trace_id);
+		assert_eq!(child.span_id, child_id);
+		assert_eq!(child.parent_span_id, Some(root_id));
+		assert_eq!(child.attributes, vec![("candidate-hash", Value::String("0x01".to_owned()))]);
+		assert_eq!(child.links, vec![(trace_id, root_id)]);
+		assert!(child.start_unix_nanos <= child.end_unix_nanos);
+
+		let root = rx.try_next().unwrap().unwrap();
+		assert_eq!(root.name, "root");
+		assert_eq!(root.parent_span_id, None);
+		assert_eq!(root.attributes, vec![("para-id", Value::Int(100))]);
+	}
+
+	#[test]
+	fn spans_are_dropped_when_the_queue_is_full() {
+		let (traces_in, mut rx) = traces_in(0);
+		let trace_id = NonZeroU128::new(1).unwrap();
+
+		// A channel has one slot per sender on top of its capacity.
+		drop(traces_in.span(trace_id, "first"));
+		drop(traces_in.span(trace_id, "second"));
+
+		assert_eq!(rx.try_next().unwrap().unwrap().name, "first");
+		assert!(rx.try_next().is_err());
+	}
+
+	#[test]
+	fn batch_encoding() {
+		let span = FinishedSpan {
+			trace_id: NonZeroU128::new(0x1f).unwrap(),
+			span_id: NonZeroU64::new(0x2e).unwrap(),
+			parent_span_id: Some(NonZeroU64::new(0x3d).unwrap()),
+			name: "candidate-backing",
+			start_unix_nanos: 1,
+			end_unix_nanos: 2,
+			attributes: vec![("para-id", Value::Int(2000)), ("relay-parent", Value::String("0x00".to_owned()))],
+			links: vec![(NonZeroU128::new(0x4c).unwrap(), NonZeroU64::new(0x5b).unwrap())],
+		};
+
+		let encoded = encode_batch("polkadot-alice", &[span]);
+
+		assert_eq!(
+			encoded["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+			"polkadot-alice",
+		);
+		assert_eq!(
+			encoded["resourceSpans"][0]["scopeSpans"][0]["spans"][0],
+			json!({
+				"traceId": "0000000000000000000000000000001f",
+				"spanId": "000000000000002e",
+				"parentSpanId": "000000000000003d",
+				"name": "candidate-backing",
+				"kind": 1,
+				"startTimeUnixNano": "1",
+				"endTimeUnixNano": "2",
+				"attributes": [
+					{ "key": "para-id", "value": { "intValue": "2000" } },
+					{ "key": "relay-parent", "value": { "stringValue": "0x00" } },
+				],
+				"links": [{ "traceId": "0000000000000000000000000000004c", "spanId": "000000000000005b" }],
+			}),
+		);
+	}
+}
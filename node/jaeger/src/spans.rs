@@ -163,6 +163,8 @@ pub enum Stage {
 pub enum Span {
 	/// Running with jaeger being enabled.
 	Enabled(mick_jaeger::Span),
+	/// Running with the OTLP exporter being enabled.
+	Otlp(crate::otlp::Span),
 	/// Running with jaeger disabled.
 	Disabled,
 }
@@ -240,7 +242,7 @@ impl Span {
 	/// Creates a new span builder based on anything that can be lazily evaluated
 	/// to and identifier.
 	pub fn new<I: LazyIdent>(identifier: I, span_name: &'static str) -> Span {
-		let mut span = INSTANCE.read_recursive().span(|| <I as LazyIdent>::eval(&identifier), span_name).into();
+		let mut span = INSTANCE.read_recursive().span(|| <I as LazyIdent>::eval(&identifier), span_name);
 		<I as LazyIdent>::extra_tags(&identifier, &mut span);
 		span
 	}
@@ -257,13 +259,13 @@ impl Span {
 				},
 				span_name,
 			)
-			.into()
 	}
 
 	/// Derive a child span from `self`.
 	pub fn child(&self, name: &'static str) -> Self {
 		match self {
 			Self::Enabled(inner) => Self::Enabled(inner.child(name)),
+			Self::Otlp(inner) => Self::Otlp(inner.child(name)),
 			Self::Disabled => Self::Disabled,
 		}
 	}
@@ -350,6 +352,7 @@ impl Span {
 	pub fn add_follows_from(&mut self, other: &Self) {
 		match (self, other) {
 			(Self::Enabled(ref mut inner), Self::Enabled(ref other_inner)) => inner.add_follows_from(&other_inner),
+			(Self::Otlp(ref mut inner), Self::Otlp(ref other_inner)) => inner.add_follows_from(&other_inner),
 			_ => {}
 		}
 	}
@@ -372,6 +375,7 @@ impl Span {
 	pub fn add_string_tag<V: ToString>(&mut self, tag: &'static str, val: V) {
 		match self {
 			Self::Enabled(ref mut inner) => inner.add_string_tag(tag, val.to_string().as_str()),
+			Self::Otlp(ref mut inner) => inner.add_string_tag(tag, val.to_string().as_str()),
 			Self::Disabled => {}
 		}
 	}
@@ -380,6 +384,7 @@ impl Span {
 	pub fn add_string_fmt_debug_tag<V: fmt::Debug>(&mut self, tag: &'static str, val: V) {
 		match self {
 			Self::Enabled(ref mut inner) => inner.add_string_tag(tag, format!("{:?}", val).as_str()),
+			Self::Otlp(ref mut inner) => inner.add_string_tag(tag, format!("{:?}", val).as_str()),
 			Self::Disabled => {}
 		}
 	}
@@ -387,6 +392,7 @@ impl Span {
 	pub fn add_int_tag(&mut self, tag: &'static str, value: i64) {
 		match self {
 			Self::Enabled(ref mut inner) => inner.add_int_tag(tag, value),
+			Self::Otlp(ref mut inner) => inner.add_int_tag(tag, value),
 			Self::Disabled => {}
 		}
 	}
@@ -394,6 +400,7 @@ impl Span {
 	pub fn add_uint_tag(&mut self, tag: &'static str, value: u64) {
 		match self {
 			Self::Enabled(ref mut inner) => inner.add_int_tag(tag, value as i64),
+			Self::Otlp(ref mut inner) => inner.add_int_tag(tag, value as i64),
 			Self::Disabled => {}
 		}
 	}
@@ -402,7 +409,7 @@ impl Span {
 	/// in order to avoid computational overhead.
 	pub const fn is_enabled(&self) -> bool {
 		match self {
-			Span::Enabled(_) => true,
+			Span::Enabled(_) | Span::Otlp(_) => true,
 			_ => false,
 		}
 	}
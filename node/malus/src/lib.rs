@@ -36,6 +36,23 @@ pub trait MsgFilter: Send + Sync + Clone + 'static {
 		Some(msg)
 	}
 
+	/// Intercept messages that are to be received by the subsystem.
+	///
+	/// Unlike `filter_in`, this has access to the unfiltered sender of the
+	/// subsystem, to issue requests of its own or to answer requests in place
+	/// of the subsystem. Defaults to `filter_in`.
+	fn intercept_in<Sender>(
+		&self,
+		sender: &mut Sender,
+		msg: FromOverseer<Self::Message>,
+	) -> Option<FromOverseer<Self::Message>>
+	where
+		Sender: overseer::SubsystemSender<AllMessages> + Clone + Send + 'static,
+	{
+		let _ = sender;
+		self.filter_in(msg)
+	}
+
 	/// Modify outgoing messages.
 	fn filter_out(&self, msg: AllMessages) -> Option<AllMessages> {
 		Some(msg)
@@ -109,6 +126,7 @@ where
 	Context: overseer::SubsystemContext + SubsystemContext,
 	Fil: MsgFilter<Message = <Context as overseer::SubsystemContext>::Message>,
 	<Context as overseer::SubsystemContext>::AllMessages: From<<Context as overseer::SubsystemContext>::Message>,
+	<Context as overseer::SubsystemContext>::Sender: Clone,
 {
 	type Message = <Context as overseer::SubsystemContext>::Message;
 	type Sender = FilteredSender<<Context as overseer::SubsystemContext>::Sender, Fil>;
@@ -121,7 +139,7 @@ where
 			match self.inner.try_recv().await? {
 				None => return Ok(None),
 				Some(msg) => {
-					if let Some(msg) = self.message_filter.filter_in(msg) {
+					if let Some(msg) = self.message_filter.intercept_in(&mut self.sender.inner, msg) {
 						return Ok(Some(msg));
 					}
 				}
@@ -132,7 +150,7 @@ where
 	async fn recv(&mut self) -> SubsystemResult<FromOverseer<Self::Message>> {
 		loop {
 			let msg = self.inner.recv().await?;
-			if let Some(msg) = self.message_filter.filter_in(msg) {
+			if let Some(msg) = self.message_filter.intercept_in(&mut self.sender.inner, msg) {
 				return Ok(msg);
 			}
 		}
@@ -159,6 +177,13 @@ where
 	}
 }
 
+/// The filtered subsystem may only send the messages it declares in the overseer.
+impl<Context, Fil, X> overseer::SendsTo<X> for FilteredContext<Context, Fil>
+where
+	Context: overseer::SubsystemContext,
+	overseer::OverseerSubsystemContext<<Context as overseer::SubsystemContext>::Message>: overseer::SendsTo<X>,
+{}
+
 /// A subsystem to which incoming and outgoing filters are applied.
 pub struct FilteredSubsystem<Sub, Fil> {
 	subsystem: Sub,
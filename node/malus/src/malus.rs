@@ -0,0 +1,84 @@
+// Copyright 2017-2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node.
+//!
+//! Runs a regular node, with the subsystems of the selected variant
+//! wrapped to expose bad behavior, for usage with `simnet`/Gurke
+//! and local testnets.
+
+use color_eyre::eyre;
+use polkadot_cli::{service::OverseerGen, Cli, RunCmd};
+use structopt::StructOpt;
+
+mod variants;
+
+use variants::*;
+
+/// A node variant and its configuration.
+#[derive(Debug, StructOpt)]
+struct VariantCmd {
+	/// The probability in percent to misbehave, decided for each message.
+	#[structopt(long, default_value = "100")]
+	percentage: f64,
+
+	#[structopt(flatten)]
+	run: RunCmd,
+}
+
+#[derive(Debug, StructOpt)]
+enum NemesisVariant {
+	/// Back and approve invalid candidates, e.g. the ones of `suggest-garbage-candidate`.
+	BackGarbageCandidate(VariantCmd),
+	/// Second and back garbage candidates in place of the collations received.
+	SuggestGarbageCandidate(VariantCmd),
+	/// Report valid candidates as invalid, refusing to back and approve them.
+	DisputeValidCandidates(VariantCmd),
+	/// Do not serve erasure chunks or available data to other validators.
+	WithholdChunks(VariantCmd),
+	/// Check approvals, but never send them.
+	NoApprovals(VariantCmd),
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Malus - the nemesis of polkadot.")]
+struct MalusCli {
+	#[structopt(subcommand)]
+	variant: NemesisVariant,
+}
+
+fn run_variant<G: OverseerGen>(cmd: VariantCmd, variant: impl FnOnce(Chance) -> G) -> eyre::Result<()> {
+	let chance = Chance::new(cmd.percentage)?;
+	let cli = Cli { subcommand: None, run: cmd.run };
+	polkadot_cli::run_node(cli, variant(chance))?;
+	Ok(())
+}
+
+fn main() -> eyre::Result<()> {
+	color_eyre::install()?;
+	match MalusCli::from_args().variant {
+		NemesisVariant::BackGarbageCandidate(cmd) =>
+			run_variant(cmd, |chance| BackGarbageCandidate { chance }),
+		NemesisVariant::SuggestGarbageCandidate(cmd) =>
+			run_variant(cmd, |chance| SuggestGarbageCandidate { chance }),
+		NemesisVariant::DisputeValidCandidates(cmd) =>
+			run_variant(cmd, |chance| DisputeValidCandidates { chance }),
+		NemesisVariant::WithholdChunks(cmd) =>
+			run_variant(cmd, |chance| WithholdChunks { chance }),
+		NemesisVariant::NoApprovals(cmd) =>
+			run_variant(cmd, |chance| NoApprovals { chance }),
+	}
+}
@@ -0,0 +1,64 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Backs and approves invalid candidates, such as the ones
+//! suggested by `suggest-garbage-candidate` nodes.
+//!
+//! Valid candidates are treated as usual.
+
+use polkadot_cli::{
+	create_default_subsystems,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerGen, OverseerGenArgs, Handle, ParachainHost, ProvideRuntimeApi,
+		SpawnNamed,
+	},
+};
+
+use malus::*;
+
+use std::sync::Arc;
+
+use super::common::{Chance, FakeValidation, ReplaceValidationResult};
+
+/// Generates an overseer which reports invalid candidates as valid.
+pub(crate) struct BackGarbageCandidate {
+	pub(crate) chance: Chance,
+}
+
+impl OverseerGen for BackGarbageCandidate {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, Handle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let spawner = args.spawner.clone();
+		let leaves = args.leaves.clone();
+		let runtime_client = args.runtime_client.clone();
+		let registry = args.registry.clone();
+
+		let fake_valid = ReplaceValidationResult::new(FakeValidation::Valid, self.chance, spawner.clone());
+		let all_subsystems = create_default_subsystems(args)?
+			.map_candidate_validation(|subsystem| FilteredSubsystem::new(subsystem, fake_valid));
+
+		Overseer::new(leaves, all_subsystems, registry, runtime_client, spawner)
+			.map_err(|e| e.into())
+	}
+}
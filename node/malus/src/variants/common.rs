@@ -0,0 +1,307 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Building blocks shared across the variants.
+
+use color_eyre::eyre;
+use futures::{channel::oneshot, Future};
+use rand::distributions::{Bernoulli, Distribution};
+
+use polkadot_node_primitives::{InvalidCandidate, SpawnNamed, ValidationResult};
+use polkadot_node_subsystem::{
+	messages::{CandidateValidationMessage, ValidationFailed},
+	SubsystemSender,
+};
+use polkadot_node_subsystem_util::request_persisted_validation_data;
+use polkadot_primitives::v1::{
+	CandidateCommitments, CandidateDescriptor, OccupiedCoreAssumption, PersistedValidationData,
+};
+
+use malus::*;
+
+pub(crate) const LOG_TARGET: &str = "parachain::malus";
+
+/// Decides for each message whether to misbehave.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Chance(Bernoulli);
+
+impl Chance {
+	/// Misbehave with the given probability, in percent.
+	pub(crate) fn new(percentage: f64) -> eyre::Result<Self> {
+		Bernoulli::new(percentage / 100.0)
+			.map(Self)
+			.map_err(|_| eyre::eyre!("Percentage must be between 0 and 100, got {}", percentage))
+	}
+
+	/// Misbehave on every message.
+	pub(crate) fn always() -> Self {
+		Self(Bernoulli::from_ratio(1, 1).expect("A ratio of one is a valid probability; qed"))
+	}
+
+	pub(crate) fn misbehave(&self) -> bool {
+		self.0.sample(&mut rand::thread_rng())
+	}
+}
+
+/// The commitments of a candidate which does not advance its para.
+///
+/// Garbage candidates are announced with these commitments, such that they
+/// match the validation outcome faked by colluding nodes.
+pub(crate) fn garbage_commitments(validation_data: &PersistedValidationData) -> CandidateCommitments {
+	CandidateCommitments {
+		upward_messages: Vec::new(),
+		horizontal_messages: Vec::new(),
+		new_validation_code: None,
+		head_data: validation_data.parent_head.clone(),
+		processed_downward_messages: 0,
+		hrmp_watermark: validation_data.relay_parent_number,
+	}
+}
+
+/// Fetch the persisted validation data the candidate with the given descriptor was built on.
+pub(crate) async fn fetch_validation_data(
+	sender: &mut impl SubsystemSender,
+	descriptor: &CandidateDescriptor,
+) -> Option<PersistedValidationData> {
+	for assumption in &[OccupiedCoreAssumption::Included, OccupiedCoreAssumption::TimedOut] {
+		let rx = request_persisted_validation_data(
+			descriptor.relay_parent,
+			descriptor.para_id,
+			*assumption,
+			sender,
+		).await;
+
+		match rx.await {
+			Ok(Ok(Some(validation_data)))
+				if validation_data.hash() == descriptor.persisted_validation_data_hash =>
+				return Some(validation_data),
+			_ => continue,
+		}
+	}
+
+	None
+}
+
+/// The outcome reported in place of the actual validation result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FakeValidation {
+	/// Report invalid candidates as valid, with the commitments of garbage candidates.
+	Valid,
+	/// Report all candidates as invalid.
+	Invalid,
+}
+
+/// Replaces the result of candidate validation, as used for backing,
+/// approval checking and dispute participation alike.
+#[derive(Clone)]
+pub(crate) struct ReplaceValidationResult<Spawner> {
+	fake: FakeValidation,
+	chance: Chance,
+	spawner: Spawner,
+}
+
+impl<Spawner> ReplaceValidationResult<Spawner> {
+	pub(crate) fn new(fake: FakeValidation, chance: Chance, spawner: Spawner) -> Self {
+		Self { fake, chance, spawner }
+	}
+}
+
+impl<Spawner> ReplaceValidationResult<Spawner>
+where
+	Spawner: SpawnNamed + Clone + 'static,
+{
+	/// Let the candidate be validated, but report an invalid candidate as
+	/// valid if the validation data it was built on can be obtained.
+	fn validate_as_valid(
+		&self,
+		descriptor: &CandidateDescriptor,
+		tx: oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
+		validation_data: impl Future<Output = Option<PersistedValidationData>> + Send + 'static,
+	) -> oneshot::Sender<Result<ValidationResult, ValidationFailed>> {
+		let (real_tx, real_rx) = oneshot::channel();
+		let candidate_para = descriptor.para_id;
+
+		self.spawner.spawn("malus-fake-valid", Box::pin(async move {
+			let result = match real_rx.await {
+				Ok(Ok(ValidationResult::Invalid(reason))) => match validation_data.await {
+					Some(validation_data) => {
+						tracing::info!(
+							target: LOG_TARGET,
+							para_id = ?candidate_para,
+							?reason,
+							"Reporting invalid candidate as valid",
+						);
+						Ok(ValidationResult::Valid(garbage_commitments(&validation_data), validation_data))
+					}
+					None => Ok(ValidationResult::Invalid(reason)),
+				},
+				Ok(result) => result,
+				Err(_) => return,
+			};
+			let _ = tx.send(result);
+		}));
+
+		real_tx
+	}
+}
+
+impl<Spawner> MsgFilter for ReplaceValidationResult<Spawner>
+where
+	Spawner: SpawnNamed + Clone + 'static,
+{
+	type Message = CandidateValidationMessage;
+
+	fn intercept_in<Sender>(
+		&self,
+		sender: &mut Sender,
+		msg: FromOverseer<Self::Message>,
+	) -> Option<FromOverseer<Self::Message>>
+	where
+		Sender: overseer::SubsystemSender<AllMessages> + Clone + Send + 'static,
+	{
+		let msg = match msg {
+			FromOverseer::Communication { msg } if self.chance.misbehave() => msg,
+			msg => return Some(msg),
+		};
+
+		let msg = match (self.fake, msg) {
+			(FakeValidation::Invalid, CandidateValidationMessage::ValidateFromChainState(descriptor, _, tx)) |
+			(FakeValidation::Invalid, CandidateValidationMessage::ValidateFromExhaustive(_, _, descriptor, _, tx)) => {
+				tracing::info!(
+					target: LOG_TARGET,
+					para_id = ?descriptor.para_id,
+					"Reporting candidate as invalid",
+				);
+				let _ = tx.send(Ok(ValidationResult::Invalid(InvalidCandidate::InvalidOutputs)));
+				return None
+			}
+			(FakeValidation::Valid, CandidateValidationMessage::ValidateFromChainState(descriptor, pov, tx)) => {
+				let mut sender = sender.clone();
+				let candidate = descriptor.clone();
+				let validation_data = async move { fetch_validation_data(&mut sender, &candidate).await };
+				let tx = self.validate_as_valid(&descriptor, tx, validation_data);
+				CandidateValidationMessage::ValidateFromChainState(descriptor, pov, tx)
+			}
+			(FakeValidation::Valid, CandidateValidationMessage::ValidateFromExhaustive(
+				validation_data,
+				validation_code,
+				descriptor,
+				pov,
+				tx,
+			)) => {
+				let known_validation_data = validation_data.clone();
+				let tx = self.validate_as_valid(&descriptor, tx, async move { Some(known_validation_data) });
+				CandidateValidationMessage::ValidateFromExhaustive(
+					validation_data,
+					validation_code,
+					descriptor,
+					pov,
+					tx,
+				)
+			}
+			(_, msg @ CandidateValidationMessage::PreCheck(..)) => msg,
+		};
+
+		Some(FromOverseer::Communication { msg })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use assert_matches::assert_matches;
+	use futures::executor;
+	use polkadot_node_primitives::{BlockData, PoV};
+	use polkadot_primitives::v1::ValidationCode;
+	use sp_core::testing::TaskExecutor;
+	use std::sync::Arc;
+
+	type ValidationResponse = oneshot::Receiver<Result<ValidationResult, ValidationFailed>>;
+
+	fn validate_from_exhaustive(
+		validation_data: PersistedValidationData,
+	) -> (FromOverseer<CandidateValidationMessage>, ValidationResponse) {
+		let (tx, rx) = oneshot::channel();
+		let msg = CandidateValidationMessage::ValidateFromExhaustive(
+			validation_data,
+			ValidationCode(Vec::new()),
+			Default::default(),
+			Arc::new(PoV { block_data: BlockData(Vec::new()) }),
+			tx,
+		);
+
+		(FromOverseer::Communication { msg }, rx)
+	}
+
+	fn replace_validation_result(fake: FakeValidation, chance: Chance) -> ReplaceValidationResult<TaskExecutor> {
+		ReplaceValidationResult::new(fake, chance, TaskExecutor::new())
+	}
+
+	#[test]
+	fn reports_candidates_as_invalid() {
+		let filter = replace_validation_result(FakeValidation::Invalid, Chance::always());
+		let (mut sender, _) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let (msg, rx) = validate_from_exhaustive(Default::default());
+
+		assert!(filter.intercept_in(&mut sender, msg).is_none());
+		assert_matches!(
+			executor::block_on(rx),
+			Ok(Ok(ValidationResult::Invalid(InvalidCandidate::InvalidOutputs)))
+		);
+	}
+
+	#[test]
+	fn reports_invalid_candidates_as_valid() {
+		let filter = replace_validation_result(FakeValidation::Valid, Chance::always());
+		let (mut sender, _) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let validation_data = PersistedValidationData { relay_parent_number: 5, ..Default::default() };
+		let (msg, rx) = validate_from_exhaustive(validation_data.clone());
+
+		let real_tx = assert_matches!(
+			filter.intercept_in(&mut sender, msg),
+			Some(FromOverseer::Communication {
+				msg: CandidateValidationMessage::ValidateFromExhaustive(.., tx),
+			}) => tx
+		);
+		real_tx.send(Ok(ValidationResult::Invalid(InvalidCandidate::InvalidOutputs))).unwrap();
+
+		assert_matches!(
+			executor::block_on(rx),
+			Ok(Ok(ValidationResult::Valid(commitments, data))) => {
+				assert_eq!(commitments, garbage_commitments(&validation_data));
+				assert_eq!(data, validation_data);
+			}
+		);
+	}
+
+	#[test]
+	fn passes_candidates_on_without_misbehaving() {
+		let filter = replace_validation_result(FakeValidation::Invalid, Chance::new(0.0).unwrap());
+		let (mut sender, _) = polkadot_node_subsystem_test_helpers::sender_receiver();
+		let (msg, rx) = validate_from_exhaustive(Default::default());
+
+		let tx = assert_matches!(
+			filter.intercept_in(&mut sender, msg),
+			Some(FromOverseer::Communication {
+				msg: CandidateValidationMessage::ValidateFromExhaustive(.., tx),
+			}) => tx
+		);
+		tx.send(Err(ValidationFailed("test".into()))).unwrap();
+
+		assert_matches!(executor::block_on(rx), Ok(Err(_)));
+	}
+}
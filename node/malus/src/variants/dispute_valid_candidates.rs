@@ -0,0 +1,65 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reports valid candidates as invalid, when backing as well as when
+//! checking approvals.
+//!
+//! The dispute subsystems are not enabled yet, so no disputes are raised: the node
+//! refuses to back and approve valid candidates, leaving them to honest validators.
+
+use polkadot_cli::{
+	create_default_subsystems,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerGen, OverseerGenArgs, Handle, ParachainHost, ProvideRuntimeApi,
+		SpawnNamed,
+	},
+};
+
+use malus::*;
+
+use std::sync::Arc;
+
+use super::common::{Chance, FakeValidation, ReplaceValidationResult};
+
+/// Generates an overseer which reports valid candidates as invalid.
+pub(crate) struct DisputeValidCandidates {
+	pub(crate) chance: Chance,
+}
+
+impl OverseerGen for DisputeValidCandidates {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, Handle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let spawner = args.spawner.clone();
+		let leaves = args.leaves.clone();
+		let runtime_client = args.runtime_client.clone();
+		let registry = args.registry.clone();
+
+		let fake_invalid = ReplaceValidationResult::new(FakeValidation::Invalid, self.chance, spawner.clone());
+		let all_subsystems = create_default_subsystems(args)?
+			.map_candidate_validation(|subsystem| FilteredSubsystem::new(subsystem, fake_invalid));
+
+		Overseer::new(leaves, all_subsystems, registry, runtime_client, spawner)
+			.map_err(|e| e.into())
+	}
+}
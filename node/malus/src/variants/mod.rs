@@ -0,0 +1,33 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The behavior variants of malicious nodes.
+
+mod common;
+
+mod back_garbage_candidate;
+mod dispute_valid_candidates;
+mod no_approvals;
+mod suggest_garbage_candidate;
+mod withhold_chunks;
+
+pub(crate) use self::common::Chance;
+
+pub(crate) use self::back_garbage_candidate::BackGarbageCandidate;
+pub(crate) use self::dispute_valid_candidates::DisputeValidCandidates;
+pub(crate) use self::no_approvals::NoApprovals;
+pub(crate) use self::suggest_garbage_candidate::SuggestGarbageCandidate;
+pub(crate) use self::withhold_chunks::WithholdChunks;
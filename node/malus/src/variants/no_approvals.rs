@@ -0,0 +1,130 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checks candidates as an approval checker, but never sends the approvals,
+//! leaving the candidates to be covered by no-show replacements.
+
+use polkadot_cli::{
+	create_default_subsystems,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerGen, OverseerGenArgs, Handle, ParachainHost, ProvideRuntimeApi,
+		SpawnNamed,
+	},
+};
+use polkadot_node_subsystem::messages::{ApprovalDistributionMessage, ApprovalVotingMessage};
+
+use malus::*;
+
+use std::sync::Arc;
+
+use super::common::{Chance, LOG_TARGET};
+
+/// Drops the approvals issued by approval voting.
+#[derive(Clone)]
+struct DropApprovals {
+	chance: Chance,
+}
+
+impl MsgFilter for DropApprovals {
+	type Message = ApprovalVotingMessage;
+
+	fn filter_out(&self, msg: AllMessages) -> Option<AllMessages> {
+		match msg {
+			AllMessages::ApprovalDistribution(ApprovalDistributionMessage::DistributeApproval(vote))
+				if self.chance.misbehave() =>
+			{
+				tracing::info!(
+					target: LOG_TARGET,
+					candidate_index = vote.candidate_index,
+					block_hash = ?vote.block_hash,
+					"Withholding approval",
+				);
+				None
+			}
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Generates an overseer which does not send approvals.
+pub(crate) struct NoApprovals {
+	pub(crate) chance: Chance,
+}
+
+impl OverseerGen for NoApprovals {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, Handle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let spawner = args.spawner.clone();
+		let leaves = args.leaves.clone();
+		let runtime_client = args.runtime_client.clone();
+		let registry = args.registry.clone();
+
+		let drop_approvals = DropApprovals { chance: self.chance };
+		let all_subsystems = create_default_subsystems(args)?
+			.map_approval_voting(|subsystem| FilteredSubsystem::new(subsystem, drop_approvals));
+
+		Overseer::new(leaves, all_subsystems, registry, runtime_client, spawner)
+			.map_err(|e| e.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use assert_matches::assert_matches;
+	use polkadot_node_primitives::approval::IndirectSignedApprovalVote;
+	use polkadot_primitives::v1::ValidatorIndex;
+
+	fn distribute_approval() -> AllMessages {
+		let vote = IndirectSignedApprovalVote {
+			block_hash: Default::default(),
+			candidate_index: 0,
+			validator: ValidatorIndex(0),
+			signature: Default::default(),
+		};
+		AllMessages::ApprovalDistribution(ApprovalDistributionMessage::DistributeApproval(vote))
+	}
+
+	#[test]
+	fn drops_approvals() {
+		let filter = DropApprovals { chance: Chance::always() };
+
+		assert!(filter.filter_out(distribute_approval()).is_none());
+		assert_matches!(
+			filter.filter_out(AllMessages::ApprovalDistribution(ApprovalDistributionMessage::NewBlocks(Vec::new()))),
+			Some(AllMessages::ApprovalDistribution(ApprovalDistributionMessage::NewBlocks(_)))
+		);
+	}
+
+	#[test]
+	fn passes_approvals_on_without_misbehaving() {
+		let filter = DropApprovals { chance: Chance::new(0.0).unwrap() };
+
+		assert_matches!(
+			filter.filter_out(distribute_approval()),
+			Some(AllMessages::ApprovalDistribution(ApprovalDistributionMessage::DistributeApproval(_)))
+		);
+	}
+}
@@ -0,0 +1,404 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Seconds garbage candidates in place of the collations received from
+//! collators, and backs them.
+//!
+//! The garbage candidates are well formed, i.e. they pass all checks besides
+//! validation itself, so they are backed by `back-garbage-candidate` nodes
+//! of the same backing group, included and eventually rejected by honest
+//! approval checkers.
+
+use polkadot_cli::{
+	create_default_subsystems,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerGen, OverseerGenArgs, Handle, ParachainHost, ProvideRuntimeApi,
+		SpawnNamed,
+	},
+};
+use polkadot_node_primitives::{AvailableData, BlockData, PoV};
+use polkadot_node_subsystem::{messages::CandidateBackingMessage, SubsystemSender};
+use polkadot_node_subsystem_util::request_validators;
+use polkadot_primitives::v1::{
+	collator_signature_payload, CandidateDescriptor, CandidateHash, CandidateReceipt, CollatorPair,
+};
+use sp_core::Pair;
+
+use malus::*;
+
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::common::{
+	fetch_validation_data, garbage_commitments, Chance, FakeValidation, ReplaceValidationResult, LOG_TARGET,
+};
+
+/// The size of the block data of garbage candidates.
+const GARBAGE_BLOCK_DATA_SIZE: usize = 64;
+
+/// Craft a garbage candidate for the para and relay parent of an honest candidate.
+///
+/// The candidate is signed by a freshly generated collator key and commits to
+/// not advancing the para, see [`garbage_commitments`].
+async fn craft_garbage_candidate(
+	sender: &mut impl SubsystemSender,
+	honest: &CandidateDescriptor,
+) -> Option<(CandidateReceipt, PoV)> {
+	let validation_data = fetch_validation_data(sender, honest).await?;
+	let n_validators = request_validators(honest.relay_parent, sender).await.await.ok()?.ok()?.len();
+
+	let pov = PoV {
+		block_data: BlockData((0..GARBAGE_BLOCK_DATA_SIZE).map(|_| rand::random()).collect()),
+	};
+	let pov_hash = pov.hash();
+
+	let commitments = garbage_commitments(&validation_data);
+	let available_data = AvailableData {
+		pov: Arc::new(pov.clone()),
+		validation_data,
+	};
+	let chunks = polkadot_erasure_coding::obtain_chunks_v1(n_validators, &available_data).ok()?;
+	let erasure_root = polkadot_erasure_coding::branches(&chunks).root();
+
+	let (collator, _) = CollatorPair::generate();
+	let signature_payload = collator_signature_payload(
+		&honest.relay_parent,
+		&honest.para_id,
+		&honest.persisted_validation_data_hash,
+		&pov_hash,
+		&honest.validation_code_hash,
+	);
+
+	let candidate = CandidateReceipt {
+		commitments_hash: commitments.hash(),
+		descriptor: CandidateDescriptor {
+			signature: collator.sign(&signature_payload),
+			para_id: honest.para_id,
+			relay_parent: honest.relay_parent,
+			collator: collator.public(),
+			persisted_validation_data_hash: honest.persisted_validation_data_hash,
+			pov_hash,
+			erasure_root,
+			para_head: commitments.head_data.hash(),
+			validation_code_hash: honest.validation_code_hash,
+		},
+	};
+
+	Some((candidate, pov))
+}
+
+/// Replaces the collations to be seconded with garbage candidates.
+#[derive(Clone)]
+struct ReplaceCollations<Spawner> {
+	chance: Chance,
+	spawner: Spawner,
+	/// Garbage candidates sent to candidate backing, to be passed on.
+	crafted: Arc<Mutex<HashSet<CandidateHash>>>,
+}
+
+impl<Spawner> MsgFilter for ReplaceCollations<Spawner>
+where
+	Spawner: SpawnNamed + Clone + 'static,
+{
+	type Message = CandidateBackingMessage;
+
+	fn intercept_in<Sender>(
+		&self,
+		sender: &mut Sender,
+		msg: FromOverseer<Self::Message>,
+	) -> Option<FromOverseer<Self::Message>>
+	where
+		Sender: overseer::SubsystemSender<AllMessages> + Clone + Send + 'static,
+	{
+		let (relay_parent, candidate) = match msg {
+			FromOverseer::Communication { msg: CandidateBackingMessage::Second(relay_parent, candidate, pov) } => {
+				if self.crafted.lock().remove(&candidate.hash()) || !self.chance.misbehave() {
+					return Some(FromOverseer::Communication {
+						msg: CandidateBackingMessage::Second(relay_parent, candidate, pov),
+					})
+				}
+				(relay_parent, candidate)
+			}
+			msg => return Some(msg),
+		};
+
+		let mut sender = sender.clone();
+		let crafted = self.crafted.clone();
+		self.spawner.spawn("malus-suggest-garbage-candidate", Box::pin(async move {
+			match craft_garbage_candidate(&mut sender, &candidate.descriptor).await {
+				Some((garbage, pov)) => {
+					tracing::info!(
+						target: LOG_TARGET,
+						honest_candidate_hash = ?candidate.hash(),
+						garbage_candidate_hash = ?garbage.hash(),
+						para_id = ?garbage.descriptor.para_id,
+						"Seconding garbage candidate",
+					);
+					crafted.lock().insert(garbage.hash());
+					sender.send_message(CandidateBackingMessage::Second(relay_parent, garbage, pov).into()).await;
+				}
+				None => tracing::warn!(
+					target: LOG_TARGET,
+					candidate_hash = ?candidate.hash(),
+					"Failed to craft a garbage candidate, dropping the collation",
+				),
+			}
+		}));
+
+		None
+	}
+}
+
+/// Generates an overseer which seconds and backs garbage candidates.
+pub(crate) struct SuggestGarbageCandidate {
+	pub(crate) chance: Chance,
+}
+
+impl OverseerGen for SuggestGarbageCandidate {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, Handle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let spawner = args.spawner.clone();
+		let leaves = args.leaves.clone();
+		let runtime_client = args.runtime_client.clone();
+		let registry = args.registry.clone();
+
+		let replace_collations = ReplaceCollations {
+			chance: self.chance,
+			spawner: spawner.clone(),
+			crafted: Default::default(),
+		};
+		// The garbage candidates must pass our own validation to be seconded.
+		let fake_valid = ReplaceValidationResult::new(FakeValidation::Valid, Chance::always(), spawner.clone());
+		let all_subsystems = create_default_subsystems(args)?
+			.map_candidate_backing(|subsystem| FilteredSubsystem::new(subsystem, replace_collations))
+			.map_candidate_validation(|subsystem| FilteredSubsystem::new(subsystem, fake_valid));
+
+		Overseer::new(leaves, all_subsystems, registry, runtime_client, spawner)
+			.map_err(|e| e.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use assert_matches::assert_matches;
+	use futures::{channel::mpsc, executor, FutureExt, StreamExt};
+	use futures_timer::Delay;
+	use polkadot_node_subsystem::{
+		messages::{RuntimeApiMessage, RuntimeApiRequest},
+		overseer::{AllSubsystems, HeadSupportsParachains},
+		OverseerSignal, SpawnedSubsystem, SubsystemError,
+	};
+	use polkadot_primitives::v1::{Hash, Id as ParaId, PersistedValidationData, ValidatorId};
+	use sp_core::testing::TaskExecutor;
+	use std::time::Duration;
+
+	fn replace_collations(chance: Chance) -> ReplaceCollations<TaskExecutor> {
+		ReplaceCollations {
+			chance,
+			spawner: TaskExecutor::new(),
+			crafted: Default::default(),
+		}
+	}
+
+	fn second(candidate: CandidateReceipt) -> FromOverseer<CandidateBackingMessage> {
+		let pov = PoV { block_data: BlockData(vec![1, 2, 3]) };
+		FromOverseer::Communication { msg: CandidateBackingMessage::Second(Default::default(), candidate, pov) }
+	}
+
+	#[test]
+	fn seconds_garbage_in_place_of_collations() {
+		let filter = replace_collations(Chance::always());
+		let (mut sender, mut rx) = polkadot_node_subsystem_test_helpers::sender_receiver();
+
+		let validation_data = PersistedValidationData { relay_parent_number: 5, ..Default::default() };
+		let mut honest = CandidateReceipt::default();
+		honest.descriptor.para_id = ParaId::from(1);
+		honest.descriptor.persisted_validation_data_hash = validation_data.hash();
+
+		assert!(filter.intercept_in(&mut sender, second(honest.clone())).is_none());
+
+		let garbage = executor::block_on(async {
+			assert_matches!(
+				rx.next().await,
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_,
+					RuntimeApiRequest::PersistedValidationData(para_id, _, tx),
+				))) => {
+					assert_eq!(para_id, honest.descriptor.para_id);
+					tx.send(Ok(Some(validation_data.clone()))).unwrap();
+				}
+			);
+			assert_matches!(
+				rx.next().await,
+				Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::Validators(tx)))) => {
+					let validators: Vec<ValidatorId> = (0..4)
+						.map(|_| sp_core::sr25519::Pair::generate().0.public().into())
+						.collect();
+					tx.send(Ok(validators)).unwrap();
+				}
+			);
+			assert_matches!(
+				rx.next().await,
+				Some(AllMessages::CandidateBacking(CandidateBackingMessage::Second(_, garbage, _))) => garbage
+			)
+		});
+
+		assert_ne!(garbage.hash(), honest.hash());
+		assert_eq!(garbage.descriptor.para_id, honest.descriptor.para_id);
+		assert_eq!(garbage.commitments_hash, garbage_commitments(&validation_data).hash());
+
+		// The garbage candidate is passed on to be seconded.
+		assert_matches!(
+			filter.intercept_in(&mut sender, second(garbage)),
+			Some(FromOverseer::Communication { msg: CandidateBackingMessage::Second(..) })
+		);
+	}
+
+	struct AlwaysSupportsParachains;
+
+	impl HeadSupportsParachains for AlwaysSupportsParachains {
+		fn head_supports_parachains(&self, _head: &Hash) -> bool {
+			true
+		}
+	}
+
+	// Reports the candidates it is asked to second.
+	struct ReportSeconded(mpsc::UnboundedSender<CandidateReceipt>);
+
+	impl<C> overseer::Subsystem<C, SubsystemError> for ReportSeconded
+	where
+		C: overseer::SubsystemContext<Message = CandidateBackingMessage, Signal = OverseerSignal>,
+	{
+		fn start(self, mut ctx: C) -> SpawnedSubsystem {
+			SpawnedSubsystem {
+				name: "report-seconded-subsystem",
+				future: Box::pin(async move {
+					loop {
+						match ctx.recv().await {
+							Ok(FromOverseer::Communication { msg: CandidateBackingMessage::Second(_, candidate, _) }) => {
+								let _ = self.0.unbounded_send(candidate);
+							}
+							Ok(FromOverseer::Signal(OverseerSignal::Conclude)) | Err(_) => return Ok(()),
+							Ok(_) => {}
+						}
+					}
+				}),
+			}
+		}
+	}
+
+	// Answers the runtime API requests made to craft a garbage candidate.
+	struct AnswerRuntimeApi(PersistedValidationData);
+
+	impl<C> overseer::Subsystem<C, SubsystemError> for AnswerRuntimeApi
+	where
+		C: overseer::SubsystemContext<Message = RuntimeApiMessage, Signal = OverseerSignal>,
+	{
+		fn start(self, mut ctx: C) -> SpawnedSubsystem {
+			SpawnedSubsystem {
+				name: "answer-runtime-api-subsystem",
+				future: Box::pin(async move {
+					loop {
+						match ctx.recv().await {
+							Ok(FromOverseer::Communication { msg: RuntimeApiMessage::Request(_, request) }) => match request {
+								RuntimeApiRequest::PersistedValidationData(_, _, tx) => {
+									let _ = tx.send(Ok(Some(self.0.clone())));
+								}
+								RuntimeApiRequest::Validators(tx) => {
+									let validators = (0..4)
+										.map(|_| sp_core::sr25519::Pair::generate().0.public().into())
+										.collect();
+									let _ = tx.send(Ok(validators));
+								}
+								_ => {}
+							},
+							Ok(FromOverseer::Signal(OverseerSignal::Conclude)) | Err(_) => return Ok(()),
+							Ok(_) => {}
+						}
+					}
+				}),
+			}
+		}
+	}
+
+	// The garbage candidate is sent by candidate backing to itself, which the overseer
+	// only allows for messages declared to be sent by the subsystem.
+	#[test]
+	fn seconds_garbage_through_the_overseer() {
+		let spawner = TaskExecutor::new();
+		let (seconded_tx, mut seconded_rx) = mpsc::unbounded();
+
+		let validation_data = PersistedValidationData { relay_parent_number: 5, ..Default::default() };
+		let mut honest = CandidateReceipt::default();
+		honest.descriptor.para_id = ParaId::from(1);
+		honest.descriptor.persisted_validation_data_hash = validation_data.hash();
+
+		let all_subsystems = AllSubsystems::<()>::dummy()
+			.replace_candidate_backing(FilteredSubsystem::new(
+				ReportSeconded(seconded_tx),
+				replace_collations(Chance::always()),
+			))
+			.replace_runtime_api(AnswerRuntimeApi(validation_data.clone()));
+		let (overseer, mut handle) = overseer::Overseer::new(
+			Vec::new(),
+			all_subsystems,
+			None,
+			AlwaysSupportsParachains,
+			spawner,
+		).unwrap();
+
+		executor::block_on(async move {
+			let overseer_fut = overseer.run().fuse();
+			futures::pin_mut!(overseer_fut);
+
+			let pov = PoV { block_data: BlockData(vec![1, 2, 3]) };
+			handle.send_msg_anon(CandidateBackingMessage::Second(Default::default(), honest.clone(), pov)).await;
+
+			let garbage = futures::select! {
+				_ = overseer_fut => panic!("overseer stopped unexpectedly"),
+				garbage = seconded_rx.next() => garbage.unwrap(),
+				_ = Delay::new(Duration::from_secs(5)).fuse() => panic!("no garbage candidate was seconded"),
+			};
+			assert_ne!(garbage.hash(), honest.hash());
+			assert_eq!(garbage.descriptor.para_id, honest.descriptor.para_id);
+
+			handle.stop().await;
+			assert!(overseer_fut.await.is_ok());
+		});
+	}
+
+	#[test]
+	fn passes_collations_on_without_misbehaving() {
+		let filter = replace_collations(Chance::new(0.0).unwrap());
+		let (mut sender, _) = polkadot_node_subsystem_test_helpers::sender_receiver();
+
+		assert_matches!(
+			filter.intercept_in(&mut sender, second(CandidateReceipt::default())),
+			Some(FromOverseer::Communication { msg: CandidateBackingMessage::Second(..) })
+		);
+	}
+}
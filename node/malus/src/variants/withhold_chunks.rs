@@ -0,0 +1,160 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Stores the erasure chunks and available data like an honest node,
+//! but does not serve them to other validators.
+
+use polkadot_cli::{
+	create_default_subsystems,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerGen, OverseerGenArgs, Handle, ParachainHost, ProvideRuntimeApi,
+		SpawnNamed,
+	},
+};
+use polkadot_node_subsystem::messages::{AvailabilityDistributionMessage, AvailabilityRecoveryMessage};
+
+use malus::*;
+
+use std::sync::Arc;
+
+use super::common::{Chance, LOG_TARGET};
+
+/// Drops incoming requests for erasure chunks.
+#[derive(Clone)]
+struct WithholdChunkRequests {
+	chance: Chance,
+}
+
+impl MsgFilter for WithholdChunkRequests {
+	type Message = AvailabilityDistributionMessage;
+
+	fn filter_in(&self, msg: FromOverseer<Self::Message>) -> Option<FromOverseer<Self::Message>> {
+		match msg {
+			FromOverseer::Communication { msg: AvailabilityDistributionMessage::ChunkFetchingRequest(req) }
+				if self.chance.misbehave() =>
+			{
+				tracing::info!(target: LOG_TARGET, peer = ?req.peer, "Withholding chunk");
+				None
+			}
+			FromOverseer::Communication { msg: AvailabilityDistributionMessage::BatchedChunkFetchingRequest(req) }
+				if self.chance.misbehave() =>
+			{
+				tracing::info!(target: LOG_TARGET, peer = ?req.peer, "Withholding batch of chunks");
+				None
+			}
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Drops incoming requests for the full available data.
+#[derive(Clone)]
+struct WithholdAvailableDataRequests {
+	chance: Chance,
+}
+
+impl MsgFilter for WithholdAvailableDataRequests {
+	type Message = AvailabilityRecoveryMessage;
+
+	fn filter_in(&self, msg: FromOverseer<Self::Message>) -> Option<FromOverseer<Self::Message>> {
+		match msg {
+			FromOverseer::Communication { msg: AvailabilityRecoveryMessage::AvailableDataFetchingRequest(req) }
+				if self.chance.misbehave() =>
+			{
+				tracing::info!(target: LOG_TARGET, peer = ?req.peer, "Withholding available data");
+				None
+			}
+			msg => Some(msg),
+		}
+	}
+}
+
+/// Generates an overseer which does not answer requests for chunks or available data.
+pub(crate) struct WithholdChunks {
+	pub(crate) chance: Chance,
+}
+
+impl OverseerGen for WithholdChunks {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, Handle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let spawner = args.spawner.clone();
+		let leaves = args.leaves.clone();
+		let runtime_client = args.runtime_client.clone();
+		let registry = args.registry.clone();
+
+		let withhold_chunks = WithholdChunkRequests { chance: self.chance };
+		let withhold_available_data = WithholdAvailableDataRequests { chance: self.chance };
+		let all_subsystems = create_default_subsystems(args)?
+			.map_availability_distribution(|subsystem| FilteredSubsystem::new(subsystem, withhold_chunks))
+			.map_availability_recovery(|subsystem| FilteredSubsystem::new(subsystem, withhold_available_data));
+
+		Overseer::new(leaves, all_subsystems, registry, runtime_client, spawner)
+			.map_err(|e| e.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use assert_matches::assert_matches;
+	use futures::channel::oneshot;
+	use polkadot_node_network_protocol::{
+		request_response::{v1::ChunkFetchingRequest, IncomingRequest},
+		PeerId,
+	};
+	use polkadot_node_subsystem::OverseerSignal;
+	use polkadot_primitives::v1::ValidatorIndex;
+
+	fn chunk_fetching_request() -> FromOverseer<AvailabilityDistributionMessage> {
+		let (tx, _) = oneshot::channel();
+		let req = IncomingRequest::new(
+			PeerId::random(),
+			ChunkFetchingRequest { candidate_hash: Default::default(), index: ValidatorIndex(0) },
+			tx,
+		);
+		FromOverseer::Communication { msg: AvailabilityDistributionMessage::ChunkFetchingRequest(req) }
+	}
+
+	#[test]
+	fn withholds_chunks() {
+		let filter = WithholdChunkRequests { chance: Chance::always() };
+
+		assert!(filter.filter_in(chunk_fetching_request()).is_none());
+		assert_matches!(
+			filter.filter_in(FromOverseer::Signal(OverseerSignal::Conclude)),
+			Some(FromOverseer::Signal(OverseerSignal::Conclude))
+		);
+	}
+
+	#[test]
+	fn serves_chunks_without_misbehaving() {
+		let filter = WithholdChunkRequests { chance: Chance::new(0.0).unwrap() };
+
+		assert_matches!(
+			filter.filter_in(chunk_fetching_request()),
+			Some(FromOverseer::Communication { msg: AvailabilityDistributionMessage::ChunkFetchingRequest(_) })
+		);
+	}
+}
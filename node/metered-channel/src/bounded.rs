@@ -16,10 +16,11 @@
 
 //! Metered variant of bounded mpsc channels to be able to extract metrics.
 
-use futures::{channel::mpsc, task::Poll, task::Context, sink::SinkExt, stream::Stream};
+use futures::{channel::mpsc, future::{self, Future}, task::Poll, task::Context, sink::SinkExt, stream::Stream};
 
 use std::result;
 use std::pin::Pin;
+use std::time::Instant;
 
 use super::Meter;
 
@@ -59,7 +60,9 @@ impl<T> Stream for MeteredReceiver<T> {
 	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
 		match mpsc::Receiver::poll_next(Pin::new(&mut self.inner), cx) {
 			Poll::Ready(x) => {
-				self.meter.note_received();
+				if x.is_some() {
+					self.meter.note_received();
+				}
 				Poll::Ready(x)
 			}
 			other => other,
@@ -131,6 +134,11 @@ impl<T> MeteredSender<T> {
 	}
 
 	/// Send message, wait until capacity is available.
+	///
+	/// If the channel is full, the send is accounted as blocked, together
+	/// with the time spent waiting for capacity.
+	/// The time in queue of a sampled message includes the time spent
+	/// waiting for capacity.
 	pub async fn send(&mut self, item: T) -> result::Result<(), mpsc::SendError>
 	where
 		Self: Unpin,
@@ -138,7 +146,22 @@ impl<T> MeteredSender<T> {
 		self.meter.note_sent();
 		let fut = self.inner.send(item);
 		futures::pin_mut!(fut);
-		fut.await.map_err(|e| {
+
+		let mut blocked_since = None;
+		let res = future::poll_fn(|cx| {
+			let poll = fut.as_mut().poll(cx);
+			if poll.is_pending() && blocked_since.is_none() {
+				blocked_since = Some(Instant::now());
+			}
+			poll
+		}).await;
+
+		if let Some(blocked_since) = blocked_since {
+			self.meter.note_blocked();
+			self.meter.note_blocked_time(blocked_since.elapsed());
+		}
+
+		res.map_err(|e| {
 			self.meter.retract_sent();
 			e
 		})
@@ -148,6 +171,9 @@ impl<T> MeteredSender<T> {
 	pub fn try_send(&mut self, msg: T) -> result::Result<(), mpsc::TrySendError<T>> {
 		self.meter.note_sent();
 		self.inner.try_send(msg).map_err(|e| {
+			if e.is_full() {
+				self.meter.note_blocked();
+			}
 			self.meter.retract_sent();
 			e
 		})
@@ -16,8 +16,11 @@
 
 //! Metered variant of mpsc channels to be able to extract metrics.
 
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::convert::TryFrom;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use derive_more::{Add, Display};
 
@@ -34,17 +37,43 @@ pub struct Meter {
 	sent: Arc<AtomicUsize>,
 	// Number of receives on this channel.
 	received: Arc<AtomicUsize>,
+	// Number of sends which found the channel full.
+	blocked: Arc<AtomicUsize>,
+	// Total time senders waited for capacity, in nanoseconds.
+	blocked_nanos: Arc<AtomicU64>,
+	// Send times of the sampled messages still in the channel, oldest first.
+	sent_at: Arc<Mutex<VecDeque<Instant>>>,
+	// Time spent in the channel by sampled messages received since the last drain.
+	time_in_queue: Arc<Mutex<Vec<Duration>>>,
 }
 
+/// Every how many sent messages the time in queue is sampled.
+///
+/// Send times are tracked next to the channel for sampled messages only, which
+/// keeps the overhead of measuring off the hot path of most messages. Sampled
+/// messages are matched by their position in the channel, which is exact for a
+/// single sender and an approximation for concurrent ones.
+const TIME_IN_QUEUE_SAMPLE_RATE: usize = 16;
+
+/// The maximum number of time-in-queue samples kept until they are drained.
+///
+/// Samples of messages received while the buffer is full are dropped.
+const MAX_TIME_IN_QUEUE_SAMPLES: usize = 4096;
+
 /// A readout of sizes from the meter. Note that it is possible, due to asynchrony, for received
 /// to be slightly higher than sent.
 #[derive(Debug, Add, Display, Clone, Default, PartialEq)]
-#[display(fmt = "(sent={} received={})", sent, received)]
+#[display(fmt = "(sent={} received={} blocked={})", sent, received, blocked)]
 pub struct Readout {
 	/// The amount of messages sent on the channel, in aggregate.
 	pub sent: usize,
 	/// The amount of messages received on the channel, in aggregate.
 	pub received: usize,
+	/// The amount of sends which found the channel full, either waiting
+	/// for capacity or failing, in aggregate.
+	pub blocked: usize,
+	/// The time senders waited for capacity, in aggregate.
+	pub blocked_time: Duration,
 }
 
 impl Meter {
@@ -55,20 +84,58 @@ impl Meter {
 		Readout {
 			sent: self.sent.load(Ordering::Relaxed),
 			received: self.received.load(Ordering::Relaxed),
+			blocked: self.blocked.load(Ordering::Relaxed),
+			blocked_time: Duration::from_nanos(self.blocked_nanos.load(Ordering::Relaxed)),
 		}
 	}
 
 	fn note_sent(&self) {
-		self.sent.fetch_add(1, Ordering::Relaxed);
+		if self.sent.fetch_add(1, Ordering::Relaxed) % TIME_IN_QUEUE_SAMPLE_RATE == 0 {
+			lock(&self.sent_at).push_back(Instant::now());
+		}
 	}
 
 	fn retract_sent(&self) {
-		self.sent.fetch_sub(1, Ordering::Relaxed);
+		if self.sent.fetch_sub(1, Ordering::Relaxed).wrapping_sub(1) % TIME_IN_QUEUE_SAMPLE_RATE == 0 {
+			lock(&self.sent_at).pop_back();
+		}
 	}
 
 	fn note_received(&self) {
-		self.received.fetch_add(1, Ordering::Relaxed);
+		if self.received.fetch_add(1, Ordering::Relaxed) % TIME_IN_QUEUE_SAMPLE_RATE == 0 {
+			if let Some(sent_at) = lock(&self.sent_at).pop_front() {
+				self.note_time_in_queue(sent_at);
+			}
+		}
+	}
+
+	fn note_blocked(&self) {
+		self.blocked.fetch_add(1, Ordering::Relaxed);
+	}
+
+	fn note_blocked_time(&self, blocked_time: Duration) {
+		let nanos = u64::try_from(blocked_time.as_nanos()).unwrap_or(u64::MAX);
+		self.blocked_nanos.fetch_add(nanos, Ordering::Relaxed);
+	}
+
+	fn note_time_in_queue(&self, sent_at: Instant) {
+		let mut samples = lock(&self.time_in_queue);
+		if samples.len() < MAX_TIME_IN_QUEUE_SAMPLES {
+			samples.push(sent_at.elapsed());
+		}
 	}
+
+	/// Take the time-in-queue samples of the messages received since the last call.
+	///
+	/// Only every `TIME_IN_QUEUE_SAMPLE_RATE`th sent message is sampled.
+	pub fn drain_time_in_queue(&self) -> Vec<Duration> {
+		let mut samples = lock(&self.time_in_queue);
+		std::mem::take(&mut *samples)
+	}
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+	mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 #[cfg(test)]
@@ -87,20 +154,20 @@ mod tests {
 		block_on(async move {
 			let (mut tx, mut rx) = channel::<Msg>(5);
 			let msg = Msg::default();
-			assert_eq!(rx.meter().read(), Readout { sent: 0, received: 0 });
+			assert_eq!(rx.meter().read(), Readout { sent: 0, received: 0, ..Default::default() });
 			tx.try_send(msg).unwrap();
-			assert_eq!(tx.meter().read(), Readout { sent: 1, received: 0 });
+			assert_eq!(tx.meter().read(), Readout { sent: 1, received: 0, ..Default::default() });
 			tx.try_send(msg).unwrap();
 			tx.try_send(msg).unwrap();
 			tx.try_send(msg).unwrap();
-			assert_eq!(tx.meter().read(), Readout { sent: 4, received: 0 });
+			assert_eq!(tx.meter().read(), Readout { sent: 4, received: 0, ..Default::default() });
 			rx.try_next().unwrap();
-			assert_eq!(rx.meter().read(), Readout { sent: 4, received: 1 });
+			assert_eq!(rx.meter().read(), Readout { sent: 4, received: 1, ..Default::default() });
 			rx.try_next().unwrap();
 			rx.try_next().unwrap();
-			assert_eq!(tx.meter().read(), Readout { sent: 4, received: 3 });
+			assert_eq!(tx.meter().read(), Readout { sent: 4, received: 3, ..Default::default() });
 			rx.try_next().unwrap();
-			assert_eq!(rx.meter().read(), Readout { sent: 4, received: 4 });
+			assert_eq!(rx.meter().read(), Readout { sent: 4, received: 4, ..Default::default() });
 			assert!(rx.try_next().is_err());
 		});
 	}
@@ -114,9 +181,9 @@ mod tests {
 			futures::join!(
 				async move {
 					let msg = Msg::default();
-					assert_eq!(tx.meter().read(), Readout { sent: 0, received: 0 });
+					assert_eq!(tx.meter().read(), Readout { sent: 0, received: 0, ..Default::default() });
 					tx.try_send(msg).unwrap();
-					assert_eq!(tx.meter().read(), Readout { sent: 1, received: 0 });
+					assert_eq!(tx.meter().read(), Readout { sent: 1, received: 0, ..Default::default() });
 					tx.try_send(msg).unwrap();
 					tx.try_send(msg).unwrap();
 					tx.try_send(msg).unwrap();
@@ -124,14 +191,14 @@ mod tests {
 				},
 				async move {
 					go.await.expect("Helper oneshot channel must work. qed");
-					assert_eq!(rx.meter().read(), Readout { sent: 4, received: 0 });
+					assert_eq!(rx.meter().read(), Readout { sent: 4, received: 0, ..Default::default() });
 					rx.try_next().unwrap();
-					assert_eq!(rx.meter().read(), Readout { sent: 4, received: 1 });
+					assert_eq!(rx.meter().read(), Readout { sent: 4, received: 1, ..Default::default() });
 					rx.try_next().unwrap();
 					rx.try_next().unwrap();
-					assert_eq!(rx.meter().read(), Readout { sent: 4, received: 3 });
+					assert_eq!(rx.meter().read(), Readout { sent: 4, received: 3, ..Default::default() });
 					rx.try_next().unwrap();
-					assert_eq!(dbg!(rx.meter().read()), Readout { sent: 4, received: 4 });
+					assert_eq!(dbg!(rx.meter().read()), Readout { sent: 4, received: 4, ..Default::default() });
 				}
 			)
 		});
@@ -166,6 +233,87 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn time_in_queue_is_recorded_on_receive() {
+		let (mut tx, mut rx) = channel::<Msg>(5);
+		let (utx, mut urx) = unbounded::<Msg>();
+
+		tx.try_send(Msg::default()).unwrap();
+		tx.try_send(Msg::default()).unwrap();
+		utx.unbounded_send(Msg::default()).unwrap();
+		std::thread::sleep(Duration::from_millis(10));
+		assert!(tx.meter().drain_time_in_queue().is_empty());
+
+		rx.try_next().unwrap();
+		block_on(rx.next()).unwrap();
+		urx.try_next().unwrap();
+
+		// Only the first of both messages is sampled.
+		let samples = rx.meter().drain_time_in_queue();
+		assert_eq!(samples.len(), 1);
+		assert!(samples.iter().all(|tiq| *tiq >= Duration::from_millis(10)));
+		assert!(tx.meter().drain_time_in_queue().is_empty());
+		assert_eq!(urx.meter().drain_time_in_queue().len(), 1);
+	}
+
+	#[test]
+	fn time_in_queue_is_sampled() {
+		let (utx, mut urx) = unbounded::<Msg>();
+
+		for _ in 0..(2 * TIME_IN_QUEUE_SAMPLE_RATE + 1) {
+			utx.unbounded_send(Msg::default()).unwrap();
+		}
+		while let Ok(Some(_)) = urx.try_next() {}
+
+		assert_eq!(urx.meter().read().received, 2 * TIME_IN_QUEUE_SAMPLE_RATE + 1);
+		assert_eq!(urx.meter().drain_time_in_queue().len(), 3);
+	}
+
+	#[test]
+	fn failed_send_does_not_leave_a_sample() {
+		let (utx, mut urx) = unbounded::<Msg>();
+
+		for _ in 0..TIME_IN_QUEUE_SAMPLE_RATE {
+			utx.unbounded_send(Msg::default()).unwrap();
+		}
+		urx.close();
+		// would be sampled
+		assert!(utx.unbounded_send(Msg::default()).is_err());
+
+		while let Ok(Some(_)) = urx.try_next() {}
+
+		assert_eq!(urx.meter().drain_time_in_queue().len(), 1);
+		assert!(lock(&urx.meter().sent_at).is_empty());
+	}
+
+	#[test]
+	fn full_channel_is_accounted_as_blocked() {
+		// A zero capacity channel still has one guaranteed slot per sender.
+		let (mut tx, mut rx) = channel::<Msg>(0);
+
+		tx.try_send(Msg::default()).unwrap();
+		assert!(tx.try_send(Msg::default()).unwrap_err().is_full());
+		assert_eq!(tx.meter().read(), Readout { sent: 1, received: 0, blocked: 1, blocked_time: Duration::ZERO });
+
+		block_on(async move {
+			futures::join!(
+				async {
+					tx.send(Msg::default()).await.unwrap();
+				},
+				async {
+					Delay::new(Duration::from_millis(20)).await;
+					rx.next().await.unwrap();
+					rx.next().await.unwrap();
+				}
+			);
+
+			let readout = rx.meter().read();
+			assert_eq!(readout.sent, 2);
+			assert_eq!(readout.blocked, 2);
+			assert!(readout.blocked_time >= Duration::from_millis(20));
+		});
+	}
+
 	#[test]
 	fn failed_send_does_not_inc_sent() {
 		let (mut bounded, _) = channel::<Msg>(5);
@@ -174,10 +322,10 @@ mod tests {
 		block_on(async move {
 			assert!(bounded.send(Msg::default()).await.is_err());
 			assert!(bounded.try_send(Msg::default()).is_err());
-			assert_eq!(bounded.meter().read(), Readout { sent: 0, received: 0 });
+			assert_eq!(bounded.meter().read(), Readout { sent: 0, received: 0, ..Default::default() });
 
 			assert!(unbounded.unbounded_send(Msg::default()).is_err());
-			assert_eq!(unbounded.meter().read(), Readout { sent: 0, received: 0 });
+			assert_eq!(unbounded.meter().read(), Readout { sent: 0, received: 0, ..Default::default() });
 		});
 	}
 }
@@ -59,7 +59,9 @@ impl<T> Stream for UnboundedMeteredReceiver<T> {
 	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
 		match mpsc::UnboundedReceiver::poll_next(Pin::new(&mut self.inner), cx) {
 			Poll::Ready(x) => {
-				self.meter.note_received();
+				if x.is_some() {
+					self.meter.note_received();
+				}
 				Poll::Ready(x)
 			}
 			other => other,
@@ -34,6 +34,8 @@ use std::{
 
 pub use metered_channel as metered;
 
+pub mod runtime;
+
 /// This module reexports Prometheus types and defines the [`Metrics`] trait.
 pub mod metrics {
 	/// Reexport Substrate Prometheus types.
@@ -0,0 +1,228 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics updated by the runtime.
+//!
+//! The runtime passes metric updates to the node through the host functions of
+//! `polkadot-runtime-metrics`. With the `runtime-metrics` feature enabled, [`register`]
+//! registers all metrics the runtime knows about as `parachain_runtime_*` metrics and applies
+//! the updates to them. Otherwise, it does nothing.
+//!
+//! Updates are applied on every execution of the runtime, including the re-executions of a
+//! block, so the metrics are only meant for test networks.
+
+use substrate_prometheus_endpoint::{PrometheusError, Registry};
+
+#[cfg(feature = "runtime-metrics")]
+mod enabled {
+	use std::collections::HashMap;
+
+	use polkadot_runtime_metrics::{
+		definitions, MetricDefinition, MetricKind, MetricOp, MetricUpdate,
+	};
+	use substrate_prometheus_endpoint::{
+		register, CounterVec, GaugeVec, HistogramOpts, HistogramVec, Opts, PrometheusError,
+		Registry, U64,
+	};
+
+	enum Metric {
+		Counter(CounterVec<U64>),
+		Gauge(GaugeVec<U64>),
+		Histogram(HistogramVec),
+	}
+
+	pub(super) fn register_all(registry: &Registry) -> Result<(), PrometheusError> {
+		let metrics = register_metrics(definitions::ALL, registry)?;
+
+		polkadot_runtime_metrics::set_sink(move |update| apply(&metrics, update));
+
+		Ok(())
+	}
+
+	fn register_metrics(
+		definitions: &[MetricDefinition],
+		registry: &Registry,
+	) -> Result<HashMap<Vec<u8>, Metric>, PrometheusError> {
+		let mut metrics = HashMap::new();
+
+		for definition in definitions {
+			let name = format!("parachain_runtime_{}", definition.name);
+			let metric = match definition.kind {
+				MetricKind::Counter => Metric::Counter(register(
+					CounterVec::new(Opts::new(name, definition.description), definition.labels)?,
+					registry,
+				)?),
+				MetricKind::Gauge => Metric::Gauge(register(
+					GaugeVec::new(Opts::new(name, definition.description), definition.labels)?,
+					registry,
+				)?),
+				MetricKind::Histogram(buckets) => Metric::Histogram(register(
+					HistogramVec::new(
+						HistogramOpts::new(name, definition.description).buckets(buckets.to_vec()),
+						definition.labels,
+					)?,
+					registry,
+				)?),
+			};
+
+			metrics.insert(definition.name.as_bytes().to_vec(), metric);
+		}
+
+		Ok(metrics)
+	}
+
+	fn apply(metrics: &HashMap<Vec<u8>, Metric>, update: MetricUpdate) {
+		let label_values = update.label_values.iter()
+			.map(|value| String::from_utf8_lossy(value).into_owned())
+			.collect::<Vec<_>>();
+		let label_values = label_values.iter().map(String::as_str).collect::<Vec<_>>();
+
+		// Updates are ignored rather than trusted if they don't match the metrics known to the
+		// node, e.g. because the runtime is newer than the node.
+		match (metrics.get(&update.name), update.op) {
+			(Some(Metric::Counter(counter)), MetricOp::IncrementCounter(value)) => {
+				if let Ok(counter) = counter.get_metric_with_label_values(&label_values) {
+					counter.inc_by(value);
+				}
+			}
+			(Some(Metric::Gauge(gauge)), MetricOp::SetGauge(value)) => {
+				if let Ok(gauge) = gauge.get_metric_with_label_values(&label_values) {
+					gauge.set(value);
+				}
+			}
+			(Some(Metric::Histogram(histogram)), MetricOp::ObserveHistogram(value)) => {
+				if let Ok(histogram) = histogram.get_metric_with_label_values(&label_values) {
+					histogram.observe(value as f64);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+
+		const COUNTER: MetricDefinition = MetricDefinition {
+			name: "test_counter_total",
+			description: "A counter.",
+			labels: &["kind"],
+			kind: MetricKind::Counter,
+		};
+
+		const GAUGE: MetricDefinition = MetricDefinition {
+			name: "test_gauge",
+			description: "A gauge.",
+			labels: &[],
+			kind: MetricKind::Gauge,
+		};
+
+		const HISTOGRAM: MetricDefinition = MetricDefinition {
+			name: "test_histogram",
+			description: "A histogram.",
+			labels: &[],
+			kind: MetricKind::Histogram(&[1.0, 10.0]),
+		};
+
+		fn update(
+			definition: &MetricDefinition,
+			label_values: &[&str],
+			op: MetricOp,
+		) -> MetricUpdate {
+			MetricUpdate {
+				name: definition.name.as_bytes().to_vec(),
+				label_values: label_values.iter().map(|value| value.as_bytes().to_vec()).collect(),
+				op,
+			}
+		}
+
+		#[test]
+		fn updates_are_applied_to_the_registered_metrics() {
+			let registry = Registry::new();
+			let metrics = register_metrics(&[COUNTER, GAUGE, HISTOGRAM], &registry).unwrap();
+
+			apply(&metrics, update(&COUNTER, &["new"], MetricOp::IncrementCounter(2)));
+			apply(&metrics, update(&COUNTER, &["new"], MetricOp::IncrementCounter(3)));
+			apply(&metrics, update(&GAUGE, &[], MetricOp::SetGauge(7)));
+			apply(&metrics, update(&GAUGE, &[], MetricOp::SetGauge(4)));
+			apply(&metrics, update(&HISTOGRAM, &[], MetricOp::ObserveHistogram(5)));
+
+			match &metrics[COUNTER.name.as_bytes()] {
+				Metric::Counter(counter) => assert_eq!(counter.with_label_values(&["new"]).get(), 5),
+				_ => panic!("expected a counter"),
+			}
+			match &metrics[GAUGE.name.as_bytes()] {
+				Metric::Gauge(gauge) => assert_eq!(gauge.with_label_values(&[]).get(), 4),
+				_ => panic!("expected a gauge"),
+			}
+			match &metrics[HISTOGRAM.name.as_bytes()] {
+				Metric::Histogram(histogram) => {
+					let histogram = histogram.with_label_values(&[]);
+					assert_eq!(histogram.get_sample_count(), 1);
+					assert_eq!(histogram.get_sample_sum(), 5.0);
+				}
+				_ => panic!("expected a histogram"),
+			}
+
+			let names = registry.gather().iter()
+				.map(|family| family.get_name().to_owned())
+				.collect::<Vec<_>>();
+			assert_eq!(names, vec![
+				"parachain_runtime_test_counter_total",
+				"parachain_runtime_test_gauge",
+				"parachain_runtime_test_histogram",
+			]);
+		}
+
+		#[test]
+		fn mismatched_updates_are_ignored() {
+			let registry = Registry::new();
+			let metrics = register_metrics(&[COUNTER, GAUGE], &registry).unwrap();
+
+			// Unknown metric, wrong operation and wrong number of labels.
+			apply(&metrics, update(&HISTOGRAM, &[], MetricOp::ObserveHistogram(5)));
+			apply(&metrics, update(&GAUGE, &[], MetricOp::IncrementCounter(1)));
+			apply(&metrics, update(&COUNTER, &[], MetricOp::IncrementCounter(1)));
+
+			match &metrics[GAUGE.name.as_bytes()] {
+				Metric::Gauge(gauge) => assert_eq!(gauge.with_label_values(&[]).get(), 0),
+				_ => panic!("expected a gauge"),
+			}
+			match &metrics[COUNTER.name.as_bytes()] {
+				Metric::Counter(counter) => assert_eq!(counter.with_label_values(&["new"]).get(), 0),
+				_ => panic!("expected a counter"),
+			}
+		}
+	}
+}
+
+/// Register the metrics updated by the runtime and apply the updates of all runtimes
+/// executed by this process to them.
+///
+/// Does nothing unless the `runtime-metrics` feature is enabled.
+#[cfg(feature = "runtime-metrics")]
+pub fn register(registry: &Registry) -> Result<(), PrometheusError> {
+	enabled::register_all(registry)
+}
+
+/// Register the metrics updated by the runtime and apply the updates of all runtimes
+/// executed by this process to them.
+///
+/// Does nothing unless the `runtime-metrics` feature is enabled.
+#[cfg(not(feature = "runtime-metrics"))]
+pub fn register(_registry: &Registry) -> Result<(), PrometheusError> {
+	Ok(())
+}
@@ -42,7 +42,8 @@ use polkadot_node_subsystem_util::{
 	self as util, MIN_GOSSIP_PEERS,
 };
 use polkadot_node_network_protocol::{
-	PeerId, View, v1 as protocol_v1, UnifiedReputationChange as Rep,
+	self as net_protocol, PeerId, View, v1 as protocol_v1, UnifiedReputationChange as Rep,
+	ProtocolVersion, peer_set::ValidationVersion,
 };
 
 #[cfg(test)]
@@ -84,6 +85,10 @@ struct State {
 	/// Peer view data is partially stored here, and partially inline within the [`BlockEntry`]s
 	peer_views: HashMap<PeerId, View>,
 
+	/// The validation protocol version negotiated with each peer.
+	/// Peers without an entry are assumed to speak v1.
+	peer_versions: HashMap<PeerId, ProtocolVersion>,
+
 	/// Track all our neighbors in the current gossip topology.
 	/// We're not necessarily connected to all of them.
 	gossip_peers: HashSet<PeerId>,
@@ -191,18 +196,20 @@ impl State {
 		&mut self,
 		ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage> + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
 		metrics: &Metrics,
-		event: NetworkBridgeEvent<protocol_v1::ApprovalDistributionMessage>,
+		event: NetworkBridgeEvent<net_protocol::ApprovalDistributionMessage>,
 	) {
 		match event {
-			NetworkBridgeEvent::PeerConnected(peer_id, role, _) => {
+			NetworkBridgeEvent::PeerConnected(peer_id, role, version, _) => {
 				// insert a blank view if none already present
 				tracing::trace!(
 					target: LOG_TARGET,
 					?peer_id,
 					?role,
+					?version,
 					"Peer connected",
 				);
-				self.peer_views.entry(peer_id).or_default();
+				self.peer_views.entry(peer_id.clone()).or_default();
+				self.peer_versions.insert(peer_id, version);
 			}
 			NetworkBridgeEvent::PeerDisconnected(peer_id) => {
 				tracing::trace!(
@@ -211,6 +218,7 @@ impl State {
 					"Peer disconnected",
 				);
 				self.peer_views.remove(&peer_id);
+				self.peer_versions.remove(&peer_id);
 				self.blocks.iter_mut().for_each(|(_hash, entry)| {
 					entry.known_by.remove(&peer_id);
 				})
@@ -252,7 +260,7 @@ impl State {
 				});
 			}
 			NetworkBridgeEvent::PeerMessage(peer_id, msg) => {
-				self.process_incoming_peer_message(ctx, metrics, peer_id, msg).await;
+				self.process_incoming_peer_message(ctx, metrics, peer_id, msg.into_inner()).await;
 			}
 		}
 	}
@@ -354,6 +362,7 @@ impl State {
 				metrics,
 				&mut self.blocks,
 				peer_id.clone(),
+				peer_version(&self.peer_versions, peer_id),
 				view_intersection,
 			).await;
 		}
@@ -485,6 +494,7 @@ impl State {
 			metrics,
 			&mut self.blocks,
 			peer_id.clone(),
+			peer_version(&self.peer_versions, &peer_id),
 			view,
 		).await;
 	}
@@ -736,12 +746,12 @@ impl State {
 				"Sending an assignment to peers",
 			);
 
-			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
+			send_to_peers(
+				ctx,
+				&self.peer_versions,
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
-				),
-			)).await;
+				protocol_v1::ApprovalDistributionMessage::Assignments(assignments),
+			).await;
 		}
 	}
 
@@ -981,12 +991,12 @@ impl State {
 				"Sending an approval to peers",
 			);
 
-			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
+			send_to_peers(
+				ctx,
+				&self.peer_versions,
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Approvals(approvals)
-				),
-			)).await;
+				protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
+			).await;
 		}
 	}
 
@@ -995,6 +1005,7 @@ impl State {
 		metrics: &Metrics,
 		entries: &mut HashMap<Hash, BlockEntry>,
 		peer_id: PeerId,
+		version: ProtocolVersion,
 		view: View,
 	) {
 		let is_gossip_peer = gossip_peers.contains(&peer_id);
@@ -1122,9 +1133,10 @@ impl State {
 
 			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
 				vec![peer_id.clone()],
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
-				),
+				net_protocol::ApprovalDistributionMessage::with_version(
+					version,
+					protocol_v1::ApprovalDistributionMessage::Assignments(assignments),
+				).into(),
 			)).await;
 		}
 
@@ -1139,14 +1151,39 @@ impl State {
 
 			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
 				vec![peer_id],
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Approvals(approvals)
-				),
+				net_protocol::ApprovalDistributionMessage::with_version(
+					version,
+					protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
+				).into(),
 			)).await;
 		}
 	}
 }
 
+/// The validation protocol version negotiated with a peer.
+fn peer_version(peer_versions: &HashMap<PeerId, ProtocolVersion>, peer_id: &PeerId) -> ProtocolVersion {
+	peer_versions.get(peer_id).copied().unwrap_or_else(|| ValidationVersion::V1.into())
+}
+
+/// Send a message to the given peers, in the protocol version each of them negotiated.
+async fn send_to_peers(
+	ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage> + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
+	peer_versions: &HashMap<PeerId, ProtocolVersion>,
+	peers: Vec<PeerId>,
+	message: protocol_v1::ApprovalDistributionMessage,
+) {
+	let mut peers_by_version: HashMap<ProtocolVersion, Vec<PeerId>> = HashMap::new();
+	for peer_id in peers {
+		peers_by_version.entry(peer_version(peer_versions, &peer_id)).or_default().push(peer_id);
+	}
+
+	for (version, peers) in peers_by_version {
+		ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
+			peers,
+			net_protocol::ApprovalDistributionMessage::with_version(version, message.clone()).into(),
+		)).await;
+	}
+}
 
 /// Modify the reputation of a peer based on its behavior.
 async fn modify_reputation(
@@ -40,7 +40,7 @@ mod pov_requester;
 
 /// Responding to erasure chunk requests:
 mod responder;
-use responder::{answer_chunk_request_log, answer_pov_request_log};
+use responder::{answer_chunk_request_log, answer_batched_chunk_request_log, answer_pov_request_log};
 
 mod metrics;
 /// Prometheus `Metrics` for availability distribution.
@@ -128,6 +128,11 @@ impl AvailabilityDistributionSubsystem {
 				} => {
 					answer_chunk_request_log(&mut ctx, req, &self.metrics).await
 				}
+				FromOverseer::Communication {
+					msg: AvailabilityDistributionMessage::BatchedChunkFetchingRequest(req),
+				} => {
+					answer_batched_chunk_request_log(&mut ctx, req, &self.metrics).await
+				}
 				FromOverseer::Communication {
 					msg: AvailabilityDistributionMessage::PoVFetchingRequest(req),
 				} => {
@@ -19,8 +19,11 @@
 use std::sync::Arc;
 
 use futures::channel::oneshot;
+use parity_scale_codec::Encode;
 
-use polkadot_node_network_protocol::request_response::{request::IncomingRequest, v1};
+use polkadot_node_network_protocol::request_response::{
+	request::IncomingRequest, v1, MAX_BATCHED_CHUNKS_RESPONSE_SIZE, MAX_CHUNKS_PER_BATCH,
+};
 use polkadot_primitives::v1::{CandidateHash, ValidatorIndex};
 use polkadot_node_primitives::{AvailableData, ErasureChunk};
 use polkadot_subsystem::{
@@ -83,6 +86,33 @@ where
 	}
 }
 
+/// Variant of `answer_batched_chunk_request` that does Prometheus metric and logging on errors.
+///
+/// Any errors of `answer_batched_chunk_request` will simply be logged.
+pub async fn answer_batched_chunk_request_log<Context>(
+	ctx: &mut Context,
+	req: IncomingRequest<v1::BatchedChunkFetchingRequest>,
+	metrics: &Metrics,
+)
+where
+	Context: SubsystemContext,
+{
+	let res = answer_batched_chunk_request(ctx, req).await;
+	match res {
+		Ok(found) => found.into_iter().for_each(|found|
+			metrics.on_served_chunk(if found {SUCCEEDED} else {NOT_FOUND})
+		),
+		Err(err) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				err= ?err,
+				"Serving batched chunks failed with error"
+			);
+			metrics.on_served_chunk(FAILED);
+		}
+	}
+}
+
 /// Answer an incoming PoV fetch request by querying the av store.
 ///
 /// Returns: `Ok(true)` if chunk was found and served.
@@ -148,6 +178,53 @@ where
 	Ok(result)
 }
 
+/// Answer an incoming request for multiple chunks by querying the av store.
+///
+/// Chunks are served in the requested order, until the response would exceed
+/// `MAX_BATCHED_CHUNKS_RESPONSE_SIZE`. Chunks beyond `MAX_CHUNKS_PER_BATCH` are never served.
+///
+/// Returns: For each served chunk, whether it was found.
+pub async fn answer_batched_chunk_request<Context>(
+	ctx: &mut Context,
+	req: IncomingRequest<v1::BatchedChunkFetchingRequest>,
+) -> Result<Vec<bool>>
+where
+	Context: SubsystemContext,
+{
+	let mut response_size = 0;
+	let mut chunks = Vec::new();
+	for chunk_req in req.payload.chunks.iter().take(MAX_CHUNKS_PER_BATCH) {
+		let chunk = query_chunk(ctx, chunk_req.candidate_hash, chunk_req.index).await?;
+
+		let response = match chunk {
+			None => v1::ChunkFetchingResponse::NoSuchChunk,
+			Some(chunk) => v1::ChunkFetchingResponse::Chunk(chunk.into()),
+		};
+
+		response_size += response.encoded_size() as u64;
+		if response_size > MAX_BATCHED_CHUNKS_RESPONSE_SIZE {
+			break
+		}
+		chunks.push(response);
+	}
+
+	let found = chunks.iter()
+		.map(|c| matches!(c, v1::ChunkFetchingResponse::Chunk(_)))
+		.collect();
+
+	tracing::trace!(
+		target: LOG_TARGET,
+		peer = ?req.peer,
+		requested = req.payload.chunks.len(),
+		served = chunks.len(),
+		"Serving batched chunks",
+	);
+
+	req.send_response(v1::BatchedChunkFetchingResponse { chunks })
+		.map_err(|_| NonFatal::SendResponse)?;
+	Ok(found)
+}
+
 /// Query chunk from the availability store.
 async fn query_chunk<Context>(
 	ctx: &mut Context,
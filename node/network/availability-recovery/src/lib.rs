@@ -46,7 +46,7 @@ use polkadot_subsystem::{
 use polkadot_node_network_protocol::{
 	IfDisconnected,
 	request_response::{
-		self as req_res, OutgoingRequest, Recipient, Requests,
+		self as req_res, OutgoingRequest, Recipient, Requests, MAX_CHUNKS_PER_BATCH,
 		request::RequestError,
 	},
 };
@@ -75,12 +75,18 @@ struct RequestFromBackersPhase {
 	// a random shuffling of the validators from the backing group which indicates the order
 	// in which we connect to them and request the chunk.
 	shuffled_backers: Vec<ValidatorIndex>,
+	// all validators of the backing group, which are asked for batches of chunks if none of
+	// them can deliver the full data.
+	backers: Vec<ValidatorIndex>,
 }
 
 struct RequestChunksPhase {
 	// a random shuffling of the validators which indicates the order in which we connect to the validators and
 	// request the chunk from them.
 	shuffling: VecDeque<ValidatorIndex>,
+	// backers we request batches of chunks from, before requesting chunks from individual
+	// validators.
+	batch_backers: Vec<ValidatorIndex>,
 	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
 	requesting_chunks: FuturesUnordered<BoxFuture<
 		'static,
@@ -122,11 +128,13 @@ struct Interaction<S> {
 }
 
 impl RequestFromBackersPhase {
-	fn new(mut backers: Vec<ValidatorIndex>) -> Self {
-		backers.shuffle(&mut rand::thread_rng());
+	fn new(backers: Vec<ValidatorIndex>) -> Self {
+		let mut shuffled_backers = backers.clone();
+		shuffled_backers.shuffle(&mut rand::thread_rng());
 
 		RequestFromBackersPhase {
-			shuffled_backers: backers,
+			shuffled_backers,
+			backers,
 		}
 	}
 
@@ -192,12 +200,13 @@ impl RequestFromBackersPhase {
 }
 
 impl RequestChunksPhase {
-	fn new(n_validators: u32) -> Self {
+	fn new(n_validators: u32, batch_backers: Vec<ValidatorIndex>) -> Self {
 		let mut shuffling: Vec<_> = (0..n_validators).map(ValidatorIndex).collect();
 		shuffling.shuffle(&mut rand::thread_rng());
 
 		RequestChunksPhase {
 			shuffling: shuffling.into(),
+			batch_backers,
 			received_chunks: HashMap::new(),
 			requesting_chunks: FuturesUnordered::new(),
 		}
@@ -263,6 +272,99 @@ impl RequestChunksPhase {
 		}
 	}
 
+	// Request the chunks we still need from all backers in parallel, split into one batch per
+	// backer.
+	//
+	// Chunks we don't get this way are requested from the individual validators afterwards.
+	async fn request_batches_from_backers(
+		&mut self,
+		params: &InteractionParams,
+		sender: &mut impl SubsystemSender,
+	) {
+		let backers = std::mem::take(&mut self.batch_backers);
+		if backers.is_empty() {
+			return
+		}
+
+		let needed = params.threshold.saturating_sub(self.received_chunks.len());
+		let n_chunks = std::cmp::min(
+			std::cmp::min(needed, backers.len() * MAX_CHUNKS_PER_BATCH),
+			self.shuffling.len(),
+		);
+
+		// Spread the chunks evenly over the backers.
+		let mut batches: Vec<Vec<ValidatorIndex>> = vec![Vec::new(); backers.len()];
+		for (i, index) in (0..n_chunks).filter_map(|_| self.shuffling.pop_back()).enumerate() {
+			batches[i % backers.len()].push(index);
+		}
+
+		let mut requests = Vec::new();
+		let mut responses = Vec::new();
+		for (backer_index, chunk_indices) in backers.into_iter().zip(batches) {
+			if chunk_indices.is_empty() {
+				continue
+			}
+
+			let raw_request = req_res::v1::BatchedChunkFetchingRequest {
+				chunks: chunk_indices.iter().map(|index| req_res::v1::ChunkFetchingRequest {
+					candidate_hash: params.candidate_hash,
+					index: *index,
+				}).collect(),
+			};
+
+			let backer = params.validator_authority_keys[backer_index.0 as usize].clone();
+			tracing::trace!(
+				target: LOG_TARGET,
+				?backer,
+				?backer_index,
+				n_chunks = chunk_indices.len(),
+				candidate_hash = ?params.candidate_hash,
+				"Requesting batch of chunks from backer",
+			);
+
+			let (req, res) = OutgoingRequest::new(Recipient::Authority(backer), raw_request.clone());
+			requests.push(Requests::BatchedChunkFetching(req));
+			responses.push(res.map(move |res| (backer_index, raw_request, res)));
+		}
+
+		if requests.is_empty() {
+			return
+		}
+
+		sender.send_message(NetworkBridgeMessage::SendRequests(
+			requests,
+			IfDisconnected::TryConnect,
+		).into()).await;
+
+		for (backer_index, raw_request, res) in futures::future::join_all(responses).await {
+			let (received, unanswered) = match res {
+				Ok(response) => response.recombine_into_chunks(&raw_request),
+				Err(e) => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						err = ?e,
+						?backer_index,
+						"Failure requesting batch of chunks",
+					);
+					(Vec::new(), raw_request.chunks)
+				}
+			};
+
+			// Chunks the backer did not deliver are requested from their validators.
+			for (chunk_request, chunk) in received {
+				match chunk {
+					Some(chunk) if is_chunk_valid(params, &chunk) => {
+						self.received_chunks.insert(chunk.index, chunk);
+					}
+					_ => self.shuffling.push_front(chunk_request.index),
+				}
+			}
+			for chunk_request in unanswered {
+				self.shuffling.push_front(chunk_request.index);
+			}
+		}
+	}
+
 	async fn wait_for_chunks(
 		&mut self,
 		params: &InteractionParams,
@@ -272,36 +374,13 @@ impl RequestChunksPhase {
 			match request_result {
 				Ok(Some(chunk)) => {
 					// Check merkle proofs of any received chunks.
-
-					let validator_index = chunk.index;
-
-					if let Ok(anticipated_hash) = branch_hash(
-						&params.erasure_root,
-						&chunk.proof,
-						chunk.index.0 as usize,
-					) {
-						let erasure_chunk_hash = BlakeTwo256::hash(&chunk.chunk);
-
-						if erasure_chunk_hash != anticipated_hash {
-							tracing::debug!(
-								target: LOG_TARGET,
-								?validator_index,
-								"Merkle proof mismatch",
-							);
-						} else {
-							tracing::trace!(
-								target: LOG_TARGET,
-								?validator_index,
-								"Received valid chunk.",
-							);
-							self.received_chunks.insert(validator_index, chunk);
-						}
-					} else {
-						tracing::debug!(
+					if is_chunk_valid(params, &chunk) {
+						tracing::trace!(
 							target: LOG_TARGET,
-							?validator_index,
-							"Invalid Merkle proof",
+							validator_index = ?chunk.index,
+							"Received valid chunk.",
 						);
+						self.received_chunks.insert(chunk.index, chunk);
 					}
 				}
 				Ok(None) => {}
@@ -361,6 +440,8 @@ impl RequestChunksPhase {
 			}
 		}
 
+		self.request_batches_from_backers(params, sender).await;
+
 		loop {
 			if self.is_unavailable(&params) {
 				tracing::debug!(
@@ -376,8 +457,11 @@ impl RequestChunksPhase {
 				return Err(RecoveryError::Unavailable);
 			}
 
-			self.launch_parallel_requests(params, sender).await;
-			self.wait_for_chunks(params).await;
+			// Batches from backers might have delivered enough chunks already.
+			if self.received_chunks.len() < params.threshold {
+				self.launch_parallel_requests(params, sender).await;
+				self.wait_for_chunks(params).await;
+			}
 
 			// If received_chunks has more than threshold entries, attempt to recover the data.
 			// If that fails, or a re-encoding of it doesn't match the expected erasure root,
@@ -425,6 +509,39 @@ impl RequestChunksPhase {
 	}
 }
 
+// Check the Merkle proof of a received chunk.
+fn is_chunk_valid(params: &InteractionParams, chunk: &ErasureChunk) -> bool {
+	let validator_index = chunk.index;
+
+	let anticipated_hash = match branch_hash(
+		&params.erasure_root,
+		&chunk.proof,
+		chunk.index.0 as usize,
+	) {
+		Ok(hash) => hash,
+		Err(_) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?validator_index,
+				"Invalid Merkle proof",
+			);
+			return false
+		}
+	};
+
+	let erasure_chunk_hash = BlakeTwo256::hash(&chunk.chunk);
+	if erasure_chunk_hash != anticipated_hash {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?validator_index,
+			"Merkle proof mismatch",
+		);
+		return false
+	}
+
+	true
+}
+
 const fn is_unavailable(
 	received_chunks: usize,
 	requesting_chunks: usize,
@@ -487,8 +604,9 @@ impl<S: SubsystemSender> Interaction<S> {
 						Ok(data) => break Ok(data),
 						Err(RecoveryError::Invalid) => break Err(RecoveryError::Invalid),
 						Err(RecoveryError::Unavailable) => {
+							let batch_backers = std::mem::take(&mut from_backers.backers);
 							self.phase = InteractionPhase::RequestChunks(
-								RequestChunksPhase::new(self.params.validators.len() as _)
+								RequestChunksPhase::new(self.params.validators.len() as _, batch_backers)
 							)
 						}
 					}
@@ -640,7 +758,7 @@ where
 			RequestFromBackersPhase::new(group.clone())
 		))
 		.unwrap_or_else(|| InteractionPhase::RequestChunks(
-			RequestChunksPhase::new(params.validators.len() as _)
+			RequestChunksPhase::new(params.validators.len() as _, Vec::new())
 		));
 
 	let interaction = Interaction {
@@ -16,6 +16,7 @@
 
 use std::time::Duration;
 use std::sync::Arc;
+use std::collections::HashSet;
 
 use futures::{executor, future};
 use futures_timer::Delay;
@@ -180,6 +181,11 @@ impl TestState {
 		recovery_threshold(self.validators.len()).unwrap()
 	}
 
+	/// Size of the backing group, which consists of all validators.
+	fn backers(&self) -> usize {
+		self.validators.len()
+	}
+
 	fn impossibility_threshold(&self) -> usize {
 		self.validators.len() - self.threshold() + 1
 	}
@@ -295,6 +301,59 @@ impl TestState {
 		}
 	}
 
+	async fn test_batched_chunk_requests(
+		&self,
+		candidate_hash: CandidateHash,
+		virtual_overseer: &mut VirtualOverseer,
+		n_backers: usize,
+		who_has: impl Fn(usize) -> Has,
+	) {
+		// Receive the batched requests to all backers at once. The needed chunks are spread over
+		// the backers, so backers are left out if there are fewer chunks than backers.
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::NetworkBridge(
+				NetworkBridgeMessage::SendRequests(
+					requests,
+					IfDisconnected::TryConnect,
+				)
+			) => {
+				assert_eq!(requests.len(), std::cmp::min(n_backers, self.threshold()));
+
+				let mut backers = HashSet::new();
+				for request in requests {
+					assert_matches!(
+						request,
+						Requests::BatchedChunkFetching(req) => {
+							assert!(req.payload.chunks.iter().all(|c| c.candidate_hash == candidate_hash));
+							let backer_index = self.validator_authority_id
+								.iter()
+								.position(|a| Recipient::Authority(a.clone()) == req.peer)
+								.unwrap();
+							assert!(backers.insert(backer_index), "Each backer is asked only once");
+
+							let response = match who_has(backer_index) {
+								Has::No => Ok(req.payload.chunks.iter()
+									.map(|_| req_res::v1::ChunkFetchingResponse::NoSuchChunk)
+									.collect()),
+								Has::Yes => Ok(req.payload.chunks.iter()
+									.map(|c| Some(self.chunks[c.index.0 as usize].clone().into()).into())
+									.collect()),
+								Has::NetworkError(e) => Err(e),
+							};
+
+							let _ = req.pending_response.send(
+								response.map(|chunks|
+									req_res::v1::BatchedChunkFetchingResponse { chunks }.encode()
+								)
+							);
+						}
+					)
+				}
+			}
+		);
+	}
+
 	async fn test_full_data_requests(
 		&self,
 		candidate_hash: CandidateHash,
@@ -885,6 +944,14 @@ fn no_answers_in_fast_path_causes_chunk_requests() {
 
 		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
 
+		// Backers are asked for batches of the needed chunks first, one chunk each.
+		test_state.test_batched_chunk_requests(
+			candidate_hash,
+			&mut virtual_overseer,
+			test_state.backers(),
+			|_| Has::timeout(),
+		).await;
+
 		test_state.test_chunk_requests(
 			candidate_hash,
 			&mut virtual_overseer,
@@ -898,6 +965,65 @@ fn no_answers_in_fast_path_causes_chunk_requests() {
 	});
 }
 
+#[test]
+fn backers_are_asked_for_batched_chunks_in_parallel() {
+	let test_state = TestState::default();
+
+	test_harness_fast_path(|mut virtual_overseer| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+				activated: smallvec![ActivatedLeaf {
+					hash: test_state.current.clone(),
+					number: 1,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				}],
+				deactivated: smallvec![],
+			}),
+		).await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				tx,
+			)
+		).await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+
+		// All backers fail to deliver the full data in time.
+		test_state.test_full_data_requests(
+			candidate_hash,
+			&mut virtual_overseer,
+			|_| Has::timeout(),
+		).await;
+
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// The needed chunks are spread over the backers and requested at once.
+		test_state.test_batched_chunk_requests(
+			candidate_hash,
+			&mut virtual_overseer,
+			test_state.backers(),
+			|_| Has::Yes,
+		).await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		virtual_overseer
+	});
+}
+
 #[test]
 fn task_canceled_when_receivers_dropped() {
 	let test_state = TestState::default();
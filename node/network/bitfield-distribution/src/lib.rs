@@ -36,7 +36,10 @@ use polkadot_node_subsystem_util::{
 	self as util, MIN_GOSSIP_PEERS,
 };
 use polkadot_primitives::v1::{Hash, SignedAvailabilityBitfield, SigningContext, ValidatorId};
-use polkadot_node_network_protocol::{v1 as protocol_v1, PeerId, View, UnifiedReputationChange as Rep, OurView};
+use polkadot_node_network_protocol::{
+	self as net_protocol, v1 as protocol_v1, PeerId, View, UnifiedReputationChange as Rep, OurView,
+	ProtocolVersion, VersionedValidationProtocol, peer_set::ValidationVersion,
+};
 use std::collections::{HashMap, HashSet};
 
 #[cfg(test)]
@@ -61,10 +64,11 @@ struct BitfieldGossipMessage {
 }
 
 impl BitfieldGossipMessage {
-	fn into_validation_protocol(self) -> protocol_v1::ValidationProtocol {
-		protocol_v1::ValidationProtocol::BitfieldDistribution(
-			self.into_network_message()
-		)
+	fn into_validation_protocol(self, version: ProtocolVersion) -> VersionedValidationProtocol {
+		net_protocol::BitfieldDistributionMessage::with_version(
+			version,
+			self.into_network_message(),
+		).into()
 	}
 
 	fn into_network_message(self)
@@ -85,6 +89,10 @@ struct ProtocolState {
 	/// to determine what is relevant to them.
 	peer_views: HashMap<PeerId, View>,
 
+	/// The validation protocol version negotiated with each peer.
+	/// Peers without an entry are assumed to speak v1.
+	peer_versions: HashMap<PeerId, ProtocolVersion>,
+
 	/// Track all our neighbors in the current gossip topology.
 	/// We're not necessarily connected to all of them.
 	gossip_peers: HashSet<PeerId>,
@@ -149,6 +157,7 @@ impl PerRelayParentData {
 const LOG_TARGET: &str = "parachain::bitfield-distribution";
 
 /// The bitfield distribution subsystem.
+#[derive(Clone)]
 pub struct BitfieldDistribution {
 	metrics: Metrics,
 }
@@ -307,7 +316,8 @@ where
 
 	let gossip_peers = &state.gossip_peers;
 	let peer_views = &mut state.peer_views;
-	relay_message(ctx, job_data, gossip_peers, peer_views, validator, msg).await;
+	let peer_versions = &state.peer_versions;
+	relay_message(ctx, job_data, gossip_peers, peer_views, peer_versions, validator, msg).await;
 
 	metrics.on_own_bitfield_gossipped();
 }
@@ -320,6 +330,7 @@ async fn relay_message<Context>(
 	job_data: &mut PerRelayParentData,
 	gossip_peers: &HashSet<PeerId>,
 	peer_views: &mut HashMap<PeerId, View>,
+	peer_versions: &HashMap<PeerId, ProtocolVersion>,
 	validator: ValidatorId,
 	message: BitfieldGossipMessage,
 )
@@ -385,13 +396,21 @@ where
 		);
 	} else {
 		let _span = span.child("gossip");
-		ctx.send_message(
-			NetworkBridgeMessage::SendValidationMessage(
-				interested_peers,
-				message.into_validation_protocol(),
-			),
-		)
-		.await;
+
+		let mut peers_by_version: HashMap<ProtocolVersion, Vec<PeerId>> = HashMap::new();
+		for peer in interested_peers {
+			peers_by_version.entry(peer_version(peer_versions, &peer)).or_default().push(peer);
+		}
+
+		for (version, peers) in peers_by_version {
+			ctx.send_message(
+				NetworkBridgeMessage::SendValidationMessage(
+					peers,
+					message.clone().into_validation_protocol(version),
+				),
+			)
+			.await;
+		}
 	}
 }
 
@@ -509,7 +528,15 @@ where
 	metrics.on_bitfield_received();
 	one_per_validator.insert(validator.clone(), message.clone());
 
-	relay_message(ctx, job_data, &state.gossip_peers, &mut state.peer_views, validator, message).await;
+	relay_message(
+		ctx,
+		job_data,
+		&state.gossip_peers,
+		&mut state.peer_views,
+		&state.peer_versions,
+		validator,
+		message,
+	).await;
 
 	modify_reputation(ctx, origin, BENEFIT_VALID_MESSAGE_FIRST).await
 }
@@ -520,7 +547,7 @@ async fn handle_network_msg<Context>(
 	ctx: &mut Context,
 	state: &mut ProtocolState,
 	metrics: &Metrics,
-	bridge_message: NetworkBridgeEvent<protocol_v1::BitfieldDistributionMessage>,
+	bridge_message: NetworkBridgeEvent<net_protocol::BitfieldDistributionMessage>,
 )
 where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
@@ -528,15 +555,17 @@ where
 	let _timer = metrics.time_handle_network_msg();
 
 	match bridge_message {
-		NetworkBridgeEvent::PeerConnected(peerid, role, _) => {
+		NetworkBridgeEvent::PeerConnected(peerid, role, version, _) => {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?peerid,
 				?role,
+				?version,
 				"Peer connected",
 			);
 			// insert if none already present
-			state.peer_views.entry(peerid).or_default();
+			state.peer_views.entry(peerid.clone()).or_default();
+			state.peer_versions.insert(peerid, version);
 		}
 		NetworkBridgeEvent::PeerDisconnected(peerid) => {
 			tracing::trace!(
@@ -546,6 +575,7 @@ where
 			);
 			// get rid of superfluous data
 			state.peer_views.remove(&peerid);
+			state.peer_versions.remove(&peerid);
 		}
 		NetworkBridgeEvent::NewGossipTopology(peers) => {
 			let newly_added: Vec<PeerId> = peers.difference(&state.gossip_peers).cloned().collect();
@@ -574,7 +604,7 @@ where
 			handle_our_view_change(state, view);
 		}
 		NetworkBridgeEvent::PeerMessage(remote, message) =>
-			process_incoming_peer_message(ctx, state, metrics, remote, message).await,
+			process_incoming_peer_message(ctx, state, metrics, remote, message.into_inner()).await,
 	}
 }
 
@@ -689,14 +719,20 @@ where
 		.or_default()
 		.insert(validator.clone());
 
+	let version = peer_version(&state.peer_versions, &dest);
 	ctx.send_message(
 		NetworkBridgeMessage::SendValidationMessage(
 			vec![dest],
-			message.into_validation_protocol(),
+			message.into_validation_protocol(version),
 		),
 	).await;
 }
 
+/// The validation protocol version negotiated with a peer.
+fn peer_version(peer_versions: &HashMap<PeerId, ProtocolVersion>, peer: &PeerId) -> ProtocolVersion {
+	peer_versions.get(peer).copied().unwrap_or_else(|| ValidationVersion::V1.into())
+}
+
 impl<Context> overseer::Subsystem<Context, SubsystemError> for BitfieldDistribution
 where
 	Context: SubsystemContext<Message = BitfieldDistributionMessage>,
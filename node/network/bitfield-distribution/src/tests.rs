@@ -28,7 +28,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::iter::FromIterator as _;
 use assert_matches::assert_matches;
-use polkadot_node_network_protocol::{view, ObservedRole, our_view};
+use polkadot_node_network_protocol::{view, ObservedRole, our_view, Versioned};
 use polkadot_subsystem::jaeger;
 
 macro_rules! launch {
@@ -69,6 +69,7 @@ fn prewarmed_state(
 			.cloned()
 			.map(|peer| (peer, view!(relay_parent)))
 			.collect(),
+		peer_versions: Default::default(),
 		gossip_peers: peers.into_iter().collect(),
 		view: our_view!(relay_parent),
 	}
@@ -190,7 +191,7 @@ fn receive_invalid_signature() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), invalid_msg.into_network_message()),
+			NetworkBridgeEvent::PeerMessage(peer_b.clone(), Versioned::V1(invalid_msg.into_network_message())),
 		));
 
 		// reputation doesn't change due to one_job_per_validator check
@@ -200,7 +201,7 @@ fn receive_invalid_signature() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), invalid_msg_2.into_network_message()),
+			NetworkBridgeEvent::PeerMessage(peer_b.clone(), Versioned::V1(invalid_msg_2.into_network_message())),
 		));
 		// reputation change due to invalid signature
 		assert_matches!(
@@ -257,7 +258,7 @@ fn receive_invalid_validator_index() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), msg.into_network_message()),
+			NetworkBridgeEvent::PeerMessage(peer_b.clone(), Versioned::V1(msg.into_network_message())),
 		));
 
 		// reputation change due to invalid validator index
@@ -317,7 +318,7 @@ fn receive_duplicate_messages() {
 			&Default::default(),
 			NetworkBridgeEvent::PeerMessage(
 				peer_b.clone(),
-				msg.clone().into_network_message(),
+				Versioned::V1(msg.clone().into_network_message()),
 			),
 		));
 
@@ -352,7 +353,7 @@ fn receive_duplicate_messages() {
 			&Default::default(),
 			NetworkBridgeEvent::PeerMessage(
 				peer_a.clone(),
-				msg.clone().into_network_message(),
+				Versioned::V1(msg.clone().into_network_message()),
 			),
 		));
 
@@ -373,7 +374,7 @@ fn receive_duplicate_messages() {
 			&Default::default(),
 			NetworkBridgeEvent::PeerMessage(
 				peer_b.clone(),
-				msg.clone().into_network_message(),
+				Versioned::V1(msg.clone().into_network_message()),
 			),
 		));
 
@@ -436,6 +437,7 @@ fn do_not_relay_message_twice() {
 			state.per_relay_parent.get_mut(&hash).unwrap(),
 			&gossip_peers,
 			&mut state.peer_views,
+			&state.peer_versions,
 			validator.clone(),
 			msg.clone(),
 		).await;
@@ -459,7 +461,7 @@ fn do_not_relay_message_twice() {
 				assert_eq!(2, peers.len());
 				assert!(peers.contains(&peer_a));
 				assert!(peers.contains(&peer_b));
-				assert_eq!(send_msg, msg.clone().into_validation_protocol());
+				assert_eq!(send_msg, msg.clone().into_validation_protocol(ValidationVersion::V1.into()));
 			}
 		);
 
@@ -469,6 +471,7 @@ fn do_not_relay_message_twice() {
 			state.per_relay_parent.get_mut(&hash).unwrap(),
 			&gossip_peers,
 			&mut state.peer_views,
+			&state.peer_versions,
 			validator.clone(),
 			msg.clone(),
 		).await;
@@ -530,7 +533,7 @@ fn changing_view() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None),
+			NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None),
 		));
 
 		// make peer b interested
@@ -550,7 +553,7 @@ fn changing_view() {
 			&Default::default(),
 			NetworkBridgeEvent::PeerMessage(
 				peer_b.clone(),
-				msg.clone().into_network_message(),
+				Versioned::V1(msg.clone().into_network_message()),
 			),
 		));
 
@@ -598,7 +601,7 @@ fn changing_view() {
 			&Default::default(),
 			NetworkBridgeEvent::PeerMessage(
 				peer_b.clone(),
-				msg.clone().into_network_message(),
+				Versioned::V1(msg.clone().into_network_message()),
 			),
 		));
 
@@ -631,7 +634,7 @@ fn changing_view() {
 			&Default::default(),
 			NetworkBridgeEvent::PeerMessage(
 				peer_a.clone(),
-				msg.clone().into_network_message(),
+				Versioned::V1(msg.clone().into_network_message()),
 			),
 		));
 
@@ -695,7 +698,7 @@ fn do_not_send_message_back_to_origin() {
 			&Default::default(),
 			NetworkBridgeEvent::PeerMessage(
 				peer_b.clone(),
-				msg.clone().into_network_message(),
+				Versioned::V1(msg.clone().into_network_message()),
 			),
 		));
 
@@ -717,7 +720,7 @@ fn do_not_send_message_back_to_origin() {
 			) => {
 				assert_eq!(1, peers.len());
 				assert!(peers.contains(&peer_a));
-				assert_eq!(send_msg, msg.clone().into_validation_protocol());
+				assert_eq!(send_msg, msg.clone().into_validation_protocol(ValidationVersion::V1.into()));
 			}
 		);
 
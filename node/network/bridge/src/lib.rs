@@ -48,8 +48,9 @@ use polkadot_subsystem::{
 };
 use polkadot_primitives::v1::{Hash, BlockNumber};
 use polkadot_node_network_protocol::{
-	PeerId, peer_set::PeerSet, View, v1 as protocol_v1, OurView, UnifiedReputationChange as Rep,
-	ObservedRole,
+	PeerId, peer_set::{PeerSet, ValidationVersion}, View, v1 as protocol_v1, v2 as protocol_v2, OurView,
+	UnifiedReputationChange as Rep, ObservedRole, ProtocolVersion, Versioned, VersionedValidationProtocol,
+	request_response::Protocol,
 };
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
 
@@ -74,6 +75,19 @@ use network::{Network, send_message};
 mod multiplexer;
 pub use multiplexer::RequestMultiplexer;
 
+/// Per peer rate limits of incoming requests.
+mod rate_limit;
+pub use rate_limit::{RateLimit, RateLimitEntry, RateLimitError, RequestRateLimits};
+
+/// Accounting of responses to requests.
+mod responses;
+use responses::PendingResponses;
+
+/// Audit log of reported reputation changes.
+pub use polkadot_node_network_protocol::reputation_audit::{
+	ReputationAudit, ReputationAuditConfig, ReputationChangeRecord,
+};
+
 use crate::network::get_peer_id_by_authority_id;
 
 #[cfg(test)]
@@ -101,7 +115,7 @@ impl Metrics {
 	fn on_peer_connected(&self, peer_set: PeerSet) {
 		self.0.as_ref().map(|metrics| metrics
 			.connected_events
-			.with_label_values(&[peer_set.get_protocol_name_static()])
+			.with_label_values(&[peer_set.get_label()])
 			.inc()
 		);
 	}
@@ -109,7 +123,7 @@ impl Metrics {
 	fn on_peer_disconnected(&self, peer_set: PeerSet) {
 		self.0.as_ref().map(|metrics| metrics
 			.disconnected_events
-			.with_label_values(&[peer_set.get_protocol_name_static()])
+			.with_label_values(&[peer_set.get_label()])
 			.inc()
 		);
 	}
@@ -117,7 +131,7 @@ impl Metrics {
 	fn note_peer_count(&self, peer_set: PeerSet, count: usize) {
 		self.0.as_ref().map(|metrics| metrics
 			.peer_count
-			.with_label_values(&[peer_set.get_protocol_name_static()])
+			.with_label_values(&[peer_set.get_label()])
 			.set(count as u64)
 		);
 	}
@@ -125,11 +139,11 @@ impl Metrics {
 	fn on_notification_received(&self, peer_set: PeerSet, size: usize) {
 		if let Some(metrics) = self.0.as_ref() {
 			metrics.notifications_received
-				.with_label_values(&[peer_set.get_protocol_name_static()])
+				.with_label_values(&[peer_set.get_label()])
 				.inc();
 
 			metrics.bytes_received
-				.with_label_values(&[peer_set.get_protocol_name_static()])
+				.with_label_values(&[peer_set.get_label()])
 				.inc_by(size as u64);
 		}
 	}
@@ -137,19 +151,67 @@ impl Metrics {
 	fn on_notification_sent(&self, peer_set: PeerSet, size: usize, to_peers: usize) {
 		if let Some(metrics) = self.0.as_ref() {
 			metrics.notifications_sent
-				.with_label_values(&[peer_set.get_protocol_name_static()])
+				.with_label_values(&[peer_set.get_label()])
 				.inc_by(to_peers as u64);
 
 			metrics.bytes_sent
-				.with_label_values(&[peer_set.get_protocol_name_static()])
+				.with_label_values(&[peer_set.get_label()])
 				.inc_by((size * to_peers) as u64);
 		}
 	}
 
+	fn on_request_received(&self, protocol: Protocol, size: usize) {
+		self.0.as_ref().map(|metrics| metrics
+			.request_bytes_received
+			.with_label_values(&[protocol.get_label()])
+			.inc_by(size as u64)
+		);
+	}
+
+	fn on_request_sent(&self, protocol: Protocol, size: usize) {
+		self.0.as_ref().map(|metrics| metrics
+			.request_bytes_sent
+			.with_label_values(&[protocol.get_label()])
+			.inc_by(size as u64)
+		);
+	}
+
+	fn on_response_received(&self, protocol: Protocol, size: usize) {
+		self.0.as_ref().map(|metrics| metrics
+			.response_bytes_received
+			.with_label_values(&[protocol.get_label()])
+			.inc_by(size as u64)
+		);
+	}
+
+	fn on_response_sent(&self, protocol: Protocol, size: usize) {
+		self.0.as_ref().map(|metrics| metrics
+			.response_bytes_sent
+			.with_label_values(&[protocol.get_label()])
+			.inc_by(size as u64)
+		);
+	}
+
+	fn on_request_throttled(&self, protocol: Protocol) {
+		self.0.as_ref().map(|metrics| metrics
+			.requests_throttled
+			.with_label_values(&[protocol.get_label()])
+			.inc()
+		);
+	}
+
+	fn on_report_event(&self, reason: &str) {
+		self.0.as_ref().map(|metrics| metrics
+			.reputation_changes
+			.with_label_values(&[reason])
+			.inc()
+		);
+	}
+
 	fn note_desired_peer_count(&self, peer_set: PeerSet, size: usize) {
 		self.0.as_ref().map(|metrics| metrics
 			.desired_peer_count
-			.with_label_values(&[peer_set.get_protocol_name_static()])
+			.with_label_values(&[peer_set.get_label()])
 			.set(size as u64)
 		);
 	}
@@ -167,6 +229,14 @@ struct MetricsInner {
 
 	bytes_received: prometheus::CounterVec<prometheus::U64>,
 	bytes_sent: prometheus::CounterVec<prometheus::U64>,
+
+	request_bytes_received: prometheus::CounterVec<prometheus::U64>,
+	request_bytes_sent: prometheus::CounterVec<prometheus::U64>,
+	response_bytes_received: prometheus::CounterVec<prometheus::U64>,
+	response_bytes_sent: prometheus::CounterVec<prometheus::U64>,
+	requests_throttled: prometheus::CounterVec<prometheus::U64>,
+
+	reputation_changes: prometheus::CounterVec<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -254,6 +324,66 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			request_bytes_received: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"parachain_request_bytes_received_total",
+						"The number of request payload bytes received on a parachain request protocol",
+					),
+					&["protocol"]
+				)?,
+				registry,
+			)?,
+			request_bytes_sent: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"parachain_request_bytes_sent_total",
+						"The number of request payload bytes sent on a parachain request protocol",
+					),
+					&["protocol"]
+				)?,
+				registry,
+			)?,
+			response_bytes_received: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"parachain_response_bytes_received_total",
+						"The number of response payload bytes received on a parachain request protocol",
+					),
+					&["protocol"]
+				)?,
+				registry,
+			)?,
+			response_bytes_sent: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"parachain_response_bytes_sent_total",
+						"The number of response payload bytes sent on a parachain request protocol",
+					),
+					&["protocol"]
+				)?,
+				registry,
+			)?,
+			requests_throttled: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"parachain_requests_throttled_total",
+						"The number of incoming requests rejected for exceeding the rate limits",
+					),
+					&["protocol"]
+				)?,
+				registry,
+			)?,
+			reputation_changes: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"parachain_reputation_changes_total",
+						"The number of reputation changes reported via the network bridge",
+					),
+					&["reason"]
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -280,6 +410,7 @@ pub struct NetworkBridge<N, AD> {
 	authority_discovery_service: AD,
 	request_multiplexer: RequestMultiplexer,
 	sync_oracle: Box<dyn SyncOracle + Send>,
+	reputation_audit: ReputationAudit,
 	metrics: Metrics,
 }
 
@@ -293,6 +424,7 @@ impl<N, AD> NetworkBridge<N, AD> {
 		authority_discovery_service: AD,
 		request_multiplexer: RequestMultiplexer,
 		sync_oracle: Box<dyn SyncOracle + Send>,
+		reputation_audit: ReputationAudit,
 		metrics: Metrics,
 	) -> Self {
 		NetworkBridge {
@@ -300,6 +432,7 @@ impl<N, AD> NetworkBridge<N, AD> {
 			authority_discovery_service,
 			request_multiplexer,
 			sync_oracle,
+			reputation_audit,
 			metrics,
 		}
 	}
@@ -333,6 +466,8 @@ impl<Net, AD, Context> Subsystem<Context, SubsystemError> for NetworkBridge<Net,
 struct PeerData {
 	/// The Latest view sent by the peer.
 	view: View,
+	/// The protocol version negotiated with the peer.
+	version: ProtocolVersion,
 }
 
 #[derive(Debug)]
@@ -378,6 +513,7 @@ async fn handle_subsystem_messages<Context, N, AD>(
 	mut authority_discovery_service: AD,
 	shared: Shared,
 	sync_oracle: Box<dyn SyncOracle + Send>,
+	reputation_audit: ReputationAudit,
 	metrics: Metrics,
 ) -> Result<(), UnexpectedAbort>
 where
@@ -393,8 +529,12 @@ where
 
 	let mut mode = Mode::Syncing(sync_oracle);
 
+	// Responses to our requests, on their way back to the requesting subsystems.
+	let mut pending_responses = PendingResponses::new();
+
 	loop {
 		futures::select! {
+			_ = pending_responses.select_next_some() => {},
 			msg = ctx.recv().fuse() => match msg {
 				Ok(FromOverseer::Signal(OverseerSignal::ActiveLeaves(active_leaves))) => {
 					let ActiveLeavesUpdate { activated, deactivated } = active_leaves;
@@ -462,7 +602,7 @@ where
 								action = "ReportPeer"
 							);
 						}
-						network_service.report_peer(peer, rep);
+						report_peer(&network_service, &reputation_audit, &metrics, peer, rep);
 					}
 					NetworkBridgeMessage::DisconnectPeer(peer, peer_set) => {
 						tracing::trace!(
@@ -480,11 +620,11 @@ where
 							num_messages = 1,
 						);
 
-						send_message(
+						send_versioned_validation_message(
 							&mut network_service,
 							peers,
-							PeerSet::Validation,
-							WireMessage::ProtocolMessage(msg),
+							msg,
+							&shared,
 							&metrics,
 						);
 					}
@@ -496,11 +636,11 @@ where
 						);
 
 						for (peers, msg) in msgs {
-							send_message(
+							send_versioned_validation_message(
 								&mut network_service,
 								peers,
-								PeerSet::Validation,
-								WireMessage::ProtocolMessage(msg),
+								msg,
+								&shared,
 								&metrics,
 							);
 						}
@@ -544,7 +684,11 @@ where
 							num_requests = %reqs.len(),
 						);
 
-						for req in reqs {
+						for mut req in reqs {
+							metrics.on_request_sent(req.get_protocol(), req.encoded_size());
+							pending_responses.push(
+								responses::intercept_incoming_response(&mut req, metrics.clone())
+							);
 							network_service
 								.start_request(&mut authority_discovery_service, req, if_disconnected)
 								.await;
@@ -617,6 +761,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 	network_stream: BoxStream<'static, NetworkEvent>,
 	mut authority_discovery_service: AD,
 	mut request_multiplexer: RequestMultiplexer,
+	reputation_audit: ReputationAudit,
 	metrics: Metrics,
 	shared: Shared,
 ) -> Result<(), UnexpectedAbort> {
@@ -628,17 +773,38 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 				Some(NetworkEvent::Dht(_))
 				| Some(NetworkEvent::SyncConnected { .. })
 				| Some(NetworkEvent::SyncDisconnected { .. }) => {}
-				Some(NetworkEvent::NotificationStreamOpened { remote: peer, protocol, role, .. }) => {
+				Some(NetworkEvent::NotificationStreamOpened { remote: peer, protocol, negotiated_fallback, role, .. }) => {
 					let role = ObservedRole::from(role);
-					let peer_set = match PeerSet::try_from_protocol_name(&protocol) {
+					let (peer_set, version) = match PeerSet::try_from_protocol_name(&protocol) {
 						None => continue,
-						Some(peer_set) => peer_set,
+						Some(p) => p,
+					};
+
+					// Substrate always reports the main protocol, but tells us in addition if an
+					// older version got negotiated instead.
+					let version = match negotiated_fallback {
+						None => version,
+						Some(fallback) => match PeerSet::try_from_protocol_name(&fallback) {
+							Some((fallback_peer_set, fallback_version)) if fallback_peer_set == peer_set =>
+								fallback_version,
+							_ => {
+								tracing::debug!(
+									target: LOG_TARGET,
+									?fallback,
+									?peer,
+									peer_set = ?peer_set,
+									"Unknown fallback protocol negotiated",
+								);
+								continue
+							}
+						},
 					};
 
 					tracing::debug!(
 						target: LOG_TARGET,
 						action = "PeerConnected",
 						peer_set = ?peer_set,
+						version,
 						peer = ?peer,
 						role = ?role
 					);
@@ -653,7 +819,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 						match peer_map.entry(peer.clone()) {
 							hash_map::Entry::Occupied(_) => continue,
 							hash_map::Entry::Vacant(vacant) => {
-								vacant.insert(PeerData { view: View::default() });
+								vacant.insert(PeerData { view: View::default(), version });
 							}
 						}
 
@@ -671,7 +837,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 						PeerSet::Validation => {
 							dispatch_validation_events_to_all(
 								vec![
-									NetworkBridgeEvent::PeerConnected(peer.clone(), role, maybe_authority),
+									NetworkBridgeEvent::PeerConnected(peer.clone(), role, version, maybe_authority),
 									NetworkBridgeEvent::PeerViewChange(
 										peer.clone(),
 										View::default(),
@@ -680,20 +846,17 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								&mut sender,
 							).await;
 
-							send_message(
+							send_validation_message_v1(
 								&mut network_service,
 								vec![peer],
-								PeerSet::Validation,
-								WireMessage::<protocol_v1::ValidationProtocol>::ViewUpdate(
-									local_view,
-								),
+								WireMessage::ViewUpdate(local_view),
 								&metrics,
 							);
 						}
 						PeerSet::Collation => {
 							dispatch_collation_events_to_all(
 								vec![
-									NetworkBridgeEvent::PeerConnected(peer.clone(), role, maybe_authority),
+									NetworkBridgeEvent::PeerConnected(peer.clone(), role, version, maybe_authority),
 									NetworkBridgeEvent::PeerViewChange(
 										peer.clone(),
 										View::default(),
@@ -702,13 +865,10 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								&mut sender,
 							).await;
 
-							send_message(
+							send_collation_message(
 								&mut network_service,
 								vec![peer],
-								PeerSet::Collation,
-								WireMessage::<protocol_v1::CollationProtocol>::ViewUpdate(
-									local_view,
-								),
+								WireMessage::ViewUpdate(local_view),
 								&metrics,
 							);
 						}
@@ -717,7 +877,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 				Some(NetworkEvent::NotificationStreamClosed { remote: peer, protocol }) => {
 					let peer_set = match PeerSet::try_from_protocol_name(&protocol) {
 						None => continue,
-						Some(peer_set) => peer_set,
+						Some((peer_set, _)) => peer_set,
 					};
 
 					tracing::debug!(
@@ -756,13 +916,20 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 					}
 				}
 				Some(NetworkEvent::NotificationsReceived { remote, messages }) => {
+					// Messages are always reported with the main protocol, so we need to look up the
+					// version negotiated with the peer in order to decode them.
+					let validation_version = shared.0.lock()
+						.validation_peers
+						.get(&remote)
+						.map(|data| data.version);
+
 					let v_messages: Result<Vec<_>, _> = messages
 						.iter()
 						.filter(|(protocol, _)| {
 							protocol == &PeerSet::Validation.into_protocol_name()
 						})
 						.map(|(_, msg_bytes)| {
-							WireMessage::decode(&mut msg_bytes.as_ref())
+							decode_validation_message(validation_version, msg_bytes.as_ref())
 								.map(|m| (m, msg_bytes.len()))
 						})
 						.collect();
@@ -774,7 +941,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								action = "ReportPeer"
 							);
 
-							network_service.report_peer(remote, MALFORMED_MESSAGE_COST);
+							report_peer(&network_service, &reputation_audit, &metrics, remote, MALFORMED_MESSAGE_COST);
 							continue;
 						}
 						Ok(v) => v,
@@ -798,7 +965,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								action = "ReportPeer"
 							);
 
-							network_service.report_peer(remote, MALFORMED_MESSAGE_COST);
+							report_peer(&network_service, &reputation_audit, &metrics, remote, MALFORMED_MESSAGE_COST);
 							continue;
 						}
 						Ok(c_messages) => {
@@ -823,7 +990,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 									);
 
 									for report in reports {
-										network_service.report_peer(remote.clone(), report);
+										report_peer(&network_service, &reputation_audit, &metrics, remote, report);
 									}
 
 									dispatch_validation_events_to_all(events, &mut sender).await;
@@ -839,7 +1006,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 									);
 
 									for report in reports {
-										network_service.report_peer(remote.clone(), report);
+										report_peer(&network_service, &reputation_audit, &metrics, remote, report);
 									}
 
 
@@ -853,7 +1020,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 			req_res_event = request_multiplexer.next() => match req_res_event {
 				None => return Err(UnexpectedAbort::RequestStreamConcluded),
 				Some(Err(err)) => {
-					network_service.report_peer(err.peer, MALFORMED_MESSAGE_COST);
+					report_peer(&network_service, &reputation_audit, &metrics, err.peer, MALFORMED_MESSAGE_COST);
 				}
 				Some(Ok(msg)) => {
 					sender.send_message(msg).await;
@@ -892,15 +1059,33 @@ where
 		authority_discovery_service,
 		metrics,
 		sync_oracle,
+		reputation_audit,
 	} = bridge;
 
-	let statement_receiver = request_multiplexer
-		.get_statement_fetching()
-		.expect("Gets initialized, must be `Some` on startup. qed.");
+	request_multiplexer.set_accounting(metrics.clone(), reputation_audit.clone());
+
+	let (statement_receiver, statement_forwarder) = rate_limit::throttle(
+		Protocol::StatementFetching,
+		request_multiplexer
+			.get_statement_fetching()
+			.expect("Gets initialized, must be `Some` on startup. qed."),
+		request_multiplexer.rate_limits().clone(),
+		metrics.clone(),
+		reputation_audit.clone(),
+	);
+
+	let (dispute_receiver, dispute_forwarder) = rate_limit::throttle(
+		Protocol::DisputeSending,
+		request_multiplexer
+			.get_dispute_sending()
+			.expect("Gets initialized, must be `Some` on startup. qed."),
+		request_multiplexer.rate_limits().clone(),
+		metrics.clone(),
+		reputation_audit.clone(),
+	);
 
-	let dispute_receiver = request_multiplexer
-		.get_dispute_sending()
-		.expect("Gets initialized, must be `Some` on startup. qed.");
+	ctx.spawn("network-bridge-statement-requests", Box::pin(statement_forwarder))?;
+	ctx.spawn("network-bridge-dispute-requests", Box::pin(dispute_forwarder))?;
 
 	let (remote, network_event_handler) = handle_network_messages::<>(
 		ctx.sender().clone(),
@@ -908,6 +1093,7 @@ where
 		network_stream,
 		authority_discovery_service.clone(),
 		request_multiplexer,
+		reputation_audit.clone(),
 		metrics.clone(),
 		shared.clone(),
 	).remote_handle();
@@ -927,6 +1113,7 @@ where
 		authority_discovery_service,
 		shared,
 		sync_oracle,
+		reputation_audit,
 		metrics,
 	);
 
@@ -971,6 +1158,19 @@ where
 	}
 }
 
+/// Report a peer to the network and record the change in the audit log.
+fn report_peer(
+	network_service: &impl Network,
+	reputation_audit: &ReputationAudit,
+	metrics: &Metrics,
+	peer: PeerId,
+	rep: Rep,
+) {
+	metrics.on_report_event(rep.description());
+	reputation_audit.note_change(peer, rep);
+	network_service.report_peer(peer, rep);
+}
+
 fn construct_view(live_heads: impl DoubleEndedIterator<Item = Hash>, finalized_number: BlockNumber) -> View {
 	View::new(
 		live_heads.take(MAX_VIEW_HEADS),
@@ -1016,7 +1216,8 @@ fn update_our_view(
 		)
 	};
 
-	send_validation_message(
+	// View updates are encoded the same in all versions.
+	send_validation_message_v1(
 		net,
 		validation_peers,
 		WireMessage::ViewUpdate(new_view.clone()),
@@ -1098,7 +1299,68 @@ fn handle_peer_messages<M>(
 	(outgoing_messages, reports)
 }
 
-fn send_validation_message(
+/// Decode a message on the validation peer-set, according to the version negotiated with the peer.
+///
+/// Messages of unknown peers are decoded as v1, they get rejected as coming from an unconnected
+/// peer anyway.
+fn decode_validation_message(
+	version: Option<ProtocolVersion>,
+	mut bytes: &[u8],
+) -> Result<WireMessage<VersionedValidationProtocol>, parity_scale_codec::Error> {
+	let message = if version == Some(ValidationVersion::V2.into()) {
+		match WireMessage::<protocol_v2::ValidationProtocol>::decode(&mut bytes)? {
+			WireMessage::ProtocolMessage(m) => WireMessage::ProtocolMessage(Versioned::V2(m)),
+			WireMessage::ViewUpdate(view) => WireMessage::ViewUpdate(view),
+		}
+	} else {
+		match WireMessage::<protocol_v1::ValidationProtocol>::decode(&mut bytes)? {
+			WireMessage::ProtocolMessage(m) => WireMessage::ProtocolMessage(Versioned::V1(m)),
+			WireMessage::ViewUpdate(view) => WireMessage::ViewUpdate(view),
+		}
+	};
+
+	Ok(message)
+}
+
+/// Send a message on the validation peer-set to all of the given peers which negotiated the
+/// version of the message.
+fn send_versioned_validation_message(
+	net: &mut impl Network,
+	peers: Vec<PeerId>,
+	message: VersionedValidationProtocol,
+	shared: &Shared,
+	metrics: &Metrics,
+) {
+	let version = message.version();
+	let peers = {
+		let shared = shared.0.lock();
+		peers.into_iter().filter(|peer| match shared.validation_peers.get(peer) {
+			Some(data) if data.version != version => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?peer,
+					peer_version = data.version,
+					message_version = version,
+					"Not sending validation message of a version the peer did not negotiate",
+				);
+				false
+			}
+			// Messages to peers which are not connected are dropped by the network.
+			_ => true,
+		}).collect::<Vec<_>>()
+	};
+
+	if peers.is_empty() {
+		return
+	}
+
+	match message {
+		Versioned::V1(msg) => send_validation_message_v1(net, peers, WireMessage::ProtocolMessage(msg), metrics),
+		Versioned::V2(msg) => send_validation_message_v2(net, peers, WireMessage::ProtocolMessage(msg), metrics),
+	}
+}
+
+fn send_validation_message_v1(
 	net: &mut impl Network,
 	peers: Vec<PeerId>,
 	message: WireMessage<protocol_v1::ValidationProtocol>,
@@ -1107,6 +1369,15 @@ fn send_validation_message(
 	send_message(net, peers, PeerSet::Validation, message, metrics);
 }
 
+fn send_validation_message_v2(
+	net: &mut impl Network,
+	peers: Vec<PeerId>,
+	message: WireMessage<protocol_v2::ValidationProtocol>,
+	metrics: &Metrics,
+) {
+	send_message(net, peers, PeerSet::Validation, message, metrics);
+}
+
 fn send_collation_message(
 	net: &mut impl Network,
 	peers: Vec<PeerId>,
@@ -1118,7 +1389,7 @@ fn send_collation_message(
 
 
 async fn dispatch_validation_event_to_all(
-	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	event: NetworkBridgeEvent<VersionedValidationProtocol>,
 	ctx: &mut impl SubsystemSender
 ) {
 	dispatch_validation_events_to_all(std::iter::once(event), ctx).await
@@ -1132,7 +1403,7 @@ async fn dispatch_collation_event_to_all(
 }
 
 fn dispatch_validation_event_to_all_unbounded(
-	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	event: NetworkBridgeEvent<VersionedValidationProtocol>,
 	ctx: &mut impl SubsystemSender
 ) {
 	for msg in AllMessages::dispatch_iter(event) {
@@ -1154,7 +1425,7 @@ async fn dispatch_validation_events_to_all<I>(
 	ctx: &mut impl SubsystemSender
 )
 	where
-		I: IntoIterator<Item = NetworkBridgeEvent<protocol_v1::ValidationProtocol>>,
+		I: IntoIterator<Item = NetworkBridgeEvent<VersionedValidationProtocol>>,
 		I::IntoIter: Send,
 {
 	ctx.send_messages(events.into_iter().flat_map(AllMessages::dispatch_iter)).await
@@ -18,7 +18,7 @@ use std::pin::Pin;
 use std::unreachable;
 
 use futures::channel::mpsc;
-use futures::stream::{FusedStream, Stream};
+use futures::stream::{FusedStream, Stream, StreamExt};
 use futures::task::{Context, Poll};
 use strum::IntoEnumIterator;
 
@@ -32,6 +32,10 @@ use polkadot_node_network_protocol::request_response::{
 };
 use polkadot_overseer::AllMessages;
 
+use crate::{Metrics, ReputationAudit};
+use crate::rate_limit::{self, RateLimiter, RequestRateLimits};
+use crate::responses::{self, PendingResponses};
+
 /// Multiplex incoming network requests.
 ///
 /// This multiplexer consumes all request streams and makes them a `Stream` of a single message
@@ -44,6 +48,10 @@ pub struct RequestMultiplexer {
 	receivers: Vec<(Protocol, mpsc::Receiver<network::IncomingRequest>)>,
 	statement_fetching: Option<mpsc::Receiver<network::IncomingRequest>>,
 	dispute_sending: Option<mpsc::Receiver<network::IncomingRequest>>,
+	rate_limiter: RateLimiter,
+	metrics: Metrics,
+	reputation_audit: ReputationAudit,
+	pending_responses: PendingResponses,
 	next_poll: usize,
 }
 
@@ -94,13 +102,37 @@ impl RequestMultiplexer {
 			Self {
 				receivers,
 				statement_fetching,
-                dispute_sending,
+				dispute_sending,
+				rate_limiter: RateLimiter::default(),
+				metrics: Metrics::default(),
+				reputation_audit: ReputationAudit::default(),
+				pending_responses: PendingResponses::new(),
 				next_poll: 0,
 			},
 			cfgs,
 		)
 	}
 
+	/// Limit the rate of incoming requests per peer.
+	///
+	/// Requests exceeding the limits are rejected before they reach any subsystem.
+	pub fn with_rate_limits(mut self, limits: RequestRateLimits) -> Self {
+		self.rate_limiter = RateLimiter::new(limits);
+		self
+	}
+
+	/// The configured rate limits, which also apply to the directly handled protocols.
+	pub(crate) fn rate_limits(&self) -> &RequestRateLimits {
+		self.rate_limiter.limits()
+	}
+
+	/// Use the given metrics and audit log for accounting of incoming requests and their
+	/// responses.
+	pub(crate) fn set_accounting(&mut self, metrics: Metrics, reputation_audit: ReputationAudit) {
+		self.metrics = metrics;
+		self.reputation_audit = reputation_audit;
+	}
+
 	/// Get the receiver for handling statement fetching requests.
 	///
 	/// This function will only return `Some` once.
@@ -120,15 +152,20 @@ impl Stream for RequestMultiplexer {
 	type Item = Result<AllMessages, RequestMultiplexError>;
 
 	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-		let len = self.receivers.len();
+		let this = &mut *self;
+
+		// Forward the responses of subsystems, the futures are done once the response is sent.
+		while let Poll::Ready(Some(())) = this.pending_responses.poll_next_unpin(cx) {}
+
+		let len = this.receivers.len();
 		let mut count = len;
-		let mut i = self.next_poll;
+		let mut i = this.next_poll;
 		let mut result = Poll::Ready(None);
 		// Poll streams in round robin fashion:
 		while count > 0 {
 			// % safe, because count initialized to len, loop would not be entered if 0, also
 			// length of receivers is fixed.
-			let (p, rx): &mut (_, _) = &mut self.receivers[i % len];
+			let (p, rx): &mut (_, _) = &mut this.receivers[i % len];
 			// Avoid panic:
 			if rx.is_terminated() {
 				// Early return, we don't want to update next_poll.
@@ -136,7 +173,33 @@ impl Stream for RequestMultiplexer {
 			}
 			i += 1;
 			count -= 1;
-			match Pin::new(rx).poll_next(cx) {
+			// Requests exceeding the rate limits are answered right away, keep polling the
+			// same receiver until we get one which is not.
+			let polled = loop {
+				match Pin::new(&mut *rx).poll_next(cx) {
+					Poll::Ready(Some(v)) => match rate_limit::admit(
+						&mut this.rate_limiter,
+						&this.metrics,
+						&this.reputation_audit,
+						*p,
+						v,
+					) {
+						Some(v) => {
+							let (v, forward) = responses::intercept_outgoing_response(
+								*p,
+								v,
+								this.metrics.clone(),
+								this.reputation_audit.clone(),
+							);
+							this.pending_responses.push(forward);
+							break Poll::Ready(Some(v))
+						}
+						None => continue,
+					},
+					other => break other,
+				}
+			};
+			match polled {
 				Poll::Pending => result = Poll::Pending,
 				// We are done, once a single receiver is done.
 				Poll::Ready(None) => return Poll::Ready(None),
@@ -146,7 +209,7 @@ impl Stream for RequestMultiplexer {
 				}
 			}
 		}
-		self.next_poll = i;
+		this.next_poll = i;
 		result
 	}
 }
@@ -177,6 +240,11 @@ fn multiplex_single(
 			decode_with_peer::<v1::ChunkFetchingRequest>(peer, payload)?,
 			pending_response,
 		)),
+		Protocol::BatchedChunkFetching => AllMessages::from(IncomingRequest::new(
+			peer,
+			decode_with_peer::<v1::BatchedChunkFetchingRequest>(peer, payload)?,
+			pending_response,
+		)),
 		Protocol::CollationFetching => AllMessages::from(IncomingRequest::new(
 			peer,
 			decode_with_peer::<v1::CollationFetchingRequest>(peer, payload)?,
@@ -0,0 +1,397 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rate limits for incoming requests.
+//!
+//! Every peer gets a token bucket per limited request protocol. Requests which find the bucket
+//! of their peer empty are answered with an error right away, so a single peer can't fill up the
+//! request queues of the subsystems.
+//!
+//! Limits are given in the form `<protocol>:<requests_per_second>[:<burst>]`, where the protocol
+//! is the label of a request protocol, e.g. `chunk_fetching`. Without a burst, peers can send
+//! up to a second worth of requests at once.
+
+use std::{collections::HashMap, str::FromStr, time::Instant};
+
+use futures::{channel::mpsc, prelude::*};
+use strum::IntoEnumIterator;
+use thiserror::Error;
+
+use sc_network::{config as network, PeerId};
+
+use polkadot_node_network_protocol::{request_response::Protocol, UnifiedReputationChange as Rep};
+
+use crate::{Metrics, ReputationAudit, LOG_TARGET};
+use crate::responses::{self, PendingResponses};
+
+/// Cost of sending requests faster than we are willing to serve them.
+const RATE_LIMIT_EXCEEDED_COST: Rep = Rep::CostMinor("Request rate limit exceeded");
+
+/// Buckets of idle peers are dropped once we track that many.
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Errors when parsing a rate limit.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RateLimitError {
+	/// The limit did not consist of two or three `:` separated fields.
+	#[error("Malformed rate limit {0:?}, expected `<protocol>:<requests_per_second>[:<burst>]`")]
+	Malformed(String),
+
+	/// The protocol is not a known request protocol.
+	#[error("Unknown request protocol {0:?}")]
+	UnknownProtocol(String),
+
+	/// Rate or burst were not positive integers.
+	#[error("Invalid number {0:?}, expected a positive integer")]
+	InvalidNumber(String),
+}
+
+/// Rate limit of a single request protocol, per peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+	/// Sustained number of requests a peer may send per second.
+	pub requests_per_second: u32,
+	/// Number of requests a peer may send at once, after being idle.
+	pub burst: u32,
+}
+
+/// A rate limit for a specific request protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitEntry {
+	/// The limited protocol.
+	pub protocol: Protocol,
+	/// The limit.
+	pub limit: RateLimit,
+}
+
+impl FromStr for RateLimitEntry {
+	type Err = RateLimitError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let fields: Vec<_> = s.trim().split(':').map(str::trim).collect();
+		let (protocol, rate, burst) = match fields[..] {
+			[protocol, rate] => (protocol, rate, None),
+			[protocol, rate, burst] => (protocol, rate, Some(burst)),
+			_ => return Err(RateLimitError::Malformed(s.into())),
+		};
+
+		let protocol = Protocol::iter()
+			.find(|p| p.get_label() == protocol)
+			.ok_or_else(|| RateLimitError::UnknownProtocol(protocol.into()))?;
+
+		let parse_positive = |n: &str| match u32::from_str(n) {
+			Ok(n) if n > 0 => Ok(n),
+			_ => Err(RateLimitError::InvalidNumber(n.into())),
+		};
+
+		let requests_per_second = parse_positive(rate)?;
+		let burst = burst.map(parse_positive).transpose()?.unwrap_or(requests_per_second);
+
+		Ok(RateLimitEntry { protocol, limit: RateLimit { requests_per_second, burst } })
+	}
+}
+
+/// Rate limits of incoming requests, per protocol.
+///
+/// Protocols without a limit are not limited.
+#[derive(Debug, Clone, Default)]
+pub struct RequestRateLimits {
+	limits: HashMap<Protocol, RateLimit>,
+}
+
+impl RequestRateLimits {
+	/// Set the limit of a protocol, replacing any previous one.
+	pub fn insert(&mut self, entry: RateLimitEntry) {
+		self.limits.insert(entry.protocol, entry.limit);
+	}
+
+	fn get(&self, protocol: Protocol) -> Option<&RateLimit> {
+		self.limits.get(&protocol)
+	}
+}
+
+struct TokenBucket {
+	tokens: f64,
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	fn full(limit: &RateLimit, now: Instant) -> Self {
+		TokenBucket { tokens: f64::from(limit.burst), last_refill: now }
+	}
+
+	fn refill(&mut self, limit: &RateLimit, now: Instant) {
+		let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+		self.tokens = f64::min(
+			self.tokens + elapsed * f64::from(limit.requests_per_second),
+			f64::from(limit.burst),
+		);
+		self.last_refill = now;
+	}
+
+	fn is_full(&self, limit: &RateLimit) -> bool {
+		self.tokens >= f64::from(limit.burst)
+	}
+}
+
+/// Token buckets of all peers.
+#[derive(Default)]
+pub(crate) struct RateLimiter {
+	limits: RequestRateLimits,
+	buckets: HashMap<(PeerId, Protocol), TokenBucket>,
+}
+
+impl RateLimiter {
+	pub(crate) fn new(limits: RequestRateLimits) -> Self {
+		RateLimiter { limits, buckets: HashMap::new() }
+	}
+
+	pub(crate) fn limits(&self) -> &RequestRateLimits {
+		&self.limits
+	}
+
+	/// Whether a request of the peer is within the limits, consumes a token if so.
+	fn allow(&mut self, peer: PeerId, protocol: Protocol, now: Instant) -> bool {
+		let limit = match self.limits.get(protocol) {
+			None => return true,
+			Some(limit) => *limit,
+		};
+
+		if self.buckets.len() >= MAX_TRACKED_BUCKETS {
+			self.prune(now);
+		}
+
+		let bucket = self.buckets
+			.entry((peer, protocol))
+			.or_insert_with(|| TokenBucket::full(&limit, now));
+		bucket.refill(&limit, now);
+
+		if bucket.tokens >= 1.0 {
+			bucket.tokens -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+
+	// Drop the buckets of peers which have been idle long enough to be indistinguishable from new
+	// peers.
+	fn prune(&mut self, now: Instant) {
+		let limits = &self.limits;
+		self.buckets.retain(|(_, protocol), bucket| match limits.get(*protocol) {
+			Some(limit) => {
+				bucket.refill(limit, now);
+				!bucket.is_full(limit)
+			}
+			None => false,
+		});
+	}
+}
+
+/// Account for an incoming request and check it against the rate limits.
+///
+/// Requests exceeding the limits are answered with an error right away and `None` is returned.
+pub(crate) fn admit(
+	limiter: &mut RateLimiter,
+	metrics: &Metrics,
+	reputation_audit: &ReputationAudit,
+	protocol: Protocol,
+	req: network::IncomingRequest,
+) -> Option<network::IncomingRequest> {
+	metrics.on_request_received(protocol, req.payload.len());
+
+	if limiter.allow(req.peer, protocol, Instant::now()) {
+		return Some(req)
+	}
+
+	tracing::debug!(
+		target: LOG_TARGET,
+		peer = ?req.peer,
+		protocol = protocol.get_label(),
+		"Request rate limit exceeded",
+	);
+	metrics.on_request_throttled(protocol);
+
+	let response = network::OutgoingResponse {
+		result: Err(()),
+		reputation_changes: vec![RATE_LIMIT_EXCEEDED_COST.into_base_rep()],
+		sent_feedback: None,
+	};
+	responses::note_reputation_changes(req.peer, &response, metrics, reputation_audit);
+	let _ = req.pending_response.send(response);
+	None
+}
+
+/// Forward requests from `incoming` to the returned receiver, subject to the rate limits of
+/// `protocol`.
+///
+/// This is used for protocols which are handled directly by subsystems instead of going through
+/// the `RequestMultiplexer`. The returned future does the forwarding and needs to be spawned. It
+/// accounts for all requests and their responses, including the reputation changes sent along with
+/// responses, even if the protocol is not limited.
+pub(crate) fn throttle(
+	protocol: Protocol,
+	mut incoming: mpsc::Receiver<network::IncomingRequest>,
+	limits: RequestRateLimits,
+	metrics: Metrics,
+	reputation_audit: ReputationAudit,
+) -> (mpsc::Receiver<network::IncomingRequest>, impl Future<Output = ()>) {
+	let (mut tx, rx) = mpsc::channel(1);
+	let mut limiter = RateLimiter::new(limits);
+
+	let forward = async move {
+		let mut pending_responses = PendingResponses::new();
+
+		loop {
+			let req = futures::select! {
+				req = incoming.next() => match req {
+					Some(req) => req,
+					None => break,
+				},
+				_ = pending_responses.select_next_some() => continue,
+			};
+
+			if let Some(req) = admit(&mut limiter, &metrics, &reputation_audit, protocol, req) {
+				let (req, forward) = responses::intercept_outgoing_response(
+					protocol,
+					req,
+					metrics.clone(),
+					reputation_audit.clone(),
+				);
+				pending_responses.push(forward);
+
+				if tx.send(req).await.is_err() {
+					break
+				}
+			}
+		}
+
+		// Answer the requests already handed out.
+		while let Some(()) = pending_responses.next().await {}
+	};
+
+	(rx, forward)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+	use futures::channel::oneshot;
+
+	fn limiter(entry: &str) -> RateLimiter {
+		let mut limits = RequestRateLimits::default();
+		limits.insert(entry.parse().unwrap());
+		RateLimiter::new(limits)
+	}
+
+	#[test]
+	fn entries_parse() {
+		assert_eq!(
+			"chunk_fetching:10".parse::<RateLimitEntry>(),
+			Ok(RateLimitEntry {
+				protocol: Protocol::ChunkFetching,
+				limit: RateLimit { requests_per_second: 10, burst: 10 },
+			}),
+		);
+		assert_eq!(
+			"statement_fetching:2:5".parse::<RateLimitEntry>(),
+			Ok(RateLimitEntry {
+				protocol: Protocol::StatementFetching,
+				limit: RateLimit { requests_per_second: 2, burst: 5 },
+			}),
+		);
+
+		assert!(matches!("chunk_fetching".parse::<RateLimitEntry>(), Err(RateLimitError::Malformed(_))));
+		assert!(matches!("chunks:10".parse::<RateLimitEntry>(), Err(RateLimitError::UnknownProtocol(_))));
+		assert!(matches!("chunk_fetching:0".parse::<RateLimitEntry>(), Err(RateLimitError::InvalidNumber(_))));
+		assert!(matches!("chunk_fetching:1:x".parse::<RateLimitEntry>(), Err(RateLimitError::InvalidNumber(_))));
+	}
+
+	#[test]
+	fn buckets_limit_and_refill() {
+		let mut limiter = limiter("chunk_fetching:2:3");
+		let peer = PeerId::random();
+		let other = PeerId::random();
+		let now = Instant::now();
+
+		// Burst is allowed, after that requests are rejected.
+		assert!((0..3).all(|_| limiter.allow(peer, Protocol::ChunkFetching, now)));
+		assert!(!limiter.allow(peer, Protocol::ChunkFetching, now));
+
+		// Other peers and other protocols are not affected.
+		assert!(limiter.allow(other, Protocol::ChunkFetching, now));
+		assert!((0..10).all(|_| limiter.allow(peer, Protocol::PoVFetching, now)));
+
+		// Tokens come back at the configured rate.
+		let later = now + Duration::from_millis(500);
+		assert!(limiter.allow(peer, Protocol::ChunkFetching, later));
+		assert!(!limiter.allow(peer, Protocol::ChunkFetching, later));
+	}
+
+	#[test]
+	fn responses_of_unlimited_protocols_are_audited() {
+		let audit = ReputationAudit::default();
+		let peer = PeerId::random();
+		let cost = Rep::CostMinor("Test cost");
+
+		let (mut incoming_tx, incoming_rx) = mpsc::channel(1);
+		let (mut forwarded, forward) = throttle(
+			Protocol::StatementFetching,
+			incoming_rx,
+			RequestRateLimits::default(),
+			Metrics::default(),
+			audit.clone(),
+		);
+
+		let (tx, rx) = oneshot::channel();
+		let answer = async move {
+			incoming_tx.send(network::IncomingRequest { peer, payload: Vec::new(), pending_response: tx })
+				.await
+				.unwrap();
+			let req = forwarded.next().await.unwrap();
+			req.pending_response.send(network::OutgoingResponse {
+				result: Ok(Vec::new()),
+				reputation_changes: vec![cost.into_base_rep()],
+				sent_feedback: None,
+			}).unwrap();
+			// Closing the incoming requests ends the forwarding once the response is out.
+		};
+
+		futures::executor::block_on(future::join(forward, answer));
+
+		assert!(futures::executor::block_on(rx).unwrap().result.is_ok());
+		assert_eq!(
+			audit.history(&peer).into_iter().map(|c| (c.reason, c.value)).collect::<Vec<_>>(),
+			vec![(cost.description(), cost.into_base_rep().value)],
+		);
+	}
+
+	#[test]
+	fn idle_buckets_are_pruned() {
+		let mut limiter = limiter("chunk_fetching:1:1");
+		let busy = PeerId::random();
+		let idle = PeerId::random();
+		let now = Instant::now();
+
+		assert!(limiter.allow(busy, Protocol::ChunkFetching, now));
+		assert!(limiter.allow(idle, Protocol::ChunkFetching, now - Duration::from_secs(10)));
+
+		limiter.prune(now);
+		assert!(limiter.buckets.contains_key(&(busy, Protocol::ChunkFetching)));
+		assert!(!limiter.buckets.contains_key(&(idle, Protocol::ChunkFetching)));
+	}
+}
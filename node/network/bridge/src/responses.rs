@@ -0,0 +1,136 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Accounting of responses passing through the bridge.
+//!
+//! Responses travel through oneshot channels between the network and the subsystems, so the
+//! bridge puts a channel of its own in between and accounts for the response while forwarding
+//! it. Reputation changes sent along with responses are applied by substrate, so they are
+//! recorded in the audit log on the way. The forwarding futures don't need a task of their own,
+//! they are polled by whoever intercepts the response.
+
+use futures::{channel::oneshot, future::BoxFuture, prelude::*, stream::FuturesUnordered};
+
+use sc_network::{config as network, PeerId};
+
+use polkadot_node_network_protocol::request_response::{Protocol, Requests};
+
+use crate::{Metrics, ReputationAudit};
+
+/// Futures forwarding intercepted responses.
+pub(crate) type PendingResponses = FuturesUnordered<BoxFuture<'static, ()>>;
+
+/// Intercept the response to an incoming request, before it is handed to a subsystem.
+///
+/// The returned future forwards the response to the network and needs to be polled, otherwise
+/// the requester never gets an answer.
+pub(crate) fn intercept_outgoing_response(
+	protocol: Protocol,
+	mut req: network::IncomingRequest,
+	metrics: Metrics,
+	reputation_audit: ReputationAudit,
+) -> (network::IncomingRequest, BoxFuture<'static, ()>) {
+	let (tx, rx) = oneshot::channel();
+	let pending_response = std::mem::replace(&mut req.pending_response, tx);
+	let peer = req.peer;
+
+	let forward = async move {
+		// Subsystems dropping the sender make the network see a canceled response, which we get
+		// by dropping `pending_response` as well.
+		if let Ok(response) = rx.await {
+			if let Ok(payload) = &response.result {
+				metrics.on_response_sent(protocol, payload.len());
+			}
+			note_reputation_changes(peer, &response, &metrics, &reputation_audit);
+			let _ = pending_response.send(response);
+		}
+	};
+
+	(req, forward.boxed())
+}
+
+/// Intercept the response to an outgoing request, before the request is handed to the network.
+///
+/// The returned future forwards the response to the requesting subsystem and needs to be polled,
+/// otherwise the subsystem never gets an answer.
+pub(crate) fn intercept_incoming_response(
+	req: &mut Requests,
+	metrics: Metrics,
+) -> BoxFuture<'static, ()> {
+	let protocol = req.get_protocol();
+	let (tx, rx) = oneshot::channel();
+	let pending_response = req.replace_response_sender(tx);
+
+	async move {
+		if let Ok(response) = rx.await {
+			if let Ok(payload) = &response {
+				metrics.on_response_received(protocol, payload.len());
+			}
+			let _ = pending_response.send(response);
+		}
+	}.boxed()
+}
+
+/// Account for the reputation changes sent along with a response to a peer.
+pub(crate) fn note_reputation_changes(
+	peer: PeerId,
+	response: &network::OutgoingResponse,
+	metrics: &Metrics,
+	reputation_audit: &ReputationAudit,
+) {
+	for change in &response.reputation_changes {
+		metrics.on_report_event(change.reason);
+		reputation_audit.note_base_change(peer, change.clone());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_network_protocol::UnifiedReputationChange as Rep;
+
+	const COST: Rep = Rep::CostMinor("Test cost");
+
+	#[test]
+	fn responses_are_forwarded_and_audited() {
+		let audit = ReputationAudit::default();
+		let peer = PeerId::random();
+		let (tx, rx) = oneshot::channel();
+		let req = network::IncomingRequest { peer, payload: Vec::new(), pending_response: tx };
+
+		let (req, forward) = intercept_outgoing_response(
+			Protocol::ChunkFetching,
+			req,
+			Metrics::default(),
+			audit.clone(),
+		);
+
+		req.pending_response.send(network::OutgoingResponse {
+			result: Ok(vec![1, 2, 3]),
+			reputation_changes: vec![COST.into_base_rep()],
+			sent_feedback: None,
+		}).unwrap();
+
+		futures::executor::block_on(forward);
+
+		let response = futures::executor::block_on(rx).unwrap();
+		assert_eq!(response.result, Ok(vec![1, 2, 3]));
+		assert_eq!(
+			audit.history(&peer).into_iter().map(|c| (c.reason, c.value)).collect::<Vec<_>>(),
+			vec![(COST.description(), COST.into_base_rep().value)],
+		);
+	}
+}
@@ -43,7 +43,9 @@ use sc_network::Multiaddr;
 use sc_network::config::RequestResponseConfig;
 use sp_keyring::Sr25519Keyring;
 use polkadot_primitives::v1::AuthorityDiscoveryId;
-use polkadot_node_network_protocol::{ObservedRole, request_response::request::Requests};
+use polkadot_node_network_protocol::{
+	ObservedRole, peer_set::CollationVersion, request_response::request::Requests,
+};
 
 use crate::network::Network;
 use crate::validator_discovery::AuthorityDiscovery;
@@ -170,11 +172,28 @@ impl TestNetworkHandle {
 		v
 	}
 
+	// Connect a peer, negotiating the first version of the peer set's protocol.
 	async fn connect_peer(&mut self, peer: PeerId, peer_set: PeerSet, role: ObservedRole) {
+		self.connect_peer_with_version(peer, peer_set, 1, role).await
+	}
+
+	async fn connect_peer_with_version(
+		&mut self,
+		peer: PeerId,
+		peer_set: PeerSet,
+		version: ProtocolVersion,
+		role: ObservedRole,
+	) {
+		let negotiated_fallback = if version == peer_set.get_main_version() {
+			None
+		} else {
+			peer_set.get_protocol_name_static(version).map(Into::into)
+		};
+
 		self.send_network_event(NetworkEvent::NotificationStreamOpened {
 			remote: peer,
 			protocol: peer_set.into_protocol_name(),
-			negotiated_fallback: None,
+			negotiated_fallback,
 			role: role.into(),
 		}).await;
 	}
@@ -289,6 +308,7 @@ fn test_harness<T: Future<Output=VirtualOverseer>>(
 		request_multiplexer,
 		metrics: Metrics(None),
 		sync_oracle,
+		reputation_audit: Default::default(),
 	};
 
 	let network_bridge = run_network(
@@ -314,7 +334,7 @@ fn test_harness<T: Future<Output=VirtualOverseer>>(
 }
 
 async fn assert_sends_validation_event_to_all(
-	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	event: NetworkBridgeEvent<VersionedValidationProtocol>,
 	virtual_overseer: &mut TestSubsystemContextHandle<NetworkBridgeMessage>,
 ) {
 	// Ordering must match the enum variant order
@@ -323,21 +343,21 @@ async fn assert_sends_validation_event_to_all(
 		virtual_overseer.recv().await,
 		AllMessages::StatementDistribution(
 			StatementDistributionMessage::NetworkBridgeUpdateV1(e)
-		) if e == event.focus().expect("could not focus message")
+		) if e == event.clone().focus().expect("could not focus message")
 	);
 
 	assert_matches!(
 		virtual_overseer.recv().await,
 		AllMessages::BitfieldDistribution(
 			BitfieldDistributionMessage::NetworkBridgeUpdateV1(e)
-		) if e == event.focus().expect("could not focus message")
+		) if e == event.clone().focus().expect("could not focus message")
 	);
 
 	assert_matches!(
 		virtual_overseer.recv().await,
 		AllMessages::ApprovalDistribution(
 			ApprovalDistributionMessage::NetworkBridgeUpdateV1(e)
-		) if e == event.focus().expect("could not focus message")
+		) if e == event.clone().focus().expect("could not focus message")
 	);
 }
 
@@ -349,7 +369,7 @@ async fn assert_sends_collation_event_to_all(
 		virtual_overseer.recv().await,
 		AllMessages::CollatorProtocol(
 			CollatorProtocolMessage::NetworkBridgeUpdateV1(e)
-		) if e == event.focus().expect("could not focus message")
+		) if e == event.clone().focus().expect("could not focus message")
 	)
 }
 
@@ -704,7 +724,7 @@ fn peer_view_updates_sent_via_overseer() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -762,7 +782,7 @@ fn peer_messages_sent_via_overseer() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -799,7 +819,7 @@ fn peer_messages_sent_via_overseer() {
 				)
 			) => {
 				assert_eq!(p, peer);
-				assert_eq!(m, approval_distribution_message);
+				assert_eq!(m, Versioned::V1(approval_distribution_message));
 			}
 		);
 
@@ -840,7 +860,7 @@ fn peer_disconnect_from_just_one_peerset() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -852,7 +872,7 @@ fn peer_disconnect_from_just_one_peerset() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, CollationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -931,7 +951,7 @@ fn relays_collation_protocol_messages() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -943,7 +963,7 @@ fn relays_collation_protocol_messages() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, CollationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -1032,7 +1052,7 @@ fn different_views_on_different_peer_sets() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -1044,7 +1064,7 @@ fn different_views_on_different_peer_sets() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, CollationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -1199,7 +1219,7 @@ fn send_messages_to_peers() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -1211,7 +1231,7 @@ fn send_messages_to_peers() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, CollationVersion::V1.into(), None),
 				&mut virtual_overseer,
 			).await;
 
@@ -1240,7 +1260,7 @@ fn send_messages_to_peers() {
 			virtual_overseer.send(FromOverseer::Communication {
 				msg: NetworkBridgeMessage::SendValidationMessage(
 					vec![peer.clone()],
-					message.clone(),
+					Versioned::V1(message.clone()),
 				)
 			}).await;
 
@@ -1287,6 +1307,119 @@ fn send_messages_to_peers() {
 	});
 }
 
+#[test]
+fn validation_messages_use_negotiated_version() {
+	test_harness(done_syncing_oracle(), |test_harness| async move {
+		let TestHarness {
+			mut network_handle,
+			mut virtual_overseer,
+		} = test_harness;
+
+		let peer = PeerId::random();
+
+		network_handle.connect_peer_with_version(
+			peer.clone(),
+			PeerSet::Validation,
+			ValidationVersion::V2.into(),
+			ObservedRole::Full,
+		).await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::DisputeDistribution(
+				DisputeDistributionMessage::DisputeSendingReceiver(_)
+			)
+		);
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::StatementDistribution(
+				StatementDistributionMessage::StatementFetchingReceiver(_)
+			)
+		);
+
+		// subsystems learn about the negotiated version.
+		{
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, ValidationVersion::V2.into(), None),
+				&mut virtual_overseer,
+			).await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+				&mut virtual_overseer,
+			).await;
+		}
+
+		// consume our view update.
+		{
+			let _peer_view_change = network_handle.next_network_action().await;
+		}
+
+		let approval_distribution_message = protocol_v1::ApprovalDistributionMessage::Approvals(
+			Vec::new()
+		);
+
+		// v1 messages are not sent to a peer which negotiated v2, v2 messages are.
+		{
+			let message_v1 = protocol_v1::ValidationProtocol::ApprovalDistribution(
+				approval_distribution_message.clone(),
+			);
+			let message_v2 = protocol_v2::ValidationProtocol::ApprovalDistribution(
+				approval_distribution_message.clone(),
+			);
+
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessage(
+					vec![peer.clone()],
+					Versioned::V1(message_v1),
+				)
+			}).await;
+
+			virtual_overseer.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessage(
+					vec![peer.clone()],
+					Versioned::V2(message_v2.clone()),
+				)
+			}).await;
+
+			assert_eq!(
+				network_handle.next_network_action().await,
+				NetworkAction::WriteNotification(
+					peer.clone(),
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(message_v2).encode(),
+				)
+			);
+		}
+
+		// messages of the peer are decoded and delivered as v2.
+		{
+			let message = protocol_v2::ValidationProtocol::ApprovalDistribution(
+				approval_distribution_message.clone(),
+			);
+
+			network_handle.peer_message(
+				peer.clone(),
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(message).encode(),
+			).await;
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::ApprovalDistribution(
+					ApprovalDistributionMessage::NetworkBridgeUpdateV1(
+						NetworkBridgeEvent::PeerMessage(p, m)
+					)
+				) => {
+					assert_eq!(p, peer);
+					assert_eq!(m, Versioned::V2(approval_distribution_message));
+				}
+			);
+		}
+		virtual_overseer
+	});
+}
+
 #[test]
 fn spread_event_to_subsystems_is_up_to_date() {
 	// Number of subsystems expected to be interested in a network event,
@@ -1314,6 +1447,7 @@ fn spread_event_to_subsystems_is_up_to_date() {
 			AllMessages::ApprovalVoting(_) => unreachable!("Not interested in network events"),
 			AllMessages::ApprovalDistribution(_) => { cnt += 1; }
 			AllMessages::GossipSupport(_) => unreachable!("Not interested in network events"),
+			AllMessages::PvfChecker(_) => unreachable!("Not interested in network events"),
 			AllMessages::DisputeCoordinator(_) => unreachable!("Not interested in network events"),
 			AllMessages::DisputeParticipation(_) => unreachable!("Not interested in network events"),
 			AllMessages::DisputeDistribution(_) => unreachable!("Not interested in network events"),
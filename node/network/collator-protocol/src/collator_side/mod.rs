@@ -20,8 +20,8 @@ use futures::{FutureExt, StreamExt, channel::oneshot, stream::FuturesUnordered,
 use sp_core::Pair;
 
 use polkadot_primitives::v1::{
-	AuthorityDiscoveryId, CandidateHash, CandidateReceipt, CollatorPair, CoreIndex, CoreState, GroupIndex, Hash,
-	Id as ParaId,
+	AuthorityDiscoveryId, BlockNumber, CandidateHash, CandidateReceipt, CollatorPair, CoreIndex, CoreState,
+	GroupIndex, Hash, Id as ParaId,
 };
 use polkadot_subsystem::{
 	overseer,
@@ -65,6 +65,13 @@ const COST_APPARENT_FLOOD: Rep = Rep::CostMinor("Message received when previous
 /// There is debug logging output, so we can adjust this value based on production results.
 const MAX_UNSHARED_UPLOAD_TIME: Duration = Duration::from_millis(400);
 
+/// Number of blocks before a group rotation in which we also advertise to the next group.
+///
+/// A collation can be backed at the earliest in the block after its relay parent. If the group
+/// rotation happens right after that, any delay in backing means that the next group is
+/// responsible for our core, so we advertise to both groups around the boundary.
+const NEXT_GROUP_ADVERTISEMENT_WINDOW: BlockNumber = 1;
+
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
 
@@ -151,20 +158,45 @@ impl metrics::Metrics for Metrics {
 struct ValidatorGroup {
 	/// All [`AuthorityDiscoveryId`]'s that are assigned to us in this group.
 	discovery_ids: HashSet<AuthorityDiscoveryId>,
+	/// All [`AuthorityDiscoveryId`]'s of the group that is assigned to us after the next rotation.
+	next_discovery_ids: HashSet<AuthorityDiscoveryId>,
+	/// Whether the next rotation is close enough that we also advertise to the next group.
+	advertise_to_next: bool,
 	/// All [`ValidatorId`]'s of the current group to that we advertised our collation.
 	advertised_to: HashSet<AuthorityDiscoveryId>,
 }
 
 impl ValidatorGroup {
+	/// Create a new group from the current and the next group assigned to our core.
+	fn new(current: GroupValidators, next: GroupValidators, blocks_until_rotation: BlockNumber) -> Self {
+		Self {
+			discovery_ids: current.validators.into_iter().collect(),
+			next_discovery_ids: next.validators.into_iter().collect(),
+			advertise_to_next: blocks_until_rotation <= NEXT_GROUP_ADVERTISEMENT_WINDOW,
+			advertised_to: HashSet::new(),
+		}
+	}
+
 	/// Returns `true` if we should advertise our collation to the given peer.
 	fn should_advertise_to(&self, peer_ids: &HashMap<PeerId, AuthorityDiscoveryId>, peer: &PeerId)
 		-> bool {
 		match peer_ids.get(peer) {
-			Some(discovery_id) => !self.advertised_to.contains(discovery_id),
+			Some(discovery_id) => self.is_responsible(discovery_id) && !self.advertised_to.contains(discovery_id),
 			None => false,
 		}
 	}
 
+	/// Whether the given validator is in the current group, or in the next group if the rotation is imminent.
+	fn is_responsible(&self, discovery_id: &AuthorityDiscoveryId) -> bool {
+		self.discovery_ids.contains(discovery_id) ||
+			(self.advertise_to_next && self.next_discovery_ids.contains(discovery_id))
+	}
+
+	/// All validators we want to be connected to, that is the current and the next group.
+	fn validators_to_connect(&self) -> impl Iterator<Item = &AuthorityDiscoveryId> {
+		self.discovery_ids.iter().chain(self.next_discovery_ids.iter())
+	}
+
 	/// Should be called after we advertised our collation to the given `peer` to keep track of it.
 	fn advertised_to_peer(&mut self, peer_ids: &HashMap<PeerId, AuthorityDiscoveryId>, peer: &PeerId) {
 		if let Some(validator_id) = peer_ids.get(peer) {
@@ -173,15 +205,6 @@ impl ValidatorGroup {
 	}
 }
 
-impl From<HashSet<AuthorityDiscoveryId>> for ValidatorGroup {
-	fn from(discovery_ids: HashSet<AuthorityDiscoveryId>) -> Self {
-		Self {
-			discovery_ids,
-			advertised_to: HashSet::new(),
-		}
-	}
-}
-
 /// The status of a collation as seen from the collator.
 enum CollationStatus {
 	/// The collation was created, but we did not advertise it to any validator.
@@ -263,6 +286,11 @@ struct State {
 	/// Our validator groups per active leaf.
 	our_validators_groups: HashMap<Hash, ValidatorGroup>,
 
+	/// The validators of our last connection request.
+	///
+	/// These are the current and next groups of all relay parents in `our_validators_groups`.
+	connected_validators: HashSet<AuthorityDiscoveryId>,
+
 	/// The mapping from [`PeerId`] to [`ValidatorId`]. This is filled over time as we learn the [`PeerId`]'s
 	/// by `PeerConnected` events.
 	peer_ids: HashMap<PeerId, AuthorityDiscoveryId>,
@@ -297,6 +325,7 @@ impl State {
 			collations: Default::default(),
 			collation_result_senders: Default::default(),
 			our_validators_groups: Default::default(),
+			connected_validators: Default::default(),
 			peer_ids: Default::default(),
 			waiting_collation_fetches: Default::default(),
 			active_collation_fetches: Default::default(),
@@ -311,6 +340,15 @@ impl State {
 			.map(|(peer, _)| *peer)
 			.collect()
 	}
+
+	/// The current and next groups of all relay parents we have a collation for.
+	fn validators_to_connect(&self) -> HashSet<AuthorityDiscoveryId> {
+		self.our_validators_groups
+			.values()
+			.flat_map(|group| group.validators_to_connect())
+			.cloned()
+			.collect()
+	}
 }
 
 /// Distribute a collation.
@@ -369,7 +407,7 @@ where
 	};
 
 	// Determine the group on that core and the next group on that core.
-	let (current_validators, next_validators) =
+	let (current_validators, next_validators, blocks_until_rotation) =
 		determine_our_validators(ctx, runtime, our_core, num_cores, relay_parent,).await?;
 
 	if current_validators.validators.is_empty() && next_validators.validators.is_empty() {
@@ -391,21 +429,19 @@ where
 		core = ?our_core,
 		?current_validators,
 		?next_validators,
+		blocks_until_rotation,
 		"Accepted collation, connecting to validators."
 	);
 
-	let validator_group: HashSet<_> = current_validators.validators.iter().map(Clone::clone).collect();
-
-	// Issue a discovery request for the validators of the current group and the next group:
-	connect_to_validators(
-		ctx,
-		current_validators.validators
-			.into_iter()
-			.chain(next_validators.validators.into_iter())
-			.collect(),
-	).await;
+	state.our_validators_groups.insert(
+		relay_parent,
+		ValidatorGroup::new(current_validators, next_validators, blocks_until_rotation),
+	);
 
-	state.our_validators_groups.insert(relay_parent, validator_group.into());
+	// Issue a discovery request for the validators of the current group and the next group. As a
+	// new request revokes the previous one, we also keep the groups of our other relay parents.
+	state.connected_validators = state.validators_to_connect();
+	connect_to_validators(ctx, state.connected_validators.iter().cloned().collect()).await;
 
 	if let Some(result_sender) = result_sender {
 		state.collation_result_senders.insert(receipt.hash(), result_sender);
@@ -457,14 +493,15 @@ struct GroupValidators {
 
 /// Figure out current and next group of validators assigned to the para being collated on.
 ///
-/// Returns [`ValidatorId`]'s of current and next group as determined based on the `relay_parent`.
+/// Returns [`ValidatorId`]'s of current and next group as determined based on the `relay_parent`, together with the
+/// number of blocks until the next group takes over our core.
 async fn determine_our_validators<Context>(
 	ctx: &mut Context,
 	runtime: &mut RuntimeInfo,
 	core_index: CoreIndex,
 	cores: usize,
 	relay_parent: Hash,
-) -> Result<(GroupValidators, GroupValidators)>
+) -> Result<(GroupValidators, GroupValidators, BlockNumber)>
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
@@ -480,8 +517,17 @@ where
 	let current_group_index = rotation_info.group_for_core(core_index, cores);
 	let current_validators = groups.get(current_group_index.0 as usize).map(|v| v.as_slice()).unwrap_or_default();
 
-	let next_group_idx = (current_group_index.0 as usize + 1) % groups.len();
-	let next_validators = groups.get(next_group_idx).map(|v| v.as_slice()).unwrap_or_default();
+	// Without rotations the current group stays assigned to our core for the whole session.
+	let (next_group_index, blocks_until_rotation) = if rotation_info.group_rotation_frequency == 0 {
+		(current_group_index, BlockNumber::MAX)
+	} else {
+		let next_rotation_info = rotation_info.bump_rotation();
+		let next_group_index = next_rotation_info.group_for_core(core_index, cores);
+		debug_assert_eq!(next_rotation_info.core_for_group(next_group_index, cores), core_index);
+
+		(next_group_index, rotation_info.next_rotation_at().saturating_sub(rotation_info.now))
+	};
+	let next_validators = groups.get(next_group_index.0 as usize).map(|v| v.as_slice()).unwrap_or_default();
 
 	let validators = &info.discovery_keys;
 
@@ -493,11 +539,11 @@ where
 		validators: current_validators,
 	};
 	let next_validators = GroupValidators {
-		group: GroupIndex(next_group_idx as u32),
+		group: next_group_index,
 		validators: next_validators,
 	};
 
-	Ok((current_validators, next_validators))
+	Ok((current_validators, next_validators, blocks_until_rotation))
 }
 
 /// Issue a `Declare` collation message to the given `peer`.
@@ -580,7 +626,7 @@ where
 				target: LOG_TARGET,
 				?relay_parent,
 				peer_id = %peer,
-				"Not advertising collation as we already advertised it to this validator.",
+				"Not advertising collation to this validator, as it is not assigned to our core or we already advertised it.",
 			);
 			return
 		}
@@ -896,7 +942,7 @@ where
 	use NetworkBridgeEvent::*;
 
 	match bridge_message {
-		PeerConnected(peer_id, observed_role, maybe_authority) => {
+		PeerConnected(peer_id, observed_role, _version, maybe_authority) => {
 			// If it is possible that a disconnected validator would attempt a reconnect
 			// it should be handled here.
 			tracing::trace!(
@@ -941,7 +987,7 @@ where
 				?view,
 				"Own view change",
 			);
-			handle_our_view_change(state, view).await?;
+			handle_our_view_change(ctx, state, view).await?;
 		}
 		PeerMessage(remote, msg) => {
 			handle_incoming_peer_message(ctx, runtime, state, remote, msg).await?;
@@ -955,10 +1001,15 @@ where
 }
 
 /// Handles our view changes.
-async fn handle_our_view_change(
+async fn handle_our_view_change<Context>(
+	ctx: &mut Context,
 	state: &mut State,
 	view: OurView,
-) -> Result<()> {
+) -> Result<()>
+where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+{
 	for removed in state.view.difference(&view) {
 		tracing::debug!(target: LOG_TARGET, relay_parent = ?removed, "Removing relay parent because our view changed.");
 
@@ -993,6 +1044,20 @@ async fn handle_our_view_change(
 
 	state.view = view;
 
+	// Drop connections to groups which are only relevant for relay parents we no longer have a
+	// collation for. If there are none left, we keep the connections until the next collation, as
+	// the next group is most likely still the right one.
+	let validators = state.validators_to_connect();
+	if !validators.is_empty() && validators != state.connected_validators {
+		tracing::debug!(
+			target: LOG_TARGET,
+			num_validators = validators.len(),
+			"Dropping connections to stale validator groups.",
+		);
+		state.connected_validators = validators;
+		connect_to_validators(ctx, state.connected_validators.iter().cloned().collect()).await;
+	}
+
 	Ok(())
 }
 
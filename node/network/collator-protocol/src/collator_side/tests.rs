@@ -29,6 +29,7 @@ use sp_runtime::traits::AppVerify;
 use polkadot_node_network_protocol::{
 	our_view,
 	view,
+	peer_set::CollationVersion,
 	request_response::request::IncomingRequest,
 };
 use polkadot_node_subsystem_util::TimeoutExt;
@@ -74,7 +75,7 @@ struct TestState {
 	group_rotation_info: GroupRotationInfo,
 	validator_peer_id: Vec<PeerId>,
 	relay_parent: Hash,
-	availability_core: CoreState,
+	availability_cores: Vec<CoreState>,
 	local_peer_id: PeerId,
 	collator_pair: CollatorPair,
 	session_index: SessionIndex,
@@ -115,10 +116,10 @@ impl Default for TestState {
 			now: 1,
 		};
 
-		let availability_core = CoreState::Scheduled(ScheduledCore {
+		let availability_cores = vec![CoreState::Scheduled(ScheduledCore {
 			para_id,
 			collator: None,
-		});
+		})];
 
 		let relay_parent = Hash::random();
 
@@ -137,7 +138,7 @@ impl Default for TestState {
 			group_rotation_info,
 			validator_peer_id,
 			relay_parent,
-			availability_core,
+			availability_cores,
 			local_peer_id,
 			collator_pair,
 			session_index: 1,
@@ -345,7 +346,7 @@ async fn distribute_collation(
 			RuntimeApiRequest::AvailabilityCores(tx)
 		)) => {
 			assert_eq!(relay_parent, test_state.relay_parent);
-			tx.send(Ok(vec![test_state.availability_core.clone()])).unwrap();
+			tx.send(Ok(test_state.availability_cores.clone())).unwrap();
 		}
 	);
 
@@ -416,6 +417,7 @@ async fn connect_peer(
 			NetworkBridgeEvent::PeerConnected(
 				peer.clone(),
 				polkadot_node_network_protocol::ObservedRole::Authority,
+				CollationVersion::V1.into(),
 				authority_id,
 			),
 		),
@@ -815,6 +817,123 @@ fn validator_reconnect_does_not_advertise_a_second_time() {
 	})
 }
 
+/// A test state with two cores, where the second group takes over our core in `blocks_until_rotation` blocks.
+fn test_state_before_rotation(blocks_until_rotation: BlockNumber) -> TestState {
+	let mut test_state = TestState::default();
+	test_state.availability_cores.push(CoreState::Free);
+	test_state.group_rotation_info.now =
+		test_state.group_rotation_info.group_rotation_frequency - blocks_until_rotation;
+	test_state
+}
+
+#[test]
+fn advertise_to_next_group_right_before_rotation() {
+	let test_state = test_state_before_rotation(1);
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(local_peer_id, collator_pair, |test_harness| async move {
+		let mut virtual_overseer = test_harness.virtual_overseer;
+
+		// Dave is only part of the next group.
+		let peer = test_state.validator_peer_id[3].clone();
+		let validator_id = test_state.session_info.discovery_keys[3].clone();
+
+		setup_system(&mut virtual_overseer, &test_state).await;
+
+		connect_peer(&mut virtual_overseer, peer.clone(), Some(validator_id)).await;
+		expect_declare_msg(&mut virtual_overseer, &test_state, &peer).await;
+
+		distribute_collation(&mut virtual_overseer, &test_state, true).await;
+
+		send_peer_view_change(&mut virtual_overseer, &peer, vec![test_state.relay_parent]).await;
+		expect_advertise_collation_msg(&mut virtual_overseer, &peer, test_state.relay_parent).await;
+		virtual_overseer
+	})
+}
+
+#[test]
+fn no_advertisement_to_next_group_long_before_rotation() {
+	let test_state = test_state_before_rotation(10);
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(local_peer_id, collator_pair, |test_harness| async move {
+		let mut virtual_overseer = test_harness.virtual_overseer;
+
+		let peer = test_state.validator_peer_id[3].clone();
+		let validator_id = test_state.session_info.discovery_keys[3].clone();
+
+		setup_system(&mut virtual_overseer, &test_state).await;
+
+		connect_peer(&mut virtual_overseer, peer.clone(), Some(validator_id)).await;
+		expect_declare_msg(&mut virtual_overseer, &test_state, &peer).await;
+
+		// We still connect to the next group in advance ...
+		distribute_collation(&mut virtual_overseer, &test_state, true).await;
+
+		// ... but only advertise to it once the rotation is imminent.
+		send_peer_view_change(&mut virtual_overseer, &peer, vec![test_state.relay_parent]).await;
+		assert!(overseer_recv_with_timeout(&mut virtual_overseer, TIMEOUT).await.is_none());
+		virtual_overseer
+	})
+}
+
+#[test]
+fn connections_to_stale_groups_are_dropped() {
+	let mut test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	// Three cores with one validator per group, so each rotation brings in a new validator.
+	test_state.session_info.validator_groups = vec![
+		vec![ValidatorIndex(0)],
+		vec![ValidatorIndex(1)],
+		vec![ValidatorIndex(2)],
+	];
+	test_state.availability_cores.extend(vec![CoreState::Free, CoreState::Free]);
+
+	test_harness(local_peer_id, collator_pair, |test_harness| async move {
+		let mut virtual_overseer = test_harness.virtual_overseer;
+		let discovery_keys = test_state.session_info.discovery_keys.clone();
+
+		setup_system(&mut virtual_overseer, &test_state).await;
+
+		// Groups 0 and 1 are responsible for our core at the first relay parent.
+		distribute_collation(&mut virtual_overseer, &test_state, true).await;
+
+		// Groups 1 and 2 are responsible at the second one, after a rotation.
+		test_state.advance_to_new_round(&mut virtual_overseer, true).await;
+		test_state.group_rotation_info.now += test_state.group_rotation_info.group_rotation_frequency;
+		distribute_collation(&mut virtual_overseer, &test_state, false).await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToValidators { validator_ids, .. }) => {
+				let validator_ids: HashSet<_> = validator_ids.into_iter().collect();
+				assert_eq!(validator_ids, discovery_keys[..3].iter().cloned().collect::<HashSet<_>>());
+			}
+		);
+
+		// Once the first relay parent goes out of view, we no longer need group 0.
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(
+				NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent]),
+			),
+		).await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToValidators { validator_ids, .. }) => {
+				let validator_ids: HashSet<_> = validator_ids.into_iter().collect();
+				assert_eq!(validator_ids, discovery_keys[1..3].iter().cloned().collect::<HashSet<_>>());
+			}
+		);
+		virtual_overseer
+	})
+}
+
 #[test]
 fn collators_reject_declare_messages() {
 	let test_state = TestState::default();
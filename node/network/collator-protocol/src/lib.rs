@@ -20,14 +20,15 @@
 #![deny(missing_docs, unused_crate_dependencies)]
 #![recursion_limit="256"]
 
-use std::time::Duration;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use futures::{FutureExt, TryFutureExt};
+use kvdb::KeyValueDB;
 
 use sp_keystore::SyncCryptoStorePtr;
 
 use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange as Rep};
-use polkadot_primitives::v1::CollatorPair;
+use polkadot_primitives::v1::{CollatorPair, Id as ParaId};
 
 use polkadot_subsystem::{
 	SpawnedSubsystem,
@@ -46,6 +47,11 @@ use error::Result;
 mod collator_side;
 mod validator_side;
 
+mod trusted_collators;
+pub use trusted_collators::{
+	CollatorTrust, TrustedCollatorEntry, TrustedCollators, TrustedCollatorsError,
+};
+
 const LOG_TARGET: &'static str = "parachain::collator-protocol";
 
 /// A collator eviction policy - how fast to evict collators which are inactive.
@@ -66,6 +72,34 @@ impl Default for CollatorEvictionPolicy {
 	}
 }
 
+/// How collation fetches are shared between paras.
+///
+/// Validators may be assigned to several paras at once, e.g. while cores rotate. Fetches of
+/// advertisements waiting in line are shared between those paras according to their weights.
+#[derive(Debug, Clone)]
+pub struct CollationFetchingConfig {
+	/// Maximum number of collations fetched concurrently per para.
+	pub max_parallel_fetches_per_para: usize,
+	/// Relative share of fetches per para. Paras without an entry have a weight of 1.
+	pub para_weights: HashMap<ParaId, u32>,
+}
+
+impl Default for CollationFetchingConfig {
+	fn default() -> Self {
+		CollationFetchingConfig {
+			max_parallel_fetches_per_para: 3,
+			para_weights: HashMap::new(),
+		}
+	}
+}
+
+/// Configuration of the persisted collator reputations.
+#[derive(Debug, Clone, Copy)]
+pub struct ReputationConfig {
+	/// The column family in the database used for collator reputations.
+	pub col_data: u32,
+}
+
 /// What side of the collator protocol is being engaged
 pub enum ProtocolSide {
 	/// Validators operate on the relay chain.
@@ -74,6 +108,14 @@ pub enum ProtocolSide {
 		keystore: SyncCryptoStorePtr,
 		/// An eviction policy for inactive peers or validators.
 		eviction_policy: CollatorEvictionPolicy,
+		/// Per-para collators to accept exclusively or to fetch from first.
+		trusted_collators: TrustedCollators,
+		/// How collation fetches are shared between paras.
+		fetching_config: CollationFetchingConfig,
+		/// The database collator reputations are persisted in.
+		db: Arc<dyn KeyValueDB>,
+		/// Where in the database collator reputations are stored.
+		reputation_config: ReputationConfig,
 		/// Prometheus metrics for validators.
 		metrics: validator_side::Metrics,
 	},
@@ -103,10 +145,21 @@ impl CollatorProtocolSubsystem {
 		Context: SubsystemContext<Message=CollatorProtocolMessage>,
 	{
 		match self.protocol_side {
-			ProtocolSide::Validator { keystore, eviction_policy, metrics } => validator_side::run(
+			ProtocolSide::Validator {
+				keystore,
+				eviction_policy,
+				trusted_collators,
+				fetching_config,
+				db,
+				reputation_config,
+				metrics,
+			} => validator_side::run(
 				ctx,
 				keystore,
 				eviction_policy,
+				trusted_collators,
+				fetching_config,
+				validator_side::ReputationStore::new(db, reputation_config),
 				metrics,
 			).await,
 			ProtocolSide::Collator(local_peer_id, collator_pair, metrics) => collator_side::run(
@@ -0,0 +1,214 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-para lists of trusted collators, as configured by the node operator.
+//!
+//! Entries are of the form `<para_id>:<allow|prefer>:<collator_id>`, where the collator id is
+//! given in SS58 format. The same format is used on the command line and in files, where each
+//! non-empty line not starting with `#` holds one entry.
+
+use std::{collections::{HashMap, HashSet}, path::Path, str::FromStr};
+
+use sp_core::crypto::Ss58Codec;
+use thiserror::Error;
+
+use polkadot_primitives::v1::{CollatorId, Id as ParaId};
+
+/// How the listed collators of a para are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollatorTrust {
+	/// Only the listed collators are accepted for the para.
+	Allow,
+	/// All collators are accepted, but collations of the listed ones are fetched first.
+	Prefer,
+}
+
+/// Errors when parsing or assembling trusted collator lists.
+#[derive(Debug, Error)]
+pub enum TrustedCollatorsError {
+	/// An entry did not consist of three `:` separated fields.
+	#[error("Malformed trusted collator entry {0:?}, expected `<para_id>:<allow|prefer>:<collator_id>`")]
+	MalformedEntry(String),
+
+	/// The para id of an entry could not be parsed.
+	#[error("Invalid para id {0:?}")]
+	InvalidParaId(String),
+
+	/// The trust mode of an entry was neither `allow` nor `prefer`.
+	#[error("Invalid trust mode {0:?}, expected `allow` or `prefer`")]
+	InvalidTrust(String),
+
+	/// The collator id of an entry was not a valid SS58 address.
+	#[error("Invalid collator id {0:?}")]
+	InvalidCollatorId(String),
+
+	/// A para was given both `allow` and `prefer` entries.
+	#[error("Para {0} has both `allow` and `prefer` entries")]
+	ConflictingTrust(ParaId),
+
+	/// Reading a trusted collators file failed.
+	#[error("Reading trusted collators file failed")]
+	Io(#[from] std::io::Error),
+}
+
+/// A single trusted collator entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedCollatorEntry {
+	/// The para the collator is trusted for.
+	pub para_id: ParaId,
+	/// How the collator is treated.
+	pub trust: CollatorTrust,
+	/// The trusted collator.
+	pub collator_id: CollatorId,
+}
+
+impl FromStr for TrustedCollatorEntry {
+	type Err = TrustedCollatorsError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut fields = s.trim().splitn(3, ':');
+		let (para_id, trust, collator_id) = match (fields.next(), fields.next(), fields.next()) {
+			(Some(p), Some(t), Some(c)) => (p.trim(), t.trim(), c.trim()),
+			_ => return Err(TrustedCollatorsError::MalformedEntry(s.into())),
+		};
+
+		let para_id = u32::from_str(para_id)
+			.map(ParaId::from)
+			.map_err(|_| TrustedCollatorsError::InvalidParaId(para_id.into()))?;
+
+		let trust = match trust {
+			"allow" => CollatorTrust::Allow,
+			"prefer" => CollatorTrust::Prefer,
+			other => return Err(TrustedCollatorsError::InvalidTrust(other.into())),
+		};
+
+		let collator_id = CollatorId::from_ss58check(collator_id)
+			.map_err(|_| TrustedCollatorsError::InvalidCollatorId(collator_id.into()))?;
+
+		Ok(TrustedCollatorEntry { para_id, trust, collator_id })
+	}
+}
+
+/// The trusted collators of all configured paras.
+///
+/// Paras without an entry accept all collators, in advertisement order.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedCollators {
+	paras: HashMap<ParaId, (CollatorTrust, HashSet<CollatorId>)>,
+}
+
+impl TrustedCollators {
+	/// Add an entry. Fails if the para already has entries with a different trust mode.
+	pub fn insert(&mut self, entry: TrustedCollatorEntry) -> Result<(), TrustedCollatorsError> {
+		let TrustedCollatorEntry { para_id, trust, collator_id } = entry;
+		let (existing, collators) = self.paras
+			.entry(para_id)
+			.or_insert_with(|| (trust, HashSet::new()));
+
+		if *existing != trust {
+			return Err(TrustedCollatorsError::ConflictingTrust(para_id))
+		}
+
+		collators.insert(collator_id);
+		Ok(())
+	}
+
+	/// Add all entries of the given file.
+	pub fn extend_from_file(&mut self, path: &Path) -> Result<(), TrustedCollatorsError> {
+		let content = std::fs::read_to_string(path)?;
+		for line in content.lines().map(str::trim) {
+			if line.is_empty() || line.starts_with('#') {
+				continue
+			}
+
+			self.insert(line.parse()?)?;
+		}
+
+		Ok(())
+	}
+
+	/// Whether a collator may collate for the given para at all.
+	pub(crate) fn is_allowed(&self, para_id: ParaId, collator_id: &CollatorId) -> bool {
+		match self.paras.get(&para_id) {
+			Some((CollatorTrust::Allow, collators)) => collators.contains(collator_id),
+			Some((CollatorTrust::Prefer, _)) | None => true,
+		}
+	}
+
+	/// Whether collations of a collator should be fetched before those of unlisted collators.
+	pub(crate) fn is_preferred(&self, para_id: ParaId, collator_id: &CollatorId) -> bool {
+		self.paras.get(&para_id).map_or(false, |(_, collators)| collators.contains(collator_id))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::Sr25519Keyring;
+
+	fn entry(para: u32, trust: &str, keyring: Sr25519Keyring) -> String {
+		format!("{}:{}:{}", para, trust, keyring.public().to_ss58check())
+	}
+
+	#[test]
+	fn entries_parse() {
+		let parsed: TrustedCollatorEntry = entry(100, "prefer", Sr25519Keyring::Alice).parse().unwrap();
+		assert_eq!(parsed.para_id, ParaId::from(100));
+		assert_eq!(parsed.trust, CollatorTrust::Prefer);
+		assert_eq!(parsed.collator_id, CollatorId::from(Sr25519Keyring::Alice.public()));
+
+		assert!(matches!(
+			"100:allow".parse::<TrustedCollatorEntry>(),
+			Err(TrustedCollatorsError::MalformedEntry(_)),
+		));
+		assert!(matches!(
+			entry(100, "trust", Sr25519Keyring::Alice).parse::<TrustedCollatorEntry>(),
+			Err(TrustedCollatorsError::InvalidTrust(_)),
+		));
+		assert!(matches!(
+			"para:allow:x".parse::<TrustedCollatorEntry>(),
+			Err(TrustedCollatorsError::InvalidParaId(_)),
+		));
+		assert!(matches!(
+			"100:allow:x".parse::<TrustedCollatorEntry>(),
+			Err(TrustedCollatorsError::InvalidCollatorId(_)),
+		));
+	}
+
+	#[test]
+	fn allow_and_prefer_semantics() {
+		let alice = CollatorId::from(Sr25519Keyring::Alice.public());
+		let bob = CollatorId::from(Sr25519Keyring::Bob.public());
+
+		let mut trusted = TrustedCollators::default();
+		trusted.insert(entry(1, "allow", Sr25519Keyring::Alice).parse().unwrap()).unwrap();
+		trusted.insert(entry(2, "prefer", Sr25519Keyring::Alice).parse().unwrap()).unwrap();
+
+		assert!(trusted.is_allowed(1.into(), &alice));
+		assert!(!trusted.is_allowed(1.into(), &bob));
+		assert!(trusted.is_allowed(2.into(), &bob));
+		assert!(trusted.is_allowed(3.into(), &bob));
+
+		assert!(trusted.is_preferred(2.into(), &alice));
+		assert!(!trusted.is_preferred(2.into(), &bob));
+		assert!(!trusted.is_preferred(3.into(), &alice));
+
+		assert!(matches!(
+			trusted.insert(entry(1, "prefer", Sr25519Keyring::Bob).parse().unwrap()),
+			Err(TrustedCollatorsError::ConflictingTrust(_)),
+		));
+	}
+}
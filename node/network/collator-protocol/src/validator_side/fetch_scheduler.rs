@@ -0,0 +1,158 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fair scheduling of collation fetches between paras.
+//!
+//! Each started fetch advances the virtual time of its para by the inverse of the para's weight,
+//! the next fetch goes to the para with the smallest virtual time (start-time fair queuing). Paras
+//! which have not fetched for a while start at the current virtual time, so they can't build up
+//! credit by being idle. On top of that the number of concurrent fetches per para is bounded.
+
+use std::collections::HashMap;
+
+use polkadot_primitives::v1::Id as ParaId;
+
+use crate::CollationFetchingConfig;
+
+/// Virtual time advanced by a fetch of a para with weight 1.
+const WEIGHT_SCALE: u64 = 1_000_000;
+
+/// Decides which para gets to fetch a collation next.
+pub struct ParaFetchScheduler {
+	config: CollationFetchingConfig,
+	/// Number of ongoing fetches per para.
+	in_flight: HashMap<ParaId, usize>,
+	/// Virtual time at which the last started fetch of a para finishes.
+	finish_tags: HashMap<ParaId, u64>,
+	/// Start tag of the last started fetch.
+	virtual_time: u64,
+}
+
+impl ParaFetchScheduler {
+	/// Create a new scheduler.
+	pub fn new(config: CollationFetchingConfig) -> Self {
+		Self {
+			config,
+			in_flight: HashMap::new(),
+			finish_tags: HashMap::new(),
+			virtual_time: 0,
+		}
+	}
+
+	/// Whether another fetch for the given para may be started right now.
+	pub fn can_fetch(&self, para_id: ParaId) -> bool {
+		self.in_flight.get(&para_id).copied().unwrap_or(0) < self.config.max_parallel_fetches_per_para
+	}
+
+	/// The virtual time a fetch of the given para would start at. Lower values go first.
+	pub fn start_tag(&self, para_id: ParaId) -> u64 {
+		self.finish_tags.get(&para_id).copied().unwrap_or(0).max(self.virtual_time)
+	}
+
+	/// Out of the given paras, pick the one which should fetch next.
+	///
+	/// Returns `None` if all of them have reached their limit of concurrent fetches.
+	pub fn pick(&self, paras: impl IntoIterator<Item = ParaId>) -> Option<ParaId> {
+		paras.into_iter()
+			.filter(|para_id| self.can_fetch(*para_id))
+			.min_by_key(|para_id| (self.start_tag(*para_id), *para_id))
+	}
+
+	/// Note that a fetch for the given para has been started.
+	pub fn note_fetch_started(&mut self, para_id: ParaId) {
+		let start = self.start_tag(para_id);
+		let weight = self.config.para_weights.get(&para_id).copied().unwrap_or(1).max(1);
+
+		self.virtual_time = start;
+		self.finish_tags.insert(para_id, start.saturating_add(WEIGHT_SCALE / u64::from(weight)));
+		*self.in_flight.entry(para_id).or_default() += 1;
+	}
+
+	/// Note that a fetch for the given para has concluded, successfully or not.
+	pub fn note_fetch_finished(&mut self, para_id: ParaId) {
+		if let Some(count) = self.in_flight.get_mut(&para_id) {
+			*count = count.saturating_sub(1);
+			if *count == 0 {
+				self.in_flight.remove(&para_id);
+			}
+		}
+	}
+
+	/// Forget about paras that we are no longer assigned to.
+	pub fn retain_paras(&mut self, mut keep: impl FnMut(&ParaId) -> bool) {
+		let in_flight = &self.in_flight;
+		self.finish_tags.retain(|para_id, _| keep(para_id) || in_flight.contains_key(para_id));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn scheduler(max_parallel_fetches_per_para: usize, weights: &[(u32, u32)]) -> ParaFetchScheduler {
+		ParaFetchScheduler::new(CollationFetchingConfig {
+			max_parallel_fetches_per_para,
+			para_weights: weights.iter().map(|(p, w)| (ParaId::from(*p), *w)).collect(),
+		})
+	}
+
+	fn schedule(scheduler: &mut ParaFetchScheduler, paras: &[u32], fetches: usize) -> Vec<u32> {
+		(0..fetches).map(|_| {
+			let para_id = scheduler.pick(paras.iter().copied().map(ParaId::from)).unwrap();
+			scheduler.note_fetch_started(para_id);
+			scheduler.note_fetch_finished(para_id);
+			u32::from(para_id)
+		}).collect()
+	}
+
+	#[test]
+	fn round_robin_without_weights() {
+		let mut scheduler = scheduler(1, &[]);
+		assert_eq!(schedule(&mut scheduler, &[1, 2, 3], 6), vec![1, 2, 3, 1, 2, 3]);
+	}
+
+	#[test]
+	fn weights_are_respected() {
+		let mut scheduler = scheduler(1, &[(1, 2)]);
+		let order = schedule(&mut scheduler, &[1, 2], 6);
+		assert_eq!(order.iter().filter(|p| **p == 1).count(), 4);
+		assert_eq!(order.iter().filter(|p| **p == 2).count(), 2);
+	}
+
+	#[test]
+	fn idle_paras_do_not_build_up_credit() {
+		let mut scheduler = scheduler(1, &[]);
+		schedule(&mut scheduler, &[1], 10);
+
+		// Para 2 only gets its fair share from now on, not 10 fetches in a row.
+		assert_eq!(schedule(&mut scheduler, &[1, 2], 4), vec![2, 1, 2, 1]);
+	}
+
+	#[test]
+	fn concurrent_fetches_are_limited() {
+		let mut scheduler = scheduler(2, &[]);
+		let para_id = ParaId::from(1);
+
+		scheduler.note_fetch_started(para_id);
+		assert!(scheduler.can_fetch(para_id));
+		scheduler.note_fetch_started(para_id);
+		assert!(!scheduler.can_fetch(para_id));
+		assert_eq!(scheduler.pick(vec![para_id]), None);
+
+		scheduler.note_fetch_finished(para_id);
+		assert_eq!(scheduler.pick(vec![para_id]), Some(para_id));
+	}
+}
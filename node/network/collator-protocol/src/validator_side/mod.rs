@@ -49,7 +49,13 @@ use polkadot_subsystem::{
 	FromOverseer, OverseerSignal, PerLeafSpan, SubsystemContext, SubsystemSender,
 };
 
-use super::{modify_reputation, Result, LOG_TARGET};
+use super::{modify_reputation, Result, TrustedCollators, LOG_TARGET};
+
+mod fetch_scheduler;
+use fetch_scheduler::ParaFetchScheduler;
+
+mod reputation;
+pub use reputation::ReputationStore;
 
 #[cfg(test)]
 mod tests;
@@ -64,6 +70,8 @@ const COST_INVALID_SIGNATURE: Rep = Rep::Malicious("Invalid network message sign
 const COST_REPORT_BAD: Rep = Rep::Malicious("A collator was reported by another subsystem");
 const COST_WRONG_PARA: Rep = Rep::Malicious("A collator provided a collation for the wrong para");
 const COST_UNNEEDED_COLLATOR: Rep = Rep::CostMinor("An unneeded collator connected");
+const COST_UNTRUSTED_COLLATOR: Rep = Rep::CostMinor("A collator not on the para's allow list connected");
+const COST_BANNED_COLLATOR: Rep = Rep::CostMajor("A collator with a persisted bad reputation connected");
 const BENEFIT_NOTIFY_GOOD: Rep = Rep::BenefitMinor("A collator was noted good by another subsystem");
 
 /// Time after starting a collation download from a collator we will start another one from the
@@ -113,6 +121,16 @@ impl Metrics {
 	fn note_collator_peer_count(&self, collator_peers: usize) {
 		self.0.as_ref().map(|metrics| metrics.collator_peer_count.set(collator_peers as u64));
 	}
+
+	/// Provide a timer for fetching a collation of the given para, from starting the fetch until
+	/// the collation has been received.
+	fn time_collation_fetch(&self, para_id: ParaId) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| {
+			metrics.collation_fetch_latency
+				.with_label_values(&[&para_id.to_string()])
+				.start_timer()
+		})
+	}
 }
 
 #[derive(Clone)]
@@ -121,6 +139,7 @@ struct MetricsInner {
 	process_msg: prometheus::Histogram,
 	handle_collation_request_result: prometheus::Histogram,
 	collator_peer_count: prometheus::Gauge<prometheus::U64>,
+	collation_fetch_latency: prometheus::HistogramVec,
 }
 
 impl metrics::Metrics for Metrics {
@@ -163,6 +182,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			collation_fetch_latency: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"parachain_collation_fetch_latency",
+						"Time from starting to fetch a collation until it has been received, per para",
+					).buckets(vec![0.01, 0.025, 0.05, 0.1, 0.2, 0.4, 0.8, 1.6, 3.2, 6.4]),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -522,6 +551,15 @@ impl CollationStatus {
 	}
 }
 
+/// The rank of an advertised collation when choosing the next one to fetch.
+///
+/// Collations of preferred collators come first, ties are broken by the persisted reputation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct CollatorRank {
+	preferred: bool,
+	score: i32,
+}
+
 /// Information about collations per relay parent.
 #[derive(Default)]
 struct CollationsPerRelayParent {
@@ -532,20 +570,48 @@ struct CollationsPerRelayParent {
 	/// This is the currently last started fetch, which did not exceed `MAX_UNSHARED_DOWNLOAD_TIME`
 	/// yet.
 	waiting_collation: Option<CollatorId>,
-	/// Collation that were advertised to us, but we did not yet fetch.
-	unfetched_collations: Vec<(PendingCollation, CollatorId)>,
+	/// Collations that were advertised to us, but we did not yet fetch, per para.
+	unfetched_collations: HashMap<ParaId, Vec<(PendingCollation, CollatorId)>>,
 }
 
 impl CollationsPerRelayParent {
+	/// Queue an advertised collation for fetching later on.
+	fn add_unfetched(&mut self, pending_collation: PendingCollation, collator_id: CollatorId) {
+		self.unfetched_collations
+			.entry(pending_collation.para_id)
+			.or_default()
+			.push((pending_collation, collator_id));
+	}
+
+	/// Paras with collations waiting to be fetched.
+	fn pending_paras(&self) -> impl Iterator<Item = ParaId> + '_ {
+		self.unfetched_collations.keys().copied()
+	}
+
+	/// Whether there are collations to fetch, but no fetch is running because all paras with
+	/// unfetched collations have reached their limit of concurrent fetches.
+	fn is_stalled(&self) -> bool {
+		matches!(self.status, CollationStatus::Waiting)
+			&& self.waiting_collation.is_none()
+			&& !self.unfetched_collations.is_empty()
+	}
+
 	/// Returns the next collation to fetch from the `unfetched_collations`.
 	///
 	/// This will reset the status back to `Waiting` using [`CollationStatus::back_to_waiting`].
 	///
-	/// Returns `Some(_)` if there is any collation to fetch, the `status` is not `Seconded` and
-	/// the passed in `finished_one` is the currently `waiting_collation`.
+	/// Returns `Some(_)` if there is any collation to fetch, the `status` is not `Seconded`, the
+	/// passed in `finished_one` is the currently `waiting_collation` and the `scheduler` lets one
+	/// of the paras fetch.
+	///
+	/// The para is picked by the `scheduler`. Out of its unfetched collations, the one with the
+	/// highest `rank` is returned. Among equally ranked collations, the most recently advertised
+	/// one is preferred.
 	pub fn get_next_collation_to_fetch(
 		&mut self,
 		finished_one: Option<CollatorId>,
+		scheduler: &ParaFetchScheduler,
+		rank: impl Fn(&PendingCollation, &CollatorId) -> CollatorRank,
 	) -> Option<(PendingCollation, CollatorId)> {
 		// If finished one does not match waiting_collation, then we already dequeued another fetch
 		// to replace it.
@@ -564,7 +630,20 @@ impl CollationsPerRelayParent {
 			// We don't need to fetch any other collation when we already have seconded one.
 			CollationStatus::Seconded => None,
 			CollationStatus::Waiting => {
-				let next = self.unfetched_collations.pop();
+				let next = scheduler.pick(self.pending_paras()).and_then(|para_id| {
+					let queue = self.unfetched_collations.get_mut(&para_id)?;
+					// `max_by_key` returns the last of several maximal elements.
+					let index = queue
+						.iter()
+						.enumerate()
+						.max_by_key(|(_, (pc, id))| rank(pc, id))
+						.map(|(index, _)| index)?;
+					let next = queue.remove(index);
+					if queue.is_empty() {
+						self.unfetched_collations.remove(&para_id);
+					}
+					Some(next)
+				});
 				self.waiting_collation = next.as_ref().map(|(_, collator_id)| collator_id.clone());
 				next
 			}
@@ -575,7 +654,6 @@ impl CollationsPerRelayParent {
 }
 
 /// All state relevant for the validator side of the protocol lives here.
-#[derive(Default)]
 struct State {
 	/// Our own view.
 	view: OurView,
@@ -614,6 +692,15 @@ struct State {
 
 	/// Keep track of all pending candidate collations
 	pending_candidates: HashMap<Hash, CollationEvent>,
+
+	/// Per-para collators to accept exclusively or to fetch from first.
+	trusted_collators: TrustedCollators,
+
+	/// Collator reputations, persisted across restarts.
+	reputations: ReputationStore,
+
+	/// Shares collation fetches between paras.
+	fetch_scheduler: ParaFetchScheduler,
 }
 
 // O(n) search for collator ID by iterating through the peers map. This should be fast enough
@@ -663,18 +750,35 @@ where
 		request_collation(ctx, state, relay_parent, para_id, peer_id, tx).await;
 	}
 
-	state.collation_fetches.push(rx.map(|r| ((id, pc), r)).boxed());
+	state.fetch_scheduler.note_fetch_started(para_id);
+
+	let timer = state.metrics.time_collation_fetch(para_id);
+	state.collation_fetches.push(rx.map(move |r| {
+		// Only successful fetches are of interest for the latency.
+		if let Some(timer) = timer {
+			if r.is_ok() {
+				timer.observe_duration();
+			} else {
+				let _ = timer.stop_and_discard();
+			}
+		}
+
+		((id, pc), r)
+	}).boxed());
 }
 
 /// Report a collator for some malicious actions.
 async fn report_collator<Context>(
 	ctx: &mut Context,
 	peer_data: &HashMap<PeerId, PeerData>,
+	reputations: &mut ReputationStore,
 	id: CollatorId,
 )
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>
 {
+	reputations.note_bad(&id);
+
 	if let Some(peer_id) = collator_peer_id(peer_data, &id) {
 		modify_reputation(ctx, peer_id, COST_REPORT_BAD).await;
 	}
@@ -684,12 +788,15 @@ where
 async fn note_good_collation<Context>(
 	ctx: &mut Context,
 	peer_data: &HashMap<PeerId, PeerData>,
+	reputations: &mut ReputationStore,
 	id: CollatorId,
 )
 where
 	Context: overseer::SubsystemContext<Message=CollatorProtocolMessage>,
 	Context: SubsystemContext<Message=CollatorProtocolMessage>,
 {
+	reputations.note_good(&id);
+
 	if let Some(peer_id) = collator_peer_id(peer_data, &id) {
 		modify_reputation(ctx, peer_id, BENEFIT_NOTIFY_GOOD).await;
 	}
@@ -846,6 +953,34 @@ where
 				return
 			}
 
+			if !state.trusted_collators.is_allowed(para_id, &collator_id) {
+				tracing::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?collator_id,
+					?para_id,
+					"Declared collator is not on the para's allow list",
+				);
+
+				modify_reputation(ctx, origin.clone(), COST_UNTRUSTED_COLLATOR).await;
+				disconnect_peer(ctx, origin).await;
+				return
+			}
+
+			if state.reputations.is_banned(&collator_id) {
+				tracing::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?collator_id,
+					?para_id,
+					"Declared collator is banned",
+				);
+
+				modify_reputation(ctx, origin.clone(), COST_BANNED_COLLATOR).await;
+				disconnect_peer(ctx, origin).await;
+				return
+			}
+
 			if state.active_paras.is_current_or_next(para_id) {
 				tracing::debug!(
 					target: LOG_TARGET,
@@ -912,7 +1047,17 @@ where
 
 					match collations.status {
 						CollationStatus::Fetching | CollationStatus::WaitingOnValidation =>
-							collations.unfetched_collations.push((pending_collation, id)),
+							collations.add_unfetched(pending_collation, id),
+						CollationStatus::Waiting if !state.fetch_scheduler.can_fetch(para_id) => {
+							tracing::debug!(
+								target: LOG_TARGET,
+								%para_id,
+								?relay_parent,
+								"Too many ongoing fetches for para, deferring fetch",
+							);
+
+							collations.add_unfetched(pending_collation, id);
+						}
 						CollationStatus::Waiting => {
 							collations.status = CollationStatus::Fetching;
 							collations.waiting_collation = Some(id.clone());
@@ -1002,6 +1147,9 @@ where
 	state.active_paras.assign_incoming(ctx.sender(), keystore, added).await;
 	state.active_paras.remove_outgoing(removed);
 
+	let active_paras = &state.active_paras;
+	state.fetch_scheduler.retain_paras(|para_id| active_paras.is_current_or_next(*para_id));
+
 	for (peer_id, peer_data) in state.peer_data.iter_mut() {
 		peer_data.prune_old_advertisements(&state.view);
 
@@ -1034,7 +1182,7 @@ where
 	use NetworkBridgeEvent::*;
 
 	match bridge_message {
-		PeerConnected(peer_id, _role, _) => {
+		PeerConnected(peer_id, _role, _version, _) => {
 			state.peer_data.entry(peer_id).or_default();
 			state.metrics.note_collator_peer_count(state.peer_data.len());
 		},
@@ -1089,7 +1237,7 @@ where
 			);
 		}
 		ReportCollator(id) => {
-			report_collator(ctx, &state.peer_data, id).await;
+			report_collator(ctx, &state.peer_data, &mut state.reputations, id).await;
 		}
 		NetworkBridgeUpdateV1(event) => {
 			if let Err(e) = handle_network_msg(
@@ -1115,7 +1263,7 @@ where
 			if let Some(collation_event) = state.pending_candidates.remove(&parent) {
 				let (collator_id, pending_collation) = collation_event;
 				let PendingCollation { relay_parent, peer_id, .. } = pending_collation;
-				note_good_collation(ctx, &state.peer_data, collator_id).await;
+				note_good_collation(ctx, &state.peer_data, &mut state.reputations, collator_id).await;
 				notify_collation_seconded(ctx, peer_id, relay_parent, stmt).await;
 
 				if let Some(collations) = state.collations_per_relay_parent.get_mut(&parent) {
@@ -1145,7 +1293,7 @@ where
 				Entry::Vacant(_) => return,
 			};
 
-			report_collator(ctx, &state.peer_data, id.clone()).await;
+			report_collator(ctx, &state.peer_data, &mut state.reputations, id.clone()).await;
 
 			dequeue_next_collation_and_fetch(ctx, state, parent, id).await;
 		}
@@ -1169,6 +1317,9 @@ pub(crate) async fn run<Context>(
 	mut ctx: Context,
 	keystore: SyncCryptoStorePtr,
 	eviction_policy: crate::CollatorEvictionPolicy,
+	trusted_collators: TrustedCollators,
+	fetching_config: crate::CollationFetchingConfig,
+	reputations: ReputationStore,
 	metrics: Metrics,
 ) -> Result<()>
 where
@@ -1178,8 +1329,19 @@ where
 	use OverseerSignal::*;
 
 	let mut state = State {
+		view: Default::default(),
+		active_paras: Default::default(),
+		peer_data: Default::default(),
+		requested_collations: Default::default(),
 		metrics,
-		..Default::default()
+		span_per_relay_parent: Default::default(),
+		collation_fetches: Default::default(),
+		collation_fetch_timeouts: Default::default(),
+		collations_per_relay_parent: Default::default(),
+		pending_candidates: Default::default(),
+		trusted_collators,
+		reputations,
+		fetch_scheduler: ParaFetchScheduler::new(fetching_config),
 	};
 
 	let next_inactivity_stream = futures::stream::unfold(
@@ -1210,7 +1372,9 @@ where
 				disconnect_inactive_peers(&mut ctx, &eviction_policy, &state.peer_data).await;
 			}
 			res = state.collation_fetches.select_next_some() => {
+				state.fetch_scheduler.note_fetch_finished((res.0).1.para_id);
 				handle_collation_fetched_result(&mut ctx, &mut state, res).await;
+				fetch_stalled_collations(&mut ctx, &mut state).await;
 			}
 			res = state.collation_fetch_timeouts.select_next_some() => {
 				let (collator_id, relay_parent) = res;
@@ -1246,14 +1410,62 @@ async fn dequeue_next_collation_and_fetch(
 	// The collator we tried to fetch from last.
 	previous_fetch: CollatorId,
 ) {
-	if let Some((next, id)) = state.collations_per_relay_parent
-		.get_mut(&relay_parent)
-		.and_then(|c| c.get_next_collation_to_fetch(Some(previous_fetch)))
-	{
+	if let Some((next, id)) = next_collation_to_fetch(state, relay_parent, Some(previous_fetch)) {
 		fetch_collation(ctx, state, next, id).await;
 	}
 }
 
+/// Resume fetching for relay parents which had to wait for other fetches of their paras to
+/// conclude.
+///
+/// Relay parents whose paras are most entitled to the next fetch according to the
+/// [`ParaFetchScheduler`] go first.
+async fn fetch_stalled_collations(
+	ctx: &mut (impl SubsystemContext<Message = CollatorProtocolMessage> + overseer::SubsystemContext<Message = CollatorProtocolMessage>),
+	state: &mut State,
+) {
+	let scheduler = &state.fetch_scheduler;
+	let mut stalled: Vec<_> = state.collations_per_relay_parent
+		.iter()
+		.filter(|(_, collations)| collations.is_stalled())
+		.filter_map(|(relay_parent, collations)| {
+			collations.pending_paras()
+				.filter(|para_id| scheduler.can_fetch(*para_id))
+				.map(|para_id| scheduler.start_tag(para_id))
+				.min()
+				.map(|start_tag| (start_tag, *relay_parent))
+		})
+		.collect();
+	stalled.sort();
+
+	for (_, relay_parent) in stalled {
+		if let Some((next, id)) = next_collation_to_fetch(state, relay_parent, None) {
+			fetch_collation(ctx, state, next, id).await;
+		}
+	}
+}
+
+/// Take the next collation to fetch for the given relay parent, see
+/// [`CollationsPerRelayParent::get_next_collation_to_fetch`].
+fn next_collation_to_fetch(
+	state: &mut State,
+	relay_parent: Hash,
+	finished_one: Option<CollatorId>,
+) -> Option<(PendingCollation, CollatorId)> {
+	let trusted_collators = &state.trusted_collators;
+	let reputations = &state.reputations;
+	let fetch_scheduler = &state.fetch_scheduler;
+
+	state.collations_per_relay_parent.get_mut(&relay_parent)?.get_next_collation_to_fetch(
+		finished_one,
+		fetch_scheduler,
+		|pc, id| CollatorRank {
+			preferred: trusted_collators.is_preferred(pc.para_id, id),
+			score: reputations.score(id),
+		},
+	)
+}
+
 /// Handle a fetched collation result.
 async fn handle_collation_fetched_result<Context>(
 	ctx: &mut Context,
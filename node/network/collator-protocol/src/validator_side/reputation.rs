@@ -0,0 +1,195 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collator reputations which persist across restarts.
+//!
+//! Peer reputations in the network bridge are bound to a `PeerId` and are lost on restart. A
+//! collator which got reported for bad collations could therefore simply come back clean. This
+//! store keeps a score per `CollatorId` in the parachains database instead. Negative scores
+//! slowly recover over time, so bans are not permanent.
+//!
+//! Scores are looked up for every advertised collation when choosing the next one to fetch, so
+//! all entries are kept in memory and changes are written through to the database.
+
+use std::{collections::HashMap, sync::Arc, time::{SystemTime, UNIX_EPOCH}};
+
+use kvdb::{DBTransaction, KeyValueDB};
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_primitives::v1::CollatorId;
+
+use crate::{ReputationConfig, LOG_TARGET};
+
+/// Score lost by a collator when being reported by another subsystem.
+const REPORT_BAD_PENALTY: i32 = 100;
+/// Score gained by a collator for a good collation.
+const NOTE_GOOD_BENEFIT: i32 = 1;
+/// Upper bound of the score, so past good behavior can't outweigh arbitrarily many bad reports.
+const MAX_SCORE: i32 = 100;
+/// Collators with a score below this are not accepted.
+const BAN_THRESHOLD: i32 = -150;
+/// Score recovered by a collator with a negative score per hour.
+const RECOVERY_PER_HOUR: i32 = 1;
+
+const SECS_PER_HOUR: u64 = 60 * 60;
+
+/// A persisted reputation entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Encode, Decode)]
+struct CollatorReputation {
+	/// The score at `last_updated`.
+	score: i32,
+	/// Seconds since the unix epoch of the last update.
+	last_updated: u64,
+}
+
+impl CollatorReputation {
+	/// The score at `now`, taking recovery of negative scores into account.
+	fn score_at(&self, now: u64) -> i32 {
+		if self.score >= 0 {
+			return self.score
+		}
+
+		let hours = now.saturating_sub(self.last_updated) / SECS_PER_HOUR;
+		let recovered = (hours.min(i32::MAX as u64) as i32).saturating_mul(RECOVERY_PER_HOUR);
+		self.score.saturating_add(recovered).min(0)
+	}
+}
+
+/// Persisted reputations of collators.
+pub struct ReputationStore {
+	db: Arc<dyn KeyValueDB>,
+	config: ReputationConfig,
+	reputations: HashMap<CollatorId, CollatorReputation>,
+}
+
+impl ReputationStore {
+	/// Create a store on top of the given database, loading all persisted reputations.
+	pub fn new(db: Arc<dyn KeyValueDB>, config: ReputationConfig) -> Self {
+		let reputations = db.iter(config.col_data)
+			.filter_map(|(key, value)| {
+				let entry = CollatorId::decode(&mut &key[..]).and_then(|collator_id| {
+					CollatorReputation::decode(&mut &value[..]).map(|reputation| (collator_id, reputation))
+				});
+				match entry {
+					Ok(entry) => Some(entry),
+					Err(error) => {
+						tracing::warn!(
+							target: LOG_TARGET,
+							?error,
+							"Corrupted collator reputation entry, ignoring",
+						);
+						None
+					}
+				}
+			})
+			.collect();
+
+		Self { db, config, reputations }
+	}
+
+	/// The current score of a collator. Unknown collators have a score of zero.
+	pub fn score(&self, collator_id: &CollatorId) -> i32 {
+		self.score_at(collator_id, unix_now())
+	}
+
+	/// Whether a collator is currently banned.
+	pub fn is_banned(&self, collator_id: &CollatorId) -> bool {
+		self.score(collator_id) < BAN_THRESHOLD
+	}
+
+	/// A collator was reported for a bad collation.
+	pub fn note_bad(&mut self, collator_id: &CollatorId) {
+		self.modify(collator_id, -REPORT_BAD_PENALTY, unix_now())
+	}
+
+	/// A collator provided a good collation.
+	pub fn note_good(&mut self, collator_id: &CollatorId) {
+		self.modify(collator_id, NOTE_GOOD_BENEFIT, unix_now())
+	}
+
+	fn score_at(&self, collator_id: &CollatorId, now: u64) -> i32 {
+		self.reputations.get(collator_id).map_or(0, |reputation| reputation.score_at(now))
+	}
+
+	fn modify(&mut self, collator_id: &CollatorId, change: i32, now: u64) {
+		let score = self.score_at(collator_id, now).saturating_add(change).min(MAX_SCORE);
+		let reputation = CollatorReputation { score, last_updated: now };
+
+		self.reputations.insert(collator_id.clone(), reputation);
+
+		let mut tx = DBTransaction::new();
+		tx.put_vec(self.config.col_data, &collator_id.encode(), reputation.encode());
+		if let Err(error) = self.db.write(tx) {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?collator_id,
+				?error,
+				"Failed to persist collator reputation",
+			);
+		}
+	}
+}
+
+fn unix_now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::Sr25519Keyring;
+
+	fn store() -> ReputationStore {
+		ReputationStore::new(Arc::new(kvdb_memorydb::create(1)), ReputationConfig { col_data: 0 })
+	}
+
+	#[test]
+	fn reports_ban_and_recover() {
+		let mut store = store();
+		let alice = CollatorId::from(Sr25519Keyring::Alice.public());
+
+		store.modify(&alice, -REPORT_BAD_PENALTY, 0);
+		assert_eq!(store.score_at(&alice, 0), -100);
+		store.modify(&alice, -REPORT_BAD_PENALTY, 0);
+		assert_eq!(store.score_at(&alice, 0), -200);
+		assert!(store.score_at(&alice, 0) < BAN_THRESHOLD);
+
+		// Negative scores recover, but never beyond zero.
+		assert_eq!(store.score_at(&alice, 10 * SECS_PER_HOUR), -190);
+		assert!(store.score_at(&alice, 51 * SECS_PER_HOUR) >= BAN_THRESHOLD);
+		assert_eq!(store.score_at(&alice, 1000 * SECS_PER_HOUR), 0);
+	}
+
+	#[test]
+	fn scores_are_capped_and_persisted() {
+		let db: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+		let config = ReputationConfig { col_data: 0 };
+		let alice = CollatorId::from(Sr25519Keyring::Alice.public());
+
+		{
+			let mut store = ReputationStore::new(db.clone(), config);
+			for _ in 0..(MAX_SCORE + 10) {
+				store.modify(&alice, NOTE_GOOD_BENEFIT, 0);
+			}
+			assert_eq!(store.score_at(&alice, 0), MAX_SCORE);
+		}
+
+		// A store created over the same database, e.g. after a restart, sees the same score.
+		let store = ReputationStore::new(db, config);
+		assert_eq!(store.score_at(&alice, 0), MAX_SCORE);
+		assert_eq!(store.score_at(&CollatorId::from(Sr25519Keyring::Bob.public()), 0), 0);
+	}
+}
@@ -33,7 +33,7 @@ use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_subsystem_testhelpers as test_helpers;
 use polkadot_subsystem::messages::{AllMessages, RuntimeApiMessage, RuntimeApiRequest};
 use polkadot_node_network_protocol::{
-	our_view, ObservedRole, request_response::{Requests, ResponseSender},
+	our_view, ObservedRole, peer_set::CollationVersion, request_response::{Requests, ResponseSender},
 };
 
 const ACTIVITY_TIMEOUT: Duration = Duration::from_millis(500);
@@ -127,6 +127,15 @@ struct TestHarness {
 }
 
 fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarness) -> T) {
+	test_harness_with(Default::default(), Default::default(), Arc::new(kvdb_memorydb::create(1)), test)
+}
+
+fn test_harness_with<T: Future<Output = VirtualOverseer>>(
+	trusted_collators: TrustedCollators,
+	fetching_config: crate::CollationFetchingConfig,
+	db: Arc<dyn kvdb::KeyValueDB>,
+	test: impl FnOnce(TestHarness) -> T,
+) {
 	let _ = env_logger::builder()
 		.is_test(true)
 		.filter(
@@ -156,6 +165,9 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarne
 			inactive_collator: ACTIVITY_TIMEOUT,
 			undeclared: DECLARE_TIMEOUT,
 		},
+		trusted_collators,
+		fetching_config,
+		ReputationStore::new(db, crate::ReputationConfig { col_data: 0 }),
 		Metrics::default(),
 	);
 
@@ -315,6 +327,27 @@ async fn assert_fetch_collation_request(
 	})
 }
 
+/// Assert that a fetch collation request was sent to the given peer.
+async fn assert_fetch_collation_request_from(
+	virtual_overseer: &mut VirtualOverseer,
+	expected_peer: PeerId,
+) -> ResponseSender {
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::NetworkBridge(NetworkBridgeMessage::SendRequests(reqs, IfDisconnected::ImmediateError)
+	) => {
+		let req = reqs.into_iter().next()
+			.expect("There should be exactly one request");
+		match req {
+			Requests::CollationFetching(req) => {
+				assert_eq!(req.peer, Recipient::Peer(expected_peer));
+				req.pending_response
+			}
+			_ => panic!("Unexpected request"),
+		}
+	})
+}
+
 /// Connect and declare a collator
 async fn connect_and_declare_collator(
 	virtual_overseer: &mut VirtualOverseer,
@@ -328,6 +361,7 @@ async fn connect_and_declare_collator(
 			NetworkBridgeEvent::PeerConnected(
 				peer.clone(),
 				ObservedRole::Full,
+				CollationVersion::V1.into(),
 				None,
 			),
 		)
@@ -474,6 +508,7 @@ fn collator_authentication_verification_works() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_b,
 					ObservedRole::Full,
+					CollationVersion::V1.into(),
 					None,
 				),
 			)
@@ -943,6 +978,7 @@ fn disconnect_if_no_declare() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_b.clone(),
 					ObservedRole::Full,
+					CollationVersion::V1.into(),
 					None,
 				)
 			)
@@ -982,6 +1018,7 @@ fn disconnect_if_wrong_declare() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_b.clone(),
 					ObservedRole::Full,
+					CollationVersion::V1.into(),
 					None,
 				)
 			)
@@ -1059,3 +1096,268 @@ fn view_change_clears_old_collators() {
 		virtual_overseer
 	})
 }
+
+fn trusted_collators(trust: &str, para_id: ParaId, collators: &[&CollatorPair]) -> TrustedCollators {
+	use sp_core::crypto::Ss58Codec;
+
+	let mut trusted = TrustedCollators::default();
+	for collator in collators {
+		let entry = format!("{}:{}:{}", u32::from(para_id), trust, collator.public().to_ss58check());
+		trusted.insert(entry.parse().unwrap()).unwrap();
+	}
+	trusted
+}
+
+// Collators not on the allow list of a para get disconnected on declare.
+#[test]
+fn disconnect_collator_not_on_allow_list() {
+	let test_state = TestState::default();
+	let trusted = trusted_collators("allow", test_state.chain_ids[0], &[&test_state.collators[0]]);
+
+	test_harness_with(trusted, Default::default(), Arc::new(kvdb_memorydb::create(1)), |test_harness| async move {
+		let TestHarness {
+			mut virtual_overseer,
+		} = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(
+				NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent])
+			)
+		).await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0].clone(),
+		).await;
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.collators[1].clone(),
+			test_state.chain_ids[0].clone(),
+		).await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_c);
+				assert_eq!(rep, COST_UNTRUSTED_COLLATOR);
+			}
+		);
+
+		assert_collator_disconnect(&mut virtual_overseer, peer_c.clone()).await;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerDisconnected(peer_c)),
+		).await;
+
+		// The allowed collator is still fine.
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+		assert_fetch_collation_request(&mut virtual_overseer, test_state.relay_parent, test_state.chain_ids[0]).await;
+
+		virtual_overseer
+	});
+}
+
+// Once the first fetch takes too long, collations of preferred collators are fetched next, even
+// if they were not advertised last.
+#[test]
+fn fetch_from_preferred_collator_first() {
+	let test_state = TestState::default();
+	let trusted = trusted_collators("prefer", test_state.chain_ids[0], &[&test_state.collators[1]]);
+
+	test_harness_with(trusted, Default::default(), Arc::new(kvdb_memorydb::create(1)), |test_harness| async move {
+		let TestHarness {
+			mut virtual_overseer,
+		} = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(
+				NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent])
+			)
+		).await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peers: Vec<_> = (0..3).map(|_| PeerId::random()).collect();
+
+		for (peer, collator) in peers.iter().zip(test_state.collators.iter()) {
+			connect_and_declare_collator(
+				&mut virtual_overseer,
+				peer.clone(),
+				collator.clone(),
+				test_state.chain_ids[0].clone(),
+			).await;
+		}
+
+		for peer in peers.iter() {
+			advertise_collation(&mut virtual_overseer, peer.clone(), test_state.relay_parent).await;
+		}
+
+		// The first advertisement is fetched right away.
+		let _response_channel = assert_fetch_collation_request_from(
+			&mut virtual_overseer,
+			peers[0].clone(),
+		).await;
+
+		// The preferred collator is next, although the third one advertised last.
+		Delay::new(MAX_UNSHARED_DOWNLOAD_TIME + Duration::from_millis(50)).await;
+		let _response_channel = assert_fetch_collation_request_from(
+			&mut virtual_overseer,
+			peers[1].clone(),
+		).await;
+
+		virtual_overseer
+	});
+}
+
+// Reports against a collator persist, so a banned collator is rejected even after a restart.
+#[test]
+fn banned_collator_rejected_after_restart() {
+	let test_state = TestState::default();
+	let db: Arc<dyn kvdb::KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+
+	{
+		let test_state = test_state.clone();
+		test_harness_with(Default::default(), Default::default(), db.clone(), |test_harness| async move {
+			let TestHarness {
+				mut virtual_overseer,
+			} = test_harness;
+
+			for _ in 0..2 {
+				overseer_send(
+					&mut virtual_overseer,
+					CollatorProtocolMessage::ReportCollator(test_state.collators[0].public()),
+				).await;
+			}
+
+			virtual_overseer
+		});
+	}
+
+	test_harness_with(Default::default(), Default::default(), db, |test_harness| async move {
+		let TestHarness {
+			mut virtual_overseer,
+		} = test_harness;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0].clone(),
+		).await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_BANNED_COLLATOR);
+			}
+		);
+
+		assert_collator_disconnect(&mut virtual_overseer, peer_b).await;
+
+		virtual_overseer
+	});
+}
+
+// Once a para has reached its limit of concurrent fetches, further fetches for it are deferred
+// until one of the ongoing fetches concludes.
+#[test]
+fn concurrent_fetches_per_para_are_limited() {
+	let test_state = TestState::default();
+	let fetching_config = crate::CollationFetchingConfig {
+		max_parallel_fetches_per_para: 1,
+		..Default::default()
+	};
+
+	test_harness_with(
+		Default::default(),
+		fetching_config,
+		Arc::new(kvdb_memorydb::create(1)),
+		|test_harness| async move {
+			let TestHarness {
+				mut virtual_overseer,
+			} = test_harness;
+
+			let second = Hash::random();
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::OurViewChange(our_view![test_state.relay_parent, second])
+				),
+			).await;
+
+			respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+			respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+			let peer_b = PeerId::random();
+
+			connect_and_declare_collator(
+				&mut virtual_overseer,
+				peer_b.clone(),
+				test_state.collators[0].clone(),
+				test_state.chain_ids[0].clone(),
+			).await;
+
+			advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+			advertise_collation(&mut virtual_overseer, peer_b.clone(), second).await;
+
+			let response_channel = assert_fetch_collation_request(
+				&mut virtual_overseer,
+				test_state.relay_parent,
+				test_state.chain_ids[0],
+			).await;
+
+			assert!(
+				overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(30)).await.is_none(),
+				"The second fetch must wait for the first one to conclude.",
+			);
+
+			let pov = PoV { block_data: BlockData(vec![]) };
+			let mut candidate_a = CandidateReceipt::default();
+			candidate_a.descriptor.para_id = test_state.chain_ids[0];
+			candidate_a.descriptor.relay_parent = test_state.relay_parent;
+			response_channel.send(Ok(
+				CollationFetchingResponse::Collation(
+					candidate_a.clone(),
+					pov.clone(),
+				).encode()
+			)).expect("Sending response should succeed");
+
+			assert_candidate_backing_second(
+				&mut virtual_overseer,
+				test_state.relay_parent,
+				test_state.chain_ids[0],
+				&pov,
+			).await;
+
+			assert_fetch_collation_request(
+				&mut virtual_overseer,
+				second,
+				test_state.chain_ids[0],
+			).await;
+
+			virtual_overseer
+		},
+	);
+}
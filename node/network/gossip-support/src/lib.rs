@@ -67,6 +67,7 @@ const BACKOFF_DURATION: Duration = Duration::from_secs(5);
 const LOW_CONNECTIVITY_WARN_DELAY: Duration = Duration::from_secs(600);
 
 /// The Gossip Support subsystem.
+#[derive(Clone)]
 pub struct GossipSupport {
 	keystore: SyncCryptoStorePtr,
 }
@@ -99,6 +100,7 @@ impl GossipSupport {
 	where
 		Context: SubsystemContext<Message = GossipSupportMessage>,
 		Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+		Context: overseer::SendsTo<NetworkBridgeMessage> + overseer::SendsTo<RuntimeApiMessage>,
 	{
 		let mut state = State::default();
 		self.run_inner(ctx, &mut state).await;
@@ -108,6 +110,7 @@ impl GossipSupport {
 	where
 		Context: SubsystemContext<Message = GossipSupportMessage>,
 		Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+		Context: overseer::SendsTo<NetworkBridgeMessage> + overseer::SendsTo<RuntimeApiMessage>,
 	{
 		let Self { keystore } = self;
 		loop {
@@ -151,8 +154,13 @@ async fn determine_relevant_authorities<Context>(
 where
 	Context: SubsystemContext<Message = GossipSupportMessage>,
 	Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+	Context: overseer::SendsTo<NetworkBridgeMessage> + overseer::SendsTo<RuntimeApiMessage>,
 {
-	let authorities = util::request_authorities(relay_parent, ctx.sender()).await.await??;
+	let authorities = util::request_from_runtime_declared(
+		relay_parent,
+		ctx,
+		RuntimeApiRequest::Authorities,
+	).await.await??;
 	tracing::debug!(
 		target: LOG_TARGET,
 		authority_count = ?authorities.len(),
@@ -187,9 +195,10 @@ async fn connect_to_authorities<Context>(
 where
 	Context: SubsystemContext<Message = GossipSupportMessage>,
 	Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+	Context: overseer::SendsTo<NetworkBridgeMessage> + overseer::SendsTo<RuntimeApiMessage>,
 {
 	let (failed, failed_rx) = oneshot::channel();
-	ctx.send_message(
+	ctx.send_declared(
 		NetworkBridgeMessage::ConnectToValidators {
 			validator_ids,
 			peer_set,
@@ -216,12 +225,13 @@ async fn update_gossip_topology<Context>(
 where
 	Context: SubsystemContext<Message = GossipSupportMessage>,
 	Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+	Context: overseer::SendsTo<NetworkBridgeMessage> + overseer::SendsTo<RuntimeApiMessage>,
 {
 	// retrieve BABE randomness
 	let random_seed = {
 		let (tx, rx) = oneshot::channel();
 
-		ctx.send_message(RuntimeApiMessage::Request(
+		ctx.send_declared(RuntimeApiMessage::Request(
 			relay_parent,
 			RuntimeApiRequest::CurrentBabeEpoch(tx),
 		)).await;
@@ -245,7 +255,7 @@ where
 	let neighbors = matrix_neighbors(our_shuffled_position, len);
 	let our_neighbors = neighbors.map(|i| authorities[indices[i]].clone()).collect();
 
-	ctx.send_message(
+	ctx.send_declared(
 		NetworkBridgeMessage::NewGossipTopology {
 			our_neighbors,
 		}
@@ -289,9 +299,14 @@ impl State {
 	where
 		Context: SubsystemContext<Message = GossipSupportMessage>,
 		Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+		Context: overseer::SendsTo<NetworkBridgeMessage> + overseer::SendsTo<RuntimeApiMessage>,
 	{
 		for leaf in leaves {
-			let current_index = util::request_session_index_for_child(leaf, ctx.sender()).await.await??;
+			let current_index = util::request_from_runtime_declared(
+				leaf,
+				ctx,
+				RuntimeApiRequest::SessionIndexForChild,
+			).await.await??;
 			let since_failure = self.last_failure.map(|i| i.elapsed()).unwrap_or_default();
 			let force_request = since_failure >= BACKOFF_DURATION;
 			let leaf_session = Some((current_index, leaf));
@@ -340,6 +355,7 @@ impl State {
 	where
 		Context: SubsystemContext<Message = GossipSupportMessage>,
 		Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+		Context: overseer::SendsTo<NetworkBridgeMessage> + overseer::SendsTo<RuntimeApiMessage>,
 	{
 		let num = authorities.len();
 		tracing::debug!(target: LOG_TARGET, %num, "Issuing a connection request");
@@ -392,6 +408,7 @@ impl<Context> overseer::Subsystem<Context, SubsystemError> for GossipSupport
 where
 	Context: SubsystemContext<Message = GossipSupportMessage>,
 	Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+	Context: overseer::SendsTo<NetworkBridgeMessage> + overseer::SendsTo<RuntimeApiMessage>,
 {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
 		let future = self.run(ctx)
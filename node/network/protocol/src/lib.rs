@@ -21,7 +21,7 @@
 
 use polkadot_primitives::v1::{Hash, BlockNumber};
 use parity_scale_codec::{Encode, Decode};
-use std::{fmt, collections::HashMap};
+use std::{convert::TryFrom, fmt, collections::HashMap};
 
 pub use sc_network::{PeerId, IfDisconnected};
 #[doc(hidden)]
@@ -32,6 +32,9 @@ pub use std::sync::Arc;
 mod reputation;
 pub use self::reputation::{ReputationChange, UnifiedReputationChange};
 
+/// Audit log of reputation changes.
+pub mod reputation_audit;
+
 /// Peer-sets and protocols used for parachains.
 pub mod peer_set;
 
@@ -105,14 +108,61 @@ macro_rules! impl_try_from {
 				}
 			}
 		}
+	}
+}
+
+/// A protocol message, tagged with the protocol version it is sent or received with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Versioned<V1, V2> {
+	/// A message of the first version.
+	V1(V1),
+	/// A message of the second version.
+	V2(V2),
+}
 
-		impl<'a> TryFrom<&'a $m_ty> for &'a $out {
+impl<V1, V2> Versioned<V1, V2> {
+	/// The protocol version of the message.
+	pub fn version(&self) -> ProtocolVersion {
+		match self {
+			Versioned::V1(_) => 1,
+			Versioned::V2(_) => 2,
+		}
+	}
+}
+
+impl<T> Versioned<T, T> {
+	/// Wrap a message, which is the same in both versions, into the given version.
+	///
+	/// Versions other than 2 are treated as version 1.
+	pub fn with_version(version: ProtocolVersion, message: T) -> Self {
+		match version {
+			2 => Versioned::V2(message),
+			_ => Versioned::V1(message),
+		}
+	}
+
+	/// Get the message independent of the version it was sent with.
+	///
+	/// Only possible for messages which are the same in both versions.
+	pub fn into_inner(self) -> T {
+		match self {
+			Versioned::V1(message) | Versioned::V2(message) => message,
+		}
+	}
+}
+
+/// Implement `TryFrom` for one versioned enum variant into the inner type.
+/// `Versioned<$m_ty::$variant(inner), ..> -> Ok(Versioned<inner, ..>)`
+macro_rules! impl_versioned_try_from {
+	($from:ty, $out:ty, $v1_pat:pat => $v1_out:expr, $v2_pat:pat => $v2_out:expr) => {
+		impl TryFrom<$from> for $out {
 			type Error = crate::WrongVariant;
 
-			fn try_from(x: &'a $m_ty) -> Result<&'a $out, Self::Error> {
+			fn try_from(x: $from) -> Result<$out, Self::Error> {
 				#[allow(unreachable_patterns)] // when there is only one variant
-				match *x {
-					$m_ty::$variant(ref y) => Ok(y),
+				match x {
+					Versioned::V1($v1_pat) => Ok(Versioned::V1($v1_out)),
+					Versioned::V2($v2_pat) => Ok(Versioned::V2($v2_out)),
 					_ => Err(crate::WrongVariant),
 				}
 			}
@@ -120,6 +170,66 @@ macro_rules! impl_try_from {
 	}
 }
 
+/// All network messages on the validation peer-set, of any supported version.
+pub type VersionedValidationProtocol = Versioned<v1::ValidationProtocol, v2::ValidationProtocol>;
+
+/// Bitfield distribution messages of any supported version.
+pub type BitfieldDistributionMessage =
+	Versioned<v1::BitfieldDistributionMessage, v2::BitfieldDistributionMessage>;
+impl_versioned_try_from!(
+	VersionedValidationProtocol,
+	BitfieldDistributionMessage,
+	v1::ValidationProtocol::BitfieldDistribution(x) => x,
+	v2::ValidationProtocol::BitfieldDistribution(x) => x
+);
+
+/// Statement distribution messages of any supported version.
+pub type StatementDistributionMessage =
+	Versioned<v1::StatementDistributionMessage, v2::StatementDistributionMessage>;
+impl_versioned_try_from!(
+	VersionedValidationProtocol,
+	StatementDistributionMessage,
+	v1::ValidationProtocol::StatementDistribution(x) => x,
+	v2::ValidationProtocol::StatementDistribution(x) => x
+);
+
+/// Approval distribution messages of any supported version.
+pub type ApprovalDistributionMessage =
+	Versioned<v1::ApprovalDistributionMessage, v2::ApprovalDistributionMessage>;
+impl_versioned_try_from!(
+	VersionedValidationProtocol,
+	ApprovalDistributionMessage,
+	v1::ValidationProtocol::ApprovalDistribution(x) => x,
+	v2::ValidationProtocol::ApprovalDistribution(x) => x
+);
+
+impl From<BitfieldDistributionMessage> for VersionedValidationProtocol {
+	fn from(message: BitfieldDistributionMessage) -> Self {
+		match message {
+			Versioned::V1(m) => Versioned::V1(v1::ValidationProtocol::BitfieldDistribution(m)),
+			Versioned::V2(m) => Versioned::V2(v2::ValidationProtocol::BitfieldDistribution(m)),
+		}
+	}
+}
+
+impl From<StatementDistributionMessage> for VersionedValidationProtocol {
+	fn from(message: StatementDistributionMessage) -> Self {
+		match message {
+			Versioned::V1(m) => Versioned::V1(v1::ValidationProtocol::StatementDistribution(m)),
+			Versioned::V2(m) => Versioned::V2(v2::ValidationProtocol::StatementDistribution(m)),
+		}
+	}
+}
+
+impl From<ApprovalDistributionMessage> for VersionedValidationProtocol {
+	fn from(message: ApprovalDistributionMessage) -> Self {
+		match message {
+			Versioned::V1(m) => Versioned::V1(v1::ValidationProtocol::ApprovalDistribution(m)),
+			Versioned::V2(m) => Versioned::V2(v2::ValidationProtocol::ApprovalDistribution(m)),
+		}
+	}
+}
+
 
 /// Specialized wrapper around [`View`].
 ///
@@ -452,3 +562,153 @@ pub mod v1 {
 		payload
 	}
 }
+
+/// v2 protocol types.
+///
+/// Only the validation peer-set has a second version so far. It is negotiated alongside v1, so
+/// changes to the wire format can be rolled out without a flag-day: nodes keep speaking v1 to
+/// peers which don't support v2 yet.
+pub mod v2 {
+	use parity_scale_codec::{Encode, Decode};
+	use std::convert::TryFrom;
+
+	use polkadot_primitives::v1::{
+		CandidateHash, CompactStatement, GroupIndex, Hash, ValidatorIndex,
+	};
+
+	use polkadot_node_primitives::UncheckedSignedFullStatement;
+
+	pub use super::v1::{
+		ApprovalDistributionMessage, BitfieldDistributionMessage, StatementMetadata,
+	};
+
+	/// Network messages used by the statement distribution subsystem.
+	///
+	/// Extends the v1 messages by the grid topology announcements of backed candidates.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum StatementDistributionMessage {
+		/// A signed full statement under a given relay-parent.
+		#[codec(index = 0)]
+		Statement(Hash, UncheckedSignedFullStatement),
+		/// Seconded statement with large payload (e.g. containing a runtime upgrade).
+		///
+		/// We only gossip the hash in that case, actual payloads can be fetched from sending node
+		/// via request/response.
+		#[codec(index = 1)]
+		LargeStatement(StatementMetadata),
+		/// Compact announcement that a candidate got backed by its group.
+		///
+		/// Validators outside of the backing group only receive these over the grid topology and
+		/// fetch the full statements on demand via `RequestBackedStatements`.
+		#[codec(index = 2)]
+		BackedCandidateManifest(BackedCandidateManifest),
+		/// Request all statements about a candidate the recipient previously announced to us via
+		/// `BackedCandidateManifest`.
+		#[codec(index = 3)]
+		RequestBackedStatements(Hash, CandidateHash),
+	}
+
+	/// Announcement of a backed candidate, as sent over the grid topology.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub struct BackedCandidateManifest {
+		/// Relay parent the candidate was backed under.
+		pub relay_parent: Hash,
+		/// Hash of the backed candidate.
+		pub candidate_hash: CandidateHash,
+		/// The group that backed the candidate.
+		pub group_index: GroupIndex,
+		/// Validators of the group the sender has statements from.
+		pub statement_knowledge: Vec<ValidatorIndex>,
+	}
+
+	impl StatementDistributionMessage {
+		/// Get meta data of the given `StatementDistributionMessage`.
+		///
+		/// Returns `None` for messages not carrying a statement.
+		pub fn get_metadata(&self) -> Option<StatementMetadata> {
+			match self {
+				Self::Statement(relay_parent, statement) => Some(StatementMetadata {
+					relay_parent: *relay_parent,
+					candidate_hash: statement.unchecked_payload().candidate_hash(),
+					signed_by: statement.unchecked_validator_index(),
+					signature: statement.unchecked_signature().clone(),
+				}),
+				Self::LargeStatement(metadata) => Some(metadata.clone()),
+				Self::BackedCandidateManifest(_) | Self::RequestBackedStatements(..) => None,
+			}
+		}
+
+		/// Get fingerprint describing the contained statement uniquely.
+		///
+		/// Returns `None` for messages not carrying a statement.
+		pub fn get_fingerprint(&self) -> Option<(CompactStatement, ValidatorIndex)> {
+			match self {
+				Self::Statement(_, statement) => Some(
+					(statement.unchecked_payload().to_compact(), statement.unchecked_validator_index())
+				),
+				Self::LargeStatement(meta) =>
+					Some((CompactStatement::Seconded(meta.candidate_hash), meta.signed_by)),
+				Self::BackedCandidateManifest(_) | Self::RequestBackedStatements(..) => None,
+			}
+		}
+
+		/// Get contained relay parent.
+		pub fn get_relay_parent(&self) -> Hash {
+			match self {
+				Self::Statement(r, _) => *r,
+				Self::LargeStatement(meta) => meta.relay_parent,
+				Self::BackedCandidateManifest(manifest) => manifest.relay_parent,
+				Self::RequestBackedStatements(r, _) => *r,
+			}
+		}
+
+		/// Whether this message contains a large statement.
+		pub fn is_large_statement(&self) -> bool {
+			if let Self::LargeStatement(_) = self {
+				true
+			} else {
+				false
+			}
+		}
+	}
+
+	impl From<super::v1::StatementDistributionMessage> for StatementDistributionMessage {
+		fn from(message: super::v1::StatementDistributionMessage) -> Self {
+			match message {
+				super::v1::StatementDistributionMessage::Statement(r, s) => Self::Statement(r, s),
+				super::v1::StatementDistributionMessage::LargeStatement(m) => Self::LargeStatement(m),
+			}
+		}
+	}
+
+	impl TryFrom<StatementDistributionMessage> for super::v1::StatementDistributionMessage {
+		type Error = crate::WrongVariant;
+
+		fn try_from(message: StatementDistributionMessage) -> Result<Self, Self::Error> {
+			match message {
+				StatementDistributionMessage::Statement(r, s) => Ok(Self::Statement(r, s)),
+				StatementDistributionMessage::LargeStatement(m) => Ok(Self::LargeStatement(m)),
+				StatementDistributionMessage::BackedCandidateManifest(_) |
+				StatementDistributionMessage::RequestBackedStatements(..) => Err(crate::WrongVariant),
+			}
+		}
+	}
+
+	/// All network messages on the validation peer-set.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum ValidationProtocol {
+		/// Bitfield distribution messages
+		#[codec(index = 1)]
+		BitfieldDistribution(BitfieldDistributionMessage),
+		/// Statement distribution messages
+		#[codec(index = 3)]
+		StatementDistribution(StatementDistributionMessage),
+		/// Approval distribution messages
+		#[codec(index = 4)]
+		ApprovalDistribution(ApprovalDistributionMessage),
+	}
+
+	impl_try_from!(ValidationProtocol, BitfieldDistribution, BitfieldDistributionMessage);
+	impl_try_from!(ValidationProtocol, StatementDistribution, StatementDistributionMessage);
+	impl_try_from!(ValidationProtocol, ApprovalDistribution, ApprovalDistributionMessage);
+}
@@ -20,6 +20,8 @@ use sc_network::config::{NonDefaultSetConfig, SetConfig};
 use std::{borrow::Cow, ops::{Index, IndexMut}};
 use strum::{EnumIter, IntoEnumIterator};
 
+use crate::ProtocolVersion;
+
 /// The peer-sets and thus the protocols which are used for the network.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
 pub enum PeerSet {
@@ -30,6 +32,34 @@ pub enum PeerSet {
 	Collation,
 }
 
+/// Supported versions of the validation peer-set protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, EnumIter)]
+pub enum ValidationVersion {
+	/// The first version.
+	V1 = 1,
+	/// The second version.
+	V2 = 2,
+}
+
+/// Supported versions of the collation peer-set protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, EnumIter)]
+pub enum CollationVersion {
+	/// The first version.
+	V1 = 1,
+}
+
+impl From<ValidationVersion> for ProtocolVersion {
+	fn from(version: ValidationVersion) -> ProtocolVersion {
+		version as ProtocolVersion
+	}
+}
+
+impl From<CollationVersion> for ProtocolVersion {
+	fn from(version: CollationVersion) -> ProtocolVersion {
+		version as ProtocolVersion
+	}
+}
+
 /// Whether a node is an authority or not.
 ///
 /// Peer set configuration gets adjusted accordingly.
@@ -45,15 +75,17 @@ impl PeerSet {
 	/// Get `sc_network` peer set configurations for each peerset.
 	///
 	/// Those should be used in the network configuration to register the protocols with the
-	/// network service.
+	/// network service. The newest version is registered as the main protocol, all older versions
+	/// as fallbacks, so peers negotiate the newest version both sides support.
 	pub fn get_info(self, is_authority: IsAuthority) -> NonDefaultSetConfig {
 		let protocol = self.into_protocol_name();
+		let fallback_names = self.fallback_protocol_names();
 		let max_notification_size = 100 * 1024;
 
 		match self {
 			PeerSet::Validation => NonDefaultSetConfig {
 				notifications_protocol: protocol,
-				fallback_names: Vec::new(),
+				fallback_names,
 				max_notification_size,
 				set_config: sc_network::config::SetConfig {
 					// we allow full nodes to connect to validators for gossip
@@ -68,7 +100,7 @@ impl PeerSet {
 			},
 			PeerSet::Collation => NonDefaultSetConfig {
 				notifications_protocol: protocol,
-				fallback_names: Vec::new(),
+				fallback_names,
 				max_notification_size,
 				set_config: SetConfig {
 					// Non-authority nodes don't need to accept incoming connections on this peer set:
@@ -85,26 +117,79 @@ impl PeerSet {
 		}
 	}
 
-	/// Get the protocol name associated with each peer set as static str.
-	pub const fn get_protocol_name_static(self) -> &'static str {
+	/// All protocol versions supported on this peer set, newest first.
+	pub fn get_versions(self) -> Vec<ProtocolVersion> {
+		let mut versions: Vec<ProtocolVersion> = match self {
+			PeerSet::Validation => ValidationVersion::iter().map(Into::into).collect(),
+			PeerSet::Collation => CollationVersion::iter().map(Into::into).collect(),
+		};
+		versions.sort_unstable_by(|a, b| b.cmp(a));
+		versions
+	}
+
+	/// The newest protocol version supported on this peer set.
+	pub fn get_main_version(self) -> ProtocolVersion {
+		match self {
+			PeerSet::Validation => ValidationVersion::V2.into(),
+			PeerSet::Collation => CollationVersion::V1.into(),
+		}
+	}
+
+	/// Get the protocol name of the given version of this peer set as static str.
+	///
+	/// Returns `None` for unsupported versions.
+	pub fn get_protocol_name_static(self, version: ProtocolVersion) -> Option<&'static str> {
+		match (self, version) {
+			(PeerSet::Validation, 1) => Some("/polkadot/validation/1"),
+			(PeerSet::Validation, 2) => Some("/polkadot/validation/2"),
+			(PeerSet::Collation, 1) => Some("/polkadot/collation/1"),
+			_ => None,
+		}
+	}
+
+	/// Get the name of the main protocol of this peer set as static str.
+	///
+	/// This is the protocol of the newest version, which identifies the peer set towards Substrate
+	/// independent of the version negotiated with a particular peer.
+	pub fn get_main_protocol_name_static(self) -> &'static str {
+		self.get_protocol_name_static(self.get_main_version())
+			.expect("The main version is always supported; qed")
+	}
+
+	/// A version independent label of this peer set for use in metrics.
+	///
+	/// These are the names of the first protocol versions, which were used before protocols got
+	/// versioned, so existing dashboards keep working.
+	pub const fn get_label(self) -> &'static str {
 		match self {
 			PeerSet::Validation => "/polkadot/validation/1",
 			PeerSet::Collation => "/polkadot/collation/1",
 		}
 	}
 
-	/// Convert a peer set into a protocol name as understood by Substrate.
+	/// Convert a peer set into its main protocol name as understood by Substrate.
 	pub fn into_protocol_name(self) -> Cow<'static, str> {
-		self.get_protocol_name_static().into()
+		self.get_main_protocol_name_static().into()
 	}
 
-	/// Try parsing a protocol name into a peer set.
-	pub fn try_from_protocol_name(name: &Cow<'static, str>) -> Option<PeerSet> {
-		match name {
-			n if n == &PeerSet::Validation.into_protocol_name() => Some(PeerSet::Validation),
-			n if n == &PeerSet::Collation.into_protocol_name() => Some(PeerSet::Collation),
-			_ => None,
-		}
+	/// The protocol names of all but the newest version, as fallbacks for the main protocol.
+	pub fn fallback_protocol_names(self) -> Vec<Cow<'static, str>> {
+		self.get_versions()
+			.into_iter()
+			.filter(|version| *version != self.get_main_version())
+			.filter_map(|version| self.get_protocol_name_static(version))
+			.map(Into::into)
+			.collect()
+	}
+
+	/// Try parsing a protocol name of any supported version into a peer set and the version.
+	pub fn try_from_protocol_name(name: &Cow<'static, str>) -> Option<(PeerSet, ProtocolVersion)> {
+		PeerSet::iter().find_map(|peer_set| {
+			peer_set.get_versions()
+				.into_iter()
+				.find(|version| peer_set.get_protocol_name_static(*version) == Some(name.as_ref()))
+				.map(|version| (peer_set, version))
+		})
 	}
 }
 
@@ -141,3 +226,31 @@ impl<T> IndexMut<PeerSet> for PerPeerSet<T> {
 pub fn peer_sets_info(is_authority: IsAuthority) -> Vec<sc_network::config::NonDefaultSetConfig> {
 	PeerSet::iter().map(|s| s.get_info(is_authority)).collect()
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn protocol_names_round_trip() {
+		for peer_set in PeerSet::iter() {
+			for version in peer_set.get_versions() {
+				let name = peer_set.get_protocol_name_static(version).unwrap().into();
+				assert_eq!(PeerSet::try_from_protocol_name(&name), Some((peer_set, version)));
+			}
+		}
+
+		assert_eq!(PeerSet::try_from_protocol_name(&"/polkadot/validation/3".into()), None);
+	}
+
+	#[test]
+	fn newest_version_is_main_protocol() {
+		let info = PeerSet::Validation.get_info(IsAuthority::Yes);
+		assert_eq!(info.notifications_protocol, "/polkadot/validation/2");
+		assert_eq!(info.fallback_names, vec![Cow::from("/polkadot/validation/1")]);
+
+		let info = PeerSet::Collation.get_info(IsAuthority::Yes);
+		assert_eq!(info.notifications_protocol, "/polkadot/collation/1");
+		assert!(info.fallback_names.is_empty());
+	}
+}
@@ -0,0 +1,277 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Audit log of the reputation changes reported via the network bridge.
+//!
+//! Keeps the most recent changes of recently reported peers, so operators can find out why a
+//! peer got banned. The network bridge records the changes, the log itself lives here so it can be
+//! queried, e.g. over RPC, without depending on the bridge. The peer set itself does not tell us about bans, so we keep an estimate of
+//! each peer's reputation, following the same rules as the peer set, and consider a peer banned
+//! once the estimate drops below the ban threshold.
+
+use std::{collections::{HashMap, VecDeque}, sync::Arc, time::{Instant, SystemTime, UNIX_EPOCH}};
+
+use parking_lot::Mutex;
+
+use sc_network::ReputationChange;
+use sc_peerset::BANNED_THRESHOLD;
+
+use crate::{PeerId, UnifiedReputationChange as Rep};
+
+const LOG_TARGET: &str = "parachain::network-bridge";
+
+/// Every second the reputation of a peer converges towards zero by that fraction, as in
+/// `sc-peerset`.
+///
+/// `sc-peerset` doesn't export its decay, `decay_follows_the_peer_set` pins it to the rule the
+/// peer set applies.
+const DECAY_PER_SECOND: f64 = 1.0 / 50.0;
+
+/// Configuration of the reputation audit log.
+#[derive(Debug, Clone, Copy)]
+pub struct ReputationAuditConfig {
+	/// Number of changes kept per peer.
+	pub max_changes_per_peer: usize,
+	/// Number of peers to keep changes for. Peers with the oldest changes are dropped first.
+	pub max_peers: usize,
+	/// Whether to log a warning with the recent changes of a peer once it gets banned.
+	pub log_bans: bool,
+}
+
+impl Default for ReputationAuditConfig {
+	fn default() -> Self {
+		ReputationAuditConfig {
+			max_changes_per_peer: 32,
+			max_peers: 1000,
+			log_bans: false,
+		}
+	}
+}
+
+/// A recorded reputation change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReputationChangeRecord {
+	/// Reason given for the change.
+	pub reason: &'static str,
+	/// The change of the reputation.
+	pub value: i32,
+	/// Milliseconds since the unix epoch at which the change was reported.
+	pub timestamp: u64,
+}
+
+struct PeerAudit {
+	changes: VecDeque<ReputationChangeRecord>,
+	/// Estimated reputation of the peer at `last_update`.
+	reputation: f64,
+	last_update: Instant,
+	banned: bool,
+}
+
+impl PeerAudit {
+	fn new(now: Instant) -> Self {
+		PeerAudit {
+			changes: VecDeque::new(),
+			reputation: 0.0,
+			last_update: now,
+			banned: false,
+		}
+	}
+
+	fn decay(&mut self, now: Instant) {
+		let elapsed = now.saturating_duration_since(self.last_update).as_secs_f64();
+		self.reputation *= (1.0 - DECAY_PER_SECOND).powf(elapsed);
+		self.last_update = now;
+	}
+}
+
+#[derive(Default)]
+struct AuditInner {
+	config: ReputationAuditConfig,
+	peers: HashMap<PeerId, PeerAudit>,
+}
+
+/// Shared handle to the reputation audit log.
+///
+/// The network bridge records all reputation changes reported through it, clones of the handle
+/// can be used to query the log, e.g. over RPC.
+#[derive(Clone, Default)]
+pub struct ReputationAudit(Arc<Mutex<AuditInner>>);
+
+impl ReputationAudit {
+	/// Create a new, empty audit log.
+	pub fn new(config: ReputationAuditConfig) -> Self {
+		ReputationAudit(Arc::new(Mutex::new(AuditInner { config, peers: HashMap::new() })))
+	}
+
+	/// Recent reputation changes of a peer, oldest first.
+	pub fn history(&self, peer: &PeerId) -> Vec<ReputationChangeRecord> {
+		self.0.lock()
+			.peers
+			.get(peer)
+			.map_or_else(Vec::new, |audit| audit.changes.iter().cloned().collect())
+	}
+
+	/// Peers with recorded reputation changes.
+	pub fn peers(&self) -> Vec<PeerId> {
+		self.0.lock().peers.keys().cloned().collect()
+	}
+
+	/// Record a reputation change.
+	pub fn note_change(&self, peer: PeerId, rep: Rep) {
+		self.note_base_change(peer, rep.into_base_rep())
+	}
+
+	/// Record a reputation change as passed to substrate, e.g. along with a response.
+	pub fn note_base_change(&self, peer: PeerId, rep: ReputationChange) {
+		self.note_change_at(peer, rep, Instant::now(), unix_millis())
+	}
+
+	fn note_change_at(&self, peer: PeerId, rep: ReputationChange, now: Instant, timestamp: u64) {
+		let mut inner = self.0.lock();
+		let config = inner.config;
+		if config.max_peers == 0 || config.max_changes_per_peer == 0 {
+			return
+		}
+
+		if !inner.peers.contains_key(&peer) && inner.peers.len() >= config.max_peers {
+			let oldest = inner.peers
+				.iter()
+				.min_by_key(|(_, audit)| audit.last_update)
+				.map(|(peer, _)| *peer);
+			if let Some(oldest) = oldest {
+				inner.peers.remove(&oldest);
+			}
+		}
+
+		let audit = inner.peers.entry(peer).or_insert_with(|| PeerAudit::new(now));
+		let value = rep.value;
+
+		audit.decay(now);
+		audit.reputation = (audit.reputation + f64::from(value)).max(f64::from(i32::MIN));
+		if audit.changes.len() >= config.max_changes_per_peer {
+			audit.changes.pop_front();
+		}
+		audit.changes.push_back(ReputationChangeRecord {
+			reason: rep.reason,
+			value,
+			timestamp,
+		});
+
+		let banned = audit.reputation < f64::from(BANNED_THRESHOLD);
+		if banned && !audit.banned && config.log_bans {
+			let reasons: Vec<_> = audit.changes.iter().map(|c| (c.reason, c.value)).collect();
+			tracing::warn!(
+				target: LOG_TARGET,
+				?peer,
+				reason = rep.reason,
+				history = ?reasons,
+				"Peer got banned",
+			);
+		}
+		audit.banned = banned;
+	}
+}
+
+fn unix_millis() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	const COST: Rep = Rep::CostMajor("Test cost");
+	const BENEFIT: Rep = Rep::BenefitMinor("Test benefit");
+
+	fn audit(max_changes_per_peer: usize, max_peers: usize) -> ReputationAudit {
+		ReputationAudit::new(ReputationAuditConfig { max_changes_per_peer, max_peers, log_bans: false })
+	}
+
+	#[test]
+	fn history_is_bounded_per_peer() {
+		let audit = audit(2, 10);
+		let peer = PeerId::random();
+		let now = Instant::now();
+
+		audit.note_change_at(peer, BENEFIT.into_base_rep(), now, 1);
+		audit.note_change_at(peer, COST.into_base_rep(), now, 2);
+		audit.note_change_at(peer, COST.into_base_rep(), now, 3);
+
+		let history = audit.history(&peer);
+		assert_eq!(history.len(), 2);
+		assert_eq!(history[0], ReputationChangeRecord { reason: "Test cost", value: -300_000, timestamp: 2 });
+		assert_eq!(history[1].timestamp, 3);
+		assert!(audit.history(&PeerId::random()).is_empty());
+	}
+
+	#[test]
+	fn peers_with_oldest_changes_are_dropped() {
+		let audit = audit(2, 2);
+		let (a, b, c) = (PeerId::random(), PeerId::random(), PeerId::random());
+		let now = Instant::now();
+
+		audit.note_change_at(a, COST.into_base_rep(), now, 0);
+		audit.note_change_at(b, COST.into_base_rep(), now + Duration::from_secs(1), 0);
+		audit.note_change_at(c, COST.into_base_rep(), now + Duration::from_secs(2), 0);
+
+		assert!(audit.history(&a).is_empty());
+		assert_eq!(audit.history(&b).len(), 1);
+		assert_eq!(audit.history(&c).len(), 1);
+	}
+
+	#[test]
+	fn decay_follows_the_peer_set() {
+		// Once per second, `sc-peerset` takes a fiftieth of each reputation away.
+		fn peer_set_tick(reputation: i32) -> i32 {
+			reputation.saturating_sub(reputation / 50)
+		}
+
+		let now = Instant::now();
+		for reputation in &[BANNED_THRESHOLD, -1_000_000, 1_000_000] {
+			let mut audit = PeerAudit::new(now);
+			audit.reputation = f64::from(*reputation);
+
+			let mut expected = *reputation;
+			for seconds in 1..=60 {
+				expected = peer_set_tick(expected);
+				audit.decay(now + Duration::from_secs(seconds));
+				assert!(
+					(audit.reputation - f64::from(expected)).abs() <= 60.0,
+					"{} after {}s: {} != {}",
+					reputation,
+					seconds,
+					audit.reputation,
+					expected,
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn bans_are_detected_and_reputation_decays() {
+		let audit = audit(10, 10);
+		let peer = PeerId::random();
+		let now = Instant::now();
+
+		audit.note_change_at(peer, Rep::Malicious("Test malicious").into_base_rep(), now, 0);
+		assert!(audit.0.lock().peers[&peer].banned);
+
+		// After some time the peer is no longer banned.
+		audit.note_change_at(peer, BENEFIT.into_base_rep(), now + Duration::from_secs(60), 0);
+		assert!(!audit.0.lock().peers[&peer].banned);
+	}
+}
@@ -58,6 +58,8 @@ pub mod v1;
 pub enum Protocol {
 	/// Protocol for chunk fetching, used by availability distribution and availability recovery.
 	ChunkFetching,
+	/// Protocol for fetching multiple chunks at once, used by availability recovery.
+	BatchedChunkFetching,
 	/// Protocol for fetching collations from collators.
 	CollationFetching,
 	/// Protocol for fetching seconded PoVs from validators of the same group.
@@ -103,6 +105,25 @@ const STATEMENTS_TIMEOUT: Duration = Duration::from_secs(1);
 /// to have 3 slow nodes connected, to delay transfer for others by `STATEMENTS_TIMEOUT`.
 pub const MAX_PARALLEL_STATEMENT_REQUESTS: u32 = 3;
 
+/// Maximum number of chunks which can be requested in a single `BatchedChunkFetchingRequest`.
+pub const MAX_CHUNKS_PER_BATCH: usize = 64;
+
+/// Maximum accumulated size of the chunks in a `BatchedChunkFetchingResponse`.
+///
+/// Responders only serve as many of the requested chunks as fit, the rest has to be requested
+/// again.
+pub const MAX_BATCHED_CHUNKS_RESPONSE_SIZE: u64 = MAX_POV_SIZE as u64;
+
+/// Timeout for batched chunk requests.
+///
+/// Responses can be as large as a PoV and are requested from all backers at once, so we assume
+/// only a tenth of the minimum bandwidth for transferring a single response, on top of the
+/// timeout for a connected peer.
+const BATCHED_CHUNKS_REQUEST_TIMEOUT_CONNECTED: Duration = Duration::from_millis(
+	DEFAULT_REQUEST_TIMEOUT_CONNECTED.as_millis() as u64 +
+		MAX_BATCHED_CHUNKS_RESPONSE_SIZE * 1000 / (MIN_BANDWIDTH_BYTES / 10)
+);
+
 impl Protocol {
 	/// Get a configuration for a given Request response protocol.
 	///
@@ -125,6 +146,15 @@ impl Protocol {
 				request_timeout: DEFAULT_REQUEST_TIMEOUT_CONNECTED,
 				inbound_queue: Some(tx),
 			},
+			Protocol::BatchedChunkFetching => RequestResponseConfig {
+				name: p_name,
+				// A single chunk request is 36 bytes.
+				max_request_size: 100 * MAX_CHUNKS_PER_BATCH as u64,
+				// + 1000 to account for protocol overhead and the chunk proofs.
+				max_response_size: MAX_BATCHED_CHUNKS_RESPONSE_SIZE + 1000,
+				request_timeout: BATCHED_CHUNKS_REQUEST_TIMEOUT_CONNECTED,
+				inbound_queue: Some(tx),
+			},
 			Protocol::CollationFetching => RequestResponseConfig {
 				name: p_name,
 				max_request_size: 1_000,
@@ -190,6 +220,9 @@ impl Protocol {
 			// assuming we can service requests relatively quickly, which would need to be measured
 			// as well.
 			Protocol::ChunkFetching => 100,
+			// Batched requests are only sent by validators recovering data from backers, so
+			// they are a lot rarer than single chunk requests.
+			Protocol::BatchedChunkFetching => 10,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
 			Protocol::CollationFetching => 10,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
@@ -229,10 +262,24 @@ impl Protocol {
 		self.get_protocol_name_static().into()
 	}
 
+	/// Get a short label of the protocol, e.g. for use in metrics and on the command line.
+	pub const fn get_label(self) -> &'static str {
+		match self {
+			Protocol::ChunkFetching => "chunk_fetching",
+			Protocol::BatchedChunkFetching => "batched_chunk_fetching",
+			Protocol::CollationFetching => "collation_fetching",
+			Protocol::PoVFetching => "pov_fetching",
+			Protocol::AvailableDataFetching => "available_data_fetching",
+			Protocol::StatementFetching => "statement_fetching",
+			Protocol::DisputeSending => "dispute_sending",
+		}
+	}
+
 	/// Get the protocol name associated with each peer set as static str.
 	pub const fn get_protocol_name_static(self) -> &'static str {
 		match self {
 			Protocol::ChunkFetching => "/polkadot/req_chunk/1",
+			Protocol::BatchedChunkFetching => "/polkadot/req_chunks_batched/1",
 			Protocol::CollationFetching => "/polkadot/req_collation/1",
 			Protocol::PoVFetching => "/polkadot/req_pov/1",
 			Protocol::AvailableDataFetching => "/polkadot/req_available_data/1",
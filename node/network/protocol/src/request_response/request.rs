@@ -48,6 +48,8 @@ pub trait IsRequest {
 pub enum Requests {
 	/// Request an availability chunk from a node.
 	ChunkFetching(OutgoingRequest<v1::ChunkFetchingRequest>),
+	/// Request multiple availability chunks from a node at once.
+	BatchedChunkFetching(OutgoingRequest<v1::BatchedChunkFetchingRequest>),
 	/// Fetch a collation from a collator which previously announced it.
 	CollationFetching(OutgoingRequest<v1::CollationFetchingRequest>),
 	/// Fetch a PoV from a validator which previously sent out a seconded statement.
@@ -65,6 +67,7 @@ impl Requests {
 	pub fn get_protocol(&self) -> Protocol {
 		match self {
 			Self::ChunkFetching(_) => Protocol::ChunkFetching,
+			Self::BatchedChunkFetching(_) => Protocol::BatchedChunkFetching,
 			Self::CollationFetching(_) => Protocol::CollationFetching,
 			Self::PoVFetching(_) => Protocol::PoVFetching,
 			Self::AvailableDataFetching(_) => Protocol::AvailableDataFetching,
@@ -73,6 +76,32 @@ impl Requests {
 		}
 	}
 
+	/// Size of the request payload once encoded.
+	pub fn encoded_size(&self) -> usize {
+		match self {
+			Self::ChunkFetching(r) => r.payload.encoded_size(),
+			Self::BatchedChunkFetching(r) => r.payload.encoded_size(),
+			Self::CollationFetching(r) => r.payload.encoded_size(),
+			Self::PoVFetching(r) => r.payload.encoded_size(),
+			Self::AvailableDataFetching(r) => r.payload.encoded_size(),
+			Self::StatementFetching(r) => r.payload.encoded_size(),
+			Self::DisputeSending(r) => r.payload.encoded_size(),
+		}
+	}
+
+	/// Replace the sender the response gets delivered to, returning the previous one.
+	pub fn replace_response_sender(&mut self, sender: ResponseSender) -> ResponseSender {
+		match self {
+			Self::ChunkFetching(r) => std::mem::replace(&mut r.pending_response, sender),
+			Self::BatchedChunkFetching(r) => std::mem::replace(&mut r.pending_response, sender),
+			Self::CollationFetching(r) => std::mem::replace(&mut r.pending_response, sender),
+			Self::PoVFetching(r) => std::mem::replace(&mut r.pending_response, sender),
+			Self::AvailableDataFetching(r) => std::mem::replace(&mut r.pending_response, sender),
+			Self::StatementFetching(r) => std::mem::replace(&mut r.pending_response, sender),
+			Self::DisputeSending(r) => std::mem::replace(&mut r.pending_response, sender),
+		}
+	}
+
 	/// Encode the request.
 	///
 	/// The corresponding protocol is returned as well, as we are now leaving typed territory.
@@ -83,6 +112,7 @@ impl Requests {
 	pub fn encode_request(self) -> (Protocol, OutgoingRequest<Vec<u8>>) {
 		match self {
 			Self::ChunkFetching(r) => r.encode_request(),
+			Self::BatchedChunkFetching(r) => r.encode_request(),
 			Self::CollationFetching(r) => r.encode_request(),
 			Self::PoVFetching(r) => r.encode_request(),
 			Self::AvailableDataFetching(r) => r.encode_request(),
@@ -90,6 +90,57 @@ impl IsRequest for ChunkFetchingRequest {
 	const PROTOCOL: Protocol = Protocol::ChunkFetching;
 }
 
+/// Request multiple availability chunks at once.
+///
+/// Chunks can be of different candidates, which is useful for fetching chunks from backers, as
+/// they have all the chunks of the candidates they backed.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct BatchedChunkFetchingRequest {
+	/// The requested chunks, at most `MAX_CHUNKS_PER_BATCH`.
+	pub chunks: Vec<ChunkFetchingRequest>,
+}
+
+/// Receive a batch of requested erasure chunks.
+///
+/// Responses are in the order of the requested chunks, but may cover only a prefix of them, as
+/// responders limit the size of a response to `MAX_BATCHED_CHUNKS_RESPONSE_SIZE`. Chunks not
+/// covered by the response have to be requested again.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct BatchedChunkFetchingResponse {
+	/// Responses to the first `chunks.len()` requested chunks.
+	pub chunks: Vec<ChunkFetchingResponse>,
+}
+
+impl BatchedChunkFetchingResponse {
+	/// Match the responses with the requested chunks.
+	///
+	/// Returns the received chunks, whether found or not, and the requests which have not been
+	/// answered. Surplus responses are ignored.
+	pub fn recombine_into_chunks(
+		self,
+		req: &BatchedChunkFetchingRequest,
+	) -> (Vec<(ChunkFetchingRequest, Option<ErasureChunk>)>, Vec<ChunkFetchingRequest>) {
+		let answered = std::cmp::min(self.chunks.len(), req.chunks.len());
+		let received = req.chunks.iter()
+			.zip(self.chunks)
+			.map(|(chunk_req, response)| {
+				let chunk = match response {
+					ChunkFetchingResponse::Chunk(chunk) => Some(chunk.recombine_into_chunk(chunk_req)),
+					ChunkFetchingResponse::NoSuchChunk => None,
+				};
+				(*chunk_req, chunk)
+			})
+			.collect();
+
+		(received, req.chunks[answered..].to_vec())
+	}
+}
+
+impl IsRequest for BatchedChunkFetchingRequest {
+	type Response = BatchedChunkFetchingResponse;
+	const PROTOCOL: Protocol = Protocol::BatchedChunkFetching;
+}
+
 /// Request the advertised collation at that relay-parent.
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct CollationFetchingRequest {
@@ -40,17 +40,21 @@ use polkadot_node_subsystem_util::{
 };
 use polkadot_node_primitives::{SignedFullStatement, UncheckedSignedFullStatement, Statement};
 use polkadot_primitives::v1::{
-	CandidateHash, CommittedCandidateReceipt, CompactStatement, Hash,
+	CandidateHash, CommittedCandidateReceipt, CompactStatement, GroupIndex, Hash,
 	SigningContext, ValidatorId, ValidatorIndex, ValidatorSignature, AuthorityDiscoveryId,
 };
 use polkadot_node_network_protocol::{
-	IfDisconnected, PeerId, UnifiedReputationChange as Rep, View,
+	self as net_protocol, IfDisconnected, PeerId, UnifiedReputationChange as Rep, View,
+	ProtocolVersion, Versioned, VersionedValidationProtocol,
 	peer_set::{
-		IsAuthority, PeerSet
+		IsAuthority, PeerSet, ValidationVersion,
 	},
 	v1::{
 		self as protocol_v1, StatementMetadata
-	}
+	},
+	v2::{
+		self as protocol_v2, BackedCandidateManifest,
+	},
 };
 
 use futures::{channel::mpsc, future::RemoteHandle, prelude::*};
@@ -60,13 +64,14 @@ use sp_keystore::SyncCryptoStorePtr;
 use util::{Fault, runtime::RuntimeInfo};
 
 use std::collections::{HashMap, HashSet, hash_map::Entry};
+use std::convert::TryFrom;
 
 mod error;
 pub use error::{Error, NonFatal, Fatal, Result};
 
 /// Background task logic for requesting of large statements.
 mod requester;
-use requester::{RequesterMessage, fetch};
+use requester::{RequesterMessage, backed_statements_timeout, fetch};
 
 /// Background task logic for responding for large statements.
 mod responder;
@@ -81,6 +86,11 @@ const COST_INVALID_SIGNATURE: Rep = Rep::CostMajor("Invalid Statement Signature"
 const COST_WRONG_HASH: Rep = Rep::CostMajor("Received candidate had wrong hash");
 const COST_DUPLICATE_STATEMENT: Rep = Rep::CostMajorRepeated("Statement sent more than once by peer");
 const COST_APPARENT_FLOOD: Rep = Rep::Malicious("Peer appears to be flooding us with statements");
+const COST_UNEXPECTED_MANIFEST: Rep = Rep::CostMinor("Unexpected backed candidate manifest");
+const COST_INVALID_MANIFEST: Rep = Rep::CostMajor("Backed candidate manifest lacks a backing quorum");
+const COST_DUPLICATE_MANIFEST: Rep = Rep::CostMajorRepeated("Manifest sent more than once by peer");
+const COST_UNEXPECTED_STATEMENTS_REQUEST: Rep =
+	Rep::CostMinor("Requested statements about a candidate we did not announce");
 
 const BENEFIT_VALID_STATEMENT: Rep = Rep::BenefitMajor("Peer provided a valid statement");
 const BENEFIT_VALID_STATEMENT_FIRST: Rep = Rep::BenefitMajorFirst(
@@ -100,6 +110,13 @@ const LOG_TARGET: &str = "parachain::statement-distribution";
 /// Large statements should be rare.
 const MAX_LARGE_STATEMENTS_PER_SENDER: usize = 20;
 
+/// The number of votes from its group needed for a candidate to be considered backed.
+///
+/// This mirrors the quorum used by candidate backing.
+const fn group_quorum(n_validators: usize) -> usize {
+	(n_validators / 2) + 1
+}
+
 /// The statement distribution subsystem.
 pub struct StatementDistribution {
 	/// Pointer to a keystore, which is required for determining this nodes validator index.
@@ -203,7 +220,12 @@ struct PeerRelayParentKnowledge {
 	seconded_counts: HashMap<ValidatorIndex, VcPerPeerTracker>,
 	/// How many statements we've received for each candidate that we're aware of.
 	received_message_count: HashMap<CandidateHash, usize>,
-
+	/// candidates we announced to the peer via a `BackedCandidateManifest`.
+	sent_manifests: HashSet<CandidateHash>,
+	/// candidates the peer announced to us via a `BackedCandidateManifest`.
+	received_manifests: HashSet<CandidateHash>,
+	/// candidates the peer requested all statements about, after we announced them.
+	requested_candidates: HashSet<CandidateHash>,
 
 	/// How many large statements this peer already sent us.
 	///
@@ -407,6 +429,45 @@ impl PeerRelayParentKnowledge {
 	fn is_known_candidate(&self, candidate: &CandidateHash) -> bool {
 		self.sent_candidates.contains(candidate) || self.received_candidates.contains(candidate)
 	}
+
+	/// Whether the peer should be told about a backed candidate via a manifest.
+	///
+	/// This is not the case if it already knows about the candidate, either via statements or
+	/// via a manifest sent by either side.
+	fn can_send_manifest(&self, candidate: &CandidateHash) -> bool {
+		!self.is_known_candidate(candidate) &&
+			!self.sent_manifests.contains(candidate) &&
+			!self.received_manifests.contains(candidate)
+	}
+
+	/// Note a manifest received from the peer.
+	///
+	/// Provide the maximum amount of manifests the peer is allowed to send at this relay-parent,
+	/// there can't be more backed candidates than `VC_THRESHOLD` per validator.
+	fn receive_manifest(
+		&mut self,
+		candidate: CandidateHash,
+		max_manifest_count: usize,
+	) -> std::result::Result<(), Rep> {
+		if self.received_manifests.contains(&candidate) {
+			return Err(COST_DUPLICATE_MANIFEST);
+		}
+		if self.received_manifests.len() >= max_manifest_count {
+			return Err(COST_APPARENT_FLOOD);
+		}
+		self.received_manifests.insert(candidate);
+		Ok(())
+	}
+
+	/// Note that the peer requested all statements about a candidate.
+	///
+	/// This is only legal for candidates we announced to the peer and only once per candidate.
+	fn receive_statements_request(&mut self, candidate: CandidateHash) -> std::result::Result<(), Rep> {
+		if !self.sent_manifests.contains(&candidate) || !self.requested_candidates.insert(candidate) {
+			return Err(COST_UNEXPECTED_STATEMENTS_REQUEST);
+		}
+		Ok(())
+	}
 }
 
 struct PeerData {
@@ -414,6 +475,8 @@ struct PeerData {
 	view_knowledge: HashMap<Hash, PeerRelayParentKnowledge>,
 	// Peer might be an authority.
 	maybe_authority: Option<AuthorityDiscoveryId>,
+	// The validation protocol version negotiated with the peer.
+	version: ProtocolVersion,
 }
 
 impl PeerData {
@@ -506,6 +569,61 @@ impl PeerData {
 			.ok_or(COST_UNEXPECTED_STATEMENT)?
 			.receive_large_statement()
 	}
+
+	/// Whether the peer should be told about a backed candidate via a manifest.
+	fn can_send_manifest(&self, relay_parent: &Hash, candidate: &CandidateHash) -> bool {
+		self.view_knowledge
+			.get(relay_parent)
+			.map_or(false, |k| k.can_send_manifest(candidate))
+	}
+
+	/// Note that we are sending a manifest to the peer.
+	///
+	/// NOTE: assumes `self.can_send_manifest` returned true before this call.
+	fn send_manifest(&mut self, relay_parent: &Hash, candidate: CandidateHash) {
+		if let Some(knowledge) = self.view_knowledge.get_mut(relay_parent) {
+			knowledge.sent_manifests.insert(candidate);
+		}
+	}
+
+	/// Note a manifest received from the peer.
+	fn receive_manifest(
+		&mut self,
+		relay_parent: &Hash,
+		candidate: CandidateHash,
+		max_manifest_count: usize,
+	) -> std::result::Result<(), Rep> {
+		self.view_knowledge
+			.get_mut(relay_parent)
+			.ok_or(COST_UNEXPECTED_MANIFEST)?
+			.receive_manifest(candidate, max_manifest_count)
+	}
+
+	/// Note that the peer requested all statements about a candidate we announced.
+	fn receive_statements_request(
+		&mut self,
+		relay_parent: &Hash,
+		candidate: CandidateHash,
+	) -> std::result::Result<(), Rep> {
+		self.view_knowledge
+			.get_mut(relay_parent)
+			.ok_or(COST_UNEXPECTED_STATEMENTS_REQUEST)?
+			.receive_statements_request(candidate)
+	}
+
+	/// Whether the peer announced the given candidate to us via a manifest.
+	fn announced_candidate(&self, relay_parent: &Hash, candidate: &CandidateHash) -> bool {
+		self.view_knowledge
+			.get(relay_parent)
+			.map_or(false, |k| k.received_manifests.contains(candidate))
+	}
+
+	/// Whether the peer requested all statements about the given candidate.
+	fn requested_candidate(&self, relay_parent: &Hash, candidate: &CandidateHash) -> bool {
+		self.view_knowledge
+			.get(relay_parent)
+			.map_or(false, |k| k.requested_candidates.contains(candidate))
+	}
 }
 
 // A statement stored while a relay chain head is active.
@@ -567,7 +685,7 @@ struct FetchingInfo {
 	///
 	/// We use an `IndexMap` here to preserve the ordering of peers sending us messages. This is
 	/// desirable because we reward first sending peers with reputation.
-	available_peers: IndexMap<PeerId, Vec<protocol_v1::StatementDistributionMessage>>,
+	available_peers: IndexMap<PeerId, Vec<protocol_v2::StatementDistributionMessage>>,
 	/// Peers left to try in case the background task needs it.
 	peers_to_try: Vec<PeerId>,
 	/// Sender for sending fresh peers to the fetching task in case of failure.
@@ -633,6 +751,28 @@ struct ActiveHeadData {
 	seconded_counts: HashMap<ValidatorIndex, usize>,
 	/// A Jaeger span for this head, so we can attach data to it.
 	span: PerLeafSpan,
+	/// The validator groups of the session, indexed by `GroupIndex`.
+	groups: Vec<Vec<ValidatorIndex>>,
+	/// The group of each validator assigned to one.
+	validator_groups: HashMap<ValidatorIndex, GroupIndex>,
+	/// Authority discovery keys of all validators in the session, together with their group.
+	///
+	/// Peers authenticated with one of those keys take part in grid based distribution.
+	authorities: HashMap<AuthorityDiscoveryId, Option<GroupIndex>>,
+	/// The group we are part of at this head, if any.
+	our_group: Option<GroupIndex>,
+	/// Candidates that gathered a quorum of statements from their group, with that group.
+	backed_candidates: HashMap<CandidateHash, GroupIndex>,
+	/// Outstanding requests for all statements about candidates peers announced to us.
+	statement_requests: HashMap<CandidateHash, StatementsRequest>,
+}
+
+/// An outstanding request for all statements about a backed candidate.
+struct StatementsRequest {
+	/// The peer the statements are currently requested from.
+	peer: PeerId,
+	/// All peers the statements were requested from so far, including `peer`.
+	tried_peers: HashSet<PeerId>,
 }
 
 impl ActiveHeadData {
@@ -649,9 +789,124 @@ impl ActiveHeadData {
 			session_index,
 			seconded_counts: Default::default(),
 			span,
+			groups: Vec::new(),
+			validator_groups: HashMap::new(),
+			authorities: HashMap::new(),
+			our_group: None,
+			backed_candidates: HashMap::new(),
+			statement_requests: HashMap::new(),
+		}
+	}
+
+	/// Provide the groups and authority discovery keys of the session, which enables grid based
+	/// distribution of statements at this head.
+	///
+	/// Without this information all statements are gossiped to all peers.
+	fn with_groups(
+		mut self,
+		discovery_keys: &[AuthorityDiscoveryId],
+		groups: Vec<Vec<ValidatorIndex>>,
+		our_group: Option<GroupIndex>,
+	) -> Self {
+		let validator_groups: HashMap<_, _> = groups.iter()
+			.enumerate()
+			.flat_map(|(g, validators)| validators.iter().map(move |v| (*v, GroupIndex(g as u32))))
+			.collect();
+
+		self.authorities = discovery_keys.iter()
+			.enumerate()
+			.map(|(i, a)| (a.clone(), validator_groups.get(&ValidatorIndex(i as _)).copied()))
+			.collect();
+		self.validator_groups = validator_groups;
+		self.groups = groups;
+		self.our_group = our_group;
+		self
+	}
+
+	/// Whether statements by validators of `group` are only to reach the given peer on request.
+	///
+	/// This is the case for validators of the session outside of `group`, they learn about backed
+	/// candidates via manifests over the grid topology. Group members, peers which are not
+	/// validators in the session and peers speaking v1 of the protocol, which has no manifests, get
+	/// statements directly.
+	fn is_grid_only(&self, peer_data: &PeerData, group: Option<GroupIndex>) -> bool {
+		let group = match group {
+			Some(group) => group,
+			None => return false,
+		};
+
+		if peer_data.version != ProtocolVersion::from(ValidationVersion::V2) {
+			return false;
+		}
+
+		match peer_data.maybe_authority.as_ref().and_then(|a| self.authorities.get(a)) {
+			Some(peer_group) => *peer_group != Some(group),
+			None => false,
 		}
 	}
 
+	/// Whether the peer is a validator in the given group.
+	fn is_in_group(&self, peer_data: &PeerData, group: GroupIndex) -> bool {
+		peer_data.maybe_authority.as_ref()
+			.and_then(|a| self.authorities.get(a))
+			.map_or(false, |peer_group| *peer_group == Some(group))
+	}
+
+	/// The group of the validator which issued the given statement, if any.
+	fn group_of(&self, validator_index: ValidatorIndex) -> Option<GroupIndex> {
+		self.validator_groups.get(&validator_index).copied()
+	}
+
+	/// Check whether a candidate gathered a quorum of statements from its group.
+	///
+	/// Returns the group, if the candidate just became backed by this call. An outstanding request
+	/// for the statements about the candidate is answered at that point.
+	fn note_maybe_backed(&mut self, candidate_hash: CandidateHash) -> Option<GroupIndex> {
+		if self.backed_candidates.contains_key(&candidate_hash) {
+			return None;
+		}
+
+		// The `Seconded` statement always comes first and is issued by a member of the group.
+		let group = self.statements_about(candidate_hash)
+			.next()
+			.and_then(|s| self.group_of(s.statement.validator_index()))?;
+		let group_size = self.groups.get(group.0 as usize).map_or(0, |g| g.len());
+		let votes = self.statement_knowledge(candidate_hash, group).len();
+
+		if group_size == 0 || votes < group_quorum(group_size) {
+			return None;
+		}
+
+		self.backed_candidates.insert(candidate_hash, group);
+		self.statement_requests.remove(&candidate_hash);
+		Some(group)
+	}
+
+	/// All validators of the given group we have statements from about a candidate.
+	fn statement_knowledge(&self, candidate_hash: CandidateHash, group: GroupIndex) -> Vec<ValidatorIndex> {
+		let signers: HashSet<ValidatorIndex> = self.statements_about(candidate_hash)
+			.map(|s| s.statement.validator_index())
+			.filter(|v| self.group_of(*v) == Some(group))
+			.collect();
+		signers.into_iter().collect()
+	}
+
+	/// Check a manifest received from a peer for consistency with the session's groups.
+	fn check_manifest(&self, manifest: &BackedCandidateManifest) -> std::result::Result<(), Rep> {
+		let group = self.groups
+			.get(manifest.group_index.0 as usize)
+			.ok_or(COST_INVALID_MANIFEST)?;
+
+		let votes: HashSet<&ValidatorIndex> = manifest.statement_knowledge.iter()
+			.filter(|v| group.contains(*v))
+			.collect();
+
+		if votes.len() < group_quorum(group.len()) {
+			return Err(COST_INVALID_MANIFEST);
+		}
+		Ok(())
+	}
+
 	/// Note the given statement.
 	///
 	/// If it was not already known and can be accepted,  returns `NotedStatement::Fresh`,
@@ -852,7 +1107,7 @@ async fn circulate_statement_and_dependents(
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
 	relay_parent: Hash,
 	statement: SignedFullStatement,
-	priority_peers: Vec<PeerId>,
+	mut priority_peers: Vec<PeerId>,
 	metrics: &Metrics,
 ) {
 	let active_head = match active_heads.get_mut(&relay_parent) {
@@ -864,6 +1119,18 @@ async fn circulate_statement_and_dependents(
 		.with_candidate(statement.payload().candidate_hash())
 		.with_stage(jaeger::Stage::StatementDistribution);
 
+	let (grid_priority_peers, grid_only_peers) = grid_recipients(
+		peers,
+		&*active_head,
+		&relay_parent,
+		&statement,
+	);
+	for peer in grid_priority_peers {
+		if !priority_peers.contains(&peer) {
+			priority_peers.push(peer);
+		}
+	}
+
 	// First circulate the statement directly to all peers needing it.
 	// The borrow of `active_head` needs to encompass only this (Rust) statement.
 	let outputs: Option<(CandidateHash, Vec<PeerId>)> = {
@@ -879,6 +1146,7 @@ async fn circulate_statement_and_dependents(
 						relay_parent,
 						stored,
 						priority_peers,
+						&grid_only_peers,
 					).await,
 				))
 			},
@@ -906,14 +1174,157 @@ async fn circulate_statement_and_dependents(
 				).await;
 			}
 		}
+
+		if active_head.note_maybe_backed(candidate_hash).is_some() {
+			circulate_manifest(
+				gossip_peers,
+				peers,
+				ctx,
+				relay_parent,
+				candidate_hash,
+				&*active_head,
+			).await;
+		}
+	}
+}
+
+/// Determine how a statement is to be circulated with regards to the grid topology.
+///
+/// Returns peers which have to receive the statement directly: members of the backing group, in
+/// case we are part of it as well, and peers which requested all statements about the candidate.
+/// Also returns the set of peers which must not get the statement via gossip, as they learn about
+/// the candidate via a `BackedCandidateManifest` once it got backed.
+fn grid_recipients(
+	peers: &HashMap<PeerId, PeerData>,
+	active_head: &ActiveHeadData,
+	relay_parent: &Hash,
+	statement: &SignedFullStatement,
+) -> (Vec<PeerId>, HashSet<PeerId>) {
+	let candidate_hash = statement.payload().candidate_hash();
+	let group = active_head.group_of(statement.validator_index());
+
+	let mut priority_peers = Vec::new();
+	let mut grid_only_peers = HashSet::new();
+
+	for (peer, peer_data) in peers {
+		let in_our_group = match (group, active_head.our_group) {
+			(Some(group), Some(our_group)) if group == our_group =>
+				active_head.is_in_group(peer_data, group),
+			_ => false,
+		};
+
+		if in_our_group || peer_data.requested_candidate(relay_parent, &candidate_hash) {
+			priority_peers.push(*peer);
+		} else if active_head.is_grid_only(peer_data, group) {
+			grid_only_peers.insert(*peer);
+		}
+	}
+
+	(priority_peers, grid_only_peers)
+}
+
+/// Announce a backed candidate to all our neighbors in the grid topology, which don't know about
+/// it yet.
+///
+/// Only validators outside of the backing group are sent manifests, everybody else gets the
+/// statements directly.
+async fn circulate_manifest(
+	gossip_peers: &HashSet<PeerId>,
+	peers: &mut HashMap<PeerId, PeerData>,
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	active_head: &ActiveHeadData,
+) {
+	let group = match active_head.backed_candidates.get(&candidate_hash) {
+		Some(group) => *group,
+		None => return,
+	};
+
+	let recipients: Vec<PeerId> = gossip_peers.iter()
+		.filter(|peer| peers.get(*peer).map_or(false, |peer_data|
+			peer_data.can_send_manifest(&relay_parent, &candidate_hash) &&
+				active_head.is_grid_only(peer_data, Some(group))
+		))
+		.copied()
+		.collect();
+
+	if recipients.is_empty() {
+		return;
+	}
+
+	for peer in &recipients {
+		if let Some(peer_data) = peers.get_mut(peer) {
+			peer_data.send_manifest(&relay_parent, candidate_hash);
+		}
+	}
+
+	let manifest = BackedCandidateManifest {
+		relay_parent,
+		candidate_hash,
+		group_index: group,
+		statement_knowledge: active_head.statement_knowledge(candidate_hash, group),
+	};
+
+	tracing::trace!(
+		target: LOG_TARGET,
+		?recipients,
+		?relay_parent,
+		?candidate_hash,
+		"Sending backed candidate manifest",
+	);
+	send_to_peers(
+		ctx,
+		peers,
+		recipients,
+		protocol_v2::StatementDistributionMessage::BackedCandidateManifest(manifest),
+	).await;
+}
+
+/// Send manifests for all backed candidates at a given relay-parent to a peer.
+async fn send_manifests(
+	peer: PeerId,
+	peer_data: &mut PeerData,
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	relay_parent: Hash,
+	active_head: &ActiveHeadData,
+) {
+	for (candidate_hash, group) in &active_head.backed_candidates {
+		if !peer_data.can_send_manifest(&relay_parent, candidate_hash) ||
+			!active_head.is_grid_only(peer_data, Some(*group))
+		{
+			continue;
+		}
+		peer_data.send_manifest(&relay_parent, *candidate_hash);
+
+		let manifest = BackedCandidateManifest {
+			relay_parent,
+			candidate_hash: *candidate_hash,
+			group_index: *group,
+			statement_knowledge: active_head.statement_knowledge(*candidate_hash, *group),
+		};
+
+		tracing::trace!(
+			target: LOG_TARGET,
+			?peer,
+			?relay_parent,
+			?candidate_hash,
+			"Sending backed candidate manifest",
+		);
+		send_to_peer(
+			ctx,
+			peer,
+			peer_data.version,
+			protocol_v2::StatementDistributionMessage::BackedCandidateManifest(manifest),
+		).await;
 	}
 }
 
 fn statement_message(relay_parent: Hash, statement: SignedFullStatement)
-	-> protocol_v1::ValidationProtocol
+	-> protocol_v2::StatementDistributionMessage
 {
-	let msg = if is_statement_large(&statement) {
-		protocol_v1::StatementDistributionMessage::LargeStatement(
+	if is_statement_large(&statement) {
+		protocol_v2::StatementDistributionMessage::LargeStatement(
 			StatementMetadata {
 				relay_parent,
 				candidate_hash: statement.payload().candidate_hash(),
@@ -922,10 +1333,77 @@ fn statement_message(relay_parent: Hash, statement: SignedFullStatement)
 			}
 		)
 	} else {
-		protocol_v1::StatementDistributionMessage::Statement(relay_parent, statement.into())
+		protocol_v2::StatementDistributionMessage::Statement(relay_parent, statement.into())
+	}
+}
+
+/// Wrap a statement distribution message into the given version of the validation protocol.
+///
+/// Returns `None` if the message does not exist in that version, v1 peers only understand plain
+/// statements.
+fn validation_protocol(
+	version: ProtocolVersion,
+	message: protocol_v2::StatementDistributionMessage,
+) -> Option<VersionedValidationProtocol> {
+	let message = if version == ValidationVersion::V2.into() {
+		Versioned::V2(message)
+	} else {
+		Versioned::V1(protocol_v1::StatementDistributionMessage::try_from(message).ok()?)
 	};
+	Some(net_protocol::StatementDistributionMessage::from(message).into())
+}
 
-	protocol_v1::ValidationProtocol::StatementDistribution(msg)
+/// Send a message to the given peers, in the protocol version each of them negotiated.
+async fn send_to_peers(
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	peers: &HashMap<PeerId, PeerData>,
+	recipients: Vec<PeerId>,
+	message: protocol_v2::StatementDistributionMessage,
+) {
+	let mut recipients_by_version: HashMap<ProtocolVersion, Vec<PeerId>> = HashMap::new();
+	for peer in recipients {
+		let version = peers.get(&peer).map_or(ValidationVersion::V1.into(), |p| p.version);
+		recipients_by_version.entry(version).or_default().push(peer);
+	}
+
+	for (version, recipients) in recipients_by_version {
+		let payload = match validation_protocol(version, message.clone()) {
+			Some(payload) => payload,
+			None => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?recipients,
+					?version,
+					"Message not supported by the protocol version of the recipients",
+				);
+				continue
+			}
+		};
+		ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+			recipients,
+			payload,
+		))).await;
+	}
+}
+
+/// Send a message to a single peer, in the protocol version it negotiated.
+async fn send_to_peer(
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	peer: PeerId,
+	version: ProtocolVersion,
+	message: protocol_v2::StatementDistributionMessage,
+) {
+	match validation_protocol(version, message) {
+		Some(payload) => ctx.send_message(AllMessages::NetworkBridge(
+			NetworkBridgeMessage::SendValidationMessage(vec![peer], payload)
+		)).await,
+		None => tracing::debug!(
+			target: LOG_TARGET,
+			?peer,
+			?version,
+			"Message not supported by the protocol version of the peer",
+		),
+	}
 }
 
 /// Check whether a statement should be treated as large statement.
@@ -953,6 +1431,8 @@ fn is_statement_large(statement: &SignedFullStatement) -> bool {
 
 /// Circulates a statement to all peers who have not seen it yet, and returns
 /// an iterator over peers who need to have dependent statements sent.
+///
+/// Peers in `grid_only_peers` are skipped, unless they are also contained in `priority_peers`.
 async fn circulate_statement<'a>(
 	gossip_peers: &HashSet<PeerId>,
 	peers: &mut HashMap<PeerId, PeerData>,
@@ -960,11 +1440,14 @@ async fn circulate_statement<'a>(
 	relay_parent: Hash,
 	stored: StoredStatement<'a>,
 	mut priority_peers: Vec<PeerId>,
+	grid_only_peers: &HashSet<PeerId>,
 ) -> Vec<PeerId> {
 	let fingerprint = stored.fingerprint();
 
 	let mut peers_to_send: Vec<PeerId> = peers.iter().filter_map(|(peer, data)| {
-		if data.can_send(&relay_parent, &fingerprint) {
+		if data.can_send(&relay_parent, &fingerprint) &&
+			(!grid_only_peers.contains(peer) || priority_peers.contains(peer))
+		{
 			Some(peer.clone())
 		} else {
 			None
@@ -1020,10 +1503,12 @@ async fn circulate_statement<'a>(
 			statement = ?stored.statement,
 			"Sending statement",
 		);
-		ctx.send_message(AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+		send_to_peers(
+			ctx,
+			peers,
 			peers_to_send.iter().map(|(p, _)| p.clone()).collect(),
 			payload,
-		))).await;
+		).await;
 	}
 
 	peers_to_send.into_iter().filter_map(|(peer, needs_dependent)| if needs_dependent {
@@ -1049,11 +1534,6 @@ async fn send_statements_about(
 			continue;
 		}
 		peer_data.send(&relay_parent, &fingerprint);
-		let payload = statement_message(
-			relay_parent,
-			statement.statement.clone(),
-		);
-
 		tracing::trace!(
 			target: LOG_TARGET,
 			?peer,
@@ -1062,9 +1542,12 @@ async fn send_statements_about(
 			statement = ?statement.statement,
 			"Sending statement",
 		);
-		ctx.send_message(AllMessages::NetworkBridge(
-			NetworkBridgeMessage::SendValidationMessage(vec![peer.clone()], payload)
-		)).await;
+		send_to_peer(
+			ctx,
+			peer.clone(),
+			peer_data.version,
+			statement_message(relay_parent, statement.statement.clone()),
+		).await;
 
 		metrics.on_statement_distributed();
 	}
@@ -1084,12 +1567,13 @@ async fn send_statements(
 		if !peer_data.can_send(&relay_parent, &fingerprint) {
 			continue;
 		}
+		let candidate_hash = statement.compact().candidate_hash();
+		if active_head.is_grid_only(peer_data, active_head.group_of(fingerprint.1)) &&
+			!peer_data.requested_candidate(&relay_parent, candidate_hash)
+		{
+			continue;
+		}
 		peer_data.send(&relay_parent, &fingerprint);
-		let payload = statement_message(
-			relay_parent,
-			statement.statement.clone(),
-		);
-
 		tracing::trace!(
 			target: LOG_TARGET,
 			?peer,
@@ -1097,9 +1581,12 @@ async fn send_statements(
 			statement = ?statement.statement,
 			"Sending statement"
 		);
-		ctx.send_message(AllMessages::NetworkBridge(
-			NetworkBridgeMessage::SendValidationMessage(vec![peer.clone()], payload)
-		)).await;
+		send_to_peer(
+			ctx,
+			peer.clone(),
+			peer_data.version,
+			statement_message(relay_parent, statement.statement.clone()),
+		).await;
 
 		metrics.on_statement_distributed();
 	}
@@ -1124,23 +1611,23 @@ async fn report_peer(
 /// If the message was large, but the result has been fetched already that one is returned.
 async fn retrieve_statement_from_message<'a>(
 	peer: PeerId,
-	message: protocol_v1::StatementDistributionMessage,
+	message: protocol_v2::StatementDistributionMessage,
 	active_head: &'a mut ActiveHeadData,
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
 ) -> Option<UncheckedSignedFullStatement> {
 
-	let fingerprint = message.get_fingerprint();
+	let fingerprint = message.get_fingerprint()?;
 	let candidate_hash = *fingerprint.0.candidate_hash();
 
 	// Immediately return any Seconded statement:
 	let message =
-		if let protocol_v1::StatementDistributionMessage::Statement(h, s) = message {
+		if let protocol_v2::StatementDistributionMessage::Statement(h, s) = message {
 			if let Statement::Seconded(_) = s.unchecked_payload() {
 				return Some(s)
 			}
-			protocol_v1::StatementDistributionMessage::Statement(h, s)
+			protocol_v2::StatementDistributionMessage::Statement(h, s)
 		} else {
 			message
 		};
@@ -1179,12 +1666,12 @@ async fn retrieve_statement_from_message<'a>(
 				}
 				LargeStatementStatus::FetchedOrShared(committed) => {
 					match message {
-						protocol_v1::StatementDistributionMessage::Statement(_, s) => {
+						protocol_v2::StatementDistributionMessage::Statement(_, s) => {
 							// We can now immediately return any statements (should only be
 							// `Statement::Valid` ones, but we don't care at this point.)
 							return Some(s)
 						}
-						protocol_v1::StatementDistributionMessage::LargeStatement(metadata) => {
+						protocol_v2::StatementDistributionMessage::LargeStatement(metadata) => {
 							return Some(UncheckedSignedFullStatement::new(
 								Statement::Seconded(
 									committed.clone()),
@@ -1192,13 +1679,16 @@ async fn retrieve_statement_from_message<'a>(
 									metadata.signature.clone(),
 							))
 						}
+						// Not carrying statements, handled in `handle_incoming_message_and_circulate`.
+						protocol_v2::StatementDistributionMessage::BackedCandidateManifest(_) |
+						protocol_v2::StatementDistributionMessage::RequestBackedStatements(..) => {}
 					}
 				}
 			}
 		}
 		Entry::Vacant(vacant) => {
 			match message {
-				protocol_v1::StatementDistributionMessage::LargeStatement(metadata) => {
+				protocol_v2::StatementDistributionMessage::LargeStatement(metadata) => {
 					if let Some(new_status) = launch_request(
 						metadata,
 						peer,
@@ -1209,12 +1699,15 @@ async fn retrieve_statement_from_message<'a>(
 						vacant.insert(new_status);
 					}
 				}
-				protocol_v1::StatementDistributionMessage::Statement(_, s) => {
+				protocol_v2::StatementDistributionMessage::Statement(_, s) => {
 					// No fetch in progress, safe to return any statement immediately (we don't bother
 					// about normal network jitter which might cause `Valid` statements to arrive early
 					// for now.).
 					return Some(s)
 				}
+				// Not carrying statements, handled in `handle_incoming_message_and_circulate`.
+				protocol_v2::StatementDistributionMessage::BackedCandidateManifest(_) |
+				protocol_v2::StatementDistributionMessage::RequestBackedStatements(..) => {}
 			}
 		}
 	}
@@ -1248,7 +1741,7 @@ async fn launch_request(
 	}
 	let available_peers = {
 		let mut m = IndexMap::new();
-		m.insert(peer, vec![protocol_v1::StatementDistributionMessage::LargeStatement(meta)]);
+		m.insert(peer, vec![protocol_v2::StatementDistributionMessage::LargeStatement(meta)]);
 		m
 	};
 	Some(LargeStatementStatus::Fetching(FetchingInfo {
@@ -1267,10 +1760,30 @@ async fn handle_incoming_message_and_circulate<'a>(
 	peers: &mut HashMap<PeerId, PeerData>,
 	active_heads: &'a mut HashMap<Hash, ActiveHeadData>,
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
-	message: protocol_v1::StatementDistributionMessage,
+	message: protocol_v2::StatementDistributionMessage,
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
 ) {
+	let message = match message {
+		protocol_v2::StatementDistributionMessage::BackedCandidateManifest(manifest) => {
+			handle_incoming_manifest(peer, peers, active_heads, ctx, req_sender, manifest).await;
+			return
+		}
+		protocol_v2::StatementDistributionMessage::RequestBackedStatements(relay_parent, candidate_hash) => {
+			handle_statements_request(
+				peer,
+				peers,
+				active_heads,
+				ctx,
+				relay_parent,
+				candidate_hash,
+				metrics,
+			).await;
+			return
+		}
+		message => message,
+	};
+
 	let handled_incoming = match peers.get_mut(&peer) {
 		Some(data) => {
 			handle_incoming_message(
@@ -1288,6 +1801,17 @@ async fn handle_incoming_message_and_circulate<'a>(
 
 	// if we got a fresh message, we need to circulate it to all peers.
 	if let Some((relay_parent, statement)) = handled_incoming {
+		// Release the borrow of `active_heads`, we need the active head for routing:
+		let comparator = statement.comparator.clone();
+		let full_statement = statement.statement.clone();
+		let statement = StoredStatement { comparator: &comparator, statement: &full_statement };
+		let candidate_hash = *statement.compact().candidate_hash();
+
+		let (priority_peers, grid_only_peers) = match active_heads.get(&relay_parent) {
+			Some(active_head) => grid_recipients(peers, active_head, &relay_parent, &full_statement),
+			None => (Vec::new(), HashSet::new()),
+		};
+
 		// we can ignore the set of peers who this function returns as now expecting
 		// dependent statements.
 		//
@@ -1301,9 +1825,229 @@ async fn handle_incoming_message_and_circulate<'a>(
 			ctx,
 			relay_parent,
 			statement,
-			Vec::new(),
+			priority_peers,
+			&grid_only_peers,
 		).await;
+
+		if let Some(active_head) = active_heads.get_mut(&relay_parent) {
+			if active_head.note_maybe_backed(candidate_hash).is_some() {
+				circulate_manifest(
+					gossip_peers,
+					peers,
+					ctx,
+					relay_parent,
+					candidate_hash,
+					&*active_head,
+				).await;
+			}
+		}
+	}
+}
+
+/// Handle a `BackedCandidateManifest` received from a peer.
+///
+/// If we don't know about a backing quorum for the candidate yet and did not request the
+/// statements from another peer already, we request all statements about it from the announcing
+/// peer.
+async fn handle_incoming_manifest(
+	peer: PeerId,
+	peers: &mut HashMap<PeerId, PeerData>,
+	active_heads: &mut HashMap<Hash, ActiveHeadData>,
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	req_sender: &mpsc::Sender<RequesterMessage>,
+	manifest: BackedCandidateManifest,
+) {
+	let relay_parent = manifest.relay_parent;
+	let candidate_hash = manifest.candidate_hash;
+
+	let (peer_data, active_head) = match (peers.get_mut(&peer), active_heads.get_mut(&relay_parent)) {
+		(Some(peer_data), Some(active_head)) => (peer_data, active_head),
+		(Some(_), None) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				%relay_parent,
+				"our view out-of-sync with active heads; head not found",
+			);
+			report_peer(ctx, peer, COST_UNEXPECTED_MANIFEST).await;
+			return
+		}
+		(None, _) => return,
+	};
+
+	if let Err(rep) = active_head.check_manifest(&manifest) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?peer,
+			?manifest,
+			"Invalid backed candidate manifest",
+		);
+		report_peer(ctx, peer, rep).await;
+		return
+	}
+
+	let max_manifest_count = active_head.validators.len() * VC_THRESHOLD;
+	if let Err(rep) = peer_data.receive_manifest(&relay_parent, candidate_hash, max_manifest_count) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?peer,
+			?manifest,
+			?rep,
+			"Unexpected backed candidate manifest",
+		);
+		report_peer(ctx, peer, rep).await;
+		return
+	}
+
+	if active_head.backed_candidates.contains_key(&candidate_hash) ||
+		active_head.statement_requests.contains_key(&candidate_hash)
+	{
+		return
+	}
+
+	request_backed_statements(
+		peers,
+		active_head,
+		ctx,
+		req_sender,
+		relay_parent,
+		candidate_hash,
+		HashSet::new(),
+	).await;
+}
+
+/// Request all statements about a backed candidate from a peer which announced the candidate to
+/// us and which was not asked before.
+///
+/// If the statements don't arrive within `BACKED_STATEMENTS_TIMEOUT` or the peer disconnects, the
+/// next peer is tried. Once no such peer is left, the request is dropped, so the next manifest
+/// about the candidate triggers a new one.
+async fn request_backed_statements(
+	peers: &HashMap<PeerId, PeerData>,
+	active_head: &mut ActiveHeadData,
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	req_sender: &mpsc::Sender<RequesterMessage>,
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	mut tried_peers: HashSet<PeerId>,
+) {
+	let next = peers.iter().find(|(peer, peer_data)|
+		!tried_peers.contains(*peer) && peer_data.announced_candidate(&relay_parent, &candidate_hash)
+	);
+
+	let (peer, version) = match next {
+		Some((peer, peer_data)) => (*peer, peer_data.version),
+		None => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?candidate_hash,
+				"No peer left to request statements about backed candidate from",
+			);
+			active_head.statement_requests.remove(&candidate_hash);
+			return
+		}
+	};
+
+	tried_peers.insert(peer);
+	active_head.statement_requests.insert(candidate_hash, StatementsRequest { peer, tried_peers });
+
+	tracing::trace!(
+		target: LOG_TARGET,
+		?peer,
+		?relay_parent,
+		?candidate_hash,
+		"Requesting statements about backed candidate",
+	);
+	send_to_peer(
+		ctx,
+		peer,
+		version,
+		protocol_v2::StatementDistributionMessage::RequestBackedStatements(relay_parent, candidate_hash),
+	).await;
+
+	let timeout = backed_statements_timeout(relay_parent, candidate_hash, peer, req_sender.clone());
+	if let Err(err) = ctx.spawn("backed-statements-timeout", timeout.boxed()) {
+		tracing::error!(target: LOG_TARGET, ?err, "Spawning task failed.");
+	}
+}
+
+/// Request the statements about a backed candidate from the next peer, as `failed_peer` did not
+/// provide them.
+///
+/// Does nothing if the request was answered in the meantime or is not with `failed_peer`.
+async fn retry_backed_statements(
+	peers: &HashMap<PeerId, PeerData>,
+	active_head: &mut ActiveHeadData,
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	req_sender: &mpsc::Sender<RequesterMessage>,
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	failed_peer: &PeerId,
+) {
+	let tried_peers = match active_head.statement_requests.get(&candidate_hash) {
+		Some(request) if request.peer == *failed_peer => request.tried_peers.clone(),
+		_ => return,
+	};
+
+	tracing::debug!(
+		target: LOG_TARGET,
+		peer = ?failed_peer,
+		?relay_parent,
+		?candidate_hash,
+		"Peer did not provide statements about backed candidate",
+	);
+	request_backed_statements(
+		peers,
+		active_head,
+		ctx,
+		req_sender,
+		relay_parent,
+		candidate_hash,
+		tried_peers,
+	).await;
+}
+
+/// Answer a peer's request for all statements about a candidate we announced to it.
+async fn handle_statements_request(
+	peer: PeerId,
+	peers: &mut HashMap<PeerId, PeerData>,
+	active_heads: &HashMap<Hash, ActiveHeadData>,
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	metrics: &Metrics,
+) {
+	let (peer_data, active_head) = match (peers.get_mut(&peer), active_heads.get(&relay_parent)) {
+		(Some(peer_data), Some(active_head)) => (peer_data, active_head),
+		(Some(_), None) => {
+			report_peer(ctx, peer, COST_UNEXPECTED_STATEMENTS_REQUEST).await;
+			return
+		}
+		(None, _) => return,
+	};
+
+	if let Err(rep) = peer_data.receive_statements_request(&relay_parent, candidate_hash) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?peer,
+			?relay_parent,
+			?candidate_hash,
+			?rep,
+			"Unexpected request for statements",
+		);
+		report_peer(ctx, peer, rep).await;
+		return
 	}
+
+	send_statements_about(
+		peer,
+		peer_data,
+		ctx,
+		relay_parent,
+		candidate_hash,
+		active_head,
+		metrics,
+	).await;
 }
 
 // Handle a statement. Returns a reference to a newly-stored statement
@@ -1316,7 +2060,7 @@ async fn handle_incoming_message<'a>(
 	peer_data: &mut PeerData,
 	active_heads: &'a mut HashMap<Hash, ActiveHeadData>,
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
-	message: protocol_v1::StatementDistributionMessage,
+	message: protocol_v2::StatementDistributionMessage,
 	req_sender: &mpsc::Sender<RequesterMessage>,
 	metrics: &Metrics,
 ) -> Option<(Hash, StoredStatement<'a>)> {
@@ -1335,7 +2079,7 @@ async fn handle_incoming_message<'a>(
 		}
 	};
 
-	if let protocol_v1::StatementDistributionMessage::LargeStatement(_) = message {
+	if let protocol_v2::StatementDistributionMessage::LargeStatement(_) = message {
 		if let Err(rep) = peer_data.receive_large_statement(&relay_parent) {
 			tracing::debug!(
 				target: LOG_TARGET,
@@ -1349,7 +2093,10 @@ async fn handle_incoming_message<'a>(
 		}
 	}
 
-	let fingerprint = message.get_fingerprint();
+	let fingerprint = match message.get_fingerprint() {
+		Some(fingerprint) => fingerprint,
+		None => return None,
+	};
 	let candidate_hash = fingerprint.0.candidate_hash().clone();
 	let handle_incoming_span = active_head.span.child("handle-incoming")
 		.with_candidate(candidate_hash)
@@ -1497,6 +2244,15 @@ async fn update_peer_view_and_maybe_send_unlocked(
 				active_head,
 				metrics,
 			).await;
+			if is_gossip_peer {
+				send_manifests(
+					peer.clone(),
+					peer_data,
+					ctx,
+					new,
+					active_head,
+				).await;
+			}
 		}
 	}
 }
@@ -1508,21 +2264,23 @@ async fn handle_network_update(
 	active_heads: &mut HashMap<Hash, ActiveHeadData>,
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
 	req_sender: &mpsc::Sender<RequesterMessage>,
-	update: NetworkBridgeEvent<protocol_v1::StatementDistributionMessage>,
+	update: NetworkBridgeEvent<net_protocol::StatementDistributionMessage>,
 	metrics: &Metrics,
 ) {
 	match update {
-		NetworkBridgeEvent::PeerConnected(peer, role, maybe_authority) => {
+		NetworkBridgeEvent::PeerConnected(peer, role, version, maybe_authority) => {
 			tracing::trace!(
 				target: LOG_TARGET,
 				?peer,
 				?role,
+				?version,
 				"Peer connected",
 			);
 			peers.insert(peer, PeerData {
 				view: Default::default(),
 				view_knowledge: Default::default(),
 				maybe_authority: maybe_authority.clone(),
+				version,
 			});
 			if let Some(authority) = maybe_authority {
 				authorities.insert(authority, peer);
@@ -1537,6 +2295,26 @@ async fn handle_network_update(
 			if let Some(auth_id) = peers.remove(&peer).and_then(|p| p.maybe_authority) {
 				authorities.remove(&auth_id);
 			}
+
+			// Statements we requested from the peer are not going to arrive:
+			for (relay_parent, active_head) in active_heads.iter_mut() {
+				let failed_requests: Vec<CandidateHash> = active_head.statement_requests.iter()
+					.filter(|(_, request)| request.peer == peer)
+					.map(|(candidate_hash, _)| *candidate_hash)
+					.collect();
+
+				for candidate_hash in failed_requests {
+					retry_backed_statements(
+						peers,
+						active_head,
+						ctx,
+						req_sender,
+						*relay_parent,
+						candidate_hash,
+						&peer,
+					).await;
+				}
+			}
 		}
 		NetworkBridgeEvent::NewGossipTopology(new_peers) => {
 			let newly_added: Vec<PeerId> = new_peers.difference(gossip_peers).cloned().collect();
@@ -1563,7 +2341,10 @@ async fn handle_network_update(
 				peers,
 				active_heads,
 				ctx,
-				message,
+				match message {
+					Versioned::V1(message) => message.into(),
+					Versioned::V2(message) => message,
+				},
 				req_sender,
 				metrics,
 			).await;
@@ -1772,6 +2553,25 @@ impl StatementDistribution {
 					}
 				}
 			}
+			RequesterMessage::BackedStatementsTimeout {
+				relay_parent,
+				candidate_hash,
+				peer,
+			} => {
+				// The head might have been deactivated in the meantime, then we are no longer
+				// interested in the statements.
+				if let Some(active_head) = active_heads.get_mut(&relay_parent) {
+					retry_backed_statements(
+						peers,
+						active_head,
+						ctx,
+						req_sender,
+						relay_parent,
+						candidate_hash,
+						&peer,
+					).await;
+				}
+			}
 			RequesterMessage::SendRequest(req) => {
 				ctx.send_message(
 					AllMessages::NetworkBridge(
@@ -1861,7 +2661,15 @@ impl StatementDistribution {
 					let session_info = &info.session_info;
 
 					active_heads.entry(relay_parent)
-						.or_insert(ActiveHeadData::new(session_info.validators.clone(), session_index, span));
+						.or_insert_with(|| ActiveHeadData::new(
+							session_info.validators.clone(),
+							session_index,
+							span,
+						).with_groups(
+							&session_info.discovery_keys,
+							session_info.validator_groups.clone(),
+							info.validator_info.our_group,
+						));
 
 					active_heads.retain(|h, _| {
 						let live = !deactivated.contains(h);
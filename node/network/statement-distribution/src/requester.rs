@@ -17,6 +17,7 @@
 use std::time::Duration;
 
 use futures::{SinkExt, channel::{mpsc, oneshot}};
+use futures_timer::Delay;
 
 use polkadot_node_network_protocol::{
 	PeerId, UnifiedReputationChange,
@@ -37,6 +38,10 @@ use crate::{LOG_TARGET, Metrics, COST_WRONG_HASH};
 // wait before retrying peers that already failed.
 const RETRY_TIMEOUT: Duration = Duration::from_millis(500);
 
+/// How long to wait for the statements about a backed candidate, after requesting them from a
+/// peer which announced the candidate via a manifest, before asking the next peer.
+pub const BACKED_STATEMENTS_TIMEOUT: Duration = Duration::from_millis(500);
+
 /// Messages coming from a background task.
 pub enum RequesterMessage {
 	/// Get an update of available peers to try for fetching a given statement.
@@ -63,6 +68,15 @@ pub enum RequesterMessage {
 	ReportPeer(PeerId, UnifiedReputationChange),
 	/// Ask subsystem to send a request for us.
 	SendRequest(Requests),
+	/// Statements about a backed candidate we requested from a peer did not arrive in time.
+	BackedStatementsTimeout {
+		/// Relay parent the candidate was backed under.
+		relay_parent: Hash,
+		/// The candidate we requested the statements about.
+		candidate_hash: CandidateHash,
+		/// The peer the statements were requested from.
+		peer: PeerId,
+	},
 }
 
 
@@ -196,6 +210,29 @@ pub async fn fetch(
 	}
 }
 
+/// Notify the subsystem once the statements about a backed candidate, requested from `peer`, can
+/// be considered overdue.
+///
+/// Whether they arrived in the meantime is checked by the subsystem.
+pub async fn backed_statements_timeout(
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	peer: PeerId,
+	mut sender: mpsc::Sender<RequesterMessage>,
+) {
+	Delay::new(BACKED_STATEMENTS_TIMEOUT).await;
+
+	if let Err(err) = sender.send(
+		RequesterMessage::BackedStatementsTimeout { relay_parent, candidate_hash, peer }
+	).await {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?err,
+			"Failed sending background task message, subsystem probably moved on."
+		);
+	}
+}
+
 /// Try getting new peers from subsystem.
 ///
 /// If there are non, we will return after a timeout with `None`.
@@ -29,7 +29,7 @@ use futures::executor::{self, block_on};
 use futures_timer::Delay;
 use sp_keystore::{CryptoStore, SyncCryptoStorePtr, SyncCryptoStore};
 use sc_keystore::LocalKeystore;
-use polkadot_node_network_protocol::{view, ObservedRole, request_response::Recipient};
+use polkadot_node_network_protocol::{view, ObservedRole, Versioned, request_response::Recipient};
 use polkadot_subsystem::{
 	jaeger, ActivatedLeaf, messages::{RuntimeApiMessage, RuntimeApiRequest}, LeafStatus,
 };
@@ -333,6 +333,150 @@ fn per_peer_relay_parent_knowledge_receive() {
 	);
 }
 
+#[test]
+fn per_peer_relay_parent_knowledge_manifests() {
+	let mut knowledge = PeerRelayParentKnowledge::default();
+
+	let hash_a = CandidateHash([1; 32].into());
+	let hash_b = CandidateHash([2; 32].into());
+	let hash_c = CandidateHash([3; 32].into());
+
+	// Requests are only accepted for candidates we announced.
+	assert_eq!(knowledge.receive_statements_request(hash_a), Err(COST_UNEXPECTED_STATEMENTS_REQUEST));
+
+	assert!(knowledge.can_send_manifest(&hash_a));
+	knowledge.sent_manifests.insert(hash_a);
+	assert!(!knowledge.can_send_manifest(&hash_a));
+
+	assert_eq!(knowledge.receive_statements_request(hash_a), Ok(()));
+	assert!(knowledge.requested_candidates.contains(&hash_a));
+	// But only once.
+	assert_eq!(knowledge.receive_statements_request(hash_a), Err(COST_UNEXPECTED_STATEMENTS_REQUEST));
+
+	// Received manifests are subject to flood protection.
+	assert_eq!(knowledge.receive_manifest(hash_b, 2), Ok(()));
+	assert!(!knowledge.can_send_manifest(&hash_b));
+	assert_eq!(knowledge.receive_manifest(hash_b, 2), Err(COST_DUPLICATE_MANIFEST));
+	assert_eq!(knowledge.receive_manifest(hash_c, 1), Err(COST_APPARENT_FLOOD));
+	assert_eq!(knowledge.receive_manifest(hash_c, 2), Ok(()));
+
+	// Peers aware of a candidate via statements don't need a manifest.
+	let hash_d = CandidateHash([4; 32].into());
+	assert!(knowledge.receive(&(CompactStatement::Seconded(hash_d), ValidatorIndex(0)), 3).unwrap());
+	assert!(!knowledge.can_send_manifest(&hash_d));
+}
+
+#[test]
+fn active_head_notes_backed_candidates() {
+	let validators = vec![
+		Sr25519Keyring::Alice.public().into(),
+		Sr25519Keyring::Bob.public().into(),
+		Sr25519Keyring::Charlie.public().into(),
+	];
+	let discovery_keys: Vec<AuthorityDiscoveryId> = vec![
+		Sr25519Keyring::Alice.public().into(),
+		Sr25519Keyring::Bob.public().into(),
+		Sr25519Keyring::Charlie.public().into(),
+	];
+	let parent_hash: Hash = [1; 32].into();
+
+	let session_index = 1;
+	let signing_context = SigningContext {
+		parent_hash,
+		session_index,
+	};
+
+	let candidate = {
+		let mut c = CommittedCandidateReceipt::default();
+		c.descriptor.relay_parent = parent_hash;
+		c.descriptor.para_id = 1.into();
+		c
+	};
+	let candidate_hash = candidate.hash();
+
+	// Alice and Bob back candidates, Charlie is on his own.
+	let mut head_data = ActiveHeadData::new(
+		validators,
+		session_index,
+		PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
+	).with_groups(
+		&discovery_keys,
+		vec![vec![ValidatorIndex(0), ValidatorIndex(1)], vec![ValidatorIndex(2)]],
+		None,
+	);
+
+	let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	let alice_public = SyncCryptoStore::sr25519_generate_new(
+		&*keystore, ValidatorId::ID, Some(&Sr25519Keyring::Alice.to_seed())
+	).unwrap();
+	let bob_public = SyncCryptoStore::sr25519_generate_new(
+		&*keystore, ValidatorId::ID, Some(&Sr25519Keyring::Bob.to_seed())
+	).unwrap();
+
+	let seconded = block_on(SignedFullStatement::sign(
+		&keystore,
+		Statement::Seconded(candidate),
+		&signing_context,
+		ValidatorIndex(0),
+		&alice_public.into(),
+	)).ok().flatten().expect("should be signed");
+	assert_matches!(head_data.note_statement(seconded), NotedStatement::Fresh(_));
+
+	// A single vote is no quorum for a group of two.
+	assert_eq!(head_data.note_maybe_backed(candidate_hash), None);
+
+	let valid = block_on(SignedFullStatement::sign(
+		&keystore,
+		Statement::Valid(candidate_hash),
+		&signing_context,
+		ValidatorIndex(1),
+		&bob_public.into(),
+	)).ok().flatten().expect("should be signed");
+	assert_matches!(head_data.note_statement(valid), NotedStatement::Fresh(_));
+
+	assert_eq!(head_data.note_maybe_backed(candidate_hash), Some(GroupIndex(0)));
+	// Only reported once.
+	assert_eq!(head_data.note_maybe_backed(candidate_hash), None);
+
+	let mut knowledge = head_data.statement_knowledge(candidate_hash, GroupIndex(0));
+	knowledge.sort();
+	assert_eq!(knowledge, vec![ValidatorIndex(0), ValidatorIndex(1)]);
+
+	// Manifests need to be consistent with the group.
+	let manifest = BackedCandidateManifest {
+		relay_parent: parent_hash,
+		candidate_hash,
+		group_index: GroupIndex(0),
+		statement_knowledge: knowledge,
+	};
+	assert_eq!(head_data.check_manifest(&manifest), Ok(()));
+	assert_eq!(
+		head_data.check_manifest(&BackedCandidateManifest {
+			statement_knowledge: vec![ValidatorIndex(0), ValidatorIndex(2)],
+			..manifest.clone()
+		}),
+		Err(COST_INVALID_MANIFEST),
+	);
+	assert_eq!(
+		head_data.check_manifest(&BackedCandidateManifest { group_index: GroupIndex(2), ..manifest }),
+		Err(COST_INVALID_MANIFEST),
+	);
+
+	// Charlie is only to learn about the candidate via the grid, unknown peers get statements.
+	let peer_data = |maybe_authority, version: ValidationVersion| PeerData {
+		view: Default::default(),
+		view_knowledge: Default::default(),
+		maybe_authority,
+		version: version.into(),
+	};
+	let charlie = Some(discovery_keys[2].clone());
+	assert!(head_data.is_grid_only(&peer_data(charlie.clone(), ValidationVersion::V2), Some(GroupIndex(0))));
+	assert!(!head_data.is_grid_only(&peer_data(Some(discovery_keys[1].clone()), ValidationVersion::V2), Some(GroupIndex(0))));
+	assert!(!head_data.is_grid_only(&peer_data(None, ValidationVersion::V2), Some(GroupIndex(0))));
+	// v1 has no manifests, so v1 peers keep receiving all statements.
+	assert!(!head_data.is_grid_only(&peer_data(charlie, ValidationVersion::V1), Some(GroupIndex(0))));
+}
+
 #[test]
 fn peer_view_update_sends_messages() {
 	let hash_a = Hash::repeat_byte(1);
@@ -433,6 +577,7 @@ fn peer_view_update_sends_messages() {
 			k
 		},
 		maybe_authority: None,
+		version: ValidationVersion::V1.into(),
 	};
 
 	let pool = sp_core::testing::TaskExecutor::new();
@@ -480,7 +625,8 @@ fn peer_view_update_sends_messages() {
 			let message = handle.recv().await;
 			let expected_to = vec![peer.clone()];
 			let expected_payload
-				= statement_message(hash_c, statement.statement.clone());
+				= validation_protocol(ValidationVersion::V1.into(), statement_message(hash_c, statement.statement.clone()))
+					.expect("Statements exist in v1");
 
 			assert_matches!(
 				message,
@@ -523,6 +669,7 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 		view: view.clone(),
 		view_knowledge: view.iter().map(|v| (v.clone(), Default::default())).collect(),
 		maybe_authority: None,
+		version: ValidationVersion::V1.into(),
 	};
 
 	let mut peer_data: HashMap<_, _> = vec![
@@ -576,6 +723,7 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 			hash_b,
 			statement,
 			Vec::new(),
+			&HashSet::new(),
 		).await;
 
 		{
@@ -611,7 +759,8 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 
 				assert_eq!(
 					payload,
-					statement_message(hash_b, statement.statement.clone()),
+					validation_protocol(ValidationVersion::V1.into(), statement_message(hash_b, statement.statement.clone()))
+						.expect("Statements exist in v1"),
 				);
 			}
 		)
@@ -687,13 +836,13 @@ fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 		// notify of peers and view
 		handle.send(FromOverseer::Communication {
 			msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-				NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, None)
+				NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None)
 			)
 		}).await;
 
 		handle.send(FromOverseer::Communication {
 			msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-				NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None)
+				NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None)
 			)
 		}).await;
 
@@ -735,7 +884,7 @@ fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 			msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
 				NetworkBridgeEvent::PeerMessage(
 					peer_a.clone(),
-					protocol_v1::StatementDistributionMessage::Statement(hash_a, statement.clone().into()),
+					Versioned::V1(protocol_v1::StatementDistributionMessage::Statement(hash_a, statement.clone().into())),
 				)
 			)
 		}).await;
@@ -759,9 +908,9 @@ fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 			AllMessages::NetworkBridge(
 				NetworkBridgeMessage::SendValidationMessage(
 					recipients,
-					protocol_v1::ValidationProtocol::StatementDistribution(
+					Versioned::V1(protocol_v1::ValidationProtocol::StatementDistribution(
 						protocol_v1::StatementDistributionMessage::Statement(r, s)
-					),
+					)),
 				)
 			) => {
 				assert_eq!(recipients, vec![peer_b.clone()]);
@@ -866,6 +1015,7 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 				NetworkBridgeEvent::PeerConnected(
 					peer_a.clone(),
 					ObservedRole::Full,
+					ValidationVersion::V1.into(),
 					Some(Sr25519Keyring::Alice.public().into())
 				)
 			)
@@ -876,6 +1026,7 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 				NetworkBridgeEvent::PeerConnected(
 					peer_b.clone(),
 					ObservedRole::Full,
+					ValidationVersion::V1.into(),
 					Some(Sr25519Keyring::Bob.public().into())
 				)
 			)
@@ -885,13 +1036,14 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 				NetworkBridgeEvent::PeerConnected(
 					peer_c.clone(),
 					ObservedRole::Full,
+					ValidationVersion::V1.into(),
 					Some(Sr25519Keyring::Charlie.public().into())
 				)
 			)
 		}).await;
 		handle.send(FromOverseer::Communication {
 			msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-				NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, None)
+				NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None)
 			)
 		}).await;
 
@@ -947,7 +1099,7 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 			msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
 				NetworkBridgeEvent::PeerMessage(
 					peer_a.clone(),
-					protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone()),
+					Versioned::V1(protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone())),
 				)
 			)
 		}).await;
@@ -981,7 +1133,7 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 			msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
 				NetworkBridgeEvent::PeerMessage(
 					peer_c.clone(),
-					protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone()),
+					Versioned::V1(protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone())),
 				)
 			)
 		}).await;
@@ -991,7 +1143,7 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 			msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
 				NetworkBridgeEvent::PeerMessage(
 					peer_bad.clone(),
-					protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone()),
+					Versioned::V1(protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone())),
 				)
 			)
 		}).await;
@@ -1151,9 +1303,9 @@ fn receiving_large_statement_from_one_sends_to_another_and_to_candidate_backing(
 			AllMessages::NetworkBridge(
 				NetworkBridgeMessage::SendValidationMessage(
 					mut recipients,
-					protocol_v1::ValidationProtocol::StatementDistribution(
+					Versioned::V1(protocol_v1::ValidationProtocol::StatementDistribution(
 						protocol_v1::StatementDistributionMessage::LargeStatement(meta)
-					),
+					)),
 				)
 			) => {
 				tracing::debug!(
@@ -1339,6 +1491,7 @@ fn share_prioritizes_backing_group() {
 					NetworkBridgeEvent::PeerConnected(
 						peer,
 						ObservedRole::Full,
+						ValidationVersion::V1.into(),
 						Some(pair.public().into()),
 					)
 				)
@@ -1357,6 +1510,7 @@ fn share_prioritizes_backing_group() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_a.clone(),
 					ObservedRole::Full,
+					ValidationVersion::V1.into(),
 					Some(Sr25519Keyring::Alice.public().into())
 				)
 			)
@@ -1366,6 +1520,7 @@ fn share_prioritizes_backing_group() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_b.clone(),
 					ObservedRole::Full,
+					ValidationVersion::V1.into(),
 					Some(Sr25519Keyring::Bob.public().into())
 				)
 			)
@@ -1375,13 +1530,14 @@ fn share_prioritizes_backing_group() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_c.clone(),
 					ObservedRole::Full,
+					ValidationVersion::V1.into(),
 					Some(Sr25519Keyring::Charlie.public().into())
 				)
 			)
 		}).await;
 		handle.send(FromOverseer::Communication {
 			msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
-				NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, None)
+				NetworkBridgeEvent::PeerConnected(peer_bad.clone(), ObservedRole::Full, ValidationVersion::V1.into(), None)
 			)
 		}).await;
 		handle.send(FromOverseer::Communication {
@@ -1389,6 +1545,7 @@ fn share_prioritizes_backing_group() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_other_group.clone(),
 					ObservedRole::Full,
+					ValidationVersion::V1.into(),
 					Some(Sr25519Keyring::Dave.public().into())
 				)
 			)
@@ -1457,9 +1614,9 @@ fn share_prioritizes_backing_group() {
 			AllMessages::NetworkBridge(
 				NetworkBridgeMessage::SendValidationMessage(
 					mut recipients,
-					protocol_v1::ValidationProtocol::StatementDistribution(
+					Versioned::V1(protocol_v1::ValidationProtocol::StatementDistribution(
 						protocol_v1::StatementDistributionMessage::LargeStatement(meta)
-					),
+					)),
 				)
 			) => {
 				tracing::debug!(
@@ -1594,6 +1751,7 @@ fn peer_cant_flood_with_large_statements() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_a.clone(),
 					ObservedRole::Full,
+					ValidationVersion::V1.into(),
 					Some(Sr25519Keyring::Alice.public().into())
 				)
 			)
@@ -1634,7 +1792,7 @@ fn peer_cant_flood_with_large_statements() {
 				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
 					NetworkBridgeEvent::PeerMessage(
 						peer_a.clone(),
-						protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone()),
+						Versioned::V1(protocol_v1::StatementDistributionMessage::LargeStatement(metadata.clone())),
 					)
 				)
 			}).await;
@@ -1685,6 +1843,144 @@ fn peer_cant_flood_with_large_statements() {
 	executor::block_on(future::join(test_fut, bg));
 }
 
+#[test]
+fn backed_statements_are_requested_from_next_peer_if_first_does_not_answer() {
+	let hash_a = Hash::repeat_byte(1);
+	let candidate_hash = CandidateHash(Hash::repeat_byte(42));
+
+	let peer_a = PeerId::random(); // Alice
+	let peer_b = PeerId::random(); // Bob
+
+	let validators = vec![
+		Sr25519Keyring::Alice.pair(),
+		Sr25519Keyring::Bob.pair(),
+		Sr25519Keyring::Charlie.pair(),
+		// We:
+		Sr25519Keyring::Ferdie.pair(),
+	];
+
+	// Alice and Bob back the candidate, we are in the other group.
+	let session_info = make_session_info(
+		validators,
+		vec![vec![0, 1], vec![2, 3]],
+	);
+
+	let session_index = 1;
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (ctx, mut handle) = polkadot_node_subsystem_test_helpers::make_subsystem_context(pool);
+
+	let bg = async move {
+		let s = StatementDistribution { metrics: Default::default(), keystore: make_ferdie_keystore()};
+		s.run(ctx).await.unwrap();
+	};
+
+	let test_fut = async move {
+		// register our active heads.
+		handle.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+			activated: vec![ActivatedLeaf {
+				hash: hash_a,
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			}].into(),
+			deactivated: vec![].into(),
+		}))).await;
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionIndexForChild(tx))
+			)
+				if r == hash_a
+			=> {
+				let _ = tx.send(Ok(session_index));
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(
+				RuntimeApiMessage::Request(r, RuntimeApiRequest::SessionInfo(sess_index, tx))
+			)
+				if r == hash_a && sess_index == session_index
+			=> {
+				let _ = tx.send(Ok(Some(session_info)));
+			}
+		);
+
+		// notify of peers and view
+		for (peer, key) in vec![(peer_a, Sr25519Keyring::Alice), (peer_b, Sr25519Keyring::Bob)] {
+			handle.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerConnected(
+						peer,
+						ObservedRole::Full,
+						ValidationVersion::V2.into(),
+						Some(key.public().into()),
+					)
+				)
+			}).await;
+
+			handle.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerViewChange(peer, view![hash_a])
+				)
+			}).await;
+		}
+
+		let manifest = protocol_v2::StatementDistributionMessage::BackedCandidateManifest(
+			BackedCandidateManifest {
+				relay_parent: hash_a,
+				candidate_hash,
+				group_index: GroupIndex(0),
+				statement_knowledge: vec![ValidatorIndex(0), ValidatorIndex(1)],
+			}
+		);
+
+		// Both backers announce the candidate, statements are only requested from the first one.
+		for peer in vec![peer_a, peer_b] {
+			handle.send(FromOverseer::Communication {
+				msg: StatementDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerMessage(peer, Versioned::V2(manifest.clone()))
+				)
+			}).await;
+		}
+
+		let expected_request = Versioned::V2(protocol_v2::ValidationProtocol::StatementDistribution(
+			protocol_v2::StatementDistributionMessage::RequestBackedStatements(hash_a, candidate_hash)
+		));
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridge(
+				NetworkBridgeMessage::SendValidationMessage(recipients, message)
+			) => {
+				assert_eq!(recipients, vec![peer_a]);
+				assert_eq!(message, expected_request);
+			}
+		);
+
+		// Alice never answers, so after the timeout Bob is asked instead.
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridge(
+				NetworkBridgeMessage::SendValidationMessage(recipients, message)
+			) => {
+				assert_eq!(recipients, vec![peer_b]);
+				assert_eq!(message, expected_request);
+			}
+		);
+
+		handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(bg);
+
+	executor::block_on(future::join(test_fut, bg));
+}
+
 fn make_session_info(validators: Vec<Pair>, groups: Vec<Vec<u32>>) -> SessionInfo {
 
 	let validator_groups: Vec<Vec<ValidatorIndex>> = groups
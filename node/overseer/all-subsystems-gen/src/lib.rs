@@ -102,11 +102,12 @@ fn impl_subsystems_gen(item: TokenStream) -> Result<proc_macro2::TokenStream> {
 			let msg = "Generated by #[derive(AllSubsystemsGen)] derive proc-macro.";
 			let mut additive = TokenStream::new();
 
-			// generate an impl of `fn replace_#name`
+			// generate an impl of `fn replace_#name` and `fn map_#name`
 			for NameTyTup { field: replacable_item, ty: replacable_item_ty } in replacable_items {
-				let keeper = all_fields.iter().filter(|ntt| ntt.field != replacable_item).map(|ntt| ntt.field.clone());
+				let keeper = all_fields.iter().filter(|ntt| ntt.field != replacable_item).map(|ntt| ntt.field.clone()).collect::<Vec<_>>();
 				let strukt_ty = strukt_ty.clone();
 				let fname = Ident::new(&format!("replace_{}", replacable_item), span);
+				let map_fname = Ident::new(&format!("map_{}", replacable_item), span);
 				// adjust the generics such that the appropriate member type is replaced
 				let mut modified_generics = orig_generics.clone();
 				modified_generics.params = modified_generics.params.into_iter().map(|mut generic| {
@@ -138,6 +139,19 @@ fn impl_subsystems_gen(item: TokenStream) -> Result<proc_macro2::TokenStream> {
 								)*
 							}
 						}
+
+						#[doc = #msg]
+						pub fn #map_fname < NEW, F > (self, mapper: F) -> #strukt_ty #modified_generics
+						where
+							F: FnOnce(#replacable_item_ty) -> NEW,
+						{
+							#strukt_ty :: #modified_generics {
+								#replacable_item: mapper(self.#replacable_item),
+								#(
+									#keeper: self.#keeper,
+								)*
+							}
+						}
 					}
 				});
 			}
@@ -13,5 +13,6 @@ fn main() {
 		a: 0u8,
 		b: 1u16,
 	};
-	let _all: AllSubsystems<_,_> = all.replace_a::<u32>(777_777u32);
+	let all: AllSubsystems<_,_> = all.replace_a::<u32>(777_777u32);
+	let _all: AllSubsystems<_,_> = all.map_b(|b| u64::from(b) + 1);
 }
@@ -14,7 +14,7 @@ impl ::polkadot_overseer_gen::Subsystem<XxxSubsystemContext<MsgStrukt>, Yikes> f
 	}
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct GoblinTower;
 
 impl ::polkadot_overseer_gen::Subsystem<XxxSubsystemContext<Plinko>, Yikes> for GoblinTower {
@@ -97,10 +97,10 @@ impl NetworkMsg {
 
 #[overlord(signal=SigSigSig, event=EvX, error=Yikes, network=NetworkMsg, gen=AllMessages)]
 struct Xxx {
-	#[subsystem(MsgStrukt)]
+	#[subsystem(MsgStrukt, sends: [Plinko])]
 	sub0: AwesomeSubSys,
 
-	#[subsystem(no_dispatch, blocking, Plinko)]
+	#[subsystem(no_dispatch, blocking, restart, Plinko)]
 	plinkos: GoblinTower,
 
 	i_like_pi: f64,
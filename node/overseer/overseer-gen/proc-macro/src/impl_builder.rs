@@ -33,12 +33,15 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 
 	let channel_name = &info.channel_names_without_wip("");
 	let channel_name_unbounded = &info.channel_names_without_wip("_unbounded");
+	let channel_name_priority = &info.channel_names_without_wip("_priority");
 
 	let channel_name_tx = &info.channel_names_without_wip("_tx");
 	let channel_name_unbounded_tx = &info.channel_names_without_wip("_unbounded_tx");
+	let channel_name_priority_tx = &info.channel_names_without_wip("_priority_tx");
 
 	let channel_name_rx = &info.channel_names_without_wip("_rx");
 	let channel_name_unbounded_rx = &info.channel_names_without_wip("_unbounded_rx");
+	let channel_name_priority_rx = &info.channel_names_without_wip("_priority_rx");
 
 	let baggage_generic_ty = &info.baggage_generic_types();
 	let baggage_name = &info.baggage_names();
@@ -60,6 +63,22 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		})
 		.collect::<Vec<_>>();
 
+	let subsystem_variant = &info.variant_names_without_wip();
+
+	// restartable subsystems are started once more from a clone of the
+	// one passed to the builder
+	let restartable_bounds = &info
+		.subsystems_without_wip()
+		.iter()
+		.map(|ssf| {
+			if ssf.supervision == Supervision::Escalate {
+				quote! {}
+			} else {
+				quote! { + Clone + Send + 'static }
+			}
+		})
+		.collect::<Vec<_>>();
+
 	let generics = quote! {
 		< S, #( #baggage_generic_ty, )* >
 	};
@@ -89,10 +108,54 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		where
 			S: #support_crate ::SpawnNamed,
 		#(
-			#builder_generic_ty : Subsystem<#subsyste_ctx_name< #consumes >, #error_ty>,
+			#builder_generic_ty : Subsystem<#subsyste_ctx_name< #consumes >, #error_ty> #restartable_bounds,
 		)*
 	};
 
+	let supervisor = &info
+		.subsystems_without_wip()
+		.iter()
+		.map(|ssf| {
+			let name = &ssf.name;
+			let generic = &ssf.generic;
+			let consumes = &ssf.consumes;
+			let blocking = ssf.blocking;
+			let strategy = match ssf.supervision {
+				Supervision::Escalate => return quote! {
+					{
+						let _ = reclaim;
+						None
+					}
+				},
+				Supervision::Restart => quote! { #support_crate ::SupervisionStrategy::Restart },
+				Supervision::RestartWithReset => quote! { #support_crate ::SupervisionStrategy::RestartWithReset },
+			};
+			quote! {
+				{
+					let prototype = #name .clone();
+					Some(SubsystemSupervisor {
+						strategy: #strategy,
+						blocking: #blocking,
+						start: Box::new(move |ctx: #subsyste_ctx_name< #consumes >| {
+							<#generic as Subsystem<#subsyste_ctx_name< #consumes >, #error_ty>>::start(
+								prototype.clone(),
+								ctx,
+							)
+						}),
+						channels_out: channels_out.clone(),
+						to_overseer: to_overseer_tx.clone(),
+						backoff: #support_crate ::RestartBackoff::new(::std::time::Instant::now()),
+						reclaim: Some(reclaim),
+						down: false,
+						buffered: ::std::collections::VecDeque::new(),
+						overflowed: false,
+						dropped: 0,
+					})
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+
 	let event = &info.extern_event_ty;
 
 	let mut ts = quote! {
@@ -195,6 +258,13 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 						>();
 				)*
 
+				#(
+					let (#channel_name_priority_tx, #channel_name_priority_rx) =
+						#support_crate ::metered::channel::<
+							MessagePacket< #consumes >
+						>(CHANNEL_CAPACITY);
+				)*
+
 				let channels_out =
 					ChannelsOut {
 						#(
@@ -203,12 +273,15 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 						#(
 							#channel_name_unbounded: #channel_name_unbounded_tx,
 						)*
+						#(
+							#channel_name_priority: #channel_name_priority_tx .clone(),
+						)*
 					};
 
 				let mut spawner = self.spawner.expect("Spawner is set. qed");
 
 				let mut running_subsystems = #support_crate ::FuturesUnordered::<
-						BoxFuture<'static, ::std::result::Result<(), #error_ty > >
+						BoxFuture<'static, #support_crate ::SubsystemExited<SubsystemId>>
 					>::new();
 
 				#(
@@ -218,27 +291,39 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 
 					let unbounded_meter = #channel_name_unbounded_rx.meter().clone();
 
-					let message_rx: SubsystemIncomingMessages< #consumes > = #support_crate ::select(
-						#channel_name_rx, #channel_name_unbounded_rx
+					let message_rx = SubsystemIncomingMessages::< #consumes >::new(
+						#channel_name_priority_rx, #channel_name_rx, #channel_name_unbounded_rx
 					);
 					let (signal_tx, signal_rx) = #support_crate ::metered::channel(SIGNAL_CHANNEL_CAPACITY);
+					let (signal_rx, reclaim_signals) = #support_crate ::Reclaimable::new(signal_rx);
+					let (message_rx, reclaim_messages) = #support_crate ::Reclaimable::new(message_rx);
+					let heartbeat = #support_crate ::SubsystemHeartbeat::default();
 					let ctx = #subsyste_ctx_name::< #consumes >::new(
 						signal_rx,
 						message_rx,
 						channels_out.clone(),
 						to_overseer_tx.clone(),
+						0,
+						heartbeat.clone(),
 					);
 
+					let reclaim = (reclaim_signals, reclaim_messages);
+					let supervisor: Option<SubsystemSupervisor< #consumes >> = #supervisor;
+
 					let #subsystem_name: OverseenSubsystem< #consumes > =
 						spawn::<_,_, #blocking, _, _, _>(
 							&mut spawner,
 							#channel_name_tx,
+							#channel_name_priority_tx,
 							signal_tx,
 							unbounded_meter,
+							heartbeat,
 							channels_out.clone(),
 							ctx,
 							#subsystem_name,
 							&mut running_subsystems,
+							SubsystemId:: #subsystem_variant,
+							supervisor,
 						)?;
 				)*
 
@@ -265,6 +350,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 
 					spawner,
 					running_subsystems,
+					pending_restarts: #support_crate ::FuturesUnordered::new(),
 					events_rx,
 					to_overseer_rx,
 				};
@@ -312,14 +398,20 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		pub fn spawn<S, M, TK, Ctx, E, SubSys>(
 			spawner: &mut S,
 			message_tx: #support_crate ::metered::MeteredSender<MessagePacket<M>>,
+			priority_tx: #support_crate ::metered::MeteredSender<MessagePacket<M>>,
 			signal_tx: #support_crate ::metered::MeteredSender< #signal >,
 			// meter for the unbounded channel
 			unbounded_meter: #support_crate ::metered::Meter,
+			// heartbeat, shared with the context
+			heartbeat: #support_crate ::SubsystemHeartbeat,
 			// connection to the subsystems
 			channels_out: ChannelsOut,
 			ctx: Ctx,
 			s: SubSys,
-			futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, ::std::result::Result<(), #error_ty> >>,
+			futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, #support_crate ::SubsystemExited<SubsystemId>>>,
+			// reported once the subsystem exits
+			subsystem: SubsystemId,
+			supervisor: ::std::option::Option<SubsystemSupervisor<M>>,
 		) -> ::std::result::Result<OverseenSubsystem<M>, #error_ty >
 		where
 			S: #support_crate ::SpawnNamed,
@@ -329,44 +421,73 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			E: std::error::Error + Send + Sync + 'static + From<#support_crate ::OverseerError>,
 			SubSys: #support_crate ::Subsystem<Ctx, E>,
 		{
-			let #support_crate ::SpawnedSubsystem::<E> { future, name } = s.start(ctx);
-
-			let (tx, rx) = #support_crate ::oneshot::channel();
-
-			let fut = Box::pin(async move {
-				if let Err(e) = future.await {
-					#support_crate ::tracing::error!(subsystem=name, err = ?e, "subsystem exited with error");
-				} else {
-					#support_crate ::tracing::debug!(subsystem=name, "subsystem exited without an error");
-				}
-				let _ = tx.send(());
-			});
-
-			<TK as TaskKind>::launch_task(spawner, name, fut);
-
-			futures.push(Box::pin(
-				rx.map(|e| {
-					tracing::warn!(err = ?e, "dropping error");
-					Ok(())
-				})
-			));
+			let name = launch::<_, TK, _>(spawner, s.start(ctx), subsystem, futures);
 
 			let instance = Some(SubsystemInstance {
 				meters: #support_crate ::SubsystemMeters {
 					unbounded: unbounded_meter,
 					bounded: message_tx.meter().clone(),
 					signals: signal_tx.meter().clone(),
+					priority: priority_tx.meter().clone(),
 				},
+				heartbeat,
 				tx_signal: signal_tx,
 				tx_bounded: message_tx,
+				tx_priority: priority_tx,
 				signals_received: 0,
 				name,
 			});
 
 			Ok(OverseenSubsystem {
 				instance,
+				supervisor,
 			})
 		}
+
+		/// Launch the task of a started subsystem, returns the name of the subsystem.
+		///
+		/// Once the task finishes, `subsystem` is reported via `futures`.
+		fn launch<S, TK, E>(
+			spawner: &mut S,
+			spawned: #support_crate ::SpawnedSubsystem<E>,
+			subsystem: SubsystemId,
+			futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, #support_crate ::SubsystemExited<SubsystemId>>>,
+		) -> &'static str
+		where
+			S: #support_crate ::SpawnNamed,
+			TK: TaskKind,
+			E: std::error::Error + Send + Sync + 'static + From<#support_crate ::OverseerError>,
+		{
+			let #support_crate ::SpawnedSubsystem::<E> { future, name } = spawned;
+
+			let (tx, rx) = #support_crate ::oneshot::channel();
+
+			let fut = Box::pin(async move {
+				let failed = if let Err(e) = future.await {
+					#support_crate ::tracing::error!(subsystem=name, err = ?e, "subsystem exited with error");
+					true
+				} else {
+					#support_crate ::tracing::debug!(subsystem=name, "subsystem exited without an error");
+					false
+				};
+				let _ = tx.send(failed);
+			});
+
+			<TK as TaskKind>::launch_task(spawner, name, fut);
+
+			futures.push(Box::pin(
+				rx.map(move |failed| {
+					// the task was dropped without finishing, most likely it panicked
+					let failed = failed.unwrap_or_else(|_| {
+						#support_crate ::tracing::error!(subsystem=name, "subsystem task was dropped");
+						true
+					});
+					#support_crate ::SubsystemExited { subsystem, failed }
+				})
+			));
+
+			name
+		}
 	};
 
 	ts
@@ -25,6 +25,8 @@ pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro
 
 	let channel_name = &info.channel_names_without_wip("");
 	let channel_name_unbounded = &info.channel_names_without_wip("_unbounded");
+	let channel_name_priority = &info.channel_names_without_wip("_priority");
+	let is_priority = &info.priority_predicates_without_wip();
 
 	let consumes = &info.consumes_without_wip();
 
@@ -54,6 +56,14 @@ pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro
 						MessagePacket< #consumes >
 					>,
 			)*
+
+			#(
+				/// Bounded channel sender for priority messages, connected to a subsystem.
+				pub #channel_name_priority:
+					#support_crate ::metered::MeteredSender<
+						MessagePacket< #consumes >
+					>,
+			)*
 		}
 
 		impl ChannelsOut {
@@ -66,7 +76,12 @@ pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro
 				let res: ::std::result::Result<_, _> = match message {
 				#(
 					#message_wrapper :: #consumes_variant ( inner ) => {
-						self. #channel_name .send(
+						let channel = if #is_priority {
+							&mut self. #channel_name_priority
+						} else {
+							&mut self. #channel_name
+						};
+						channel.send(
 							#support_crate ::make_packet(signals_received, inner)
 						).await.map_err(|_| stringify!( #channel_name ))
 					}
@@ -90,19 +105,29 @@ pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro
 
 			/// Send a message to another subsystem via an unbounded channel.
 			pub fn send_unbounded_and_log_error(
-				&self,
+				&mut self,
 				signals_received: usize,
 				message: #message_wrapper,
 			) {
-				use ::std::sync::mpsc::TrySendError;
-
 				let res: ::std::result::Result<_, _> = match message {
 				#(
 					#message_wrapper :: #consumes_variant (inner) => {
-						self. #channel_name_unbounded .unbounded_send(
-							#support_crate ::make_packet(signals_received, inner)
-						)
-						.map_err(|_| stringify!( #channel_name ))
+						let packet = #support_crate ::make_packet(signals_received, inner);
+						// Priority messages only fall back to the unbounded channel
+						// if the priority channel is full. The sender is not cloned,
+						// a clone would bring a free slot of its own.
+						if #is_priority {
+							match self. #channel_name_priority .try_send(packet) {
+								Ok(()) => Ok(()),
+								Err(e) if e.is_full() => self. #channel_name_unbounded
+									.unbounded_send(e.into_inner())
+									.map_err(|_| stringify!( #channel_name )),
+								Err(_) => Err(stringify!( #channel_name )),
+							}
+						} else {
+							self. #channel_name_unbounded .unbounded_send(packet)
+								.map_err(|_| stringify!( #channel_name ))
+						}
 					},
 				)*
 					// subsystems that are wip
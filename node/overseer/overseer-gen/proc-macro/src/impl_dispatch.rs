@@ -47,7 +47,8 @@ pub(crate) fn impl_dispatch(info: &OverseerInfo) -> TokenStream {
 					::std::array::IntoIter::new([
 					#(
 						extern_msg
-							// focuses on a `NetworkBridgeEvent< protocol_v1::* >`
+							.clone()
+							// focuses on a `NetworkBridgeEvent< VersionedValidationProtocol >`
 							// TODO do not require this to be hardcoded, either externalize or ...
 							// https://github.com/paritytech/polkadot/issues/3427
 							.focus()
@@ -0,0 +1,119 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt::Write;
+
+use quote::quote;
+use syn::{Ident, LitStr, Path};
+
+use super::*;
+
+/// An edge of the subsystem graph, a subsystem sending a message type to its consumer.
+struct Edge<'a> {
+	from: &'a Ident,
+	to: &'a Ident,
+	message: &'a Path,
+}
+
+fn edges(info: &OverseerInfo) -> Vec<Edge<'_>> {
+	info.subsystems()
+		.iter()
+		.flat_map(|ssf| {
+			ssf.sends.iter().filter_map(move |message| {
+				info.consumer_of(message).map(|consumer| Edge { from: &ssf.name, to: &consumer.name, message })
+			})
+		})
+		.collect()
+}
+
+/// The last segment of a message type, used as edge label.
+fn message_label(message: &Path) -> String {
+	message.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default()
+}
+
+/// Render the subsystem graph in graphviz `dot` format.
+pub(crate) fn graphviz(info: &OverseerInfo) -> String {
+	let mut dot = String::new();
+	let _ = writeln!(dot, "digraph {} {{", info.overseer_name);
+	let _ = writeln!(dot, "\tnode [shape=box];");
+	for ssf in info.subsystems() {
+		if ssf.wip {
+			let _ = writeln!(dot, "\t{} [style=dashed];", ssf.name);
+		} else {
+			let _ = writeln!(dot, "\t{};", ssf.name);
+		}
+	}
+	for edge in edges(info) {
+		let _ = writeln!(dot, "\t{} -> {} [label=\"{}\"];", edge.from, edge.to, message_label(edge.message));
+	}
+	dot.push('}');
+	dot
+}
+
+/// Render the subsystem graph as mermaid flowchart.
+pub(crate) fn mermaid(info: &OverseerInfo) -> String {
+	let mut chart = String::new();
+	let _ = writeln!(chart, "flowchart LR");
+	for ssf in info.subsystems() {
+		if ssf.wip {
+			let _ = writeln!(chart, "\t{}:::wip", ssf.name);
+		} else {
+			let _ = writeln!(chart, "\t{}", ssf.name);
+		}
+	}
+	for edge in edges(info) {
+		let _ = writeln!(chart, "\t{} -- {} --> {}", edge.from, message_label(edge.message), edge.to);
+	}
+	chart.push_str("\tclassDef wip stroke-dasharray: 5 5");
+	chart
+}
+
+/// Implement the graph of messages sent between subsystems, as declared
+/// via `sends: [..]`, and the `SendsTo` bounds of the subsystem contexts.
+pub(crate) fn impl_subsystem_graph(info: &OverseerInfo) -> proc_macro2::TokenStream {
+	let overseer_name = &info.overseer_name;
+	let subsystem_ctx_name = Ident::new(&(overseer_name.to_string() + "SubsystemContext"), overseer_name.span());
+	let support_crate = info.support_crate_name();
+
+	let (sender_consumes, sends): (Vec<_>, Vec<_>) = info.subsystems()
+		.iter()
+		.flat_map(|ssf| ssf.sends.iter().map(move |message| (&ssf.consumes, message)))
+		.unzip();
+
+	let graphviz = graphviz(info);
+	let graphviz_doc = LitStr::new(&format!("```text\n{}\n```", graphviz), overseer_name.span());
+	let graphviz = LitStr::new(&graphviz, overseer_name.span());
+
+	let mermaid = mermaid(info);
+	let mermaid_doc = LitStr::new(&format!("```text\n{}\n```", mermaid), overseer_name.span());
+	let mermaid = LitStr::new(&mermaid, overseer_name.span());
+
+	quote! {
+		#(
+			impl #support_crate ::SendsTo< #sends > for #subsystem_ctx_name < #sender_consumes > {}
+		)*
+
+		/// The messages sent between subsystems, in graphviz `dot` format.
+		///
+		#[doc = #graphviz_doc]
+		pub const SUBSYSTEM_GRAPH_DOT: &'static str = #graphviz;
+
+		/// The messages sent between subsystems, as mermaid flowchart.
+		///
+		#[doc = #mermaid_doc]
+		pub const SUBSYSTEM_GRAPH_MERMAID: &'static str = #mermaid;
+	}
+}
@@ -31,21 +31,93 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 	let error_ty = &info.extern_error_ty;
 	let support_crate = info.support_crate_name();
 
+	// Per subsystem, the variants of the messages it must not send, since
+	// they are consumed by subsystems it does not declare to send to.
+	let undeclared = info.subsystems()
+		.iter()
+		.map(|ssf| {
+			let undeclared = info.subsystems()
+				.iter()
+				.filter(|receiver| {
+					let declared = ssf.sends.iter()
+						.filter_map(|message| info.consumer_of(message))
+						.any(|consumer| consumer.name == receiver.name);
+					!declared
+				})
+				.map(|receiver| &receiver.generic)
+				.collect::<Vec<_>>();
+			if undeclared.is_empty() {
+				quote! { true }
+			} else {
+				quote! { !matches!(msg, #( #wrapper_message :: #undeclared (..) )|* ) }
+			}
+		})
+		.collect::<Vec<_>>();
+
 	let ts = quote! {
 		/// Connector to send messages towards all subsystems,
 		/// while tracking the which signals where already received.
-		#[derive(Debug, Clone)]
-		pub struct #subsystem_sender_name {
+		///
+		/// Typed on the message type `M` of the sending subsystem, so only the
+		/// messages it declares via `sends: [..]` can be sent without wrapping.
+		#[derive(Debug)]
+		pub struct #subsystem_sender_name<M> {
 			/// Collection of channels to all subsystems.
 			channels: ChannelsOut,
 			/// Systemwide tick for which signals were received by all subsystems.
 			signals_received: SignalsReceived,
+			_phantom: ::std::marker::PhantomData<fn() -> M>,
+		}
+
+		impl<M> Clone for #subsystem_sender_name<M> {
+			fn clone(&self) -> Self {
+				Self {
+					channels: self.channels.clone(),
+					signals_received: self.signals_received.clone(),
+					_phantom: ::std::marker::PhantomData,
+				}
+			}
+		}
+
+		impl<M: 'static> #subsystem_sender_name<M> {
+			/// Whether the subsystem consuming `M` declares to send the given message
+			/// via `sends: [..]`.
+			///
+			/// Senders which are not part of the overseer are not restricted.
+			pub fn declares(msg: & #wrapper_message) -> bool {
+				let sender = ::std::any::TypeId::of::<M>();
+				#(
+					if sender == ::std::any::TypeId::of::< #consumes >() {
+						return #undeclared;
+					}
+				)*
+				true
+			}
+
+			/// Sending a message which is not declared is a bug, the message is
+			/// still delivered in release builds.
+			fn check_declared(msg: & #wrapper_message) {
+				if !Self::declares(msg) {
+					#support_crate ::tracing::error!(
+						sender = ::std::any::type_name::<M>(),
+						?msg,
+						"subsystem sent a message it does not declare to send",
+					);
+					if cfg!(debug_assertions) {
+						panic!(
+							"subsystem consuming {} sent a message it does not declare to send",
+							::std::any::type_name::<M>(),
+						);
+					}
+				}
+			}
 		}
 
 		/// implementation for wrapping message type...
 		#[#support_crate ::async_trait]
-		impl SubsystemSender< #wrapper_message > for #subsystem_sender_name {
+		impl<M: 'static> SubsystemSender< #wrapper_message > for #subsystem_sender_name<M> {
 			async fn send_message(&mut self, msg: #wrapper_message) {
+				Self::check_declared(&msg);
 				self.channels.send_and_log_error(self.signals_received.load(), msg).await;
 			}
 
@@ -61,16 +133,20 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			}
 
 			fn send_unbounded_message(&mut self, msg: #wrapper_message) {
+				Self::check_declared(&msg);
 				self.channels.send_unbounded_and_log_error(self.signals_received.load(), msg);
 			}
 		}
 
-		// ... but also implement for all individual messages to avoid
-		// the necessity for manual wrapping, and do the conversion
+		// ... but also implement for all individual messages the subsystem declares
+		// to send, to avoid the necessity for manual wrapping, and do the conversion
 		// based on the generated `From::from` impl for the individual variants.
 		#(
 		#[#support_crate ::async_trait]
-		impl SubsystemSender< #consumes > for #subsystem_sender_name {
+		impl<M: 'static> SubsystemSender< #consumes > for #subsystem_sender_name<M>
+		where
+			#subsystem_ctx_name<M>: #support_crate ::SendsTo< #consumes >,
+		{
 			async fn send_message(&mut self, msg: #consumes) {
 				self.channels.send_and_log_error(self.signals_received.load(), #wrapper_message ::from ( msg )).await;
 			}
@@ -102,59 +178,49 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		#[derive(Debug)]
 		#[allow(missing_docs)]
 		pub struct #subsystem_ctx_name<M>{
-			signals: #support_crate ::metered::MeteredReceiver< #signal >,
-			messages: SubsystemIncomingMessages<M>,
-			to_subsystems: #subsystem_sender_name,
+			signals: #support_crate ::Reclaimable<#support_crate ::metered::MeteredReceiver< #signal >>,
+			messages: #support_crate ::Reclaimable<SubsystemIncomingMessages<M>>,
+			to_subsystems: #subsystem_sender_name<M>,
 			to_overseer: #support_crate ::metered::UnboundedMeteredSender<
 				#support_crate ::ToOverseer
 				>,
 			signals_received: SignalsReceived,
 			pending_incoming: Option<(usize, M)>,
+			heartbeat: #support_crate ::SubsystemHeartbeat,
 		}
 
 		impl<M> #subsystem_ctx_name<M> {
 			/// Create a new context.
+			///
+			/// `signals_received` is the number of signals sent to the subsystem before,
+			/// which is non-zero for restarted subsystems.
 			fn new(
-				signals: #support_crate ::metered::MeteredReceiver< #signal >,
-				messages: SubsystemIncomingMessages<M>,
+				signals: #support_crate ::Reclaimable<#support_crate ::metered::MeteredReceiver< #signal >>,
+				messages: #support_crate ::Reclaimable<SubsystemIncomingMessages<M>>,
 				to_subsystems: ChannelsOut,
 				to_overseer: #support_crate ::metered::UnboundedMeteredSender<#support_crate:: ToOverseer>,
+				signals_received: usize,
+				heartbeat: #support_crate ::SubsystemHeartbeat,
 			) -> Self {
-				let signals_received = SignalsReceived::default();
+				let signals_received = SignalsReceived::new(signals_received);
 				#subsystem_ctx_name {
 					signals,
 					messages,
 					to_subsystems: #subsystem_sender_name {
 						channels: to_subsystems,
 						signals_received: signals_received.clone(),
+						_phantom: ::std::marker::PhantomData,
 					},
 					to_overseer,
 					signals_received,
 					pending_incoming: None,
+					heartbeat,
 				}
 			}
-		}
-
-		#[#support_crate ::async_trait]
-		impl<M: std::fmt::Debug + Send + 'static> #support_crate ::SubsystemContext for #subsystem_ctx_name<M>
-		where
-			#subsystem_sender_name: #support_crate ::SubsystemSender< #wrapper_message >,
-			#wrapper_message: From<M>,
-		{
-			type Message = M;
-			type Signal = #signal;
-			type Sender = #subsystem_sender_name;
-			type AllMessages = #wrapper_message;
-			type Error = #error_ty;
 
-			async fn try_recv(&mut self) -> ::std::result::Result<Option<FromOverseer<M, #signal>>, ()> {
-				match #support_crate ::poll!(self.recv()) {
-					#support_crate ::Poll::Ready(msg) => Ok(Some(msg.map_err(|_| ())?)),
-					#support_crate ::Poll::Pending => Ok(None),
-				}
-			}
-
-			async fn recv(&mut self) -> ::std::result::Result<FromOverseer<M, #signal>, #error_ty> {
+			/// Receive the next signal or message, holding back messages until the signals
+			/// they were sent after are received.
+			async fn recv_next(&mut self) -> ::std::result::Result<FromOverseer<M, #signal>, #error_ty> {
 				loop {
 					// If we have a message pending an overseer signal, we only poll for signals
 					// in the meantime.
@@ -217,6 +283,36 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					return Ok(from_overseer);
 				}
 			}
+		}
+
+		#[#support_crate ::async_trait]
+		impl<M: std::fmt::Debug + Send + 'static> #support_crate ::SubsystemContext for #subsystem_ctx_name<M>
+		where
+			#subsystem_sender_name<M>: #support_crate ::SubsystemSender< #wrapper_message >,
+			#wrapper_message: From<M>,
+		{
+			type Message = M;
+			type Signal = #signal;
+			type Sender = #subsystem_sender_name<M>;
+			type AllMessages = #wrapper_message;
+			type Error = #error_ty;
+
+			async fn try_recv(&mut self) -> ::std::result::Result<Option<FromOverseer<M, #signal>>, ()> {
+				match #support_crate ::poll!(self.recv()) {
+					#support_crate ::Poll::Ready(msg) => Ok(Some(msg.map_err(|_| ())?)),
+					#support_crate ::Poll::Pending => {
+						self.heartbeat.busy();
+						Ok(None)
+					}
+				}
+			}
+
+			async fn recv(&mut self) -> ::std::result::Result<FromOverseer<M, #signal>, #error_ty> {
+				self.heartbeat.waiting(self.signals.meter().read().received);
+				let from_overseer = self.recv_next().await;
+				self.heartbeat.busy();
+				from_overseer
+			}
 
 			fn sender(&mut self) -> &mut Self::Sender {
 				&mut self.to_subsystems
@@ -22,6 +22,10 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 	let message_wrapper = &info.message_wrapper.clone();
 	let overseer_name = info.overseer_name.clone();
 	let subsystem_name = &info.subsystem_names_without_wip();
+	let subsystem_name_str = &subsystem_name
+		.iter()
+		.map(|name| syn::LitStr::new(&name.to_string(), name.span()))
+		.collect::<Vec<_>>();
 	let support_crate = info.support_crate_name();
 
 	let baggage_decl = &info.baggage_decl();
@@ -42,6 +46,7 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 	let consumes = &info.consumes_without_wip();
 	let consumes_variant = &info.variant_names_without_wip();
 	let unconsumes_variant = &info.variant_names_only_wip();
+	let is_priority = &info.priority_predicates_without_wip();
 
 	let signal_ty = &info.extern_signal_ty;
 
@@ -85,7 +90,13 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 
 			/// The set of running subsystems.
 			running_subsystems: #support_crate ::FuturesUnordered<
-				BoxFuture<'static, ::std::result::Result<(), #error_ty>>
+				BoxFuture<'static, #support_crate ::SubsystemExited<SubsystemId>>
+			>,
+
+			/// Failed subsystems waiting for their restart, resolving once their
+			/// channels are reclaimed and the backoff is over.
+			pending_restarts: #support_crate ::FuturesUnordered<
+				BoxFuture<'static, PendingRestart>
 			>,
 
 			/// Gather running subsystems' outbound streams into one.
@@ -97,6 +108,57 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 			events_rx: #support_crate ::metered::MeteredReceiver< #event_ty >,
 		}
 
+		/// Identifies a subsystem of the overseer.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum SubsystemId {
+			#(
+				#[allow(missing_docs)]
+				#consumes_variant,
+			)*
+		}
+
+		impl SubsystemId {
+			/// All subsystems of the overseer.
+			pub const ALL: &'static [SubsystemId] = &[
+				#(
+					SubsystemId:: #consumes_variant,
+				)*
+			];
+
+			/// Name of the subsystem field of the overseer.
+			pub fn field_name(&self) -> &'static str {
+				match self {
+					#(
+						Self:: #consumes_variant => #subsystem_name_str,
+					)*
+				}
+			}
+		}
+
+		/// A failed subsystem waiting for its restart.
+		///
+		/// Carries the channels of the failed instance, `None` if they could not be reclaimed.
+		pub enum PendingRestart {
+			#(
+				#[allow(missing_docs)]
+				#consumes_variant(::std::option::Option<(
+					#support_crate ::metered::MeteredReceiver< #signal_ty >,
+					SubsystemIncomingMessages< #consumes >,
+				)>),
+			)*
+		}
+
+		impl PendingRestart {
+			/// The subsystem to be restarted.
+			pub fn subsystem(&self) -> SubsystemId {
+				match self {
+					#(
+						Self:: #consumes_variant(_) => SubsystemId:: #consumes_variant,
+					)*
+				}
+			}
+		}
+
 		impl #generics #overseer_name #generics #where_clause {
 			/// Send the given signal, a termination signal, to all subsystems
 			/// and wait for all subsystems to go down.
@@ -142,8 +204,10 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 			pub async fn route_message(&mut self, message: #message_wrapper, origin: &'static str) -> ::std::result::Result<(), #error_ty > {
 				match message {
 					#(
-						#message_wrapper :: #consumes_variant ( inner ) =>
-							OverseenSubsystem::< #consumes >::send_message2(&mut self. #subsystem_name, inner, origin ).await?,
+						#message_wrapper :: #consumes_variant ( inner ) => {
+							let priority = #is_priority;
+							OverseenSubsystem::< #consumes >::send_message2(&mut self. #subsystem_name, inner, priority, origin ).await?
+						}
 					)*
 					// subsystems that are still work in progress
 					#(
@@ -169,6 +233,53 @@ pub(crate) fn impl_overseer_struct(info: &OverseerInfo) -> proc_macro2::TokenStr
 				]
 			}
 
+			/// Apply the supervision strategy of a subsystem which finished.
+			///
+			/// Returns `true` if a restart of the subsystem is pending, `false` if
+			/// the overseer has to shut down.
+			pub fn supervise(&mut self, exited: #support_crate ::SubsystemExited<SubsystemId>) -> bool {
+				let pending = match exited.subsystem {
+					#(
+						SubsystemId:: #consumes_variant => self. #subsystem_name .on_exit(exited.failed)
+							.map(|reclaim| Box::pin(reclaim.map(PendingRestart:: #consumes_variant)) as BoxFuture<'static, _>),
+					)*
+				};
+
+				match pending {
+					Some(pending) => {
+						#support_crate ::tracing::warn!(
+							target: LOG_TARGET,
+							subsystem = exited.subsystem.field_name(),
+							"Restarting failed subsystem",
+						);
+						self.pending_restarts.push(pending);
+						true
+					}
+					None => false,
+				}
+			}
+
+			/// Restart a subsystem once its backoff is over.
+			///
+			/// `signal` is the first signal the new instance receives, it replaces
+			/// all signals which were not received by the failed instance.
+			///
+			/// Returns `false` if the channels of the failed instance could not be
+			/// reclaimed, the overseer has to shut down then.
+			pub async fn restart_subsystem(&mut self, pending: PendingRestart, signal: #signal_ty) -> ::std::result::Result<bool, #error_ty > {
+				match pending {
+					#(
+						PendingRestart:: #consumes_variant(receivers) => self. #subsystem_name .restart(
+							receivers,
+							&mut self.spawner,
+							&mut self.running_subsystems,
+							SubsystemId:: #consumes_variant,
+							signal,
+						).await,
+					)*
+				}
+			}
+
 			/// Get access to internal task spawner.
 			pub fn spawner<'a> (&'a mut self) -> &'a mut S {
 				&mut self.spawner
@@ -184,6 +295,8 @@ pub(crate) fn impl_overseen_subsystem(info: &OverseerInfo) -> proc_macro2::Token
 	let signal = &info.extern_signal_ty;
 	let error_ty = &info.extern_error_ty;
 	let support_crate = info.support_crate_name();
+	let overseer_name = &info.overseer_name;
+	let subsystem_ctx_name = syn::Ident::new(&(overseer_name.to_string() + "SubsystemContext"), overseer_name.span());
 
 	let ts = quote::quote! {
 
@@ -201,21 +314,235 @@ pub(crate) fn impl_overseen_subsystem(info: &OverseerInfo) -> proc_macro2::Token
 			pub instance: std::option::Option<
 				#support_crate ::SubsystemInstance<M, #signal>
 			>,
+			/// Restarts the subsystem on failure, `None` if failures are escalated.
+			pub supervisor: std::option::Option<SubsystemSupervisor<M>>,
+		}
+
+		/// Restarts a subsystem which returned an error or panicked.
+		pub struct SubsystemSupervisor<M> {
+			/// How failures of the subsystem are handled.
+			strategy: #support_crate ::SupervisionStrategy,
+			/// Whether the subsystem is spawned as a blocking task.
+			blocking: bool,
+			/// Start a new instance of the subsystem.
+			start: Box<dyn FnMut(#subsystem_ctx_name<M>) -> #support_crate ::SpawnedSubsystem<#error_ty> + Send>,
+			channels_out: ChannelsOut,
+			to_overseer: #support_crate ::metered::UnboundedMeteredSender<#support_crate ::ToOverseer>,
+			backoff: #support_crate ::RestartBackoff,
+			/// Receivers of the running instance, handed back once its context is dropped.
+			reclaim: std::option::Option<(
+				#support_crate ::oneshot::Receiver<#support_crate ::metered::MeteredReceiver< #signal >>,
+				#support_crate ::oneshot::Receiver<SubsystemIncomingMessages<M>>,
+			)>,
+			/// Whether the subsystem failed and waits for its restart.
+			down: bool,
+			/// Messages sent while the subsystem is down, along with their priority.
+			///
+			/// Holds at most `CHANNEL_CAPACITY` messages, further ones are dropped.
+			buffered: ::std::collections::VecDeque<(bool, MessagePacket<M>)>,
+			/// Whether messages were dropped since the subsystem went down.
+			overflowed: bool,
+			/// Number of messages dropped because the buffer was full.
+			dropped: u64,
+		}
+
+		impl<M> SubsystemSupervisor<M> {
+			/// How failures of the subsystem are handled.
+			pub fn strategy(&self) -> #support_crate ::SupervisionStrategy {
+				self.strategy
+			}
+
+			/// Whether the subsystem failed and waits for its restart.
+			pub fn is_down(&self) -> bool {
+				self.down
+			}
+
+			/// Number of messages dropped while the subsystem was down, because too many
+			/// were buffered for its restart.
+			pub fn dropped_messages(&self) -> u64 {
+				self.dropped
+			}
 		}
 
 		impl<M> OverseenSubsystem<M> {
+			fn is_down(&self) -> bool {
+				self.supervisor.as_ref().map_or(false, |supervisor| supervisor.is_down())
+			}
+
+			/// Take back the channels of a failed subsystem.
+			///
+			/// Returns a future resolving to the channels once the backoff is over, `None` if
+			/// the subsystem exited cleanly or is not restartable.
+			fn on_exit(&mut self, failed: bool) -> ::std::option::Option<BoxFuture<'static, ::std::option::Option<(
+				#support_crate ::metered::MeteredReceiver< #signal >,
+				SubsystemIncomingMessages<M>,
+			)>>>
+			where
+				M: Send + 'static,
+			{
+				let reclaim = self.reclaim_on_exit(failed);
+				if failed {
+					if let Some(ref instance) = self.instance {
+						instance.heartbeat.failed(reclaim.is_some());
+					}
+				}
+				reclaim
+			}
+
+			/// Take back the channels of a failed, restartable subsystem.
+			///
+			/// The channels are handed back once the context of the failed instance is dropped,
+			/// which is not awaited here, such that other subsystems are not held up.
+			fn reclaim_on_exit(&mut self, failed: bool) -> ::std::option::Option<BoxFuture<'static, ::std::option::Option<(
+				#support_crate ::metered::MeteredReceiver< #signal >,
+				SubsystemIncomingMessages<M>,
+			)>>>
+			where
+				M: Send + 'static,
+			{
+				const RECLAIM_TIMEOUT: Duration = Duration::from_secs(1);
+
+				let supervisor = match self.supervisor {
+					Some(ref mut supervisor) if failed => supervisor,
+					_ => return None,
+				};
+				let (signals, messages) = supervisor.reclaim.take()?;
+				let delay = supervisor.backoff.next_delay(::std::time::Instant::now());
+				supervisor.down = true;
+
+				Some(Box::pin(async move {
+					match #support_crate ::futures::future::join(signals, messages).timeout(RECLAIM_TIMEOUT).await {
+						Some((Ok(signals), Ok(messages))) => {
+							#support_crate ::Delay::new(delay).await;
+							Some((signals, messages))
+						}
+						_ => {
+							// the context is still alive, e.g. moved into a task which is still running
+							#support_crate ::tracing::error!(
+								target: LOG_TARGET,
+								"Channels of a failed subsystem could not be reclaimed",
+							);
+							None
+						}
+					}
+				}))
+			}
+
+			/// Start a new instance of a failed subsystem from the reclaimed `receivers`.
+			///
+			/// Returns `false` if there is nothing to restart from.
+			async fn restart<S>(
+				&mut self,
+				receivers: ::std::option::Option<(
+					#support_crate ::metered::MeteredReceiver< #signal >,
+					SubsystemIncomingMessages<M>,
+				)>,
+				spawner: &mut S,
+				futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, #support_crate ::SubsystemExited<SubsystemId>>>,
+				subsystem: SubsystemId,
+				signal: #signal,
+			) -> ::std::result::Result<bool, #error_ty >
+			where
+				S: #support_crate ::SpawnNamed,
+			{
+				let (supervisor, instance) = match (self.supervisor.as_mut(), self.instance.as_mut()) {
+					(Some(supervisor), Some(instance)) => (supervisor, instance),
+					_ => return Ok(false),
+				};
+				let (mut signals, mut messages) = match receivers {
+					Some(receivers) => receivers,
+					None => return Ok(false),
+				};
+
+				// Signals the failed instance did not get to are superseded by `signal`.
+				while let Some(Some(_)) = #support_crate ::StreamExt::next(&mut signals).now_or_never() {}
+				if supervisor.strategy == #support_crate ::SupervisionStrategy::RestartWithReset {
+					while let Some(Some(_)) = #support_crate ::StreamExt::next(&mut messages).now_or_never() {}
+				}
+
+				let (signals, reclaim_signals) = #support_crate ::Reclaimable::new(signals);
+				let (messages, reclaim_messages) = #support_crate ::Reclaimable::new(messages);
+				supervisor.reclaim = Some((reclaim_signals, reclaim_messages));
+
+				let ctx = #subsystem_ctx_name::<M>::new(
+					signals,
+					messages,
+					supervisor.channels_out.clone(),
+					supervisor.to_overseer.clone(),
+					instance.signals_received,
+					instance.heartbeat.clone(),
+				);
+				let spawned = (supervisor.start)(ctx);
+				instance.name = if supervisor.blocking {
+					launch::<_, Blocking, _>(spawner, spawned, subsystem, futures)
+				} else {
+					launch::<_, Regular, _>(spawner, spawned, subsystem, futures)
+				};
+				supervisor.backoff.restarted(::std::time::Instant::now());
+				supervisor.down = false;
+				supervisor.overflowed = false;
+				let buffered = ::std::mem::take(&mut supervisor.buffered);
+				instance.heartbeat.restarted();
+
+				self.send_signal(signal).await?;
+
+				// Messages sent while the subsystem was down are delivered in order.
+				for (priority, packet) in buffered {
+					self.send_packet(packet, priority, "buffered").await?;
+				}
+
+				Ok(true)
+			}
+
 			/// Send a message to the wrapped subsystem.
 			///
 			/// If the inner `instance` is `None`, nothing is happening.
-			pub async fn send_message2(&mut self, message: M, origin: &'static str) -> ::std::result::Result<(), #error_ty > {
+			/// Messages with `priority` set are sent via the priority channel.
+			/// Messages to a subsystem which is down are buffered until its restart.
+			pub async fn send_message2(&mut self, message: M, priority: bool, origin: &'static str) -> ::std::result::Result<(), #error_ty > {
+				let (signals_received, instance_name) = match self.instance {
+					Some(ref instance) => (instance.signals_received, instance.name),
+					None => return Ok(()),
+				};
+				let packet = MessagePacket {
+					signals_received,
+					message: message.into(),
+				};
+
+				match self.supervisor {
+					Some(ref mut supervisor) if supervisor.down => {
+						// Don't wait for a subsystem which is not running.
+						if supervisor.buffered.len() < CHANNEL_CAPACITY {
+							supervisor.buffered.push_back((priority, packet));
+						} else {
+							if !supervisor.overflowed {
+								#support_crate ::tracing::warn!(
+									target: LOG_TARGET,
+									subsystem = instance_name,
+									"Too many messages for a subsystem which is down, dropping further ones",
+								);
+								supervisor.overflowed = true;
+							}
+							supervisor.dropped += 1;
+						}
+						Ok(())
+					}
+					_ => self.send_packet(packet, priority, origin).await,
+				}
+			}
+
+			/// Send a message packet to the running instance.
+			async fn send_packet(&mut self, packet: MessagePacket<M>, priority: bool, origin: &'static str) -> ::std::result::Result<(), #error_ty > {
 				const MESSAGE_TIMEOUT: Duration = Duration::from_secs(10);
 
 				if let Some(ref mut instance) = self.instance {
-					match instance.tx_bounded.send(MessagePacket {
-						signals_received: instance.signals_received,
-						message: message.into(),
-					}).timeout(MESSAGE_TIMEOUT).await
-					{
+					let tx = if priority {
+						&mut instance.tx_priority
+					} else {
+						&mut instance.tx_bounded
+					};
+
+					match tx.send(packet).timeout(MESSAGE_TIMEOUT).await {
 						None => {
 							#support_crate ::tracing::error!(
 								target: LOG_TARGET,
@@ -240,7 +567,15 @@ pub(crate) fn impl_overseen_subsystem(info: &OverseerInfo) -> proc_macro2::Token
 			pub async fn send_signal(&mut self, signal: #signal) -> ::std::result::Result<(), #error_ty > {
 				const SIGNAL_TIMEOUT: ::std::time::Duration = ::std::time::Duration::from_secs(10);
 
+				let is_down = self.is_down();
 				if let Some(ref mut instance) = self.instance {
+					if is_down {
+						// A restarted subsystem starts with a signal of its own, but keep
+						// counting, such that messages sent based on this one are not held back.
+						instance.signals_received += 1;
+						return Ok(());
+					}
+
 					match instance.tx_signal.send(signal).timeout(SIGNAL_TIMEOUT).await {
 						None => {
 							Err(#error_ty :: from(
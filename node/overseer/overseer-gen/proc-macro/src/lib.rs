@@ -28,10 +28,12 @@ mod parse_struct;
 mod impl_channels_out;
 mod impl_dispatch;
 mod impl_message_wrapper;
+mod impl_graph;
 
 use impl_builder::*;
 use impl_channels_out::*;
 use impl_dispatch::*;
+use impl_graph::*;
 use impl_message_wrapper::*;
 use impl_misc::*;
 use impl_overseer::*;
@@ -78,7 +80,10 @@ pub(crate) fn impl_overseer_gen(attr: TokenStream, orig: TokenStream) -> Result<
 		extern_error_ty: args.extern_error_ty,
 		extern_network_ty: args.extern_network_ty,
 		outgoing_ty: args.outgoing_ty,
+		priority_messages: args.priority_messages,
 	};
+	info.check_priority_messages()?;
+	info.check_sends()?;
 
 	let mut additive = impl_overseer_struct(&info);
 	additive.extend(impl_builder(&info));
@@ -89,6 +94,7 @@ pub(crate) fn impl_overseer_gen(attr: TokenStream, orig: TokenStream) -> Result<
 
 	additive.extend(impl_message_wrapper_enum(&info)?);
 	additive.extend(impl_dispatch(&info));
+	additive.extend(impl_subsystem_graph(&info));
 
 	Ok(additive)
 }
@@ -31,6 +31,7 @@ mod kw {
 	syn::custom_keyword!(gen);
 	syn::custom_keyword!(signal_capacity);
 	syn::custom_keyword!(message_capacity);
+	syn::custom_keyword!(priority);
 }
 
 
@@ -76,6 +77,12 @@ enum OverseerAttrItem {
 		eq_token: Token![=],
 		value: usize
 	},
+	PriorityMessages {
+		tag: kw::priority,
+		eq_token: Token![=],
+		bracket_token: syn::token::Bracket,
+		value: Punctuated<Path, Token![,]>
+	},
 }
 
 impl ToTokens for OverseerAttrItem {
@@ -89,6 +96,11 @@ impl ToTokens for OverseerAttrItem {
 			Self::MessageWrapperName { tag, eq_token, value } => { quote!{ #tag #eq_token, #value } }
 			Self::SignalChannelCapacity { tag, eq_token, value } => { quote!{ #tag #eq_token, #value } }
 			Self::MessageChannelCapacity { tag, eq_token, value } => { quote!{ #tag #eq_token, #value } }
+			Self::PriorityMessages { tag, eq_token, bracket_token, value } => {
+				let mut ts = quote!{ #tag #eq_token };
+				bracket_token.surround(&mut ts, |ts| value.to_tokens(ts));
+				ts
+			}
 		};
 		tokens.extend(ts.into_iter());
 	}
@@ -145,6 +157,14 @@ impl Parse for OverseerAttrItem {
 				eq_token: input.parse()?,
 				value: input.parse::<LitInt>()?.base10_parse::<usize>()?,
 			})
+		} else if lookahead.peek(kw::priority) {
+			let content;
+			Ok(OverseerAttrItem::PriorityMessages {
+				tag: input.parse::<kw::priority>()?,
+				eq_token: input.parse()?,
+				bracket_token: syn::bracketed!(content in input),
+				value: content.parse_terminated(Path::parse)?,
+			})
 		} else {
 			Err(lookahead.error())
 		}
@@ -165,6 +185,9 @@ pub(crate) struct AttrArgs {
 	pub(crate) outgoing_ty: Option<Path>,
 	pub(crate) signal_channel_capacity: usize,
 	pub(crate) message_channel_capacity: usize,
+	/// Message variants which are sent via the priority channel
+	/// of the consuming subsystem, i.e. `Msg::Variant`.
+	pub(crate) priority_messages: Vec<Path>,
 }
 
 macro_rules! extract_variant {
@@ -212,6 +235,9 @@ impl Parse for AttrArgs {
 		let message_wrapper = extract_variant!(unique, MessageWrapperName; err = "Must declare the overseer generated wrapping message type via `gen=..`.")?;
 		let network = extract_variant!(unique, ExternNetworkType);
 		let outgoing = extract_variant!(unique, OutgoingType);
+		let priority_messages = extract_variant!(unique, PriorityMessages)
+			.map(|paths| paths.into_iter().collect())
+			.unwrap_or_default();
 
 		Ok(AttrArgs {
 			signal_channel_capacity,
@@ -222,6 +248,7 @@ impl Parse for AttrArgs {
 			extern_network_ty: network,
 			outgoing_ty: outgoing,
 			message_wrapper,
+			priority_messages,
 		})
 	}
 }
@@ -30,6 +30,9 @@ mod kw {
 	syn::custom_keyword!(wip);
 	syn::custom_keyword!(no_dispatch);
 	syn::custom_keyword!(blocking);
+	syn::custom_keyword!(restart);
+	syn::custom_keyword!(reset);
+	syn::custom_keyword!(sends);
 }
 
 
@@ -44,6 +47,12 @@ enum SubSysAttrItem {
 	/// External messages should not be - after being converted -
 	/// be dispatched to the annotated subsystem.
 	NoDispatch(kw::no_dispatch),
+	/// The subsystem is restarted with backoff if it
+	/// returns an error or panics.
+	Restart(kw::restart),
+	/// Like `restart`, but queued messages are dropped
+	/// on restart.
+	Reset(kw::reset),
 }
 
 impl Parse for SubSysAttrItem {
@@ -55,6 +64,10 @@ impl Parse for SubSysAttrItem {
 			Self::Blocking(input.parse::<kw::blocking>()?)
 		} else if lookahead.peek(kw::no_dispatch) {
 			Self::NoDispatch(input.parse::<kw::no_dispatch>()?)
+		} else if lookahead.peek(kw::restart) {
+			Self::Restart(input.parse::<kw::restart>()?)
+		} else if lookahead.peek(kw::reset) {
+			Self::Reset(input.parse::<kw::reset>()?)
 		} else {
 			return Err(lookahead.error())
 		})
@@ -67,12 +80,25 @@ impl ToTokens for SubSysAttrItem {
 			Self::Wip(wip) => { quote!{ #wip } }
 			Self::Blocking(blocking) => { quote!{ #blocking } }
 			Self::NoDispatch(no_dispatch) => { quote!{ #no_dispatch } }
+			Self::Restart(restart) => { quote!{ #restart } }
+			Self::Reset(reset) => { quote!{ #reset } }
 		};
 		tokens.extend(ts.into_iter());
 	}
 }
 
 
+/// What to do if a subsystem returns an error or panics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Supervision {
+	/// Shut down the overseer, the default.
+	Escalate,
+	/// Restart the subsystem, annotated with `restart`.
+	Restart,
+	/// Restart the subsystem with an empty message queue, annotated with `reset`.
+	RestartWithReset,
+}
+
 /// A field of the struct annotated with
 /// `#[subsystem(no_dispatch, , A | B | C)]`
 #[derive(Clone, Debug)]
@@ -95,6 +121,10 @@ pub(crate) struct SubSysField {
 	/// Avoids dispatching `Wrapper` type messages, but generates the variants.
 	/// Does not require the subsystem to be instantiated with the builder pattern.
 	pub(crate) wip: bool,
+	/// How failures of the subsystem are handled.
+	pub(crate) supervision: Supervision,
+	/// Message types the subsystem sends to other subsystems.
+	pub(crate) sends: Vec<Path>,
 }
 
 fn try_type_to_path(ty: Type, span: Span) -> Result<Path> {
@@ -137,7 +167,11 @@ pub(crate) struct SubSystemTags {
 	/// and also not include the subsystem in the list of subsystems.
 	pub(crate) wip: bool,
 	pub(crate) blocking: bool,
+	pub(crate) supervision: Supervision,
 	pub(crate) consumes: Path,
+	/// Message types sent by the subsystem, declared via `sends: [..]`
+	/// after the consumed message type.
+	pub(crate) sends: Vec<Path>,
 }
 
 impl Parse for SubSystemTags {
@@ -158,6 +192,24 @@ impl Parse for SubSystemTags {
 
 		let consumes = content.parse::<Path>()?;
 
+		let mut sends = Vec::new();
+		if content.peek(Token![,]) {
+			content.parse::<Token![,]>()?;
+			if !content.is_empty() {
+				content.parse::<kw::sends>()?;
+				content.parse::<Token![:]>()?;
+				let sends_content;
+				let _ = syn::bracketed!(sends_content in content);
+				let mut unique_sends = HashSet::<String>::new();
+				for path in sends_content.parse_terminated::<Path, Token![,]>(Path::parse)? {
+					if !unique_sends.insert(path.to_token_stream().to_string()) {
+						return Err(Error::new(path.span(), "Duplicate declaration of a sent message type."));
+					}
+					sends.push(path);
+				}
+			}
+		}
+
 		let mut unique = HashMap::<std::mem::Discriminant<SubSysAttrItem>, SubSysAttrItem, RandomState>::default();
 		for item in items {
 			if let Some(first) = unique.insert(std::mem::discriminant(&item), item.clone()) {
@@ -170,8 +222,21 @@ impl Parse for SubSystemTags {
 		let no_dispatch = extract_variant!(unique, NoDispatch; default = false);
 		let blocking = extract_variant!(unique, Blocking; default = false);
 		let wip = extract_variant!(unique, Wip; default = false);
+		let supervision = match (
+			unique.values().find(|item| matches!(item, SubSysAttrItem::Restart(_))),
+			unique.values().find(|item| matches!(item, SubSysAttrItem::Reset(_))),
+		) {
+			(Some(restart), Some(reset)) => {
+				let mut e = Error::new(reset.span(), "`reset` already implies a restart");
+				e.combine(Error::new(restart.span(), "so `restart` must not be given as well."));
+				return Err(e);
+			}
+			(Some(_), None) => Supervision::Restart,
+			(None, Some(_)) => Supervision::RestartWithReset,
+			(None, None) => Supervision::Escalate,
+		};
 
-		Ok(Self { attrs, no_dispatch, blocking, consumes, wip })
+		Ok(Self { attrs, no_dispatch, blocking, supervision, consumes, sends, wip })
 	}
 }
 
@@ -220,6 +285,9 @@ pub(crate) struct OverseerInfo {
 
 	/// Incoming event type from the outer world, commonly from the network.
 	pub(crate) extern_error_ty: Path,
+
+	/// Message variants that are sent via the priority channel of the consuming subsystem.
+	pub(crate) priority_messages: Vec<Path>,
 }
 
 impl OverseerInfo {
@@ -312,6 +380,13 @@ impl OverseerInfo {
 			.collect::<Vec<_>>()
 	}
 
+	pub(crate) fn subsystems_without_wip(&self) -> Vec<&SubSysField> {
+		self.subsystems
+			.iter()
+			.filter(|ssf| !ssf.wip)
+			.collect::<Vec<_>>()
+	}
+
 	pub(crate) fn consumes_without_wip(&self) -> Vec<Path> {
 		self.subsystems
 			.iter()
@@ -319,6 +394,88 @@ impl OverseerInfo {
 			.map(|ssf| ssf.consumes.clone())
 			.collect::<Vec<_>>()
 	}
+
+	/// Assure every priority message is a variant of a message type consumed by a subsystem.
+	pub(crate) fn check_priority_messages(&self) -> Result<()> {
+		let consumes = self.consumes_without_wip()
+			.iter()
+			.map(|consumes| consumes.to_token_stream().to_string())
+			.collect::<HashSet<_>>();
+
+		for path in self.priority_messages.iter() {
+			match priority_message_type(path) {
+				Some(message_ty) if consumes.contains(&message_ty) => {}
+				Some(_) => return Err(Error::new(
+					path.span(),
+					"Priority message is not consumed by any subsystem, expected `Message::Variant`."
+				)),
+				None => return Err(Error::new(
+					path.span(),
+					"Priority message must be a message variant, i.e. `Message::Variant`."
+				)),
+			}
+		}
+		Ok(())
+	}
+
+	/// Assure every message type declared in `sends` is consumed by a subsystem.
+	pub(crate) fn check_sends(&self) -> Result<()> {
+		let consumes = self.subsystems
+			.iter()
+			.map(|ssf| ssf.consumes.to_token_stream().to_string())
+			.collect::<HashSet<_>>();
+
+		for path in self.subsystems.iter().flat_map(|ssf| ssf.sends.iter()) {
+			if !consumes.contains(&path.to_token_stream().to_string()) {
+				return Err(Error::new(
+					path.span(),
+					"Sent message type is not consumed by any subsystem."
+				));
+			}
+		}
+		Ok(())
+	}
+
+	/// The subsystem consuming the given message type.
+	pub(crate) fn consumer_of(&self, message_ty: &Path) -> Option<&SubSysField> {
+		let message_ty = message_ty.to_token_stream().to_string();
+		self.subsystems
+			.iter()
+			.find(|ssf| ssf.consumes.to_token_stream().to_string() == message_ty)
+	}
+
+	/// An expression per subsystem, telling whether the message `inner`
+	/// has to be sent via the priority channel.
+	pub(crate) fn priority_predicates_without_wip(&self) -> Vec<TokenStream> {
+		self.subsystems
+			.iter()
+			.filter(|ssf| !ssf.wip)
+			.map(|ssf| {
+				let message_ty = ssf.consumes.to_token_stream().to_string();
+				let variants = self.priority_messages
+					.iter()
+					.filter(|path| priority_message_type(path).as_ref() == Some(&message_ty))
+					.collect::<Vec<_>>();
+				if variants.is_empty() {
+					quote!{ false }
+				} else {
+					quote!{ matches!(inner, #( #variants { .. } )|* ) }
+				}
+			})
+			.collect::<Vec<_>>()
+	}
+}
+
+/// The message type of a priority message `Message::Variant`,
+/// in the stringified form used for comparison with `consumes`.
+fn priority_message_type(path: &Path) -> Option<String> {
+	let n = path.segments.len();
+	if n < 2 {
+		return None
+	}
+	let leading_colon = &path.leading_colon;
+	let segments = path.segments.iter().take(n - 1);
+	Some(quote!{ #leading_colon #( #segments )::* }.to_string())
 }
 
 /// Internals of the overseer.
@@ -378,6 +535,8 @@ impl OverseerGuts {
 					no_dispatch: variant.no_dispatch,
 					wip: variant.wip,
 					blocking: variant.blocking,
+					supervision: variant.supervision,
+					sends: variant.sends,
 				});
 			} else {
 				let field_ty = try_type_to_path(ty, ident.span())?;
@@ -115,3 +115,177 @@ fn attr_partial() {
 	} => {
 	});
 }
+
+#[test]
+fn attr_priority() {
+	let attr: AttrArgs = parse_quote! {
+		gen=AllMessage, event=::some::why::ExternEvent, signal=SigSigSig,
+		error=OverseerError, priority=[Foo::Urgent, ::bar::Bar::Important],
+	};
+	let priority_messages = attr.priority_messages.iter()
+		.map(|path| path.to_token_stream().to_string())
+		.collect::<Vec<_>>();
+	assert_eq!(priority_messages, vec!["Foo :: Urgent", ":: bar :: Bar :: Important"]);
+}
+
+#[test]
+fn priority_message_must_be_consumed() {
+	let item = quote! {
+		pub struct Ooooh {
+			#[subsystem(Foo)]
+			sub0: FooSubsystem,
+
+			#[subsystem(wip, Bar)]
+			sub1: BarSubsystem,
+		}
+	};
+
+	let consumed = quote! {
+		gen=AllMessage, event=Ev, signal=Sig, error=Err, priority=[Foo::Urgent],
+	};
+	assert!(impl_overseer_gen(consumed, item.clone()).is_ok());
+
+	let wip = quote! {
+		gen=AllMessage, event=Ev, signal=Sig, error=Err, priority=[Bar::Urgent],
+	};
+	assert!(impl_overseer_gen(wip, item.clone()).is_err());
+
+	let no_variant = quote! {
+		gen=AllMessage, event=Ev, signal=Sig, error=Err, priority=[Foo],
+	};
+	assert!(impl_overseer_gen(no_variant, item).is_err());
+}
+
+#[test]
+fn struct_parse_sends() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(Foo, sends: [Bar, ::baz::Baz])]
+			sub0: FooSubsystem,
+
+			#[subsystem(no_dispatch, Bar,)]
+			sub1: BarSubsystem,
+
+			#[subsystem(Baz)]
+			sub2: BazSubsystem,
+		}
+	};
+	let sends: Vec<Vec<String>> = item.subsystems.iter()
+		.map(|ssf| ssf.sends.iter().map(|path| path.to_token_stream().to_string()).collect())
+		.collect();
+	assert_eq!(sends, vec![vec!["Bar".to_owned(), ":: baz :: Baz".to_owned()], vec![], vec![]]);
+}
+
+#[test]
+fn struct_parse_sends_duplicate() {
+	let item: Result<OverseerGuts> = syn::parse2(quote! {
+		pub struct Ooooh {
+			#[subsystem(Foo, sends: [Bar, Bar])]
+			sub0: FooSubsystem,
+		}
+	});
+	assert!(item.is_err());
+}
+
+#[test]
+fn sent_message_must_be_consumed() {
+	let attr = quote! {
+		gen=AllMessage, event=Ev, signal=Sig, error=Err,
+	};
+
+	let consumed = quote! {
+		pub struct Ooooh {
+			#[subsystem(Foo, sends: [Bar])]
+			sub0: FooSubsystem,
+
+			#[subsystem(wip, Bar)]
+			sub1: BarSubsystem,
+		}
+	};
+	assert!(impl_overseer_gen(attr.clone(), consumed).is_ok());
+
+	let not_consumed = quote! {
+		pub struct Ooooh {
+			#[subsystem(Foo, sends: [Baz])]
+			sub0: FooSubsystem,
+		}
+	};
+	assert!(impl_overseer_gen(attr, not_consumed).is_err());
+}
+
+#[test]
+fn subsystem_graph() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(Foo, sends: [Bar, Foo])]
+			sub0: FooSubsystem,
+
+			#[subsystem(wip, Bar)]
+			sub1: BarSubsystem,
+		}
+	};
+	let attr: AttrArgs = parse_quote! {
+		gen=AllMessage, event=Ev, signal=Sig, error=Err,
+	};
+	let info = OverseerInfo {
+		support_crate_name: quote!{crate},
+		subsystems: item.subsystems,
+		baggage: item.baggage,
+		overseer_name: item.name,
+		message_wrapper: attr.message_wrapper,
+		message_channel_capacity: attr.message_channel_capacity,
+		signal_channel_capacity: attr.signal_channel_capacity,
+		extern_event_ty: attr.extern_event_ty,
+		extern_signal_ty: attr.extern_signal_ty,
+		extern_error_ty: attr.extern_error_ty,
+		extern_network_ty: attr.extern_network_ty,
+		outgoing_ty: attr.outgoing_ty,
+		priority_messages: attr.priority_messages,
+	};
+
+	assert_eq!(impl_graph::graphviz(&info), "\
+digraph Ooooh {
+	node [shape=box];
+	sub0;
+	sub1 [style=dashed];
+	sub0 -> sub1 [label=\"Bar\"];
+	sub0 -> sub0 [label=\"Foo\"];
+}");
+
+	assert_eq!(impl_graph::mermaid(&info), "\
+flowchart LR
+	sub0
+	sub1:::wip
+	sub0 -- Bar --> sub1
+	sub0 -- Foo --> sub0
+	classDef wip stroke-dasharray: 5 5");
+}
+
+#[test]
+fn struct_parse_supervision() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(Foo)]
+			sub0: FooSubsystem,
+
+			#[subsystem(restart, Bar)]
+			sub1: BarSubsystem,
+
+			#[subsystem(no_dispatch, reset, Baz)]
+			sub2: BazSubsystem,
+		}
+	};
+	let supervision: Vec<_> = item.subsystems.iter().map(|ssf| ssf.supervision).collect();
+	assert_eq!(supervision, vec![Supervision::Escalate, Supervision::Restart, Supervision::RestartWithReset]);
+}
+
+#[test]
+fn struct_parse_supervision_conflict() {
+	let item: Result<OverseerGuts> = syn::parse2(quote! {
+		pub struct Ooooh {
+			#[subsystem(restart, reset, Foo)]
+			sub0: FooSubsystem,
+		}
+	});
+	assert!(item.is_err());
+}
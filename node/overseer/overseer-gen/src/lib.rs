@@ -94,7 +94,8 @@ pub use async_trait::async_trait;
 
 #[doc(hidden)]
 pub use std::time::Duration;
-use std::sync::{Arc, atomic::{self, AtomicUsize}};
+use std::sync::{Arc, atomic::{self, AtomicBool, AtomicU64, AtomicUsize}};
+use std::time::Instant;
 
 #[doc(hidden)]
 pub use futures_timer::Delay;
@@ -184,11 +185,55 @@ pub fn make_packet<T>(signals_received: usize, message: T) -> MessagePacket<T> {
 	}
 }
 
-/// Incoming messages from both the bounded and unbounded channel.
-pub type SubsystemIncomingMessages<M> = self::stream::Select<
-	self::metered::MeteredReceiver<MessagePacket<M>>,
-	self::metered::UnboundedMeteredReceiver<MessagePacket<M>>,
->;
+/// Incoming messages from the priority, bounded and unbounded channel.
+///
+/// Messages from the priority channel are always yielded first,
+/// the bounded and unbounded channel are polled alternately.
+#[derive(Debug)]
+pub struct SubsystemIncomingMessages<M> {
+	priority: self::metered::MeteredReceiver<MessagePacket<M>>,
+	regular: self::stream::Select<
+		self::metered::MeteredReceiver<MessagePacket<M>>,
+		self::metered::UnboundedMeteredReceiver<MessagePacket<M>>,
+	>,
+}
+
+impl<M> SubsystemIncomingMessages<M> {
+	/// Combine the receiving ends of the channels to a subsystem.
+	pub fn new(
+		priority: self::metered::MeteredReceiver<MessagePacket<M>>,
+		bounded: self::metered::MeteredReceiver<MessagePacket<M>>,
+		unbounded: self::metered::UnboundedMeteredReceiver<MessagePacket<M>>,
+	) -> Self {
+		SubsystemIncomingMessages {
+			priority,
+			regular: self::stream::select(bounded, unbounded),
+		}
+	}
+}
+
+impl<M> self::stream::Stream for SubsystemIncomingMessages<M> {
+	type Item = MessagePacket<M>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let priority_terminated = match self.priority.poll_next_unpin(cx) {
+			Poll::Ready(Some(packet)) => return Poll::Ready(Some(packet)),
+			Poll::Ready(None) => true,
+			Poll::Pending => false,
+		};
+		match self.regular.poll_next_unpin(cx) {
+			Poll::Ready(None) if !priority_terminated => Poll::Pending,
+			other => other,
+		}
+	}
+}
+
+impl<M> self::stream::FusedStream for SubsystemIncomingMessages<M> {
+	fn is_terminated(&self) -> bool {
+		self::stream::FusedStream::is_terminated(&self.priority)
+			&& self::stream::FusedStream::is_terminated(&self.regular)
+	}
+}
 
 
 /// Watermark to track the received signals.
@@ -202,13 +247,250 @@ impl SignalsReceived {
 		self.0.load(atomic::Ordering::Relaxed)
 	}
 
+	/// Create a watermark starting at `value`.
+	pub fn new(value: usize) -> Self {
+		SignalsReceived(Arc::new(AtomicUsize::new(value)))
+	}
+
 	/// Increase the number of signals by one.
 	pub fn inc(&self) {
 		self.0.fetch_add(1, atomic::Ordering::Acquire);
 	}
 }
 
+/// Heartbeat of a subsystem, updated by its context whenever the subsystem
+/// asks for its next message or signal and whenever it got one.
+///
+/// A subsystem which is busy for a long time without asking for its next message
+/// is likely stalled. Note that subsystems which keep working on other futures while
+/// waiting for a message look like they are waiting.
+///
+/// Also tracks the failures of the subsystem, as noted by the overseer.
+#[derive(Debug, Clone)]
+pub struct SubsystemHeartbeat(Arc<Heartbeat>);
+
+/// The state of a [`SubsystemHeartbeat`], updated without locking since subsystems beat on every
+/// message.
+#[derive(Debug)]
+struct Heartbeat {
+	/// The reference point of `last_beat`.
+	created: Instant,
+	/// Microseconds since `created` the subsystem last asked for or got a message or signal.
+	last_beat: AtomicU64,
+	waiting: AtomicBool,
+	signals_processed: AtomicUsize,
+	failures: AtomicUsize,
+	restarting: AtomicBool,
+}
+
+impl Heartbeat {
+	fn beat(&self) {
+		let micros = Instant::now().saturating_duration_since(self.created).as_micros();
+		self.last_beat.store(micros.min(u64::MAX as u128) as u64, atomic::Ordering::Relaxed);
+	}
+}
+
+/// A readout of the heartbeat of a subsystem.
+#[derive(Debug, Clone, Copy)]
+pub struct HeartbeatReadout {
+	/// When the subsystem last asked for or got a message or signal.
+	pub last_beat: Instant,
+	/// Whether the subsystem is waiting for its next message or signal.
+	pub waiting: bool,
+	/// Number of signals received from the signal channel before the subsystem last
+	/// asked for its next message or signal, i.e. the signals it is done with.
+	pub signals_processed: usize,
+	/// Number of times the subsystem returned an error or panicked.
+	pub failures: usize,
+	/// Whether the subsystem failed and waits for its restart.
+	pub restarting: bool,
+}
 
+impl Default for SubsystemHeartbeat {
+	fn default() -> Self {
+		SubsystemHeartbeat(Arc::new(Heartbeat {
+			created: Instant::now(),
+			last_beat: AtomicU64::new(0),
+			waiting: AtomicBool::new(false),
+			signals_processed: AtomicUsize::new(0),
+			failures: AtomicUsize::new(0),
+			restarting: AtomicBool::new(false),
+		}))
+	}
+}
+
+impl SubsystemHeartbeat {
+	/// Read the current state of the heartbeat.
+	///
+	/// The fields are read one by one, so a readout taken while the subsystem beats may mix
+	/// the states before and after the beat.
+	pub fn read(&self) -> HeartbeatReadout {
+		let beat = &self.0;
+		HeartbeatReadout {
+			last_beat: beat.created
+				+ Duration::from_micros(beat.last_beat.load(atomic::Ordering::Relaxed)),
+			waiting: beat.waiting.load(atomic::Ordering::Relaxed),
+			signals_processed: beat.signals_processed.load(atomic::Ordering::Relaxed),
+			failures: beat.failures.load(atomic::Ordering::Relaxed),
+			restarting: beat.restarting.load(atomic::Ordering::Relaxed),
+		}
+	}
+
+	/// Note that the subsystem waits for its next message or signal, after having received
+	/// `signals_received` signals from its signal channel.
+	pub fn waiting(&self, signals_received: usize) {
+		self.0.signals_processed.store(signals_received, atomic::Ordering::Relaxed);
+		self.0.waiting.store(true, atomic::Ordering::Relaxed);
+		self.0.beat();
+	}
+
+	/// Note that the subsystem got a message or signal, or stopped waiting for one.
+	pub fn busy(&self) {
+		self.0.waiting.store(false, atomic::Ordering::Relaxed);
+		self.0.beat();
+	}
+
+	/// Note that the subsystem returned an error or panicked, and whether it is restarted.
+	pub fn failed(&self, restarting: bool) {
+		let _ = self.0.failures.fetch_update(
+			atomic::Ordering::Relaxed,
+			atomic::Ordering::Relaxed,
+			|failures| Some(failures.saturating_add(1)),
+		);
+		self.0.restarting.store(restarting, atomic::Ordering::Relaxed);
+	}
+
+	/// Note that the subsystem was restarted.
+	pub fn restarted(&self) {
+		self.0.waiting.store(false, atomic::Ordering::Relaxed);
+		self.0.restarting.store(false, atomic::Ordering::Relaxed);
+		self.0.beat();
+	}
+}
+
+
+
+/// How the overseer reacts to a subsystem returning an error or panicking.
+///
+/// Exits without an error are always escalated, since subsystems only return
+/// cleanly once they were told to conclude or lost their connection to the overseer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisionStrategy {
+	/// Shut down the overseer and with it all other subsystems.
+	Escalate,
+	/// Restart the subsystem after a backoff.
+	///
+	/// Messages which were queued for the failed instance are delivered to the new one.
+	Restart,
+	/// Restart the subsystem after a backoff, with an empty message queue.
+	RestartWithReset,
+}
+
+/// Notification about a subsystem task having finished.
+#[derive(Debug, Clone, Copy)]
+pub struct SubsystemExited<Id> {
+	/// The subsystem, as identified by the overseer.
+	pub subsystem: Id,
+	/// Whether the subsystem returned an error or panicked.
+	pub failed: bool,
+}
+
+/// Exponential backoff between restarts of a subsystem.
+#[derive(Debug, Clone)]
+pub struct RestartBackoff {
+	restarts: u32,
+	last_start: Instant,
+}
+
+impl RestartBackoff {
+	/// Delay before the first restart.
+	const INITIAL_DELAY: Duration = Duration::from_millis(500);
+	/// Upper bound of the delay.
+	///
+	/// Other subsystems block once the message queue of the restarting subsystem is full,
+	/// so this is kept well below the time after which the overseer considers a subsystem
+	/// as stalled.
+	const MAX_DELAY: Duration = Duration::from_secs(8);
+	/// Subsystems running for that long are considered recovered, their next failure
+	/// is restarted after the initial delay again.
+	const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+	/// Create a backoff for a subsystem started at `now`.
+	pub fn new(now: Instant) -> Self {
+		RestartBackoff {
+			restarts: 0,
+			last_start: now,
+		}
+	}
+
+	/// Number of restarts since the subsystem was last considered stable.
+	pub fn restarts(&self) -> u32 {
+		self.restarts
+	}
+
+	/// Delay before restarting a subsystem which failed at `now`.
+	pub fn next_delay(&mut self, now: Instant) -> Duration {
+		if now.saturating_duration_since(self.last_start) >= Self::STABLE_AFTER {
+			self.restarts = 0;
+		}
+
+		let delay = Self::INITIAL_DELAY
+			.checked_mul(1 << self.restarts.min(16))
+			.map_or(Self::MAX_DELAY, |d| d.min(Self::MAX_DELAY));
+		self.restarts = self.restarts.saturating_add(1);
+		delay
+	}
+
+	/// Note that the subsystem got restarted at `now`.
+	pub fn restarted(&mut self, now: Instant) {
+		self.last_start = now;
+	}
+}
+
+/// A value which is handed back via a `oneshot` channel once dropped.
+///
+/// Used for the receiving ends of the channels of a subsystem, which are owned by its context,
+/// so they are available again for a restarted instance, even if the previous one panicked.
+pub struct Reclaimable<T> {
+	inner: Option<T>,
+	reclaim: Option<oneshot::Sender<T>>,
+}
+
+impl<T> Reclaimable<T> {
+	/// Wrap `value`, it can be received from the returned `oneshot::Receiver` once dropped.
+	pub fn new(value: T) -> (Self, oneshot::Receiver<T>) {
+		let (tx, rx) = oneshot::channel();
+		(Reclaimable { inner: Some(value), reclaim: Some(tx) }, rx)
+	}
+}
+
+impl<T> std::ops::Deref for Reclaimable<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		self.inner.as_ref().expect("Only taken on drop. qed.")
+	}
+}
+
+impl<T> std::ops::DerefMut for Reclaimable<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		self.inner.as_mut().expect("Only taken on drop. qed.")
+	}
+}
+
+impl<T> Drop for Reclaimable<T> {
+	fn drop(&mut self) {
+		if let (Some(value), Some(reclaim)) = (self.inner.take(), self.reclaim.take()) {
+			let _ = reclaim.send(value);
+		}
+	}
+}
+
+impl<T: fmt::Debug> fmt::Debug for Reclaimable<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.inner.fmt(f)
+	}
+}
 
 /// A trait to support the origin annotation
 /// such that errors across subsystems can be easier tracked.
@@ -290,6 +572,8 @@ pub struct SubsystemMeters {
 	pub unbounded: metered::Meter,
 	#[allow(missing_docs)]
 	pub signals: metered::Meter,
+	#[allow(missing_docs)]
+	pub priority: metered::Meter,
 }
 
 impl SubsystemMeters {
@@ -299,10 +583,29 @@ impl SubsystemMeters {
 			bounded: self.bounded.read(),
 			unbounded: self.unbounded.read(),
 			signals: self.signals.read(),
+			priority: self.priority.read(),
+		}
+	}
+
+	/// Take the time-in-queue samples of all messages received since the last call.
+	pub fn drain_time_in_queue(&self) -> SubsystemTimeInQueue {
+		let mut regular = self.bounded.drain_time_in_queue();
+		regular.extend(self.unbounded.drain_time_in_queue());
+		SubsystemTimeInQueue {
+			regular,
+			priority: self.priority.drain_time_in_queue(),
 		}
 	}
 }
 
+/// Time-in-queue samples of the messages received by a subsystem, per priority.
+pub struct SubsystemTimeInQueue {
+	/// Messages received via the bounded or unbounded channel.
+	pub regular: Vec<Duration>,
+	/// Messages received via the priority channel.
+	pub priority: Vec<Duration>,
+}
+
 
 /// Set of readouts of the `Meter`s of a subsystem.
 pub struct SubsystemMeterReadouts {
@@ -312,6 +615,8 @@ pub struct SubsystemMeterReadouts {
 	pub unbounded: metered::Readout,
 	#[allow(missing_docs)]
 	pub signals: metered::Readout,
+	#[allow(missing_docs)]
+	pub priority: metered::Readout,
 }
 
 /// A running instance of some [`Subsystem`].
@@ -324,8 +629,12 @@ pub struct SubsystemInstance<Message, Signal> {
 	pub tx_signal: crate::metered::MeteredSender<Signal>,
 	/// Send sink for `Message`s to be sent to a subsystem.
 	pub tx_bounded: crate::metered::MeteredSender<MessagePacket<Message>>,
+	/// Send sink for priority `Message`s to be sent to a subsystem.
+	pub tx_priority: crate::metered::MeteredSender<MessagePacket<Message>>,
 	/// All meters of the particular subsystem instance.
 	pub meters: SubsystemMeters,
+	/// Heartbeat of the subsystem, shared with restarted instances.
+	pub heartbeat: SubsystemHeartbeat,
 	/// The number of signals already received.
 	/// Required to assure messages and signals
 	/// are processed correctly.
@@ -431,10 +740,31 @@ pub trait SubsystemContext: Send + 'static {
 		self.sender().send_unbounded_message(Self::AllMessages::from(msg))
 	}
 
+	/// Send a direct message of a type the subsystem declared to send.
+	///
+	/// Unlike [`send_message`](Self::send_message), sending a message type which is not
+	/// declared via `sends: [..]` of the subsystem fails to compile.
+	async fn send_declared<X>(&mut self, msg: X)
+		where
+			Self: SendsTo<X>,
+			Self::AllMessages: From<X>,
+			X: Send + 'static,
+	{
+		self.sender().send_message(<Self::AllMessages>::from(msg)).await
+	}
+
 	/// Obtain the sender.
 	fn sender(&mut self) -> &mut Self::Sender;
 }
 
+/// Implemented by the context of a subsystem for every message type
+/// the subsystem declares to send via `sends: [..]`.
+///
+/// Subsystems bound their context by it for the message types they send
+/// with [`SubsystemContext::send_declared`], which fails to compile once
+/// the subsystem is added to an overseer that does not declare the message type.
+pub trait SendsTo<Message> {}
+
 /// A trait that describes the [`Subsystem`]s that can run on the [`Overseer`].
 ///
 /// It is generic over the message type circulating in the system.
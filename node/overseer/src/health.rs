@@ -0,0 +1,228 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Health of the subsystems, aggregated from their heartbeats and channels.
+//!
+//! The overseer registers every subsystem once it runs and notes the leaves it activates,
+//! clones of the [`SubsystemsHealth`] handle can be used to query the health at any time,
+//! e.g. over RPC.
+
+use std::{collections::VecDeque, sync::{Arc, Mutex}, time::{Duration, Instant}};
+
+use polkadot_primitives::v1::{BlockNumber, Hash};
+
+use crate::{SubsystemHeartbeat, SubsystemMeters};
+
+/// Time after which a subsystem which is not waiting for its next message or signal
+/// is considered stalled.
+///
+/// Matches the time the overseer waits for a subsystem to accept a message.
+const STALL_THRESHOLD: Duration = Duration::from_secs(10);
+
+/// Number of activated leaves kept to find the last leaf processed by a subsystem.
+const NOTED_LEAVES: usize = 32;
+
+struct MonitoredSubsystem {
+	name: &'static str,
+	meters: SubsystemMeters,
+	heartbeat: SubsystemHeartbeat,
+}
+
+/// A leaf activated by the overseer, along with the number of signals sent to each
+/// subsystem up to and including the one activating the leaf.
+struct NotedLeaf {
+	hash: Hash,
+	number: BlockNumber,
+	signals_sent: Vec<usize>,
+}
+
+#[derive(Default)]
+struct HealthInner {
+	subsystems: Vec<MonitoredSubsystem>,
+	leaves: VecDeque<NotedLeaf>,
+	is_major_syncing: Option<Box<dyn Fn() -> bool + Send>>,
+}
+
+/// Health of a single subsystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubsystemHealth {
+	/// Name of the subsystem.
+	pub name: &'static str,
+	/// Whether the subsystem is running and not stalled.
+	pub up: bool,
+	/// Whether the subsystem waits for its next message or signal.
+	pub waiting: bool,
+	/// Time since the subsystem last asked for or got a message or signal.
+	pub since_heartbeat: Duration,
+	/// The most recently activated leaf the subsystem is done with.
+	pub last_processed_leaf: Option<(Hash, BlockNumber)>,
+	/// Whether the subsystem is done with all signals sent to it.
+	pub signals_processed: bool,
+	/// Number of signals sent to the subsystem it did not receive yet.
+	pub pending_signals: usize,
+	/// Number of messages sent to the subsystem it did not receive yet.
+	pub queue_depth: usize,
+	/// Number of times the subsystem returned an error or panicked.
+	pub failures: usize,
+}
+
+/// Aggregated health of all subsystems.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+	/// Health of every subsystem.
+	pub subsystems: Vec<SubsystemHealth>,
+	/// Whether the node is doing a major sync, `None` if unknown.
+	pub is_major_syncing: Option<bool>,
+	/// The most recently activated leaf.
+	pub current_leaf: Option<(Hash, BlockNumber)>,
+}
+
+impl HealthReport {
+	/// Whether the node is synced, all subsystems are up and have processed the current leaves.
+	pub fn is_ready(&self) -> bool {
+		self.is_major_syncing == Some(false)
+			&& self.current_leaf.is_some()
+			&& !self.subsystems.is_empty()
+			&& self.subsystems.iter().all(|s| s.up && s.signals_processed)
+	}
+}
+
+/// Shared handle to the health of the subsystems.
+#[derive(Clone, Default)]
+pub struct SubsystemsHealth(Arc<Mutex<HealthInner>>);
+
+impl SubsystemsHealth {
+	/// Set the source of the sync status of the node, which is required for the node to be ready.
+	pub fn set_is_major_syncing(&self, is_major_syncing: impl Fn() -> bool + Send + 'static) {
+		self.lock().is_major_syncing = Some(Box::new(is_major_syncing));
+	}
+
+	/// The current health of the subsystems.
+	pub fn report(&self) -> HealthReport {
+		self.report_at(Instant::now())
+	}
+
+	fn report_at(&self, now: Instant) -> HealthReport {
+		let inner = self.lock();
+
+		let subsystems = inner.subsystems.iter().enumerate().map(|(index, subsystem)| {
+			let readouts = subsystem.meters.read();
+			let beat = subsystem.heartbeat.read();
+			let since_heartbeat = now.saturating_duration_since(beat.last_beat);
+
+			let last_processed_leaf = inner.leaves.iter()
+				.rev()
+				.find(|leaf| leaf.signals_sent[index] <= beat.signals_processed)
+				.map(|leaf| (leaf.hash, leaf.number));
+
+			let pending = |readout: &crate::gen::metered::Readout| readout.sent.saturating_sub(readout.received);
+
+			SubsystemHealth {
+				name: subsystem.name,
+				up: !beat.restarting && (beat.waiting || since_heartbeat < STALL_THRESHOLD),
+				waiting: beat.waiting,
+				since_heartbeat,
+				last_processed_leaf,
+				signals_processed: readouts.signals.sent <= beat.signals_processed,
+				pending_signals: pending(&readouts.signals),
+				queue_depth: pending(&readouts.bounded)
+					+ pending(&readouts.unbounded)
+					+ pending(&readouts.priority),
+				failures: beat.failures,
+			}
+		}).collect();
+
+		HealthReport {
+			subsystems,
+			is_major_syncing: inner.is_major_syncing.as_ref().map(|is_major_syncing| is_major_syncing()),
+			current_leaf: inner.leaves.back().map(|leaf| (leaf.hash, leaf.number)),
+		}
+	}
+
+	/// Monitor the given subsystems, replacing the ones monitored before.
+	pub(crate) fn register(
+		&self,
+		subsystems: impl IntoIterator<Item = (&'static str, SubsystemMeters, SubsystemHeartbeat)>,
+	) {
+		let mut inner = self.lock();
+		inner.subsystems = subsystems.into_iter()
+			.map(|(name, meters, heartbeat)| MonitoredSubsystem { name, meters, heartbeat })
+			.collect();
+		inner.leaves.clear();
+	}
+
+	/// Note a leaf activated by a signal which was just sent to all subsystems.
+	pub(crate) fn note_leaf_activated(&self, hash: Hash, number: BlockNumber) {
+		let mut inner = self.lock();
+		let signals_sent = inner.subsystems.iter()
+			.map(|subsystem| subsystem.meters.signals.read().sent)
+			.collect();
+
+		if inner.leaves.len() >= NOTED_LEAVES {
+			inner.leaves.pop_front();
+		}
+		inner.leaves.push_back(NotedLeaf { hash, number, signals_sent });
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, HealthInner> {
+		self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn monitored(health: &SubsystemsHealth) -> SubsystemHeartbeat {
+		let meters = SubsystemMeters {
+			bounded: Default::default(),
+			unbounded: Default::default(),
+			signals: Default::default(),
+			priority: Default::default(),
+		};
+		let heartbeat = SubsystemHeartbeat::default();
+		health.register(vec![("subsystem", meters, heartbeat.clone())]);
+		heartbeat
+	}
+
+	#[test]
+	fn stalls_only_while_busy() {
+		let health = SubsystemsHealth::default();
+		let heartbeat = monitored(&health);
+		let later = Instant::now() + STALL_THRESHOLD * 2;
+
+		heartbeat.waiting(0);
+		assert!(health.report_at(later).subsystems[0].up);
+
+		heartbeat.busy();
+		assert!(!health.report_at(later).subsystems[0].up);
+		assert!(health.report().subsystems[0].up);
+	}
+
+	#[test]
+	fn restarting_subsystems_are_down() {
+		let health = SubsystemsHealth::default();
+		let heartbeat = monitored(&health);
+
+		heartbeat.failed(true);
+		let report = health.report();
+		assert!(!report.subsystems[0].up);
+		assert_eq!(report.subsystems[0].failures, 1);
+
+		heartbeat.restarted();
+		assert!(health.report().subsystems[0].up);
+	}
+}
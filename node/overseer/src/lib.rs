@@ -78,9 +78,7 @@ use polkadot_primitives::v1::{Block, BlockId,BlockNumber, Hash, ParachainHost};
 use client::{BlockImportNotification, BlockchainEvents, FinalityNotification};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 
-use polkadot_node_network_protocol::{
-	v1 as protocol_v1,
-};
+use polkadot_node_network_protocol::VersionedValidationProtocol;
 use polkadot_node_subsystem_types::messages::{
 	CandidateValidationMessage, CandidateBackingMessage,
 	ChainApiMessage, StatementDistributionMessage,
@@ -88,7 +86,7 @@ use polkadot_node_subsystem_types::messages::{
 	ProvisionerMessage, RuntimeApiMessage,
 	AvailabilityStoreMessage, NetworkBridgeMessage, CollationGenerationMessage,
 	CollatorProtocolMessage, AvailabilityRecoveryMessage, ApprovalDistributionMessage,
-	ApprovalVotingMessage, GossipSupportMessage,
+	ApprovalVotingMessage, GossipSupportMessage, PvfCheckerMessage,
 	NetworkBridgeEvent,
 	DisputeParticipationMessage, DisputeCoordinatorMessage, ChainSelectionMessage,
 	DisputeDistributionMessage,
@@ -103,11 +101,16 @@ pub use polkadot_node_subsystem_types::{
 // TODO legacy, to be deleted, left for easier integration
 // TODO https://github.com/paritytech/polkadot/issues/3427
 mod subsystems;
-pub use self::subsystems::AllSubsystems;
+pub use self::subsystems::{AllSubsystems, DummySubsystem};
 
 mod metrics;
 use self::metrics::Metrics;
 
+mod health;
+pub use self::health::{HealthReport, SubsystemHealth, SubsystemsHealth};
+
+pub mod recording;
+
 use polkadot_node_metrics::{
 	metrics::{
 		prometheus,
@@ -121,16 +124,22 @@ pub use polkadot_overseer_gen::{
 	Subsystem,
 	SubsystemMeterReadouts,
 	SubsystemMeters,
+	SubsystemHeartbeat,
+	HeartbeatReadout,
+	SubsystemTimeInQueue,
 	SubsystemIncomingMessages,
 	SubsystemInstance,
 	SubsystemSender,
 	SubsystemContext,
+	SendsTo,
 	overlord,
 	MessagePacket,
 	SignalsReceived,
 	FromOverseer,
 	ToOverseer,
 	MapSubsystem,
+	SupervisionStrategy,
+	SubsystemExited,
 };
 pub use polkadot_overseer_gen as gen;
 
@@ -335,71 +344,166 @@ pub async fn forward_events<P: BlockchainEvents<Block>>(
 	event=Event,
 	signal=OverseerSignal,
 	error=SubsystemError,
-	network=NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	network=NetworkBridgeEvent<VersionedValidationProtocol>,
+	priority=[
+		ApprovalVotingMessage::ApprovedAncestor,
+	],
 )]
 pub struct Overseer<SupportsParachains> {
 
-	#[subsystem(no_dispatch, CandidateValidationMessage)]
+	#[subsystem(no_dispatch, CandidateValidationMessage, sends: [
+		RuntimeApiMessage,
+	])]
 	candidate_validation: CandidateValidation,
 
-	#[subsystem(no_dispatch, CandidateBackingMessage)]
+	#[subsystem(no_dispatch, CandidateBackingMessage, sends: [
+		// Only used by malus, to second candidates of its own.
+		CandidateBackingMessage,
+		CandidateValidationMessage,
+		CollatorProtocolMessage,
+		AvailabilityDistributionMessage,
+		AvailabilityStoreMessage,
+		StatementDistributionMessage,
+		ProvisionerMessage,
+		RuntimeApiMessage,
+		DisputeCoordinatorMessage,
+	])]
 	candidate_backing: CandidateBacking,
 
-	#[subsystem(StatementDistributionMessage)]
+	#[subsystem(StatementDistributionMessage, sends: [
+		NetworkBridgeMessage,
+		CandidateBackingMessage,
+		RuntimeApiMessage,
+	])]
 	statement_distribution: StatementDistribution,
 
-	#[subsystem(no_dispatch, AvailabilityDistributionMessage)]
+	#[subsystem(no_dispatch, AvailabilityDistributionMessage, sends: [
+		AvailabilityStoreMessage,
+		NetworkBridgeMessage,
+		RuntimeApiMessage,
+	])]
 	availability_distribution: AvailabilityDistribution,
 
-	#[subsystem(no_dispatch, AvailabilityRecoveryMessage)]
+	#[subsystem(no_dispatch, AvailabilityRecoveryMessage, sends: [
+		NetworkBridgeMessage,
+		RuntimeApiMessage,
+		AvailabilityStoreMessage,
+	])]
 	availability_recovery: AvailabilityRecovery,
 
-	#[subsystem(blocking, no_dispatch, BitfieldSigningMessage)]
+	#[subsystem(blocking, no_dispatch, BitfieldSigningMessage, sends: [
+		AvailabilityStoreMessage,
+		RuntimeApiMessage,
+		BitfieldDistributionMessage,
+	])]
 	bitfield_signing: BitfieldSigning,
 
-	#[subsystem(BitfieldDistributionMessage)]
+	#[subsystem(BitfieldDistributionMessage, sends: [
+		RuntimeApiMessage,
+		NetworkBridgeMessage,
+		ProvisionerMessage,
+	])]
 	bitfield_distribution: BitfieldDistribution,
 
-	#[subsystem(no_dispatch, ProvisionerMessage)]
+	#[subsystem(no_dispatch, ProvisionerMessage, sends: [
+		RuntimeApiMessage,
+		CandidateBackingMessage,
+		ChainApiMessage,
+		DisputeCoordinatorMessage,
+	])]
 	provisioner: Provisioner,
 
 	#[subsystem(no_dispatch, blocking, RuntimeApiMessage)]
 	runtime_api: RuntimeApi,
 
-	#[subsystem(no_dispatch, blocking, AvailabilityStoreMessage)]
+	#[subsystem(no_dispatch, blocking, AvailabilityStoreMessage, sends: [
+		ChainApiMessage,
+		RuntimeApiMessage,
+	])]
 	availability_store: AvailabilityStore,
 
-	#[subsystem(no_dispatch, NetworkBridgeMessage)]
+	#[subsystem(no_dispatch, NetworkBridgeMessage, sends: [
+		StatementDistributionMessage,
+		BitfieldDistributionMessage,
+		ApprovalDistributionMessage,
+		CollatorProtocolMessage,
+		GossipSupportMessage,
+		DisputeDistributionMessage,
+	])]
 	network_bridge: NetworkBridge,
 
 	#[subsystem(no_dispatch, blocking, ChainApiMessage)]
 	chain_api: ChainApi,
 
-	#[subsystem(no_dispatch, CollationGenerationMessage)]
+	#[subsystem(no_dispatch, CollationGenerationMessage, sends: [
+		RuntimeApiMessage,
+		CollatorProtocolMessage,
+	])]
 	collation_generation: CollationGeneration,
 
-	#[subsystem(no_dispatch, CollatorProtocolMessage)]
+	#[subsystem(no_dispatch, CollatorProtocolMessage, sends: [
+		NetworkBridgeMessage,
+		RuntimeApiMessage,
+		CandidateBackingMessage,
+	])]
 	collator_protocol: CollatorProtocol,
 
-	#[subsystem(ApprovalDistributionMessage)]
+	#[subsystem(ApprovalDistributionMessage, sends: [
+		NetworkBridgeMessage,
+		ApprovalVotingMessage,
+	])]
 	approval_distribution: ApprovalDistribution,
 
-	#[subsystem(no_dispatch, ApprovalVotingMessage)]
+	#[subsystem(no_dispatch, ApprovalVotingMessage, sends: [
+		ApprovalDistributionMessage,
+		AvailabilityRecoveryMessage,
+		CandidateValidationMessage,
+		ChainApiMessage,
+		ChainSelectionMessage,
+		DisputeCoordinatorMessage,
+		RuntimeApiMessage,
+	])]
 	approval_voting: ApprovalVoting,
 
-	#[subsystem(no_dispatch, GossipSupportMessage)]
+	#[subsystem(no_dispatch, reset, GossipSupportMessage, sends: [
+		NetworkBridgeMessage,
+		RuntimeApiMessage,
+	])]
 	gossip_support: GossipSupport,
 
-	#[subsystem(no_dispatch, wip, DisputeCoordinatorMessage)]
+	#[subsystem(no_dispatch, PvfCheckerMessage, sends: [
+		CandidateValidationMessage,
+		RuntimeApiMessage,
+	])]
+	pvf_checker: PvfChecker,
+
+	#[subsystem(no_dispatch, wip, DisputeCoordinatorMessage, sends: [
+		RuntimeApiMessage,
+		ChainApiMessage,
+		DisputeParticipationMessage,
+		DisputeDistributionMessage,
+	])]
 	dipute_coordinator: DisputeCoordinator,
 
-	#[subsystem(no_dispatch, wip, DisputeParticipationMessage)]
+	#[subsystem(no_dispatch, wip, DisputeParticipationMessage, sends: [
+		RuntimeApiMessage,
+		CandidateValidationMessage,
+		AvailabilityRecoveryMessage,
+		AvailabilityStoreMessage,
+		DisputeCoordinatorMessage,
+	])]
 	dispute_participation: DisputeParticipation,
 
-	#[subsystem(no_dispatch, wip, DisputeDistributionMessage)]
+	#[subsystem(no_dispatch, wip, DisputeDistributionMessage, sends: [
+		RuntimeApiMessage,
+		DisputeCoordinatorMessage,
+		NetworkBridgeMessage,
+	])]
 	dipute_distribution: DisputeDistribution,
 
-	#[subsystem(no_dispatch, wip, ChainSelectionMessage)]
+	#[subsystem(no_dispatch, wip, ChainSelectionMessage, sends: [
+		ChainApiMessage,
+	])]
 	chain_selection: ChainSelection,
 
 	/// External listeners waiting for a hash to be in the active-leave set.
@@ -424,6 +528,9 @@ pub struct Overseer<SupportsParachains> {
 
 	/// Various Prometheus metrics.
 	pub metrics: Metrics,
+
+	/// Health of the subsystems.
+	pub health: SubsystemsHealth,
 }
 
 impl<S, SupportsParachains> Overseer<S, SupportsParachains>
@@ -549,9 +656,9 @@ where
 	/// # 	});
 	/// # }
 	/// ```
-	pub fn new<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>(
+	pub fn new<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS, PC>(
 		leaves: impl IntoIterator<Item = BlockInfo>,
-		all_subsystems: AllSubsystems<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS>,
+		all_subsystems: AllSubsystems<CV, CB, SD, AD, AR, BS, BD, P, RA, AS, NB, CA, CG, CP, ApD, ApV, GS, PC>,
 		prometheus_registry: Option<&prometheus::Registry>,
 		supports_parachains: SupportsParachains,
 		s: S,
@@ -563,7 +670,7 @@ where
 		AD: Subsystem<OverseerSubsystemContext<AvailabilityDistributionMessage>, SubsystemError> + Send,
 		AR: Subsystem<OverseerSubsystemContext<AvailabilityRecoveryMessage>, SubsystemError> + Send,
 		BS: Subsystem<OverseerSubsystemContext<BitfieldSigningMessage>, SubsystemError> + Send,
-		BD: Subsystem<OverseerSubsystemContext<BitfieldDistributionMessage>, SubsystemError> + Send,
+		BD: Subsystem<OverseerSubsystemContext<BitfieldDistributionMessage>, SubsystemError> + Clone + Send + 'static,
 		P: Subsystem<OverseerSubsystemContext<ProvisionerMessage>, SubsystemError> + Send,
 		RA: Subsystem<OverseerSubsystemContext<RuntimeApiMessage>, SubsystemError> + Send,
 		AS: Subsystem<OverseerSubsystemContext<AvailabilityStoreMessage>, SubsystemError> + Send,
@@ -573,7 +680,8 @@ where
 		CP: Subsystem<OverseerSubsystemContext<CollatorProtocolMessage>, SubsystemError> + Send,
 		ApD: Subsystem<OverseerSubsystemContext<ApprovalDistributionMessage>, SubsystemError> + Send,
 		ApV: Subsystem<OverseerSubsystemContext<ApprovalVotingMessage>, SubsystemError> + Send,
-		GS: Subsystem<OverseerSubsystemContext<GossipSupportMessage>, SubsystemError> + Send,
+		GS: Subsystem<OverseerSubsystemContext<GossipSupportMessage>, SubsystemError> + Clone + Send + 'static,
+		PC: Subsystem<OverseerSubsystemContext<PvfCheckerMessage>, SubsystemError> + Send,
 		S: SpawnNamed,
 	{
 		let metrics: Metrics = <Metrics as MetricsTrait>::register(prometheus_registry)?;
@@ -596,6 +704,7 @@ where
 			.approval_distribution(all_subsystems.approval_distribution)
 			.approval_voting(all_subsystems.approval_voting)
 			.gossip_support(all_subsystems.gossip_support)
+			.pvf_checker(all_subsystems.pvf_checker)
 			.leaves(Vec::from_iter(
 				leaves.into_iter().map(|BlockInfo { hash, parent_hash: _, number }| (hash, number))
 			))
@@ -605,6 +714,7 @@ where
 			.activation_external_listeners(Default::default())
 			.supports_parachains(supports_parachains)
 			.metrics(metrics.clone())
+			.health(Default::default())
 			.spawner(s)
 			.build()?;
 
@@ -639,6 +749,12 @@ where
 							.filter_map(|x| x)
 							.map(|(name, ref meters)| (name, meters.read()))
 					);
+					metronome_metrics.time_in_queue_snapshot(
+						subsystem_meters.iter()
+							.cloned()
+							.filter_map(|x| x)
+							.map(|(name, ref meters)| (name, meters.drain_time_in_queue()))
+					);
 
 					async move {
 						()
@@ -650,6 +766,55 @@ where
 		Ok((overseer, Handle(handler)))
 	}
 
+	/// Report the health of the subsystems via `health`.
+	///
+	/// Allows to hand out the handle before the overseer is created, e.g. to the RPC server.
+	pub fn with_health(mut self, health: SubsystemsHealth) -> Self {
+		self.health = health;
+		self
+	}
+
+	/// Monitor the health of all subsystems and keep the related metrics up to date.
+	fn spawn_health_monitor(&mut self) {
+		struct ExtractHealthSources;
+
+		impl<'a, T: 'a> MapSubsystem<&'a OverseenSubsystem<T>> for ExtractHealthSources {
+			type Output = Option<(&'static str, SubsystemMeters, SubsystemHeartbeat)>;
+
+			fn map_subsystem(&self, subsystem: &'a OverseenSubsystem<T>) -> Self::Output {
+				subsystem.instance.as_ref().map(|instance| {
+					(
+						instance.name,
+						instance.meters.clone(),
+						instance.heartbeat.clone(),
+					)
+				})
+			}
+		}
+		self.health.register(self.map_subsystems(ExtractHealthSources).into_iter().flatten());
+
+		let health = self.health.clone();
+		let metrics = self.metrics.clone();
+		let monitor = Metronome::new(std::time::Duration::from_millis(950))
+			.for_each(move |_| {
+				metrics.health_snapshot(&health.report());
+
+				async {}
+			});
+		self.spawner().spawn("subsystems-health", Box::pin(monitor));
+	}
+
+	/// Send an update of the active leaves to all subsystems, noting the activated leaves
+	/// for the health of the subsystems.
+	async fn broadcast_leaves_update(&mut self, update: ActiveLeavesUpdate) -> SubsystemResult<()> {
+		let activated: Vec<_> = update.activated.iter().map(|leaf| (leaf.hash, leaf.number)).collect();
+		self.broadcast_signal(OverseerSignal::ActiveLeaves(update)).await?;
+		for (hash, number) in activated {
+			self.health.note_leaf_activated(hash, number);
+		}
+		Ok(())
+	}
+
 	/// Stop the overseer.
 	async fn stop(mut self) {
 		let _ = self.wait_terminate(
@@ -660,6 +825,8 @@ where
 
 	/// Run the `Overseer`.
 	pub async fn run(mut self) -> SubsystemResult<()> {
+		self.spawn_health_monitor();
+
 		let mut update = ActiveLeavesUpdate::default();
 
 		for (hash, number) in std::mem::take(&mut self.leaves) {
@@ -675,7 +842,7 @@ where
 		}
 
 		if !update.is_empty() {
-			self.broadcast_signal(OverseerSignal::ActiveLeaves(update)).await?;
+			self.broadcast_leaves_update(update).await?;
 		}
 
 		loop {
@@ -711,14 +878,30 @@ where
 						}
 					}
 				},
-				res = self.running_subsystems.select_next_some() => {
-					tracing::error!(
-						target: LOG_TARGET,
-						subsystem = ?res,
-						"subsystem finished unexpectedly",
-					);
-					self.stop().await;
-					return res;
+				exited = self.running_subsystems.select_next_some() => {
+					if !self.supervise(exited) {
+						tracing::error!(
+							target: LOG_TARGET,
+							subsystem = ?exited,
+							"subsystem finished unexpectedly",
+						);
+						self.stop().await;
+						return Ok(());
+					}
+				},
+				pending = self.pending_restarts.select_next_some() => {
+					let subsystem = pending.subsystem();
+					let update = self.current_leaves_update();
+					if !self.restart_subsystem(pending, OverseerSignal::ActiveLeaves(update)).await? {
+						tracing::error!(
+							target: LOG_TARGET,
+							?subsystem,
+							"failed subsystem could not be restarted",
+						);
+						self.stop().await;
+						return Ok(());
+					}
+					self.metrics.on_subsystem_restarted(subsystem.field_name());
 				},
 			}
 		}
@@ -752,7 +935,7 @@ where
 		self.clean_up_external_listeners();
 
 		if !update.is_empty() {
-			self.broadcast_signal(OverseerSignal::ActiveLeaves(update)).await?;
+			self.broadcast_leaves_update(update).await?;
 		}
 		Ok(())
 	}
@@ -820,6 +1003,23 @@ where
 		Some((span, status))
 	}
 
+	/// All currently active leaves which support parachains, for a restarted subsystem
+	/// to catch up with.
+	fn current_leaves_update(&self) -> ActiveLeavesUpdate {
+		let mut update = ActiveLeavesUpdate::default();
+		for (hash, number) in &self.active_leaves {
+			if let Some(span) = self.span_per_active_leaf.get(hash) {
+				update.activated.push(ActivatedLeaf {
+					hash: *hash,
+					number: *number,
+					status: LeafStatus::Stale,
+					span: span.clone(),
+				});
+			}
+		}
+		update
+	}
+
 	fn on_head_deactivated(&mut self, hash: &Hash) {
 		self.metrics.on_head_deactivated();
 		self.activation_external_listeners.remove(hash);
@@ -881,6 +1081,11 @@ impl From<IncomingRequest<req_res_v1::ChunkFetchingRequest>> for AllMessages {
 		From::<AvailabilityDistributionMessage>::from(From::from(req))
 	}
 }
+impl From<IncomingRequest<req_res_v1::BatchedChunkFetchingRequest>> for AllMessages {
+	fn from(req: IncomingRequest<req_res_v1::BatchedChunkFetchingRequest>) -> Self {
+		From::<AvailabilityDistributionMessage>::from(From::from(req))
+	}
+}
 impl From<IncomingRequest<req_res_v1::CollationFetchingRequest>> for AllMessages {
 	fn from(req: IncomingRequest<req_res_v1::CollationFetchingRequest>) -> Self {
 		From::<CollatorProtocolMessage>::from(From::from(req))
@@ -29,8 +29,16 @@ struct MetricsInner {
 	to_subsystem_bounded_received: prometheus::GaugeVec<prometheus::U64>,
 	to_subsystem_unbounded_sent: prometheus::GaugeVec<prometheus::U64>,
 	to_subsystem_unbounded_received: prometheus::GaugeVec<prometheus::U64>,
+	to_subsystem_priority_sent: prometheus::GaugeVec<prometheus::U64>,
+	to_subsystem_priority_received: prometheus::GaugeVec<prometheus::U64>,
+	to_subsystem_time_in_queue: prometheus::HistogramVec,
+	to_subsystem_blocked: prometheus::GaugeVec<prometheus::U64>,
+	to_subsystem_blocked_time: prometheus::GaugeVec<prometheus::F64>,
 	signals_sent: prometheus::GaugeVec<prometheus::U64>,
 	signals_received: prometheus::GaugeVec<prometheus::U64>,
+	subsystem_restarts: prometheus::CounterVec<prometheus::U64>,
+	subsystem_up: prometheus::GaugeVec<prometheus::U64>,
+	subsystems_ready: prometheus::Gauge<prometheus::U64>,
 }
 
 
@@ -57,6 +65,12 @@ impl Metrics {
 		}
 	}
 
+	pub(crate) fn on_subsystem_restarted(&self, subsystem: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.subsystem_restarts.with_label_values(&[subsystem]).inc();
+		}
+	}
+
 	pub(crate) fn channel_fill_level_snapshot(
 		&self,
 		collection: impl IntoIterator<Item=(&'static str, SubsystemMeterReadouts)>,
@@ -76,6 +90,24 @@ impl Metrics {
 						metrics.to_subsystem_unbounded_received.with_label_values(&[name])
 							.set(readouts.unbounded.received as u64);
 
+						metrics.to_subsystem_priority_sent.with_label_values(&[name])
+							.set(readouts.priority.sent as u64);
+
+						metrics.to_subsystem_priority_received.with_label_values(&[name])
+							.set(readouts.priority.received as u64);
+
+						metrics.to_subsystem_blocked.with_label_values(&[name, "regular"])
+							.set(readouts.bounded.blocked as u64);
+
+						metrics.to_subsystem_blocked.with_label_values(&[name, "priority"])
+							.set(readouts.priority.blocked as u64);
+
+						metrics.to_subsystem_blocked_time.with_label_values(&[name, "regular"])
+							.set(readouts.bounded.blocked_time.as_secs_f64());
+
+						metrics.to_subsystem_blocked_time.with_label_values(&[name, "priority"])
+							.set(readouts.priority.blocked_time.as_secs_f64());
+
 						metrics.signals_sent.with_label_values(&[name])
 							.set(readouts.signals.sent as u64);
 
@@ -87,6 +119,34 @@ impl Metrics {
 	}
 }
 
+impl Metrics {
+	pub(crate) fn health_snapshot(&self, report: &HealthReport) {
+		if let Some(metrics) = &self.0 {
+			for subsystem in &report.subsystems {
+				metrics.subsystem_up.with_label_values(&[subsystem.name]).set(subsystem.up as u64);
+			}
+			metrics.subsystems_ready.set(report.is_ready() as u64);
+		}
+	}
+
+	pub(crate) fn time_in_queue_snapshot(
+		&self,
+		collection: impl IntoIterator<Item=(&'static str, SubsystemTimeInQueue)>,
+	) {
+		if let Some(metrics) = &self.0 {
+			collection.into_iter().for_each(
+					|(name, time_in_queue): (_, SubsystemTimeInQueue)| {
+						let regular = metrics.to_subsystem_time_in_queue.with_label_values(&[name, "regular"]);
+						time_in_queue.regular.iter().for_each(|tiq| regular.observe(tiq.as_secs_f64()));
+
+						let priority = metrics.to_subsystem_time_in_queue.with_label_values(&[name, "priority"]);
+						time_in_queue.priority.iter().for_each(|tiq| priority.observe(tiq.as_secs_f64()));
+					}
+			);
+		}
+	}
+}
+
 impl metrics::Metrics for Metrics {
 	fn try_register(registry: &prometheus::Registry) -> Result<Self, prometheus::PrometheusError> {
 		let metrics = MetricsInner {
@@ -159,6 +219,69 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			to_subsystem_priority_sent: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"parachain_subsystem_priority_sent",
+						"Number of elements sent to subsystems' priority queues",
+					),
+					&[
+						"subsystem_name",
+					],
+				)?,
+				registry,
+			)?,
+			to_subsystem_priority_received: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"parachain_subsystem_priority_received",
+						"Number of elements received by subsystems' priority queues",
+					),
+					&[
+						"subsystem_name",
+					],
+				)?,
+				registry,
+			)?,
+			to_subsystem_time_in_queue: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"parachain_subsystem_time_in_queue",
+						"Time sampled messages spent in the queues of subsystems before being received, per priority",
+					).buckets(vec![0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]),
+					&[
+						"subsystem_name",
+						"priority",
+					],
+				)?,
+				registry,
+			)?,
+			to_subsystem_blocked: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"parachain_subsystem_blocked",
+						"Number of sends to subsystems' bounded queues which found the queue full, per priority",
+					),
+					&[
+						"subsystem_name",
+						"priority",
+					],
+				)?,
+				registry,
+			)?,
+			to_subsystem_blocked_time: prometheus::register(
+				prometheus::GaugeVec::<prometheus::F64>::new(
+					prometheus::Opts::new(
+						"parachain_subsystem_blocked_time",
+						"Total time in seconds senders waited for capacity in subsystems' bounded queues, per priority",
+					),
+					&[
+						"subsystem_name",
+						"priority",
+					],
+				)?,
+				registry,
+			)?,
 			signals_sent: prometheus::register(
 				prometheus::GaugeVec::<prometheus::U64>::new(
 					prometheus::Opts::new(
@@ -183,6 +306,37 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			subsystem_restarts: prometheus::register(
+				prometheus::CounterVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"parachain_overseer_subsystem_restarts_total",
+						"Number of restarts of failed subsystems",
+					),
+					&[
+						"subsystem",
+					],
+				)?,
+				registry,
+			)?,
+			subsystem_up: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"parachain_subsystem_up",
+						"Whether a subsystem is running and not stalled",
+					),
+					&[
+						"subsystem_name",
+					],
+				)?,
+				registry,
+			)?,
+			subsystems_ready: prometheus::register(
+				prometheus::Gauge::new(
+					"parachain_subsystems_ready",
+					"Whether the node is synced and all subsystems are up and processed the current leaves",
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
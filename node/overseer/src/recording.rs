@@ -0,0 +1,746 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Opt-in recording of the traffic of selected subsystems.
+//!
+//! A subsystem wrapped in a [`RecordingSubsystem`] gets a context which writes
+//! every signal and message delivered to it, as well as every message it sends,
+//! into a recording file, together with a timestamp. The recording can later be
+//! fed into the same subsystem in isolation to reproduce the exact interleaving
+//! of messages that it observed.
+//!
+//! Signals are recorded in full. Messages are recorded with their `Debug`
+//! representation and, if the message type supports it (see [`RecordableMessage`]),
+//! their SCALE encoding. All subsystems of the overseer can be recorded, see
+//! [`recordable_subsystems`], but only encoded messages can be replayed. Outgoing
+//! messages are compared by their `Debug` representation during a replay and are
+//! not encoded.
+//!
+//! Records are written by a thread of their own, such that recorded subsystems
+//! don't wait for the disk. If the writer falls behind, records are dropped.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, mpsc::{self, SyncSender, Receiver, TrySendError}};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::JoinHandle;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::{channel::oneshot, Future};
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_node_network_protocol::{
+	ObservedRole, OurView, PeerId, ProtocolVersion, Versioned, View, v1 as protocol_v1,
+};
+use polkadot_node_subsystem_types::messages::{
+	ApprovalDistributionMessage, ApprovalVotingMessage, AvailabilityDistributionMessage,
+	AvailabilityRecoveryMessage, AvailabilityStoreMessage, BitfieldDistributionMessage,
+	BitfieldSigningMessage, CandidateBackingMessage, CandidateValidationMessage, ChainApiMessage,
+	CollationGenerationMessage, CollatorProtocolMessage, GossipSupportMessage, NetworkBridgeEvent,
+	PvfCheckerMessage,
+	NetworkBridgeMessage, ProvisionerMessage, RuntimeApiMessage, StatementDistributionMessage,
+};
+use polkadot_primitives::v1::{AuthorityDiscoveryId, BlockNumber, Hash};
+
+use crate::{
+	ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, LeafStatus, MapSubsystem, OverseerSignal,
+	OverseerSubsystemContext, SendsTo, SubsystemContext, SubsystemError, SubsystemId, SubsystemSender,
+	Subsystem, jaeger,
+};
+
+const LOG_TARGET: &str = "parachain::overseer-recording";
+
+/// The version of the recording format, written at the start of every recording.
+pub const RECORDING_VERSION: u32 = 1;
+
+/// The number of records waiting for the writer, further records are dropped.
+const RECORD_QUEUE_SIZE: usize = 4096;
+
+/// The names of the subsystems which can be recorded, i.e. all subsystems of the overseer.
+///
+/// Only subsystems whose incoming messages are encoded can be replayed, see
+/// [`RecordableMessage`].
+pub fn recordable_subsystems() -> impl Iterator<Item = &'static str> {
+	SubsystemId::ALL.iter().map(|id| id.field_name())
+}
+
+/// Configuration of the message recording.
+#[derive(Debug, Clone)]
+pub struct RecordingConfig {
+	/// The file the recording is written to. Existing files are overwritten.
+	pub path: PathBuf,
+	/// The names of the subsystems to record, as used by the overseer,
+	/// e.g. `bitfield_distribution`. See [`recordable_subsystems`].
+	pub subsystems: Vec<String>,
+}
+
+impl RecordingConfig {
+	/// Check that all subsystems to record exist.
+	pub fn validate(&self) -> io::Result<()> {
+		for name in &self.subsystems {
+			if !recordable_subsystems().any(|recordable| recordable == name.as_str()) {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					format!(
+						"Unknown subsystem `{}`, supported are: {}",
+						name,
+						recordable_subsystems().collect::<Vec<_>>().join(", "),
+					),
+				));
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Whether a recorded item was delivered to or sent by the subsystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum Direction {
+	/// A signal or message delivered to the subsystem by the overseer.
+	Incoming,
+	/// A message sent by the subsystem.
+	Outgoing,
+}
+
+/// A recorded overseer signal.
+///
+/// Jaeger spans are not recorded, replayed signals carry disabled spans.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum RecordedSignal {
+	/// See [`OverseerSignal::ActiveLeaves`], activated leaves are recorded as
+	/// `(hash, number, is_stale)`.
+	ActiveLeaves {
+		/// The activated leaves.
+		activated: Vec<(Hash, BlockNumber, bool)>,
+		/// The deactivated leaves.
+		deactivated: Vec<Hash>,
+	},
+	/// See [`OverseerSignal::BlockFinalized`].
+	BlockFinalized(Hash, BlockNumber),
+	/// See [`OverseerSignal::Conclude`].
+	Conclude,
+}
+
+impl From<&OverseerSignal> for RecordedSignal {
+	fn from(signal: &OverseerSignal) -> Self {
+		match signal {
+			OverseerSignal::ActiveLeaves(update) => RecordedSignal::ActiveLeaves {
+				activated: update.activated
+					.iter()
+					.map(|leaf| (leaf.hash, leaf.number, leaf.status.is_stale()))
+					.collect(),
+				deactivated: update.deactivated.iter().cloned().collect(),
+			},
+			OverseerSignal::BlockFinalized(hash, number) => RecordedSignal::BlockFinalized(*hash, *number),
+			OverseerSignal::Conclude => RecordedSignal::Conclude,
+		}
+	}
+}
+
+impl From<RecordedSignal> for OverseerSignal {
+	fn from(signal: RecordedSignal) -> Self {
+		match signal {
+			RecordedSignal::ActiveLeaves { activated, deactivated } => {
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+					activated: activated
+						.into_iter()
+						.map(|(hash, number, stale)| ActivatedLeaf {
+							hash,
+							number,
+							status: if stale { LeafStatus::Stale } else { LeafStatus::Fresh },
+							span: Arc::new(jaeger::Span::Disabled),
+						})
+						.collect(),
+					deactivated: deactivated.into_iter().collect(),
+				})
+			}
+			RecordedSignal::BlockFinalized(hash, number) => OverseerSignal::BlockFinalized(hash, number),
+			RecordedSignal::Conclude => OverseerSignal::Conclude,
+		}
+	}
+}
+
+/// A recorded message.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct RecordedMessage {
+	/// The SCALE encoding of the message, if the message type supports it.
+	pub encoded: Option<Vec<u8>>,
+	/// The `Debug` representation of the message.
+	pub debug: String,
+}
+
+/// A recorded signal or message.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum RecordedItem {
+	/// An overseer signal.
+	Signal(RecordedSignal),
+	/// A message.
+	Message(RecordedMessage),
+}
+
+/// A single entry of a recording.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Record {
+	/// Milliseconds since the unix epoch at the time the item was recorded.
+	pub timestamp: u64,
+	/// The name of the recorded subsystem.
+	pub subsystem: String,
+	/// Whether the item was received or sent by the subsystem.
+	pub direction: Direction,
+	/// The recorded signal or message.
+	pub item: RecordedItem,
+}
+
+/// A message type of a subsystem which can be wrapped in a [`RecordingSubsystem`].
+///
+/// Messages of types without an encoding are only recorded by their `Debug`
+/// representation and end a replay.
+pub trait RecordableMessage: Debug + Sized {
+	/// The name of the subsystem consuming this message type, as used by the overseer.
+	const SUBSYSTEM: &'static str;
+
+	/// Encode the message for a recording, `None` if the message can't be encoded.
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		None
+	}
+
+	/// Decode a message previously encoded with [`Self::encode_recorded`].
+	fn decode_recorded(_encoded: &[u8]) -> Option<Self> {
+		None
+	}
+}
+
+macro_rules! impl_not_recordable {
+	($($message:ty => $subsystem:literal),* $(,)?) => {
+		$(
+			impl RecordableMessage for $message {
+				const SUBSYSTEM: &'static str = $subsystem;
+			}
+		)*
+	}
+}
+
+impl_not_recordable!(
+	CandidateValidationMessage => "candidate_validation",
+	CandidateBackingMessage => "candidate_backing",
+	StatementDistributionMessage => "statement_distribution",
+	AvailabilityDistributionMessage => "availability_distribution",
+	AvailabilityRecoveryMessage => "availability_recovery",
+	BitfieldSigningMessage => "bitfield_signing",
+	ProvisionerMessage => "provisioner",
+	RuntimeApiMessage => "runtime_api",
+	AvailabilityStoreMessage => "availability_store",
+	NetworkBridgeMessage => "network_bridge",
+	ChainApiMessage => "chain_api",
+	CollationGenerationMessage => "collation_generation",
+	CollatorProtocolMessage => "collator_protocol",
+	ApprovalDistributionMessage => "approval_distribution",
+	ApprovalVotingMessage => "approval_voting",
+	GossipSupportMessage => "gossip_support",
+	PvfCheckerMessage => "pvf_checker",
+);
+
+/// The encodable form of the network bridge events of the bitfield distribution.
+#[derive(Encode, Decode)]
+enum RecordedBitfieldNetworkEvent {
+	PeerConnected(Vec<u8>, u8, ProtocolVersion, Option<AuthorityDiscoveryId>),
+	PeerDisconnected(Vec<u8>),
+	NewGossipTopology(Vec<Vec<u8>>),
+	PeerMessage(Vec<u8>, ProtocolVersion, protocol_v1::BitfieldDistributionMessage),
+	PeerViewChange(Vec<u8>, View),
+	OurViewChange(View),
+}
+
+fn encode_role(role: &ObservedRole) -> u8 {
+	match role {
+		ObservedRole::Light => 0,
+		ObservedRole::Full => 1,
+		ObservedRole::Authority => 2,
+	}
+}
+
+fn decode_role(role: u8) -> Option<ObservedRole> {
+	match role {
+		0 => Some(ObservedRole::Light),
+		1 => Some(ObservedRole::Full),
+		2 => Some(ObservedRole::Authority),
+		_ => None,
+	}
+}
+
+fn decode_peer(peer: &[u8]) -> Option<PeerId> {
+	PeerId::from_bytes(peer).ok()
+}
+
+// Network bridge events are encoded. Bitfields signed by the node itself can't be
+// reconstructed without the signing context and validator key, they are only
+// recorded by their `Debug` representation and end a replay as undecodable.
+impl RecordableMessage for BitfieldDistributionMessage {
+	const SUBSYSTEM: &'static str = "bitfield_distribution";
+
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		let event = match self {
+			BitfieldDistributionMessage::DistributeBitfield(..) => return None,
+			BitfieldDistributionMessage::NetworkBridgeUpdateV1(event) => event,
+		};
+
+		let recorded = match event {
+			NetworkBridgeEvent::PeerConnected(peer, role, version, authority_id) =>
+				RecordedBitfieldNetworkEvent::PeerConnected(
+					peer.to_bytes(),
+					encode_role(role),
+					*version,
+					authority_id.clone(),
+				),
+			NetworkBridgeEvent::PeerDisconnected(peer) =>
+				RecordedBitfieldNetworkEvent::PeerDisconnected(peer.to_bytes()),
+			NetworkBridgeEvent::NewGossipTopology(peers) =>
+				RecordedBitfieldNetworkEvent::NewGossipTopology(
+					peers.iter().map(|peer| peer.to_bytes()).collect(),
+				),
+			NetworkBridgeEvent::PeerMessage(peer, message) => {
+				let version = message.version();
+				let message = match message {
+					Versioned::V1(message) | Versioned::V2(message) => message.clone(),
+				};
+				RecordedBitfieldNetworkEvent::PeerMessage(peer.to_bytes(), version, message)
+			}
+			NetworkBridgeEvent::PeerViewChange(peer, view) =>
+				RecordedBitfieldNetworkEvent::PeerViewChange(peer.to_bytes(), view.clone()),
+			NetworkBridgeEvent::OurViewChange(view) =>
+				RecordedBitfieldNetworkEvent::OurViewChange((**view).clone()),
+		};
+
+		Some(recorded.encode())
+	}
+
+	fn decode_recorded(mut encoded: &[u8]) -> Option<Self> {
+		let event = match RecordedBitfieldNetworkEvent::decode(&mut encoded).ok()? {
+			RecordedBitfieldNetworkEvent::PeerConnected(peer, role, version, authority_id) =>
+				NetworkBridgeEvent::PeerConnected(
+					decode_peer(&peer)?,
+					decode_role(role)?,
+					version,
+					authority_id,
+				),
+			RecordedBitfieldNetworkEvent::PeerDisconnected(peer) =>
+				NetworkBridgeEvent::PeerDisconnected(decode_peer(&peer)?),
+			RecordedBitfieldNetworkEvent::NewGossipTopology(peers) =>
+				NetworkBridgeEvent::NewGossipTopology(
+					peers.iter().map(|peer| decode_peer(peer)).collect::<Option<_>>()?,
+				),
+			RecordedBitfieldNetworkEvent::PeerMessage(peer, version, message) =>
+				NetworkBridgeEvent::PeerMessage(
+					decode_peer(&peer)?,
+					Versioned::with_version(version, message),
+				),
+			RecordedBitfieldNetworkEvent::PeerViewChange(peer, view) =>
+				NetworkBridgeEvent::PeerViewChange(decode_peer(&peer)?, view),
+			RecordedBitfieldNetworkEvent::OurViewChange(view) => {
+				let finalized_number = view.finalized_number;
+				NetworkBridgeEvent::OurViewChange(OurView::new(
+					view.into_iter().map(|head| (head, Arc::new(jaeger::Span::Disabled))),
+					finalized_number,
+				))
+			}
+		};
+
+		Some(BitfieldDistributionMessage::NetworkBridgeUpdateV1(event))
+	}
+}
+
+impl RecordedItem {
+	fn incoming<M: RecordableMessage>(from_overseer: &FromOverseer<M, OverseerSignal>) -> Self {
+		match from_overseer {
+			FromOverseer::Signal(signal) => RecordedItem::Signal(signal.into()),
+			FromOverseer::Communication { msg } => RecordedItem::Message(RecordedMessage {
+				encoded: msg.encode_recorded(),
+				debug: format!("{:?}", msg),
+			}),
+		}
+	}
+
+	fn outgoing<M: Debug>(msg: &M) -> Self {
+		RecordedItem::Message(RecordedMessage {
+			encoded: None,
+			debug: format!("{:?}", msg),
+		})
+	}
+}
+
+/// An instruction to the writer thread.
+enum WriterMessage {
+	/// Append an encoded record.
+	Record(Vec<u8>),
+	/// Flush all records received so far, then notify.
+	Flush(oneshot::Sender<()>),
+}
+
+struct RecorderInner {
+	subsystems: HashSet<String>,
+	writer_tx: Option<SyncSender<WriterMessage>>,
+	writer: Option<JoinHandle<()>>,
+	/// The number of records dropped because the writer fell behind.
+	dropped: AtomicU64,
+}
+
+impl Drop for RecorderInner {
+	fn drop(&mut self) {
+		// closing the channel lets the writer write out the remaining records and finish
+		self.writer_tx.take();
+		if let Some(writer) = self.writer.take() {
+			let _ = writer.join();
+		}
+	}
+}
+
+/// Writes the records of all recorded subsystems into one file.
+///
+/// Records are handed to a writer thread, which flushes them whenever it runs out of
+/// records to write, so the recording is usable even if the node is killed. At most
+/// `RECORD_QUEUE_SIZE` records wait for the writer, further ones are dropped and counted.
+#[derive(Clone)]
+pub struct Recorder {
+	inner: Arc<RecorderInner>,
+}
+
+impl Recorder {
+	/// Create the recording file and a recorder writing to it.
+	pub fn new(config: RecordingConfig) -> io::Result<Self> {
+		config.validate()?;
+
+		let mut file = BufWriter::new(File::create(&config.path)?);
+		file.write_all(&RECORDING_VERSION.encode())?;
+		file.flush()?;
+
+		let (writer_tx, writer_rx) = mpsc::sync_channel(RECORD_QUEUE_SIZE);
+		let writer = std::thread::Builder::new()
+			.name("overseer-recording".into())
+			.spawn(move || write_records(file, writer_rx))?;
+
+		Ok(Recorder {
+			inner: Arc::new(RecorderInner {
+				subsystems: config.subsystems.into_iter().collect(),
+				writer_tx: Some(writer_tx),
+				writer: Some(writer),
+				dropped: AtomicU64::new(0),
+			}),
+		})
+	}
+
+	/// Whether the given subsystem is recorded.
+	pub fn records(&self, subsystem: &str) -> bool {
+		self.inner.subsystems.contains(subsystem)
+	}
+
+	/// The number of records dropped because the writer fell behind.
+	pub fn dropped_records(&self) -> u64 {
+		self.inner.dropped.load(Ordering::Relaxed)
+	}
+
+	/// Wait until all records so far are written to the recording file.
+	///
+	/// Blocks the calling thread while the writer's queue is full.
+	pub async fn flush(&self) {
+		let (tx, rx) = oneshot::channel();
+		let sent = self.inner.writer_tx
+			.as_ref()
+			.map_or(false, |writer_tx| writer_tx.send(WriterMessage::Flush(tx)).is_ok());
+		if sent {
+			let _ = rx.await;
+		}
+	}
+
+	fn record(&self, subsystem: &'static str, direction: Direction, item: RecordedItem) {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_millis() as u64)
+			.unwrap_or_default();
+		let record = Record {
+			timestamp,
+			subsystem: subsystem.to_owned(),
+			direction,
+			item,
+		};
+
+		let writer_tx = match self.inner.writer_tx {
+			Some(ref writer_tx) => writer_tx,
+			None => return,
+		};
+		match writer_tx.try_send(WriterMessage::Record(record.encode().encode())) {
+			Ok(()) => {}
+			Err(TrySendError::Full(_)) => {
+				if self.inner.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+					tracing::warn!(
+						target: LOG_TARGET,
+						subsystem,
+						"Recording writer falls behind, dropping records",
+					);
+				}
+			}
+			Err(TrySendError::Disconnected(_)) => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					subsystem,
+					"Recording writer is gone, dropping a record",
+				);
+			}
+		}
+	}
+}
+
+fn write_records(mut file: BufWriter<File>, messages: Receiver<WriterMessage>) {
+	loop {
+		let message = match messages.try_recv() {
+			Ok(message) => message,
+			Err(mpsc::TryRecvError::Disconnected) => break,
+			Err(mpsc::TryRecvError::Empty) => {
+				// nothing to write right now
+				if let Err(err) = file.flush() {
+					tracing::warn!(target: LOG_TARGET, err = ?err, "Failed to flush the recording");
+				}
+				match messages.recv() {
+					Ok(message) => message,
+					Err(_) => break,
+				}
+			}
+		};
+
+		match message {
+			WriterMessage::Record(record) => {
+				if let Err(err) = file.write_all(&record) {
+					tracing::warn!(target: LOG_TARGET, err = ?err, "Failed to write a record");
+				}
+			}
+			WriterMessage::Flush(done) => {
+				if let Err(err) = file.flush() {
+					tracing::warn!(target: LOG_TARGET, err = ?err, "Failed to flush the recording");
+				}
+				let _ = done.send(());
+			}
+		}
+	}
+
+	if let Err(err) = file.flush() {
+		tracing::warn!(target: LOG_TARGET, err = ?err, "Failed to flush the recording");
+	}
+}
+
+/// Read all records from a recording file.
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<Vec<Record>> {
+	let bytes = std::fs::read(path)?;
+	decode_recording(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+/// Decode all records of a recording.
+pub fn decode_recording(mut bytes: &[u8]) -> Result<Vec<Record>, parity_scale_codec::Error> {
+	let version = u32::decode(&mut bytes)?;
+	if version != RECORDING_VERSION {
+		return Err("unsupported recording version".into());
+	}
+
+	let mut records = Vec::new();
+	while !bytes.is_empty() {
+		let record = Vec::<u8>::decode(&mut bytes)?;
+		records.push(Record::decode(&mut &record[..])?);
+	}
+	Ok(records)
+}
+
+/// A sender which records all outgoing messages.
+#[derive(Clone)]
+pub struct RecordingSender<Sender> {
+	inner: Sender,
+	recorder: Option<(Recorder, &'static str)>,
+}
+
+impl<Sender> RecordingSender<Sender> {
+	fn record<M: Debug>(&self, msg: &M) {
+		if let Some((recorder, subsystem)) = &self.recorder {
+			recorder.record(*subsystem, Direction::Outgoing, RecordedItem::outgoing(msg));
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl<Sender, M> SubsystemSender<M> for RecordingSender<Sender>
+where
+	Sender: SubsystemSender<M>,
+	M: Debug + Send + 'static,
+{
+	async fn send_message(&mut self, msg: M) {
+		self.record(&msg);
+		self.inner.send_message(msg).await;
+	}
+
+	async fn send_messages<T>(&mut self, msgs: T)
+	where
+		T: IntoIterator<Item = M> + Send,
+		T::IntoIter: Send,
+	{
+		for msg in msgs {
+			self.send_message(msg).await;
+		}
+	}
+
+	fn send_unbounded_message(&mut self, msg: M) {
+		self.record(&msg);
+		self.inner.send_unbounded_message(msg);
+	}
+}
+
+/// A subsystem context which records all incoming and outgoing traffic.
+pub struct RecordingContext<Context: SubsystemContext> {
+	inner: Context,
+	recorder: Option<Recorder>,
+	sender: RecordingSender<Context::Sender>,
+}
+
+impl<Context> RecordingContext<Context>
+where
+	Context: SubsystemContext<Signal = OverseerSignal>,
+	Context::Message: RecordableMessage,
+{
+	/// Wrap a context, recording its traffic if the recorder covers its subsystem.
+	pub fn new(mut inner: Context, recorder: Option<Recorder>) -> Self {
+		let recorder = recorder.filter(|r| r.records(Context::Message::SUBSYSTEM));
+		let sender = RecordingSender {
+			inner: inner.sender().clone(),
+			recorder: recorder.clone().map(|r| (r, Context::Message::SUBSYSTEM)),
+		};
+
+		Self {
+			inner,
+			recorder,
+			sender,
+		}
+	}
+
+	fn record_incoming(&self, from_overseer: &FromOverseer<Context::Message, OverseerSignal>) {
+		if let Some(recorder) = &self.recorder {
+			recorder.record(
+				Context::Message::SUBSYSTEM,
+				Direction::Incoming,
+				RecordedItem::incoming(from_overseer),
+			);
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl<Context> SubsystemContext for RecordingContext<Context>
+where
+	Context: SubsystemContext<Signal = OverseerSignal>,
+	Context::Message: RecordableMessage,
+	Context::AllMessages: Debug,
+{
+	type Message = Context::Message;
+	type Signal = OverseerSignal;
+	type AllMessages = Context::AllMessages;
+	type Sender = RecordingSender<Context::Sender>;
+	type Error = Context::Error;
+
+	async fn try_recv(&mut self) -> Result<Option<FromOverseer<Self::Message, Self::Signal>>, ()> {
+		let msg = self.inner.try_recv().await?;
+		if let Some(msg) = &msg {
+			self.record_incoming(msg);
+		}
+		Ok(msg)
+	}
+
+	async fn recv(&mut self) -> Result<FromOverseer<Self::Message, Self::Signal>, Self::Error> {
+		let msg = self.inner.recv().await?;
+		self.record_incoming(&msg);
+		Ok(msg)
+	}
+
+	fn spawn(
+		&mut self,
+		name: &'static str,
+		s: Pin<Box<dyn Future<Output = ()> + Send>>,
+	) -> Result<(), Self::Error> {
+		self.inner.spawn(name, s)
+	}
+
+	fn spawn_blocking(
+		&mut self,
+		name: &'static str,
+		s: Pin<Box<dyn Future<Output = ()> + Send>>,
+	) -> Result<(), Self::Error> {
+		self.inner.spawn_blocking(name, s)
+	}
+
+	fn sender(&mut self) -> &mut Self::Sender {
+		&mut self.sender
+	}
+}
+
+/// The recorded subsystem may only send the messages it declares in the overseer.
+impl<Context, X> SendsTo<X> for RecordingContext<Context>
+where
+	Context: SubsystemContext,
+	OverseerSubsystemContext<Context::Message>: SendsTo<X>,
+{}
+
+/// A subsystem whose traffic is recorded.
+///
+/// Without a recorder, or if the recorder doesn't cover the subsystem, the
+/// wrapped subsystem runs as usual.
+#[derive(Clone)]
+pub struct RecordingSubsystem<Sub> {
+	subsystem: Sub,
+	recorder: Option<Recorder>,
+}
+
+impl<Sub> RecordingSubsystem<Sub> {
+	/// Wrap a subsystem.
+	pub fn new(subsystem: Sub, recorder: Option<Recorder>) -> Self {
+		Self {
+			subsystem,
+			recorder,
+		}
+	}
+}
+
+impl<Context, Sub> Subsystem<Context, SubsystemError> for RecordingSubsystem<Sub>
+where
+	Context: SubsystemContext<Signal = OverseerSignal, Error = SubsystemError>,
+	Context::Message: RecordableMessage,
+	Context::AllMessages: Debug,
+	Sub: Subsystem<RecordingContext<Context>, SubsystemError>,
+{
+	fn start(self, ctx: Context) -> crate::gen::SpawnedSubsystem<SubsystemError> {
+		let ctx = RecordingContext::new(ctx, self.recorder);
+		self.subsystem.start(ctx)
+	}
+}
+
+/// Wraps every subsystem into a [`RecordingSubsystem`], to be used with
+/// [`AllSubsystems::map_subsystems`](crate::AllSubsystems::map_subsystems).
+#[derive(Clone)]
+pub struct WithRecorder(pub Option<Recorder>);
+
+impl<Sub> MapSubsystem<Sub> for WithRecorder {
+	type Output = RecordingSubsystem<Sub>;
+
+	fn map_subsystem(&self, subsystem: Sub) -> Self::Output {
+		RecordingSubsystem::new(subsystem, self.0.clone())
+	}
+}
@@ -17,9 +17,9 @@
 use std::sync::atomic;
 use std::collections::HashMap;
 use std::task::{Poll};
-use futures::{executor, pin_mut, select, FutureExt, pending, poll, stream};
+use futures::{executor, pin_mut, select, FutureExt, pending, poll};
 
-use polkadot_primitives::v1::{CollatorPair, CandidateHash};
+use polkadot_primitives::v1::{CollatorPair, CandidateHash, ValidatorIndex};
 use polkadot_node_primitives::{CollationResult, CollationGenerationConfig, PoV, BlockData};
 use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange};
 use polkadot_node_subsystem_types::{
@@ -302,6 +302,429 @@ fn overseer_ends_on_subsystem_exit() {
 	})
 }
 
+// A subsystem which fails on its first start.
+#[derive(Clone)]
+struct FailOnceSubsystem {
+	starts: Arc<atomic::AtomicUsize>,
+	signals: metered::MeteredSender<OverseerSignal>,
+}
+
+impl<C> overseer::Subsystem<C, SubsystemError> for FailOnceSubsystem
+where
+	C: overseer::SubsystemContext<Message=GossipSupportMessage,Signal=OverseerSignal,AllMessages=AllMessages>,
+{
+	fn start(self, mut ctx: C) -> SpawnedSubsystem {
+		let first_start = self.starts.fetch_add(1, atomic::Ordering::SeqCst) == 0;
+		let mut signals = self.signals;
+
+		SpawnedSubsystem {
+			name: "fail-once-subsystem",
+			future: Box::pin(async move {
+				if first_start {
+					return Err(SubsystemError::Context("fail on purpose".to_owned()));
+				}
+
+				loop {
+					match ctx.recv().await {
+						Ok(FromOverseer::Signal(OverseerSignal::Conclude)) => return Ok(()),
+						Ok(FromOverseer::Signal(s)) => {
+							let _ = signals.send(s).await;
+						}
+						Ok(_) => {}
+						Err(_) => return Ok(()),
+					}
+				}
+			}),
+		}
+	}
+}
+
+// A restartable subsystem failing is restarted and catches up with the active leaves.
+#[test]
+fn failed_subsystem_is_restarted_with_current_leaves() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	executor::block_on(async move {
+		let first_block_hash = [1; 32].into();
+		let first_block = BlockInfo {
+			hash: first_block_hash,
+			parent_hash: [0; 32].into(),
+			number: 1,
+		};
+
+		let starts = Arc::new(atomic::AtomicUsize::new(0));
+		let (signal_tx, signal_rx) = metered::channel::<OverseerSignal>(64);
+		let mut signal_rx = signal_rx.fuse();
+
+		let all_subsystems = AllSubsystems::<()>::dummy()
+			.replace_gossip_support(FailOnceSubsystem {
+				starts: starts.clone(),
+				signals: signal_tx,
+			});
+		let (overseer, mut handler) = Overseer::new(
+			vec![first_block],
+			all_subsystems,
+			None,
+			MockSupportsParachains,
+			spawner,
+		).unwrap();
+		let overseer_fut = overseer.run().fuse();
+
+		pin_mut!(overseer_fut);
+
+		let signal = select! {
+			_ = overseer_fut => panic!("overseer must not stop on a restartable subsystem failing"),
+			signal = signal_rx.next() => signal,
+		};
+
+		assert_matches!(signal, Some(OverseerSignal::ActiveLeaves(update)) => {
+			assert_eq!(update.activated.len(), 1);
+			assert_eq!(update.activated[0].hash, first_block_hash);
+			assert!(update.activated[0].status.is_stale());
+			assert!(update.deactivated.is_empty());
+		});
+		assert_eq!(starts.load(atomic::Ordering::SeqCst), 2);
+
+		handler.stop().await;
+		assert!(overseer_fut.await.is_ok());
+	});
+}
+
+// The health of the subsystems reflects the leaves they processed.
+#[test]
+fn health_reports_processed_leaves() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	executor::block_on(async move {
+		let first_block = BlockInfo {
+			hash: Hash::repeat_byte(1),
+			parent_hash: Hash::repeat_byte(0),
+			number: 1,
+		};
+		let second_block = BlockInfo {
+			hash: Hash::repeat_byte(2),
+			parent_hash: Hash::repeat_byte(1),
+			number: 2,
+		};
+
+		let health = SubsystemsHealth::default();
+		let (overseer, mut handler) = Overseer::new(
+			vec![first_block],
+			AllSubsystems::<()>::dummy(),
+			None,
+			MockSupportsParachains,
+			spawner,
+		).unwrap();
+		let overseer_fut = overseer.with_health(health.clone()).run().fuse();
+
+		pin_mut!(overseer_fut);
+
+		handler.block_imported(second_block).await;
+
+		let processed = async {
+			loop {
+				let report = health.report();
+				if report.current_leaf == Some((Hash::repeat_byte(2), 2))
+					&& report.subsystems.iter().all(|s| s.signals_processed)
+				{
+					return report;
+				}
+				Delay::new(Duration::from_millis(10)).await;
+			}
+		}.timeout(Duration::from_secs(10)).fuse();
+		pin_mut!(processed);
+
+		let mut report = select! {
+			_ = overseer_fut => panic!("overseer must not stop"),
+			report = processed => report.expect("the subsystems must process the leaf in time"),
+		};
+
+		assert!(!report.subsystems.is_empty());
+		for subsystem in &report.subsystems {
+			assert!(subsystem.up);
+			assert_eq!(subsystem.last_processed_leaf, Some((Hash::repeat_byte(2), 2)));
+			assert_eq!(subsystem.failures, 0);
+		}
+
+		// Not ready as long as the sync status is unknown.
+		assert!(!report.is_ready());
+		report.is_major_syncing = Some(false);
+		assert!(report.is_ready());
+
+		handler.stop().await;
+		assert!(overseer_fut.await.is_ok());
+	});
+}
+
+// A subsystem which reports a peer for every message it receives.
+struct ReportOnMessage(metered::MeteredSender<()>);
+
+impl<C> overseer::Subsystem<C, SubsystemError> for ReportOnMessage
+where
+	C: overseer::SubsystemContext<Message=BitfieldDistributionMessage,Signal=OverseerSignal,AllMessages=AllMessages>,
+{
+	fn start(self, mut ctx: C) -> SpawnedSubsystem {
+		let mut reported = self.0;
+
+		SpawnedSubsystem {
+			name: "report-on-message-subsystem",
+			future: Box::pin(async move {
+				loop {
+					match ctx.recv().await {
+						Ok(FromOverseer::Signal(OverseerSignal::Conclude)) => return Ok(()),
+						Ok(FromOverseer::Communication { .. }) => {
+							ctx.send_message(test_network_bridge_msg()).await;
+							let _ = reported.send(()).await;
+						}
+						Ok(_) => {}
+						Err(_) => return Ok(()),
+					}
+				}
+			}),
+		}
+	}
+}
+
+// Once `go` fires, receives `expected` messages and reports which of them were
+// approved ancestor queries, in the order received.
+struct ReportOrderOnGo {
+	go: oneshot::Receiver<()>,
+	expected: usize,
+	received: oneshot::Sender<Vec<bool>>,
+}
+
+impl<C> overseer::Subsystem<C, SubsystemError> for ReportOrderOnGo
+where
+	C: overseer::SubsystemContext<Message=ApprovalVotingMessage,Signal=OverseerSignal,AllMessages=AllMessages>,
+{
+	fn start(self, mut ctx: C) -> SpawnedSubsystem {
+		let ReportOrderOnGo { go, expected, received } = self;
+		let mut received = Some(received);
+
+		SpawnedSubsystem {
+			name: "report-order-on-go-subsystem",
+			future: Box::pin(async move {
+				let _ = go.await;
+				let mut order = Vec::with_capacity(expected);
+				loop {
+					match ctx.recv().await {
+						Ok(FromOverseer::Signal(OverseerSignal::Conclude)) => return Ok(()),
+						Ok(FromOverseer::Communication { msg }) => {
+							order.push(matches!(msg, ApprovalVotingMessage::ApprovedAncestor(..)));
+							if order.len() == expected {
+								if let Some(received) = received.take() {
+									let _ = received.send(order.clone());
+								}
+							}
+						}
+						Ok(_) => {}
+						Err(_) => return Ok(()),
+					}
+				}
+			}),
+		}
+	}
+}
+
+fn test_approval_import_msg() -> ApprovalVotingMessage {
+	let (sender, _) = oneshot::channel();
+	let vote = polkadot_node_primitives::approval::IndirectSignedApprovalVote {
+		block_hash: Default::default(),
+		candidate_index: 0,
+		validator: ValidatorIndex(0),
+		signature: sp_core::sr25519::Pair::generate().0.sign(&[]).into(),
+	};
+	ApprovalVotingMessage::CheckAndImportApproval(vote, sender)
+}
+
+// A priority message overtakes a bounded channel full of regular messages.
+#[test]
+fn priority_message_overtakes_full_bounded_queue() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	executor::block_on(async move {
+		let (go_tx, go_rx) = oneshot::channel();
+		let (received_tx, received_rx) = oneshot::channel();
+		let (reported_tx, reported_rx) = metered::channel::<()>(64);
+		let mut reported_rx = reported_rx.fuse();
+
+		let all_subsystems = AllSubsystems::<()>::dummy()
+			.replace_approval_voting(ReportOrderOnGo {
+				go: go_rx,
+				expected: CHANNEL_CAPACITY + 1,
+				received: received_tx,
+			})
+			.replace_bitfield_distribution(ReportOnMessage(reported_tx));
+		let (overseer, mut handler) = Overseer::new(
+			vec![],
+			all_subsystems,
+			None,
+			MockSupportsParachains,
+			spawner,
+		).unwrap();
+		let overseer_fut = overseer.run().fuse();
+
+		pin_mut!(overseer_fut);
+
+		for _ in 0..CHANNEL_CAPACITY {
+			handler.send_msg_anon(test_approval_import_msg()).await;
+		}
+		handler.send_msg_anon(test_approval_voting_msg()).await;
+
+		// messages are routed in order, so all of the above are queued once this arrives
+		handler.send_msg_anon(test_bitfield_distribution_msg()).await;
+		select! {
+			_ = overseer_fut => panic!("overseer stopped unexpectedly"),
+			_ = reported_rx.next() => {},
+		}
+
+		go_tx.send(()).unwrap();
+		let order = select! {
+			_ = overseer_fut => panic!("overseer stopped unexpectedly"),
+			order = received_rx.fuse() => order.unwrap(),
+		};
+
+		assert_eq!(order.len(), CHANNEL_CAPACITY + 1);
+		assert!(order[0], "the approved ancestor query is received first");
+		assert!(order[1..].iter().all(|is_priority| !is_priority));
+
+		handler.stop().await;
+		assert!(overseer_fut.await.is_ok());
+	});
+}
+
+// The traffic of recorded subsystems ends up in the recording, in order.
+#[test]
+fn recording_subsystem_records_traffic() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let path = std::env::temp_dir().join(format!("overseer-recording-{}", std::process::id()));
+	let recorder = recording::Recorder::new(recording::RecordingConfig {
+		path: path.clone(),
+		subsystems: vec!["bitfield_distribution".to_owned()],
+	}).unwrap();
+	let writer = recorder.clone();
+
+	executor::block_on(async move {
+		let first_block_hash = [1; 32].into();
+		let first_block = BlockInfo {
+			hash: first_block_hash,
+			parent_hash: [0; 32].into(),
+			number: 1,
+		};
+
+		let (reported_tx, reported_rx) = metered::channel::<()>(64);
+		let mut reported_rx = reported_rx.fuse();
+
+		let all_subsystems = AllSubsystems::<()>::dummy()
+			.replace_bitfield_distribution(ReportOnMessage(reported_tx))
+			.map_subsystems(recording::WithRecorder(Some(recorder)));
+		let (overseer, mut handler) = Overseer::new(
+			vec![first_block],
+			all_subsystems,
+			None,
+			MockSupportsParachains,
+			spawner,
+		).unwrap();
+		let overseer_fut = overseer.run().fuse();
+
+		pin_mut!(overseer_fut);
+
+		handler.send_msg_anon(test_bitfield_distribution_msg()).await;
+		select! {
+			_ = overseer_fut => panic!("overseer stopped unexpectedly"),
+			_ = reported_rx.next() => {},
+		}
+
+		handler.stop().await;
+		assert!(overseer_fut.await.is_ok());
+		writer.flush().await;
+	});
+
+	let records = recording::read_recording(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	assert!(records.iter().all(|r| r.subsystem == "bitfield_distribution"));
+	assert_eq!(records.len(), 4);
+	assert_matches!(
+		&records[0],
+		recording::Record {
+			direction: recording::Direction::Incoming,
+			item: recording::RecordedItem::Signal(recording::RecordedSignal::ActiveLeaves { activated, .. }),
+			..
+		} => {
+			assert_eq!(activated, &vec![(Hash::repeat_byte(1), 1, false)]);
+		}
+	);
+	assert_matches!(
+		&records[1],
+		recording::Record {
+			direction: recording::Direction::Incoming,
+			item: recording::RecordedItem::Message(msg),
+			..
+		} => {
+			assert!(msg.debug.contains("PeerDisconnected"));
+			assert!(msg.encoded.is_some());
+		}
+	);
+	assert_matches!(
+		&records[2],
+		recording::Record {
+			direction: recording::Direction::Outgoing,
+			item: recording::RecordedItem::Message(msg),
+			..
+		} => {
+			assert!(msg.debug.contains("ReportPeer"));
+		}
+	);
+	assert_matches!(
+		&records[3],
+		recording::Record {
+			direction: recording::Direction::Incoming,
+			item: recording::RecordedItem::Signal(recording::RecordedSignal::Conclude),
+			..
+		}
+	);
+}
+
+// All subsystems of the overseer can be recorded.
+#[test]
+fn recording_config_is_validated() {
+	let config = |name: &str| recording::RecordingConfig {
+		path: std::env::temp_dir().join("overseer-recording-unused"),
+		subsystems: vec![name.to_owned()],
+	};
+
+	assert!(config("bitfield_distribution").validate().is_ok());
+	assert!(config("collator_protocol").validate().is_ok());
+	assert!(config("bitfield-distribution").validate().is_err());
+	assert!(recording::Recorder::new(config("no_such_subsystem")).is_err());
+}
+
+// Network bridge events of the bitfield distribution survive a roundtrip through a recording.
+#[test]
+fn recorded_bitfield_distribution_events_roundtrip() {
+	use polkadot_node_network_protocol::{ObservedRole, OurView, View};
+	use recording::RecordableMessage;
+
+	let peer = PeerId::random();
+	let events = vec![
+		NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, 2, None),
+		NetworkBridgeEvent::PeerViewChange(peer.clone(), View::new(vec![Hash::repeat_byte(1)], 1)),
+		NetworkBridgeEvent::OurViewChange(OurView::new(
+			vec![(Hash::repeat_byte(2), Arc::new(jaeger::Span::Disabled))],
+			2,
+		)),
+		NetworkBridgeEvent::PeerDisconnected(peer),
+	];
+
+	for event in events {
+		let msg = BitfieldDistributionMessage::NetworkBridgeUpdateV1(event);
+		let encoded = msg.encode_recorded().expect("network bridge events are encoded");
+		let decoded = BitfieldDistributionMessage::decode_recorded(&encoded).unwrap();
+		assert_eq!(format!("{:?}", decoded), format!("{:?}", msg));
+	}
+}
+
 struct TestSubsystem5(metered::MeteredSender<OverseerSignal>);
 
 impl<C> overseer::Subsystem<C, SubsystemError> for TestSubsystem5
@@ -812,9 +1235,9 @@ fn test_approval_voting_msg() -> ApprovalVotingMessage {
 // Checks that `stop`, `broadcast_signal` and `broadcast_message` are implemented correctly.
 #[test]
 fn overseer_all_subsystems_receive_signals_and_messages() {
-	const NUM_SUBSYSTEMS: usize = 17;
-	// -3 for BitfieldSigning, GossipSupport and AvailabilityDistribution
-	const NUM_SUBSYSTEMS_MESSAGED: usize = NUM_SUBSYSTEMS - 3;
+	const NUM_SUBSYSTEMS: usize = 18;
+	// -4 for BitfieldSigning, GossipSupport, PvfChecker and AvailabilityDistribution
+	const NUM_SUBSYSTEMS_MESSAGED: usize = NUM_SUBSYSTEMS - 4;
 
 	let spawner = sp_core::testing::TaskExecutor::new();
 	executor::block_on(async move {
@@ -846,6 +1269,7 @@ fn overseer_all_subsystems_receive_signals_and_messages() {
 			approval_distribution: subsystem.clone(),
 			approval_voting: subsystem.clone(),
 			gossip_support: subsystem.clone(),
+			pvf_checker: subsystem.clone(),
 		};
 		let (overseer, mut handler) = Overseer::new(
 			vec![],
@@ -866,7 +1290,7 @@ fn overseer_all_subsystems_receive_signals_and_messages() {
 		}).await;
 
 		// send a msg to each subsystem
-		// except for BitfieldSigning and GossipSupport as the messages are not instantiable
+		// except for BitfieldSigning, GossipSupport and PvfChecker as the messages are not instantiable
 		handler.send_msg_anon(AllMessages::CandidateValidation(test_candidate_validation_msg())).await;
 		handler.send_msg_anon(AllMessages::CandidateBacking(test_candidate_backing_msg())).await;
 		handler.send_msg_anon(AllMessages::CollationGeneration(test_collator_generation_msg())).await;
@@ -914,8 +1338,7 @@ fn overseer_all_subsystems_receive_signals_and_messages() {
 	});
 }
 
-#[test]
-fn context_holds_onto_message_until_enough_signals_received() {
+fn dummy_channels_out() -> ChannelsOut {
 	let (candidate_validation_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
 	let (candidate_backing_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
 	let (statement_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
@@ -933,6 +1356,7 @@ fn context_holds_onto_message_until_enough_signals_received() {
 	let (approval_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
 	let (approval_voting_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
 	let (gossip_support_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (pvf_checker_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
 
 	let (candidate_validation_unbounded_tx, _) = metered::unbounded();
 	let (candidate_backing_unbounded_tx, _) = metered::unbounded();
@@ -951,8 +1375,28 @@ fn context_holds_onto_message_until_enough_signals_received() {
 	let (approval_distribution_unbounded_tx, _) = metered::unbounded();
 	let (approval_voting_unbounded_tx, _) = metered::unbounded();
 	let (gossip_support_unbounded_tx, _) = metered::unbounded();
-
-	let channels_out = ChannelsOut {
+	let (pvf_checker_unbounded_tx, _) = metered::unbounded();
+
+	let (candidate_validation_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (candidate_backing_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (statement_distribution_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (availability_distribution_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (availability_recovery_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (bitfield_signing_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (bitfield_distribution_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (provisioner_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (runtime_api_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (availability_store_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (network_bridge_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (chain_api_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (collator_protocol_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (collation_generation_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (approval_distribution_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (approval_voting_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (gossip_support_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (pvf_checker_priority_tx, _) = metered::channel(CHANNEL_CAPACITY);
+
+	ChannelsOut {
 		candidate_validation: candidate_validation_bounded_tx.clone(),
 		candidate_backing: candidate_backing_bounded_tx.clone(),
 		statement_distribution: statement_distribution_bounded_tx.clone(),
@@ -970,6 +1414,7 @@ fn context_holds_onto_message_until_enough_signals_received() {
 		approval_distribution: approval_distribution_bounded_tx.clone(),
 		approval_voting: approval_voting_bounded_tx.clone(),
 		gossip_support: gossip_support_bounded_tx.clone(),
+		pvf_checker: pvf_checker_bounded_tx.clone(),
 
 		candidate_validation_unbounded: candidate_validation_unbounded_tx.clone(),
 		candidate_backing_unbounded: candidate_backing_unbounded_tx.clone(),
@@ -988,18 +1433,46 @@ fn context_holds_onto_message_until_enough_signals_received() {
 		approval_distribution_unbounded: approval_distribution_unbounded_tx.clone(),
 		approval_voting_unbounded: approval_voting_unbounded_tx.clone(),
 		gossip_support_unbounded: gossip_support_unbounded_tx.clone(),
-	};
+		pvf_checker_unbounded: pvf_checker_unbounded_tx.clone(),
+
+		candidate_validation_priority: candidate_validation_priority_tx.clone(),
+		candidate_backing_priority: candidate_backing_priority_tx.clone(),
+		statement_distribution_priority: statement_distribution_priority_tx.clone(),
+		availability_distribution_priority: availability_distribution_priority_tx.clone(),
+		availability_recovery_priority: availability_recovery_priority_tx.clone(),
+		bitfield_signing_priority: bitfield_signing_priority_tx.clone(),
+		bitfield_distribution_priority: bitfield_distribution_priority_tx.clone(),
+		provisioner_priority: provisioner_priority_tx.clone(),
+		runtime_api_priority: runtime_api_priority_tx.clone(),
+		availability_store_priority: availability_store_priority_tx.clone(),
+		network_bridge_priority: network_bridge_priority_tx.clone(),
+		chain_api_priority: chain_api_priority_tx.clone(),
+		collator_protocol_priority: collator_protocol_priority_tx.clone(),
+		collation_generation_priority: collation_generation_priority_tx.clone(),
+		approval_distribution_priority: approval_distribution_priority_tx.clone(),
+		approval_voting_priority: approval_voting_priority_tx.clone(),
+		gossip_support_priority: gossip_support_priority_tx.clone(),
+		pvf_checker_priority: pvf_checker_priority_tx.clone(),
+	}
+}
+
+#[test]
+fn context_holds_onto_message_until_enough_signals_received() {
+	let channels_out = dummy_channels_out();
 
 	let (mut signal_tx, signal_rx) = metered::channel(CHANNEL_CAPACITY);
 	let (mut bounded_tx, bounded_rx) = metered::channel(CHANNEL_CAPACITY);
 	let (unbounded_tx, unbounded_rx) = metered::unbounded();
+	let (_priority_tx, priority_rx) = metered::channel(CHANNEL_CAPACITY);
 	let (to_overseer_tx, _to_overseer_rx) = metered::unbounded();
 
 	let mut ctx = OverseerSubsystemContext::new(
-		signal_rx,
-		stream::select(bounded_rx, unbounded_rx),
+		gen::Reclaimable::new(signal_rx).0,
+		gen::Reclaimable::new(SubsystemIncomingMessages::new(priority_rx, bounded_rx, unbounded_rx)).0,
 		channels_out,
 		to_overseer_tx,
+		0,
+		SubsystemHeartbeat::default(),
 	);
 
 	assert_eq!(ctx.signals_received.load(), 0);
@@ -1034,3 +1507,78 @@ fn context_holds_onto_message_until_enough_signals_received() {
 
 	futures::executor::block_on(test_fut);
 }
+
+#[test]
+fn context_receives_priority_messages_first() {
+	let (_signal_tx, signal_rx) = metered::channel(CHANNEL_CAPACITY);
+	let (mut bounded_tx, bounded_rx) = metered::channel(CHANNEL_CAPACITY);
+	let (unbounded_tx, unbounded_rx) = metered::unbounded();
+	let (mut priority_tx, priority_rx) = metered::channel(CHANNEL_CAPACITY);
+	let (to_overseer_tx, _to_overseer_rx) = metered::unbounded();
+
+	let mut ctx = OverseerSubsystemContext::new(
+		gen::Reclaimable::new(signal_rx).0,
+		gen::Reclaimable::new(SubsystemIncomingMessages::new(priority_rx, bounded_rx, unbounded_rx)).0,
+		dummy_channels_out(),
+		to_overseer_tx,
+		0,
+		SubsystemHeartbeat::default(),
+	);
+
+	let test_fut = async move {
+		bounded_tx.send(MessagePacket { signals_received: 0, message: 1u32 }).await.unwrap();
+		unbounded_tx.unbounded_send(MessagePacket { signals_received: 0, message: 2u32 }).unwrap();
+		priority_tx.send(MessagePacket { signals_received: 0, message: 3u32 }).await.unwrap();
+		priority_tx.send(MessagePacket { signals_received: 0, message: 4u32 }).await.unwrap();
+
+		assert_matches!(ctx.recv().await.unwrap(), FromOverseer::Communication { msg: 3 });
+		assert_matches!(ctx.recv().await.unwrap(), FromOverseer::Communication { msg: 4 });
+
+		let mut regular = vec![];
+		for _ in 0..2 {
+			match ctx.recv().await.unwrap() {
+				FromOverseer::Communication { msg } => regular.push(msg),
+				FromOverseer::Signal(_) => panic!("no signal was sent"),
+			}
+		}
+		regular.sort();
+		assert_eq!(regular, vec![1, 2]);
+
+		// Only the first of both priority messages is sampled.
+		assert_eq!(priority_tx.meter().drain_time_in_queue().len(), 1);
+		assert_eq!(bounded_tx.meter().drain_time_in_queue().len(), 1);
+	};
+
+	futures::executor::block_on(test_fut);
+}
+
+#[test]
+fn subsystem_graph_contains_declared_sends() {
+	// Print the graphs with `cargo test -p polkadot-overseer subsystem_graph -- --nocapture`.
+	println!("{}", SUBSYSTEM_GRAPH_DOT);
+	println!("{}", SUBSYSTEM_GRAPH_MERMAID);
+
+	assert!(SUBSYSTEM_GRAPH_DOT.contains("\tgossip_support -> network_bridge [label=\"NetworkBridgeMessage\"];\n"));
+	assert!(SUBSYSTEM_GRAPH_DOT.contains("\tgossip_support -> runtime_api [label=\"RuntimeApiMessage\"];\n"));
+	assert!(SUBSYSTEM_GRAPH_DOT.contains("\tchain_selection [style=dashed];\n"));
+	assert!(SUBSYSTEM_GRAPH_MERMAID.contains("\tapproval_voting -- ApprovalDistributionMessage --> approval_distribution\n"));
+}
+
+#[test]
+fn senders_check_the_declared_sends() {
+	let (tx, _) = oneshot::channel();
+	let validation = AllMessages::from(CandidateValidationMessage::ValidateFromChainState(
+		Default::default(),
+		PoV { block_data: BlockData(Vec::new()) }.into(),
+		tx,
+	));
+	let network = AllMessages::from(test_network_bridge_msg());
+
+	assert!(OverseerSubsystemSender::<CandidateBackingMessage>::declares(&validation));
+	assert!(!OverseerSubsystemSender::<CandidateBackingMessage>::declares(&network));
+	assert!(OverseerSubsystemSender::<BitfieldDistributionMessage>::declares(&network));
+	assert!(!OverseerSubsystemSender::<BitfieldDistributionMessage>::declares(&validation));
+
+	// Senders which are not part of the overseer are not restricted.
+	assert!(OverseerSubsystemSender::<u32>::declares(&network));
+}
@@ -1805,3 +1805,320 @@ pub fn rococo_local_testnet_config() -> Result<RococoChainSpec, String> {
 		Default::default(),
 	))
 }
+
+/// A parachain registered at the genesis of a local testnet.
+#[derive(Clone)]
+pub struct GenesisParachain {
+	/// The id of the parachain.
+	pub id: polkadot_primitives::v1::Id,
+	/// The genesis head data.
+	pub genesis_head: Vec<u8>,
+	/// The genesis validation code.
+	pub validation_code: Vec<u8>,
+}
+
+/// The parameters of a local testnet built by [`local_testnet_config`].
+#[derive(Clone, Default)]
+pub struct LocalTestnetParams {
+	/// The seeds the keys of the validators are derived from, e.g. `Alice`.
+	pub validator_seeds: Vec<String>,
+	/// The parachains registered at genesis.
+	pub parachains: Vec<GenesisParachain>,
+	/// Values overriding the fields of the default host configuration, keyed by the field name,
+	/// e.g. `max_code_size`.
+	pub host_configuration_overrides: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LocalTestnetParams {
+	/// The accounts endowed at genesis: the well-known testnet accounts plus the stash and
+	/// controller accounts of the validators.
+	fn endowed_accounts(&self) -> Vec<AccountId> {
+		let mut accounts = testnet_accounts();
+		for seed in &self.validator_seeds {
+			let (stash, controller, ..) = get_authority_keys_from_seed_no_beefy(seed);
+			for account in vec![stash, controller] {
+				if !accounts.contains(&account) {
+					accounts.push(account);
+				}
+			}
+		}
+		accounts
+	}
+
+	fn root_key(&self) -> Result<AccountId, String> {
+		self.validator_seeds
+			.first()
+			.map(|seed| get_account_id_from_seed::<sr25519::Public>(seed))
+			.ok_or_else(|| "A local testnet needs at least one validator".into())
+	}
+
+	#[cfg(any(feature = "rococo-native", feature = "kusama-native", feature = "westend-native"))]
+	fn host_configuration(&self) -> Result<
+		polkadot_runtime_parachains::configuration::HostConfiguration<polkadot_primitives::v1::BlockNumber>,
+		String,
+	> {
+		let mut config = serde_json::to_value(default_parachains_host_configuration())
+			.map_err(|e| e.to_string())?;
+		let fields = config.as_object_mut()
+			.ok_or("The host configuration is not serialized as a map")?;
+
+		for (field, value) in &self.host_configuration_overrides {
+			match fields.get_mut(field) {
+				Some(v) => *v = value.clone(),
+				None => return Err(format!("Unknown host configuration field `{}`", field)),
+			}
+		}
+
+		serde_json::from_value(config).map_err(|e| format!("Invalid host configuration: {}", e))
+	}
+
+	#[cfg(any(feature = "rococo-native", feature = "kusama-native", feature = "westend-native"))]
+	fn genesis_paras(&self) -> Vec<(polkadot_primitives::v1::Id, polkadot_runtime_parachains::paras::ParaGenesisArgs)> {
+		self.parachains.iter().map(|para| (
+			para.id,
+			polkadot_runtime_parachains::paras::ParaGenesisArgs {
+				genesis_head: para.genesis_head.clone().into(),
+				validation_code: para.validation_code.clone().into(),
+				parachain: true,
+			},
+		)).collect()
+	}
+}
+
+/// A key to be inserted into the keystore of a validator of a local testnet.
+pub struct KeyInsertion {
+	/// The key type, e.g. `babe`.
+	pub key_type: sp_core::crypto::KeyTypeId,
+	/// The secret URI of the key.
+	pub suri: String,
+	/// The public key.
+	pub public: Vec<u8>,
+}
+
+/// Whether the local testnets of the given runtime use BEEFY. Only the Rococo runtime does.
+fn local_testnet_uses_beefy(runtime: &str) -> bool {
+	runtime == "rococo"
+}
+
+/// The session keys a validator of a local testnet of the given runtime needs in its keystore,
+/// derived the same way as by [`get_authority_keys_from_seed`].
+pub fn local_testnet_key_insertions(runtime: &str, seed: &str) -> Vec<KeyInsertion> {
+	use sp_runtime::app_crypto::AppKey;
+
+	let (_, _, babe, grandpa, im_online, para_validator, assignment, authority_discovery, beefy) =
+		get_authority_keys_from_seed(seed);
+	let suri = format!("//{}", seed);
+	let insertion = |key_type, public: Vec<u8>| KeyInsertion { key_type, suri: suri.clone(), public };
+
+	let mut insertions = vec![
+		insertion(BabeId::ID, babe.to_raw_vec()),
+		insertion(GrandpaId::ID, grandpa.to_raw_vec()),
+		insertion(ImOnlineId::ID, im_online.to_raw_vec()),
+		insertion(ValidatorId::ID, para_validator.to_raw_vec()),
+		insertion(AssignmentId::ID, assignment.to_raw_vec()),
+		insertion(AuthorityDiscoveryId::ID, authority_discovery.to_raw_vec()),
+	];
+	if local_testnet_uses_beefy(runtime) {
+		insertions.push(insertion(BeefyId::ID, beefy.to_raw_vec()));
+	}
+	insertions
+}
+
+/// Build a local testnet config of the given runtime, i.e. `polkadot`, `kusama`, `westend` or
+/// `rococo`.
+///
+/// The Polkadot runtime has no parachains, so neither genesis parachains nor host configuration
+/// overrides are supported for it.
+pub fn local_testnet_config(
+	runtime: &str,
+	params: LocalTestnetParams,
+) -> Result<Box<dyn service::ChainSpec>, String> {
+	let root_key = params.root_key()?;
+	let endowed_accounts = params.endowed_accounts();
+
+	Ok(match runtime {
+		"polkadot" => {
+			if !params.parachains.is_empty() || !params.host_configuration_overrides.is_empty() {
+				return Err("The Polkadot runtime does not support parachains".into())
+			}
+			let wasm_binary = polkadot::WASM_BINARY.ok_or("Polkadot development wasm not available")?;
+			let authorities = params.validator_seeds.iter()
+				.map(|seed| get_authority_keys_from_seed_no_beefy(seed))
+				.collect::<Vec<_>>();
+
+			Box::new(PolkadotChainSpec::from_genesis(
+				"Local Testnet",
+				"local_testnet",
+				ChainType::Local,
+				move || polkadot_testnet_genesis(
+					wasm_binary,
+					authorities.clone(),
+					root_key.clone(),
+					Some(endowed_accounts.clone()),
+				),
+				vec![],
+				None,
+				Some(DEFAULT_PROTOCOL_ID),
+				None,
+				Default::default(),
+			))
+		},
+		#[cfg(feature = "kusama-native")]
+		"kusama" => {
+			let wasm_binary = kusama::WASM_BINARY.ok_or("Kusama development wasm not available")?;
+			let authorities = params.validator_seeds.iter()
+				.map(|seed| get_authority_keys_from_seed_no_beefy(seed))
+				.collect::<Vec<_>>();
+			let host_configuration = params.host_configuration()?;
+			let paras = params.genesis_paras();
+
+			Box::new(KusamaChainSpec::from_genesis(
+				"Kusama Local Testnet",
+				"kusama_local_testnet",
+				ChainType::Local,
+				move || {
+					let mut genesis = kusama_testnet_genesis(
+						wasm_binary,
+						authorities.clone(),
+						root_key.clone(),
+						Some(endowed_accounts.clone()),
+					);
+					genesis.parachains_configuration.config = host_configuration.clone();
+					genesis.paras.paras = paras.clone();
+					genesis
+				},
+				vec![],
+				None,
+				Some(DEFAULT_PROTOCOL_ID),
+				None,
+				Default::default(),
+			))
+		},
+		#[cfg(feature = "westend-native")]
+		"westend" => {
+			let wasm_binary = westend::WASM_BINARY.ok_or("Westend development wasm not available")?;
+			let authorities = params.validator_seeds.iter()
+				.map(|seed| get_authority_keys_from_seed_no_beefy(seed))
+				.collect::<Vec<_>>();
+			let host_configuration = params.host_configuration()?;
+			let paras = params.genesis_paras();
+
+			Box::new(WestendChainSpec::from_genesis(
+				"Westend Local Testnet",
+				"westend_local_testnet",
+				ChainType::Local,
+				move || {
+					let mut genesis = westend_testnet_genesis(
+						wasm_binary,
+						authorities.clone(),
+						root_key.clone(),
+						Some(endowed_accounts.clone()),
+					);
+					genesis.parachains_configuration.config = host_configuration.clone();
+					genesis.paras.paras = paras.clone();
+					genesis
+				},
+				vec![],
+				None,
+				Some(DEFAULT_PROTOCOL_ID),
+				None,
+				Default::default(),
+			))
+		},
+		#[cfg(feature = "rococo-native")]
+		"rococo" => {
+			let wasm_binary = rococo::WASM_BINARY.ok_or("Rococo development wasm not available")?;
+			let authorities = params.validator_seeds.iter()
+				.map(|seed| get_authority_keys_from_seed(seed))
+				.collect::<Vec<_>>();
+			let host_configuration = params.host_configuration()?;
+			let paras = params.genesis_paras();
+
+			Box::new(RococoChainSpec::from_genesis(
+				"Rococo Local Testnet",
+				"rococo_local_testnet",
+				ChainType::Local,
+				move || {
+					let mut genesis = rococo_testnet_genesis(
+						wasm_binary,
+						authorities.clone(),
+						root_key.clone(),
+						Some(endowed_accounts.clone()),
+					);
+					genesis.parachains_configuration.config = host_configuration.clone();
+					genesis.paras.paras = paras.clone();
+					RococoGenesisExt {
+						runtime_genesis_config: genesis,
+						// Use 1 minute session length.
+						session_length_in_blocks: Some(10),
+					}
+				},
+				vec![],
+				None,
+				Some(DEFAULT_PROTOCOL_ID),
+				None,
+				Default::default(),
+			))
+		},
+		name => return Err(format!(
+			"Unsupported runtime `{}`, the runtime may need its `{}-native` feature to be enabled",
+			name,
+			name,
+		)),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::app_crypto::AppKey;
+
+	#[test]
+	fn only_rococo_validators_get_beefy_keys() {
+		let has_beefy_key = |runtime| local_testnet_key_insertions(runtime, "Validator1")
+			.iter()
+			.any(|insertion| insertion.key_type == BeefyId::ID);
+
+		assert!(has_beefy_key("rococo"));
+		assert!(!has_beefy_key("polkadot"));
+		assert!(!has_beefy_key("kusama"));
+		assert!(!has_beefy_key("westend"));
+	}
+
+	#[cfg(any(feature = "rococo-native", feature = "kusama-native", feature = "westend-native"))]
+	fn with_overrides(overrides: &[(&str, serde_json::Value)]) -> LocalTestnetParams {
+		LocalTestnetParams {
+			host_configuration_overrides: overrides.iter()
+				.map(|(field, value)| (field.to_string(), value.clone()))
+				.collect(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	#[cfg(any(feature = "rococo-native", feature = "kusama-native", feature = "westend-native"))]
+	fn host_configuration_applies_the_overrides() {
+		let params = with_overrides(&[
+			("max_code_size", serde_json::json!(1024)),
+			("needed_approvals", serde_json::json!(5)),
+		]);
+
+		let config = params.host_configuration().unwrap();
+		assert_eq!(config.max_code_size, 1024);
+		assert_eq!(config.needed_approvals, 5);
+		assert_eq!(config.max_pov_size, default_parachains_host_configuration().max_pov_size);
+	}
+
+	#[test]
+	#[cfg(any(feature = "rococo-native", feature = "kusama-native", feature = "westend-native"))]
+	fn host_configuration_rejects_unknown_fields_and_invalid_values() {
+		let params = with_overrides(&[("max_code_sise", serde_json::json!(1024))]);
+		assert_eq!(
+			params.host_configuration().unwrap_err(),
+			"Unknown host configuration field `max_code_sise`",
+		);
+
+		let params = with_overrides(&[("max_code_size", serde_json::json!("big"))]);
+		assert!(params.host_configuration().unwrap_err().starts_with("Invalid host configuration"));
+	}
+}
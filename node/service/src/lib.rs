@@ -26,11 +26,25 @@ mod relay_chain_selection;
 #[cfg(feature = "full-node")]
 mod overseer;
 
+#[cfg(feature = "full-node")]
+pub use self::parachains_db::{
+	check_consistency as check_parachains_db,
+	ConsistencyReport as ParachainsDbConsistencyReport,
+};
+
 #[cfg(feature = "full-node")]
 pub use self::overseer::{
 	OverseerGen,
 	OverseerGenArgs,
 	RealOverseerGen,
+	RequestRateLimits,
+	ReputationAudit,
+	ReputationAuditConfig,
+	CollationFetchingConfig,
+	RecordingConfig,
+	RuntimeApiCacheConfig,
+	TrustedCollators,
+	create_collator_subsystems,
 	create_default_subsystems,
 };
 
@@ -42,7 +56,8 @@ use {
 	polkadot_node_core_av_store::Error as AvailabilityError,
 	polkadot_node_core_approval_voting::Config as ApprovalVotingConfig,
 	polkadot_node_core_candidate_validation::Config as CandidateValidationConfig,
-	polkadot_overseer::BlockInfo,
+	polkadot_collator_protocol::ReputationConfig as CollatorReputationConfig,
+	polkadot_overseer::{BlockInfo, recording::Recorder},
 	sp_trie::PrefixedMemoryDB,
 	sc_client_api::ExecutorProvider,
 	grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider},
@@ -56,12 +71,12 @@ pub use {
 	sp_authority_discovery::AuthorityDiscoveryApi,
 	sc_client_api::AuxStore,
 	polkadot_primitives::v1::ParachainHost,
-	polkadot_overseer::{Overseer, Handle},
+	polkadot_overseer::{Overseer, Handle, SubsystemsHealth},
 };
 pub use sp_core::traits::SpawnNamed;
 
 #[cfg(feature = "full-node")]
-use polkadot_subsystem::jaeger;
+pub use polkadot_subsystem::jaeger;
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -151,6 +166,10 @@ pub enum Error {
 	#[cfg(feature = "full-node")]
 	#[error("Creating a custom database is required for validators")]
 	DatabasePathRequired,
+
+	#[cfg(feature = "full-node")]
+	#[error("The collator subsystems require a collator key")]
+	CollatorKeyRequired,
 }
 
 /// Can be called for a `Configuration` to identify which network the configuration targets.
@@ -198,16 +217,11 @@ fn set_prometheus_registry(config: &mut Configuration) -> Result<(), Error> {
 	Ok(())
 }
 
-/// Initialize the `Jeager` collector. The destination must listen
-/// on the given address and port for `UDP` packets.
+/// Initialize the `Jeager` collector. The spans are sent to the jaeger agent
+/// or the OpenTelemetry collector given in the configuration.
 #[cfg(any(test,feature = "full-node"))]
-fn jaeger_launch_collector_with_agent(spawner: impl SpawnNamed, config: &Configuration, agent: Option<std::net::SocketAddr>) -> Result<(), Error> {
-	if let Some(agent) = agent {
-		let cfg = jaeger::JaegerConfig::builder()
-			.agent(agent)
-			.named(&config.network.node_name)
-			.build();
-
+fn jaeger_launch_collector(spawner: impl SpawnNamed, jaeger_config: Option<jaeger::JaegerConfig>) -> Result<(), Error> {
+	if let Some(cfg) = jaeger_config {
 		jaeger::Jaeger::new(cfg).launch(spawner)?;
 	}
 	Ok(())
@@ -230,8 +244,10 @@ type LightClient<RuntimeApi, Executor> =
 #[cfg(feature = "full-node")]
 fn new_partial<RuntimeApi, Executor>(
 	config: &mut Configuration,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_config: Option<jaeger::JaegerConfig>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
+	reputation_audit: ReputationAudit,
+	subsystems_health: SubsystemsHealth,
 ) -> Result<
 	service::PartialComponents<
 		FullClient<RuntimeApi, Executor>, FullBackend, FullSelectChain,
@@ -296,7 +312,7 @@ fn new_partial<RuntimeApi, Executor>(
 			telemetry
 		});
 
-	jaeger_launch_collector_with_agent(task_manager.spawn_handle(), &*config, jaeger_agent)?;
+	jaeger_launch_collector(task_manager.spawn_handle(), jaeger_config)?;
 
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
@@ -405,6 +421,8 @@ fn new_partial<RuntimeApi, Executor>(
 					beefy_commitment_stream: beefy_commitment_stream.clone(),
 					subscription_executor,
 				},
+				reputation_audit: reputation_audit.clone(),
+				subsystems_health: subsystems_health.clone(),
 			};
 
 			polkadot_rpc::create_full(deps)
@@ -528,6 +546,30 @@ where
 	Ok(leaves.into_iter().rev().take(MAX_ACTIVE_LEAVES).collect())
 }
 
+/// Configuration of the parachain subsystems of a full node.
+///
+/// Everything not covered by the substrate [`Configuration`] goes here, so call sites which are
+/// fine with the defaults don't have to change when an option is added.
+#[cfg(feature = "full-node")]
+#[derive(Debug, Clone, Default)]
+pub struct NodeConfig {
+	/// Collators the validator side of the collator protocol always accepts.
+	pub trusted_collators: TrustedCollators,
+	/// How the validator side of the collator protocol shares fetches between paras.
+	pub collation_fetching: CollationFetchingConfig,
+	/// Per peer limits for incoming requests.
+	pub request_rate_limits: RequestRateLimits,
+	/// Configuration of the reputation change audit log.
+	pub reputation_audit: ReputationAuditConfig,
+	/// Record overseer messages of the given subsystems, if set.
+	pub recording: Option<RecordingConfig>,
+	/// Cache budgets of the runtime API subsystem.
+	pub runtime_api_cache: RuntimeApiCacheConfig,
+	/// Start only the subsystems a collator needs, see [`create_collator_subsystems`].
+	/// Requires the node to be a collator.
+	pub collator_subsystems_only: bool,
+}
+
 /// Create a new full node of arbitrary runtime and executor.
 ///
 /// This is an advanced feature and not recommended for general use. Generally, `build_full` is
@@ -538,9 +580,10 @@ pub fn new_full<RuntimeApi, Executor, OverseerGenerator>(
 	is_collator: IsCollator,
 	grandpa_pause: Option<(u32, u32)>,
 	disable_beefy: bool,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_config: Option<jaeger::JaegerConfig>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	program_path: Option<std::path::PathBuf>,
+	node_config: NodeConfig,
 	overseer_gen: OverseerGenerator,
 ) -> Result<NewFull<Arc<FullClient<RuntimeApi, Executor>>>, Error>
 	where
@@ -568,6 +611,20 @@ pub fn new_full<RuntimeApi, Executor, OverseerGenerator>(
 	let disable_grandpa = config.disable_grandpa;
 	let name = config.network.node_name.clone();
 
+	let NodeConfig {
+		trusted_collators,
+		collation_fetching: collation_fetching_config,
+		request_rate_limits,
+		reputation_audit: reputation_audit_config,
+		recording: recording_config,
+		runtime_api_cache: runtime_api_cache_config,
+		collator_subsystems_only,
+	} = node_config;
+
+	let reputation_audit = ReputationAudit::new(reputation_audit_config);
+	let subsystems_health = SubsystemsHealth::default();
+	let recorder = recording_config.map(Recorder::new).transpose()?;
+
 	let service::PartialComponents {
 		client,
 		backend,
@@ -577,10 +634,20 @@ pub fn new_full<RuntimeApi, Executor, OverseerGenerator>(
 		import_queue,
 		transaction_pool,
 		other: (rpc_extensions_builder, import_setup, rpc_setup, slot_duration, mut telemetry)
-	} = new_partial::<RuntimeApi, Executor>(&mut config, jaeger_agent, telemetry_worker_handle)?;
+	} = new_partial::<RuntimeApi, Executor>(
+		&mut config,
+		jaeger_config,
+		telemetry_worker_handle,
+		reputation_audit.clone(),
+		subsystems_health.clone(),
+	)?;
 
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	if let Some(ref registry) = prometheus_registry {
+		polkadot_node_metrics::runtime::register(registry)?;
+	}
+
 	let shared_voter_state = rpc_setup;
 	let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 
@@ -609,7 +676,7 @@ pub fn new_full<RuntimeApi, Executor, OverseerGenerator>(
 	let request_multiplexer = {
 		let (multiplexer, configs) = RequestMultiplexer::new();
 		config.network.request_response_protocols.extend(configs);
-		multiplexer
+		multiplexer.with_rate_limits(request_rate_limits)
 	};
 
 	let (network, system_rpc_tx, network_starter) =
@@ -623,6 +690,11 @@ pub fn new_full<RuntimeApi, Executor, OverseerGenerator>(
 			block_announce_validator_builder: None,
 		})?;
 
+	{
+		let network = network.clone();
+		subsystems_health.set_is_major_syncing(move || network.is_major_syncing());
+	}
+
 	if config.offchain_worker.enabled {
 		let _ = service::build_offchain_workers(
 			&config, task_manager.spawn_handle(), client.clone(), network.clone(),
@@ -644,6 +716,10 @@ pub fn new_full<RuntimeApi, Executor, OverseerGenerator>(
 		slot_duration_millis: slot_duration.as_millis() as u64,
 	};
 
+	let collator_reputation_config = CollatorReputationConfig {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_collator_reputation,
+	};
+
 	let candidate_validation_config = CandidateValidationConfig {
 		artifacts_cache_path: config.database
 			.path()
@@ -739,10 +815,18 @@ pub fn new_full<RuntimeApi, Executor, OverseerGenerator>(
 				network_service: network.clone(),
 				authority_discovery_service,
 				request_multiplexer,
+				reputation_audit,
+				subsystems_health,
 				registry: prometheus_registry.as_ref(),
 				spawner,
 				is_collator,
 				candidate_validation_config,
+				trusted_collators,
+				collation_fetching_config,
+				collator_reputation_config,
+				recorder,
+				runtime_api_cache_config,
+				collator_subsystems_only,
 			}
 		)?;
 		let overseer_handler_clone = overseer_handler.clone();
@@ -1113,7 +1197,7 @@ fn new_light<Runtime, Dispatch>(mut config: Configuration) -> Result<(
 #[cfg(feature = "full-node")]
 pub fn new_chain_ops(
 	mut config: &mut Configuration,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_config: Option<jaeger::JaegerConfig>,
 ) -> Result<
 	(
 		Arc<Client>,
@@ -1129,26 +1213,26 @@ pub fn new_chain_ops(
 	#[cfg(feature = "rococo-native")]
 	if config.chain_spec.is_rococo() || config.chain_spec.is_wococo() {
 		let service::PartialComponents { client, backend, import_queue, task_manager, .. }
-			= new_partial::<rococo_runtime::RuntimeApi, RococoExecutor>(config, jaeger_agent, None)?;
+			= new_partial::<rococo_runtime::RuntimeApi, RococoExecutor>(config, jaeger_config, None, Default::default(), Default::default())?;
 		return Ok((Arc::new(Client::Rococo(client)), backend, import_queue, task_manager))
 	}
 
 	#[cfg(feature = "kusama-native")]
 	if config.chain_spec.is_kusama() {
 		let service::PartialComponents { client, backend, import_queue, task_manager, .. }
-			= new_partial::<kusama_runtime::RuntimeApi, KusamaExecutor>(config, jaeger_agent, None)?;
+			= new_partial::<kusama_runtime::RuntimeApi, KusamaExecutor>(config, jaeger_config, None, Default::default(), Default::default())?;
 		return Ok((Arc::new(Client::Kusama(client)), backend, import_queue, task_manager))
 	}
 
 	#[cfg(feature = "westend-native")]
 	if config.chain_spec.is_westend() {
 		let service::PartialComponents { client, backend, import_queue, task_manager, .. }
-			= new_partial::<westend_runtime::RuntimeApi, WestendExecutor>(config, jaeger_agent, None)?;
+			= new_partial::<westend_runtime::RuntimeApi, WestendExecutor>(config, jaeger_config, None, Default::default(), Default::default())?;
 		return Ok((Arc::new(Client::Westend(client)), backend, import_queue, task_manager))
 	}
 
 	let service::PartialComponents { client, backend, import_queue, task_manager, .. }
-		= new_partial::<polkadot_runtime::RuntimeApi, PolkadotExecutor>(config, jaeger_agent, None)?;
+		= new_partial::<polkadot_runtime::RuntimeApi, PolkadotExecutor>(config, jaeger_config, None, Default::default(), Default::default())?;
 	Ok((Arc::new(Client::Polkadot(client)), backend, import_queue, task_manager))
 }
 
@@ -1183,8 +1267,9 @@ pub fn build_full(
 	is_collator: IsCollator,
 	grandpa_pause: Option<(u32, u32)>,
 	disable_beefy: bool,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_config: Option<jaeger::JaegerConfig>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
+	node_config: NodeConfig,
 	overseer_gen: impl OverseerGen,
 ) -> Result<NewFull<Client>, Error> {
 	#[cfg(feature = "rococo-native")]
@@ -1194,9 +1279,10 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			disable_beefy,
-			jaeger_agent,
+			jaeger_config,
 			telemetry_worker_handle,
 			None,
+			node_config,
 			overseer_gen,
 		).map(|full| full.with_client(Client::Rococo))
 	}
@@ -1208,9 +1294,10 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			disable_beefy,
-			jaeger_agent,
+			jaeger_config,
 			telemetry_worker_handle,
 			None,
+			node_config,
 			overseer_gen,
 		).map(|full| full.with_client(Client::Kusama))
 	}
@@ -1222,9 +1309,10 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			disable_beefy,
-			jaeger_agent,
+			jaeger_config,
 			telemetry_worker_handle,
 			None,
+			node_config,
 			overseer_gen,
 		).map(|full| full.with_client(Client::Westend))
 	}
@@ -1234,9 +1322,10 @@ pub fn build_full(
 		is_collator,
 		grandpa_pause,
 		disable_beefy,
-		jaeger_agent,
+		jaeger_config,
 		telemetry_worker_handle,
 		None,
+		node_config,
 		overseer_gen,
 	).map(|full| full.with_client(Client::Polkadot))
 }
@@ -28,7 +28,12 @@ use polkadot_network_bridge::RequestMultiplexer;
 use polkadot_node_core_av_store::Config as AvailabilityConfig;
 use polkadot_node_core_approval_voting::Config as ApprovalVotingConfig;
 use polkadot_node_core_candidate_validation::Config as CandidateValidationConfig;
-use polkadot_overseer::{AllSubsystems, BlockInfo, Overseer, Handle};
+use polkadot_collator_protocol::ReputationConfig as CollatorReputationConfig;
+use polkadot_overseer::{
+	AllSubsystems, BlockInfo, DummySubsystem, Overseer, Handle, SubsystemsHealth,
+	recording::{Recorder, WithRecorder},
+};
+pub use polkadot_overseer::recording::RecordingConfig;
 use polkadot_primitives::v1::ParachainHost;
 use sc_authority_discovery::Service as AuthorityDiscoveryService;
 use sp_api::ProvideRuntimeApi;
@@ -45,10 +50,15 @@ pub use polkadot_node_core_backing::CandidateBackingSubsystem;
 pub use polkadot_node_core_candidate_validation::CandidateValidationSubsystem;
 pub use polkadot_node_core_chain_api::ChainApiSubsystem;
 pub use polkadot_node_collation_generation::CollationGenerationSubsystem;
-pub use polkadot_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
-pub use polkadot_network_bridge::NetworkBridge as NetworkBridgeSubsystem;
+pub use polkadot_collator_protocol::{
+	CollationFetchingConfig, CollatorProtocolSubsystem, ProtocolSide, TrustedCollators,
+};
+pub use polkadot_network_bridge::{
+	NetworkBridge as NetworkBridgeSubsystem, RequestRateLimits, ReputationAudit, ReputationAuditConfig,
+};
 pub use polkadot_node_core_provisioner::ProvisioningSubsystem as ProvisionerSubsystem;
-pub use polkadot_node_core_runtime_api::RuntimeApiSubsystem;
+pub use polkadot_node_core_pvf_checker::PvfCheckerSubsystem;
+pub use polkadot_node_core_runtime_api::{CacheConfig as RuntimeApiCacheConfig, RuntimeApiSubsystem};
 pub use polkadot_statement_distribution::StatementDistribution as StatementDistributionSubsystem;
 pub use polkadot_availability_recovery::AvailabilityRecoverySubsystem;
 pub use polkadot_approval_distribution::ApprovalDistribution as ApprovalDistributionSubsystem;
@@ -79,6 +89,10 @@ pub struct OverseerGenArgs<'a, Spawner, RuntimeClient> where
 	pub authority_discovery_service: AuthorityDiscoveryService,
 	/// A multiplexer to arbitrate incoming `IncomingRequest`s from the network.
 	pub request_multiplexer: RequestMultiplexer,
+	/// Audit log for the reputation changes reported via the network bridge.
+	pub reputation_audit: ReputationAudit,
+	/// Health of the subsystems, as reported by the overseer.
+	pub subsystems_health: SubsystemsHealth,
 	/// Prometheus registry, commonly used for production systems, less so for test.
 	pub registry: Option<&'a Registry>,
 	/// Task spawner to be used throughout the overseer and the APIs it provides.
@@ -87,6 +101,18 @@ pub struct OverseerGenArgs<'a, Spawner, RuntimeClient> where
 	pub is_collator: IsCollator,
 	/// Configuration for the candidate validation subsystem.
 	pub candidate_validation_config: CandidateValidationConfig,
+	/// Per-para collators a validator accepts exclusively or fetches from first.
+	pub trusted_collators: TrustedCollators,
+	/// How a validator shares collation fetches between the paras it is assigned to.
+	pub collation_fetching_config: CollationFetchingConfig,
+	/// Configuration for the persisted collator reputations.
+	pub collator_reputation_config: CollatorReputationConfig,
+	/// Records the traffic of selected subsystems, if enabled.
+	pub recorder: Option<Recorder>,
+	/// Budgets of the runtime API result caches.
+	pub runtime_api_cache_config: RuntimeApiCacheConfig,
+	/// Start only the subsystems of [`create_collator_subsystems`]. Requires a collator.
+	pub collator_subsystems_only: bool,
 }
 
 /// Create a default, unaltered set of subsystems.
@@ -104,10 +130,15 @@ pub fn create_default_subsystems<'a, Spawner, RuntimeClient>
 		network_service,
 		authority_discovery_service,
 		request_multiplexer,
+		reputation_audit,
 		registry,
 		spawner,
 		is_collator,
 		candidate_validation_config,
+		trusted_collators,
+		collation_fetching_config,
+		collator_reputation_config,
+		runtime_api_cache_config,
 		..
 	} : OverseerGenArgs<'a, Spawner, RuntimeClient>
 ) -> Result<
@@ -129,6 +160,7 @@ pub fn create_default_subsystems<'a, Spawner, RuntimeClient>
 	ApprovalDistributionSubsystem,
 	ApprovalVotingSubsystem,
 	GossipSupportSubsystem,
+	PvfCheckerSubsystem,
 >,
 	Error
 >
@@ -185,6 +217,10 @@ where
 				IsCollator::No => ProtocolSide::Validator {
 					keystore: keystore.clone(),
 					eviction_policy: Default::default(),
+					trusted_collators,
+					fetching_config: collation_fetching_config,
+					db: parachains_db.clone(),
+					reputation_config: collator_reputation_config,
 					metrics: Metrics::register(registry)?,
 				},
 			};
@@ -197,6 +233,7 @@ where
 			authority_discovery_service.clone(),
 			request_multiplexer,
 			Box::new(network_service.clone()),
+			reputation_audit,
 			Metrics::register(registry)?,
 		),
 		provisioner: ProvisionerSubsystem::new(
@@ -204,10 +241,11 @@ where
 			(),
 			Metrics::register(registry)?,
 		),
-		runtime_api: RuntimeApiSubsystem::new(
+		runtime_api: RuntimeApiSubsystem::with_config(
 			runtime_client.clone(),
 			Metrics::register(registry)?,
 			spawner.clone(),
+			runtime_api_cache_config,
 		),
 		statement_distribution: StatementDistributionSubsystem::new(
 			keystore.clone(),
@@ -226,10 +264,142 @@ where
 		gossip_support: GossipSupportSubsystem::new(
 			keystore.clone(),
 		),
+		pvf_checker: PvfCheckerSubsystem::new(
+			keystore.clone(),
+		),
 	};
 	Ok(all_subsystems)
 }
 
+/// Create the subsystems of a collator.
+///
+/// A collator only builds, advertises and serves its own collations, so the subsystems concerned
+/// with validation, backing, availability and approval of candidates are replaced by the
+/// [`DummySubsystem`]. The availability store is kept for the PoVs of the collations.
+pub fn create_collator_subsystems<'a, Spawner, RuntimeClient>
+(
+	OverseerGenArgs {
+		runtime_client,
+		parachains_db,
+		availability_config,
+		network_service,
+		authority_discovery_service,
+		request_multiplexer,
+		reputation_audit,
+		registry,
+		spawner,
+		is_collator,
+		runtime_api_cache_config,
+		..
+	} : OverseerGenArgs<'a, Spawner, RuntimeClient>
+) -> Result<
+	AllSubsystems<
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	RuntimeApiSubsystem<RuntimeClient>,
+	AvailabilityStoreSubsystem,
+	NetworkBridgeSubsystem<Arc<sc_network::NetworkService<Block, Hash>>, AuthorityDiscoveryService>,
+	ChainApiSubsystem<RuntimeClient>,
+	CollationGenerationSubsystem,
+	CollatorProtocolSubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+>,
+	Error
+>
+where
+	RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+	RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+	Spawner: 'static + SpawnNamed + Clone + Unpin
+{
+	use polkadot_node_subsystem_util::metrics::Metrics;
+
+	let collator_pair = match is_collator {
+		IsCollator::Yes(collator_pair) => collator_pair,
+		IsCollator::No => return Err(Error::CollatorKeyRequired),
+	};
+
+	Ok(collator_subsystems(
+		RuntimeApiSubsystem::with_config(
+			runtime_client.clone(),
+			Metrics::register(registry)?,
+			spawner,
+			runtime_api_cache_config,
+		),
+		AvailabilityStoreSubsystem::new(
+			parachains_db,
+			availability_config,
+			Metrics::register(registry)?,
+		),
+		NetworkBridgeSubsystem::new(
+			network_service.clone(),
+			authority_discovery_service,
+			request_multiplexer,
+			Box::new(network_service.clone()),
+			reputation_audit,
+			Metrics::register(registry)?,
+		),
+		ChainApiSubsystem::new(
+			runtime_client,
+			Metrics::register(registry)?,
+		),
+		CollationGenerationSubsystem::new(
+			Metrics::register(registry)?,
+		),
+		CollatorProtocolSubsystem::new(
+			ProtocolSide::Collator(
+				network_service.local_peer_id().clone(),
+				collator_pair,
+				Metrics::register(registry)?,
+			),
+		),
+	))
+}
+
+/// Put the subsystems a collator runs in place, leaving the [`DummySubsystem`] everywhere else.
+fn collator_subsystems<RA, AS, NB, CA, CG, CP>(
+	runtime_api: RA,
+	availability_store: AS,
+	network_bridge: NB,
+	chain_api: CA,
+	collation_generation: CG,
+	collator_protocol: CP,
+) -> AllSubsystems<
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	RA,
+	AS,
+	NB,
+	CA,
+	CG,
+	CP,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+	DummySubsystem,
+> {
+	AllSubsystems::<()>::dummy()
+		.replace_runtime_api(runtime_api)
+		.replace_availability_store(availability_store)
+		.replace_network_bridge(network_bridge)
+		.replace_chain_api(chain_api)
+		.replace_collation_generation(collation_generation)
+		.replace_collator_protocol(collator_protocol)
+}
 
 /// Trait for the `fn` generating the overseer.
 ///
@@ -250,7 +420,8 @@ pub trait OverseerGen {
 	// as consequence make this rather annoying to implement and use.
 }
 
-/// The regular set of subsystems.
+/// The regular set of subsystems, or the reduced set of [`create_collator_subsystems`] if
+/// [`OverseerGenArgs::collator_subsystems_only`] is set.
 pub struct RealOverseerGen;
 
 impl OverseerGen for RealOverseerGen {
@@ -266,8 +437,26 @@ impl OverseerGen for RealOverseerGen {
 		let leaves = args.leaves.clone();
 		let runtime_client = args.runtime_client.clone();
 		let registry = args.registry.clone();
+		let recorder = args.recorder.clone();
+		let subsystems_health = args.subsystems_health.clone();
+
+		if args.collator_subsystems_only {
+			let all_subsystems = create_collator_subsystems::<Spawner, RuntimeClient>(args)?
+				.map_subsystems(WithRecorder(recorder));
+
+			return Overseer::new(
+				leaves,
+				all_subsystems,
+				registry,
+				runtime_client,
+				spawner,
+			)
+			.map(|(overseer, handle)| (overseer.with_health(subsystems_health), handle))
+			.map_err(|e| e.into())
+		}
 
-		let all_subsystems = create_default_subsystems::<Spawner, RuntimeClient>(args)?;
+		let all_subsystems = create_default_subsystems::<Spawner, RuntimeClient>(args)?
+			.map_subsystems(WithRecorder(recorder));
 
 		Overseer::new(
 			leaves,
@@ -275,6 +464,72 @@ impl OverseerGen for RealOverseerGen {
 			registry,
 			runtime_client,
 			spawner,
-		).map_err(|e| e.into())
+		)
+		.map(|(overseer, handle)| (overseer.with_health(subsystems_health), handle))
+		.map_err(|e| e.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_overseer::MapSubsystem;
+
+	/// Maps every subsystem to the name of its type.
+	struct TypeName;
+
+	impl<T> MapSubsystem<T> for TypeName {
+		type Output = &'static str;
+
+		fn map_subsystem(&self, _: T) -> &'static str {
+			std::any::type_name::<T>()
+		}
+	}
+
+	#[test]
+	fn collators_only_build_the_collation_subsystems() {
+		let subsystems = collator_subsystems(
+			"runtime-api",
+			"availability-store",
+			"network-bridge",
+			"chain-api",
+			"collation-generation",
+			"collator-protocol",
+		);
+		assert_eq!(
+			[
+				subsystems.runtime_api,
+				subsystems.availability_store,
+				subsystems.network_bridge,
+				subsystems.chain_api,
+				subsystems.collation_generation,
+				subsystems.collator_protocol,
+			],
+			[
+				"runtime-api",
+				"availability-store",
+				"network-bridge",
+				"chain-api",
+				"collation-generation",
+				"collator-protocol",
+			],
+		);
+
+		let types = subsystems.map_subsystems(TypeName);
+		let dummies = [
+			types.candidate_validation,
+			types.candidate_backing,
+			types.statement_distribution,
+			types.availability_distribution,
+			types.availability_recovery,
+			types.bitfield_signing,
+			types.bitfield_distribution,
+			types.provisioner,
+			types.approval_distribution,
+			types.approval_voting,
+			types.gossip_support,
+			types.pvf_checker,
+		];
+		assert!(dummies.iter().all(|name| *name == std::any::type_name::<DummySubsystem>()));
 	}
 }
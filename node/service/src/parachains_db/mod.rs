@@ -26,12 +26,16 @@ mod upgrade;
 
 #[cfg(any(test,feature = "full-node"))]
 mod columns {
-	pub const NUM_COLUMNS: u32 = 3;
+	pub mod v0 {
+		pub const NUM_COLUMNS: u32 = 3;
+	}
 
+	pub const NUM_COLUMNS: u32 = 4;
 
 	pub const COL_AVAILABILITY_DATA: u32 = 0;
 	pub const COL_AVAILABILITY_META: u32 = 1;
 	pub const COL_APPROVAL_DATA: u32 = 2;
+	pub const COL_COLLATOR_REPUTATION: u32 = 3;
 }
 
 /// Columns used by different subsystems.
@@ -44,6 +48,8 @@ pub struct ColumnsConfig {
 	pub col_availability_meta: u32,
 	/// The column used by approval voting for data.
 	pub col_approval_data: u32,
+	/// The column used by the collator protocol for collator reputations.
+	pub col_collator_reputation: u32,
 }
 
 /// The real columns used by the parachains DB.
@@ -52,8 +58,17 @@ pub const REAL_COLUMNS: ColumnsConfig = ColumnsConfig {
 	col_availability_data: columns::COL_AVAILABILITY_DATA,
 	col_availability_meta: columns::COL_AVAILABILITY_META,
 	col_approval_data: columns::COL_APPROVAL_DATA,
+	col_collator_reputation: columns::COL_COLLATOR_REPUTATION,
 };
 
+/// The subsystems keeping their data in the parachains database, with the columns they use.
+#[cfg(any(test,feature = "full-node"))]
+pub const SUBSYSTEM_COLUMNS: &[(&str, &[u32])] = &[
+	("availability store", &[columns::COL_AVAILABILITY_DATA, columns::COL_AVAILABILITY_META]),
+	("approval voting", &[columns::COL_APPROVAL_DATA]),
+	("collator protocol", &[columns::COL_COLLATOR_REPUTATION]),
+];
+
 /// The cache size for each column, in megabytes.
 #[derive(Debug, Clone)]
 pub struct CacheSizes {
@@ -63,6 +78,8 @@ pub struct CacheSizes {
 	pub availability_meta: usize,
 	/// Cache used by approval data.
 	pub approval_data: usize,
+	/// Cache used by collator reputations.
+	pub collator_reputation: usize,
 }
 
 impl Default for CacheSizes {
@@ -71,6 +88,7 @@ impl Default for CacheSizes {
 			availability_data: 25,
 			availability_meta: 1,
 			approval_data: 5,
+			collator_reputation: 1,
 		}
 	}
 }
@@ -98,6 +116,8 @@ pub fn open_creating(
 		.insert(columns::COL_AVAILABILITY_META, cache_sizes.availability_meta);
 	let _ = db_config.memory_budget
 		.insert(columns::COL_APPROVAL_DATA, cache_sizes.approval_data);
+	let _ = db_config.memory_budget
+		.insert(columns::COL_COLLATOR_REPUTATION, cache_sizes.collator_reputation);
 
 	let path_str = path.to_str().ok_or_else(|| other_io_error(
 		format!("Bad database path: {:?}", path),
@@ -109,3 +129,101 @@ pub fn open_creating(
 
 	Ok(Arc::new(db))
 }
+
+/// The inconsistencies found in the database, by subsystem.
+#[cfg(feature = "full-node")]
+#[derive(Debug, Default)]
+pub struct ConsistencyReport {
+	/// Inconsistencies of the availability store.
+	pub availability: Vec<polkadot_node_core_av_store::Inconsistency>,
+	/// Inconsistencies of the approval voting data.
+	pub approval_voting: Vec<polkadot_node_core_approval_voting::Inconsistency>,
+}
+
+#[cfg(feature = "full-node")]
+impl ConsistencyReport {
+	/// Whether no inconsistencies were found.
+	pub fn is_consistent(&self) -> bool {
+		self.availability.is_empty() && self.approval_voting.is_empty()
+	}
+
+	/// The inconsistencies found for each subsystem of [`SUBSYSTEM_COLUMNS`], or `None` if
+	/// the data of the subsystem isn't checked.
+	pub fn by_subsystem(&self) -> Vec<(&'static str, Option<Vec<String>>)> {
+		fn describe(inconsistencies: &[impl std::fmt::Display]) -> Option<Vec<String>> {
+			Some(inconsistencies.iter().map(ToString::to_string).collect())
+		}
+
+		SUBSYSTEM_COLUMNS.iter().map(|&(subsystem, subsystem_columns)| {
+			let inconsistencies = if subsystem_columns.contains(&columns::COL_AVAILABILITY_DATA) {
+				describe(&self.availability)
+			} else if subsystem_columns.contains(&columns::COL_APPROVAL_DATA) {
+				describe(&self.approval_voting)
+			} else {
+				None
+			};
+
+			(subsystem, inconsistencies)
+		}).collect()
+	}
+}
+
+/// Check the consistency of the existing database on disk, repairing or pruning the
+/// inconsistencies found if `repair` is set.
+///
+/// Only the availability store and approval voting data are checked, see
+/// [`ConsistencyReport::by_subsystem`].
+#[cfg(feature = "full-node")]
+pub fn check_consistency(
+	root: PathBuf,
+	repair: bool,
+) -> io::Result<ConsistencyReport> {
+	use kvdb_rocksdb::{DatabaseConfig, Database};
+
+	let path = root.join("parachains").join("db");
+	if !path.is_dir() {
+		return Err(other_io_error(format!("No database found at {:?}", path)));
+	}
+
+	let path_str = path.to_str().ok_or_else(|| other_io_error(
+		format!("Bad database path: {:?}", path),
+	))?;
+
+	upgrade::ensure_current_version(&path)?;
+	let db: Arc<dyn KeyValueDB> = Arc::new(
+		Database::open(&DatabaseConfig::with_columns(columns::NUM_COLUMNS), &path_str)?,
+	);
+
+	let availability_config = polkadot_node_core_av_store::Config {
+		col_data: columns::COL_AVAILABILITY_DATA,
+		col_meta: columns::COL_AVAILABILITY_META,
+	};
+	let availability = polkadot_node_core_av_store::check_consistency(&db, &availability_config, repair)
+		.map_err(|e| other_io_error(format!("Failed to check the availability store: {}", e)))?;
+
+	let approval_voting = polkadot_node_core_approval_voting::check_consistency(
+		&*db,
+		columns::COL_APPROVAL_DATA,
+		repair,
+	).map_err(|e| other_io_error(format!("Failed to check the approval voting data: {}", e)))?;
+
+	Ok(ConsistencyReport {
+		availability,
+		approval_voting,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn every_column_belongs_to_one_subsystem() {
+		let mut owned = SUBSYSTEM_COLUMNS.iter()
+			.flat_map(|(_, subsystem_columns)| subsystem_columns.iter().cloned())
+			.collect::<Vec<_>>();
+		owned.sort();
+
+		assert_eq!(owned, (0..columns::NUM_COLUMNS).collect::<Vec<_>>());
+	}
+}
@@ -27,7 +27,7 @@ type Version = u32;
 const VERSION_FILE_NAME: &'static str = "parachain_db_version";
 
 /// Current db version.
-const CURRENT_VERSION: Version = 0;
+const CURRENT_VERSION: Version = 1;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -40,6 +40,11 @@ pub enum Error {
 		current: Version,
 		got: Version,
 	},
+	#[error("Outdated version (expected {current:?}, found {got:?})")]
+	OutdatedVersion {
+		current: Version,
+		got: Version,
+	},
 }
 
 impl From<Error> for io::Error {
@@ -56,6 +61,7 @@ pub fn try_upgrade_db(db_path: &Path) -> Result<(), Error> {
 	let is_empty = db_path.read_dir().map_or(true, |mut d| d.next().is_none());
 	if !is_empty {
 		match current_version(db_path)? {
+			0 => migrate_from_version_0_to_1(db_path)?,
 			CURRENT_VERSION => (),
 			v => return Err(Error::FutureVersion {
 				current: CURRENT_VERSION,
@@ -67,6 +73,21 @@ pub fn try_upgrade_db(db_path: &Path) -> Result<(), Error> {
 	update_version(db_path)
 }
 
+/// Ensure the database at the given path is of the current version, without upgrading it.
+pub fn ensure_current_version(db_path: &Path) -> Result<(), Error> {
+	match current_version(db_path)? {
+		CURRENT_VERSION => Ok(()),
+		v if v > CURRENT_VERSION => Err(Error::FutureVersion {
+			current: CURRENT_VERSION,
+			got: v,
+		}),
+		v => Err(Error::OutdatedVersion {
+			current: CURRENT_VERSION,
+			got: v,
+		}),
+	}
+}
+
 /// Reads current database version from the file at given path.
 /// If the file does not exist, assumes version 0.
 fn current_version(path: &Path) -> Result<Version, Error> {
@@ -84,6 +105,21 @@ fn update_version(path: &Path) -> Result<(), Error> {
 	fs::write(version_file_path(path), CURRENT_VERSION.to_string()).map_err(Into::into)
 }
 
+/// Migration from version 0 to version 1:
+/// * the number of columns has changed from 3 to 4, adding the collator reputation column.
+fn migrate_from_version_0_to_1(path: &Path) -> Result<(), Error> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let db_path = path.to_str()
+		.ok_or_else(|| super::other_io_error("Invalid database path".into()))?;
+	let db_cfg = DatabaseConfig::with_columns(super::columns::v0::NUM_COLUMNS);
+	let db = Database::open(&db_cfg, db_path)?;
+
+	db.add_column()?;
+
+	Ok(())
+}
+
 /// Returns the version file path.
 fn version_file_path(path: &Path) -> PathBuf {
 	let mut file_path = path.to_owned();
@@ -41,6 +41,8 @@ use std::time::Duration;
 /// Generally useful mock data providers for unit tests.
 pub mod mock;
 
+pub mod replay;
+
 enum SinkState<T> {
 	Empty {
 		read_waker: Option<Waker>,
@@ -256,6 +258,12 @@ where
 	}
 }
 
+/// Subsystems under test may only send the messages they declare in the overseer.
+impl<M, S, X> overseer::SendsTo<X> for TestSubsystemContext<M, S>
+where
+	overseer::OverseerSubsystemContext<M>: overseer::SendsTo<X>,
+{}
+
 /// A handle for interacting with the subsystem context.
 pub struct TestSubsystemContextHandle<M> {
 	/// Direct access to sender of messages.
@@ -378,7 +386,11 @@ mod tests {
 	use polkadot_overseer::{Overseer, HeadSupportsParachains, AllSubsystems};
 	use futures::executor::block_on;
 	use polkadot_primitives::v1::Hash;
-	use polkadot_node_subsystem::messages::CollatorProtocolMessage;
+	use polkadot_node_subsystem::messages::{CollatorProtocolMessage, GossipSupportMessage};
+	use polkadot_node_subsystem::overseer::recording::{
+		Direction, Record, RecordedItem, RecordedMessage, RecordedSignal,
+	};
+	use crate::replay::{Replay, ReplayError};
 
 	struct AlwaysSupportsParachains;
 	impl HeadSupportsParachains for AlwaysSupportsParachains {
@@ -403,4 +415,64 @@ mod tests {
 		block_on(handler.send_msg_anon(CollatorProtocolMessage::CollateOn(Default::default())));
 		assert!(matches!(block_on(rx.into_future()).0.unwrap(), CollatorProtocolMessage::CollateOn(_)));
 	}
+
+	// Sends `CollateOn` for the number of every finalized block.
+	struct CollateOnFinalized;
+
+	impl<Context> overseer::Subsystem<Context, SubsystemError> for CollateOnFinalized
+	where
+		Context: SubsystemContext<Message = GossipSupportMessage> + overseer::SubsystemContext<Message = GossipSupportMessage>,
+	{
+		fn start(self, mut ctx: Context) -> SpawnedSubsystem {
+			let future = Box::pin(async move {
+				loop {
+					match ctx.recv().await {
+						Ok(FromOverseer::Signal(OverseerSignal::BlockFinalized(_, number))) => {
+							ctx.send_message(CollatorProtocolMessage::CollateOn(number.into())).await;
+						}
+						Ok(FromOverseer::Signal(OverseerSignal::Conclude)) | Err(_) => return Ok(()),
+						Ok(_) => {},
+					}
+				}
+			});
+
+			SpawnedSubsystem {
+				name: "collate-on-finalized-subsystem",
+				future,
+			}
+		}
+	}
+
+	fn finalized_records(collate_on: u32) -> Vec<Record> {
+		let record = |direction, item| Record {
+			timestamp: 0,
+			subsystem: "gossip_support".to_owned(),
+			direction,
+			item,
+		};
+		let sent = AllMessages::from(CollatorProtocolMessage::CollateOn(collate_on.into()));
+
+		vec![
+			record(Direction::Incoming, RecordedItem::Signal(RecordedSignal::BlockFinalized(Hash::repeat_byte(1), 1))),
+			record(Direction::Outgoing, RecordedItem::Message(RecordedMessage {
+				encoded: None,
+				debug: format!("{:?}", sent),
+			})),
+			record(Direction::Incoming, RecordedItem::Signal(RecordedSignal::Conclude)),
+		]
+	}
+
+	#[test]
+	fn replay_matching_recording_works() {
+		Replay::new(finalized_records(1))
+			.run::<GossipSupportMessage, _>(CollateOnFinalized)
+			.unwrap();
+	}
+
+	#[test]
+	fn replay_detects_diverging_messages() {
+		let result = Replay::new(finalized_records(2))
+			.run::<GossipSupportMessage, _>(CollateOnFinalized);
+		assert!(matches!(result, Err(ReplayError::Mismatch { index: 1, .. })));
+	}
 }
@@ -0,0 +1,214 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replay of overseer recordings into a single subsystem.
+//!
+//! The incoming signals and messages of a recording are fed into the subsystem
+//! in the recorded order, and every message it sends is compared against the
+//! recorded outgoing messages. Timestamps are not honoured, the replay runs as
+//! fast as the subsystem processes its input.
+
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use futures::{future, prelude::*};
+use sp_core::testing::TaskExecutor;
+
+use polkadot_node_subsystem::{
+	messages::AllMessages,
+	overseer::{
+		self,
+		recording::{self, Direction, Record, RecordableMessage, RecordedItem},
+	},
+	FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemError,
+};
+use polkadot_node_subsystem_util::TimeoutExt;
+
+use crate::{make_subsystem_context, TestSubsystemContext, TestSubsystemContextHandle};
+
+/// The time the subsystem gets to consume an input or produce an output.
+const STEP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Ways a replay can diverge from its recording.
+#[derive(Debug)]
+pub enum ReplayError {
+	/// A recorded incoming message can't be decoded.
+	Undecodable {
+		/// The index of the record within the records of the subsystem.
+		index: usize,
+		/// The `Debug` representation of the recorded message.
+		debug: String,
+	},
+	/// The subsystem sent a different message than recorded.
+	Mismatch {
+		/// The index of the record within the records of the subsystem.
+		index: usize,
+		/// The recorded message.
+		expected: String,
+		/// The message sent during the replay.
+		actual: String,
+	},
+	/// The subsystem sent a message which was not recorded.
+	Unexpected(String),
+	/// The subsystem neither consumed an input nor sent an expected message in time.
+	Timeout {
+		/// The index of the record within the records of the subsystem.
+		index: usize,
+	},
+	/// The subsystem exited with an error.
+	SubsystemFailed(SubsystemError),
+}
+
+/// Replays a recording into a single subsystem.
+pub struct Replay {
+	records: Vec<Record>,
+	responder: Box<dyn FnMut(AllMessages) + Send>,
+}
+
+impl Replay {
+	/// Create a replay of the given records.
+	///
+	/// Records of other subsystems than the replayed one are ignored.
+	pub fn new(records: Vec<Record>) -> Self {
+		Replay {
+			records,
+			responder: Box::new(|_| {}),
+		}
+	}
+
+	/// Create a replay from a recording file.
+	pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+		recording::read_recording(path).map(Self::new)
+	}
+
+	/// Pass every message sent by the subsystem to `responder` after it has been checked.
+	///
+	/// Responses to requests are not part of a recording, this allows answering them.
+	pub fn with_responder(mut self, responder: impl FnMut(AllMessages) + Send + 'static) -> Self {
+		self.responder = Box::new(responder);
+		self
+	}
+
+	/// Run the replay to completion.
+	///
+	/// The subsystem is concluded once all records have been replayed, unless the
+	/// recording already concludes it.
+	pub fn run<M, Sub>(self, subsystem: Sub) -> Result<(), ReplayError>
+	where
+		M: RecordableMessage + Send + 'static,
+		Sub: overseer::Subsystem<TestSubsystemContext<M, TaskExecutor>, SubsystemError>,
+	{
+		let Replay { records, mut responder } = self;
+		let records = records
+			.into_iter()
+			.filter(|record| record.subsystem == M::SUBSYSTEM)
+			.collect::<Vec<_>>();
+
+		let (context, mut handle) = make_subsystem_context::<M, _>(TaskExecutor::new());
+		let SpawnedSubsystem { future: subsystem_future, .. } = overseer::Subsystem::start(subsystem, context);
+
+		futures::executor::block_on(async move {
+			let result = {
+				let driver = drive(records, &mut handle, &mut *responder);
+				futures::pin_mut!(driver);
+
+				match future::select(driver, subsystem_future).await {
+					future::Either::Left((result, subsystem_future)) => {
+						result.and(match subsystem_future.timeout(STEP_TIMEOUT).await {
+							Some(Err(e)) => Err(ReplayError::SubsystemFailed(e)),
+							Some(Ok(())) | None => Ok(()),
+						})
+					}
+					future::Either::Right((subsystem_result, driver)) => {
+						match subsystem_result {
+							Err(e) => Err(ReplayError::SubsystemFailed(e)),
+							Ok(()) => driver.await,
+						}
+					}
+				}
+			};
+			result?;
+
+			match handle.rx.try_next() {
+				Ok(Some(msg)) => Err(ReplayError::Unexpected(format!("{:?}", msg))),
+				_ => Ok(()),
+			}
+		})
+	}
+}
+
+async fn drive<M: RecordableMessage>(
+	records: Vec<Record>,
+	handle: &mut TestSubsystemContextHandle<M>,
+	responder: &mut (dyn FnMut(AllMessages) + Send),
+) -> Result<(), ReplayError> {
+	let mut concluded = false;
+
+	for (index, record) in records.into_iter().enumerate() {
+		match (record.direction, record.item) {
+			(Direction::Incoming, item) => {
+				if concluded {
+					continue;
+				}
+
+				let from_overseer = match item {
+					RecordedItem::Signal(signal) => {
+						let signal = OverseerSignal::from(signal);
+						concluded = matches!(signal, OverseerSignal::Conclude);
+						FromOverseer::Signal(signal)
+					}
+					RecordedItem::Message(msg) => {
+						let decoded = msg.encoded.as_ref().and_then(|encoded| M::decode_recorded(encoded));
+						match decoded {
+							Some(msg) => FromOverseer::Communication { msg },
+							None => return Err(ReplayError::Undecodable { index, debug: msg.debug }),
+						}
+					}
+				};
+
+				handle.send(from_overseer)
+					.timeout(STEP_TIMEOUT)
+					.await
+					.ok_or(ReplayError::Timeout { index })?;
+			}
+			(Direction::Outgoing, RecordedItem::Message(expected)) => {
+				let msg = handle.try_recv()
+					.timeout(STEP_TIMEOUT)
+					.await
+					.flatten()
+					.ok_or(ReplayError::Timeout { index })?;
+
+				let actual = format!("{:?}", msg);
+				if actual != expected.debug {
+					return Err(ReplayError::Mismatch { index, expected: expected.debug, actual });
+				}
+
+				responder(msg);
+			}
+			// Subsystems don't send signals.
+			(Direction::Outgoing, RecordedItem::Signal(_)) => {},
+		}
+	}
+
+	if !concluded {
+		let _ = handle.send(FromOverseer::Signal(OverseerSignal::Conclude))
+			.timeout(STEP_TIMEOUT)
+			.await;
+	}
+
+	Ok(())
+}
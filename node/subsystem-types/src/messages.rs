@@ -28,14 +28,14 @@ use thiserror::Error;
 
 pub use sc_network::IfDisconnected;
 
-use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange, peer_set::PeerSet, request_response::{request::IncomingRequest, v1 as req_res_v1, Requests}, v1 as protocol_v1};
+use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange, peer_set::PeerSet, request_response::{request::IncomingRequest, v1 as req_res_v1, Requests}, v1 as protocol_v1, self as net_protocol};
 use polkadot_node_primitives::{AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig, DisputeMessage, ErasureChunk, PoV, SignedDisputeStatement, SignedFullStatement, ValidationResult, approval::{BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote}};
 use polkadot_primitives::v1::{
 	AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateDescriptor, CandidateEvent,
 	CandidateHash, CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt,
 	CoreState, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader, Id as ParaId,
 	InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet, OccupiedCoreAssumption,
-	PersistedValidationData, SessionIndex, SessionInfo, SignedAvailabilityBitfield,
+	PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo, SignedAvailabilityBitfield,
 	SignedAvailabilityBitfields, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
 	ValidatorSignature,
 };
@@ -124,6 +124,16 @@ pub enum CandidateValidationMessage {
 		Arc<PoV>,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
+	/// Try to compile the given validation code and report back whether it is fit for execution.
+	///
+	/// The code is fetched by its hash from the runtime API in the state of the given relay-parent.
+	/// This is used by the PVF pre-checking, in order to vote on a code upgrade before it is
+	/// enacted.
+	PreCheck(
+		Hash,
+		ValidationCodeHash,
+		oneshot::Sender<PreCheckOutcome>,
+	),
 }
 
 impl CandidateValidationMessage {
@@ -132,10 +142,23 @@ impl CandidateValidationMessage {
 		match self {
 			Self::ValidateFromChainState(_, _, _) => None,
 			Self::ValidateFromExhaustive(_, _, _, _, _) => None,
+			Self::PreCheck(relay_parent, _, _) => Some(*relay_parent),
 		}
 	}
 }
 
+/// The outcome of a [`CandidateValidationMessage::PreCheck`] request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreCheckOutcome {
+	/// The PVF has been compiled successfully.
+	Valid,
+	/// The PVF could not be compiled. The validator should vote against it.
+	Invalid,
+	/// The PVF could not be checked due to a local issue, such as the code not being available or
+	/// the compilation being interrupted. The validator should abstain from voting.
+	Failed,
+}
+
 /// Messages received by the Collator Protocol subsystem.
 #[derive(Debug, derive_more::From)]
 pub enum CollatorProtocolMessage {
@@ -305,7 +328,9 @@ pub enum NetworkBridgeMessage {
 	DisconnectPeer(PeerId, PeerSet),
 
 	/// Send a message to one or more peers on the validation peer-set.
-	SendValidationMessage(Vec<PeerId>, protocol_v1::ValidationProtocol),
+	///
+	/// The message is only sent to those peers which negotiated the version of the message.
+	SendValidationMessage(Vec<PeerId>, net_protocol::VersionedValidationProtocol),
 
 	/// Send a message to one or more peers on the collation peer-set.
 	SendCollationMessage(Vec<PeerId>, protocol_v1::CollationProtocol),
@@ -313,7 +338,7 @@ pub enum NetworkBridgeMessage {
 	/// Send a batch of validation messages.
 	///
 	/// NOTE: Messages will be processed in order (at least statement distribution relies on this).
-	SendValidationMessages(Vec<(Vec<PeerId>, protocol_v1::ValidationProtocol)>),
+	SendValidationMessages(Vec<(Vec<PeerId>, net_protocol::VersionedValidationProtocol)>),
 
 	/// Send a batch of collation messages.
 	///
@@ -375,6 +400,8 @@ impl NetworkBridgeMessage {
 pub enum AvailabilityDistributionMessage {
 	/// Incoming network request for an availability chunk.
 	ChunkFetchingRequest(IncomingRequest<req_res_v1::ChunkFetchingRequest>),
+	/// Incoming network request for multiple availability chunks.
+	BatchedChunkFetchingRequest(IncomingRequest<req_res_v1::BatchedChunkFetchingRequest>),
 	/// Incoming network request for a seconded PoV.
 	PoVFetchingRequest(IncomingRequest<req_res_v1::PoVFetchingRequest>),
 	/// Instruct availability distribution to fetch a remote PoV.
@@ -419,7 +446,7 @@ pub enum BitfieldDistributionMessage {
 
 	/// Event from the network bridge.
 	#[from]
-	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::BitfieldDistributionMessage>),
+	NetworkBridgeUpdateV1(NetworkBridgeEvent<net_protocol::BitfieldDistributionMessage>),
 }
 
 impl BitfieldDistributionMessage {
@@ -578,6 +605,8 @@ pub type RuntimeApiSender<T> = oneshot::Sender<Result<T, crate::errors::RuntimeA
 /// A request to the Runtime API subsystem.
 #[derive(Debug)]
 pub enum RuntimeApiRequest {
+	/// Get the version of the `ParachainHost` runtime API.
+	Version(RuntimeApiSender<u32>),
 	/// Get the next, current and some previous authority discovery set deduplicated.
 	Authorities(RuntimeApiSender<Vec<AuthorityDiscoveryId>>),
 	/// Get the current validator set.
@@ -633,6 +662,12 @@ pub enum RuntimeApiRequest {
 	),
 	/// Get information about the BABE epoch the block was included in.
 	CurrentBabeEpoch(RuntimeApiSender<BabeEpoch>),
+	/// Get the backing votes and dispute statements imported in the block.
+	FetchOnChainVotes(RuntimeApiSender<Option<ScrapedOnChainVotes>>),
+	/// Get the hashes of the validation code that are awaiting the pre-checking votes.
+	PvfsRequirePrecheck(RuntimeApiSender<Vec<ValidationCodeHash>>),
+	/// Submit a signed PVF pre-checking statement to the transaction pool.
+	SubmitPvfCheckStatement(PvfCheckStatement, ValidatorSignature, RuntimeApiSender<()>),
 }
 
 /// A message to the Runtime API subsystem.
@@ -659,7 +694,7 @@ pub enum StatementDistributionMessage {
 	Share(Hash, SignedFullStatement),
 	/// Event from the network bridge.
 	#[from]
-	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::StatementDistributionMessage>),
+	NetworkBridgeUpdateV1(NetworkBridgeEvent<net_protocol::StatementDistributionMessage>),
 	/// Get receiver for receiving incoming network requests for statement fetching.
 	StatementFetchingReceiver(mpsc::Receiver<sc_network::config::IncomingRequest>),
 }
@@ -832,7 +867,7 @@ pub enum ApprovalDistributionMessage {
 	DistributeApproval(IndirectSignedApprovalVote),
 	/// An update from the network bridge.
 	#[from]
-	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::ApprovalDistributionMessage>),
+	NetworkBridgeUpdateV1(NetworkBridgeEvent<net_protocol::ApprovalDistributionMessage>),
 }
 
 /// Message to the Gossip Support subsystem.
@@ -840,6 +875,11 @@ pub enum ApprovalDistributionMessage {
 pub enum GossipSupportMessage {
 }
 
+/// Message to the PVF Pre-Checker subsystem.
+#[derive(Debug)]
+pub enum PvfCheckerMessage {
+}
+
 impl From<IncomingRequest<req_res_v1::PoVFetchingRequest>> for AvailabilityDistributionMessage {
 	fn from(req: IncomingRequest<req_res_v1::PoVFetchingRequest>) -> Self {
 		Self::PoVFetchingRequest(req)
@@ -850,6 +890,11 @@ impl From<IncomingRequest<req_res_v1::ChunkFetchingRequest>> for AvailabilityDis
 		Self::ChunkFetchingRequest(req)
 	}
 }
+impl From<IncomingRequest<req_res_v1::BatchedChunkFetchingRequest>> for AvailabilityDistributionMessage {
+	fn from(req: IncomingRequest<req_res_v1::BatchedChunkFetchingRequest>) -> Self {
+		Self::BatchedChunkFetchingRequest(req)
+	}
+}
 impl From<IncomingRequest<req_res_v1::CollationFetchingRequest>> for CollatorProtocolMessage {
 	fn from(req: IncomingRequest<req_res_v1::CollationFetchingRequest>) -> Self {
 		Self::CollationFetchingRequest(req)
@@ -19,14 +19,16 @@ use std::collections::HashSet;
 
 pub use sc_network::{ReputationChange, PeerId};
 
-use polkadot_node_network_protocol::{WrongVariant, ObservedRole, OurView, View};
+use polkadot_node_network_protocol::{WrongVariant, ObservedRole, OurView, ProtocolVersion, View};
 use polkadot_primitives::v1::AuthorityDiscoveryId;
 
 /// Events from network.
 #[derive(Debug, Clone, PartialEq)]
 pub enum NetworkBridgeEvent<M> {
 	/// A peer has connected.
-	PeerConnected(PeerId, ObservedRole, Option<AuthorityDiscoveryId>),
+	///
+	/// Messages to the peer need to be sent with the negotiated protocol version.
+	PeerConnected(PeerId, ObservedRole, ProtocolVersion, Option<AuthorityDiscoveryId>),
 
 	/// A peer has disconnected.
 	PeerDisconnected(PeerId),
@@ -41,6 +43,8 @@ pub enum NetworkBridgeEvent<M> {
 	NewGossipTopology(HashSet<PeerId>),
 
 	/// Peer has sent a message.
+	///
+	/// On versioned peer-sets the message is tagged with the protocol version it was sent with.
 	PeerMessage(PeerId, M),
 
 	/// Peer's `View` has changed.
@@ -63,25 +67,24 @@ impl<M> NetworkBridgeEvent<M> {
 	/// `NetworkBridgeEvent<BitfieldDistributionMessage>`, with the more specific message type
 	/// `BitfieldDistributionMessage`.
 	///
-	/// This acts as a call to `clone`, except in the case where the event is a message event,
-	/// in which case the clone can be expensive and it only clones if the message type can
-	/// be focused.
-	pub fn focus<'a, T>(&'a self) -> Result<NetworkBridgeEvent<T>, WrongVariant>
-		where T: 'a + Clone, &'a T: TryFrom<&'a M, Error = WrongVariant>
+	/// Messages are matched by value, so the event is consumed. Callers dispatching one event to
+	/// several subsystems have to clone it for each of them.
+	pub fn focus<T>(self) -> Result<NetworkBridgeEvent<T>, WrongVariant>
+		where T: TryFrom<M, Error = WrongVariant>
 	{
-		Ok(match *self {
-			NetworkBridgeEvent::PeerMessage(ref peer, ref msg)
-				=> NetworkBridgeEvent::PeerMessage(peer.clone(), <&'a T>::try_from(msg)?.clone()),
-			NetworkBridgeEvent::PeerConnected(ref peer, ref role, ref authority_id)
-				=> NetworkBridgeEvent::PeerConnected(peer.clone(), role.clone(), authority_id.clone()),
-			NetworkBridgeEvent::PeerDisconnected(ref peer)
-				=> NetworkBridgeEvent::PeerDisconnected(peer.clone()),
-			NetworkBridgeEvent::NewGossipTopology(ref peers)
-				=> NetworkBridgeEvent::NewGossipTopology(peers.clone()),
-			NetworkBridgeEvent::PeerViewChange(ref peer, ref view)
-				=> NetworkBridgeEvent::PeerViewChange(peer.clone(), view.clone()),
-			NetworkBridgeEvent::OurViewChange(ref view)
-				=> NetworkBridgeEvent::OurViewChange(view.clone()),
+		Ok(match self {
+			NetworkBridgeEvent::PeerMessage(peer, msg)
+				=> NetworkBridgeEvent::PeerMessage(peer, T::try_from(msg)?),
+			NetworkBridgeEvent::PeerConnected(peer, role, version, authority_id)
+				=> NetworkBridgeEvent::PeerConnected(peer, role, version, authority_id),
+			NetworkBridgeEvent::PeerDisconnected(peer)
+				=> NetworkBridgeEvent::PeerDisconnected(peer),
+			NetworkBridgeEvent::NewGossipTopology(peers)
+				=> NetworkBridgeEvent::NewGossipTopology(peers),
+			NetworkBridgeEvent::PeerViewChange(peer, view)
+				=> NetworkBridgeEvent::PeerViewChange(peer, view),
+			NetworkBridgeEvent::OurViewChange(view)
+				=> NetworkBridgeEvent::OurViewChange(view),
 		})
 	}
 }
@@ -64,7 +64,7 @@ use polkadot_primitives::v1::{
 	CandidateEvent, CommittedCandidateReceipt, CoreState, EncodeAs, PersistedValidationData,
 	GroupRotationInfo, Hash, Id as ParaId, OccupiedCoreAssumption,
 	SessionIndex, Signed, SigningContext, ValidationCode, ValidatorId, ValidatorIndex, SessionInfo,
-AuthorityDiscoveryId, GroupIndex,
+AuthorityDiscoveryId, GroupIndex, PvfCheckStatement, ValidationCodeHash, ValidatorSignature,
 };
 use sp_core::{traits::SpawnNamed, Public};
 use sp_application_crypto::AppKey;
@@ -163,6 +163,26 @@ where
 	rx
 }
 
+/// Request some data from the `RuntimeApi` through the context of a subsystem.
+///
+/// Unlike [`request_from_runtime`], this fails to compile for subsystems which
+/// do not declare to send [`RuntimeApiMessage`]s.
+pub async fn request_from_runtime_declared<RequestBuilder, Response, Context>(
+	parent: Hash,
+	ctx: &mut Context,
+	request_builder: RequestBuilder,
+) -> RuntimeApiReceiver<Response>
+where
+	RequestBuilder: FnOnce(RuntimeApiSender<Response>) -> RuntimeApiRequest,
+	Context: SubsystemContext + overseer::SendsTo<RuntimeApiMessage>,
+{
+	let (tx, rx) = oneshot::channel();
+
+	ctx.send_declared(RuntimeApiMessage::Request(parent, request_builder(tx))).await;
+
+	rx
+}
+
 /// Construct specialized request functions for the runtime.
 ///
 /// These would otherwise get pretty repetitive.
@@ -222,6 +242,8 @@ specialize_requests! {
 	fn request_candidate_pending_availability(para_id: ParaId) -> Option<CommittedCandidateReceipt>; CandidatePendingAvailability;
 	fn request_candidate_events() -> Vec<CandidateEvent>; CandidateEvents;
 	fn request_session_info(index: SessionIndex) -> Option<SessionInfo>; SessionInfo;
+	fn request_pvfs_require_precheck() -> Vec<ValidationCodeHash>; PvfsRequirePrecheck;
+	fn request_submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature) -> (); SubmitPvfCheckStatement;
 }
 
 /// From the given set of validators, find the first key we can sign with, if any.
@@ -60,7 +60,7 @@ native_executor_instance!(
 	pub PolkadotTestExecutor,
 	polkadot_test_runtime::api::dispatch,
 	polkadot_test_runtime::native_version,
-	frame_benchmarking::benchmarking::HostFunctions,
+	(frame_benchmarking::benchmarking::HostFunctions, polkadot_runtime_metrics::HostFunctions),
 );
 
 /// The client type being used by the test service.
@@ -86,6 +86,7 @@ pub fn new_full(
 		None,
 		None,
 		worker_program_path,
+		Default::default(),
 		polkadot_service::RealOverseerGen,
 	)
 }
@@ -65,6 +65,10 @@ fn main() -> Result<()> {
 							true,
 							None,
 							None,
+							polkadot_service::NodeConfig {
+								collator_subsystems_only: true,
+								..Default::default()
+							},
 							polkadot_service::RealOverseerGen,
 						).map_err(|e| e.to_string())?;
 						let mut overseer_handler = full_node
@@ -65,11 +65,11 @@ pub use signed::{Signed, UncheckedSigned, EncodeAs};
 
 /// A declarations of storage keys where an external observer can find some interesting data.
 pub mod well_known_keys {
-	use super::{Id, HrmpChannelId};
+	use super::{Id, HrmpChannelId, ValidationCodeHash};
 	use hex_literal::hex;
 	use sp_io::hashing::twox_64;
 	use sp_std::prelude::*;
-	use parity_scale_codec::Encode as _;
+	use parity_scale_codec::{Decode as _, Encode as _};
 
 	// A note on generating these magic values below:
 	//
@@ -178,6 +178,204 @@ pub mod well_known_keys {
 				.collect()
 		})
 	}
+
+	/// The prefix of the lifecycles of all registered paras.
+	///
+	/// Each key under the prefix is followed by the `twox_64` hash of the encoded para id and the
+	/// encoded para id itself. See [`para_lifecycle`] and [`para_id_from_lifecycle_key`].
+	pub const PARA_LIFECYCLES: &[u8] =
+		&hex!["cd710b30bd2eab0352ddcc26417aa194281e0bfde17b36573208a06cb5cfba6b"];
+
+	/// The lifecycle of the given para.
+	///
+	/// The storage entry should be accessed as a `ParaLifecycle` encoded value.
+	pub fn para_lifecycle(para_id: Id) -> Vec<u8> {
+		para_id.using_encoded(|para_id: &[u8]| {
+			PARA_LIFECYCLES
+				.iter()
+				.chain(twox_64(para_id).iter())
+				.chain(para_id.iter())
+				.cloned()
+				.collect()
+		})
+	}
+
+	/// The id of the para whose lifecycle is stored under the given key.
+	///
+	/// This is the inverse of [`para_lifecycle`], for the keys found when iterating over
+	/// [`PARA_LIFECYCLES`]. Returns `None` if the key is not a lifecycle key.
+	pub fn para_id_from_lifecycle_key(key: &[u8]) -> Option<Id> {
+		// Skip the `twox_64` hash preceding the encoded para id.
+		let mut encoded = key.strip_prefix(PARA_LIFECYCLES)?.get(8..)?;
+
+		Id::decode(&mut encoded).ok().filter(|_| encoded.is_empty())
+	}
+
+	/// The current head of the given para.
+	///
+	/// The storage entry should be accessed as a `HeadData` encoded value.
+	pub fn para_head(para_id: Id) -> Vec<u8> {
+		let prefix = hex!["cd710b30bd2eab0352ddcc26417aa1941b3c252fcb29d88eff4f3de5de4476c3"];
+
+		para_id.using_encoded(|para_id: &[u8]| {
+			prefix.as_ref()
+				.iter()
+				.chain(twox_64(para_id).iter())
+				.chain(para_id.iter())
+				.cloned()
+				.collect()
+		})
+	}
+
+	/// The hash of the current validation code of the given para.
+	///
+	/// The storage entry should be accessed as a `ValidationCodeHash` encoded value.
+	pub fn current_code_hash(para_id: Id) -> Vec<u8> {
+		let prefix = hex!["cd710b30bd2eab0352ddcc26417aa194e2d1c22ba0a888147714a3487bd51c63"];
+
+		para_id.using_encoded(|para_id: &[u8]| {
+			prefix.as_ref()
+				.iter()
+				.chain(twox_64(para_id).iter())
+				.chain(para_id.iter())
+				.cloned()
+				.collect()
+		})
+	}
+
+	/// The hash of the validation code the given para is going to upgrade to.
+	///
+	/// The storage entry should be accessed as a `ValidationCodeHash` encoded value.
+	pub fn future_code_hash(para_id: Id) -> Vec<u8> {
+		let prefix = hex!["cd710b30bd2eab0352ddcc26417aa194cad3324113c312669f1ac3d50722524b"];
+
+		para_id.using_encoded(|para_id: &[u8]| {
+			prefix.as_ref()
+				.iter()
+				.chain(twox_64(para_id).iter())
+				.chain(para_id.iter())
+				.cloned()
+				.collect()
+		})
+	}
+
+	/// The relay chain block number at which the pending code upgrade of the given para is
+	/// applied.
+	///
+	/// The storage entry should be accessed as a `BlockNumber` encoded value.
+	pub fn future_code_upgrade_at(para_id: Id) -> Vec<u8> {
+		let prefix = hex!["cd710b30bd2eab0352ddcc26417aa19440a2e3ef2d6b1b7b64ce36356e02087e"];
+
+		para_id.using_encoded(|para_id: &[u8]| {
+			prefix.as_ref()
+				.iter()
+				.chain(twox_64(para_id).iter())
+				.chain(para_id.iter())
+				.cloned()
+				.collect()
+		})
+	}
+
+	/// The validation code with the given hash.
+	///
+	/// The storage entry should be accessed as a `ValidationCode` encoded value.
+	pub fn code_by_hash(code_hash: ValidationCodeHash) -> Vec<u8> {
+		let prefix = hex!["cd710b30bd2eab0352ddcc26417aa194383e6dcb39e0be0a2e6aeb8b94951ab6"];
+
+		code_hash.using_encoded(|code_hash: &[u8]| {
+			prefix.as_ref()
+				.iter()
+				.chain(code_hash.iter())
+				.cloned()
+				.collect()
+		})
+	}
+
+	/// The downward message queue of the given para.
+	///
+	/// The storage entry should be accessed as a `Vec<InboundDownwardMessage>` encoded value.
+	pub fn downward_message_queue(para_id: Id) -> Vec<u8> {
+		let prefix = hex!["63f78c98723ddc9073523ef3beefda0ca95dac46c07a40d91506e7637ec4ba57"];
+
+		para_id.using_encoded(|para_id: &[u8]| {
+			prefix.as_ref()
+				.iter()
+				.chain(twox_64(para_id).iter())
+				.chain(para_id.iter())
+				.cloned()
+				.collect()
+		})
+	}
+}
+
+/// The possible states of a para, to take into account delayed lifecycle changes.
+///
+/// If the para is in a "transition state", it is expected that the parachain is
+/// queued in the `ActionsQueue` to transition it into a stable state. Its lifecycle
+/// state will be used to determine the state transition to apply to the para.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum ParaLifecycle {
+	/// Para is new and is onboarding as a Parathread or Parachain.
+	Onboarding,
+	/// Para is a Parathread.
+	Parathread,
+	/// Para is a Parachain.
+	Parachain,
+	/// Para is a Parathread which is upgrading to a Parachain.
+	UpgradingParathread,
+	/// Para is a Parachain which is downgrading to a Parathread.
+	DowngradingParachain,
+	/// Parathread is queued to be offboarded.
+	OffboardingParathread,
+	/// Parachain is queued to be offboarded.
+	OffboardingParachain,
+}
+
+impl ParaLifecycle {
+	/// Returns true if parachain is currently onboarding. To learn if the
+	/// parachain is onboarding as a parachain or parathread, look at the
+	/// `UpcomingGenesis` storage item.
+	pub fn is_onboarding(&self) -> bool {
+		matches!(self, ParaLifecycle::Onboarding)
+	}
+
+	/// Returns true if para is in a stable state, i.e. it is currently
+	/// a parachain or parathread, and not in any transition state.
+	pub fn is_stable(&self) -> bool {
+		matches!(self, ParaLifecycle::Parathread | ParaLifecycle::Parachain)
+	}
+
+	/// Returns true if para is currently treated as a parachain.
+	/// This also includes transitioning states, so you may want to combine
+	/// this check with `is_stable` if you specifically want `Paralifecycle::Parachain`.
+	pub fn is_parachain(&self) -> bool {
+		matches!(self,
+			ParaLifecycle::Parachain |
+			ParaLifecycle::DowngradingParachain |
+			ParaLifecycle::OffboardingParachain
+		)
+	}
+
+	/// Returns true if para is currently treated as a parathread.
+	/// This also includes transitioning states, so you may want to combine
+	/// this check with `is_stable` if you specifically want `Paralifecycle::Parathread`.
+	pub fn is_parathread(&self) -> bool {
+		matches!(self,
+			ParaLifecycle::Parathread |
+			ParaLifecycle::UpgradingParathread |
+			ParaLifecycle::OffboardingParathread
+		)
+	}
+
+	/// Returns true if para is currently offboarding.
+	pub fn is_offboarding(&self) -> bool {
+		matches!(self, ParaLifecycle::OffboardingParathread | ParaLifecycle::OffboardingParachain)
+	}
+
+	/// Returns true if para is in any transitionary state.
+	pub fn is_transitioning(&self) -> bool {
+		!Self::is_stable(self)
+	}
 }
 
 
@@ -871,8 +1069,39 @@ impl ApprovalVote {
 	}
 }
 
+/// A statement from a validator on whether a pending validation code upgrade can be prepared.
+///
+/// Validators issue these statements after attempting to compile the new code with the PVF host,
+/// and the `paras` pallet only enacts the upgrade once a supermajority of the active validators
+/// accepted it.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct PvfCheckStatement {
+	/// `true` if the subject passed pre-checking and `false` otherwise.
+	pub accept: bool,
+	/// The validation code hash that was checked.
+	pub subject: ValidationCodeHash,
+	/// The index of a session during which this statement is considered valid.
+	pub session_index: SessionIndex,
+	/// The index of the validator from which this statement originates.
+	pub validator_index: ValidatorIndex,
+}
+
+impl PvfCheckStatement {
+	/// Produce the payload used for signing this type of statement.
+	pub fn signing_payload(&self) -> Vec<u8> {
+		const MAGIC: [u8; 4] = *b"VCPC";
+
+		(MAGIC, self.accept, self.subject, self.session_index, self.validator_index).encode()
+	}
+}
+
 sp_api::decl_runtime_apis! {
 	/// The API for querying the state of parachains on-chain.
+	///
+	/// Version history:
+	/// - 2: `on_chain_votes`.
+	/// - 3: `submit_pvf_check_statement` and `pvfs_require_precheck`.
+	#[api_version(3)]
 	pub trait ParachainHost<H: Decode = Hash, N: Encode + Decode = BlockNumber> {
 		/// Get the current validators.
 		fn validators() -> Vec<ValidatorId>;
@@ -930,6 +1159,25 @@ sp_api::decl_runtime_apis! {
 
 		/// Get the validation code from its hash.
 		fn validation_code_by_hash(hash: ValidationCodeHash) -> Option<ValidationCode>;
+
+		/// Get the backing votes and dispute statements imported in the current block.
+		///
+		/// Available since version 2 of the API.
+		fn on_chain_votes() -> Option<ScrapedOnChainVotes<H>>;
+
+		/// Submits a PVF pre-checking statement into the transaction pool.
+		///
+		/// NOTE: This function is meant only to be called from the offchain context.
+		///
+		/// Available since version 3 of the API.
+		fn submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature);
+
+		/// Returns the code hashes of PVFs that require pre-checking by validators in the active set.
+		///
+		/// NOTE: This function is meant only to be called from the offchain context.
+		///
+		/// Available since version 3 of the API.
+		fn pvfs_require_precheck() -> Vec<ValidationCodeHash>;
 	}
 }
 
@@ -1185,6 +1433,23 @@ pub struct DisputeStatementSet {
 /// A set of dispute statements.
 pub type MultiDisputeStatementSet = Vec<DisputeStatementSet>;
 
+/// The votes imported into a relay chain block by the parachains inherent.
+///
+/// Contains the backing votes of every candidate backed in the block, together with
+/// all dispute statements that were imported.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct ScrapedOnChainVotes<H: Encode + Decode = Hash> {
+	/// The session in which the block was included.
+	pub session: SessionIndex,
+	/// Set of backing validators for each candidate, represented by its candidate
+	/// receipt.
+	pub backing_validators_per_candidate: Vec<(CandidateReceipt<H>, Vec<(ValidatorIndex, ValidityAttestation)>)>,
+	/// On-chain-recorded set of disputes.
+	/// Note that the above `backing_validators` are
+	/// unrelated to the backers of the disputes candidates.
+	pub disputes: MultiDisputeStatementSet,
+}
+
 /// The entire state of a dispute.
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq)]
 pub struct DisputeState<N = BlockNumber> {
@@ -1298,4 +1563,14 @@ mod tests {
 		assert_eq!(supermajority_threshold(6), 5);
 		assert_eq!(supermajority_threshold(7), 5);
 	}
+
+	#[test]
+	fn para_id_from_lifecycle_key_inverts_para_lifecycle() {
+		let para_id = Id::from(2000);
+		let key = well_known_keys::para_lifecycle(para_id);
+
+		assert_eq!(well_known_keys::para_id_from_lifecycle_key(&key), Some(para_id));
+		assert_eq!(well_known_keys::para_id_from_lifecycle_key(&key[..key.len() - 1]), None);
+		assert_eq!(well_known_keys::para_id_from_lifecycle_key(&well_known_keys::para_head(para_id)), None);
+	}
 }
@@ -0,0 +1,135 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC access to the health of the subsystems.
+
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use serde::Serialize;
+
+use polkadot_overseer::{HealthReport, SubsystemsHealth};
+use polkadot_primitives::v1::{BlockNumber, Hash};
+use sc_rpc::DenyUnsafe;
+
+/// A leaf of the relay chain.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaf {
+	/// Hash of the leaf.
+	pub hash: Hash,
+	/// Number of the leaf.
+	pub number: BlockNumber,
+}
+
+/// Health of a single subsystem.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemStatus {
+	/// Name of the subsystem.
+	pub name: String,
+	/// Whether the subsystem is running and not stalled.
+	pub up: bool,
+	/// Whether the subsystem waits for its next message or signal.
+	pub waiting: bool,
+	/// Milliseconds since the subsystem last asked for or got a message or signal.
+	pub since_heartbeat: u64,
+	/// The most recently activated leaf the subsystem is done with.
+	pub last_processed_leaf: Option<Leaf>,
+	/// Whether the subsystem is done with all signals sent to it.
+	pub signals_processed: bool,
+	/// Number of signals sent to the subsystem it did not receive yet.
+	pub pending_signals: usize,
+	/// Number of messages sent to the subsystem it did not receive yet.
+	pub queue_depth: usize,
+	/// Number of times the subsystem returned an error or panicked.
+	pub failures: usize,
+}
+
+/// Aggregated health of the subsystems.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemsStatus {
+	/// Whether the node is synced and all subsystems are up and processed the current leaves.
+	pub ready: bool,
+	/// Whether the node is doing a major sync, `None` if unknown.
+	pub is_major_syncing: Option<bool>,
+	/// The most recently activated leaf.
+	pub current_leaf: Option<Leaf>,
+	/// Health of every subsystem.
+	pub subsystems: Vec<SubsystemStatus>,
+}
+
+impl From<HealthReport> for SubsystemsStatus {
+	fn from(report: HealthReport) -> Self {
+		let leaf = |(hash, number)| Leaf { hash, number };
+
+		SubsystemsStatus {
+			ready: report.is_ready(),
+			is_major_syncing: report.is_major_syncing,
+			current_leaf: report.current_leaf.map(leaf),
+			subsystems: report.subsystems.into_iter().map(|subsystem| SubsystemStatus {
+				name: subsystem.name.into(),
+				up: subsystem.up,
+				waiting: subsystem.waiting,
+				since_heartbeat: subsystem.since_heartbeat.as_millis() as u64,
+				last_processed_leaf: subsystem.last_processed_leaf.map(leaf),
+				signals_processed: subsystem.signals_processed,
+				pending_signals: subsystem.pending_signals,
+				queue_depth: subsystem.queue_depth,
+				failures: subsystem.failures,
+			}).collect(),
+		}
+	}
+}
+
+/// Subsystem health RPC methods.
+#[rpc]
+pub trait HealthApi {
+	/// The health of all subsystems.
+	#[rpc(name = "parachain_subsystemsHealth")]
+	fn subsystems_health(&self) -> Result<SubsystemsStatus>;
+
+	/// Whether the node is synced and all subsystems are up and processed the current leaves.
+	#[rpc(name = "parachain_ready")]
+	fn ready(&self) -> Result<bool>;
+}
+
+/// Implementation of the subsystem health RPC methods.
+pub struct Health {
+	health: SubsystemsHealth,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Health {
+	/// Create a new handler on top of the given health handle.
+	pub fn new(health: SubsystemsHealth, deny_unsafe: DenyUnsafe) -> Self {
+		Health { health, deny_unsafe }
+	}
+}
+
+impl HealthApi for Health {
+	fn subsystems_health(&self) -> Result<SubsystemsStatus> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.health.report().into())
+	}
+
+	fn ready(&self) -> Result<bool> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.health.report().is_ready())
+	}
+}
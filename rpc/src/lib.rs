@@ -21,6 +21,7 @@
 use std::sync::Arc;
 
 use polkadot_primitives::v0::{Block, BlockNumber, AccountId, Nonce, Balance, Hash};
+use polkadot_primitives::v1::ParachainHost;
 use sp_api::ProvideRuntimeApi;
 use txpool_api::TransactionPool;
 use sp_block_builder::BlockBuilder;
@@ -28,13 +29,25 @@ use sp_blockchain::{HeaderBackend, HeaderMetadata, Error as BlockChainError};
 use sp_consensus::SelectChain;
 use sp_consensus_babe::BabeApi;
 use sp_keystore::SyncCryptoStorePtr;
-use sc_client_api::AuxStore;
+use sc_client_api::{AuxStore, BlockchainEvents, StorageProvider};
 use sc_client_api::light::{Fetcher, RemoteBlockchain};
 use sc_consensus_babe::Epoch;
 use sc_finality_grandpa::FinalityProofProvider;
 use sc_sync_state_rpc::{SyncStateRpcApi, SyncStateRpcHandler};
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 
+mod health;
+mod para_storage;
+mod parachain;
+mod reputation;
+pub use health::{Health, HealthApi, Leaf, SubsystemStatus, SubsystemsStatus};
+pub use parachain::{
+	CandidateEventKind, CandidateEventNotification, HrmpQueueLength, Para, ParaHead, Parachain,
+	ParachainApi, PendingAvailability, QueueLengths,
+};
+pub use para_storage::{lifecycle_name, ParaStorage, ParaStorageError};
+pub use reputation::{Reputation, ReputationApi, ReputationChange};
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 
@@ -100,17 +113,23 @@ pub struct FullDeps<C, P, SC, B> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps,
+	/// Audit log of the reputation changes reported via the network bridge.
+	pub reputation_audit: polkadot_node_network_protocol::reputation_audit::ReputationAudit,
+	/// Health of the subsystems, as reported by the overseer.
+	pub subsystems_health: polkadot_overseer::SubsystemsHealth,
 }
 
 /// Instantiate all RPC extensions.
 pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> RpcExtension where
 	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore +
 		HeaderMetadata<Block, Error=BlockChainError> + Send + Sync + 'static,
+	C: StorageProvider<Block, B> + BlockchainEvents<Block>,
 	C::Api: frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: pallet_mmr_rpc::MmrRuntimeApi<Block, <Block as sp_runtime::traits::Block>::Hash>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: ParachainHost<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
@@ -132,6 +151,8 @@ pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> RpcExtension whe
 		babe,
 		grandpa,
 		beefy,
+		reputation_audit,
+		subsystems_health,
 	} = deps;
 	let BabeDeps {
 		keystore,
@@ -172,10 +193,13 @@ pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> RpcExtension whe
 			shared_authority_set.clone(),
 			shared_voter_state,
 			justification_stream,
-			subscription_executor,
+			subscription_executor.clone(),
 			finality_provider,
 		))
 	);
+	io.extend_with(
+		ParachainApi::to_delegate(Parachain::new(client.clone(), subscription_executor))
+	);
 	io.extend_with(
 		SyncStateRpcApi::to_delegate(SyncStateRpcHandler::new(
 			chain_spec,
@@ -193,6 +217,13 @@ pub fn create_full<C, P, SC, B>(deps: FullDeps<C, P, SC, B>) -> RpcExtension whe
 		),
 	));
 
+	io.extend_with(
+		ReputationApi::to_delegate(Reputation::new(reputation_audit, deny_unsafe))
+	);
+	io.extend_with(
+		HealthApi::to_delegate(Health::new(subsystems_health, deny_unsafe))
+	);
+
 	io
 }
 
@@ -0,0 +1,117 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reading the state of the paras from the well known storage keys, without executing the runtime.
+
+use std::{fmt, marker::PhantomData};
+
+use parity_scale_codec::Decode;
+use polkadot_primitives::v1::{well_known_keys, Block, Id as ParaId, ParaLifecycle};
+use sc_client_api::{Backend, StorageProvider};
+use sp_core::{hexdisplay::HexDisplay, storage::StorageKey};
+use sp_runtime::generic::BlockId;
+
+/// The human readable name of a lifecycle, e.g. `parachain` or `upgradingParathread`.
+pub fn lifecycle_name(lifecycle: &ParaLifecycle) -> &'static str {
+	match lifecycle {
+		ParaLifecycle::Onboarding => "onboarding",
+		ParaLifecycle::Parathread => "parathread",
+		ParaLifecycle::Parachain => "parachain",
+		ParaLifecycle::UpgradingParathread => "upgradingParathread",
+		ParaLifecycle::DowngradingParachain => "downgradingParachain",
+		ParaLifecycle::OffboardingParathread => "offboardingParathread",
+		ParaLifecycle::OffboardingParachain => "offboardingParachain",
+	}
+}
+
+/// An error reading the state of the paras.
+#[derive(Debug)]
+pub enum ParaStorageError {
+	/// The client failed to read the storage.
+	Client(sp_blockchain::Error),
+	/// A storage entry couldn't be decoded.
+	MalformedEntry {
+		/// The key of the entry.
+		key: Vec<u8>,
+		/// The decoding error.
+		error: parity_scale_codec::Error,
+	},
+	/// A key under the lifecycle prefix doesn't end with a para id.
+	MalformedLifecycleKey(Vec<u8>),
+}
+
+impl fmt::Display for ParaStorageError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ParaStorageError::Client(e) => write!(f, "{}", e),
+			ParaStorageError::MalformedEntry { key, error } =>
+				write!(f, "Malformed storage entry 0x{}: {}", HexDisplay::from(key), error),
+			ParaStorageError::MalformedLifecycleKey(key) =>
+				write!(f, "Malformed lifecycle key 0x{}", HexDisplay::from(key)),
+		}
+	}
+}
+
+impl std::error::Error for ParaStorageError {}
+
+/// Reads the storage of the paras at a given block.
+pub struct ParaStorage<'a, C, B> {
+	client: &'a C,
+	at: BlockId<Block>,
+	_marker: PhantomData<B>,
+}
+
+impl<'a, C, B> ParaStorage<'a, C, B>
+where
+	C: StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	/// Read the storage of the given client at the given block.
+	pub fn new(client: &'a C, at: BlockId<Block>) -> Self {
+		ParaStorage { client, at, _marker: PhantomData }
+	}
+
+	/// Read and decode the entry at the given key, if any.
+	pub fn get<T: Decode>(&self, key: Vec<u8>) -> Result<Option<T>, ParaStorageError> {
+		let data = self.client.storage(&self.at, &StorageKey(key.clone()))
+			.map_err(ParaStorageError::Client)?;
+
+		data.map(|data| T::decode(&mut &data.0[..]))
+			.transpose()
+			.map_err(|error| ParaStorageError::MalformedEntry { key, error })
+	}
+
+	/// All registered paras with their lifecycle, in ascending order of their ids.
+	pub fn lifecycles(&self) -> Result<Vec<(ParaId, ParaLifecycle)>, ParaStorageError> {
+		let prefix = StorageKey(well_known_keys::PARA_LIFECYCLES.to_vec());
+
+		let mut lifecycles = self.client.storage_pairs(&self.at, &prefix)
+			.map_err(ParaStorageError::Client)?
+			.into_iter()
+			.map(|(key, value)| {
+				let para_id = well_known_keys::para_id_from_lifecycle_key(&key.0)
+					.ok_or_else(|| ParaStorageError::MalformedLifecycleKey(key.0.clone()))?;
+				let lifecycle = ParaLifecycle::decode(&mut &value.0[..])
+					.map_err(|error| ParaStorageError::MalformedEntry { key: key.0, error })?;
+
+				Ok((para_id, lifecycle))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		lifecycles.sort_by_key(|(para_id, _)| *para_id);
+
+		Ok(lifecycles)
+	}
+}
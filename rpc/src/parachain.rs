@@ -0,0 +1,499 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC access to the state of the parachains, as seen by the relay chain.
+//!
+//! Heads, code hashes and lifecycles are read from the well known storage keys of the `Paras`
+//! module, everything else is queried through the [`ParachainHost`] runtime API.
+
+use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+
+use futures::{StreamExt, TryStreamExt};
+use jsonrpc_core::{
+	futures::{Future as _, Sink as _},
+	Error, ErrorCode, Result,
+};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use serde::Serialize;
+
+use polkadot_primitives::v1::{
+	well_known_keys, Block, BlockNumber, CandidateEvent, CoreState, Hash, HeadData, Id as ParaId,
+	ParachainHost,
+};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_rpc::{Metadata, SubscriptionTaskExecutor};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::generic::BlockId;
+
+use crate::para_storage::{lifecycle_name, ParaStorage};
+
+const LOG_TARGET: &str = "rpc::parachain";
+
+/// A registered para.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Para {
+	/// The id of the para.
+	pub para_id: u32,
+	/// The lifecycle of the para, e.g. `parachain` or `upgradingParathread`.
+	pub lifecycle: &'static str,
+}
+
+/// The current head of a para.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParaHead {
+	/// The encoded head data.
+	pub head: Bytes,
+	/// The hash of the current validation code, if any.
+	pub code_hash: Option<Hash>,
+}
+
+/// A candidate occupying an availability core.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingAvailability {
+	/// The index of the occupied core.
+	pub core: u32,
+	/// The para the candidate belongs to.
+	pub para_id: u32,
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The relay parent of the candidate.
+	pub relay_parent: Hash,
+	/// The group responsible for distributing the availability pieces.
+	pub group_responsible: u32,
+	/// The number of validators that attested to the availability on-chain.
+	pub available: u32,
+	/// The number of validators in the set.
+	pub validators: u32,
+	/// The block number the core began being occupied at.
+	pub occupied_since: BlockNumber,
+	/// The block number the candidate times out at.
+	pub time_out_at: BlockNumber,
+}
+
+/// The length of the inbound HRMP channel from a sender.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HrmpQueueLength {
+	/// The sender of the channel.
+	pub sender: u32,
+	/// The number of messages in the channel.
+	pub length: u32,
+}
+
+/// The lengths of the message queues addressed to a para.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueLengths {
+	/// The number of messages in the downward message queue.
+	pub downward: u32,
+	/// The number of messages in each inbound HRMP channel.
+	pub inbound_hrmp: Vec<HrmpQueueLength>,
+}
+
+/// The kind of a [`CandidateEventNotification`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CandidateEventKind {
+	/// The candidate was backed.
+	Backed,
+	/// The candidate was included and became a parablock.
+	Included,
+	/// The candidate was not made available in time and timed out.
+	TimedOut,
+}
+
+/// A candidate event that occurred within an imported block.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateEventNotification {
+	/// The block the event occurred in.
+	pub block_hash: Hash,
+	/// What happened to the candidate.
+	pub kind: CandidateEventKind,
+	/// The para the candidate belongs to.
+	pub para_id: u32,
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The core the candidate occupies.
+	pub core: u32,
+	/// The group backing the candidate, unknown for timed out candidates.
+	pub group: Option<u32>,
+}
+
+impl CandidateEventNotification {
+	fn new(block_hash: Hash, event: CandidateEvent) -> Self {
+		let (kind, receipt, core, group) = match event {
+			CandidateEvent::CandidateBacked(receipt, _, core, group) =>
+				(CandidateEventKind::Backed, receipt, core, Some(group)),
+			CandidateEvent::CandidateIncluded(receipt, _, core, group) =>
+				(CandidateEventKind::Included, receipt, core, Some(group)),
+			CandidateEvent::CandidateTimedOut(receipt, _, core) =>
+				(CandidateEventKind::TimedOut, receipt, core, None),
+		};
+
+		CandidateEventNotification {
+			block_hash,
+			kind,
+			para_id: receipt.descriptor.para_id.into(),
+			candidate_hash: receipt.hash().0,
+			core: core.0,
+			group: group.map(|g| g.0),
+		}
+	}
+}
+
+/// Parachain RPC methods.
+#[rpc]
+pub trait ParachainApi {
+	/// RPC metadata
+	type Metadata;
+
+	/// All registered paras with their lifecycle.
+	#[rpc(name = "parachain_paras")]
+	fn paras(&self, at: Option<Hash>) -> Result<Vec<Para>>;
+
+	/// The current head and validation code hash of a para.
+	#[rpc(name = "parachain_head")]
+	fn head(&self, para_id: u32, at: Option<Hash>) -> Result<Option<ParaHead>>;
+
+	/// The candidates pending availability, per occupied core.
+	#[rpc(name = "parachain_pendingAvailability")]
+	fn pending_availability(&self, at: Option<Hash>) -> Result<Vec<PendingAvailability>>;
+
+	/// The lengths of the downward and inbound HRMP message queues of a para.
+	#[rpc(name = "parachain_queueLengths")]
+	fn queue_lengths(&self, para_id: u32, at: Option<Hash>) -> Result<QueueLengths>;
+
+	/// Subscribe to the candidate events of the imported blocks, optionally of a single para.
+	#[pubsub(
+		subscription = "parachain_candidateEvents",
+		subscribe,
+		name = "parachain_subscribeCandidateEvents"
+	)]
+	fn subscribe_candidate_events(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<CandidateEventNotification>,
+		para_id: Option<u32>,
+	);
+
+	/// Unsubscribe from the candidate events.
+	#[pubsub(
+		subscription = "parachain_candidateEvents",
+		unsubscribe,
+		name = "parachain_unsubscribeCandidateEvents"
+	)]
+	fn unsubscribe_candidate_events(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool>;
+}
+
+fn internal_error(e: impl Debug) -> Error {
+	Error {
+		code: ErrorCode::InternalError,
+		message: format!("{:?}", e),
+		data: None,
+	}
+}
+
+/// Implementation of the parachain RPC methods.
+pub struct Parachain<C, B> {
+	client: Arc<C>,
+	manager: SubscriptionManager,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> Parachain<C, B> {
+	/// Create a new handler on top of the given client.
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Parachain {
+			client,
+			manager: SubscriptionManager::new(Arc::new(executor)),
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<C, B> Parachain<C, B> where
+	C: HeaderBackend<Block> + StorageProvider<Block, B>,
+	B: Backend<Block>,
+{
+	fn block_id(&self, at: Option<Hash>) -> BlockId<Block> {
+		BlockId::Hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+
+	fn para_storage(&self, at: Option<Hash>) -> ParaStorage<C, B> {
+		ParaStorage::new(&*self.client, self.block_id(at))
+	}
+}
+
+impl<C, B> ParachainApi for Parachain<C, B> where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+	C: BlockchainEvents<Block> + Send + Sync + 'static,
+	C::Api: ParachainHost<Block>,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	type Metadata = Metadata;
+
+	fn paras(&self, at: Option<Hash>) -> Result<Vec<Para>> {
+		let lifecycles = self.para_storage(at).lifecycles().map_err(internal_error)?;
+
+		Ok(lifecycles.into_iter()
+			.map(|(para_id, lifecycle)| Para { para_id: para_id.into(), lifecycle: lifecycle_name(&lifecycle) })
+			.collect())
+	}
+
+	fn head(&self, para_id: u32, at: Option<Hash>) -> Result<Option<ParaHead>> {
+		let storage = self.para_storage(at);
+		let para_id = ParaId::from(para_id);
+
+		let head = match storage.get::<HeadData>(well_known_keys::para_head(para_id)).map_err(internal_error)? {
+			Some(head) => head,
+			None => return Ok(None),
+		};
+		// `ValidationCodeHash` is encoded as the plain hash.
+		let code_hash = storage.get::<Hash>(well_known_keys::current_code_hash(para_id))
+			.map_err(internal_error)?;
+
+		Ok(Some(ParaHead { head: head.0.into(), code_hash }))
+	}
+
+	fn pending_availability(&self, at: Option<Hash>) -> Result<Vec<PendingAvailability>> {
+		let at = self.block_id(at);
+		let cores = self.client.runtime_api().availability_cores(&at).map_err(internal_error)?;
+
+		Ok(cores.into_iter().enumerate().filter_map(|(core, state)| match state {
+			CoreState::Occupied(occupied) => Some(PendingAvailability {
+				core: core as u32,
+				para_id: occupied.para_id().into(),
+				candidate_hash: occupied.candidate_hash.0,
+				relay_parent: occupied.candidate_descriptor.relay_parent,
+				group_responsible: occupied.group_responsible.0,
+				available: occupied.availability.count_ones() as u32,
+				validators: occupied.availability.len() as u32,
+				occupied_since: occupied.occupied_since,
+				time_out_at: occupied.time_out_at,
+			}),
+			CoreState::Scheduled(_) | CoreState::Free => None,
+		}).collect())
+	}
+
+	fn queue_lengths(&self, para_id: u32, at: Option<Hash>) -> Result<QueueLengths> {
+		let at = self.block_id(at);
+		let para_id = ParaId::from(para_id);
+		let api = self.client.runtime_api();
+
+		let downward = api.dmq_contents(&at, para_id).map_err(internal_error)?;
+		let inbound_hrmp = api.inbound_hrmp_channels_contents(&at, para_id).map_err(internal_error)?;
+
+		Ok(QueueLengths {
+			downward: downward.len() as u32,
+			inbound_hrmp: inbound_hrmp.into_iter().map(|(sender, messages)| HrmpQueueLength {
+				sender: sender.into(),
+				length: messages.len() as u32,
+			}).collect(),
+		})
+	}
+
+	fn subscribe_candidate_events(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<CandidateEventNotification>,
+		para_id: Option<u32>,
+	) {
+		let client = self.client.clone();
+		let stream = self.client.import_notification_stream()
+			.flat_map(move |notification| {
+				let events = client.runtime_api()
+					.candidate_events(&BlockId::Hash(notification.hash))
+					.unwrap_or_else(|e| {
+						log::warn!(
+							target: LOG_TARGET,
+							"Failed to fetch the candidate events of {}: {:?}",
+							notification.hash,
+							e,
+						);
+						Vec::new()
+					})
+					.into_iter()
+					.map(|event| CandidateEventNotification::new(notification.hash, event))
+					.filter(|event| para_id.map_or(true, |para_id| para_id == event.para_id))
+					.collect::<Vec<_>>();
+
+				futures::stream::iter(events)
+			})
+			.map(|event| Ok::<_, ()>(Ok(event)))
+			.boxed()
+			.compat();
+
+		self.manager.add(subscriber, |sink| {
+			sink.sink_map_err(|e| log::warn!(target: LOG_TARGET, "Error sending candidate events: {:?}", e))
+				.send_all(stream)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_candidate_events(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool> {
+		Ok(self.manager.cancel(id))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpc_core::{futures::Future as _, MetaIoHandler};
+	use parity_scale_codec::Encode;
+	use polkadot_primitives::v1::{
+		CandidateReceipt, CoreIndex, GroupIndex, InboundDownwardMessage, ParaLifecycle,
+	};
+	use polkadot_test_client::{
+		DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+	};
+	use sp_core::{hashing::{twox_128, twox_64}, testing::TaskExecutor};
+
+	fn io_handler(builder: TestClientBuilder) -> MetaIoHandler<Metadata> {
+		let client = Arc::new(builder.build());
+		let executor = SubscriptionTaskExecutor::new(TaskExecutor::new());
+
+		let mut io = MetaIoHandler::default();
+		io.extend_with(ParachainApi::to_delegate(Parachain::new(client, executor)));
+		io
+	}
+
+	// The storage key of the downward message queue of a para.
+	fn downward_message_queue(para_id: ParaId) -> Vec<u8> {
+		let para_id = para_id.encode();
+		[
+			&twox_128(b"Dmp")[..],
+			&twox_128(b"DownwardMessageQueues")[..],
+			&twox_64(&para_id)[..],
+			&para_id[..],
+		].concat()
+	}
+
+	#[test]
+	fn paras_are_read_from_the_lifecycle_keys() {
+		let io = io_handler(TestClientBuilder::new().add_extra_storage(
+			well_known_keys::para_lifecycle(2000.into()),
+			ParaLifecycle::UpgradingParathread.encode(),
+		));
+
+		let request = r#"{"jsonrpc":"2.0","method":"parachain_paras","params":[],"id":1}"#;
+		let response = r#"{"jsonrpc":"2.0","result":[{"paraId":2000,"lifecycle":"upgradingParathread"}],"id":1}"#;
+
+		assert_eq!(io.handle_request_sync(request, Metadata::default()), Some(response.into()));
+	}
+
+	#[test]
+	fn heads_are_read_from_the_head_keys() {
+		let io = io_handler(TestClientBuilder::new().add_extra_storage(
+			well_known_keys::para_head(2000.into()),
+			HeadData(vec![1, 2, 3]).encode(),
+		));
+
+		let request = r#"{"jsonrpc":"2.0","method":"parachain_head","params":[2000],"id":1}"#;
+		let response = r#"{"jsonrpc":"2.0","result":{"head":"0x010203","codeHash":null},"id":1}"#;
+		assert_eq!(io.handle_request_sync(request, Metadata::default()), Some(response.into()));
+
+		let request = r#"{"jsonrpc":"2.0","method":"parachain_head","params":[3000],"id":1}"#;
+		let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+		assert_eq!(io.handle_request_sync(request, Metadata::default()), Some(response.into()));
+	}
+
+	#[test]
+	fn no_candidates_are_pending_availability_at_genesis() {
+		let io = io_handler(TestClientBuilder::new());
+
+		let request = r#"{"jsonrpc":"2.0","method":"parachain_pendingAvailability","params":[],"id":1}"#;
+		let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+		assert_eq!(io.handle_request_sync(request, Metadata::default()), Some(response.into()));
+	}
+
+	#[test]
+	fn queue_lengths_count_the_queued_messages() {
+		let messages = vec![
+			InboundDownwardMessage { sent_at: 0, msg: vec![1] },
+			InboundDownwardMessage { sent_at: 0, msg: vec![2, 3] },
+		];
+		let io = io_handler(TestClientBuilder::new().add_extra_storage(
+			downward_message_queue(2000.into()),
+			messages.encode(),
+		));
+
+		let request = r#"{"jsonrpc":"2.0","method":"parachain_queueLengths","params":[2000],"id":1}"#;
+		let response = r#"{"jsonrpc":"2.0","result":{"downward":2,"inboundHrmp":[]},"id":1}"#;
+		assert_eq!(io.handle_request_sync(request, Metadata::default()), Some(response.into()));
+
+		let request = r#"{"jsonrpc":"2.0","method":"parachain_queueLengths","params":[3000],"id":1}"#;
+		let response = r#"{"jsonrpc":"2.0","result":{"downward":0,"inboundHrmp":[]},"id":1}"#;
+		assert_eq!(io.handle_request_sync(request, Metadata::default()), Some(response.into()));
+	}
+
+	#[test]
+	fn candidate_event_subscriptions_can_be_cancelled() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let api = Parachain::new(client, SubscriptionTaskExecutor::new(TaskExecutor::new()));
+
+		let (subscriber, id, _notifications) = Subscriber::new_test("parachain_subscribeCandidateEvents");
+		api.subscribe_candidate_events(Metadata::default(), subscriber, Some(2000));
+
+		let id = id.wait().unwrap().unwrap();
+		assert!(api.unsubscribe_candidate_events(None, id.clone()).unwrap());
+		assert!(!api.unsubscribe_candidate_events(None, id).unwrap());
+	}
+
+	#[test]
+	fn candidate_events_are_notified_with_their_kind() {
+		let block_hash = Hash::repeat_byte(1);
+		let mut receipt = CandidateReceipt::default();
+		receipt.descriptor.para_id = 2000.into();
+
+		let backed = CandidateEventNotification::new(block_hash, CandidateEvent::CandidateBacked(
+			receipt.clone(),
+			HeadData::default(),
+			CoreIndex(1),
+			GroupIndex(2),
+		));
+		assert_eq!(backed.kind, CandidateEventKind::Backed);
+		assert_eq!(backed.para_id, 2000);
+		assert_eq!(backed.candidate_hash, receipt.hash().0);
+		assert_eq!((backed.core, backed.group), (1, Some(2)));
+
+		let timed_out = CandidateEventNotification::new(block_hash, CandidateEvent::CandidateTimedOut(
+			receipt,
+			HeadData::default(),
+			CoreIndex(1),
+		));
+		assert_eq!(timed_out.kind, CandidateEventKind::TimedOut);
+		assert_eq!(timed_out.group, None);
+		assert_eq!(
+			jsonrpc_core::serde_json::to_value(&timed_out).unwrap()["kind"],
+			"timedOut",
+		);
+	}
+}
@@ -0,0 +1,87 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC access to the reputation audit log of the network bridge.
+
+use std::str::FromStr;
+
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::Serialize;
+
+use polkadot_node_network_protocol::{reputation_audit::ReputationAudit, PeerId};
+use sc_rpc::DenyUnsafe;
+
+/// A reputation change reported for a peer.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReputationChange {
+	/// Reason given for the change.
+	pub reason: String,
+	/// The change of the reputation.
+	pub value: i32,
+	/// Milliseconds since the unix epoch at which the change was reported.
+	pub timestamp: u64,
+}
+
+/// Reputation audit RPC methods.
+#[rpc]
+pub trait ReputationApi {
+	/// Recent reputation changes of a peer, oldest first.
+	#[rpc(name = "parachain_peerReputationChanges")]
+	fn peer_reputation_changes(&self, peer_id: String) -> Result<Vec<ReputationChange>>;
+
+	/// Peers with recorded reputation changes.
+	#[rpc(name = "parachain_reputationChangedPeers")]
+	fn reputation_changed_peers(&self) -> Result<Vec<String>>;
+}
+
+/// Implementation of the reputation audit RPC methods.
+pub struct Reputation {
+	audit: ReputationAudit,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Reputation {
+	/// Create a new handler on top of the given audit log.
+	pub fn new(audit: ReputationAudit, deny_unsafe: DenyUnsafe) -> Self {
+		Reputation { audit, deny_unsafe }
+	}
+}
+
+impl ReputationApi for Reputation {
+	fn peer_reputation_changes(&self, peer_id: String) -> Result<Vec<ReputationChange>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let peer_id = PeerId::from_str(&peer_id).map_err(|e| Error {
+			code: ErrorCode::InvalidParams,
+			message: format!("Invalid peer id: {}", e),
+			data: None,
+		})?;
+
+		Ok(self.audit.history(&peer_id).into_iter().map(|change| ReputationChange {
+			reason: change.reason.into(),
+			value: change.value,
+			timestamp: change.timestamp,
+		}).collect())
+	}
+
+	fn reputation_changed_peers(&self) -> Result<Vec<String>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.audit.peers().into_iter().map(|peer| peer.to_base58()).collect())
+	}
+}
@@ -163,6 +163,11 @@ impl configuration::Config for Test { }
 
 impl shared::Config for Test { }
 
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where Call: From<C> {
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = Call;
+}
+
 impl paras::Config for Test {
 	type Origin = Origin;
 	type Event = Event;
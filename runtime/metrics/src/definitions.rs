@@ -0,0 +1,91 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The metrics updated by the runtime.
+
+use crate::{MetricDefinition, MetricKind};
+
+/// Upward messages dispatched by `process_pending_upward_messages`.
+pub const UMP_MESSAGES_PROCESSED: MetricDefinition = MetricDefinition {
+	name: "ump_messages_processed_total",
+	description: "Number of upward messages processed.",
+	labels: &[],
+	kind: MetricKind::Counter,
+};
+
+/// Weight consumed by a single call of `process_pending_upward_messages`.
+pub const UMP_WEIGHT_CONSUMED: MetricDefinition = MetricDefinition {
+	name: "ump_weight_consumed",
+	description: "Weight consumed by processing the upward messages of a block.",
+	labels: &[],
+	kind: MetricKind::Histogram(&[1e9, 5e9, 1e10, 5e10, 1e11, 2.5e11, 5e11, 1e12]),
+};
+
+/// HRMP messages routed to their recipients.
+pub const HRMP_MESSAGES_ROUTED: MetricDefinition = MetricDefinition {
+	name: "hrmp_messages_routed_total",
+	description: "Number of HRMP messages routed.",
+	labels: &[],
+	kind: MetricKind::Counter,
+};
+
+/// Dispute statements imported, labeled by whether the dispute was `new` or `existing`.
+pub const DISPUTES_IMPORTED: MetricDefinition = MetricDefinition {
+	name: "disputes_imported_total",
+	description: "Number of disputes imported.",
+	labels: &["dispute"],
+	kind: MetricKind::Counter,
+};
+
+/// Pending availability candidates which timed out.
+pub const CANDIDATES_TIMED_OUT: MetricDefinition = MetricDefinition {
+	name: "candidates_timed_out_total",
+	description: "Number of candidates which timed out while pending availability.",
+	labels: &[],
+	kind: MetricKind::Counter,
+};
+
+/// All metrics updated by the runtime.
+pub const ALL: &[MetricDefinition] = &[
+	UMP_MESSAGES_PROCESSED,
+	UMP_WEIGHT_CONSUMED,
+	HRMP_MESSAGES_ROUTED,
+	DISPUTES_IMPORTED,
+	CANDIDATES_TIMED_OUT,
+];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn definitions_are_well_formed() {
+		let mut names = ALL.iter().map(|definition| definition.name).collect::<Vec<_>>();
+		names.sort();
+		names.dedup();
+		assert_eq!(names.len(), ALL.len(), "metric names must be unique");
+
+		for definition in ALL {
+			if let MetricKind::Histogram(buckets) = definition.kind {
+				assert!(
+					buckets.windows(2).all(|pair| pair[0] < pair[1]),
+					"buckets of {} must be increasing",
+					definition.name,
+				);
+			}
+		}
+	}
+}
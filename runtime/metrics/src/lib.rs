@@ -0,0 +1,191 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Metrics of the runtime, exported by the node.
+//!
+//! Runtime modules update the [`Counter`]s, [`Gauge`]s and [`Histogram`]s of the metrics
+//! listed in [`definitions`]. The updates are passed to the node through the host functions
+//! of [`runtime_metrics`], which hand them to the sink installed by the node, if any.
+//!
+//! Unless the `runtime-metrics` feature is enabled, updating a metric is a no-op and the
+//! runtime doesn't import the host functions.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime_interface::{pass_by::PassByCodec, runtime_interface};
+use sp_std::vec::Vec;
+
+pub mod definitions;
+
+/// The kind of a runtime metric.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricKind {
+	/// A counter, which is only ever increased.
+	Counter,
+	/// A gauge, which is set to the latest value.
+	Gauge,
+	/// A histogram with the given upper bounds of its buckets.
+	Histogram(&'static [f64]),
+}
+
+/// The definition of a runtime metric, shared by the runtime and the node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricDefinition {
+	/// The name of the metric, without the prefix added by the node.
+	pub name: &'static str,
+	/// The description of the metric.
+	pub description: &'static str,
+	/// The names of the labels of the metric.
+	pub labels: &'static [&'static str],
+	/// The kind of the metric.
+	pub kind: MetricKind,
+}
+
+/// An operation on a runtime metric.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub enum MetricOp {
+	/// Increase a counter by the given value.
+	#[codec(index = 0)]
+	IncrementCounter(u64),
+	/// Observe the given value in a histogram.
+	#[codec(index = 1)]
+	ObserveHistogram(u64),
+	/// Set a gauge to the given value.
+	#[codec(index = 2)]
+	SetGauge(u64),
+}
+
+/// An update of a runtime metric, passed from the runtime to the node.
+#[derive(Debug, Clone, PartialEq, Encode, Decode, PassByCodec)]
+pub struct MetricUpdate {
+	/// The name of the metric.
+	pub name: Vec<u8>,
+	/// The values of the labels of the metric, in the order of the definition.
+	pub label_values: Vec<Vec<u8>>,
+	/// The operation applied to the metric.
+	pub op: MetricOp,
+}
+
+/// Host functions passing metric updates from the runtime to the node.
+#[runtime_interface]
+pub trait RuntimeMetrics {
+	/// Apply the update to the metrics of the node.
+	fn update_metric(update: MetricUpdate) {
+		sink::apply(update)
+	}
+}
+
+/// The host functions an executor must provide to run a runtime with the `runtime-metrics`
+/// feature enabled.
+#[cfg(feature = "std")]
+pub use runtime_metrics::HostFunctions;
+
+#[cfg(feature = "std")]
+pub use sink::set_sink;
+
+#[cfg(feature = "std")]
+mod sink {
+	use std::sync::RwLock;
+
+	use super::MetricUpdate;
+
+	type Sink = Box<dyn Fn(MetricUpdate) + Send + Sync>;
+
+	lazy_static::lazy_static! {
+		static ref SINK: RwLock<Option<Sink>> = RwLock::new(None);
+	}
+
+	/// Set the sink of the metric updates of all runtimes executed by this process,
+	/// replacing the previous one.
+	///
+	/// Updates are dropped until a sink is set.
+	pub fn set_sink(sink: impl Fn(MetricUpdate) + Send + Sync + 'static) {
+		*SINK.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Box::new(sink));
+	}
+
+	pub(crate) fn apply(update: MetricUpdate) {
+		if let Some(ref sink) = *SINK.read().unwrap_or_else(|poisoned| poisoned.into_inner()) {
+			sink(update);
+		}
+	}
+}
+
+/// Handle to a counter listed in [`definitions`].
+#[derive(Debug, Clone, Copy)]
+pub struct Counter(&'static MetricDefinition);
+
+impl Counter {
+	/// Create a handle to the given counter.
+	pub const fn new(definition: &'static MetricDefinition) -> Self {
+		Counter(definition)
+	}
+
+	/// Increase the counter with the given label values by one.
+	pub fn inc(&self, label_values: &[&str]) {
+		self.inc_by(label_values, 1)
+	}
+
+	/// Increase the counter with the given label values by the given value.
+	pub fn inc_by(&self, label_values: &[&str], value: u64) {
+		emit(self.0, label_values, MetricOp::IncrementCounter(value))
+	}
+}
+
+/// Handle to a gauge listed in [`definitions`].
+#[derive(Debug, Clone, Copy)]
+pub struct Gauge(&'static MetricDefinition);
+
+impl Gauge {
+	/// Create a handle to the given gauge.
+	pub const fn new(definition: &'static MetricDefinition) -> Self {
+		Gauge(definition)
+	}
+
+	/// Set the gauge with the given label values to the given value.
+	pub fn set(&self, label_values: &[&str], value: u64) {
+		emit(self.0, label_values, MetricOp::SetGauge(value))
+	}
+}
+
+/// Handle to a histogram listed in [`definitions`].
+#[derive(Debug, Clone, Copy)]
+pub struct Histogram(&'static MetricDefinition);
+
+impl Histogram {
+	/// Create a handle to the given histogram.
+	pub const fn new(definition: &'static MetricDefinition) -> Self {
+		Histogram(definition)
+	}
+
+	/// Observe the given value in the histogram with the given label values.
+	pub fn observe(&self, label_values: &[&str], value: u64) {
+		emit(self.0, label_values, MetricOp::ObserveHistogram(value))
+	}
+}
+
+#[cfg(feature = "runtime-metrics")]
+fn emit(definition: &MetricDefinition, label_values: &[&str], op: MetricOp) {
+	runtime_metrics::update_metric(MetricUpdate {
+		name: definition.name.as_bytes().to_vec(),
+		label_values: label_values.iter().map(|value| value.as_bytes().to_vec()).collect(),
+		op,
+	});
+}
+
+#[cfg(not(feature = "runtime-metrics"))]
+#[inline(always)]
+fn emit(_definition: &MetricDefinition, _label_values: &[&str], _op: MetricOp) {}
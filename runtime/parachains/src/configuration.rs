@@ -24,6 +24,7 @@ use frame_support::{
 	decl_storage, decl_module, decl_error,
 	ensure,
 	dispatch::DispatchResult,
+	traits::StorageVersion,
 	weights::{DispatchClass, Weight},
 };
 use parity_scale_codec::{Encode, Decode};
@@ -31,6 +32,10 @@ use frame_system::ensure_root;
 use sp_runtime::traits::Zero;
 use crate::shared;
 
+/// The storage version of this pallet. Version 1 introduced PVF pre-checking to the host
+/// configuration.
+pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 /// All configuration of the runtime with respect to parachains and parathreads.
 #[derive(Clone, Encode, Decode, PartialEq, sp_core::RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
@@ -172,6 +177,12 @@ pub struct HostConfiguration<BlockNumber> {
 	pub needed_approvals: u32,
 	/// The number of samples to do of the `RelayVRFModulo` approval assignment criterion.
 	pub relay_vrf_modulo_samples: u32,
+	/// If true, validation code upgrades are only enacted after they pass PVF pre-checking.
+	pub pvf_checking_enabled: bool,
+	/// The number of sessions a PVF pre-checking vote may stay open before the code is rejected.
+	///
+	/// Must be at least 1.
+	pub pvf_voting_ttl: SessionIndex,
 }
 
 impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber> {
@@ -217,6 +228,8 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 			hrmp_max_parachain_outbound_channels: Default::default(),
 			hrmp_max_parathread_outbound_channels: Default::default(),
 			hrmp_max_message_num_per_candidate: Default::default(),
+			pvf_checking_enabled: false,
+			pvf_voting_ttl: 2,
 		}
 	}
 }
@@ -255,6 +268,10 @@ impl<BlockNumber: Zero> HostConfiguration<BlockNumber> {
 		if self.max_pov_size > MAX_POV_SIZE {
 			panic!("`max_pov_size` is bigger than allowed by the client")
 		}
+
+		if self.pvf_voting_ttl.is_zero() {
+			panic!("`pvf_voting_ttl` must be at least 1!")
+		}
 	}
 }
 
@@ -270,6 +287,7 @@ decl_storage! {
 	add_extra_genesis {
 		build(|config: &Self| {
 			config.config.check_consistency();
+			STORAGE_VERSION.put::<Module<T>>();
 		})
 	}
 }
@@ -707,6 +725,27 @@ decl_module! {
 			});
 			Ok(())
 		}
+
+		/// Enable or disable PVF pre-checking of validation code upgrades.
+		#[weight = (1_000, DispatchClass::Operational)]
+		pub fn set_pvf_checking_enabled(origin, new: bool) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::update_config_member(|config| {
+				sp_std::mem::replace(&mut config.pvf_checking_enabled, new) != new
+			});
+			Ok(())
+		}
+
+		/// Set the number of sessions after which an unresolved PVF pre-checking vote is rejected.
+		#[weight = (1_000, DispatchClass::Operational)]
+		pub fn set_pvf_voting_ttl(origin, new: SessionIndex) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(new >= 1, Error::<T>::InvalidNewValue);
+			Self::update_config_member(|config| {
+				sp_std::mem::replace(&mut config.pvf_voting_ttl, new) != new
+			});
+			Ok(())
+		}
 	}
 }
 
@@ -758,6 +797,141 @@ impl<T: Config> Module<T> {
 	}
 }
 
+/// Migration of the stored host configuration to the layout which includes the
+/// PVF pre-checking parameters.
+pub mod migration {
+	use super::*;
+	use frame_support::{
+		storage::{IterableStorageMap, StorageValue as _},
+		traits::{Get, OnRuntimeUpgrade},
+	};
+
+	/// The host configuration as it was stored before `pvf_checking_enabled` and
+	/// `pvf_voting_ttl` were introduced.
+	#[derive(Encode, Decode)]
+	pub(crate) struct OldHostConfiguration<BlockNumber> {
+		pub(crate) max_code_size: u32,
+		pub(crate) max_head_data_size: u32,
+		pub(crate) max_upward_queue_count: u32,
+		pub(crate) max_upward_queue_size: u32,
+		pub(crate) max_upward_message_size: u32,
+		pub(crate) max_upward_message_num_per_candidate: u32,
+		pub(crate) hrmp_max_message_num_per_candidate: u32,
+		pub(crate) validation_upgrade_frequency: BlockNumber,
+		pub(crate) validation_upgrade_delay: BlockNumber,
+		pub(crate) max_pov_size: u32,
+		pub(crate) max_downward_message_size: u32,
+		pub(crate) ump_service_total_weight: Weight,
+		pub(crate) hrmp_max_parachain_outbound_channels: u32,
+		pub(crate) hrmp_max_parathread_outbound_channels: u32,
+		pub(crate) hrmp_open_request_ttl: u32,
+		pub(crate) hrmp_sender_deposit: Balance,
+		pub(crate) hrmp_recipient_deposit: Balance,
+		pub(crate) hrmp_channel_max_capacity: u32,
+		pub(crate) hrmp_channel_max_total_size: u32,
+		pub(crate) hrmp_max_parachain_inbound_channels: u32,
+		pub(crate) hrmp_max_parathread_inbound_channels: u32,
+		pub(crate) hrmp_channel_max_message_size: u32,
+		pub(crate) code_retention_period: BlockNumber,
+		pub(crate) parathread_cores: u32,
+		pub(crate) parathread_retries: u32,
+		pub(crate) group_rotation_frequency: BlockNumber,
+		pub(crate) chain_availability_period: BlockNumber,
+		pub(crate) thread_availability_period: BlockNumber,
+		pub(crate) scheduling_lookahead: u32,
+		pub(crate) max_validators_per_core: Option<u32>,
+		pub(crate) max_validators: Option<u32>,
+		pub(crate) dispute_period: SessionIndex,
+		pub(crate) dispute_post_conclusion_acceptance_period: BlockNumber,
+		pub(crate) dispute_max_spam_slots: u32,
+		pub(crate) dispute_conclusion_by_time_out_period: BlockNumber,
+		pub(crate) no_show_slots: u32,
+		pub(crate) n_delay_tranches: u32,
+		pub(crate) zeroth_delay_tranche_width: u32,
+		pub(crate) needed_approvals: u32,
+		pub(crate) relay_vrf_modulo_samples: u32,
+	}
+
+	impl<BlockNumber> OldHostConfiguration<BlockNumber> {
+		fn into_current(self) -> HostConfiguration<BlockNumber> {
+			HostConfiguration {
+				max_code_size: self.max_code_size,
+				max_head_data_size: self.max_head_data_size,
+				max_upward_queue_count: self.max_upward_queue_count,
+				max_upward_queue_size: self.max_upward_queue_size,
+				max_upward_message_size: self.max_upward_message_size,
+				max_upward_message_num_per_candidate: self.max_upward_message_num_per_candidate,
+				hrmp_max_message_num_per_candidate: self.hrmp_max_message_num_per_candidate,
+				validation_upgrade_frequency: self.validation_upgrade_frequency,
+				validation_upgrade_delay: self.validation_upgrade_delay,
+				max_pov_size: self.max_pov_size,
+				max_downward_message_size: self.max_downward_message_size,
+				ump_service_total_weight: self.ump_service_total_weight,
+				hrmp_max_parachain_outbound_channels: self.hrmp_max_parachain_outbound_channels,
+				hrmp_max_parathread_outbound_channels: self.hrmp_max_parathread_outbound_channels,
+				hrmp_open_request_ttl: self.hrmp_open_request_ttl,
+				hrmp_sender_deposit: self.hrmp_sender_deposit,
+				hrmp_recipient_deposit: self.hrmp_recipient_deposit,
+				hrmp_channel_max_capacity: self.hrmp_channel_max_capacity,
+				hrmp_channel_max_total_size: self.hrmp_channel_max_total_size,
+				hrmp_max_parachain_inbound_channels: self.hrmp_max_parachain_inbound_channels,
+				hrmp_max_parathread_inbound_channels: self.hrmp_max_parathread_inbound_channels,
+				hrmp_channel_max_message_size: self.hrmp_channel_max_message_size,
+				code_retention_period: self.code_retention_period,
+				parathread_cores: self.parathread_cores,
+				parathread_retries: self.parathread_retries,
+				group_rotation_frequency: self.group_rotation_frequency,
+				chain_availability_period: self.chain_availability_period,
+				thread_availability_period: self.thread_availability_period,
+				scheduling_lookahead: self.scheduling_lookahead,
+				max_validators_per_core: self.max_validators_per_core,
+				max_validators: self.max_validators,
+				dispute_period: self.dispute_period,
+				dispute_post_conclusion_acceptance_period: self.dispute_post_conclusion_acceptance_period,
+				dispute_max_spam_slots: self.dispute_max_spam_slots,
+				dispute_conclusion_by_time_out_period: self.dispute_conclusion_by_time_out_period,
+				no_show_slots: self.no_show_slots,
+				n_delay_tranches: self.n_delay_tranches,
+				zeroth_delay_tranche_width: self.zeroth_delay_tranche_width,
+				needed_approvals: self.needed_approvals,
+				relay_vrf_modulo_samples: self.relay_vrf_modulo_samples,
+				pvf_checking_enabled: false,
+				pvf_voting_ttl: 2,
+			}
+		}
+	}
+
+	/// Translates the active and all pending host configurations into the current layout.
+	///
+	/// PVF pre-checking stays disabled after the migration. This is a no-op if the pallet's
+	/// on-chain storage version is already [`STORAGE_VERSION`].
+	pub struct MigrateToPvfChecking<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToPvfChecking<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Module<T>>() >= STORAGE_VERSION {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let _ = <ActiveConfig<T>>::translate::<OldHostConfiguration<T::BlockNumber>, _>(
+				|old| old.map(OldHostConfiguration::into_current),
+			);
+
+			let mut translated = 0u64;
+			<PendingConfig<T> as IterableStorageMap<_, _>>::translate::<OldHostConfiguration<T::BlockNumber>, _>(
+				|_, old| {
+					translated += 1;
+					Some(old.into_current())
+				},
+			);
+
+			STORAGE_VERSION.put::<Module<T>>();
+
+			T::DbWeight::get().reads_writes(2 + translated, 2 + translated)
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -834,6 +1008,8 @@ mod tests {
 				hrmp_max_parachain_outbound_channels: 100,
 				hrmp_max_parathread_outbound_channels: 200,
 				hrmp_max_message_num_per_candidate: 20,
+				pvf_checking_enabled: true,
+				pvf_voting_ttl: 3,
 			};
 
 			assert!(<Configuration as Store>::PendingConfig::get(shared::SESSION_DELAY).is_none());
@@ -969,6 +1145,14 @@ mod tests {
 				Origin::root(),
 				new_config.hrmp_max_message_num_per_candidate,
 			).unwrap();
+			Configuration::set_pvf_checking_enabled(
+				Origin::root(),
+				new_config.pvf_checking_enabled,
+			).unwrap();
+			Configuration::set_pvf_voting_ttl(
+				Origin::root(),
+				new_config.pvf_voting_ttl,
+			).unwrap();
 
 			assert_eq!(<Configuration as Store>::PendingConfig::get(shared::SESSION_DELAY), Some(new_config));
 		})
@@ -1026,4 +1210,85 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn migration_translates_old_layout() {
+		use frame_support::{
+			storage::{StorageMap as _, StorageValue as _, unhashed},
+			traits::OnRuntimeUpgrade,
+		};
+		use migration::{MigrateToPvfChecking, OldHostConfiguration};
+
+		new_test_ext(Default::default()).execute_with(|| {
+			let current = HostConfiguration::<primitives::v1::BlockNumber> {
+				validation_upgrade_delay: 10,
+				max_code_size: 1024,
+				needed_approvals: 3,
+				..Default::default()
+			};
+			let old = OldHostConfiguration {
+				max_code_size: current.max_code_size,
+				max_head_data_size: current.max_head_data_size,
+				max_upward_queue_count: current.max_upward_queue_count,
+				max_upward_queue_size: current.max_upward_queue_size,
+				max_upward_message_size: current.max_upward_message_size,
+				max_upward_message_num_per_candidate: current.max_upward_message_num_per_candidate,
+				hrmp_max_message_num_per_candidate: current.hrmp_max_message_num_per_candidate,
+				validation_upgrade_frequency: current.validation_upgrade_frequency,
+				validation_upgrade_delay: current.validation_upgrade_delay,
+				max_pov_size: current.max_pov_size,
+				max_downward_message_size: current.max_downward_message_size,
+				ump_service_total_weight: current.ump_service_total_weight,
+				hrmp_max_parachain_outbound_channels: current.hrmp_max_parachain_outbound_channels,
+				hrmp_max_parathread_outbound_channels: current.hrmp_max_parathread_outbound_channels,
+				hrmp_open_request_ttl: current.hrmp_open_request_ttl,
+				hrmp_sender_deposit: current.hrmp_sender_deposit,
+				hrmp_recipient_deposit: current.hrmp_recipient_deposit,
+				hrmp_channel_max_capacity: current.hrmp_channel_max_capacity,
+				hrmp_channel_max_total_size: current.hrmp_channel_max_total_size,
+				hrmp_max_parachain_inbound_channels: current.hrmp_max_parachain_inbound_channels,
+				hrmp_max_parathread_inbound_channels: current.hrmp_max_parathread_inbound_channels,
+				hrmp_channel_max_message_size: current.hrmp_channel_max_message_size,
+				code_retention_period: current.code_retention_period,
+				parathread_cores: current.parathread_cores,
+				parathread_retries: current.parathread_retries,
+				group_rotation_frequency: current.group_rotation_frequency,
+				chain_availability_period: current.chain_availability_period,
+				thread_availability_period: current.thread_availability_period,
+				scheduling_lookahead: current.scheduling_lookahead,
+				max_validators_per_core: current.max_validators_per_core,
+				max_validators: current.max_validators,
+				dispute_period: current.dispute_period,
+				dispute_post_conclusion_acceptance_period: current.dispute_post_conclusion_acceptance_period,
+				dispute_max_spam_slots: current.dispute_max_spam_slots,
+				dispute_conclusion_by_time_out_period: current.dispute_conclusion_by_time_out_period,
+				no_show_slots: current.no_show_slots,
+				n_delay_tranches: current.n_delay_tranches,
+				zeroth_delay_tranche_width: current.zeroth_delay_tranche_width,
+				needed_approvals: current.needed_approvals,
+				relay_vrf_modulo_samples: current.relay_vrf_modulo_samples,
+			};
+
+			// Genesis already writes the current storage version, so roll it back.
+			assert_eq!(StorageVersion::get::<Configuration>(), STORAGE_VERSION);
+			StorageVersion::new(0).put::<Configuration>();
+
+			let key = <Configuration as Store>::ActiveConfig::hashed_key();
+			unhashed::put_raw(&key, &old.encode());
+			unhashed::put_raw(&<Configuration as Store>::PendingConfig::hashed_key_for(3), &old.encode());
+
+			MigrateToPvfChecking::<crate::mock::Test>::on_runtime_upgrade();
+
+			assert_eq!(Configuration::config(), current);
+			assert_eq!(<Configuration as Store>::PendingConfig::get(3), Some(current.clone()));
+			assert_eq!(StorageVersion::get::<Configuration>(), STORAGE_VERSION);
+
+			// Running the migration again must leave the configuration untouched.
+			let mut enabled = current.clone();
+			enabled.pvf_checking_enabled = true;
+			<Configuration as Store>::ActiveConfig::put(enabled.clone());
+			MigrateToPvfChecking::<crate::mock::Test>::on_runtime_upgrade();
+			assert_eq!(Configuration::config(), enabled);
+		});
+	}
 }
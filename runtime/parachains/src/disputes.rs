@@ -30,6 +30,7 @@ use sp_runtime::{
 use frame_support::{ensure, traits::Get, weights::Weight};
 use parity_scale_codec::{Encode, Decode};
 use bitvec::{bitvec, order::Lsb0 as BitOrderLsb0};
+use polkadot_runtime_metrics::{Counter, definitions};
 use crate::{
 	configuration::{self, HostConfiguration},
 	initializer::SessionChangeNotification,
@@ -633,7 +634,10 @@ impl<T: Config> Pallet<T> {
 			ensure!(submitted == targets.len(), Error::<T>::DuplicateDisputeStatementSets);
 		}
 
-		let mut fresh = Vec::with_capacity(statement_sets.len());
+		const DISPUTES_IMPORTED: Counter = Counter::new(&definitions::DISPUTES_IMPORTED);
+
+		let submitted = statement_sets.len();
+		let mut fresh = Vec::with_capacity(submitted);
 		for statement_set in statement_sets {
 			let dispute_target = (statement_set.session, statement_set.candidate_hash);
 			if Self::provide_dispute_data(&config, statement_set)? {
@@ -641,6 +645,14 @@ impl<T: Config> Pallet<T> {
 			}
 		}
 
+		let existing = submitted - fresh.len();
+		if !fresh.is_empty() {
+			DISPUTES_IMPORTED.inc_by(&["new"], fresh.len() as u64);
+		}
+		if existing > 0 {
+			DISPUTES_IMPORTED.inc_by(&["existing"], existing as u64);
+		}
+
 		Ok(fresh)
 	}
 
@@ -411,4 +411,25 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn verify_dmq_is_externally_accessible() {
+		use primitives::v1::well_known_keys;
+		use parity_scale_codec::Decode as _;
+
+		let a = ParaId::from(2020);
+
+		new_test_ext(default_genesis_config()).execute_with(|| {
+			assert_eq!(sp_io::storage::get(&well_known_keys::downward_message_queue(a)), None);
+
+			queue_downward_message(a, vec![1, 2, 3]).unwrap();
+
+			let raw_queue = sp_io::storage::get(&well_known_keys::downward_message_queue(a))
+				.expect("queueing a message must create the queue");
+			let queue = <Vec<InboundDownwardMessage<BlockNumber>>>::decode(&mut &raw_queue[..])
+				.expect("the queue must be decodable");
+			assert_eq!(queue.len(), 1);
+			assert_eq!(queue[0].msg, vec![1, 2, 3]);
+		});
+	}
 }
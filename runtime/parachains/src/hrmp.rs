@@ -30,6 +30,7 @@ use primitives::v1::{
 	SessionIndex,
 };
 use sp_runtime::traits::{UniqueSaturatedInto, AccountIdConversion, BlakeTwo256, Hash as HashT};
+use polkadot_runtime_metrics::{Counter, definitions};
 use sp_std::{
 	mem, fmt,
 	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
@@ -889,6 +890,8 @@ impl<T: Config> Module<T> {
 		sender: ParaId,
 		out_hrmp_msgs: Vec<OutboundHrmpMessage<ParaId>>,
 	) -> Weight {
+		const MESSAGES_ROUTED: Counter = Counter::new(&definitions::HRMP_MESSAGES_ROUTED);
+
 		let mut weight = 0;
 		let now = <frame_system::Pallet<T>>::block_number();
 
@@ -953,6 +956,7 @@ impl<T: Config> Module<T> {
 			}
 			<Self as Store>::HrmpChannelDigests::insert(&channel_id.recipient, recipient_digest);
 
+			MESSAGES_ROUTED.inc(&[]);
 			weight += T::DbWeight::get().reads_writes(2, 2);
 		}
 
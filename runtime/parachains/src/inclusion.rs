@@ -25,7 +25,7 @@ use primitives::v1::{
 	CandidateCommitments, CandidateDescriptor, ValidatorIndex, Id as ParaId,
 	AvailabilityBitfield as AvailabilityBitfield, UncheckedSignedAvailabilityBitfields, SigningContext,
 	BackedCandidate, CoreIndex, GroupIndex, CommittedCandidateReceipt,
-	CandidateReceipt, HeadData, CandidateHash,
+	CandidateReceipt, HeadData, CandidateHash, ValidityAttestation,
 };
 use frame_support::{
 	decl_storage, decl_module, decl_error, decl_event, ensure, dispatch::DispatchResult, IterableStorageMap,
@@ -34,6 +34,7 @@ use frame_support::{
 use parity_scale_codec::{Encode, Decode};
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use sp_runtime::{DispatchError, traits::{One, Saturating}};
+use polkadot_runtime_metrics::{Counter, definitions};
 
 use crate::{configuration, disputes, paras, dmp, ump, hrmp, shared, scheduler::CoreAssignment};
 
@@ -49,6 +50,26 @@ pub struct AvailabilityBitfieldRecord<N> {
 	submitted_at: N, // for accounting, as meaning of bits may change over time.
 }
 
+/// The result of processing the backed candidates of a block.
+#[derive(PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub(crate) struct ProcessedCandidates<H = primitives::v1::Hash> {
+	/// The cores occupied by the backed candidates, in ascending order.
+	pub(crate) core_indices: Vec<CoreIndex>,
+	/// The receipts of the backed candidates, with the validators that attested to each of them.
+	pub(crate) candidate_receipt_with_backing_validator_indices:
+		Vec<(CandidateReceipt<H>, Vec<(ValidatorIndex, ValidityAttestation)>)>,
+}
+
+impl<H> Default for ProcessedCandidates<H> {
+	fn default() -> Self {
+		Self {
+			core_indices: Vec::new(),
+			candidate_receipt_with_backing_validator_indices: Vec::new(),
+		}
+	}
+}
+
 /// A backed candidate pending availability.
 #[derive(Encode, Decode, PartialEq)]
 #[cfg_attr(test, derive(Debug))]
@@ -387,11 +408,11 @@ impl<T: Config> Module<T> {
 		candidates: Vec<BackedCandidate<T::Hash>>,
 		scheduled: Vec<CoreAssignment>,
 		group_validators: impl Fn(GroupIndex) -> Option<Vec<ValidatorIndex>>,
-	) -> Result<Vec<CoreIndex>, DispatchError> {
+	) -> Result<ProcessedCandidates<T::Hash>, DispatchError> {
 		ensure!(candidates.len() <= scheduled.len(), Error::<T>::UnscheduledCandidate);
 
 		if scheduled.is_empty() {
-			return Ok(Vec::new());
+			return Ok(ProcessedCandidates::default());
 		}
 
 		let validators = shared::Module::<T>::active_validator_keys();
@@ -404,9 +425,11 @@ impl<T: Config> Module<T> {
 		let check_cx = CandidateCheckContext::<T>::new(now, relay_parent_number);
 
 		// do all checks before writing storage.
-		let core_indices_and_backers = {
+		let (core_indices_and_backers, candidate_receipt_with_backing_validator_indices) = {
 			let mut skip = 0;
 			let mut core_indices_and_backers = Vec::with_capacity(candidates.len());
+			let mut candidate_receipt_with_backing_validator_indices =
+				Vec::with_capacity(candidates.len());
 			let mut last_core = None;
 
 			let mut check_assignment_in_order = |assignment: &CoreAssignment| -> DispatchResult {
@@ -503,7 +526,7 @@ impl<T: Config> Module<T> {
 										// We don't want to error out here because it will
 										// brick the relay-chain. So we return early without
 										// doing anything.
-										return Ok(Vec::new());
+										return Ok(ProcessedCandidates::default());
 									}
 								};
 
@@ -547,15 +570,26 @@ impl<T: Config> Module<T> {
 								Err(()) => { Err(Error::<T>::InvalidBacking)?; }
 							}
 
-							for (bit_idx, _) in candidate
+							let mut backer_idx_and_attestation =
+								Vec::with_capacity(candidate.validity_votes.len());
+
+							// the validity votes are in the order of the set bits of
+							// `validator_indices`, as checked above.
+							for ((bit_idx, _), attestation) in candidate
 								.validator_indices.iter()
 								.enumerate().filter(|(_, signed)| **signed)
+								.zip(candidate.validity_votes.iter().cloned())
 							{
 								let val_idx = group_vals.get(bit_idx)
 									.expect("this query done above; qed");
 
 								backers.set(val_idx.0 as _, true);
+								backer_idx_and_attestation.push((*val_idx, attestation));
 							}
+
+							candidate_receipt_with_backing_validator_indices.push(
+								(candidate.candidate.to_plain(), backer_idx_and_attestation),
+							);
 						}
 
 						core_indices_and_backers.push((assignment.core, backers, assignment.group_idx));
@@ -577,7 +611,7 @@ impl<T: Config> Module<T> {
 				check_assignment_in_order(assignment)?;
 			}
 
-			core_indices_and_backers
+			(core_indices_and_backers, candidate_receipt_with_backing_validator_indices)
 		};
 
 		// one more sweep for actually writing to storage.
@@ -616,7 +650,10 @@ impl<T: Config> Module<T> {
 			<PendingAvailabilityCommitments>::insert(&para_id, commitments);
 		}
 
-		Ok(core_indices)
+		Ok(ProcessedCandidates {
+			core_indices,
+			candidate_receipt_with_backing_validator_indices,
+		})
 	}
 
 	/// Run the acceptance criteria checks on the given candidate commitments.
@@ -676,10 +713,11 @@ impl<T: Config> Module<T> {
 		// initial weight is config read.
 		let mut weight = T::DbWeight::get().reads_writes(1, 0);
 		if let Some(new_code) = commitments.new_validation_code {
-			weight += <paras::Pallet<T>>::schedule_code_upgrade(
+			weight += <paras::Pallet<T>>::request_code_upgrade(
 				receipt.descriptor.para_id,
 				new_code,
-				relay_parent_number + config.validation_upgrade_delay,
+				relay_parent_number,
+				&config,
 			);
 		}
 
@@ -719,6 +757,8 @@ impl<T: Config> Module<T> {
 	///
 	/// Returns a vector of cleaned-up core IDs.
 	pub(crate) fn collect_pending(pred: impl Fn(CoreIndex, T::BlockNumber) -> bool) -> Vec<CoreIndex> {
+		const CANDIDATES_TIMED_OUT: Counter = Counter::new(&definitions::CANDIDATES_TIMED_OUT);
+
 		let mut cleaned_up_ids = Vec::new();
 		let mut cleaned_up_cores = Vec::new();
 
@@ -745,6 +785,7 @@ impl<T: Config> Module<T> {
 					commitments.head_data,
 					pending.core,
 				));
+				CANDIDATES_TIMED_OUT.inc(&[]);
 			}
 		}
 
@@ -898,6 +939,11 @@ impl<T: Config> CandidateCheckContext<T> {
 				valid_upgrade_attempt,
 				AcceptanceCheckErr::PrematureCodeUpgrade,
 			);
+			// an upgrade that is still being pre-checked blocks any further upgrades.
+			ensure!(
+				!<paras::Pallet<T>>::pvf_check_in_progress(para_id),
+				AcceptanceCheckErr::PrematureCodeUpgrade,
+			);
 			ensure!(
 				new_validation_code.0.len() <= self.config.max_code_size as _,
 				AcceptanceCheckErr::NewCodeTooLarge,
@@ -2281,7 +2327,22 @@ mod tests {
 				BackingKind::Threshold,
 			));
 
-			let occupied_cores = Inclusion::process_candidates(
+			let backing_votes = |backed: &BackedCandidate, group: &[u32]| {
+				group.iter()
+					.map(|i| ValidatorIndex(*i))
+					.zip(backed.validity_votes.iter().cloned())
+					.collect::<Vec<_>>()
+			};
+			let expected_backing_votes = vec![
+				(candidate_a.to_plain(), backing_votes(&backed_a, &[0, 1])),
+				(candidate_b.to_plain(), backing_votes(&backed_b, &[2, 3])),
+				(candidate_c.to_plain(), backing_votes(&backed_c, &[4])),
+			];
+
+			let ProcessedCandidates {
+				core_indices: occupied_cores,
+				candidate_receipt_with_backing_validator_indices,
+			} = Inclusion::process_candidates(
 				Default::default(),
 				vec![backed_a, backed_b, backed_c],
 				vec![
@@ -2293,6 +2354,7 @@ mod tests {
 			).expect("candidates scheduled, in order, and backed");
 
 			assert_eq!(occupied_cores, vec![CoreIndex::from(0), CoreIndex::from(1), CoreIndex::from(2)]);
+			assert_eq!(candidate_receipt_with_backing_validator_indices, expected_backing_votes);
 
 			assert_eq!(
 				<PendingAvailability<Test>>::get(&chain_a),
@@ -2417,7 +2479,7 @@ mod tests {
 				BackingKind::Threshold,
 			));
 
-			let occupied_cores = Inclusion::process_candidates(
+			let ProcessedCandidates { core_indices: occupied_cores, .. } = Inclusion::process_candidates(
 				Default::default(),
 				vec![backed_a],
 				vec![
@@ -45,7 +45,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		Paras: paras::{Pallet, Origin, Call, Storage, Event, Config},
+		Paras: paras::{Pallet, Origin, Call, Storage, Event, Config, ValidateUnsigned},
 		Configuration: configuration::{Pallet, Call, Storage, Config<T>},
 		Shared: shared::{Pallet, Call, Storage},
 		Inclusion: inclusion::{Pallet, Call, Storage, Event<T>},
@@ -118,6 +118,11 @@ impl crate::configuration::Config for Test { }
 
 impl crate::shared::Config for Test { }
 
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where Call: From<C> {
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = Call;
+}
+
 impl crate::paras::Config for Test {
 	type Origin = Origin;
 	type Event = Event;
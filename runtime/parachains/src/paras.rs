@@ -24,16 +24,20 @@
 //! only occur at session boundaries.
 
 use sp_std::prelude::*;
-use sp_std::result;
+use sp_std::{cmp, result};
 use primitives::v1::{
 	Id as ParaId, ValidationCode, ValidationCodeHash, HeadData, SessionIndex, ConsensusLog,
+	PvfCheckStatement, ValidatorSignature, supermajority_threshold,
 };
-use sp_runtime::{traits::One, DispatchResult, SaturatedConversion};
+
+pub use primitives::v1::ParaLifecycle;
+use sp_runtime::{traits::{AppVerify, One}, DispatchResult, SaturatedConversion};
 use frame_system::pallet_prelude::*;
 use frame_support::pallet_prelude::*;
 use parity_scale_codec::{Encode, Decode};
 use crate::{configuration, shared, initializer::SessionChangeNotification};
 use sp_core::RuntimeDebug;
+use bitvec::{bitvec, order::Lsb0 as BitOrderLsb0, vec::BitVec};
 
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
@@ -82,76 +86,6 @@ enum UseCodeAt<N> {
 	ReplacedAt(N),
 }
 
-/// The possible states of a para, to take into account delayed lifecycle changes.
-///
-/// If the para is in a "transition state", it is expected that the parachain is
-/// queued in the `ActionsQueue` to transition it into a stable state. Its lifecycle
-/// state will be used to determine the state transition to apply to the para.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
-pub enum ParaLifecycle {
-	/// Para is new and is onboarding as a Parathread or Parachain.
-	Onboarding,
-	/// Para is a Parathread.
-	Parathread,
-	/// Para is a Parachain.
-	Parachain,
-	/// Para is a Parathread which is upgrading to a Parachain.
-	UpgradingParathread,
-	/// Para is a Parachain which is downgrading to a Parathread.
-	DowngradingParachain,
-	/// Parathread is queued to be offboarded.
-	OffboardingParathread,
-	/// Parachain is queued to be offboarded.
-	OffboardingParachain,
-}
-
-impl ParaLifecycle {
-	/// Returns true if parachain is currently onboarding. To learn if the
-	/// parachain is onboarding as a parachain or parathread, look at the
-	/// `UpcomingGenesis` storage item.
-	pub fn is_onboarding(&self) -> bool {
-		matches!(self, ParaLifecycle::Onboarding)
-	}
-
-	/// Returns true if para is in a stable state, i.e. it is currently
-	/// a parachain or parathread, and not in any transition state.
-	pub fn is_stable(&self) -> bool {
-		matches!(self, ParaLifecycle::Parathread | ParaLifecycle::Parachain)
-	}
-
-	/// Returns true if para is currently treated as a parachain.
-	/// This also includes transitioning states, so you may want to combine
-	/// this check with `is_stable` if you specifically want `Paralifecycle::Parachain`.
-	pub fn is_parachain(&self) -> bool {
-		matches!(self,
-			ParaLifecycle::Parachain |
-			ParaLifecycle::DowngradingParachain |
-			ParaLifecycle::OffboardingParachain
-		)
-	}
-
-	/// Returns true if para is currently treated as a parathread.
-	/// This also includes transitioning states, so you may want to combine
-	/// this check with `is_stable` if you specifically want `Paralifecycle::Parathread`.
-	pub fn is_parathread(&self) -> bool {
-		matches!(self,
-			ParaLifecycle::Parathread |
-			ParaLifecycle::UpgradingParathread |
-			ParaLifecycle::OffboardingParathread
-		)
-	}
-
-	/// Returns true if para is currently offboarding.
-	pub fn is_offboarding(&self) -> bool {
-		matches!(self, ParaLifecycle::OffboardingParathread | ParaLifecycle::OffboardingParachain)
-	}
-
-	/// Returns true if para is in any transitionary state.
-	pub fn is_transitioning(&self) -> bool {
-		!Self::is_stable(self)
-	}
-}
-
 impl<N: Ord + Copy + PartialEq> ParaPastCodeMeta<N> {
 	// note a replacement has occurred at a given block number.
 	fn note_replacement(&mut self, expected_at: N, activated_at: N) {
@@ -250,6 +184,76 @@ impl<N: Ord + Copy + PartialEq> ParaPastCodeMeta<N> {
 	}
 }
 
+/// The reason a PVF pre-checking vote was started.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum PvfCheckCause<N> {
+	/// The PVF is a validation code upgrade of the given para, signalled by a candidate with
+	/// the given relay-parent number.
+	Upgrade {
+		id: ParaId,
+		relay_parent_number: N,
+	},
+}
+
+/// The outcome of a PVF pre-checking vote.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum PvfCheckOutcome {
+	Accepted,
+	Rejected,
+}
+
+/// The state of an ongoing PVF pre-checking vote.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct PvfCheckActiveVoteState<N> {
+	// Both bitfields are indexed by the validator index in the active set of the session the
+	// vote is currently running in. They are reset on every session change, since the active
+	// set may have changed.
+	votes_accept: BitVec<BitOrderLsb0, u8>,
+	votes_reject: BitVec<BitOrderLsb0, u8>,
+	/// The number of session changes this vote has survived.
+	age: SessionIndex,
+	/// The relay-chain block number at which the vote was started.
+	created_at: N,
+	/// The reasons the PVF is checked. There may be several if multiple paras upgrade to the
+	/// same code at the same time.
+	causes: Vec<PvfCheckCause<N>>,
+}
+
+impl<N> PvfCheckActiveVoteState<N> {
+	fn new(now: N, n_validators: usize, cause: PvfCheckCause<N>) -> Self {
+		Self {
+			votes_accept: bitvec![BitOrderLsb0, u8; 0; n_validators],
+			votes_reject: bitvec![BitOrderLsb0, u8; 0; n_validators],
+			age: 0,
+			created_at: now,
+			causes: vec![cause],
+		}
+	}
+
+	fn reinitialize_ballots(&mut self, n_validators: usize) {
+		self.votes_accept = bitvec![BitOrderLsb0, u8; 0; n_validators];
+		self.votes_reject = bitvec![BitOrderLsb0, u8; 0; n_validators];
+	}
+
+	// Whether the validator with the given index already cast a vote.
+	fn has_vote(&self, validator_index: usize) -> bool {
+		self.votes_accept.get(validator_index).map_or(false, |v| *v)
+			|| self.votes_reject.get(validator_index).map_or(false, |v| *v)
+	}
+
+	// Returns the outcome of the vote, if a supermajority of the validators agreed on one.
+	fn quorum(&self) -> Option<PvfCheckOutcome> {
+		let threshold = supermajority_threshold(self.votes_accept.len());
+		if self.votes_accept.count_ones() >= threshold {
+			Some(PvfCheckOutcome::Accepted)
+		} else if self.votes_reject.count_ones() >= threshold {
+			Some(PvfCheckOutcome::Rejected)
+		} else {
+			None
+		}
+	}
+}
+
 /// Arguments for initializing a para.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -274,7 +278,8 @@ pub mod pallet {
 	pub trait Config:
 		frame_system::Config +
 		configuration::Config +
-		shared::Config
+		shared::Config +
+		frame_system::offchain::SendTransactionTypes<Call<Self>>
 	{
 		/// The outer origin type.
 		type Origin: From<Origin>
@@ -297,6 +302,15 @@ pub mod pallet {
 		NewHeadNoted(ParaId),
 		/// A para has been queued to execute pending actions. `para_id`
 		ActionQueued(ParaId, SessionIndex),
+		/// A PVF pre-checking vote has been started for the given code upgrade of a para.
+		/// `code_hash` `para_id`
+		PvfCheckStarted(ValidationCodeHash, ParaId),
+		/// The given code upgrade of a para passed PVF pre-checking and has been scheduled.
+		/// `code_hash` `para_id`
+		PvfCheckAccepted(ValidationCodeHash, ParaId),
+		/// The given code upgrade of a para failed PVF pre-checking and has been dropped.
+		/// `code_hash` `para_id`
+		PvfCheckRejected(ValidationCodeHash, ParaId),
 	}
 
 	#[pallet::error]
@@ -311,6 +325,18 @@ pub mod pallet {
 		CannotUpgrade,
 		/// Para cannot be downgraded to a parathread.
 		CannotDowngrade,
+		/// The statement for PVF pre-checking is stale.
+		PvfCheckStatementStale,
+		/// The statement for PVF pre-checking is for a future session.
+		PvfCheckStatementFuture,
+		/// Claimed validator index is out of bounds.
+		PvfCheckValidatorIndexOutOfBounds,
+		/// The signature for the PVF pre-checking is invalid.
+		PvfCheckInvalidSignature,
+		/// The given validator already has cast a vote.
+		PvfCheckDoubleVote,
+		/// The given PVF does not exist at the moment of process a vote.
+		PvfCheckSubjectInvalid,
 	}
 
 	/// All parachains. Ordered ascending by `ParaId`. Parathreads are not included.
@@ -380,6 +406,21 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type FutureCodeHash<T: Config> = StorageMap<_, Twox64Concat, ParaId, ValidationCodeHash>;
 
+	/// The active PVF pre-checking votes, by the hash of the checked code.
+	///
+	/// Every code hash present here is also present in [`PvfActiveVoteList`].
+	#[pallet::storage]
+	pub(super) type PvfActiveVoteMap<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		ValidationCodeHash,
+		PvfCheckActiveVoteState<T::BlockNumber>,
+	>;
+
+	/// The code hashes of all active PVF pre-checking votes, in the order the votes were started.
+	#[pallet::storage]
+	pub(super) type PvfActiveVoteList<T: Config> = StorageValue<_, Vec<ValidationCodeHash>, ValueQuery>;
+
 	/// The actions to perform during the start of a specific session index.
 	#[pallet::storage]
 	#[pallet::getter(fn actions_queue)]
@@ -512,6 +553,66 @@ pub mod pallet {
 			Self::deposit_event(Event::ActionQueued(para, next_session));
 			Ok(())
 		}
+
+		/// Include a statement of a validator for an active PVF pre-checking vote.
+		///
+		/// Concludes the vote and enacts its outcome if the statement completes a supermajority.
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 4))]
+		pub fn include_pvf_check_statement(
+			origin: OriginFor<T>,
+			stmt: PvfCheckStatement,
+			signature: ValidatorSignature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			Self::check_pvf_check_statement(&stmt, &signature)?;
+
+			let mut vote = PvfActiveVoteMap::<T>::get(&stmt.subject)
+				.ok_or(Error::<T>::PvfCheckSubjectInvalid)?;
+			let validator_index = stmt.validator_index.0 as usize;
+			if stmt.accept {
+				vote.votes_accept.set(validator_index, true);
+			} else {
+				vote.votes_reject.set(validator_index, true);
+			}
+
+			match vote.quorum() {
+				Some(outcome) => {
+					Self::conclude_pvf_check(&stmt.subject, vote.causes, outcome);
+				},
+				None => PvfActiveVoteMap::<T>::insert(&stmt.subject, vote),
+			}
+
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (stmt, signature) = match call {
+				Call::include_pvf_check_statement(stmt, signature) => (stmt, signature),
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			Self::check_pvf_check_statement(stmt, signature).map_err(|e| match e {
+				Error::<T>::PvfCheckStatementFuture => InvalidTransaction::Future,
+				Error::<T>::PvfCheckStatementStale
+				| Error::<T>::PvfCheckDoubleVote
+				| Error::<T>::PvfCheckSubjectInvalid => InvalidTransaction::Stale,
+				Error::<T>::PvfCheckValidatorIndexOutOfBounds
+				| Error::<T>::PvfCheckInvalidSignature => InvalidTransaction::BadProof,
+				_ => InvalidTransaction::Call,
+			})?;
+
+			ValidTransaction::with_tag_prefix("PvfPreCheckingVote")
+				.priority(TransactionPriority::max_value())
+				.longevity(TransactionLongevity::max_value())
+				.and_provides((stmt.session_index, stmt.validator_index, stmt.subject))
+				.propagate(true)
+				.build()
+		}
 	}
 }
 
@@ -529,6 +630,7 @@ impl<T: Config> Pallet<T> {
 	/// Returns the list of outgoing paras from the actions queue.
 	pub(crate) fn initializer_on_new_session(notification: &SessionChangeNotification<T::BlockNumber>) -> Vec<ParaId> {
 		let outgoing_paras = Self::apply_actions_queue(notification.session_index);
+		Self::groom_pvf_votes(notification);
 		outgoing_paras
 	}
 
@@ -815,28 +917,203 @@ impl<T: Config> Pallet<T> {
 	/// of a block of the same parachain executed in the context of a relay-chain block
 	/// with number >= `expected_at`
 	///
-	/// If there is already a scheduled code upgrade for the para, this is a no-op.
+	/// If there is already a scheduled code upgrade for the para, or one is being pre-checked,
+	/// this is a no-op.
 	pub(crate) fn schedule_code_upgrade(
 		id: ParaId,
 		new_code: ValidationCode,
 		expected_at: T::BlockNumber,
 	) -> Weight {
-		<Self as Store>::FutureCodeUpgrades::mutate(&id, |up| {
-			if up.is_some() {
-				T::DbWeight::get().reads_writes(1, 0)
-			} else {
-				*up = Some(expected_at);
+		if FutureCodeHash::<T>::contains_key(&id) {
+			return T::DbWeight::get().reads_writes(1, 0);
+		}
 
-				let new_code_hash = new_code.hash();
-				let expected_at_u32 = expected_at.saturated_into();
-				let log = ConsensusLog::ParaScheduleUpgradeCode(id, new_code_hash, expected_at_u32);
-				<frame_system::Pallet<T>>::deposit_log(log.into());
+		let new_code_hash = new_code.hash();
+		let (reads, writes) = Self::increase_code_ref(&new_code_hash, &new_code);
+		FutureCodeHash::<T>::insert(&id, new_code_hash);
+		Self::set_future_code_upgrade(id, new_code_hash, expected_at);
+		T::DbWeight::get().reads_writes(1 + reads, 2 + writes)
+	}
+
+	/// Request a code upgrade of the given para, signalled by a candidate with the given
+	/// relay-parent number.
+	///
+	/// If PVF pre-checking is disabled, the upgrade is scheduled right away for
+	/// `validation_upgrade_delay` blocks after the relay-parent. Otherwise a pre-checking vote
+	/// is started (or joined, if the same code is already being checked) and the upgrade is only
+	/// scheduled once the vote accepts the code.
+	///
+	/// If there is already a scheduled code upgrade for the para, or one is being pre-checked,
+	/// this is a no-op.
+	pub(crate) fn request_code_upgrade(
+		id: ParaId,
+		new_code: ValidationCode,
+		relay_parent_number: T::BlockNumber,
+		cfg: &configuration::HostConfiguration<T::BlockNumber>,
+	) -> Weight {
+		if !cfg.pvf_checking_enabled {
+			return Self::schedule_code_upgrade(
+				id,
+				new_code,
+				relay_parent_number + cfg.validation_upgrade_delay,
+			);
+		}
+
+		if FutureCodeHash::<T>::contains_key(&id) {
+			return T::DbWeight::get().reads_writes(1, 0);
+		}
+
+		let code_hash = new_code.hash();
+		let (reads, writes) = Self::increase_code_ref(&code_hash, &new_code);
+		FutureCodeHash::<T>::insert(&id, code_hash);
 
-				let (reads, writes) = Self::increase_code_ref(&new_code_hash, &new_code);
-				FutureCodeHash::<T>::insert(&id, new_code_hash);
-				T::DbWeight::get().reads_writes(1 + reads, 2 + writes)
+		let cause = PvfCheckCause::Upgrade { id, relay_parent_number };
+		let now = <frame_system::Pallet<T>>::block_number();
+		PvfActiveVoteMap::<T>::mutate(&code_hash, |vote| match vote {
+			Some(vote) => vote.causes.push(cause),
+			None => {
+				let n_validators = shared::Pallet::<T>::active_validator_keys().len();
+				*vote = Some(PvfCheckActiveVoteState::new(now, n_validators, cause));
+				PvfActiveVoteList::<T>::append(code_hash);
+			},
+		});
+		Self::deposit_event(Event::PvfCheckStarted(code_hash, id));
+
+		T::DbWeight::get().reads_writes(3 + reads, 3 + writes)
+	}
+
+	// Set the block at which an upgrade to the code in `FutureCodeHash` is expected and inform
+	// the para via the consensus log.
+	fn set_future_code_upgrade(id: ParaId, code_hash: ValidationCodeHash, expected_at: T::BlockNumber) {
+		<Self as Store>::FutureCodeUpgrades::insert(&id, expected_at);
+
+		let expected_at_u32 = expected_at.saturated_into();
+		let log = ConsensusLog::ParaScheduleUpgradeCode(id, code_hash, expected_at_u32);
+		<frame_system::Pallet<T>>::deposit_log(log.into());
+	}
+
+	/// Whether a code upgrade of the given para is waiting for the outcome of PVF pre-checking.
+	pub(crate) fn pvf_check_in_progress(id: ParaId) -> bool {
+		FutureCodeHash::<T>::contains_key(&id) && !FutureCodeUpgrades::<T>::contains_key(&id)
+	}
+
+	// Checks a PVF pre-checking statement against the current session, the active validator set
+	// and the active votes.
+	fn check_pvf_check_statement(
+		stmt: &PvfCheckStatement,
+		signature: &ValidatorSignature,
+	) -> Result<(), Error<T>> {
+		let current_session = shared::Pallet::<T>::session_index();
+		if stmt.session_index < current_session {
+			return Err(Error::<T>::PvfCheckStatementStale);
+		} else if stmt.session_index > current_session {
+			return Err(Error::<T>::PvfCheckStatementFuture);
+		}
+
+		let validators = shared::Pallet::<T>::active_validator_keys();
+		let validator_index = stmt.validator_index.0 as usize;
+		let validator_public = validators
+			.get(validator_index)
+			.ok_or(Error::<T>::PvfCheckValidatorIndexOutOfBounds)?;
+
+		if !signature.verify(&stmt.signing_payload()[..], validator_public) {
+			return Err(Error::<T>::PvfCheckInvalidSignature);
+		}
+
+		let vote = PvfActiveVoteMap::<T>::get(&stmt.subject)
+			.ok_or(Error::<T>::PvfCheckSubjectInvalid)?;
+		if vote.has_vote(validator_index) {
+			return Err(Error::<T>::PvfCheckDoubleVote);
+		}
+
+		Ok(())
+	}
+
+	// Removes the vote on the given code and enacts its outcome for every cause.
+	fn conclude_pvf_check(
+		code_hash: &ValidationCodeHash,
+		causes: Vec<PvfCheckCause<T::BlockNumber>>,
+		outcome: PvfCheckOutcome,
+	) {
+		PvfActiveVoteMap::<T>::remove(code_hash);
+		PvfActiveVoteList::<T>::mutate(|list| list.retain(|h| h != code_hash));
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		let cfg = configuration::Pallet::<T>::config();
+		for cause in causes {
+			match cause {
+				PvfCheckCause::Upgrade { id, relay_parent_number } => {
+					// The para may have been offboarded while the vote was running, in which case
+					// its future code was already dropped.
+					if FutureCodeHash::<T>::get(&id).as_ref() != Some(code_hash) {
+						continue;
+					}
+
+					match outcome {
+						PvfCheckOutcome::Accepted => {
+							let expected_at = cmp::max(
+								relay_parent_number + cfg.validation_upgrade_delay,
+								now,
+							);
+							Self::set_future_code_upgrade(id, *code_hash, expected_at);
+							Self::deposit_event(Event::PvfCheckAccepted(*code_hash, id));
+						},
+						PvfCheckOutcome::Rejected => {
+							FutureCodeHash::<T>::remove(&id);
+							Self::decrease_code_ref(code_hash);
+							Self::deposit_event(Event::PvfCheckRejected(*code_hash, id));
+						},
+					}
+				},
 			}
-		})
+		}
+	}
+
+	// Ages all active PVF votes on a session change. Votes that reached the configured TTL are
+	// rejected, the remaining ones start over with the new validator set.
+	fn groom_pvf_votes(notification: &SessionChangeNotification<T::BlockNumber>) {
+		let n_validators = notification.validators.len();
+		for code_hash in PvfActiveVoteList::<T>::get() {
+			let mut vote = match PvfActiveVoteMap::<T>::get(&code_hash) {
+				Some(vote) => vote,
+				None => {
+					log::error!(
+						target: "runtime::paras",
+						"Pallet paras storage is inconsistent, no active vote for {}",
+						code_hash,
+					);
+					continue;
+				},
+			};
+
+			vote.age += 1;
+			if vote.age >= notification.new_config.pvf_voting_ttl {
+				Self::conclude_pvf_check(&code_hash, vote.causes, PvfCheckOutcome::Rejected);
+			} else {
+				vote.reinitialize_ballots(n_validators);
+				PvfActiveVoteMap::<T>::insert(&code_hash, vote);
+			}
+		}
+	}
+
+	/// Submits a PVF pre-checking statement into the transaction pool.
+	///
+	/// NOTE: This is meant to be called from the offchain context only.
+	pub(crate) fn submit_pvf_check_statement(stmt: PvfCheckStatement, signature: ValidatorSignature) {
+		use frame_system::offchain::SubmitTransaction;
+
+		let call = Call::include_pvf_check_statement(stmt, signature);
+		if let Err(()) = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()) {
+			log::error!(
+				target: "runtime::paras",
+				"Failed to submit a PVF pre-checking statement",
+			);
+		}
+	}
+
+	/// Returns the code hashes of all PVFs that currently await pre-checking votes.
+	pub(crate) fn pvfs_require_precheck() -> Vec<ValidationCodeHash> {
+		PvfActiveVoteList::<T>::get()
 	}
 
 	/// Note that a para has progressed to a new head, where the new head was executed in the context
@@ -1016,7 +1293,14 @@ mod tests {
 	use primitives::v1::BlockNumber;
 	use frame_support::assert_ok;
 
-	use crate::mock::{new_test_ext, Paras, Shared, System, MockGenesisConfig};
+	use primitives::v1::{ValidatorId, ValidatorIndex};
+	use frame_support::assert_noop;
+	use keyring::Sr25519Keyring;
+
+	use crate::mock::{
+		new_test_ext, Configuration, Paras, Shared, System, MockGenesisConfig, Origin, Test,
+		Event as MockEvent,
+	};
 	use crate::configuration::HostConfiguration;
 
 	fn run_to_block(to: BlockNumber, new_session: Option<Vec<BlockNumber>>) {
@@ -1848,4 +2132,312 @@ mod tests {
 			assert!(!<Paras as Store>::CodeByHashRefs::contains_key(code.hash()));
 		});
 	}
+
+	#[test]
+	fn verify_externally_accessible() {
+		use primitives::v1::well_known_keys;
+
+		let a = ParaId::from(2020);
+
+		new_test_ext(Default::default()).execute_with(|| {
+			let head = HeadData(vec![1, 2, 3]);
+			let code_hash = ValidationCode(vec![4, 5, 6]).hash();
+
+			<Paras as Store>::ParaLifecycles::insert(&a, ParaLifecycle::Parathread);
+			<Paras as Store>::Heads::insert(&a, head.clone());
+			<Paras as Store>::CurrentCodeHash::insert(&a, code_hash);
+
+			let raw_lifecycle = sp_io::storage::get(&well_known_keys::para_lifecycle(a))
+				.expect("lifecycle must be present in storage");
+			assert!(well_known_keys::para_lifecycle(a).starts_with(well_known_keys::PARA_LIFECYCLES));
+			assert_eq!(
+				ParaLifecycle::decode(&mut &raw_lifecycle[..]),
+				Ok(ParaLifecycle::Parathread),
+			);
+
+			let raw_head = sp_io::storage::get(&well_known_keys::para_head(a))
+				.expect("head must be present in storage");
+			assert_eq!(HeadData::decode(&mut &raw_head[..]), Ok(head));
+
+			let raw_code_hash = sp_io::storage::get(&well_known_keys::current_code_hash(a))
+				.expect("code hash must be present in storage");
+			assert_eq!(ValidationCodeHash::decode(&mut &raw_code_hash[..]), Ok(code_hash));
+		});
+	}
+
+	#[test]
+	fn verify_pending_upgrade_externally_accessible() {
+		use primitives::v1::well_known_keys;
+
+		let a = ParaId::from(2020);
+
+		new_test_ext(Default::default()).execute_with(|| {
+			let code = ValidationCode(vec![4, 5, 6]);
+
+			<Paras as Store>::FutureCodeHash::insert(&a, code.hash());
+			<Paras as Store>::FutureCodeUpgrades::insert(&a, 10);
+			<Paras as Store>::CodeByHash::insert(code.hash(), code.clone());
+
+			let raw_code_hash = sp_io::storage::get(&well_known_keys::future_code_hash(a))
+				.expect("future code hash must be present in storage");
+			assert_eq!(ValidationCodeHash::decode(&mut &raw_code_hash[..]), Ok(code.hash()));
+
+			let raw_upgrade_at = sp_io::storage::get(&well_known_keys::future_code_upgrade_at(a))
+				.expect("upgrade block must be present in storage");
+			assert_eq!(BlockNumber::decode(&mut &raw_upgrade_at[..]), Ok(10));
+
+			let raw_code = sp_io::storage::get(&well_known_keys::code_by_hash(code.hash()))
+				.expect("code must be present in storage");
+			assert_eq!(ValidationCode::decode(&mut &raw_code[..]), Ok(code));
+		});
+	}
+
+	const PVF_CHECKERS: [Sr25519Keyring; 4] = [
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+	];
+
+	fn pvf_checking_genesis() -> MockGenesisConfig {
+		let paras = (0u32..2).map(|id| (id.into(), ParaGenesisArgs {
+			parachain: true,
+			genesis_head: Default::default(),
+			validation_code: ValidationCode(vec![1, 2, 3]),
+		})).collect();
+
+		MockGenesisConfig {
+			paras: GenesisConfig { paras, ..Default::default() },
+			configuration: crate::configuration::GenesisConfig {
+				config: HostConfiguration {
+					code_retention_period: 10,
+					validation_upgrade_delay: 5,
+					pvf_checking_enabled: true,
+					pvf_voting_ttl: 2,
+					..Default::default()
+				},
+				..Default::default()
+			},
+			..Default::default()
+		}
+	}
+
+	fn pvf_checker_pubkeys() -> Vec<ValidatorId> {
+		PVF_CHECKERS.iter().map(|k| k.public().into()).collect()
+	}
+
+	fn pvf_check_statement(
+		accept: bool,
+		subject: ValidationCodeHash,
+		validator_index: u32,
+	) -> (PvfCheckStatement, ValidatorSignature) {
+		let stmt = PvfCheckStatement {
+			accept,
+			subject,
+			session_index: Shared::session_index(),
+			validator_index: ValidatorIndex(validator_index),
+		};
+		let signature = PVF_CHECKERS[validator_index as usize].sign(&stmt.signing_payload()).into();
+		(stmt, signature)
+	}
+
+	fn pvf_checking_new_session() {
+		let session_index = Shared::session_index() + 1;
+		Shared::set_session_index(session_index);
+		Shared::set_active_validators_ascending(pvf_checker_pubkeys());
+		Paras::initializer_on_new_session(&SessionChangeNotification {
+			validators: pvf_checker_pubkeys(),
+			new_config: Configuration::config(),
+			session_index,
+			..Default::default()
+		});
+	}
+
+	fn paras_event_deposited(event: Event) -> bool {
+		System::events().iter().any(|record| record.event == MockEvent::Paras(event.clone()))
+	}
+
+	#[test]
+	fn pvf_check_accepted_upgrade_is_scheduled() {
+		new_test_ext(pvf_checking_genesis()).execute_with(|| {
+			let (a, b) = (ParaId::from(0), ParaId::from(1));
+			let new_code = ValidationCode(vec![4, 5, 6]);
+			let code_hash = new_code.hash();
+
+			run_to_block(2, None);
+			Shared::set_active_validators_ascending(pvf_checker_pubkeys());
+
+			let config = Configuration::config();
+			Paras::request_code_upgrade(a, new_code.clone(), 1, &config);
+			Paras::request_code_upgrade(b, new_code.clone(), 2, &config);
+
+			assert!(Paras::pvf_check_in_progress(a));
+			assert!(Paras::pvf_check_in_progress(b));
+			assert_eq!(Paras::pvfs_require_precheck(), vec![code_hash]);
+			assert_eq!(<Paras as Store>::FutureCodeUpgrades::get(&a), None);
+			assert_eq!(<Paras as Store>::CodeByHashRefs::get(&code_hash), 2);
+			assert!(paras_event_deposited(Event::PvfCheckStarted(code_hash, a)));
+			assert!(paras_event_deposited(Event::PvfCheckStarted(code_hash, b)));
+
+			// A further upgrade is ignored while the first one is being checked.
+			Paras::request_code_upgrade(a, ValidationCode(vec![7, 8, 9]), 2, &config);
+			assert_eq!(<Paras as Store>::FutureCodeHash::get(&a), Some(code_hash));
+
+			// 3 out of 4 validators form a supermajority.
+			for validator_index in 0..3 {
+				assert_eq!(Paras::pvfs_require_precheck(), vec![code_hash]);
+				let (stmt, signature) = pvf_check_statement(true, code_hash, validator_index);
+				assert_ok!(Paras::include_pvf_check_statement(Origin::none(), stmt, signature));
+			}
+
+			assert!(Paras::pvfs_require_precheck().is_empty());
+			assert!(!<Paras as Store>::PvfActiveVoteMap::contains_key(&code_hash));
+			assert!(!Paras::pvf_check_in_progress(a));
+			assert_eq!(<Paras as Store>::FutureCodeUpgrades::get(&a), Some(1 + 5));
+			assert_eq!(<Paras as Store>::FutureCodeUpgrades::get(&b), Some(2 + 5));
+			assert!(paras_event_deposited(Event::PvfCheckAccepted(code_hash, a)));
+			assert!(paras_event_deposited(Event::PvfCheckAccepted(code_hash, b)));
+
+			run_to_block(7, None);
+			Paras::note_new_head(a, Default::default(), 6);
+			assert_eq!(Paras::current_code(&a), Some(new_code.clone()));
+		});
+	}
+
+	#[test]
+	fn pvf_check_rejected_upgrade_is_dropped() {
+		new_test_ext(pvf_checking_genesis()).execute_with(|| {
+			let para_id = ParaId::from(0);
+			let new_code = ValidationCode(vec![4, 5, 6]);
+			let code_hash = new_code.hash();
+
+			run_to_block(2, None);
+			Shared::set_active_validators_ascending(pvf_checker_pubkeys());
+
+			Paras::request_code_upgrade(para_id, new_code.clone(), 1, &Configuration::config());
+			check_code_is_stored(&new_code);
+
+			for validator_index in 1..4 {
+				let (stmt, signature) = pvf_check_statement(false, code_hash, validator_index);
+				assert_ok!(Paras::include_pvf_check_statement(Origin::none(), stmt, signature));
+			}
+
+			assert!(Paras::pvfs_require_precheck().is_empty());
+			assert!(!Paras::pvf_check_in_progress(para_id));
+			assert_eq!(<Paras as Store>::FutureCodeHash::get(&para_id), None);
+			assert_eq!(<Paras as Store>::FutureCodeUpgrades::get(&para_id), None);
+			check_code_is_not_stored(&new_code);
+			assert!(paras_event_deposited(Event::PvfCheckRejected(code_hash, para_id)));
+
+			// The para may try again afterwards.
+			Paras::request_code_upgrade(para_id, new_code.clone(), 2, &Configuration::config());
+			assert!(Paras::pvf_check_in_progress(para_id));
+		});
+	}
+
+	#[test]
+	fn pvf_check_vote_expires_after_ttl() {
+		new_test_ext(pvf_checking_genesis()).execute_with(|| {
+			let para_id = ParaId::from(0);
+			let new_code = ValidationCode(vec![4, 5, 6]);
+			let code_hash = new_code.hash();
+
+			run_to_block(2, None);
+			Shared::set_active_validators_ascending(pvf_checker_pubkeys());
+			Paras::request_code_upgrade(para_id, new_code.clone(), 1, &Configuration::config());
+
+			for validator_index in 0..2 {
+				let (stmt, signature) = pvf_check_statement(true, code_hash, validator_index);
+				assert_ok!(Paras::include_pvf_check_statement(Origin::none(), stmt, signature));
+			}
+
+			// The votes are reset, but the vote stays active.
+			pvf_checking_new_session();
+			let vote = <Paras as Store>::PvfActiveVoteMap::get(&code_hash).unwrap();
+			assert_eq!(vote.age, 1);
+			assert!(!vote.has_vote(0));
+			assert!(Paras::pvf_check_in_progress(para_id));
+
+			// Statements from the previous session are stale.
+			let mut stale = pvf_check_statement(true, code_hash, 2);
+			stale.0.session_index -= 1;
+			stale.1 = PVF_CHECKERS[2].sign(&stale.0.signing_payload()).into();
+			assert_noop!(
+				Paras::include_pvf_check_statement(Origin::none(), stale.0, stale.1),
+				Error::<Test>::PvfCheckStatementStale,
+			);
+
+			pvf_checking_new_session();
+			assert!(Paras::pvfs_require_precheck().is_empty());
+			assert!(!Paras::pvf_check_in_progress(para_id));
+			check_code_is_not_stored(&new_code);
+			assert!(paras_event_deposited(Event::PvfCheckRejected(code_hash, para_id)));
+		});
+	}
+
+	#[test]
+	fn pvf_check_statements_are_validated() {
+		new_test_ext(pvf_checking_genesis()).execute_with(|| {
+			let para_id = ParaId::from(0);
+			let new_code = ValidationCode(vec![4, 5, 6]);
+			let code_hash = new_code.hash();
+
+			run_to_block(2, None);
+			Shared::set_active_validators_ascending(pvf_checker_pubkeys());
+			Paras::request_code_upgrade(para_id, new_code, 1, &Configuration::config());
+
+			let validate = |stmt: PvfCheckStatement, signature: ValidatorSignature| {
+				<Paras as ValidateUnsigned>::validate_unsigned(
+					TransactionSource::External,
+					&Call::include_pvf_check_statement(stmt, signature),
+				)
+			};
+
+			let (stmt, signature) = pvf_check_statement(true, code_hash, 0);
+			assert!(validate(stmt.clone(), signature.clone()).is_ok());
+
+			let mut future = stmt.clone();
+			future.session_index += 1;
+			let future_signature = PVF_CHECKERS[0].sign(&future.signing_payload()).into();
+			assert_eq!(
+				validate(future, future_signature),
+				Err(InvalidTransaction::Future.into()),
+			);
+
+			let mut wrong_signer = stmt.clone();
+			wrong_signer.validator_index = ValidatorIndex(1);
+			assert_eq!(
+				validate(wrong_signer, signature.clone()),
+				Err(InvalidTransaction::BadProof.into()),
+			);
+
+			let mut out_of_bounds = stmt.clone();
+			out_of_bounds.validator_index = ValidatorIndex(4);
+			assert_eq!(
+				validate(out_of_bounds, signature.clone()),
+				Err(InvalidTransaction::BadProof.into()),
+			);
+
+			let (unknown, unknown_signature) =
+				pvf_check_statement(true, ValidationCode(vec![7]).hash(), 0);
+			assert_eq!(
+				validate(unknown, unknown_signature),
+				Err(InvalidTransaction::Stale.into()),
+			);
+
+			assert_ok!(Paras::include_pvf_check_statement(
+				Origin::none(),
+				stmt.clone(),
+				signature.clone(),
+			));
+			assert_eq!(
+				validate(stmt.clone(), signature.clone()),
+				Err(InvalidTransaction::Stale.into()),
+			);
+			assert_noop!(
+				Paras::include_pvf_check_statement(Origin::none(), stmt, signature),
+				Error::<Test>::PvfCheckDoubleVote,
+			);
+		});
+	}
 }
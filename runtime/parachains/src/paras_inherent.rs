@@ -25,6 +25,7 @@ use sp_std::prelude::*;
 use sp_runtime::traits::Header as HeaderT;
 use primitives::v1::{
 	BackedCandidate, PARACHAINS_INHERENT_IDENTIFIER, InherentData as ParachainsInherentData,
+	ScrapedOnChainVotes,
 };
 use frame_support::{
 	decl_error, decl_module, decl_storage, ensure,
@@ -49,6 +50,11 @@ const INCLUSION_INHERENT_CLAIMED_WEIGHT: Weight = 1_000_000_000;
 // we assume that 75% of an paras inherent's weight is used processing backed candidates
 const MINIMAL_INCLUSION_INHERENT_WEIGHT: Weight = INCLUSION_INHERENT_CLAIMED_WEIGHT / 4;
 
+/// The weight of recording the votes of the block in `OnChainVotes`, paid by every inherent.
+fn on_chain_votes_weight<T: Config>() -> Weight {
+	T::DbWeight::get().writes(1)
+}
+
 pub trait Config: inclusion::Config + scheduler::Config {}
 
 decl_storage! {
@@ -60,6 +66,11 @@ decl_storage! {
 		///
 		/// If this is `None` at the end of the block, we panic and render the block invalid.
 		Included: Option<()>;
+
+		/// Scraped on chain data for extracting resolved disputes as well as backing votes.
+		///
+		/// Overwritten by every paras inherent, so it always refers to the current block.
+		OnChainVotes get(fn on_chain_votes): Option<ScrapedOnChainVotes<T::Hash>>;
 	}
 }
 
@@ -92,7 +103,9 @@ decl_module! {
 
 		/// Enter the paras inherent. This will process bitfields and backed candidates.
 		#[weight = (
-			MINIMAL_INCLUSION_INHERENT_WEIGHT + data.backed_candidates.len() as Weight * BACKED_CANDIDATE_WEIGHT,
+			MINIMAL_INCLUSION_INHERENT_WEIGHT +
+				data.backed_candidates.len() as Weight * BACKED_CANDIDATE_WEIGHT +
+				on_chain_votes_weight::<T>(),
 			DispatchClass::Mandatory,
 		)]
 		pub fn enter(
@@ -119,12 +132,18 @@ decl_module! {
 			// Handle disputes logic.
 			let current_session = <shared::Module<T>>::session_index();
 			let freed_disputed: Vec<(_, FreedReason)> = {
-				let fresh_disputes = T::DisputesHandler::provide_multi_dispute_data(disputes)?;
+				// Keep a copy of the imported disputes around for `OnChainVotes`.
+				let fresh_disputes = T::DisputesHandler::provide_multi_dispute_data(disputes.clone())?;
 				if T::DisputesHandler::is_frozen() {
 					// The relay chain we are currently on is invalid. Proceed no further on parachains.
+					<OnChainVotes<T>>::put(ScrapedOnChainVotes {
+						session: current_session,
+						backing_validators_per_candidate: Vec::new(),
+						disputes,
+					});
 					Included::set(Some(()));
 					return Ok(Some(
-						MINIMAL_INCLUSION_INHERENT_WEIGHT
+						MINIMAL_INCLUSION_INHERENT_WEIGHT + on_chain_votes_weight::<T>()
 					).into());
 				}
 
@@ -199,13 +218,23 @@ decl_module! {
 
 			// Process backed candidates according to scheduled cores.
 			let parent_storage_root = parent_header.state_root().clone();
-			let occupied = <inclusion::Module<T>>::process_candidates(
+			let inclusion::ProcessedCandidates {
+				core_indices: occupied,
+				candidate_receipt_with_backing_validator_indices,
+			} = <inclusion::Module<T>>::process_candidates(
 				parent_storage_root,
 				backed_candidates,
 				<scheduler::Module<T>>::scheduled(),
 				<scheduler::Module<T>>::group_validators,
 			)?;
 
+			// Record the backing votes and disputes of this block for the runtime API.
+			<OnChainVotes<T>>::put(ScrapedOnChainVotes {
+				session: current_session,
+				backing_validators_per_candidate: candidate_receipt_with_backing_validator_indices,
+				disputes,
+			});
+
 			// Note which of the scheduled cores were actually occupied by a backed candidate.
 			<scheduler::Module<T>>::occupied(&occupied);
 
@@ -217,7 +246,8 @@ decl_module! {
 
 			Ok(Some(
 				MINIMAL_INCLUSION_INHERENT_WEIGHT +
-				(backed_candidates_len * BACKED_CANDIDATE_WEIGHT)
+				(backed_candidates_len * BACKED_CANDIDATE_WEIGHT) +
+				on_chain_votes_weight::<T>()
 			).into())
 		}
 	}
@@ -422,7 +452,8 @@ mod tests {
 
 				// the expected weight can always be computed by this formula
 				let expected_weight = MINIMAL_INCLUSION_INHERENT_WEIGHT +
-					(backed_candidates.len() as Weight * BACKED_CANDIDATE_WEIGHT);
+					(backed_candidates.len() as Weight * BACKED_CANDIDATE_WEIGHT) +
+					on_chain_votes_weight::<Test>();
 
 				// we've used half the block weight; there's plenty of margin
 				let max_block_weight = <Test as frame_system::Config>::BlockWeights::get().max_block;
@@ -467,7 +498,7 @@ mod tests {
 				let backed_candidates = vec![BackedCandidate::default(); 10];
 
 				// the expected weight with no blocks is just the minimum weight
-				let expected_weight = MINIMAL_INCLUSION_INHERENT_WEIGHT;
+				let expected_weight = MINIMAL_INCLUSION_INHERENT_WEIGHT + on_chain_votes_weight::<Test>();
 
 				// oops, looks like this mandatory call pushed the block weight over the limit
 				let max_block_weight = <Test as frame_system::Config>::BlockWeights::get().max_block;
@@ -490,6 +521,16 @@ mod tests {
 					post_info.actual_weight.unwrap(),
 					expected_weight,
 				);
+
+				// the votes of this block are recorded even if no candidate was backed.
+				assert_eq!(
+					Module::<Test>::on_chain_votes(),
+					Some(ScrapedOnChainVotes {
+						session: 0,
+						backing_validators_per_candidate: Vec::new(),
+						disputes: Vec::new(),
+					}),
+				);
 			});
 		}
 	}
@@ -24,10 +24,13 @@ use primitives::v1::{
 	AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreIndex, CoreOccupied,
 	CoreState, GroupIndex, GroupRotationInfo, Id as ParaId, InboundDownwardMessage,
 	InboundHrmpMessage, OccupiedCore, OccupiedCoreAssumption, PersistedValidationData,
-	ScheduledCore, SessionIndex, SessionInfo, ValidationCode, ValidationCodeHash, ValidatorId,
-	ValidatorIndex,
+	PvfCheckStatement, ScheduledCore, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+	ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+};
+use crate::{
+	initializer, inclusion, scheduler, configuration, paras, paras_inherent, session_info, dmp,
+	hrmp, shared,
 };
-use crate::{initializer, inclusion, scheduler, configuration, paras, session_info, dmp, hrmp, shared};
 
 
 /// Implementation for the `validators` function of the runtime API.
@@ -329,3 +332,21 @@ pub fn validation_code_by_hash<T: paras::Config>(
 ) -> Option<ValidationCode> {
 	<paras::Pallet<T>>::code_by_hash(hash)
 }
+
+/// Implementation for the `on_chain_votes` function of the runtime API.
+pub fn on_chain_votes<T: paras_inherent::Config>() -> Option<ScrapedOnChainVotes<T::Hash>> {
+	<paras_inherent::Module<T>>::on_chain_votes()
+}
+
+/// Submits an PVF pre-checking statement into the transaction pool.
+pub fn submit_pvf_check_statement<T: paras::Config>(
+	stmt: PvfCheckStatement,
+	signature: ValidatorSignature,
+) {
+	<paras::Pallet<T>>::submit_pvf_check_statement(stmt, signature)
+}
+
+/// Returns the list of all PVF code hashes that require pre-checking.
+pub fn pvfs_require_precheck<T: paras::Config>() -> Vec<ValidationCodeHash> {
+	<paras::Pallet<T>>::pvfs_require_precheck()
+}
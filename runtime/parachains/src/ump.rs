@@ -23,6 +23,7 @@ use sp_std::collections::{btree_map::BTreeMap, vec_deque::VecDeque};
 use frame_support::{decl_module, decl_event, decl_storage, StorageMap, StorageValue, weights::Weight, traits::Get};
 use primitives::v1::{Id as ParaId, UpwardMessage};
 use xcm::v0::Outcome;
+use polkadot_runtime_metrics::{Counter, Histogram, definitions};
 
 /// All upward messages coming from parachains will be funneled into an implementation of this trait.
 ///
@@ -365,6 +366,9 @@ impl<T: Config> Module<T> {
 
 	/// Devote some time into dispatching pending upward messages.
 	pub(crate) fn process_pending_upward_messages() -> Weight {
+		const MESSAGES_PROCESSED: Counter = Counter::new(&definitions::UMP_MESSAGES_PROCESSED);
+		const WEIGHT_CONSUMED: Histogram = Histogram::new(&definitions::UMP_WEIGHT_CONSUMED);
+
 		let mut weight_used = 0;
 
 		let config = <configuration::Module<T>>::config();
@@ -391,7 +395,10 @@ impl<T: Config> Module<T> {
 			let (upward_message, became_empty) = queue_cache.dequeue::<T>(dispatchee);
 			if let Some(upward_message) = upward_message {
 				match T::UmpSink::process_upward_message(dispatchee, &upward_message[..], max_weight) {
-					Ok(used) => weight_used += used,
+					Ok(used) => {
+						weight_used += used;
+						MESSAGES_PROCESSED.inc(&[]);
+					},
 					Err((id, required)) => {
 						// we process messages in order and don't drop them if we run out of weight, so need to break
 						// here.
@@ -412,6 +419,8 @@ impl<T: Config> Module<T> {
 		cursor.flush::<T>();
 		queue_cache.flush::<T>();
 
+		WEIGHT_CONSUMED.observe(&[], weight_used);
+
 		weight_used
 	}
 }